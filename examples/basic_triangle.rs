@@ -1,11 +1,7 @@
 use glam::{Mat4, Vec3};
 
-use vulkan_slang_renderer::game::Game;
-use vulkan_slang_renderer::renderer::{
-    DrawError, DrawIndexed, FrameRenderer, PipelineHandle, Renderer, UniformBufferHandle,
-};
+use vulkan_slang_renderer::prelude::*;
 
-use vulkan_slang_renderer::generated::shader_atlas::ShaderAtlas;
 use vulkan_slang_renderer::generated::shader_atlas::basic_triangle::*;
 
 fn main() -> Result<(), anyhow::Error> {
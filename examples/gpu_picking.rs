@@ -1,8 +1,13 @@
+//! Click a cube to select it, then drag the gizmo's handles to move it.
+//! W switches the gizmo to translate, R to scale. F toggles the floor grid.
+
 use glam::{Mat4, Vec3};
+use vulkan_slang_renderer::editor::gizmo::{Gizmo, GizmoMode, Transform};
+use vulkan_slang_renderer::editor::grid::Grid;
 use vulkan_slang_renderer::game::*;
 use vulkan_slang_renderer::renderer::{
-    DrawError, DrawVertexCount, FrameRenderer, PickingPipelineHandle, PipelineHandle, Renderer,
-    StorageBufferHandle, UniformBufferHandle,
+    DrawError, DrawVertexCount, FrameRenderer, PickingPipelineHandle, PickingPolicy,
+    PipelineHandle, Renderer, StorageBufferHandle, UniformBufferHandle,
 };
 
 use vulkan_slang_renderer::generated::shader_atlas::ShaderAtlas;
@@ -15,6 +20,7 @@ fn main() -> Result<(), anyhow::Error> {
 }
 
 const MAX_CUBES: u32 = 256;
+const CUBE_RADIUS: f32 = 0.3;
 
 struct GpuPicking {
     params_buffer: UniformBufferHandle<GpuPickingParams>,
@@ -23,6 +29,12 @@ struct GpuPicking {
     pipeline: PipelineHandle<DrawVertexCount>,
     picking_pipeline: PickingPipelineHandle,
     cubes: Vec<Cube>,
+    /// the cubes' positions and sizes, as edited by the gizmo
+    transforms: Vec<Transform>,
+    gizmo: Gizmo,
+    /// painted over the cubes, so it starts hidden
+    grid: Grid,
+    hovered_id: u32,
     mouse_x: f32,
     mouse_y: f32,
 }
@@ -54,24 +66,20 @@ impl Game for GpuPicking {
         };
         let picking_config = atlas.gpu_picking_id.pipeline_config(picking_resources);
         let picking_pipeline = renderer.create_picking_pipeline(picking_config)?;
+        // keep hovering stable along the cubes' antialiased edges
+        renderer.set_picking_policy(PickingPolicy::Neighborhood { radius: 2 });
 
-        let mut cubes = Vec::new();
-        let radii = 0.3_f32;
+        let mut transforms = Vec::new();
         let spacing: f32 = 1.0;
         for x in -1..=1 {
             for y in -1..=1 {
                 for z in -1..=1 {
                     let position = spacing * Vec3::new(x as f32, y as f32, z as f32);
-
-                    cubes.push(Cube {
-                        position,
-                        _padding_0: Default::default(),
-                        radii: Vec3::splat(radii),
-                        _padding_1: Default::default(),
-                    });
+                    transforms.push(Transform::from_translation(position));
                 }
             }
         }
+        let cubes = transforms.iter().map(cube).collect();
 
         Ok(Self {
             params_buffer,
@@ -80,24 +88,58 @@ impl Game for GpuPicking {
             pipeline,
             picking_pipeline,
             cubes,
+            transforms,
+            gizmo: Gizmo::new(),
+            grid: Grid {
+                visible: false,
+                ..Grid::new()
+            },
+            hovered_id: 0,
             mouse_x: 0.0,
             mouse_y: 0.0,
         })
     }
 
     fn input(&mut self, input: Input) {
-        if let Input::MouseMotion { x, y } = input {
-            self.mouse_x = x;
-            self.mouse_y = y;
+        match input {
+            Input::MouseMotion { x, y } => {
+                self.mouse_x = x;
+                self.mouse_y = y;
+            }
+            // clicks on the gizmo's handles go to the editor instead
+            Input::MouseDown {
+                button: MouseButton::Left,
+                ..
+            } => self.gizmo.select_picked(self.hovered_id),
+            Input::KeyDown(Key::W) => self.gizmo.mode = GizmoMode::Translate,
+            Input::KeyDown(Key::R) => self.gizmo.mode = GizmoMode::Scale,
+            Input::KeyDown(Key::F) => self.grid.visible = !self.grid.visible,
+            _ => {}
         }
     }
 
+    fn editor_gizmo(&mut self) -> Option<(&mut Gizmo, &mut [Transform])> {
+        Some((&mut self.gizmo, &mut self.transforms))
+    }
+
+    fn editor_grid(&mut self) -> Option<&mut Grid> {
+        Some(&mut self.grid)
+    }
+
     fn draw(&mut self, renderer: FrameRenderer) -> Result<(), DrawError> {
         let picked_id = renderer.picked_object_id();
+        self.hovered_id = picked_id;
         let aspect_ratio = renderer.aspect_ratio();
 
+        let (view, proj) = camera_matrices(aspect_ratio);
+        self.gizmo.set_camera(view, proj);
+        self.grid.set_camera(view, proj);
         let camera = build_camera(aspect_ratio);
 
+        for (cube_params, transform) in self.cubes.iter_mut().zip(&self.transforms) {
+            *cube_params = cube(transform);
+        }
+
         let mouse_position = [self.mouse_x, self.mouse_y];
 
         renderer.draw_vertex_count_with_picking(
@@ -127,14 +169,21 @@ impl Game for GpuPicking {
     }
 }
 
-fn build_camera(aspect_ratio: f32) -> RayMarchCamera {
-    let position = Vec3::new(5.0, 5.0, -5.0);
+const CAMERA_POSITION: Vec3 = Vec3::new(5.0, 5.0, -5.0);
+
+fn camera_matrices(aspect_ratio: f32) -> (Mat4, Mat4) {
     let target = Vec3::ZERO;
     let up = Vec3::Y;
 
     let fov_y = 45.0_f32.to_radians();
-    let view = Mat4::look_at_rh(position, target, up);
+    let view = Mat4::look_at_rh(CAMERA_POSITION, target, up);
     let proj = Mat4::perspective_rh(fov_y, aspect_ratio, 0.1, 100.0);
+    (view, proj)
+}
+
+fn build_camera(aspect_ratio: f32) -> RayMarchCamera {
+    let position = CAMERA_POSITION;
+    let (view, proj) = camera_matrices(aspect_ratio);
     let inverse_view_proj = (proj * view).inverse();
 
     RayMarchCamera {
@@ -145,3 +194,13 @@ fn build_camera(aspect_ratio: f32) -> RayMarchCamera {
         _padding_0: Default::default(),
     }
 }
+
+/// the cubes are axis-aligned boxes, so rotation is ignored
+fn cube(transform: &Transform) -> Cube {
+    Cube {
+        position: transform.translation,
+        _padding_0: Default::default(),
+        radii: CUBE_RADIUS * transform.scale,
+        _padding_1: Default::default(),
+    }
+}
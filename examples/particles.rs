@@ -4,6 +4,7 @@ use ash::vk;
 use glam::{Vec2, Vec4};
 
 use vulkan_slang_renderer::game::*;
+use vulkan_slang_renderer::quad::quad_vertex_count;
 use vulkan_slang_renderer::renderer::{
     Compute, DrawError, DrawVertexCount, FrameRenderer, GpuOnlyBufferHandle, PipelineHandle,
     Renderer, UniformBufferHandle,
@@ -101,7 +102,7 @@ impl Game for Particles {
             vk::AccessFlags2::SHADER_READ,
         );
 
-        let vertex_count = NUM_PARTICLES * 6; // 6 vertices per particle quad
+        let vertex_count = quad_vertex_count(NUM_PARTICLES);
         renderer.draw_vertex_count(&self.render_pipeline, vertex_count, |gpu| {
             gpu.write_uniform(
                 &mut self.sim_params_buffer,
@@ -1,7 +1,8 @@
 use std::f32::consts::TAU;
-use std::time::Instant;
+use std::time::Duration;
 
 use glam::{Mat4, Quat, Vec3};
+use vulkan_slang_renderer::camera::{FlyController, FlyInput};
 use vulkan_slang_renderer::game::*;
 use vulkan_slang_renderer::renderer::{
     DrawError, DrawVertexCount, FrameRenderer, PipelineHandle, Renderer, StorageBufferHandle,
@@ -21,7 +22,8 @@ const MOON_START: Vec3 = Vec3::new(1.0, 0.0, 1.0);
 const SUN_START: Vec3 = Vec3::new(4.0, 5.0, 2.0);
 
 struct RayMarching {
-    start_time: Instant,
+    /// game time, which stops with the app's debug pause
+    elapsed: Duration,
     params_buffer: UniformBufferHandle<RayMarchingParams>,
     sun_position: Vec3,
     spheres_buffer: StorageBufferHandle<Sphere>,
@@ -29,8 +31,9 @@ struct RayMarching {
     spheres: Vec<Sphere>,
     boxes: Vec<BoxRect>,
     pipeline: PipelineHandle<DrawVertexCount>,
-    intent: Intent,
-    camera_controller: RaymarchCameraController,
+    camera_controller: FlyController,
+    /// toggled with the right mouse button
+    mouse_look: bool,
 }
 
 impl Game for RayMarching {
@@ -44,8 +47,6 @@ impl Game for RayMarching {
     where
         Self: Sized,
     {
-        let start_time = Instant::now();
-
         let params_buffer = renderer.create_uniform_buffer::<RayMarchingParams>()?;
         let spheres_buffer = renderer.create_storage_buffer::<Sphere>(SHAPE_BUFFER_SIZE)?;
         let boxes_buffer = renderer.create_storage_buffer::<BoxRect>(SHAPE_BUFFER_SIZE)?;
@@ -74,15 +75,11 @@ impl Game for RayMarching {
             _padding_1: Default::default(),
         }];
 
-        let camera_controller = RaymarchCameraController {
-            position: Vec3::new(0.0, 0.0, -5.0),
-            yaw: 0.0,
-            pitch: 0.0,
-            roll: 0.2,
-        };
+        let mut camera_controller = FlyController::new(Vec3::new(0.0, 0.0, -5.0));
+        camera_controller.roll = 0.2;
 
         Ok(Self {
-            start_time,
+            elapsed: Duration::ZERO,
             params_buffer,
             sun_position: SUN_START,
             spheres_buffer,
@@ -92,44 +89,28 @@ impl Game for RayMarching {
             spheres,
             pipeline,
 
-            intent: Default::default(),
             camera_controller,
+            mouse_look: false,
         })
     }
 
     fn input(&mut self, input: Input) {
-        match input {
-            Input::KeyDown(key) => match key {
-                Key::W => self.intent.forward = true,
-                Key::S => self.intent.backward = true,
-                Key::A => self.intent.left = true,
-                Key::D => self.intent.right = true,
-                Key::Q => self.intent.roll_left = true,
-                Key::E => self.intent.roll_right = true,
-                Key::Space => {}
-                _ => {}
-            },
-
-            Input::KeyUp(key) => match key {
-                Key::W => self.intent.forward = false,
-                Key::S => self.intent.backward = false,
-                Key::A => self.intent.left = false,
-                Key::D => self.intent.right = false,
-                Key::Q => self.intent.roll_left = false,
-                Key::E => self.intent.roll_right = false,
-                Key::Space => {}
-                _ => {}
-            },
-
-            _ => {}
+        if let Input::MouseDown {
+            button: MouseButton::Right,
+            ..
+        } = input
+        {
+            self.mouse_look = !self.mouse_look;
         }
+        self.camera_controller.look_input(&input);
     }
 
-    fn update(&mut self) {
-        self.camera_controller.update(&self.intent);
+    fn update(&mut self, keyboard: &KeyboardState, dt: Duration) {
+        let intent = FlyInput::from_keyboard(keyboard);
+        self.camera_controller.update(&intent, dt);
 
-        let elapsed = (Instant::now() - self.start_time).as_secs_f32();
-        let elapsed = elapsed * 0.1;
+        self.elapsed += dt;
+        let elapsed = self.elapsed.as_secs_f32() * 0.1;
 
         let sun_rotation = Mat4::from_rotation_y(TAU * (elapsed * 0.25).fract());
         self.sun_position = sun_rotation.transform_point3(SUN_START);
@@ -147,8 +128,9 @@ impl Game for RayMarching {
         self.boxes[0].transform = cube_moon_transform;
     }
 
-    fn draw(&mut self, renderer: FrameRenderer) -> Result<(), DrawError> {
-        let camera = self.camera_controller.camera(renderer.aspect_ratio());
+    fn draw(&mut self, mut renderer: FrameRenderer) -> Result<(), DrawError> {
+        renderer.set_relative_mouse_mode(self.mouse_look);
+        let camera = ray_march_camera(&self.camera_controller, renderer.aspect_ratio());
 
         let resolution = renderer.window_resolution();
 
@@ -171,90 +153,12 @@ impl Game for RayMarching {
     }
 }
 
-// Translated player camera controls
-#[derive(Default)]
-struct Intent {
-    forward: bool,
-    backward: bool,
-    left: bool,
-    right: bool,
-    roll_left: bool,
-    roll_right: bool,
-}
-
-struct RaymarchCameraController {
-    position: Vec3,
-    // aka left/right facing angle
-    yaw: f32,
-    // aka up/down facing angle
-    pitch: f32,
-    // aka left/right lean angle
-    roll: f32,
-}
-
-impl RaymarchCameraController {
-    fn forward_direction(&self) -> Vec3 {
-        Vec3::new(
-            self.yaw.sin() * self.pitch.cos(),
-            self.pitch.sin(),
-            self.yaw.cos() * self.pitch.cos(),
-        )
-    }
-
-    fn right_direction(&self) -> Vec3 {
-        let forward = self.forward_direction();
-        let base_right = forward.cross(Vec3::Y).normalize_or_zero();
-        Quat::from_axis_angle(forward, self.roll) * base_right
-    }
-
-    fn update(&mut self, intent: &Intent) {
-        const MOVE_SPEED: f32 = 0.01;
-        const ROLL_SPEED: f32 = 0.03;
-
-        let forward_dir = self.forward_direction();
-        let right_dir = self.right_direction();
-
-        let mut movement = Vec3::ZERO;
-        if intent.forward {
-            movement += forward_dir;
-        }
-        if intent.backward {
-            movement -= forward_dir;
-        }
-        if intent.left {
-            movement -= right_dir;
-        }
-        if intent.right {
-            movement += right_dir;
-        }
-
-        if intent.roll_left {
-            self.roll += ROLL_SPEED;
-        }
-        if intent.roll_right {
-            self.roll -= ROLL_SPEED;
-        }
-
-        self.position += movement.normalize_or_zero() * MOVE_SPEED;
-    }
-
-    fn camera(&self, aspect_ratio: f32) -> RayMarchCamera {
-        let fov_y_radians = 45.0_f32.to_radians();
-
-        let forward = self.forward_direction();
-        let up = Quat::from_axis_angle(forward, self.roll) * Vec3::Y;
-
-        let target = self.position + forward;
-        let view = Mat4::look_at_rh(self.position, target, up);
-        let proj = Mat4::perspective_rh(fov_y_radians, aspect_ratio, 0.1, 1000.0);
-        let inverse_view_proj = (proj * view).inverse();
-
-        RayMarchCamera {
-            position: self.position,
-            inverse_view_proj: Projection {
-                matrix: inverse_view_proj,
-            },
-            _padding_0: Default::default(),
-        }
+fn ray_march_camera(camera: &FlyController, aspect_ratio: f32) -> RayMarchCamera {
+    RayMarchCamera {
+        position: camera.position,
+        inverse_view_proj: Projection {
+            matrix: camera.view_projection(aspect_ratio).inverse(),
+        },
+        _padding_0: Default::default(),
     }
 }
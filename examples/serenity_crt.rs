@@ -1,15 +1,24 @@
+//! A CRT filter over a scene drawn each frame into an offscreen render target:
+//! the pixel art, with a smaller copy of it spinning in front.
+
 use std::time::Instant;
 
+use ash::vk;
 use facet::Facet;
+use glam::{Mat4, Vec2, Vec4};
 use vulkan_slang_renderer::editor::Slider;
 use vulkan_slang_renderer::game::*;
+use vulkan_slang_renderer::quad::quad_vertex_count;
 use vulkan_slang_renderer::renderer::{
-    DrawError, DrawVertexCount, FrameRenderer, PipelineHandle, Renderer, TextureFilter,
-    UniformBufferHandle,
+    DrawError, DrawVertexCount, FrameRenderer, ImmutableBufferHandle, PipelineHandle, Renderer,
+    TextureFilter, UniformBufferHandle,
 };
 
 use vulkan_slang_renderer::generated::shader_atlas::ShaderAtlas;
 use vulkan_slang_renderer::generated::shader_atlas::serenity_crt::*;
+use vulkan_slang_renderer::generated::shader_atlas::sprite_batch::{
+    self, Projection, Sprite, SpriteBatchParams,
+};
 use vulkan_slang_renderer::util::load_image;
 
 fn main() -> Result<(), anyhow::Error> {
@@ -21,6 +30,45 @@ struct SerenityCRT {
     edit_state: EditState,
     pipeline: PipelineHandle<DrawVertexCount>,
     params_buffer: UniformBufferHandle<SerenityCRTParams>,
+    scene: Scene,
+}
+
+/// sprites drawn into the render target the CRT pipeline samples
+struct Scene {
+    pipeline: PipelineHandle<DrawVertexCount>,
+    params_buffer: UniformBufferHandle<SpriteBatchParams>,
+    sprites_buffer: ImmutableBufferHandle<Sprite>,
+    size: Vec2,
+}
+
+impl Scene {
+    const SPRITE_COUNT: u32 = 2;
+
+    fn sprites(&self, elapsed: f32) -> [Sprite; Self::SPRITE_COUNT as usize] {
+        let whole_image = |position: Vec2, scale: Vec2, rotation: f32, color: Vec4| Sprite {
+            position: position.extend(0.0),
+            rotation,
+            scale,
+            padding: Vec2::ZERO,
+            tex_u: 0.0,
+            tex_v: 0.0,
+            tex_w: 1.0,
+            tex_h: 1.0,
+            color,
+        };
+
+        let inset_scale = self.size / 3.0;
+        let bob = Vec2::new(0.0, (elapsed * 2.0).sin() * self.size.y / 16.0);
+        [
+            whole_image(Vec2::ZERO, self.size, 0.0, Vec4::ONE),
+            whole_image(
+                self.size / 2.0 - inset_scale / 2.0 + bob,
+                inset_scale,
+                elapsed * 0.5,
+                Vec4::new(1.0, 0.8, 0.6, 1.0),
+            ),
+        ]
+    }
 }
 
 #[derive(Facet)]
@@ -60,9 +108,30 @@ impl Game for SerenityCRT {
         let texture =
             renderer.create_texture(image_name, &pixel_art_image, TextureFilter::Nearest)?;
 
+        let (width, height) = (pixel_art_image.width(), pixel_art_image.height());
+        let render_target =
+            renderer.create_render_target(width, height, vk::Format::R8G8B8A8_SRGB)?;
+
+        let scene_params_buffer = renderer.create_uniform_buffer::<SpriteBatchParams>()?;
+        let sprites_buffer = renderer.create_immutable_buffer::<Sprite>(Scene::SPRITE_COUNT)?;
+        let scene_resources = sprite_batch::Resources {
+            texture: &texture,
+            params_buffer: &scene_params_buffer,
+        };
+        let scene_config = ShaderAtlas::init()
+            .sprite_batch
+            .pipeline_config(scene_resources)
+            .with_render_target(&render_target);
+        let scene = Scene {
+            pipeline: renderer.create_pipeline(scene_config)?,
+            params_buffer: scene_params_buffer,
+            sprites_buffer,
+            size: Vec2::new(width as f32, height as f32),
+        };
+
         let params_buffer = renderer.create_uniform_buffer::<SerenityCRTParams>()?;
         let resources = Resources {
-            tex: &texture,
+            tex: render_target.texture(),
             params_buffer: &params_buffer,
         };
 
@@ -91,6 +160,7 @@ impl Game for SerenityCRT {
             edit_state,
             pipeline,
             params_buffer,
+            scene,
         })
     }
 
@@ -116,8 +186,30 @@ impl Game for SerenityCRT {
             flicker_strength: self.edit_state.flicker_strength.value,
         };
 
-        renderer.draw_vertex_count(&self.pipeline, 3, |gpu| {
-            gpu.write_uniform(&mut self.params_buffer, params);
-        })
+        let scene = &mut self.scene;
+        let sprites = scene.sprites(elapsed);
+        let projection = Projection {
+            matrix: Mat4::orthographic_lh(0.0, scene.size.x, scene.size.y, 0.0, 0.0, -1.0),
+        };
+
+        renderer
+            .render_pass()
+            .draw_vertex_count(
+                &scene.pipeline,
+                quad_vertex_count(Scene::SPRITE_COUNT),
+                |gpu| {
+                    let scene_params = SpriteBatchParams {
+                        sprites: gpu.current_immutable_addr(&scene.sprites_buffer),
+                        _padding_0: Default::default(),
+                        projection,
+                    };
+                    gpu.write_uniform(&mut scene.params_buffer, scene_params);
+                    gpu.write_immutable(&mut scene.sprites_buffer, &sprites);
+                },
+            )
+            .draw_vertex_count(&self.pipeline, 3, |gpu| {
+                gpu.write_uniform(&mut self.params_buffer, params);
+            })
+            .submit()
     }
 }
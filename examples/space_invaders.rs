@@ -3,15 +3,13 @@ use std::time::Duration;
 
 use glam::{Mat4, Vec2, Vec3, Vec4};
 
-use vulkan_slang_renderer::game::*;
-use vulkan_slang_renderer::renderer::{
-    DrawError, DrawVertexCount, FrameRenderer, PipelineHandle, Renderer, StorageBufferHandle,
-    TextureFilter, TextureHandle, UniformBufferHandle,
-};
-use vulkan_slang_renderer::util::{load_image, manifest_path};
-
-use vulkan_slang_renderer::generated::shader_atlas::ShaderAtlas;
+use vulkan_slang_renderer::prelude::*;
+use vulkan_slang_renderer::quad::quad_vertex_count;
+use vulkan_slang_renderer::sequences::Sequence;
+use vulkan_slang_renderer::sprites::{Animation, AtlasFrame};
+
 use vulkan_slang_renderer::generated::shader_atlas::space_invaders::*;
+use vulkan_slang_renderer::generated::sprite_atlas::space_invaders::AtlasSprite;
 
 fn main() -> Result<(), anyhow::Error> {
     SpaceInvaders::run()
@@ -27,13 +25,11 @@ struct SpaceInvaders {
     debug_boxes: Vec<DebugBox>,
     player: Player,
     enemies: Vec<Enemy>,
-    sprite_atlas_size: SpriteAtlasSize,
-    player_animation_frames: Vec<SpriteFrame>,
-    enemy_animation_frames: Vec<SpriteFrame>,
     game_screen: GameScreen,
     game_lost_sprite: usize,
     you_win_sprite: usize,
     bullets: Vec<Bullet>,
+    bindings: InputMap<PlayerAction>,
 }
 
 const INITIAL_WINDOW_WIDTH_PIXELS: u32 = 160;
@@ -60,50 +56,35 @@ impl Game for SpaceInvaders {
     where
         Self: Sized,
     {
-        let sprite_atlas = load_sprite_atlas()?;
-
-        let player_animation_frames = get_animation_frames(&sprite_atlas, "ship");
-        assert!(!player_animation_frames.is_empty());
-        let enemy_animation_frames = get_animation_frames(&sprite_atlas, "bug");
-        assert!(!enemy_animation_frames.is_empty());
-        let bullet_animation_frames = get_animation_frames(&sprite_atlas, "bullet");
-        assert!(!bullet_animation_frames.is_empty());
+        let player_animation_frames = AtlasSprite::Ship.frames();
+        let enemy_animation_frames = AtlasSprite::Bug.frames();
+        let bullet_animation_frames = AtlasSprite::Bullet.frames();
 
         let mut sprites = vec![];
 
-        let game_over_frames = get_animation_frames(&sprite_atlas, "game_over");
-        let game_over_frame = &game_over_frames[0].frame;
-        let game_lost_sprite = Sprite::init(&mut sprites, &sprite_atlas.meta.size, game_over_frame);
+        let game_over_frame = &AtlasSprite::GameOver.frames()[0];
+        let game_lost_sprite = Sprite::init(&mut sprites, game_over_frame);
         {
             let game_over_sprite = &mut sprites[game_lost_sprite];
             game_over_sprite.flags |= SPRITE_FLAG_UI;
             game_over_sprite.flags &= !SPRITE_FLAG_VISIBLE;
         }
-        let you_win_frames = get_animation_frames(&sprite_atlas, "you_win");
-        let you_win_frame = &you_win_frames[0].frame;
-        let you_win_sprite = Sprite::init(&mut sprites, &sprite_atlas.meta.size, you_win_frame);
+        let you_win_frame = &AtlasSprite::YouWin.frames()[0];
+        let you_win_sprite = Sprite::init(&mut sprites, you_win_frame);
         {
             let you_win_sprite = &mut sprites[you_win_sprite];
             you_win_sprite.flags |= SPRITE_FLAG_UI;
             you_win_sprite.flags &= !SPRITE_FLAG_VISIBLE;
         }
 
-        let player_sprite = Sprite::init(
-            &mut sprites,
-            &sprite_atlas.meta.size,
-            &player_animation_frames[0].frame,
-        );
-        let enemy_sprite = Sprite::init(
-            &mut sprites,
-            &sprite_atlas.meta.size,
-            &enemy_animation_frames[0].frame,
-        );
-
-        let player_frame = &player_animation_frames[0].frame;
+        let player_sprite = Sprite::init(&mut sprites, &player_animation_frames[0]);
+        let enemy_sprite = Sprite::init(&mut sprites, &enemy_animation_frames[0]);
+
+        let player_frame = &player_animation_frames[0];
         let player = Player {
             sprite_id: player_sprite,
             intent: Default::default(),
-            animation: Animation::from_frames(&player_animation_frames),
+            animation: Animation::new(player_animation_frames),
             bounding_box: BoundingBox {
                 x: 0.0,
                 y: 0.0,
@@ -112,7 +93,7 @@ impl Game for SpaceInvaders {
             },
         };
 
-        let enemy_frame = &enemy_animation_frames[0].frame;
+        let enemy_frame = &enemy_animation_frames[0];
         let enemies = vec![Enemy {
             sprite_id: enemy_sprite,
             bounding_box: BoundingBox {
@@ -122,22 +103,26 @@ impl Game for SpaceInvaders {
                 h: enemy_frame.h as f32,
             },
             intent: EnemyIntent::Right,
-            animation: Animation::from_frames(&enemy_animation_frames),
+            animation: Animation::new(enemy_animation_frames),
             health: 50,
-            movement_script: EnemyMovementScript::new(vec![
-                (EnemyIntent::Right, 100),
-                (EnemyIntent::Down, 200),
-                (EnemyIntent::Left, 100),
-                (EnemyIntent::Up, 100),
-            ]),
+            movement_script: Sequence::builder()
+                .emit(EnemyIntent::Right)
+                .wait(100)
+                .emit(EnemyIntent::Down)
+                .wait(200)
+                .emit(EnemyIntent::Left)
+                .wait(100)
+                .emit(EnemyIntent::Up)
+                .wait(100)
+                .looping(),
         }];
 
-        let bullet_frame = &bullet_animation_frames[0].frame;
+        let bullet_frame = &bullet_animation_frames[0];
         let bullets = {
             let mut bullets = vec![];
 
             for _ in 0..Bullet::MAX_BULLETS {
-                let bullet = Bullet::new(&mut sprites, &sprite_atlas.meta.size, bullet_frame);
+                let bullet = Bullet::new(&mut sprites, bullet_frame);
                 bullets.push(bullet);
             }
 
@@ -165,8 +150,6 @@ impl Game for SpaceInvaders {
         pipeline_config.disable_depth_test = true;
         let pipeline = renderer.create_pipeline(pipeline_config)?;
 
-        let sprite_atlas_size = sprite_atlas.meta.size;
-
         Ok(Self {
             frame_counter: 0,
             pipeline,
@@ -177,56 +160,40 @@ impl Game for SpaceInvaders {
             debug_boxes,
             player,
             enemies,
-            sprite_atlas_size,
-            player_animation_frames,
-            enemy_animation_frames,
             game_screen: Default::default(),
             game_lost_sprite,
             you_win_sprite,
             bullets,
+            bindings: PlayerAction::default_bindings(),
         })
     }
 
-    fn input(&mut self, input: Input) {
-        match input {
-            Input::KeyDown(key) => match key {
-                Key::W => self.player.intent.up = true,
-                Key::A => self.player.intent.left = true,
-                Key::S => self.player.intent.down = true,
-                Key::D => self.player.intent.right = true,
-                Key::Space => self.player.intent.fire = true,
-                _ => {}
-            },
-
-            Input::KeyUp(key) => match key {
-                Key::W => self.player.intent.up = false,
-                Key::A => self.player.intent.left = false,
-                Key::S => self.player.intent.down = false,
-                Key::D => self.player.intent.right = false,
-                Key::Space => self.player.intent.fire = false,
-                _ => {}
-            },
-
-            _ => {}
-        }
-    }
+    fn update(&mut self, keyboard: &KeyboardState, dt: Duration) {
+        let held = |action| self.bindings.is_down(action, keyboard);
+        self.player.intent = PlayerIntent {
+            up: held(PlayerAction::Up),
+            left: held(PlayerAction::Left),
+            down: held(PlayerAction::Down),
+            right: held(PlayerAction::Right),
+            fire: held(PlayerAction::Fire),
+        };
 
-    fn update(&mut self) {
         if self.game_screen.game_over() {
             return;
         }
 
         // timers
         self.frame_counter += 1;
-        let elapsed = self.frame_delay();
-        self.player.animation.tick(elapsed);
+        self.player.animation.tick(dt);
         for enemy in &mut self.enemies {
             if !enemy.is_alive() {
                 continue;
             }
 
-            enemy.animation.tick(elapsed);
-            enemy.movement_script.tick();
+            enemy.animation.tick(dt);
+            for intent in enemy.movement_script.tick() {
+                enemy.intent = intent;
+            }
         }
 
         // player movement
@@ -267,8 +234,6 @@ impl Game for SpaceInvaders {
                 continue;
             }
 
-            enemy.intent = enemy.movement_script.intent();
-
             let enemy_movement = enemy.intent.direction() * Enemy::SPEED;
             enemy.bounding_box.x += enemy_movement.x;
             enemy.bounding_box.y += enemy_movement.y;
@@ -334,8 +299,8 @@ impl Game for SpaceInvaders {
             // dbg!(player_sprite.debug_box_id, debug_box);
         }
 
-        let player_frame = self.player.animation.frame(&self.player_animation_frames);
-        player_sprite.set_frame(player_frame, &self.sprite_atlas_size);
+        let player_frame = self.player.animation.frame();
+        player_sprite.set_frame(player_frame);
 
         // bullet sprites
         for bullet in &self.bullets {
@@ -348,8 +313,8 @@ impl Game for SpaceInvaders {
             let enemy_sprite = &mut self.sprites[enemy.sprite_id];
             enemy_sprite.set_position(&enemy.bounding_box);
 
-            let enemy_frame = enemy.animation.frame(&self.enemy_animation_frames);
-            enemy_sprite.set_frame(enemy_frame, &self.sprite_atlas_size);
+            let enemy_frame = enemy.animation.frame();
+            enemy_sprite.set_frame(enemy_frame);
         }
 
         // game over screens
@@ -375,7 +340,7 @@ impl Game for SpaceInvaders {
             .iter()
             .filter(|sprite| flag_enabled(sprite, SPRITE_FLAG_VISIBLE))
             .count();
-        let vertex_count = visible_sprites as u32 * 6;
+        let vertex_count = quad_vertex_count(visible_sprites as u32);
 
         renderer.draw_vertex_count(&self.pipeline, vertex_count, |gpu| {
             let params = SpaceInvadersParams {
@@ -444,6 +409,31 @@ impl Player {
     const SPEED: f32 = 2.0;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlayerAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Fire,
+}
+
+impl PlayerAction {
+    /// WASD or the arrows to move, space to fire
+    fn default_bindings() -> InputMap<Self> {
+        InputMap::new()
+            .bind(Self::Up, Key::W)
+            .bind(Self::Up, Key::Up)
+            .bind(Self::Left, Key::A)
+            .bind(Self::Left, Key::Left)
+            .bind(Self::Down, Key::S)
+            .bind(Self::Down, Key::Down)
+            .bind(Self::Right, Key::D)
+            .bind(Self::Right, Key::Right)
+            .bind(Self::Fire, Key::Space)
+    }
+}
+
 #[derive(Default)]
 struct PlayerIntent {
     up: bool,
@@ -479,7 +469,8 @@ struct Enemy {
     animation: Animation,
     bounding_box: BoundingBox,
     health: i32,
-    movement_script: EnemyMovementScript,
+    /// one tick per frame
+    movement_script: Sequence<EnemyIntent>,
 }
 
 impl Enemy {
@@ -527,12 +518,8 @@ impl Bullet {
     const HIT_BOX_OFFSET: f32 = 15.0;
     const HIT_BOX_SIDE: f32 = 2.0;
 
-    fn new(
-        sprites: &mut Vec<Sprite>,
-        atlas_size: &SpriteAtlasSize,
-        offsets: &SpriteAtlasFrameOffsets,
-    ) -> Self {
-        let sprite_id = Sprite::init(sprites, atlas_size, offsets);
+    fn new(sprites: &mut Vec<Sprite>, frame: &AtlasFrame) -> Self {
+        let sprite_id = Sprite::init(sprites, frame);
 
         let bullet_sprite = &mut sprites[sprite_id];
         bullet_sprite.flags &= !SPRITE_FLAG_VISIBLE;
@@ -540,8 +527,8 @@ impl Bullet {
         let bounding_box = BoundingBox {
             x: 0.0,
             y: 0.0,
-            w: offsets.w as f32,
-            h: offsets.h as f32,
+            w: frame.w as f32,
+            h: frame.h as f32,
         };
 
         Bullet {
@@ -626,184 +613,19 @@ fn load_texture(renderer: &mut Renderer, file_name: &str) -> anyhow::Result<Text
     Ok(texture)
 }
 
-// Aseprite integration
-
-fn load_sprite_atlas() -> anyhow::Result<SpriteAtlas> {
-    let sprite_atlas_path = manifest_path(["textures", "space_invaders", "sprite_sheet.json"]);
-
-    let sprite_atlas_json = std::fs::read_to_string(&sprite_atlas_path)?;
-    let sprite_atlas: SpriteAtlas = serde_json::from_str(&sprite_atlas_json)?;
-
-    Ok(sprite_atlas)
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct SpriteAtlas {
-    meta: SpriteAtlasMeta,
-    frames: Vec<SpriteFrame>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct SpriteAtlasMeta {
-    size: SpriteAtlasSize,
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct SpriteAtlasSize {
-    w: usize,
-    h: usize,
-}
-
-#[derive(Debug, serde::Deserialize, Clone)]
-struct SpriteFrame {
-    filename: String,
-    frame: SpriteAtlasFrameOffsets,
-    duration: u64,
-}
-
-#[derive(Debug, serde::Deserialize, Clone)]
-struct SpriteAtlasFrameOffsets {
-    x: usize,
-    y: usize,
-    w: usize,
-    h: usize,
-}
-
-fn get_animation_frames(sprite_atlas: &SpriteAtlas, name: &str) -> Vec<SpriteFrame> {
-    sprite_atlas
-        .frames
-        .iter()
-        .filter(|f| match f.filename.rsplit_once(" ") {
-            Some((title, _frame_index)) => title == name,
-            None => f.filename == name,
-        })
-        .cloned()
-        .collect()
-}
-
-struct Animation {
-    /// the index of the current frame in the animation
-    current_frame: usize,
-    /// the remaining millis of the current frame
-    frame_millis: usize,
-    /// the time within the full animation;
-    /// always less than total_duration
-    timer: Duration,
-    /// the sum total duration of all animation frames
-    total_duration: Duration,
-    /// the individual durations of each frame, in milliseconds
-    frame_durations: Vec<u64>,
-}
-
-impl Animation {
-    fn from_frames(frames: &[SpriteFrame]) -> Self {
-        let frame_durations: Vec<_> = frames.iter().map(|f| f.duration).collect();
-        let total_duration = Duration::from_millis(frame_durations.iter().sum());
-
-        Self {
-            current_frame: 0,
-            frame_millis: 0,
-            timer: Duration::ZERO,
-            total_duration,
-            frame_durations,
-        }
-    }
-
-    fn tick(&mut self, elapsed: Duration) {
-        self.timer += elapsed;
-        self.timer = mod_duration(self.timer, self.total_duration);
-
-        let mut next_current_frame = self.current_frame;
-        self.frame_millis += elapsed.as_millis() as usize;
-        loop {
-            let current_frame_duration = self.frame_durations[next_current_frame] as usize;
-
-            if self.frame_millis >= current_frame_duration {
-                self.frame_millis %= current_frame_duration;
-                next_current_frame += 1;
-                next_current_frame %= self.frame_durations.len();
-            } else {
-                break;
-            }
-        }
-
-        self.current_frame = next_current_frame;
-    }
-
-    fn frame<'f>(&self, frames: &'f [SpriteFrame]) -> &'f SpriteFrame {
-        &frames[self.current_frame % frames.len()]
-    }
-}
-
-fn mod_duration(timer: Duration, limit: Duration) -> Duration {
-    let millis = timer.as_millis() % limit.as_millis();
-    Duration::from_millis(millis as u64)
-}
-
-struct EnemyMovementScript {
-    steps: Vec<(EnemyIntent, usize)>,
-    total_frames: usize,
-    frame_counter: usize,
-}
-
-impl EnemyMovementScript {
-    fn new(steps: Vec<(EnemyIntent, usize)>) -> Self {
-        assert!(!steps.is_empty());
-
-        let total_frames = steps.iter().map(|(_intent, frames)| frames).sum();
-
-        Self {
-            steps,
-            total_frames,
-            frame_counter: 0,
-        }
-    }
-
-    fn tick(&mut self) {
-        self.frame_counter += 1;
-        self.frame_counter %= self.total_frames;
-    }
-
-    fn intent(&self) -> EnemyIntent {
-        let mut remaining_frames = self.frame_counter;
-
-        for &(intent, step_frames) in &self.steps {
-            if step_frames >= remaining_frames {
-                return intent;
-            }
-
-            remaining_frames -= step_frames
-        }
-
-        debug_assert!(false, "invalid enemy movement script");
-
-        self.steps[0].0
-    }
-}
-
 const SPRITE_SCALE: f32 = 5.0;
 
 trait CPUSprite {
-    fn init(
-        sprites: &mut Vec<Sprite>,
-        sprite_atlas_size: &SpriteAtlasSize,
-        frame: &SpriteAtlasFrameOffsets,
-    ) -> usize;
+    fn init(sprites: &mut Vec<Sprite>, frame: &AtlasFrame) -> usize;
 
-    fn set_frame(&mut self, sprite_frame: &SpriteFrame, sprite_atlas_size: &SpriteAtlasSize);
+    fn set_frame(&mut self, frame: &AtlasFrame);
 
     fn set_position(&mut self, bounding_box: &BoundingBox);
 }
 
 impl CPUSprite for Sprite {
-    fn init(
-        sprites: &mut Vec<Sprite>,
-        sprite_atlas_size: &SpriteAtlasSize,
-        frame: &SpriteAtlasFrameOffsets,
-    ) -> usize {
-        let sheet_width = sprite_atlas_size.w as f32;
-        let sheet_height = sprite_atlas_size.h as f32;
-
+    fn init(sprites: &mut Vec<Sprite>, frame: &AtlasFrame) -> usize {
+        let [tex_u, tex_v, tex_w, tex_h] = frame.uv;
         let sprite = Sprite {
             scale: Vec2::new(frame.w as f32 * SPRITE_SCALE, frame.h as f32 * SPRITE_SCALE),
             flags: SPRITE_FLAG_VISIBLE,
@@ -812,10 +634,10 @@ impl CPUSprite for Sprite {
             position: Vec3::ZERO,
             rotation: 0.0,
 
-            tex_u: frame.x as f32 / sheet_width,
-            tex_v: frame.y as f32 / sheet_height,
-            tex_w: frame.w as f32 / sheet_width,
-            tex_h: frame.h as f32 / sheet_height,
+            tex_u,
+            tex_v,
+            tex_w,
+            tex_h,
 
             color: Vec4::splat(1.0),
         };
@@ -826,15 +648,8 @@ impl CPUSprite for Sprite {
         sprite_id
     }
 
-    fn set_frame(&mut self, sprite_frame: &SpriteFrame, sprite_atlas_size: &SpriteAtlasSize) {
-        let sheet_width = sprite_atlas_size.w as f32;
-        let sheet_height = sprite_atlas_size.h as f32;
-        let frame = &sprite_frame.frame;
-
-        self.tex_u = frame.x as f32 / sheet_width;
-        self.tex_v = frame.y as f32 / sheet_height;
-        self.tex_w = frame.w as f32 / sheet_width;
-        self.tex_h = frame.h as f32 / sheet_height;
+    fn set_frame(&mut self, frame: &AtlasFrame) {
+        [self.tex_u, self.tex_v, self.tex_w, self.tex_h] = frame.uv;
     }
 
     fn set_position(&mut self, bounding_box: &BoundingBox) {
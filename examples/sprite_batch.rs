@@ -5,6 +5,9 @@
 //!
 //! which uses the method described in this blog post:
 //! https://moonside.games/posts/sdl-gpu-sprite-batcher/
+//!
+//! Sprites are split across two textures, and grouped into one draw per texture
+//! with SpritePages. The sprite under the cursor is found with a SpatialIndex2D.
 
 use std::collections::VecDeque;
 use std::f32::consts::TAU;
@@ -12,14 +15,15 @@ use std::time::{Duration, Instant};
 
 use facet::Facet;
 use glam::{Mat4, Vec2, Vec3, Vec4};
-use sdl3::sys::everything::{SDL_rand, SDL_randf, SDL_srand};
 
 use vulkan_slang_renderer::editor::Label;
-use vulkan_slang_renderer::game::{Game, MaxMSAASamples};
+use vulkan_slang_renderer::game::{Game, Input, KeyboardState, MaxMSAASamples};
+use vulkan_slang_renderer::random::Rng;
 use vulkan_slang_renderer::renderer::{
-    DrawError, DrawVertexCount, FrameRenderer, ImmutableBufferHandle, PipelineHandle, Renderer,
-    TextureFilter, UniformBufferHandle,
+    DrawError, FrameRenderer, ImmutableBufferHandle, Renderer, TextureFilter, UniformBufferHandle,
 };
+use vulkan_slang_renderer::spatial_index::{Rect, SpatialIndex2D};
+use vulkan_slang_renderer::sprite_pages::{PageId, SpritePages};
 use vulkan_slang_renderer::util::load_image;
 
 use vulkan_slang_renderer::generated::shader_atlas::ShaderAtlas;
@@ -32,13 +36,21 @@ fn main() -> Result<(), anyhow::Error> {
 #[derive(Facet)]
 pub struct EditState {
     fps: Label,
+    batching: Label,
+    hovered: Label,
 }
 
 pub struct SpriteBatch {
-    pipeline: PipelineHandle<DrawVertexCount>,
+    pages: SpritePages<Sprite>,
+    page_ids: [PageId; 2],
     params_buffer: UniformBufferHandle<SpriteBatchParams>,
     sprites_buffer: ImmutableBufferHandle<Sprite>,
-    sprites: Vec<Sprite>,
+    /// sprites and the index into page_ids of the texture they sample
+    sprites: Vec<(usize, Sprite)>,
+    rng: Rng,
+    /// the drawn sprites' bounds, by index in the sprite buffer
+    sprite_index: SpatialIndex2D<usize>,
+    mouse_position: Vec2,
     edit_state: EditState,
     last_frame_time: Instant,
     frame_times: VecDeque<Duration>,
@@ -54,8 +66,8 @@ impl Game for SpriteBatch {
         "Sprite Batch"
     }
 
-    fn frame_delay(&self) -> Duration {
-        Duration::from_nanos(10)
+    fn frame_delay(&self) -> Option<Duration> {
+        Some(Duration::from_nanos(10))
     }
 
     fn setup(renderer: &mut Renderer) -> anyhow::Result<Self>
@@ -65,35 +77,49 @@ impl Game for SpriteBatch {
         let mut sprites = Vec::with_capacity(SPRITE_COUNT);
         for _ in 0..SPRITE_COUNT {
             let sprite = init_sprite();
-            sprites.push(sprite);
+            sprites.push((0, sprite));
         }
 
-        unsafe { SDL_srand(0) };
-
         let params_buffer = renderer.create_uniform_buffer::<SpriteBatchParams>()?;
         let sprites_buffer = renderer.create_immutable_buffer::<Sprite>(sprites.len() as u32)?;
 
-        let image_file_name = "ravioli_atlas.bmp";
-        let image = load_image(image_file_name)?;
-        let texture = renderer.create_texture(image_file_name, &image, TextureFilter::Nearest)?;
+        // each page is a pipeline binding its own texture;
+        // they all read from the same params and sprite buffers
+        let mut pages = SpritePages::new();
+        let mut add_page = |image_file_name: &str, filter: TextureFilter| {
+            let image = load_image(image_file_name)?;
+            let texture = renderer.create_texture(image_file_name, &image, filter)?;
 
-        let resources = Resources {
-            params_buffer: &params_buffer,
-            texture: &texture,
-        };
+            let resources = Resources {
+                params_buffer: &params_buffer,
+                texture: &texture,
+            };
 
-        let shader = ShaderAtlas::init().sprite_batch;
-        let mut pipeline_config = shader.pipeline_config(resources);
-        pipeline_config.disable_depth_test = true;
-        let pipeline = renderer.create_pipeline(pipeline_config)?;
+            let shader = ShaderAtlas::init().sprite_batch;
+            let mut pipeline_config = shader.pipeline_config(resources);
+            pipeline_config.disable_depth_test = true;
+            let pipeline = renderer.create_pipeline(pipeline_config)?;
+
+            anyhow::Ok(pages.add_page(pipeline))
+        };
+        let page_ids = [
+            add_page("ravioli_atlas.bmp", TextureFilter::Nearest)?,
+            add_page("texture.jpg", TextureFilter::Linear)?,
+        ];
 
         Ok(Self {
-            pipeline,
+            pages,
+            page_ids,
             params_buffer,
             sprites_buffer,
             sprites,
+            rng: Rng::new(0),
+            sprite_index: SpatialIndex2D::new(32.0),
+            mouse_position: Vec2::ZERO,
             edit_state: EditState {
                 fps: Label::new("FPS: --"),
+                batching: Label::new("draws: --"),
+                hovered: Label::new("hovered: none"),
             },
             last_frame_time: Instant::now(),
             frame_times: VecDeque::with_capacity(FRAME_HISTORY_SIZE),
@@ -104,7 +130,13 @@ impl Game for SpriteBatch {
         MaxMSAASamples::Max2
     }
 
-    fn update(&mut self) {
+    fn input(&mut self, input: Input) {
+        if let Input::MouseMotion { x, y } = input {
+            self.mouse_position = Vec2::new(x, y);
+        }
+    }
+
+    fn update(&mut self, _keyboard: &KeyboardState, _dt: Duration) {
         let now = Instant::now();
         let delta = now.duration_since(self.last_frame_time);
         self.last_frame_time = now;
@@ -121,28 +153,53 @@ impl Game for SpriteBatch {
 
         let window_size = Self::initial_window_size();
 
-        for sprite in &mut self.sprites {
-            randomize_sprite(sprite, window_size);
+        for (page, sprite) in &mut self.sprites {
+            *page = randomize_sprite(&mut self.rng, sprite, window_size);
         }
     }
 
-    fn draw(&mut self, renderer: FrameRenderer) -> Result<(), DrawError> {
+    fn draw(&mut self, mut renderer: FrameRenderer) -> Result<(), DrawError> {
         let (width, height) = Self::initial_window_size();
         let projection = Projection {
             matrix: Mat4::orthographic_lh(0.0, width as f32, height as f32, 0.0, 0.0, -1.0),
         };
-        // 6 = the corners in 2 triangles to make a quad
-        let vertex_count = self.sprites.len() as u32 * 6;
 
-        renderer.draw_vertex_count(&self.pipeline, vertex_count, |gpu| {
+        for (page, sprite) in &self.sprites {
+            self.pages.push(self.page_ids[*page], sprite.clone());
+        }
+        self.pages.finish();
+
+        self.pages.queue_draws(&mut renderer);
+        renderer.submit_draws(|gpu| {
             let params = SpriteBatchParams {
                 sprites: gpu.current_immutable_addr(&self.sprites_buffer),
                 _padding_0: Default::default(),
                 projection,
             };
             gpu.write_uniform(&mut self.params_buffer, params);
-            gpu.write_immutable(&mut self.sprites_buffer, &self.sprites);
-        })
+            gpu.write_immutable(&mut self.sprites_buffer, self.pages.sprites());
+        })?;
+
+        // inserted in draw order, so the last hit is the one on top
+        self.sprite_index.clear();
+        for (i, sprite) in self.pages.sprites().iter().enumerate() {
+            let bounds =
+                Rect::rotated_quad(sprite.position.truncate(), sprite.scale, sprite.rotation);
+            self.sprite_index.insert(bounds, i);
+        }
+        let hovered = self.sprite_index.query_point(self.mouse_position).last();
+        self.edit_state.hovered.set(match hovered {
+            Some(i) => format!("hovered: sprite {i}"),
+            None => "hovered: none".to_string(),
+        });
+
+        let stats = self.pages.stats();
+        self.edit_state.batching.set(format!(
+            "{} draws for {} page switches",
+            stats.draws, stats.batch_breaks
+        ));
+
+        Ok(())
     }
 
     fn editor_ui(&mut self) -> Option<(&str, &mut Self::EditState)> {
@@ -150,19 +207,22 @@ impl Game for SpriteBatch {
     }
 }
 
-fn randomize_sprite(sprite: &mut Sprite, (width, height): (u32, u32)) {
+/// returns the index of the page the sprite now samples
+fn randomize_sprite(rng: &mut Rng, sprite: &mut Sprite, (width, height): (u32, u32)) -> usize {
     // the U and V offsets into the sprite sheet for the 4 sprites
     const U_COORDS: [f32; 4] = [0.0, 0.5, 0.0, 0.5];
     const V_COORDS: [f32; 4] = [0.0, 0.0, 0.5, 0.5];
 
-    sprite.position.x = unsafe { SDL_rand(width as i32) } as f32;
-    sprite.position.y = unsafe { SDL_rand(height as i32) } as f32;
+    sprite.position.x = rng.below(width) as f32;
+    sprite.position.y = rng.below(height) as f32;
 
-    sprite.rotation = unsafe { SDL_randf() } * TAU;
+    sprite.rotation = rng.f32() * TAU;
 
-    let sprite_index = unsafe { SDL_rand(4) } as usize;
+    let sprite_index = rng.index(4);
     sprite.tex_u = U_COORDS[sprite_index];
     sprite.tex_v = V_COORDS[sprite_index];
+
+    rng.index(2)
 }
 
 fn init_sprite() -> Sprite {
@@ -853,7 +853,7 @@ impl Game for Watercolor {
         })
     }
 
-    fn update(&mut self) {
+    fn update(&mut self, _keyboard: &KeyboardState, _dt: Duration) {
         let now = Instant::now();
         let delta = now.duration_since(self.last_frame_time);
         self.last_frame_time = now;
@@ -18,6 +18,15 @@ impl<T> Clone for Addr<T> {
 
 impl<T> Copy for Addr<T> {}
 
+impl<T> Default for Addr<T> {
+    fn default() -> Self {
+        Self {
+            address: 0,
+            _pointee: PhantomData,
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for Addr<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Addr({:#x})", self.address)
@@ -55,6 +64,15 @@ impl<T> Clone for ReadAddr<T> {
 
 impl<T> Copy for ReadAddr<T> {}
 
+impl<T> Default for ReadAddr<T> {
+    fn default() -> Self {
+        Self {
+            address: 0,
+            _pointee: PhantomData,
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for ReadAddr<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "ReadAddr({:#x})", self.address)
@@ -92,6 +110,15 @@ impl<T> Clone for ImmutableAddr<T> {
 
 impl<T> Copy for ImmutableAddr<T> {}
 
+impl<T> Default for ImmutableAddr<T> {
+    fn default() -> Self {
+        Self {
+            address: 0,
+            _pointee: PhantomData,
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for ImmutableAddr<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "ImmutableAddr({:#x})", self.address)
@@ -1,6 +1,73 @@
+use super::{Addr, ImmutableAddr, ReadAddr};
+
 /// Marker type for shaders that don't use vertex input buffers.
 pub enum NoVertex {}
 
 pub trait GPUWrite {}
 
 impl GPUWrite for NoVertex {}
+
+/// Stub of the real renderer::gpu_write::SampleValue (src/renderer/gpu_write.rs)
+pub trait SampleValue {
+    fn sample_at(word: u32) -> Self;
+}
+
+impl SampleValue for f32 {
+    fn sample_at(word: u32) -> Self {
+        word as f32
+    }
+}
+
+impl SampleValue for u32 {
+    fn sample_at(word: u32) -> Self {
+        word
+    }
+}
+
+impl SampleValue for u64 {
+    fn sample_at(word: u32) -> Self {
+        word as u64
+    }
+}
+
+impl SampleValue for glam::Vec2 {
+    fn sample_at(word: u32) -> Self {
+        glam::Vec2::splat(word as f32)
+    }
+}
+
+impl SampleValue for glam::Vec3 {
+    fn sample_at(word: u32) -> Self {
+        glam::Vec3::splat(word as f32)
+    }
+}
+
+impl SampleValue for glam::Vec4 {
+    fn sample_at(word: u32) -> Self {
+        glam::Vec4::splat(word as f32)
+    }
+}
+
+impl SampleValue for glam::Mat4 {
+    fn sample_at(word: u32) -> Self {
+        glam::Mat4::from_diagonal(glam::Vec4::splat(word as f32))
+    }
+}
+
+impl<T> SampleValue for Addr<T> {
+    fn sample_at(_word: u32) -> Self {
+        Self::default()
+    }
+}
+
+impl<T> SampleValue for ReadAddr<T> {
+    fn sample_at(_word: u32) -> Self {
+        Self::default()
+    }
+}
+
+impl<T> SampleValue for ImmutableAddr<T> {
+    fn sample_at(_word: u32) -> Self {
+        Self::default()
+    }
+}
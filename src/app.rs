@@ -1,81 +1,881 @@
-use std::time::Instant;
+#[cfg(feature = "editor")]
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use sdl3::EventPump;
 use sdl3::event::{Event, WindowEvent};
 use sdl3::keyboard::Keycode;
+use sdl3::sys::events::SDL_WaitEventTimeout;
 use sdl3::sys::timer::SDL_DelayPrecise;
 
+#[cfg(feature = "editor")]
+use crate::game::Presentation;
+use crate::game::RedrawMode;
 use crate::game::traits::RuntimeGame;
-use crate::renderer::{FrameRenderer, Renderer};
-use crate::traits::{Input, Key, MouseButton};
+use crate::gamepads::Gamepads;
+#[cfg(feature = "editor")]
+use crate::renderer::debug;
+#[cfg(feature = "editor")]
+use crate::renderer::{
+    AuditedBuffer, InspectableBuffer, InspectedField, InspectedImage, InspectorSnapshot,
+    InspectorTarget, InspectorView, PixelDebugTarget, RendererSettings, ShadingRate, UpscaleFilter,
+};
+use crate::renderer::{DrawError, FrameRenderer, Renderer};
+use crate::time_control::TimeControl;
+#[cfg(debug_assertions)]
+use crate::time_control::{FAST_FORWARD_SCALE, SLOW_MOTION_SCALE};
+use crate::traits::{
+    DrawErrorPolicy, GamepadAxis, GamepadButton, Input, InputEvent, Key, KeyboardState,
+    MouseButton, is_emulated_mouse,
+};
+
+/// captures the next frame when running under RenderDoc,
+/// which uses F12 and Print Screen for its own capture keys
+const CAPTURE_KEY: Keycode = Keycode::F10;
+
+/// debug time controls, in debug builds; see [`TimeControl`]
+#[cfg(debug_assertions)]
+const PAUSE_KEY: Keycode = Keycode::F5;
+#[cfg(debug_assertions)]
+const STEP_KEY: Keycode = Keycode::F6;
+#[cfg(debug_assertions)]
+const SLOW_MOTION_KEY: Keycode = Keycode::F7;
+#[cfg(debug_assertions)]
+const FAST_FORWARD_KEY: Keycode = Keycode::F8;
+
+/// the frame delay when the display's refresh rate is unknown; about 60 fps
+const FALLBACK_FRAME_DELAY: Duration = Duration::from_millis(15);
 
 pub struct App {
     renderer: Renderer,
     pub game: Box<dyn RuntimeGame>,
-    pub minimized: bool,
+    keyboard: KeyboardState,
+    gamepads: Gamepads,
+    /// the last pressure reported by a pen axis event, which
+    /// SDL sends separately from pen down and motion
+    pen_pressure: f32,
+    /// a refresh of the window's display, for games without a frame delay
+    /// of their own; updated when the window moves to another display
+    display_frame_delay: Duration,
+    redraw_mode: RedrawMode,
+    /// whether the next frame is drawn without waiting for an event,
+    /// under [`RedrawMode::OnDemand`]
+    redraw_requested: bool,
+    pub time: TimeControl,
+    /// the error chain of a failed draw, shown on the error screen;
+    /// the game is paused while it's set
+    draw_error: Option<Vec<String>>,
+    window: WindowState,
+    #[cfg(feature = "editor")]
+    texture_inspector: TextureInspectorUi,
+    #[cfg(feature = "editor")]
+    buffer_inspector: BufferInspectorUi,
+    /// the index into [`Renderer::shading_rates`] whose regions are drawn
+    /// over the frame
+    #[cfg(feature = "editor")]
+    shading_rate_overlay: Option<usize>,
+    #[cfg(feature = "editor")]
+    settings: SettingsUi,
     pub quit: bool,
 }
 
 impl App {
-    pub fn init(renderer: Renderer, game: impl RuntimeGame + 'static) -> anyhow::Result<App> {
+    pub fn init(
+        renderer: Renderer,
+        game: impl RuntimeGame + 'static,
+        gamepads: Gamepads,
+    ) -> anyhow::Result<App> {
+        let display_frame_delay = refresh_frame_delay(renderer.display_refresh_rate());
+        let redraw_mode = game.redraw_mode();
         Ok(Self {
             renderer,
             game: Box::new(game),
-            minimized: false,
+            keyboard: KeyboardState::default(),
+            gamepads,
+            pen_pressure: 0.0,
+            display_frame_delay,
+            redraw_mode,
+            redraw_requested: true,
+            time: TimeControl::new(),
+            draw_error: None,
+            window: WindowState::default(),
+            #[cfg(feature = "editor")]
+            texture_inspector: TextureInspectorUi::default(),
+            #[cfg(feature = "editor")]
+            buffer_inspector: BufferInspectorUi::default(),
+            #[cfg(feature = "editor")]
+            shading_rate_overlay: None,
+            #[cfg(feature = "editor")]
+            settings: SettingsUi::default(),
             quit: false,
         })
     }
 
+    /// The player's settings the renderer started with, for the Settings
+    /// window to edit and save to `path`
+    #[cfg(feature = "editor")]
+    pub fn with_settings(mut self, settings: RendererSettings, path: Option<PathBuf>) -> Self {
+        self.settings = SettingsUi {
+            settings,
+            path,
+            status: None,
+        };
+        self
+    }
+
+    /// The game's frame delay, or else the display's
+    fn frame_delay(&self) -> Duration {
+        self.game.frame_delay().unwrap_or(self.display_frame_delay)
+    }
+
+    /// The connected gamepads, to rumble outside of [`Game::gamepad_feedback`]
+    ///
+    /// [`Game::gamepad_feedback`]: crate::game::Game::gamepad_feedback
+    pub fn gamepads(&mut self) -> &mut Gamepads {
+        &mut self.gamepads
+    }
+
     pub fn run_loop(mut self, mut event_pump: EventPump) -> anyhow::Result<()> {
+        let result = self.run_frames(&mut event_pump);
+
+        // drain after a draw error too, so the renderer isn't torn down under
+        // the GPU; a failed drain is logged, leaving the draw error to report
+        if let Err(error) = self.renderer.drain_gpu() {
+            if result.is_ok() {
+                return Err(error);
+            }
+            log::error!("failed to drain the gpu: {error}");
+        }
+
+        result
+    }
+
+    fn run_frames(&mut self, event_pump: &mut EventPump) -> anyhow::Result<()> {
         let mut end_of_last_frame = Instant::now();
+        let mut last_update = Instant::now();
 
         loop {
-            let Ok(()) = self.handle_events(&mut event_pump) else {
+            if self.redraw_mode == RedrawMode::OnDemand && !self.redraw_requested {
+                self.wait_for_redraw();
+            }
+
+            let Ok(()) = self.handle_events(event_pump) else {
                 break;
             };
             if self.quit {
                 break;
             }
 
-            if !self.minimized {
-                self.game.update();
+            if !self.window.minimized {
+                let now = Instant::now();
+                let real_dt = now - last_update;
+                last_update = now;
+                if self.draw_error.is_none()
+                    && let Some(dt) = self.time.update_dt(real_dt, self.frame_delay())
+                {
+                    self.game.update(&self.keyboard, dt);
+                    self.game.gamepad_feedback(&mut self.gamepads);
+                }
 
-                self.renderer.begin_egui_frame();
-                if let Some(ctx) = self.renderer.egui_context() {
-                    self.game.draw_edit_ui(&ctx);
+                #[cfg(feature = "editor")]
+                {
+                    self.renderer.begin_egui_frame();
+                    if let Some(ctx) = self.renderer.egui_context() {
+                        let overlay = self.renderer.scene_overlay()?;
+                        self.game.draw_edit_ui(&ctx, overlay);
+                        self.draw_capture_ui(&ctx);
+                        self.draw_time_ui(&ctx);
+                        self.draw_upscale_ui(&ctx);
+                        self.draw_hdr_ui(&ctx);
+                        self.draw_shading_rate_ui(&ctx);
+                        self.draw_settings_ui(&ctx);
+                        self.draw_printf_console(&ctx);
+                        self.draw_pixel_debugger(&ctx);
+                        self.draw_texture_inspector(&ctx);
+                        self.draw_buffer_inspector(&ctx);
+                        self.draw_error_screen(&ctx);
+                    }
                 }
 
-                let frame_renderer = FrameRenderer::new(&mut self.renderer);
-                self.game.draw_frame(frame_renderer)?;
+                if self.draw_error.is_some() {
+                    // nothing of the game's, just the error screen over a cleared frame;
+                    // if even that fails, the renderer itself is broken
+                    FrameRenderer::new(&mut self.renderer).submit_draws(|_gpu| {})?;
+                } else {
+                    let frame_renderer = FrameRenderer::new(&mut self.renderer);
+                    if let Err(error) = self.game.draw_frame(frame_renderer) {
+                        self.handle_draw_error(error)?;
+                    }
+                }
             }
+            self.redraw_requested = self.renderer.take_redraw_request();
 
-            let spent_frame_time = (Instant::now() - end_of_last_frame).as_nanos() as u64;
-            let desired_frame_time = self.game.frame_delay().as_nanos() as u64;
-            let remaining_frame_time = desired_frame_time.saturating_sub(spent_frame_time);
-            if remaining_frame_time > 0 {
-                unsafe { SDL_DelayPrecise(remaining_frame_time) };
-            }
+            let frame_delay = self.frame_delay();
+            let minimized = self.window.minimized;
+            let renderer = &mut self.renderer;
+            pace_frame(
+                end_of_last_frame,
+                frame_delay,
+                || Ok(!minimized && renderer.wait_for_display()?),
+                |remaining| unsafe { SDL_DelayPrecise(remaining.as_nanos() as u64) },
+            )?;
 
             end_of_last_frame = Instant::now();
         }
 
-        self.renderer.drain_gpu()?;
-
         Ok(())
     }
 
+    /// Sleeps until there's an event to handle, or the editor overlay wants
+    /// to draw again; the event is left queued for [`Self::handle_events`]
+    fn wait_for_redraw(&self) {
+        #[cfg(feature = "editor")]
+        let deadline = self.renderer.egui_repaint_deadline();
+        #[cfg(not(feature = "editor"))]
+        let deadline = None;
+
+        let timeout_ms = wait_timeout_ms(Instant::now(), deadline);
+        if timeout_ms != 0 {
+            unsafe { SDL_WaitEventTimeout(std::ptr::null_mut(), timeout_ms) };
+        }
+    }
+
+    /// Applies the game's [`DrawErrorPolicy`], returning the error when it's time to exit
+    fn handle_draw_error(&mut self, error: DrawError) -> Result<(), DrawError> {
+        let policy = match self.game.draw_error_policy(&error) {
+            DrawErrorPolicy::ShowErrorScreen if !self.has_error_screen() => DrawErrorPolicy::Exit,
+            policy => policy,
+        };
+
+        match policy {
+            DrawErrorPolicy::Exit => Err(error),
+            DrawErrorPolicy::SkipFrame => {
+                log::error!("skipped a frame: {error}");
+                Ok(())
+            }
+            DrawErrorPolicy::ShowErrorScreen => {
+                log::error!("{error}");
+                let error = anyhow::Error::from(error);
+                self.draw_error = Some(error.chain().map(|cause| cause.to_string()).collect());
+                // the game is paused; don't leave a long rumble going under the error screen
+                self.gamepads.stop_rumble();
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg(feature = "editor")]
+    fn has_error_screen(&mut self) -> bool {
+        self.renderer.egui().is_some()
+    }
+
+    #[cfg(not(feature = "editor"))]
+    fn has_error_screen(&mut self) -> bool {
+        false
+    }
+
+    /// the failed draw's error chain, with ways out
+    #[cfg(feature = "editor")]
+    fn draw_error_screen(&mut self, ctx: &egui::Context) {
+        let Some(chain) = &self.draw_error else {
+            return;
+        };
+
+        let mut retry = false;
+        #[cfg(all(debug_assertions, feature = "shader-compiler"))]
+        let mut reload_shaders = false;
+        egui::Window::new("Draw error")
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                for (depth, cause) in chain.iter().enumerate() {
+                    let text = egui::RichText::new(cause).monospace();
+                    if depth == 0 {
+                        ui.label(text.strong());
+                    } else {
+                        ui.label(text);
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    retry = ui.button("Retry").clicked();
+                    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
+                    {
+                        reload_shaders = ui.button("Reload shaders").clicked();
+                    }
+                    if ui.button("Quit").clicked() {
+                        self.quit = true;
+                    }
+                });
+            });
+
+        #[cfg(all(debug_assertions, feature = "shader-compiler"))]
+        if reload_shaders {
+            self.renderer.request_shader_reload();
+            retry = true;
+        }
+        if retry {
+            self.draw_error = None;
+        }
+    }
+
+    /// a capture button, when running under RenderDoc
+    #[cfg(feature = "editor")]
+    fn draw_capture_ui(&mut self, ctx: &egui::Context) {
+        if !self.renderer.can_capture_frames() {
+            return;
+        }
+
+        let mut capture = false;
+        egui::Window::new("RenderDoc")
+            .default_open(false)
+            .show(ctx, |ui| {
+                capture = ui.button("Capture frame (F10)").clicked();
+            });
+
+        if capture {
+            self.renderer.trigger_capture();
+        }
+    }
+
+    /// pause, step, and speed controls, in debug builds
+    #[cfg(feature = "editor")]
+    fn draw_time_ui(&mut self, ctx: &egui::Context) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        egui::Window::new("Time")
+            .default_open(false)
+            .show(ctx, |ui| {
+                self.time.ui(ui, ["F5", "F6", "F7", "F8"]);
+                if let Some(latency) = self.renderer.frame_stats().display_latency {
+                    ui.label(format!(
+                        "display latency {:.1}ms",
+                        latency.as_secs_f64() * 1000.0
+                    ));
+                }
+            });
+    }
+
+    /// the upscale filter, when rendering below window resolution
+    #[cfg(feature = "editor")]
+    fn draw_upscale_ui(&mut self, ctx: &egui::Context) {
+        let integer_scaled = matches!(
+            self.renderer.presentation(),
+            Presentation::Virtual {
+                integer_scale: true,
+                ..
+            }
+        );
+        if self.renderer.render_scale() >= 1.0 || integer_scaled {
+            return;
+        }
+
+        let mut filter = self.renderer.upscale_filter();
+        egui::Window::new("Upscale")
+            .default_open(false)
+            .show(ctx, |ui| {
+                ui.label(format!("render scale {:.2}", self.renderer.render_scale()));
+                for option in UpscaleFilter::ALL {
+                    ui.radio_value(&mut filter, option, option.name());
+                }
+            });
+        if let Err(error) = self.renderer.set_upscale_filter(filter) {
+            log::error!("failed to set the upscale filter: {error}");
+        }
+    }
+
+    /// the display's luminance, and an SDR preview, on an HDR swapchain
+    #[cfg(feature = "editor")]
+    fn draw_hdr_ui(&mut self, ctx: &egui::Context) {
+        let output = self.renderer.hdr_output();
+        if !output.is_hdr() {
+            return;
+        }
+
+        let mut sdr_preview = self.renderer.hdr_sdr_preview();
+        let luminance = self.renderer.display_luminance();
+        egui::Window::new("HDR")
+            .default_open(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{output:?} output"));
+                ui.label(format!(
+                    "paper white {:.0} nits, max {:.0} nits",
+                    luminance.paper_white_nits, luminance.max_nits
+                ));
+                ui.checkbox(&mut sdr_preview, "preview SDR range");
+            });
+        self.renderer.set_hdr_sdr_preview(sdr_preview);
+    }
+
+    /// the variable rate shading pipelines, and an overlay of one's rates
+    #[cfg(feature = "editor")]
+    fn draw_shading_rate_ui(&mut self, ctx: &egui::Context) {
+        if !self.renderer.variable_rate_shading() {
+            return;
+        }
+
+        let rates = self.renderer.shading_rates();
+        let mut overlay = self.shading_rate_overlay.filter(|&i| i < rates.len());
+        egui::Window::new("Shading rate")
+            .default_open(false)
+            .show(ctx, |ui| {
+                if rates.is_empty() {
+                    ui.label("no pipelines with a shading rate");
+                }
+                ui.radio_value(&mut overlay, None, "no overlay");
+                for (i, (name, rate)) in rates.iter().enumerate() {
+                    ui.radio_value(&mut overlay, Some(i), format!("{name}: {rate:?}"));
+                }
+            });
+        self.shading_rate_overlay = overlay;
+
+        if let Some(&(_, rate)) = overlay.and_then(|i| rates.get(i)) {
+            self.paint_shading_rate_overlay(ctx, rate);
+        }
+    }
+
+    /// each region of `rate` over the render area, tinted from green at
+    /// full rate to red at 4x4
+    #[cfg(feature = "editor")]
+    fn paint_shading_rate_overlay(&self, ctx: &egui::Context, rate: ShadingRate) {
+        let painter = ctx.debug_painter();
+        for (region, size) in rate.regions(self.renderer.render_area()) {
+            let min = self
+                .renderer
+                .render_to_window_position(glam::Vec2::new(region.x as f32, region.y as f32));
+            let max = self.renderer.render_to_window_position(glam::Vec2::new(
+                (region.x + region.width) as f32,
+                (region.y + region.height) as f32,
+            ));
+            let rect = egui::Rect::from_min_max(egui::pos2(min.x, min.y), egui::pos2(max.x, max.y));
+
+            let extent = size.extent();
+            // 0 at 1 pixel per invocation, 1 at 16
+            let coarseness = ((extent.width * extent.height) as f32).log2() / 4.0;
+            let color = egui::Color32::from_rgba_unmultiplied(
+                (255.0 * coarseness) as u8,
+                (255.0 * (1.0 - coarseness)) as u8,
+                0,
+                48,
+            );
+            painter.rect_filled(rect, 0.0, color);
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                size.name(),
+                egui::FontId::monospace(14.0),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+
+    /// the player's renderer.toml, saved for the next launch
+    #[cfg(feature = "editor")]
+    fn draw_settings_ui(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.settings.path.clone() else {
+            return;
+        };
+
+        let current_scale = self.renderer.render_scale();
+        let ui_state = &mut self.settings;
+        let settings = &mut ui_state.settings;
+        egui::Window::new("Settings")
+            .default_open(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{}, applied at launch", path.display()));
+
+                let msaa_text = |msaa: Option<u32>| match msaa {
+                    None => "default".to_string(),
+                    Some(1) => "off".to_string(),
+                    Some(samples) => format!("up to {samples}x"),
+                };
+                egui::ComboBox::from_label("MSAA")
+                    .selected_text(msaa_text(settings.msaa))
+                    .show_ui(ui, |ui| {
+                        for option in [None, Some(1), Some(2), Some(4), Some(8)] {
+                            ui.selectable_value(&mut settings.msaa, option, msaa_text(option));
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    let mut custom_scale = settings.render_scale.is_some();
+                    ui.checkbox(&mut custom_scale, "render scale");
+                    let mut scale = settings.render_scale.unwrap_or(current_scale);
+                    ui.add_enabled(custom_scale, egui::Slider::new(&mut scale, 0.25..=1.0));
+                    settings.render_scale = custom_scale.then_some(scale);
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("vsync");
+                    ui.radio_value(&mut settings.vsync, None, "default");
+                    ui.radio_value(&mut settings.vsync, Some(true), "on");
+                    ui.radio_value(&mut settings.vsync, Some(false), "off");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("GPU");
+                    let mut gpu = settings.gpu.clone().unwrap_or_default();
+                    ui.text_edit_singleline(&mut gpu)
+                        .on_hover_text("part of the device name; empty for the default");
+                    let gpu = gpu.trim();
+                    settings.gpu = (!gpu.is_empty()).then(|| gpu.to_string());
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("editor");
+                    ui.radio_value(&mut settings.editor, None, "default");
+                    ui.radio_value(&mut settings.editor, Some(true), "shown");
+                    ui.radio_value(&mut settings.editor, Some(false), "hidden");
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        ui_state.status = Some(match settings.save(&path) {
+                            Ok(()) => "saved".to_string(),
+                            Err(error) => format!("{error:#}"),
+                        });
+                    }
+                    if let Some(status) = &ui_state.status {
+                        ui.label(status);
+                    }
+                });
+            });
+    }
+
+    /// recent shader printf output, when enabled
+    #[cfg(feature = "editor")]
+    fn draw_printf_console(&self, ctx: &egui::Context) {
+        if !self.renderer.shader_printf_enabled() {
+            return;
+        }
+
+        egui::Window::new("Shader printf")
+            .default_open(false)
+            .show(ctx, |ui| {
+                if ui.button("Clear").clicked() {
+                    debug::clear_printf_lines();
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in debug::printf_lines() {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+    }
+
+    /// replays the chosen pipeline for the pixel under the cursor,
+    /// which stays put while the cursor is over the editor's windows
+    #[cfg(feature = "editor")]
+    fn draw_pixel_debugger(&mut self, ctx: &egui::Context) {
+        let target = self.renderer.pixel_debug_target();
+        let mut pipeline = target.map(|target| target.pipeline);
+        let mut pixel = target.map(|target| target.pixel).unwrap_or_default();
+        if !ctx.is_pointer_over_area()
+            && let Some(position) = ctx.pointer_latest_pos()
+        {
+            pixel = self
+                .renderer
+                .window_to_render_pixel(glam::Vec2::new(position.x, position.y));
+        }
+
+        let frame_stats = self.renderer.frame_stats();
+        let sample = self.renderer.pixel_debug_sample();
+        egui::Window::new("Pixel debugger")
+            .default_open(false)
+            .show(ctx, |ui| {
+                let selected_text = match pipeline {
+                    None => "off",
+                    Some(pipeline) => frame_stats
+                        .pipelines
+                        .iter()
+                        .find(|stats| stats.pipeline() == pipeline)
+                        .map_or("(not drawn)", |stats| stats.shader_name.as_str()),
+                };
+                egui::ComboBox::from_label("pipeline")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut pipeline, None, "off");
+                        for stats in &frame_stats.pipelines {
+                            ui.selectable_value(
+                                &mut pipeline,
+                                Some(stats.pipeline()),
+                                &stats.shader_name,
+                            );
+                        }
+                    });
+
+                if pipeline.is_none() {
+                    return;
+                }
+                ui.separator();
+                let Some(sample) = sample else {
+                    ui.label("waiting for a replay");
+                    return;
+                };
+
+                let [x, y] = sample.target.pixel;
+                ui.monospace(format!("pixel  {x}, {y}"));
+                if !sample.covered() {
+                    ui.label("not covered by this pipeline");
+                    return;
+                }
+
+                let [r, g, b, a] = sample.color.to_array();
+                ui.horizontal(|ui| {
+                    let (swatch, _) =
+                        ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                    let [sr, sg, sb, sa] = [r, g, b, a].map(|channel| channel.clamp(0.0, 1.0));
+                    let swatch_color = egui::Rgba::from_rgba_unmultiplied(sr, sg, sb, sa);
+                    ui.painter().rect_filled(swatch, 2.0, swatch_color);
+                    ui.monospace(format!("color  {r:.4} {g:.4} {b:.4} {a:.4}"));
+                });
+                let [x, y, z, w] = sample.debug.to_array();
+                ui.monospace(format!("debug  {x:.4} {y:.4} {z:.4} {w:.4}"));
+                ui.monospace(format!("id     {}", sample.object_id));
+                ui.monospace(format!("depth  {:.6}", sample.depth));
+            });
+
+        let target = pipeline.map(|pipeline| PixelDebugTarget { pipeline, pixel });
+        self.renderer.set_pixel_debug_target(target);
+    }
+
+    /// reads back a texture's mip level and layer (or the depth buffer)
+    /// to view its channels, and the values of the texel under the cursor
+    #[cfg(feature = "editor")]
+    fn draw_texture_inspector(&mut self, ctx: &egui::Context) {
+        let images = self.renderer.inspectable_images();
+        let find = |inspected: InspectedImage| images.iter().find(|image| image.image == inspected);
+        let mut target = self
+            .renderer
+            .texture_inspector_target()
+            .filter(|target| find(target.image).is_some());
+        let snapshot = self.renderer.texture_inspector_snapshot();
+        let ui_state = &mut self.texture_inspector;
+
+        egui::Window::new("Texture inspector")
+            .default_open(false)
+            .show(ctx, |ui| {
+                let selected = target.and_then(|target| find(target.image));
+                egui::ComboBox::from_label("image")
+                    .selected_text(selected.map_or("off", |image| image.name.as_str()))
+                    .show_ui(ui, |ui| {
+                        let mut image = target.map(|target| target.image);
+                        ui.selectable_value(&mut image, None, "off");
+                        for inspectable in &images {
+                            ui.selectable_value(
+                                &mut image,
+                                Some(inspectable.image),
+                                &inspectable.name,
+                            );
+                        }
+                        if image != target.map(|target| target.image) {
+                            target = image.map(|image| InspectorTarget {
+                                image,
+                                mip_level: 0,
+                                layer: 0,
+                            });
+                        }
+                    });
+
+                let Some(target) = &mut target else {
+                    return;
+                };
+                let Some(selected) = find(target.image) else {
+                    return;
+                };
+                let [width, height] = selected.extent;
+                ui.monospace(format!("{width}x{height} {:?}", selected.format));
+                if selected.mip_levels > 1 {
+                    ui.add(
+                        egui::Slider::new(&mut target.mip_level, 0..=selected.mip_levels - 1)
+                            .text("mip"),
+                    );
+                }
+                if selected.layers > 1 {
+                    ui.add(
+                        egui::Slider::new(&mut target.layer, 0..=selected.layers - 1).text("layer"),
+                    );
+                }
+
+                let view = &mut ui_state.view;
+                if target.image == InspectedImage::Depth {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut ui_state.linearize_depth, "linearize");
+                        let [near, far] = &mut ui_state.depth_planes;
+                        ui.add(egui::DragValue::new(near).speed(0.01).prefix("near "));
+                        ui.add(egui::DragValue::new(far).speed(1.0).prefix("far "));
+                    });
+                    view.linearize_depth =
+                        ui_state.linearize_depth.then_some(ui_state.depth_planes);
+                } else {
+                    ui.horizontal(|ui| {
+                        for (shown, name) in view.channels.iter_mut().zip(["R", "G", "B", "A"]) {
+                            ui.toggle_value(shown, name);
+                        }
+                    });
+                }
+                ui.add(
+                    egui::Slider::new(&mut view.zoom, 0.25..=32.0)
+                        .logarithmic(true)
+                        .text("zoom"),
+                );
+                ui.separator();
+
+                let Some(snapshot) = snapshot else {
+                    ui.label("waiting for a readback");
+                    return;
+                };
+                let texture = ui_state.upload(ctx, snapshot);
+
+                let view = ui_state.view;
+                let size = egui::vec2(snapshot.width as f32, snapshot.height as f32) * view.zoom;
+                egui::ScrollArea::both().max_height(480.0).show(ui, |ui| {
+                    let image = egui::Image::new(egui::load::SizedTexture::new(texture, size))
+                        .sense(egui::Sense::hover());
+                    let response = ui.add(image);
+                    if let Some(position) = response.hover_pos() {
+                        let texel = (position - response.rect.min) / view.zoom;
+                        let [x, y] = [texel.x as u32, texel.y as u32];
+                        if let Some([r, g, b, a]) = snapshot.texel(x, y) {
+                            response
+                                .on_hover_text(format!("{x}, {y}\n{r:.4} {g:.4} {b:.4} {a:.4}"));
+                        }
+                    }
+                });
+            });
+
+        self.renderer.set_texture_inspector_target(target);
+    }
+
+    /// shows a uniform or storage buffer's first elements,
+    /// read through the layout its shader was compiled with
+    #[cfg(feature = "editor")]
+    fn draw_buffer_inspector(&mut self, ctx: &egui::Context) {
+        let buffers = self.renderer.inspectable_buffers();
+        let ui_state = &mut self.buffer_inspector;
+
+        egui::Window::new("Buffer inspector")
+            .default_open(false)
+            .show(ctx, |ui| {
+                let label = |inspectable: &InspectableBuffer| {
+                    format!("{} ({})", inspectable.buffer, inspectable.source)
+                };
+                let find = |buffer: AuditedBuffer| {
+                    buffers
+                        .iter()
+                        .find(|inspectable| inspectable.buffer == buffer)
+                };
+
+                let selected = ui_state.selected.and_then(find);
+                egui::ComboBox::from_label("buffer")
+                    .selected_text(selected.map_or("off".to_string(), label))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut ui_state.selected, None, "off");
+                        for inspectable in &buffers {
+                            ui.selectable_value(
+                                &mut ui_state.selected,
+                                Some(inspectable.buffer),
+                                label(inspectable),
+                            );
+                        }
+                    });
+
+                let Some(selected) = ui_state.selected.and_then(find) else {
+                    return;
+                };
+                let layout = &selected.layout;
+                ui.monospace(format!(
+                    "{} stride {} from {}",
+                    layout.type_name, layout.stride, selected.start
+                ));
+                let is_array = matches!(selected.buffer, AuditedBuffer::Storage(_));
+                if is_array {
+                    ui.add(
+                        egui::Slider::new(&mut ui_state.element_count, 1..=256)
+                            .logarithmic(true)
+                            .text("elements"),
+                    );
+                }
+                ui.separator();
+
+                let Some(bytes) = self.renderer.inspected_buffer_bytes(selected.buffer) else {
+                    ui.label("not mapped");
+                    return;
+                };
+                let count = if is_array { ui_state.element_count } else { 1 };
+                let elements = layout.read(bytes, selected.start, count);
+
+                let fields_grid = |ui: &mut egui::Ui, id: usize, fields: &[InspectedField]| {
+                    egui::Grid::new(("buffer inspector fields", id))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for field in fields {
+                                ui.monospace(&field.path);
+                                ui.monospace(format!("+{}", field.offset));
+                                ui.monospace(field.value.to_string());
+                                ui.end_row();
+                            }
+                        });
+                };
+                egui::ScrollArea::vertical()
+                    .max_height(480.0)
+                    .show(ui, |ui| {
+                        if !is_array {
+                            fields_grid(ui, 0, &elements[0]);
+                            return;
+                        }
+                        for (index, fields) in elements.iter().enumerate() {
+                            egui::CollapsingHeader::new(format!("[{index}]"))
+                                .default_open(index == 0)
+                                .show(ui, |ui| fields_grid(ui, index, fields));
+                        }
+                    });
+            });
+    }
+
+    /// the keys currently held down
+    pub fn keyboard_state(&self) -> &KeyboardState {
+        &self.keyboard
+    }
+
+    /// SDL reports touch positions normalized to the window;
+    /// scale them to window coordinates to match mouse events
+    fn touch_to_window(&self, x: f32, y: f32) -> (f32, f32) {
+        let (width, height) = self.renderer.window_size();
+        (x * width as f32, y * height as f32)
+    }
+
     // https://wiki.libsdl.org/SDL3/SDL_EventType
     pub fn handle_events(&mut self, event_pump: &mut EventPump) -> anyhow::Result<()> {
+        #[cfg(feature = "editor")]
         let egui_wants_pointer = self
             .renderer
             .egui()
             .map(|e| e.ctx.wants_pointer_input())
             .unwrap_or(false);
+        #[cfg(not(feature = "editor"))]
+        let egui_wants_pointer = false;
 
         for event in event_pump.poll_iter() {
-            if let Some(egui) = self.renderer.egui() {
-                egui.handle_sdl_event(&event);
-            }
+            // checked per event, since a gamepad button toggles it
+            #[cfg(feature = "editor")]
+            let egui_wants_gamepad = match self.renderer.egui() {
+                Some(egui) => {
+                    egui.handle_sdl_event(&event);
+                    egui.wants_gamepad_input()
+                }
+                None => false,
+            };
+            #[cfg(not(feature = "editor"))]
+            let egui_wants_gamepad = false;
 
             match event {
                 Event::Quit { .. }
@@ -88,18 +888,14 @@ impl App {
                 }
 
                 Event::Window { win_event, .. } => match win_event {
-                    WindowEvent::Resized(_new_width, _new_height) => {
-                        // we take the new dimensions off the renderer's window ref
-                        self.renderer.on_resize()?;
-                    }
-                    WindowEvent::Minimized => {
-                        self.minimized = true;
-                    }
-                    WindowEvent::Maximized => {
-                        self.minimized = false;
-                    }
-                    WindowEvent::Restored => {
-                        self.minimized = false;
+                    WindowEvent::Resized(_, _)
+                    | WindowEvent::Minimized
+                    | WindowEvent::Maximized
+                    | WindowEvent::Restored => {
+                        if self.window.on_event(&win_event) {
+                            // we take the new dimensions off the renderer's window ref
+                            self.renderer.on_resize()?;
+                        }
                     }
 
                     WindowEvent::Exposed => {
@@ -111,10 +907,12 @@ impl App {
                     }
                     WindowEvent::FocusLost => {
                         // pause in-game?
+                        self.keyboard.clear();
                     }
                     WindowEvent::DisplayChanged(_) => {
-                        // vulkan: update whatever is necessary for new surface
-                        // ie, display scale
+                        let refresh_rate = self.renderer.display_refresh_rate();
+                        self.display_frame_delay = refresh_frame_delay(refresh_rate);
+                        log::debug!("display frame delay: {:?}", self.display_frame_delay);
                     }
                     WindowEvent::Shown => {}
                     WindowEvent::Hidden => {
@@ -134,30 +932,102 @@ impl App {
                     WindowEvent::None => {}
                 },
 
-                Event::KeyDown { scancode, .. } => {
+                Event::KeyDown {
+                    keycode: Some(CAPTURE_KEY),
+                    repeat: false,
+                    ..
+                } => {
+                    self.renderer.trigger_capture();
+                }
+
+                #[cfg(debug_assertions)]
+                Event::KeyDown {
+                    keycode: Some(PAUSE_KEY),
+                    repeat: false,
+                    ..
+                } => {
+                    self.time.toggle_pause();
+                }
+
+                // held down, steps once per key repeat
+                #[cfg(debug_assertions)]
+                Event::KeyDown {
+                    keycode: Some(STEP_KEY),
+                    ..
+                } => {
+                    self.time.step();
+                }
+
+                #[cfg(debug_assertions)]
+                Event::KeyDown {
+                    keycode: Some(SLOW_MOTION_KEY),
+                    repeat: false,
+                    ..
+                } => {
+                    self.time.toggle_scale(SLOW_MOTION_SCALE);
+                }
+
+                #[cfg(debug_assertions)]
+                Event::KeyDown {
+                    keycode: Some(FAST_FORWARD_KEY),
+                    repeat: false,
+                    ..
+                } => {
+                    self.time.toggle_scale(FAST_FORWARD_SCALE);
+                }
+
+                Event::KeyDown {
+                    timestamp,
+                    scancode,
+                    ..
+                } => {
                     let Some(key) = scancode.and_then(Key::from_sdl_scancode) else {
                         continue;
                     };
+                    self.keyboard.press(key);
                     let input = Input::KeyDown(key);
-                    self.game.input(input);
+                    self.game.input(input_event(input, timestamp));
                 }
 
-                Event::KeyUp { scancode, .. } => {
+                Event::KeyUp {
+                    timestamp,
+                    scancode,
+                    ..
+                } => {
                     let Some(key) = scancode.and_then(Key::from_sdl_scancode) else {
                         continue;
                     };
+                    self.keyboard.release(key);
                     let input = Input::KeyUp(key);
-                    self.game.input(input);
+                    self.game.input(input_event(input, timestamp));
                 }
 
-                Event::MouseMotion { x, y, .. } if !egui_wants_pointer => {
+                Event::MouseMotion {
+                    timestamp,
+                    which,
+                    x,
+                    y,
+                    xrel,
+                    yrel,
+                    ..
+                } if !egui_wants_pointer && !is_emulated_mouse(which) => {
                     let input = Input::MouseMotion { x, y };
-                    self.game.input(input);
+                    self.game.input(input_event(input, timestamp));
+
+                    if self.renderer.relative_mouse_mode() {
+                        let input = Input::RelativeMouseMotion { dx: xrel, dy: yrel };
+                        self.game.input(input_event(input, timestamp));
+                    }
                 }
 
                 Event::MouseButtonDown {
-                    mouse_btn, x, y, ..
-                } if !egui_wants_pointer => {
+                    timestamp,
+                    which,
+                    mouse_btn,
+                    x,
+                    y,
+                    ..
+                } if !egui_wants_pointer && !is_emulated_mouse(which) => {
                     let button = match mouse_btn {
                         sdl3::mouse::MouseButton::Left => MouseButton::Left,
                         sdl3::mouse::MouseButton::Middle => MouseButton::Middle,
@@ -167,12 +1037,17 @@ impl App {
 
                     let input = Input::MouseDown { button, x, y };
 
-                    self.game.input(input);
+                    self.game.input(input_event(input, timestamp));
                 }
 
                 Event::MouseButtonUp {
-                    mouse_btn, x, y, ..
-                } if !egui_wants_pointer => {
+                    timestamp,
+                    which,
+                    mouse_btn,
+                    x,
+                    y,
+                    ..
+                } if !egui_wants_pointer && !is_emulated_mouse(which) => {
                     let button = match mouse_btn {
                         sdl3::mouse::MouseButton::Left => MouseButton::Left,
                         sdl3::mouse::MouseButton::Middle => MouseButton::Middle,
@@ -182,7 +1057,192 @@ impl App {
 
                     let input = Input::MouseUp { button, x, y };
 
-                    self.game.input(input);
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::FingerDown {
+                    timestamp,
+                    finger_id,
+                    x,
+                    y,
+                    pressure,
+                    ..
+                } if !egui_wants_pointer => {
+                    let (x, y) = self.touch_to_window(x, y);
+                    let input = Input::TouchDown {
+                        finger_id,
+                        x,
+                        y,
+                        pressure,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::FingerUp {
+                    timestamp,
+                    finger_id,
+                    x,
+                    y,
+                    pressure,
+                    ..
+                } if !egui_wants_pointer => {
+                    let (x, y) = self.touch_to_window(x, y);
+                    let input = Input::TouchUp {
+                        finger_id,
+                        x,
+                        y,
+                        pressure,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::FingerMotion {
+                    timestamp,
+                    finger_id,
+                    x,
+                    y,
+                    pressure,
+                    ..
+                } if !egui_wants_pointer => {
+                    let (x, y) = self.touch_to_window(x, y);
+                    let input = Input::TouchMotion {
+                        finger_id,
+                        x,
+                        y,
+                        pressure,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::DropFile {
+                    timestamp,
+                    filename,
+                    ..
+                } => {
+                    let input = Input::FileDropped(filename.into());
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::PenAxis {
+                    axis: sdl3::pen::PenAxis::Pressure,
+                    value,
+                    ..
+                } => {
+                    self.pen_pressure = value;
+                }
+
+                Event::PenDown {
+                    timestamp,
+                    which,
+                    x,
+                    y,
+                    eraser,
+                    ..
+                } if !egui_wants_pointer => {
+                    let input = Input::PenDown {
+                        pen_id: which,
+                        x,
+                        y,
+                        pressure: self.pen_pressure,
+                        eraser,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::PenUp {
+                    timestamp,
+                    which,
+                    x,
+                    y,
+                    eraser,
+                    ..
+                } if !egui_wants_pointer => {
+                    let input = Input::PenUp {
+                        pen_id: which,
+                        x,
+                        y,
+                        eraser,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::PenMotion {
+                    timestamp,
+                    which,
+                    x,
+                    y,
+                    ..
+                } if !egui_wants_pointer => {
+                    let input = Input::PenMotion {
+                        pen_id: which,
+                        x,
+                        y,
+                        pressure: self.pen_pressure,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::ControllerDeviceAdded {
+                    timestamp, which, ..
+                } => {
+                    self.gamepads.connect(which);
+                    let input = Input::GamepadConnected { gamepad_id: which };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::ControllerDeviceRemoved {
+                    timestamp, which, ..
+                } => {
+                    self.gamepads.disconnect(which);
+                    let input = Input::GamepadDisconnected { gamepad_id: which };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::ControllerButtonDown {
+                    timestamp,
+                    which,
+                    button,
+                    ..
+                } if !egui_wants_gamepad => {
+                    let Some(button) = GamepadButton::from_sdl_button(button) else {
+                        continue;
+                    };
+                    let input = Input::GamepadButtonDown {
+                        gamepad_id: which,
+                        button,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::ControllerButtonUp {
+                    timestamp,
+                    which,
+                    button,
+                    ..
+                } if !egui_wants_gamepad => {
+                    let Some(button) = GamepadButton::from_sdl_button(button) else {
+                        continue;
+                    };
+                    let input = Input::GamepadButtonUp {
+                        gamepad_id: which,
+                        button,
+                    };
+                    self.game.input(input_event(input, timestamp));
+                }
+
+                Event::ControllerAxisMotion {
+                    timestamp,
+                    which,
+                    axis,
+                    value,
+                    ..
+                } if !egui_wants_gamepad => {
+                    let input = Input::GamepadAxisMotion {
+                        gamepad_id: which,
+                        axis: GamepadAxis::from_sdl_axis(axis),
+                        value: GamepadAxis::normalize_sdl_value(value),
+                    };
+                    self.game.input(input_event(input, timestamp));
                 }
 
                 _ => {}
@@ -192,3 +1252,253 @@ impl App {
         Ok(())
     }
 }
+
+/// Whether frames are drawn, as the window is minimized, restored, and resized
+#[derive(Debug, Default)]
+struct WindowState {
+    /// no frames are updated or drawn while minimized
+    minimized: bool,
+    /// a resize that arrived while minimized, when the surface may be
+    /// zero-sized; the swapchain is recreated once the window is restored
+    resize_pending: bool,
+}
+
+impl WindowState {
+    /// Returns whether the swapchain should be recreated now
+    fn on_event(&mut self, win_event: &WindowEvent) -> bool {
+        match win_event {
+            WindowEvent::Resized(_, _) if self.minimized => {
+                self.resize_pending = true;
+                false
+            }
+            WindowEvent::Resized(_, _) => true,
+            WindowEvent::Minimized => {
+                self.minimized = true;
+                false
+            }
+            WindowEvent::Maximized | WindowEvent::Restored => {
+                self.minimized = false;
+                std::mem::take(&mut self.resize_pending)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A refresh at `refresh_rate` Hz, or the fallback when it's unknown
+fn refresh_frame_delay(refresh_rate: Option<f32>) -> Duration {
+    refresh_rate
+        .filter(|&rate| rate > 0.0)
+        .map_or(FALLBACK_FRAME_DELAY, |rate| {
+            Duration::from_secs_f32(1.0 / rate)
+        })
+}
+
+/// Waits for the display where present wait is supported, then sleeps out
+/// whatever is left of `frame_delay` since the last frame ended, so a target
+/// frame rate below the display's refresh still holds
+fn pace_frame(
+    end_of_last_frame: Instant,
+    frame_delay: Duration,
+    wait_for_display: impl FnOnce() -> anyhow::Result<bool>,
+    sleep: impl FnOnce(Duration),
+) -> anyhow::Result<()> {
+    wait_for_display()?;
+    let remaining = frame_delay.saturating_sub(end_of_last_frame.elapsed());
+    if !remaining.is_zero() {
+        sleep(remaining);
+    }
+    Ok(())
+}
+
+/// How long SDL_WaitEventTimeout waits for an event before `deadline`,
+/// rounded up to a whole millisecond; -1 waits for an event however long it takes
+fn wait_timeout_ms(now: Instant, deadline: Option<Instant>) -> i32 {
+    match deadline {
+        None => -1,
+        Some(deadline) => {
+            let wait = deadline.saturating_duration_since(now);
+            wait.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32
+        }
+    }
+}
+
+/// The player's settings as the Settings window edits them
+#[cfg(feature = "editor")]
+#[derive(Default)]
+struct SettingsUi {
+    settings: RendererSettings,
+    /// None when the game doesn't read settings
+    path: Option<PathBuf>,
+    /// the last save's outcome
+    status: Option<String>,
+}
+
+/// The texture inspector's display settings, and its snapshot uploaded to egui
+#[cfg(feature = "editor")]
+struct TextureInspectorUi {
+    view: InspectorView,
+    linearize_depth: bool,
+    depth_planes: [f32; 2],
+    texture: Option<egui::TextureHandle>,
+    /// the snapshot generation and view the texture was uploaded with
+    uploaded: Option<(u64, InspectorView)>,
+}
+
+#[cfg(feature = "editor")]
+impl Default for TextureInspectorUi {
+    fn default() -> Self {
+        Self {
+            view: InspectorView::default(),
+            linearize_depth: false,
+            depth_planes: [0.1, 100.0],
+            texture: None,
+            uploaded: None,
+        }
+    }
+}
+
+#[cfg(feature = "editor")]
+impl TextureInspectorUi {
+    /// Uploads the snapshot as the view displays it, if it's changed
+    fn upload(&mut self, ctx: &egui::Context, snapshot: &InspectorSnapshot) -> egui::TextureId {
+        // zoom is applied by egui; only the colors need a new upload
+        let colors = InspectorView {
+            zoom: 1.0,
+            ..self.view
+        };
+        let current = Some((snapshot.generation, colors));
+
+        let size = [snapshot.width as usize, snapshot.height as usize];
+        let options = egui::TextureOptions::NEAREST;
+        match &mut self.texture {
+            Some(texture) if self.uploaded == current => texture.id(),
+            Some(texture) => {
+                let image =
+                    egui::ColorImage::from_rgba_unmultiplied(size, &snapshot.to_rgba(&colors));
+                texture.set(image, options);
+                self.uploaded = current;
+                texture.id()
+            }
+            None => {
+                let image =
+                    egui::ColorImage::from_rgba_unmultiplied(size, &snapshot.to_rgba(&colors));
+                let texture = ctx.load_texture("texture inspector", image, options);
+                let id = texture.id();
+                self.texture = Some(texture);
+                self.uploaded = current;
+                id
+            }
+        }
+    }
+}
+
+/// The buffer inspector's selection
+#[cfg(feature = "editor")]
+struct BufferInspectorUi {
+    selected: Option<AuditedBuffer>,
+    /// how many elements of a storage buffer to show
+    element_count: usize,
+}
+
+#[cfg(feature = "editor")]
+impl Default for BufferInspectorUi {
+    fn default() -> Self {
+        Self {
+            selected: None,
+            element_count: 8,
+        }
+    }
+}
+
+/// SDL3 event timestamps are in nanoseconds
+fn input_event(input: Input, timestamp: u64) -> InputEvent {
+    InputEvent {
+        input,
+        timestamp: Duration::from_nanos(timestamp),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resizes_recreate_the_swapchain() {
+        let mut window = WindowState::default();
+        assert!(window.on_event(&WindowEvent::Resized(1024, 768)));
+        assert!(!window.minimized);
+    }
+
+    #[test]
+    fn minimized_windows_skip_frames_and_defer_resizes() {
+        let mut window = WindowState::default();
+        assert!(!window.on_event(&WindowEvent::Minimized));
+        assert!(window.minimized);
+
+        // some platforms resize the window to nothing when it's minimized
+        assert!(!window.on_event(&WindowEvent::Resized(0, 0)));
+        assert!(window.minimized);
+
+        assert!(window.on_event(&WindowEvent::Restored));
+        assert!(!window.minimized);
+
+        // the deferred resize is only applied once
+        assert!(!window.on_event(&WindowEvent::Maximized));
+    }
+
+    #[test]
+    fn redraw_waits_until_the_repaint_deadline() {
+        let now = Instant::now();
+        assert_eq!(wait_timeout_ms(now, None), -1);
+        assert_eq!(wait_timeout_ms(now, Some(now)), 0);
+        assert_eq!(wait_timeout_ms(now + Duration::from_secs(1), Some(now)), 0);
+        assert_eq!(
+            wait_timeout_ms(now, Some(now + Duration::from_micros(2500))),
+            3
+        );
+    }
+
+    #[test]
+    fn frame_delay_follows_the_refresh_rate() {
+        let delay = refresh_frame_delay(Some(144.0));
+        assert!((delay.as_secs_f64() - 1.0 / 144.0).abs() < 1e-6);
+        assert_eq!(refresh_frame_delay(None), FALLBACK_FRAME_DELAY);
+        assert_eq!(refresh_frame_delay(Some(0.0)), FALLBACK_FRAME_DELAY);
+    }
+
+    #[test]
+    fn frames_paced_by_the_display_still_sleep_out_the_frame_delay() {
+        let frame_delay = Duration::from_millis(100);
+        let mut slept = None;
+        pace_frame(
+            Instant::now(),
+            frame_delay,
+            || Ok(true),
+            |remaining| slept = Some(remaining),
+        )
+        .unwrap();
+        let slept = slept.expect("a 10 fps frame outlasts the display wait");
+        assert!(slept > Duration::from_millis(50) && slept <= frame_delay);
+
+        // a frame that already took its whole delay doesn't sleep
+        let mut slept = None;
+        let long_ago = Instant::now() - frame_delay;
+        pace_frame(
+            long_ago,
+            frame_delay,
+            || Ok(true),
+            |remaining| slept = Some(remaining),
+        )
+        .unwrap();
+        assert_eq!(slept, None);
+    }
+
+    #[test]
+    fn restoring_without_a_resize_keeps_the_swapchain() {
+        let mut window = WindowState::default();
+        window.on_event(&WindowEvent::Minimized);
+        assert!(!window.on_event(&WindowEvent::Restored));
+        assert!(!window.minimized);
+    }
+}
@@ -1,6 +1,26 @@
-use vulkan_slang_renderer::build_tasks::{self, Config};
+//! Compiles every shader in shaders/source to SPIR-V and reflection json, and
+//! with GENERATE_RUST_SOURCE set, writes the generated rust module too,
+//! including a module per sprite sheet in textures.
+//!
+//! With `--watch`, it keeps running and recompiles whenever a slang file in
+//! shaders/source or shaders/lib changes. Rust source is only regenerated (and
+//! formatted) when a shader's reflection changed, so editing a function body
+//! doesn't touch src/generated. Pairs with the runtime's shader hot reload.
+
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use notify::{RecursiveMode, Watcher};
+
+use vulkan_slang_renderer::build_tasks::{self, Config, ShaderTiming, SpriteAtlasConfig};
 use vulkan_slang_renderer::util::manifest_path;
 
+/// how long to wait for an editor's burst of save events to settle
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
 pub fn main() {
     let arg = std::env::var("GENERATE_RUST_SOURCE").ok();
 
@@ -11,12 +31,250 @@ pub fn main() {
         _ => true,
     };
 
-    let config = Config {
+    let watch = std::env::args().skip(1).any(|arg| arg == "--watch");
+    if watch {
+        watch_shaders(generate_rust_source).unwrap();
+    } else {
+        let start = Instant::now();
+        let timings = build_tasks::write_precompiled_shaders(config(generate_rust_source)).unwrap();
+        if generate_rust_source {
+            build_tasks::write_sprite_atlases(&sprite_atlas_config()).unwrap();
+        }
+        print_timings(&timings, start.elapsed());
+    }
+}
+
+/// per-shader compile times, slowest first; shaders compile in parallel,
+/// so these sum to more than the total
+fn print_timings(timings: &[ShaderTiming], total: Duration) {
+    let mut timings = timings.to_vec();
+    timings.sort_by(|a, b| b.duration.cmp(&a.duration));
+
+    for timing in &timings {
+        println!("{:>10.2?}  {}", timing.duration, timing.source_file_name);
+    }
+    println!("compiled {} shaders in {total:.2?}", timings.len());
+}
+
+fn config(generate_rust_source: bool) -> Config {
+    Config {
         generate_rust_source,
         rust_source_dir: manifest_path(["src"]),
         shaders_source_dir: manifest_path(["shaders", "source"]),
         compiled_shaders_dir: manifest_path(["shaders", "compiled"]),
+    }
+}
+
+fn sprite_atlas_config() -> SpriteAtlasConfig {
+    SpriteAtlasConfig {
+        rust_source_dir: manifest_path(["src"]),
+        textures_dir: manifest_path(["textures"]),
+    }
+}
+
+fn watch_shaders(generate_rust_source: bool) -> anyhow::Result<()> {
+    let (sender, receiver) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(
+        &manifest_path(["shaders", "source"]),
+        RecursiveMode::Recursive,
+    )?;
+    watcher.watch(&manifest_path(["shaders", "lib"]), RecursiveMode::Recursive)?;
+
+    let style = Style::detect();
+    rebuild(generate_rust_source, style);
+    println!("watching for shader changes...");
+
+    loop {
+        let first = receiver.recv()?;
+        std::thread::sleep(DEBOUNCE);
+        let events: Vec<_> = std::iter::once(first).chain(receiver.try_iter()).collect();
+
+        let mut slang_changed = false;
+        for event in events {
+            match event {
+                Ok(event) => slang_changed |= is_slang_edit(&event),
+                Err(error) => eprintln!("{}: {error}", style.warning("watch error")),
+            }
+        }
+
+        if slang_changed {
+            rebuild(generate_rust_source, style);
+        }
+    }
+}
+
+fn is_slang_edit(event: &notify::Event) -> bool {
+    let is_edit = matches!(
+        event.kind,
+        notify::EventKind::Create(_) | notify::EventKind::Modify(_) | notify::EventKind::Remove(_)
+    );
+    let is_slang = event.paths.iter().any(|path| {
+        path.extension()
+            .is_some_and(|extension| extension == "slang")
+    });
+
+    is_edit && is_slang
+}
+
+/// Recompiles all shaders, then regenerates rust source if any reflection json changed.
+/// Errors are printed rather than returned, so the watch keeps running.
+fn rebuild(generate_rust_source: bool, style: Style) {
+    let start = Instant::now();
+    let compiled_shaders_dir = manifest_path(["shaders", "compiled"]);
+    let reflection_before = read_reflection_json(&compiled_shaders_dir);
+
+    let timings = match build_tasks::write_precompiled_shaders(config(false)) {
+        Ok(timings) => timings,
+        Err(error) => {
+            print_compile_error(&error, style);
+            return;
+        }
     };
+    let slowest = timings.iter().max_by_key(|timing| timing.duration);
+
+    let reflection_changed = read_reflection_json(&compiled_shaders_dir) != reflection_before;
+    if generate_rust_source && reflection_changed {
+        if let Err(error) = build_tasks::write_precompiled_shaders(config(true)) {
+            print_compile_error(&error, style);
+            return;
+        }
+
+        match std::process::Command::new("cargo").arg("fmt").status() {
+            Ok(status) if status.success() => {}
+            Ok(status) => eprintln!(
+                "{}: cargo fmt exited with {status}",
+                style.warning("warning")
+            ),
+            Err(error) => eprintln!("{}: cargo fmt: {error}", style.warning("warning")),
+        }
+
+        println!(
+            "{} {} shaders and regenerated rust source in {:.2?}",
+            style.success("compiled"),
+            timings.len(),
+            start.elapsed()
+        );
+    } else {
+        println!(
+            "{} {} shaders in {:.2?}",
+            style.success("compiled"),
+            timings.len(),
+            start.elapsed()
+        );
+    }
+
+    if let Some(slowest) = slowest {
+        println!(
+            "  slowest: {} ({:.2?})",
+            slowest.source_file_name, slowest.duration
+        );
+    }
+}
+
+/// the contents of every reflection json in the compiled shaders dir, by file name
+fn read_reflection_json(compiled_shaders_dir: &Path) -> BTreeMap<PathBuf, String> {
+    let Ok(entries) = std::fs::read_dir(compiled_shaders_dir) else {
+        return BTreeMap::new();
+    };
+
+    entries
+        .filter_map(|entry_res| entry_res.ok())
+        .map(|dir_entry| dir_entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .filter_map(|path| std::fs::read_to_string(&path).ok().map(|json| (path, json)))
+        .collect()
+}
+
+/// Prints the error and its causes; slang's diagnostics arrive as one
+/// multi-line message, so each line is colored by its severity.
+fn print_compile_error(error: &anyhow::Error, style: Style) {
+    eprintln!("{}", style.error("shader compilation failed"));
+    for cause in error.chain() {
+        for line in cause.to_string().lines() {
+            if line.contains("error") {
+                eprintln!("  {}", style.error(line));
+            } else if line.contains("warning") {
+                eprintln!("  {}", style.warning(line));
+            } else {
+                eprintln!("  {line}");
+            }
+        }
+    }
+}
+
+/// ANSI colors for terminal output, disabled when stderr isn't a terminal or NO_COLOR is set
+#[derive(Clone, Copy)]
+struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    fn detect() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self {
+            enabled: std::io::stderr().is_terminal() && !no_color,
+        }
+    }
+
+    fn paint(self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn error(self, text: &str) -> String {
+        self.paint("1;31", text)
+    }
+
+    fn warning(self, text: &str) -> String {
+        self.paint("33", text)
+    }
+
+    fn success(self, text: &str) -> String {
+        self.paint("32", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: notify::EventKind, path: &str) -> notify::Event {
+        notify::Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn only_slang_edits_trigger_a_rebuild() {
+        let modify = notify::EventKind::Modify(notify::event::ModifyKind::Any);
+        let access = notify::EventKind::Access(notify::event::AccessKind::Any);
+
+        assert!(is_slang_edit(&event(
+            modify,
+            "shaders/source/sprite.shader.slang"
+        )));
+        assert!(is_slang_edit(&event(modify, "shaders/lib/noise.slang")));
+        assert!(!is_slang_edit(&event(
+            modify,
+            "shaders/source/.sprite.shader.slang.swp"
+        )));
+        assert!(!is_slang_edit(&event(
+            access,
+            "shaders/source/sprite.shader.slang"
+        )));
+    }
+
+    #[test]
+    fn plain_style_has_no_escapes() {
+        let style = Style { enabled: false };
+        assert_eq!(style.error("oops"), "oops");
 
-    build_tasks::write_precompiled_shaders(config).unwrap();
+        let style = Style { enabled: true };
+        assert_eq!(style.error("oops"), "\x1b[1;31moops\x1b[0m");
+    }
 }
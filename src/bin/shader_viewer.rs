@@ -0,0 +1,431 @@
+//! A playground for any shader in the atlas that draws without vertex buffers:
+//!
+//! ```text
+//! cargo run --bin shader_viewer -- sdf_2d
+//! cargo run --bin shader_viewer -- serenity_crt 6
+//! cargo run --bin shader_viewer -- --windowed sdf_2d
+//! ```
+//!
+//! The shader is loaded from its compiled SPIR-V and reflection json, so it
+//! needs no generated code or example of its own. In debug builds, where the
+//! editor runs, every uniform field gets a widget; a float `time` and a float2
+//! `resolution` are filled in each frame instead. The optional second argument
+//! is the vertex count to draw (default 3, a fullscreen triangle). Shaders that
+//! bind textures or storage images can't be filled automatically, and are
+//! rejected.
+
+use std::ffi::CString;
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::Context;
+use ash::vk;
+
+use vulkan_slang_renderer::game::*;
+use vulkan_slang_renderer::renderer::vertex_description::NoVertex;
+use vulkan_slang_renderer::renderer::{
+    DrawError, DrawVertexCount, FrameRenderer, LayoutDescription, PipelineConfigBuilder,
+    PipelineHandle, RawUniformBufferHandle, Renderer, VertexConfig,
+};
+use vulkan_slang_renderer::shaders::atlas::{
+    PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry,
+};
+use vulkan_slang_renderer::shaders::json::{
+    EntryPointParameter, ReflectedPipelineLayout, ReflectionJson, UniformField, UniformFieldKind,
+    global_descriptor_sets,
+};
+use vulkan_slang_renderer::util::manifest_path;
+
+fn main() -> anyhow::Result<()> {
+    ShaderViewer::run()
+}
+
+const DEFAULT_VERTEX_COUNT: u32 = 3;
+
+struct ViewerArgs {
+    shader_name: String,
+    vertex_count: u32,
+}
+
+impl ViewerArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let usage = "usage: shader_viewer <shader_name> [vertex_count]";
+        let shader_name = args.next().context(usage)?;
+        let vertex_count = match args.next() {
+            Some(count) => count
+                .parse()
+                .with_context(|| format!("invalid vertex count '{count}'; {usage}"))?,
+            None => DEFAULT_VERTEX_COUNT,
+        };
+
+        Ok(Self {
+            shader_name,
+            vertex_count,
+        })
+    }
+}
+
+struct ShaderViewer {
+    shader_name: String,
+    vertex_count: u32,
+    start_time: Instant,
+    pipeline: PipelineHandle<DrawVertexCount>,
+    uniform_blocks: Vec<UniformBlock>,
+}
+
+/// One parameter block's uniform buffer, and the cpu copy edited in the panel
+struct UniformBlock {
+    parameter_name: String,
+    buffer: RawUniformBufferHandle,
+    fields: Vec<UniformField>,
+    bytes: Vec<u8>,
+}
+
+impl Game for ShaderViewer {
+    type EditState = ();
+
+    fn window_title() -> &'static str {
+        "Shader Viewer"
+    }
+
+    fn setup(renderer: &mut Renderer) -> anyhow::Result<Self> {
+        // the standard launch flags were already taken by Game::run
+        let args = ViewerArgs::parse(LaunchOptions::from_env()?.extra_args.into_iter())?;
+        let shader = CompiledShader::load(&args.shader_name)?;
+
+        let mut uniform_blocks = vec![];
+        let mut uniform_buffer_handles = vec![];
+        let descriptor_sets = global_descriptor_sets(&shader.reflection_json.global_parameters);
+        for (set, descriptions) in shader.layout_bindings().iter().enumerate() {
+            let descriptor_set = descriptor_sets
+                .get(set)
+                .with_context(|| format!("no global parameters for descriptor set {set}"))?;
+
+            for description in descriptions {
+                let LayoutDescription::Uniform(uniform) = description else {
+                    anyhow::bail!(
+                        "{}: `{}` binds textures or storage images, \
+                         which the viewer can't fill",
+                        args.shader_name,
+                        descriptor_set.parameter_path
+                    );
+                };
+
+                let buffer = renderer.create_raw_uniform_buffer(uniform.size)?;
+                let fields = descriptor_set.uniform_fields();
+                let mut bytes = vec![0; uniform.size as usize];
+                for field in &fields {
+                    write_default(&mut bytes, field);
+                }
+
+                uniform_buffer_handles.push(buffer.clone());
+                uniform_blocks.push(UniformBlock {
+                    parameter_name: descriptor_set.parameter_path.clone(),
+                    buffer,
+                    fields,
+                    bytes,
+                });
+            }
+        }
+
+        let pipeline_config = PipelineConfigBuilder::<NoVertex> {
+            shader: Box::new(shader),
+            vertex_config: VertexConfig::VertexCount,
+            texture_handles: vec![],
+            uniform_buffer_handles,
+            storage_texture_handles: vec![],
+            disable_depth_test: false,
+        }
+        .build::<DrawVertexCount>();
+        let pipeline = renderer.create_pipeline(pipeline_config)?;
+
+        Ok(Self {
+            shader_name: args.shader_name,
+            vertex_count: args.vertex_count,
+            start_time: Instant::now(),
+            pipeline,
+            uniform_blocks,
+        })
+    }
+
+    fn draw(&mut self, renderer: FrameRenderer) -> Result<(), DrawError> {
+        let time = self.start_time.elapsed().as_secs_f32();
+        let resolution = renderer.window_resolution();
+
+        for block in &mut self.uniform_blocks {
+            for field in &block.fields {
+                match automatic_value(field) {
+                    Some(Automatic::Time) => write_floats(&mut block.bytes, field.offset, &[time]),
+                    Some(Automatic::Resolution) => {
+                        write_floats(&mut block.bytes, field.offset, &resolution.to_array())
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let blocks = &self.uniform_blocks;
+        renderer.draw_vertex_count(&self.pipeline, self.vertex_count, |gpu| {
+            for block in blocks {
+                gpu.write_uniform_bytes(&block.buffer, &block.bytes);
+            }
+        })
+    }
+
+    fn editor_windows(&mut self, ctx: &egui::Context) {
+        egui::Window::new(self.shader_name.as_str()).show(ctx, |ui| {
+            if self.uniform_blocks.is_empty() {
+                ui.label("no uniform parameters");
+            }
+
+            for block in &mut self.uniform_blocks {
+                ui.collapsing(block.parameter_name.as_str(), |ui| {
+                    egui::Grid::new(&block.parameter_name)
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for field in &block.fields {
+                                ui.label(field.path.as_str());
+                                field_ui(ui, &mut block.bytes, field);
+                                ui.end_row();
+                            }
+                        });
+                });
+            }
+        });
+    }
+}
+
+/// Fields the viewer fills in every frame, recognized by name and type
+enum Automatic {
+    Time,
+    Resolution,
+}
+
+fn automatic_value(field: &UniformField) -> Option<Automatic> {
+    let name = field.path.rsplit('.').next().unwrap_or(&field.path);
+    match (name, field.kind) {
+        ("time", UniformFieldKind::Float) => Some(Automatic::Time),
+        ("resolution", UniformFieldKind::FloatVector(2)) => Some(Automatic::Resolution),
+        _ => None,
+    }
+}
+
+/// Matrices start as identity, so a camera or model transform shows something;
+/// colors start white, and everything else zero
+fn write_default(bytes: &mut [u8], field: &UniformField) {
+    match field.kind {
+        UniformFieldKind::Float4x4 => {
+            write_floats(bytes, field.offset, &glam::Mat4::IDENTITY.to_cols_array())
+        }
+        UniformFieldKind::FloatVector(count) if is_color(field, count) => {
+            write_floats(bytes, field.offset, &[1.0; 4][..count])
+        }
+        _ => {}
+    }
+}
+
+fn is_color(field: &UniformField, component_count: usize) -> bool {
+    (component_count == 3 || component_count == 4) && field.path.to_lowercase().contains("color")
+}
+
+fn field_ui(ui: &mut egui::Ui, bytes: &mut [u8], field: &UniformField) {
+    if automatic_value(field).is_some() {
+        ui.weak("(automatic)");
+        return;
+    }
+
+    let offset = field.offset;
+    match field.kind {
+        UniformFieldKind::Float => {
+            let mut value = read_floats::<1>(bytes, offset);
+            if ui
+                .add(egui::DragValue::new(&mut value[0]).speed(0.01))
+                .changed()
+            {
+                write_floats(bytes, offset, &value);
+            }
+        }
+        UniformFieldKind::Uint => {
+            let mut value = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+            if ui.add(egui::DragValue::new(&mut value)).changed() {
+                bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        UniformFieldKind::Uint64 => {
+            let mut value = u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+            if ui.add(egui::DragValue::new(&mut value)).changed() {
+                bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        UniformFieldKind::FloatVector(3) if is_color(field, 3) => {
+            let mut color = read_floats::<3>(bytes, offset);
+            if ui.color_edit_button_rgb(&mut color).changed() {
+                write_floats(bytes, offset, &color);
+            }
+        }
+        UniformFieldKind::FloatVector(4) if is_color(field, 4) => {
+            let mut color = read_floats::<4>(bytes, offset);
+            if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+                write_floats(bytes, offset, &color);
+            }
+        }
+        UniformFieldKind::FloatVector(count) => {
+            let mut values = read_floats::<4>(bytes, offset);
+            let changed = ui
+                .horizontal(|ui| {
+                    let mut changed = false;
+                    for value in &mut values[..count] {
+                        changed |= ui.add(egui::DragValue::new(value).speed(0.01)).changed();
+                    }
+                    changed
+                })
+                .inner;
+            if changed {
+                write_floats(bytes, offset, &values[..count]);
+            }
+        }
+        UniformFieldKind::Float4x4 => {
+            ui.weak("float4x4 (identity)");
+        }
+    }
+}
+
+/// Reads N floats at offset; vectors shorter than N read zeros past their end
+fn read_floats<const N: usize>(bytes: &[u8], offset: usize) -> [f32; N] {
+    std::array::from_fn(|i| {
+        let start = offset + 4 * i;
+        bytes
+            .get(start..start + 4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .unwrap_or(0.0)
+    })
+}
+
+fn write_floats(bytes: &mut [u8], offset: usize, values: &[f32]) {
+    for (i, value) in values.iter().enumerate() {
+        let start = offset + 4 * i;
+        bytes[start..start + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// A shader loaded from shaders/compiled at runtime, in place of a generated
+/// atlas entry
+struct CompiledShader {
+    reflection_json: ReflectionJson,
+    vert_spv: Vec<u32>,
+    frag_spv: Vec<u32>,
+}
+
+impl CompiledShader {
+    fn load(shader_name: &str) -> anyhow::Result<Self> {
+        let compiled_dir = manifest_path(["shaders", "compiled"]);
+        let json_path = compiled_dir.join(format!("{shader_name}.json"));
+        let json = std::fs::read_to_string(&json_path)
+            .with_context(|| format!("no compiled shader named '{shader_name}': {json_path:?}"))?;
+        let reflection_json: ReflectionJson = serde_json::from_str(&json)
+            .with_context(|| format!("failed to parse {json_path:?}"))?;
+
+        anyhow::ensure!(
+            !reflection_json.is_mesh_shader(),
+            "{shader_name} is a mesh shader; the viewer draws vertex shaders"
+        );
+        let takes_vertices = reflection_json
+            .vertex_entry_point
+            .parameters
+            .iter()
+            .any(|parameter| matches!(parameter, EntryPointParameter::Struct(_)));
+        anyhow::ensure!(
+            !takes_vertices,
+            "{shader_name} reads a vertex buffer; the viewer only draws shaders by vertex count"
+        );
+
+        let vert_spv = read_spv_file(&compiled_dir.join(format!("{shader_name}.vert.spv")))?;
+        let frag_spv = read_spv_file(&compiled_dir.join(format!("{shader_name}.frag.spv")))?;
+
+        Ok(Self {
+            reflection_json,
+            vert_spv,
+            frag_spv,
+        })
+    }
+}
+
+fn read_spv_file(path: &Path) -> anyhow::Result<Vec<u32>> {
+    let bytes = std::fs::read(path).with_context(|| format!("failed to read {path:?}"))?;
+    let words = ash::util::read_spv(&mut std::io::Cursor::new(bytes))
+        .with_context(|| format!("invalid spir-v: {path:?}"))?;
+
+    Ok(words)
+}
+
+impl ShaderAtlasEntry for CompiledShader {
+    fn source_file_name(&self) -> &str {
+        &self.reflection_json.source_file_name
+    }
+
+    fn reflection_json(&self) -> &ReflectionJson {
+        &self.reflection_json
+    }
+
+    fn vertex_binding_descriptions(&self) -> Vec<vk::VertexInputBindingDescription> {
+        vec![]
+    }
+
+    fn vertex_attribute_descriptions(&self) -> Vec<vk::VertexInputAttributeDescription> {
+        vec![]
+    }
+
+    fn layout_bindings(&self) -> Vec<Vec<LayoutDescription>> {
+        self.reflection_json.layout_bindings()
+    }
+
+    fn precompiled_shaders(&self) -> PrecompiledShaders {
+        let entry_point_name =
+            |name: &str| CString::new(name).expect("entry point names have no nul bytes");
+
+        let vert = PrecompiledShader {
+            entry_point_name: entry_point_name(
+                &self.reflection_json.vertex_entry_point.entry_point_name,
+            ),
+            spv_bytes: self.vert_spv.clone(),
+        };
+        let frag = PrecompiledShader {
+            entry_point_name: entry_point_name(
+                &self.reflection_json.fragment_entry_point.entry_point_name,
+            ),
+            spv_bytes: self.frag_spv.clone(),
+        };
+
+        PrecompiledShaders { vert, frag }
+    }
+
+    fn pipeline_layout(&self) -> &ReflectedPipelineLayout {
+        &self.reflection_json.pipeline_layout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_default_to_a_fullscreen_triangle() {
+        let args = ViewerArgs::parse(["sdf_2d".to_string()].into_iter()).unwrap();
+        assert_eq!(args.shader_name, "sdf_2d");
+        assert_eq!(args.vertex_count, 3);
+
+        let args = ViewerArgs::parse(["quad".to_string(), "6".to_string()].into_iter()).unwrap();
+        assert_eq!(args.vertex_count, 6);
+
+        assert!(ViewerArgs::parse(std::iter::empty()).is_err());
+    }
+
+    #[test]
+    fn float_fields_round_trip_through_bytes() {
+        let mut bytes = vec![0; 32];
+        write_floats(&mut bytes, 16, &[1.5, -2.0]);
+        assert_eq!(read_floats::<2>(&bytes, 16), [1.5, -2.0]);
+        // past the end of the buffer reads as zero
+        assert_eq!(read_floats::<4>(&bytes, 24), [0.0; 4]);
+    }
+}
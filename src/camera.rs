@@ -0,0 +1,208 @@
+//! A free-flying first person camera, for examples that explore a 3D scene.
+//!
+//! [`FlyController`] moves from a [`FlyInput`] each update, scaled by `dt`,
+//! and turns from mouse motion. For mouse-look, turn on
+//! [`FrameRenderer::set_relative_mouse_mode`](crate::renderer::FrameRenderer::set_relative_mouse_mode),
+//! and pass it [`Input::RelativeMouseMotion`]:
+//!
+//! ```ignore
+//! fn input(&mut self, input: Input) {
+//!     if let Input::RelativeMouseMotion { dx, dy } = input {
+//!         self.camera.look(Vec2::new(dx, dy));
+//!     }
+//! }
+//!
+//! fn update(&mut self, keyboard: &KeyboardState, dt: Duration) {
+//!     self.camera.update(&FlyInput::from_keyboard(keyboard), dt);
+//! }
+//! ```
+
+use std::f32::consts::FRAC_PI_2;
+use std::time::Duration;
+
+use glam::{Mat4, Quat, Vec2, Vec3};
+
+use crate::game::{Input, Key, KeyboardState};
+
+/// The camera controls held down this update
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlyInput {
+    pub forward: bool,
+    pub backward: bool,
+    pub left: bool,
+    pub right: bool,
+    pub roll_left: bool,
+    pub roll_right: bool,
+}
+
+impl FlyInput {
+    /// WASD to move, Q and E to roll
+    pub fn from_keyboard(keyboard: &KeyboardState) -> Self {
+        Self {
+            forward: keyboard.is_key_down(Key::W),
+            backward: keyboard.is_key_down(Key::S),
+            left: keyboard.is_key_down(Key::A),
+            right: keyboard.is_key_down(Key::D),
+            roll_left: keyboard.is_key_down(Key::Q),
+            roll_right: keyboard.is_key_down(Key::E),
+        }
+    }
+}
+
+/// A right-handed, +Y up camera that flies along its own facing
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlyController {
+    pub position: Vec3,
+    /// aka left/right facing angle; 0 faces +Z
+    pub yaw: f32,
+    /// aka up/down facing angle, kept short of straight up or down
+    pub pitch: f32,
+    /// aka left/right lean angle
+    pub roll: f32,
+
+    /// units per second
+    pub move_speed: f32,
+    /// radians per second
+    pub roll_speed: f32,
+    /// radians per pixel of mouse motion
+    pub look_sensitivity: f32,
+
+    pub fov_y_radians: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl FlyController {
+    const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+    /// A level camera at `position`, facing +Z
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            yaw: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            move_speed: 0.6,
+            roll_speed: 2.0,
+            look_sensitivity: 0.003,
+            fov_y_radians: 45.0_f32.to_radians(),
+            near: 0.1,
+            far: 1000.0,
+        }
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        Vec3::new(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        )
+    }
+
+    pub fn right(&self) -> Vec3 {
+        let forward = self.forward();
+        let base_right = forward.cross(Vec3::Y).normalize_or_zero();
+        Quat::from_axis_angle(forward, self.roll) * base_right
+    }
+
+    pub fn up(&self) -> Vec3 {
+        Quat::from_axis_angle(self.forward(), self.roll) * Vec3::Y
+    }
+
+    /// Move and roll for one update
+    pub fn update(&mut self, input: &FlyInput, dt: Duration) {
+        let dt = dt.as_secs_f32();
+        let forward = self.forward();
+        let right = self.right();
+
+        let mut movement = Vec3::ZERO;
+        if input.forward {
+            movement += forward;
+        }
+        if input.backward {
+            movement -= forward;
+        }
+        if input.left {
+            movement -= right;
+        }
+        if input.right {
+            movement += right;
+        }
+        self.position += movement.normalize_or_zero() * self.move_speed * dt;
+
+        if input.roll_left {
+            self.roll += self.roll_speed * dt;
+        }
+        if input.roll_right {
+            self.roll -= self.roll_speed * dt;
+        }
+    }
+
+    /// Turn by a mouse motion in pixels, with +y down
+    pub fn look(&mut self, delta: Vec2) {
+        self.yaw -= delta.x * self.look_sensitivity;
+        self.pitch =
+            (self.pitch - delta.y * self.look_sensitivity).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+    }
+
+    /// Turn from an [`Input::RelativeMouseMotion`]; ignores any other input
+    pub fn look_input(&mut self, input: &Input) {
+        if let Input::RelativeMouseMotion { dx, dy } = *input {
+            self.look(Vec2::new(dx, dy));
+        }
+    }
+
+    pub fn view(&self) -> Mat4 {
+        Mat4::look_at_rh(self.position, self.position + self.forward(), self.up())
+    }
+
+    pub fn projection(&self, aspect_ratio: f32) -> Mat4 {
+        Mat4::perspective_rh(self.fov_y_radians, aspect_ratio, self.near, self.far)
+    }
+
+    pub fn view_projection(&self, aspect_ratio: f32) -> Mat4 {
+        self.projection(aspect_ratio) * self.view()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn movement_follows_the_facing_and_scales_with_dt() {
+        let mut camera = FlyController::new(Vec3::ZERO);
+        let forward = FlyInput {
+            forward: true,
+            ..Default::default()
+        };
+        camera.update(&forward, SECOND / 2);
+        assert!(camera.position.abs_diff_eq(Vec3::new(0.0, 0.0, 0.3), 1e-6));
+
+        // a quarter turn to the right faces -X
+        camera.yaw = -FRAC_PI_2;
+        let diagonal = FlyInput {
+            forward: true,
+            right: true,
+            ..Default::default()
+        };
+        camera.position = Vec3::ZERO;
+        camera.update(&diagonal, SECOND);
+        let expected = Vec3::new(-1.0, 0.0, -1.0).normalize() * camera.move_speed;
+        assert!(camera.position.abs_diff_eq(expected, 1e-6));
+    }
+
+    #[test]
+    fn looking_turns_toward_the_mouse_and_stops_short_of_vertical() {
+        let mut camera = FlyController::new(Vec3::ZERO);
+        camera.look_input(&Input::RelativeMouseMotion { dx: 100.0, dy: 0.0 });
+        assert!(camera.forward().x < 0.0, "turned right, toward -X");
+        assert!(camera.right().z < 0.0);
+
+        camera.look(Vec2::new(0.0, -10_000.0));
+        assert_eq!(camera.pitch, FlyController::MAX_PITCH);
+        assert!(camera.view().is_finite());
+    }
+}
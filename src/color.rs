@@ -0,0 +1,135 @@
+//! Color conversions for values written to vertex, uniform, and storage buffers.
+//!
+//! Conventions, which the generated params structs repeat on their color fields:
+//! - colors given to shaders are linear RGB, with straight alpha unless the
+//!   pipeline uses [`BlendMode::Premultiplied`](crate::renderer::BlendMode::Premultiplied)
+//! - shaders output linear color; the swapchain (and any `_SRGB` texture view)
+//!   applies the sRGB encoding, so nothing should encode by hand before that
+//! - colors picked in an image editor or written as hex are sRGB-encoded, and
+//!   go through [`srgb8`] or [`srgb_hex`] on their way in
+//!
+//! Alpha is never sRGB-encoded. Blending two colors for a gradient looks best in
+//! OkLab ([`oklab_lerp`]); a plain `Vec4::lerp` of linear colors darkens and
+//! desaturates through the middle.
+
+use glam::{Vec3, Vec4};
+
+/// Decode one sRGB-encoded channel in [0, 1] to linear
+pub fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode one linear channel in [0, 1] as sRGB
+pub fn linear_to_srgb(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// A linear color from 8-bit sRGB channels and alpha, like `[255, 128, 0, 255]`
+pub fn srgb8([r, g, b, a]: [u8; 4]) -> Vec4 {
+    let decode = |channel: u8| srgb_to_linear(channel as f32 / 255.0);
+    Vec4::new(decode(r), decode(g), decode(b), a as f32 / 255.0)
+}
+
+/// A linear, opaque color from a `0xRRGGBB` hex code
+pub fn srgb_hex(rgb: u32) -> Vec4 {
+    let [_, r, g, b] = rgb.to_be_bytes();
+    srgb8([r, g, b, 255])
+}
+
+/// A linear color as 8-bit sRGB channels and alpha, e.g. for writing an image
+pub fn to_srgb8(color: Vec4) -> [u8; 4] {
+    let encode = |channel: f32| (linear_to_srgb(channel.clamp(0.0, 1.0)) * 255.0).round() as u8;
+    let alpha = (color.w.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [encode(color.x), encode(color.y), encode(color.z), alpha]
+}
+
+/// Linear RGB to OkLab (lightness, green-red, blue-yellow)
+pub fn linear_to_oklab(rgb: Vec3) -> Vec3 {
+    let l = 0.41222146 * rgb.x + 0.53633255 * rgb.y + 0.051445995 * rgb.z;
+    let m = 0.2119035 * rgb.x + 0.6806995 * rgb.y + 0.10739696 * rgb.z;
+    let s = 0.08830246 * rgb.x + 0.28171885 * rgb.y + 0.6299787 * rgb.z;
+
+    let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+    Vec3::new(
+        0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+        1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+        0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+    )
+}
+
+/// OkLab back to linear RGB; colors outside the sRGB gamut come back outside [0, 1]
+pub fn oklab_to_linear(lab: Vec3) -> Vec3 {
+    let l = lab.x + 0.39633778 * lab.y + 0.21580376 * lab.z;
+    let m = lab.x - 0.105561346 * lab.y - 0.06385417 * lab.z;
+    let s = lab.x - 0.08948418 * lab.y - 1.2914855 * lab.z;
+
+    let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+    Vec3::new(
+        4.0767417 * l - 3.3077116 * m + 0.23096994 * s,
+        -1.268438 * l + 2.6097574 * m - 0.34131938 * s,
+        -0.0041960864 * l - 0.7034186 * m + 1.7076147 * s,
+    )
+}
+
+/// Interpolate between two linear colors through OkLab, for perceptually even
+/// gradients; alpha is interpolated linearly
+pub fn oklab_lerp(from: Vec4, to: Vec4, t: f32) -> Vec4 {
+    let from_lab = linear_to_oklab(from.truncate());
+    let to_lab = linear_to_oklab(to.truncate());
+    let rgb = oklab_to_linear(from_lab.lerp(to_lab, t));
+
+    rgb.extend(from.w + (to.w - from.w) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb8_round_trips() {
+        for value in 0..=255u8 {
+            let color = srgb8([value, value, value, value]);
+            assert_eq!(to_srgb8(color), [value; 4]);
+        }
+    }
+
+    #[test]
+    fn hex_codes_decode_to_linear() {
+        let orange = srgb_hex(0xff8000);
+        assert!(
+            orange.abs_diff_eq(Vec4::new(1.0, 0.2158605, 0.0, 1.0), 1e-5),
+            "{orange}"
+        );
+    }
+
+    #[test]
+    fn oklab_round_trips() {
+        for rgb in [Vec3::ZERO, Vec3::ONE, Vec3::new(0.9, 0.2, 0.05)] {
+            let lab = linear_to_oklab(rgb);
+            assert!(oklab_to_linear(lab).abs_diff_eq(rgb, 1e-4), "{rgb}");
+        }
+
+        let white = linear_to_oklab(Vec3::ONE);
+        assert!(white.abs_diff_eq(Vec3::X, 1e-4), "{white}");
+    }
+
+    #[test]
+    fn oklab_lerp_hits_the_endpoints() {
+        let red = Vec4::new(1.0, 0.0, 0.0, 1.0);
+        let blue = Vec4::new(0.0, 0.0, 1.0, 0.0);
+
+        assert!(oklab_lerp(red, blue, 0.0).abs_diff_eq(red, 1e-4));
+        assert!(oklab_lerp(red, blue, 1.0).abs_diff_eq(blue, 1e-4));
+        assert_eq!(oklab_lerp(red, blue, 0.5).w, 0.5);
+    }
+}
@@ -0,0 +1,201 @@
+//! Immediate-mode world space lines, drawn in the main pass with the crate's
+//! own line shader (`shaders/source/debug_lines.shader.slang`), for gizmos,
+//! bounds and other debug shapes that should sit in the scene.
+//!
+//! ```ignore
+//! // in setup
+//! let mut lines = DebugLines::new(renderer, 1024)?;
+//!
+//! // in draw
+//! lines.set_camera(view, projection);
+//! lines.line(Vec3::ZERO, Vec3::X, Vec4::new(1.0, 0.0, 0.0, 1.0), 2.0);
+//! lines.flush(renderer)?;
+//! ```
+//!
+//! Lines are depth tested against the scene without writing depth, so
+//! geometry in front hides them. Lines drawn with [`DebugLines::x_ray_line`]
+//! skip the test and show through everything, eg a faint copy of a gizmo's
+//! hidden handles. Widths are in window pixels whatever the render scale.
+
+use glam::{Mat4, Vec2, Vec3, Vec4};
+
+use crate::generated::shader_atlas::debug_lines::{
+    DebugLine, DebugLinesParams, Projection, Resources, Shader,
+};
+use crate::quad::quad_vertex_range;
+use crate::renderer::{
+    DrawError, DrawVertexCount, FrameRenderer, Gpu, ImmutableBufferHandle, PipelineHandle,
+    RasterState, Renderer, UniformBufferHandle,
+};
+
+pub struct DebugLines {
+    params_buffer: UniformBufferHandle<DebugLinesParams>,
+    lines_buffer: ImmutableBufferHandle<DebugLine>,
+    depth_tested: PipelineHandle<DrawVertexCount>,
+    x_ray: PipelineHandle<DrawVertexCount>,
+    capacity: u32,
+    view_projection: Mat4,
+    /// lines drawn this frame, with the depth test
+    lines: Vec<DebugLine>,
+    /// lines drawn this frame, without it
+    x_ray_lines: Vec<DebugLine>,
+    /// the lines from the last queue_draws, depth tested first
+    queued: Vec<DebugLine>,
+    /// the params the queued lines are drawn with
+    frame_params: DebugLinesParams,
+    /// lines drawn past capacity this frame, and left out
+    dropped: u32,
+}
+
+impl DebugLines {
+    /// Lines for up to `capacity` segments a frame
+    pub fn new(renderer: &mut Renderer, capacity: u32) -> anyhow::Result<Self> {
+        let params_buffer = renderer.create_uniform_buffer::<DebugLinesParams>()?;
+        let lines_buffer = renderer.create_immutable_buffer::<DebugLine>(capacity)?;
+
+        let mut create_pipeline = |raster_state: RasterState| {
+            let resources = Resources {
+                params_buffer: &params_buffer,
+            };
+            let pipeline_config = Shader::init()
+                .pipeline_config(resources)
+                .with_raster_state(raster_state);
+            renderer.create_pipeline(pipeline_config)
+        };
+        let depth_tested = create_pipeline(RasterState::depth_tested_overlay())?;
+        let x_ray = create_pipeline(RasterState::overlay())?;
+
+        Ok(Self {
+            params_buffer,
+            lines_buffer,
+            depth_tested,
+            x_ray,
+            capacity,
+            view_projection: Mat4::IDENTITY,
+            lines: vec![],
+            x_ray_lines: vec![],
+            queued: vec![],
+            frame_params: DebugLinesParams::default(),
+            dropped: 0,
+        })
+    }
+
+    /// The view and projection the scene is drawn with, before the
+    /// shader-side Y flip (e.g. from `Mat4::look_at_rh` and `Mat4::perspective_rh`)
+    pub fn set_camera(&mut self, view: Mat4, projection: Mat4) {
+        self.view_projection = flip_y() * projection * view;
+    }
+
+    /// Draws a segment from `start` to `end` in world space, `width` window
+    /// pixels wide, in `color` (linear, straight alpha), behind any geometry
+    /// in front of it
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Vec4, width: f32) {
+        if let Some(line) = self.segment(start, end, color, width) {
+            self.lines.push(line);
+        }
+    }
+
+    /// Like [`Self::line`], drawn over everything
+    pub fn x_ray_line(&mut self, start: Vec3, end: Vec3, color: Vec4, width: f32) {
+        if let Some(line) = self.segment(start, end, color, width) {
+            self.x_ray_lines.push(line);
+        }
+    }
+
+    /// Draws lines through `points` in turn
+    pub fn polyline(&mut self, points: &[Vec3], color: Vec4, width: f32) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], color, width);
+        }
+    }
+
+    /// None past capacity
+    fn segment(&mut self, start: Vec3, end: Vec3, color: Vec4, width: f32) -> Option<DebugLine> {
+        let drawn = self.lines.len() + self.x_ray_lines.len();
+        if drawn == self.capacity as usize {
+            self.dropped += 1;
+            return None;
+        }
+
+        Some(DebugLine {
+            start,
+            width,
+            end,
+            padding: 0.0,
+            color,
+        })
+    }
+
+    /// Queues this frame's lines, for [`Self::write`] in the same
+    /// [`FrameRenderer::submit_draws`] to upload
+    pub fn queue_draws(&mut self, renderer: &mut FrameRenderer) {
+        if self.dropped > 0 {
+            log::warn!(
+                "drew {} lines past the debug lines' capacity of {}",
+                self.dropped,
+                self.capacity
+            );
+            self.dropped = 0;
+        }
+
+        self.queued.clear();
+        self.queued.append(&mut self.lines);
+        let depth_tested_count = self.queued.len() as u32;
+        self.queued.append(&mut self.x_ray_lines);
+        let x_ray_count = self.queued.len() as u32 - depth_tested_count;
+
+        self.frame_params.projection = Projection {
+            matrix: self.view_projection,
+        };
+        self.frame_params.pixel_size = Vec2::splat(2.0) / renderer.window_resolution();
+
+        for (pipeline, first_line, line_count) in [
+            (&self.depth_tested, 0, depth_tested_count),
+            (&self.x_ray, depth_tested_count, x_ray_count),
+        ] {
+            if line_count > 0 {
+                let (first_vertex, vertex_count) = quad_vertex_range(first_line, line_count);
+                renderer.queue_draw_vertex_range(pipeline, first_vertex, vertex_count);
+            }
+        }
+    }
+
+    /// Uploads the lines from the last [`Self::queue_draws`]
+    pub fn write(&mut self, gpu: &mut Gpu) {
+        let params = DebugLinesParams {
+            lines: gpu.current_immutable_addr(&self.lines_buffer),
+            ..self.frame_params.clone()
+        };
+        gpu.write_uniform(&mut self.params_buffer, params);
+        gpu.write_immutable(&mut self.lines_buffer, &self.queued);
+    }
+
+    /// Queues and uploads this frame's lines, and submits the frame;
+    /// for games that draw nothing else
+    pub fn flush(&mut self, mut renderer: FrameRenderer) -> Result<(), DrawError> {
+        self.queue_draws(&mut renderer);
+        renderer.submit_draws(|gpu| self.write(gpu))
+    }
+}
+
+/// The flip the scene's shaders apply after the projection; see mvp.slang
+pub(crate) fn flip_y() -> Mat4 {
+    Mat4::from_scale(Vec3::new(1.0, -1.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flipped_projections_put_world_up_at_the_top_of_the_window() {
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y);
+        let projection = Mat4::perspective_rh(60_f32.to_radians(), 1.0, 0.1, 100.0);
+        let view_projection = flip_y() * projection * view;
+
+        // vulkan's NDC has y down, so up in the world is negative y
+        let above = view_projection.project_point3(Vec3::Y);
+        assert!(above.y < 0.0);
+        assert!(above.z > 0.0 && above.z < 1.0);
+    }
+}
@@ -6,6 +6,10 @@
 use egui::Ui;
 use facet::Facet;
 
+pub mod gizmo;
+pub mod grid;
+pub mod scene_overlay;
+
 /// A value edited via egui::Slider with a defined range.
 #[derive(Clone, Debug, Facet)]
 pub struct Slider {
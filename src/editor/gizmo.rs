@@ -0,0 +1,608 @@
+//! Translate/rotate/scale gizmos for editing transforms in the editor.
+//!
+//! The handles are drawn as [`DebugLines`] in the scene's main pass: depth
+//! tested, so geometry in front hides them, with a faint copy over everything
+//! so a hidden handle can still be found and grabbed. Dragging goes through
+//! egui, which keeps the pointer from the game. The game owns a [`Gizmo`],
+//! gives it the camera each frame, chooses the selection (e.g. from
+//! [`FrameRenderer::picked_object_id`](crate::renderer::FrameRenderer::picked_object_id)),
+//! and returns it with its transforms from
+//! [`Game::editor_gizmo`](crate::game::Game::editor_gizmo).
+
+use glam::{Mat4, Quat, Vec2, Vec3, Vec4, Vec4Swizzles};
+
+use crate::color::srgb8;
+use crate::debug_lines::DebugLines;
+
+/// How far (in points) the pointer can be from a handle and still grab it
+const GRAB_DISTANCE: f32 = 8.0;
+const RING_SEGMENTS: usize = 48;
+/// handle line widths, in points
+const HANDLE_WIDTH: f32 = 2.5;
+/// the opacity of the copy of the handles drawn over the scene
+const HIDDEN_OPACITY: f32 = 0.3;
+
+const AXES: [Vec3; 3] = [Vec3::X, Vec3::Y, Vec3::Z];
+pub(super) const AXIS_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(230, 70, 70),
+    egui::Color32::from_rgb(70, 200, 70),
+    egui::Color32::from_rgb(70, 110, 240),
+];
+const ACTIVE_COLOR: egui::Color32 = egui::Color32::from_rgb(250, 220, 60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// The model matrix: scale, then rotate, then translate
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GizmoMode {
+    /// arrows along the world axes
+    #[default]
+    Translate,
+    /// rings around the world axes
+    Rotate,
+    /// handles along the transform's local axes
+    Scale,
+}
+
+pub struct Gizmo {
+    pub mode: GizmoMode,
+    /// the length of the handles on screen, in points
+    pub size: f32,
+    selected: Option<usize>,
+    view: Mat4,
+    projection: Mat4,
+    drag: Option<Drag>,
+}
+
+/// A handle being dragged
+struct Drag {
+    axis: usize,
+    start_pointer: Vec2,
+    start: Transform,
+}
+
+impl Gizmo {
+    pub fn new() -> Self {
+        Self {
+            mode: GizmoMode::default(),
+            size: 80.0,
+            selected: None,
+            view: Mat4::IDENTITY,
+            projection: Mat4::IDENTITY,
+            drag: None,
+        }
+    }
+
+    /// The view and projection the scene is drawn with, before the
+    /// shader-side Y flip (e.g. from `Mat4::look_at_rh` and `Mat4::perspective_rh`)
+    pub fn set_camera(&mut self, view: Mat4, projection: Mat4) {
+        self.view = view;
+        self.projection = projection;
+    }
+
+    /// Select a transform by index, or nothing
+    pub fn select(&mut self, selected: Option<usize>) {
+        if self.selected != selected {
+            self.drag = None;
+        }
+        self.selected = selected;
+    }
+
+    /// Select from a picking pass result, where 0 is nothing
+    /// and anything else is an index plus one
+    pub fn select_picked(&mut self, picked_object_id: u32) {
+        self.select(picked_object_id.checked_sub(1).map(|index| index as usize));
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// True while a handle is being dragged;
+    /// games may want to skip picking-based selection meanwhile
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Draw the selected transform's handles into `lines`, and apply any
+    /// drag to it. Returns true if the transform changed.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        transforms: &mut [Transform],
+        lines: &mut DebugLines,
+    ) -> bool {
+        let Some(transform) = self.selected.and_then(|index| transforms.get_mut(index)) else {
+            self.drag = None;
+            return false;
+        };
+
+        let viewport = Viewport::new(ctx, self.view, self.projection);
+        let Some(handles) = Handles::new(&viewport, self.mode, transform, self.size) else {
+            return false;
+        };
+
+        let reach = Vec2::splat(self.size + GRAB_DISTANCE);
+        let bounds = egui::Rect::from_min_max(
+            to_pos(handles.origin - reach),
+            to_pos(handles.origin + reach),
+        );
+
+        let mut changed = false;
+        egui::Area::new(egui::Id::new("editor_gizmo"))
+            .order(egui::Order::Background)
+            .fixed_pos(bounds.min)
+            .show(ctx, |ui| {
+                // claiming the handles' area keeps these clicks from the game
+                let response = ui.allocate_rect(bounds, egui::Sense::drag());
+                let pointer = response.interact_pointer_pos().or(response.hover_pos());
+                let pointer = pointer.map(|pos| Vec2::new(pos.x, pos.y));
+
+                if response.drag_started()
+                    && let Some(pointer) = pointer
+                    && let Some(axis) = handles.hit(pointer)
+                {
+                    self.drag = Some(Drag {
+                        axis,
+                        start_pointer: pointer,
+                        start: *transform,
+                    });
+                }
+
+                if let Some(drag) = &self.drag
+                    && let Some(pointer) = pointer
+                    && response.dragged()
+                {
+                    let dragged = handles.apply(drag, pointer);
+                    changed = dragged != *transform;
+                    *transform = dragged;
+                }
+
+                if response.drag_stopped() {
+                    self.drag = None;
+                }
+
+                let active = match &self.drag {
+                    Some(drag) => Some(drag.axis),
+                    None => pointer.and_then(|pointer| handles.hit(pointer)),
+                };
+                lines.set_camera(self.view, self.projection);
+                handles.draw(lines, active, HANDLE_WIDTH * ctx.pixels_per_point());
+            });
+
+        changed
+    }
+}
+
+impl Default for Gizmo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Viewport {
+    view_proj: Mat4,
+    camera_position: Vec3,
+    /// the screen size in points
+    size: Vec2,
+}
+
+impl Viewport {
+    fn new(ctx: &egui::Context, view: Mat4, projection: Mat4) -> Self {
+        let screen = ctx.screen_rect();
+        Self {
+            view_proj: projection * view,
+            camera_position: view.inverse().w_axis.xyz(),
+            size: Vec2::new(screen.width(), screen.height()),
+        }
+    }
+
+    /// Screen position in points, or None behind the camera
+    fn to_screen(&self, world: Vec3) -> Option<Vec2> {
+        let clip = self.view_proj * world.extend(1.0);
+        if clip.w <= f32::EPSILON {
+            return None;
+        }
+
+        Some(self.clip_to_screen(clip))
+    }
+
+    fn clip_to_screen(&self, clip: Vec4) -> Vec2 {
+        let ndc = clip.xy() / clip.w;
+        // y up in NDC; the shaders flip it for vulkan
+        Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * self.size
+    }
+}
+
+/// One mode's handles for a transform, laid out on screen
+struct Handles {
+    mode: GizmoMode,
+    center: Vec3,
+    origin: Vec2,
+    /// world axes for translate and rotate, local axes for scale
+    axes: [Vec3; 3],
+    /// each axis's direction on screen (normalized),
+    /// or None when it points (almost) straight at the camera
+    screen_axes: [Option<Vec2>; 3],
+    /// world units per point of screen movement along each axis
+    world_per_point: [f32; 3],
+    /// whether each axis points toward the camera, for rotation direction
+    facing_camera: [bool; 3],
+    /// each rotate ring's points in world space, closed
+    world_rings: [Vec<Vec3>; 3],
+    /// the rings on screen, without any points behind the camera
+    rings: [Vec<Vec2>; 3],
+    size: f32,
+}
+
+impl Handles {
+    fn new(viewport: &Viewport, mode: GizmoMode, transform: &Transform, size: f32) -> Option<Self> {
+        let center = transform.translation;
+        let origin = viewport.to_screen(center)?;
+
+        let axes = match mode {
+            GizmoMode::Translate | GizmoMode::Rotate => AXES,
+            GizmoMode::Scale => AXES.map(|axis| transform.rotation * axis),
+        };
+
+        // measure each axis with a step small enough to stay in front of the camera
+        let step = 0.01 * center.distance(viewport.camera_position).max(0.01);
+        let mut screen_axes = [None; 3];
+        let mut world_per_point = [0.0; 3];
+        for (i, axis) in axes.iter().enumerate() {
+            let Some(end) = viewport.to_screen(center + *axis * step) else {
+                continue;
+            };
+            let delta = end - origin;
+            let length = delta.length();
+            if length > 1e-3 {
+                screen_axes[i] = Some(delta / length);
+                world_per_point[i] = step / length;
+            }
+        }
+
+        let to_camera = viewport.camera_position - center;
+        let facing_camera = axes.map(|axis| axis.dot(to_camera) > 0.0);
+
+        let world_rings: [Vec<Vec3>; 3] = std::array::from_fn(|i| {
+            if mode != GizmoMode::Rotate {
+                return vec![];
+            }
+
+            // sized to match the other handles where the view is head-on
+            let radius = size * world_per_point.iter().copied().fold(0.0, f32::max);
+            let (u, v) = axes[i].any_orthonormal_pair();
+            (0..=RING_SEGMENTS)
+                .map(|segment| {
+                    let angle = segment as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU;
+                    center + (u * angle.cos() + v * angle.sin()) * radius
+                })
+                .collect()
+        });
+        let rings = std::array::from_fn(|i| {
+            world_rings[i]
+                .iter()
+                .filter_map(|&point| viewport.to_screen(point))
+                .collect()
+        });
+
+        Some(Self {
+            mode,
+            center,
+            origin,
+            axes,
+            screen_axes,
+            world_per_point,
+            facing_camera,
+            world_rings,
+            rings,
+            size,
+        })
+    }
+
+    fn handle_end(&self, axis: usize) -> Option<Vec2> {
+        self.screen_axes[axis].map(|direction| self.origin + direction * self.size)
+    }
+
+    /// The axis whose handle is nearest the pointer, if any is in reach
+    fn hit(&self, pointer: Vec2) -> Option<usize> {
+        (0..3)
+            .filter_map(|axis| {
+                let distance = match self.mode {
+                    GizmoMode::Translate | GizmoMode::Scale => {
+                        distance_to_segment(pointer, self.origin, self.handle_end(axis)?)
+                    }
+                    GizmoMode::Rotate => self.rings[axis]
+                        .windows(2)
+                        .map(|segment| distance_to_segment(pointer, segment[0], segment[1]))
+                        .reduce(f32::min)?,
+                };
+                (distance <= GRAB_DISTANCE).then_some((axis, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(axis, _)| axis)
+    }
+
+    /// The drag's start transform with the pointer's movement since applied
+    fn apply(&self, drag: &Drag, pointer: Vec2) -> Transform {
+        let mut transform = drag.start;
+        let axis = drag.axis;
+        let Some(screen_axis) = self.screen_axes[axis] else {
+            return transform;
+        };
+        let along = (pointer - drag.start_pointer).dot(screen_axis);
+
+        match self.mode {
+            GizmoMode::Translate => {
+                transform.translation += self.axes[axis] * along * self.world_per_point[axis];
+            }
+
+            GizmoMode::Scale => {
+                let factor = (1.0 + along / self.size).max(0.01);
+                transform.scale[axis] *= factor;
+            }
+
+            GizmoMode::Rotate => {
+                let from = drag.start_pointer - self.origin;
+                let to = pointer - self.origin;
+                // screen y points down, so a clockwise-looking turn has a positive angle
+                let clockwise = from.perp_dot(to).atan2(from.dot(to));
+                let angle = if self.facing_camera[axis] {
+                    -clockwise
+                } else {
+                    clockwise
+                };
+                transform.rotation =
+                    Quat::from_axis_angle(self.axes[axis], angle) * transform.rotation;
+            }
+        }
+
+        transform
+    }
+
+    /// The world space end of an axis's translate or scale handle
+    fn world_handle_end(&self, axis: usize) -> Option<Vec3> {
+        self.screen_axes[axis]?;
+        Some(self.center + self.axes[axis] * self.size * self.world_per_point[axis])
+    }
+
+    /// `width` is in window pixels
+    fn draw(&self, lines: &mut DebugLines, active: Option<usize>, width: f32) {
+        let mut draw_line = |start: Vec3, end: Vec3, color: egui::Color32| {
+            let color = srgb8(color.to_array());
+            let hidden = color * Vec4::new(1.0, 1.0, 1.0, HIDDEN_OPACITY);
+            lines.line(start, end, color, width);
+            lines.x_ray_line(start, end, hidden, width);
+        };
+
+        for axis in 0..3 {
+            let color = if active == Some(axis) {
+                ACTIVE_COLOR
+            } else {
+                AXIS_COLORS[axis]
+            };
+
+            match self.mode {
+                GizmoMode::Translate => {
+                    if let Some(tip) = self.world_handle_end(axis) {
+                        draw_line(self.center, tip, color);
+                        for [start, end] in arrow_head(self.center, tip, self.axes[axis]) {
+                            draw_line(start, end, color);
+                        }
+                    }
+                }
+
+                GizmoMode::Scale => {
+                    if let Some(end) = self.world_handle_end(axis) {
+                        draw_line(self.center, end, color);
+                        // about 8 points across, like the translate arrow heads
+                        let half_size = 4.0 * self.world_per_point[axis];
+                        for [start, end] in cube_edges(end, self.axes, half_size) {
+                            draw_line(start, end, color);
+                        }
+                    }
+                }
+
+                GizmoMode::Rotate => {
+                    for pair in self.world_rings[axis].windows(2) {
+                        draw_line(pair[0], pair[1], color);
+                    }
+                }
+            }
+        }
+
+        // a small white cross at the transform's origin
+        let reach = 3.0 * self.world_per_point.iter().copied().fold(0.0, f32::max);
+        for axis in AXES {
+            let offset = axis * reach;
+            draw_line(
+                self.center - offset,
+                self.center + offset,
+                egui::Color32::WHITE,
+            );
+        }
+    }
+}
+
+/// The lines of an arrow head at `tip`, pointing along `axis` from `start`
+fn arrow_head(start: Vec3, tip: Vec3, axis: Vec3) -> [[Vec3; 2]; 4] {
+    let length = start.distance(tip);
+    let back = tip - axis * length * 0.25;
+    let (u, v) = axis.any_orthonormal_pair();
+    let spread = length * 0.1;
+    [u, -u, v, -v].map(|side| [tip, back + side * spread])
+}
+
+/// The 12 edges of a cube around `center`, aligned to `axes`
+fn cube_edges(center: Vec3, axes: [Vec3; 3], half_size: f32) -> Vec<[Vec3; 2]> {
+    let corner = |signs: [f32; 3]| {
+        center + (axes[0] * signs[0] + axes[1] * signs[1] + axes[2] * signs[2]) * half_size
+    };
+
+    let mut edges = vec![];
+    for axis in 0..3 {
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        for (sign_a, sign_b) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+            let mut from = [0.0; 3];
+            from[a] = sign_a;
+            from[b] = sign_b;
+            let mut to = from;
+            from[axis] = -1.0;
+            to[axis] = 1.0;
+            edges.push([corner(from), corner(to)]);
+        }
+    }
+    edges
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let t = if segment.length_squared() > 0.0 {
+        ((point - start).dot(segment) / segment.length_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(start + segment * t)
+}
+
+fn to_pos(point: Vec2) -> egui::Pos2 {
+    egui::pos2(point.x, point.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport() -> Viewport {
+        let camera_position = Vec3::new(0.0, 0.0, 5.0);
+        let view = Mat4::look_at_rh(camera_position, Vec3::ZERO, Vec3::Y);
+        let projection = Mat4::perspective_rh(60_f32.to_radians(), 1.0, 0.1, 100.0);
+        Viewport {
+            view_proj: projection * view,
+            camera_position,
+            size: Vec2::splat(800.0),
+        }
+    }
+
+    #[test]
+    fn screen_space_points_y_down() {
+        let viewport = viewport();
+        let center = viewport.to_screen(Vec3::ZERO).unwrap();
+        let above = viewport.to_screen(Vec3::Y).unwrap();
+
+        assert!((center - Vec2::splat(400.0)).length() < 1e-3);
+        assert!(above.y < center.y);
+        assert!(viewport.to_screen(Vec3::new(0.0, 0.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn axes_toward_the_camera_have_no_handle() {
+        let handles = Handles::new(
+            &viewport(),
+            GizmoMode::Translate,
+            &Transform::IDENTITY,
+            80.0,
+        )
+        .unwrap();
+
+        assert!(handles.screen_axes[0].is_some());
+        assert!(handles.screen_axes[1].is_some());
+        assert!(handles.screen_axes[2].is_none());
+    }
+
+    #[test]
+    fn dragging_along_a_translate_handle_moves_along_its_axis() {
+        let handles = Handles::new(
+            &viewport(),
+            GizmoMode::Translate,
+            &Transform::IDENTITY,
+            80.0,
+        )
+        .unwrap();
+        let grab = handles.origin + Vec2::new(40.0, 0.0);
+        assert_eq!(handles.hit(grab), Some(0));
+
+        let drag = Drag {
+            axis: 0,
+            start_pointer: grab,
+            start: Transform::IDENTITY,
+        };
+        // sideways pointer movement doesn't count
+        let moved = handles.apply(&drag, grab + Vec2::new(100.0, 30.0));
+
+        assert!(moved.translation.x > 0.0);
+        assert_eq!(moved.translation.y, 0.0);
+        assert_eq!(moved.translation.z, 0.0);
+    }
+
+    #[test]
+    fn rotate_drags_turn_the_way_the_pointer_does() {
+        let handles =
+            Handles::new(&viewport(), GizmoMode::Rotate, &Transform::IDENTITY, 80.0).unwrap();
+        let drag = Drag {
+            axis: 2,
+            start_pointer: handles.origin + Vec2::new(80.0, 0.0),
+            start: Transform::IDENTITY,
+        };
+
+        // up on screen from the right side: counterclockwise as seen from +z
+        let turned = handles.apply(&drag, handles.origin + Vec2::new(0.0, -80.0));
+        let x_axis = turned.rotation * Vec3::X;
+
+        assert!((x_axis - Vec3::Y).length() < 1e-4, "{x_axis}");
+    }
+
+    #[test]
+    fn scale_handles_end_in_a_cube() {
+        let center = Vec3::new(1.0, 2.0, 3.0);
+        let edges = cube_edges(center, AXES, 0.5);
+
+        assert_eq!(edges.len(), 12);
+        for [start, end] in edges {
+            assert!((start.distance(end) - 1.0).abs() < 1e-6);
+            let corner_distance = 0.5 * 3_f32.sqrt();
+            assert!((start.distance(center) - corner_distance).abs() < 1e-6);
+            assert!((end.distance(center) - corner_distance).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn picked_ids_select_by_index_plus_one() {
+        let mut gizmo = Gizmo::new();
+        gizmo.select_picked(3);
+        assert_eq!(gizmo.selected(), Some(2));
+
+        gizmo.select_picked(0);
+        assert_eq!(gizmo.selected(), None);
+    }
+}
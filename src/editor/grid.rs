@@ -0,0 +1,214 @@
+//! A ground grid and world axes for 3D scenes in the editor.
+//!
+//! The grid is drawn in the scene's main pass by the crate's grid shader
+//! (`shaders/source/grid.shader.slang`), a fullscreen triangle that finds
+//! where each pixel's view ray meets the XZ plane and writes that depth, so
+//! geometry in front hides it. It follows the camera so it seems endless, and
+//! fades out toward its edge; the y axis is a line through the origin. The
+//! game owns a [`Grid`], gives it the camera each frame, and returns it from
+//! [`Game::editor_grid`](crate::game::Game::editor_grid); the editor then
+//! draws a window to toggle it.
+
+use glam::{Mat4, Vec2, Vec3, Vec4, Vec4Swizzles};
+
+use crate::color::srgb8;
+use crate::debug_lines::flip_y;
+use crate::generated::shader_atlas::grid::{GridParams, Projection, Resources, Shader};
+use crate::renderer::{
+    DrawVertexCount, FrameRenderer, Gpu, PipelineHandle, RasterState, Renderer, UniformBufferHandle,
+};
+
+use super::gizmo::AXIS_COLORS;
+use super::scene_overlay::SceneOverlay;
+
+const LINE_COLOR: egui::Color32 = egui::Color32::from_gray(150);
+/// the y axis's width, in window pixels
+const AXIS_WIDTH: f32 = 2.0;
+
+pub struct Grid {
+    pub visible: bool,
+    /// world units between lines
+    pub spacing: f32,
+    /// how many lines to draw on each side of the camera, along each axis
+    pub line_count: u32,
+    view: Mat4,
+    projection: Mat4,
+}
+
+impl Grid {
+    pub fn new() -> Self {
+        Self {
+            visible: true,
+            spacing: 1.0,
+            line_count: 20,
+            view: Mat4::IDENTITY,
+            projection: Mat4::IDENTITY,
+        }
+    }
+
+    /// The view and projection the scene is drawn with, before the
+    /// shader-side Y flip (e.g. from `Mat4::look_at_rh` and `Mat4::perspective_rh`)
+    pub fn set_camera(&mut self, view: Mat4, projection: Mat4) {
+        self.view = view;
+        self.projection = projection;
+    }
+
+    /// The grid's toggle and settings
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.visible, "Grid");
+        ui.add_enabled_ui(self.visible, |ui| {
+            ui.add(
+                egui::Slider::new(&mut self.spacing, 0.1..=10.0)
+                    .logarithmic(true)
+                    .text("spacing"),
+            );
+            ui.add(egui::Slider::new(&mut self.line_count, 1..=100).text("lines"));
+        });
+    }
+
+    /// Draw the grid and axes into the next frame, if visible
+    pub fn show(&self, overlay: &mut SceneOverlay) {
+        if !self.visible {
+            return;
+        }
+
+        let params = self.params();
+        if let Some(opacity) = y_axis_opacity(params.camera_position, params.radius) {
+            let color = srgb8(AXIS_COLORS[1].to_array()) * Vec4::new(1.0, 1.0, 1.0, opacity);
+            let reach = Vec3::Y * params.radius;
+            overlay.lines.set_camera(self.view, self.projection);
+            overlay.lines.line(-reach, reach, color, AXIS_WIDTH);
+        }
+        overlay.grid.params = Some(params);
+    }
+
+    fn params(&self) -> GridParams {
+        let spacing = self.spacing.max(f32::EPSILON);
+        let view_projection = flip_y() * self.projection * self.view;
+        GridParams {
+            view_projection: Projection {
+                matrix: view_projection,
+            },
+            inverse_view_projection: Projection {
+                matrix: view_projection.inverse(),
+            },
+            camera_position: self.view.inverse().w_axis.xyz(),
+            spacing,
+            line_color: srgb8(LINE_COLOR.to_array()),
+            x_axis_color: srgb8(AXIS_COLORS[0].to_array()),
+            z_axis_color: srgb8(AXIS_COLORS[2].to_array()),
+            radius: self.line_count.max(1) as f32 * spacing,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The y axis stands at the origin, fading like the grid's lines
+/// with the camera's distance along the ground; None past the grid's edge
+fn y_axis_opacity(camera_position: Vec3, radius: f32) -> Option<f32> {
+    let distance = Vec2::new(camera_position.x, camera_position.z).length();
+    (distance < radius).then(|| 1.0 - distance / radius)
+}
+
+/// The grid shader's pipeline, drawn in frames the grid is shown in
+pub(crate) struct GridPass {
+    params_buffer: UniformBufferHandle<GridParams>,
+    pipeline: PipelineHandle<DrawVertexCount>,
+    /// set by [`Grid::show`] for the next frame
+    params: Option<GridParams>,
+    /// the params of the draw from the last queue_draws
+    queued: Option<GridParams>,
+}
+
+impl GridPass {
+    pub(crate) fn new(renderer: &mut Renderer) -> anyhow::Result<Self> {
+        let params_buffer = renderer.create_uniform_buffer::<GridParams>()?;
+        let resources = Resources {
+            params_buffer: &params_buffer,
+        };
+        // the grid's depth hides it behind geometry, without hiding the gizmo's lines
+        let pipeline_config = Shader::init()
+            .pipeline_config(resources)
+            .with_raster_state(RasterState::depth_tested_overlay());
+        let pipeline = renderer.create_pipeline(pipeline_config)?;
+
+        Ok(Self {
+            params_buffer,
+            pipeline,
+            params: None,
+            queued: None,
+        })
+    }
+
+    pub(crate) fn queue_draws(&mut self, renderer: &mut FrameRenderer) {
+        self.queued = self.params.take();
+        if self.queued.is_some() {
+            // a fullscreen triangle
+            renderer.queue_draw_vertex_count(&self.pipeline, 3);
+        }
+    }
+
+    pub(crate) fn write(&mut self, gpu: &mut Gpu) {
+        if let Some(params) = self.queued.take() {
+            gpu.write_uniform(&mut self.params_buffer, params);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(camera_position: Vec3) -> Grid {
+        let mut grid = Grid {
+            spacing: 2.0,
+            line_count: 3,
+            ..Grid::new()
+        };
+        let view = Mat4::look_at_rh(camera_position, Vec3::new(0.0, 0.0, -10.0), Vec3::Y);
+        let projection = Mat4::perspective_rh(60_f32.to_radians(), 1.0, 0.1, 100.0);
+        grid.set_camera(view, projection);
+        grid
+    }
+
+    #[test]
+    fn pixel_rays_meet_the_ground_at_its_depth() {
+        let params = grid(Vec3::new(3.0, 5.0, 4.0)).params();
+        assert!(
+            params
+                .camera_position
+                .abs_diff_eq(Vec3::new(3.0, 5.0, 4.0), 1e-4)
+        );
+        assert_eq!(params.radius, 6.0);
+
+        // as the shader does it, for the middle of the screen
+        let unproject = |depth: f32| {
+            let world = params.inverse_view_projection.matrix * Vec4::new(0.0, 0.0, depth, 1.0);
+            world.xyz() / world.w
+        };
+        let near = unproject(0.0);
+        let ray = unproject(1.0) - near;
+        let t = -near.y / ray.y;
+        assert!((0.0..=1.0).contains(&t));
+
+        let hit = near + ray * t;
+        assert!(hit.y.abs() < 1e-3);
+        let clip = params.view_projection.matrix * hit.extend(1.0);
+        let depth = clip.z / clip.w;
+        assert!(depth > 0.0 && depth < 1.0);
+    }
+
+    #[test]
+    fn the_y_axis_fades_with_distance_from_the_origin() {
+        let near = y_axis_opacity(Vec3::new(1.0, 8.0, 0.0), 4.0).unwrap();
+        let far = y_axis_opacity(Vec3::new(3.0, 8.0, 0.0), 4.0).unwrap();
+        assert!(near > far);
+        assert_eq!(y_axis_opacity(Vec3::new(3.0, 0.0, 3.0), 4.0), None);
+    }
+}
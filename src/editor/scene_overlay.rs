@@ -0,0 +1,39 @@
+//! What the editor draws into the scene's main pass, rather than over the
+//! finished frame with egui: the ground grid, and the gizmos' lines. The
+//! renderer owns one, created with the first edit ui, and queues it after the
+//! game's draws; the grid and gizmo from
+//! [`Game::editor_grid`](crate::game::Game::editor_grid) and
+//! [`Game::editor_gizmo`](crate::game::Game::editor_gizmo) draw into it.
+
+use crate::debug_lines::DebugLines;
+use crate::renderer::{FrameRenderer, Gpu, Renderer};
+
+use super::grid::GridPass;
+
+/// line segments a frame for the gizmos
+const LINE_CAPACITY: u32 = 4096;
+
+pub struct SceneOverlay {
+    pub lines: DebugLines,
+    pub(super) grid: GridPass,
+}
+
+impl SceneOverlay {
+    pub(crate) fn new(renderer: &mut Renderer) -> anyhow::Result<Self> {
+        Ok(Self {
+            lines: DebugLines::new(renderer, LINE_CAPACITY)?,
+            grid: GridPass::new(renderer)?,
+        })
+    }
+
+    /// The grid goes first, so the lines blend over it
+    pub(crate) fn queue_draws(&mut self, renderer: &mut FrameRenderer) {
+        self.grid.queue_draws(renderer);
+        self.lines.queue_draws(renderer);
+    }
+
+    pub(crate) fn write(&mut self, gpu: &mut Gpu) {
+        self.grid.write(gpu);
+        self.lines.write(gpu);
+    }
+}
@@ -1,2 +1,11 @@
+mod input_map;
+pub mod launch_options;
 pub(crate) mod traits;
-pub use traits::{Game, Input, Key, MaxMSAASamples, MouseButton, WindowDescription};
+
+pub use input_map::InputMap;
+pub use launch_options::LaunchOptions;
+pub use traits::{
+    DrawErrorPolicy, Editable, Game, GamepadAxis, GamepadButton, Input, InputEvent, Key,
+    KeyboardState, MaxMSAASamples, MouseButton, Presentation, RedrawMode, ViewMode,
+    WindowDescription,
+};
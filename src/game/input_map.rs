@@ -0,0 +1,190 @@
+//! Key bindings for a game's own actions, so it can ask whether "fire" is
+//! held instead of matching on keys, and let players rebind them.
+//!
+//! ```ignore
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//! enum Action { Left, Right, Fire }
+//!
+//! let mut bindings = InputMap::new()
+//!     .bind(Action::Left, Key::A)
+//!     .bind(Action::Left, Key::Left)
+//!     .bind(Action::Right, Key::D)
+//!     .bind(Action::Right, Key::Right)
+//!     .bind(Action::Fire, Key::Space);
+//!
+//! // in update
+//! let x = bindings.axis(Action::Left, Action::Right, keyboard);
+//! let firing = bindings.is_down(Action::Fire, keyboard);
+//!
+//! // in input
+//! for action in bindings.pressed(&input) { ... }
+//! ```
+
+use super::{Input, Key, KeyboardState};
+
+/// Actions bound to keys; an action can have several keys, and a key
+/// several actions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMap<A> {
+    /// in the order they were bound
+    bindings: Vec<(A, Key)>,
+}
+
+impl<A> Default for InputMap<A> {
+    fn default() -> Self {
+        Self { bindings: vec![] }
+    }
+}
+
+impl<A: Copy + PartialEq> InputMap<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key for `action`, keeping any it already has
+    pub fn bind(mut self, action: A, key: Key) -> Self {
+        self.add_binding(action, key);
+        self
+    }
+
+    /// Adds a key for `action`, keeping any it already has
+    pub fn add_binding(&mut self, action: A, key: Key) {
+        if !self.bindings.contains(&(action, key)) {
+            self.bindings.push((action, key));
+        }
+    }
+
+    /// Replaces all of `action`'s keys with `key`, eg from a rebinding menu
+    pub fn rebind(&mut self, action: A, key: Key) {
+        self.bindings.retain(|&(bound, _)| bound != action);
+        self.bindings.push((action, key));
+    }
+
+    /// Removes every binding to `key`
+    pub fn unbind_key(&mut self, key: Key) {
+        self.bindings.retain(|&(_, bound)| bound != key);
+    }
+
+    /// The keys bound to `action`, in the order they were bound
+    pub fn keys(&self, action: A) -> impl Iterator<Item = Key> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |&&(bound, _)| bound == action)
+            .map(|&(_, key)| key)
+    }
+
+    /// The actions bound to `key`, in the order they were bound
+    pub fn actions(&self, key: Key) -> impl Iterator<Item = A> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |&&(_, bound)| bound == key)
+            .map(|&(action, _)| action)
+    }
+
+    /// Whether any of `action`'s keys is held
+    pub fn is_down(&self, action: A, keyboard: &KeyboardState) -> bool {
+        self.keys(action).any(|key| keyboard.is_key_down(key))
+    }
+
+    /// -1 while only `negative` is held, 1 while only `positive` is,
+    /// and 0 for both or neither
+    pub fn axis(&self, negative: A, positive: A, keyboard: &KeyboardState) -> f32 {
+        let negative = self.is_down(negative, keyboard) as i8;
+        let positive = self.is_down(positive, keyboard) as i8;
+        (positive - negative) as f32
+    }
+
+    /// The actions a [`Input::KeyDown`] presses, key repeats included;
+    /// none for any other input
+    pub fn pressed(&self, input: &Input) -> impl Iterator<Item = A> + '_ {
+        let key = match *input {
+            Input::KeyDown(key) => Some(key),
+            _ => None,
+        };
+        key.into_iter().flat_map(|key| self.actions(key))
+    }
+
+    /// The actions a [`Input::KeyUp`] releases; none for any other input
+    pub fn released(&self, input: &Input) -> impl Iterator<Item = A> + '_ {
+        let key = match *input {
+            Input::KeyUp(key) => Some(key),
+            _ => None,
+        };
+        key.into_iter().flat_map(|key| self.actions(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Action {
+        Left,
+        Right,
+        Fire,
+    }
+
+    fn bindings() -> InputMap<Action> {
+        InputMap::new()
+            .bind(Action::Left, Key::A)
+            .bind(Action::Left, Key::Left)
+            .bind(Action::Right, Key::D)
+            .bind(Action::Fire, Key::Space)
+            .bind(Action::Fire, Key::Space)
+    }
+
+    #[test]
+    fn actions_are_down_while_any_of_their_keys_is() {
+        let bindings = bindings();
+        let mut keyboard = KeyboardState::default();
+        assert!(!bindings.is_down(Action::Left, &keyboard));
+        assert_eq!(bindings.axis(Action::Left, Action::Right, &keyboard), 0.0);
+
+        keyboard.press(Key::Left);
+        assert!(bindings.is_down(Action::Left, &keyboard));
+        assert_eq!(bindings.axis(Action::Left, Action::Right, &keyboard), -1.0);
+
+        keyboard.press(Key::D);
+        assert_eq!(bindings.axis(Action::Left, Action::Right, &keyboard), 0.0);
+        keyboard.release(Key::Left);
+        assert_eq!(bindings.axis(Action::Left, Action::Right, &keyboard), 1.0);
+    }
+
+    #[test]
+    fn rebinding_replaces_an_actions_keys() {
+        let mut bindings = bindings();
+        assert_eq!(
+            bindings.keys(Action::Fire).collect::<Vec<_>>(),
+            [Key::Space]
+        );
+
+        bindings.rebind(Action::Left, Key::J);
+        assert_eq!(bindings.keys(Action::Left).collect::<Vec<_>>(), [Key::J]);
+
+        bindings.add_binding(Action::Fire, Key::J);
+        assert_eq!(
+            bindings.actions(Key::J).collect::<Vec<_>>(),
+            [Action::Left, Action::Fire]
+        );
+
+        bindings.unbind_key(Key::J);
+        assert_eq!(bindings.keys(Action::Left).count(), 0);
+        assert_eq!(
+            bindings.keys(Action::Fire).collect::<Vec<_>>(),
+            [Key::Space]
+        );
+    }
+
+    #[test]
+    fn key_events_press_and_release_their_actions() {
+        let bindings = bindings();
+        let pressed: Vec<_> = bindings.pressed(&Input::KeyDown(Key::Space)).collect();
+        assert_eq!(pressed, [Action::Fire]);
+        assert_eq!(bindings.released(&Input::KeyDown(Key::Space)).count(), 0);
+
+        let released: Vec<_> = bindings.released(&Input::KeyUp(Key::A)).collect();
+        assert_eq!(released, [Action::Left]);
+        assert_eq!(bindings.pressed(&Input::KeyDown(Key::Z)).count(), 0);
+    }
+}
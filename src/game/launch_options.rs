@@ -0,0 +1,180 @@
+//! The command line flags every game accepts, parsed by [`Game::run`](super::Game::run)
+//! before the window and renderer are created. Each one overrides the
+//! player's [`RendererSettings`](crate::renderer::RendererSettings), the
+//! game, and the device profile. Anything else is left for the game, in
+//! [`LaunchOptions::extra_args`].
+
+use std::path::PathBuf;
+
+use ash::vk;
+
+pub const USAGE: &str = "\
+options:
+  --windowed             open a window, even where the device profile goes fullscreen
+  --fullscreen           fill the display
+  --width <pixels>       the window's width
+  --height <pixels>      the window's height
+  --render-scale <0.25-1.0>
+                         render below window resolution, and scale up
+  --gpu <name>           the graphics device whose name contains <name>, ignoring case
+  --no-egui              leave out the editor overlay
+  --present-mode <fifo|fifo-relaxed|mailbox|immediate>
+                         falls back to fifo where the surface doesn't support it
+  --settings <path>      the renderer.toml to read, instead of the game's
+  --help                 print this and exit";
+
+/// Launch options from the command line; None where a flag wasn't given
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    pub fullscreen: Option<bool>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub render_scale: Option<f32>,
+    /// part of the device name, as logged at startup
+    pub gpu: Option<String>,
+    pub no_egui: bool,
+    pub present_mode: Option<vk::PresentModeKHR>,
+    /// see [`RendererSettings`](crate::renderer::RendererSettings)
+    pub settings: Option<PathBuf>,
+    /// print [`USAGE`] instead of running
+    pub help: bool,
+    /// positional arguments and flags that aren't listed in [`USAGE`], in order
+    pub extra_args: Vec<String>,
+}
+
+impl LaunchOptions {
+    /// The options from this process's arguments
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parses arguments, without the program name; values follow their
+    /// flag either as the next argument or after an `=`.
+    /// Unknown arguments go to [`LaunchOptions::extra_args`], not errors.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| anyhow::anyhow!("{flag} needs a value"))
+            };
+
+            match flag.as_str() {
+                "--windowed" => options.fullscreen = Some(false),
+                "--fullscreen" => options.fullscreen = Some(true),
+                "--width" => options.width = Some(parse_size(&flag, &value()?)?),
+                "--height" => options.height = Some(parse_size(&flag, &value()?)?),
+                "--render-scale" => {
+                    let scale: f32 = value()?
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("--render-scale needs a number"))?;
+                    if !(0.25..=1.0).contains(&scale) {
+                        anyhow::bail!("--render-scale must be between 0.25 and 1.0, not {scale}");
+                    }
+                    options.render_scale = Some(scale);
+                }
+                "--gpu" => options.gpu = Some(value()?),
+                "--no-egui" => options.no_egui = true,
+                "--present-mode" => options.present_mode = Some(parse_present_mode(&value()?)?),
+                "--settings" => options.settings = Some(PathBuf::from(value()?)),
+                "--help" | "-h" => options.help = true,
+                _ => options.extra_args.push(arg),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_size(flag: &str, value: &str) -> anyhow::Result<u32> {
+    match value.parse::<u32>() {
+        Ok(size) if size > 0 => Ok(size),
+        _ => anyhow::bail!("{flag} needs a positive whole number of pixels, not {value}"),
+    }
+}
+
+fn parse_present_mode(value: &str) -> anyhow::Result<vk::PresentModeKHR> {
+    let mode = match value {
+        "fifo" => vk::PresentModeKHR::FIFO,
+        "fifo-relaxed" => vk::PresentModeKHR::FIFO_RELAXED,
+        "mailbox" => vk::PresentModeKHR::MAILBOX,
+        "immediate" => vk::PresentModeKHR::IMMEDIATE,
+        _ => anyhow::bail!(
+            "unknown present mode {value}; expected fifo, fifo-relaxed, mailbox, or immediate"
+        ),
+    };
+    Ok(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<LaunchOptions> {
+        LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn flags_override_only_what_they_name() {
+        assert_eq!(parse(&[]).unwrap(), LaunchOptions::default());
+
+        let options = parse(&[
+            "--fullscreen",
+            "--width",
+            "1920",
+            "--height=1080",
+            "--render-scale",
+            "0.5",
+            "--gpu",
+            "radeon",
+            "--no-egui",
+            "--present-mode=mailbox",
+        ])
+        .unwrap();
+        assert_eq!(
+            options,
+            LaunchOptions {
+                fullscreen: Some(true),
+                width: Some(1920),
+                height: Some(1080),
+                render_scale: Some(0.5),
+                gpu: Some("radeon".to_string()),
+                no_egui: true,
+                present_mode: Some(vk::PresentModeKHR::MAILBOX),
+                settings: None,
+                help: false,
+                extra_args: vec![],
+            }
+        );
+
+        // the last of --windowed and --fullscreen wins
+        let options = parse(&["--fullscreen", "--windowed"]).unwrap();
+        assert_eq!(options.fullscreen, Some(false));
+    }
+
+    #[test]
+    fn bad_arguments_are_errors() {
+        assert!(parse(&["--width"]).is_err());
+        assert!(parse(&["--width", "0"]).is_err());
+        assert!(parse(&["--height", "tall"]).is_err());
+        assert!(parse(&["--render-scale", "2"]).is_err());
+        assert!(parse(&["--present-mode", "adaptive"]).is_err());
+    }
+
+    #[test]
+    fn unknown_arguments_are_left_for_the_game() {
+        let options = parse(&["level1.map", "--windowed", "--vsync", "--seed=4"]).unwrap();
+        assert_eq!(options.fullscreen, Some(false));
+        assert_eq!(
+            options.extra_args,
+            vec!["level1.map", "--vsync", "--seed=4"]
+        );
+    }
+}
@@ -1,12 +1,28 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use ash::vk;
+#[cfg(feature = "editor")]
 use facet::Facet;
+use sdl3::gamepad::{Axis as SDLAxis, Button as SDLButton};
 use sdl3::keyboard::Scancode as SDLScancode;
 
 use crate::app::App;
-use crate::renderer::{DrawError, FrameRenderer, Renderer};
+#[cfg(feature = "editor")]
+use crate::editor::gizmo::{Gizmo, Transform};
+#[cfg(feature = "editor")]
+use crate::editor::grid::Grid;
+#[cfg(feature = "editor")]
+use crate::editor::scene_overlay::SceneOverlay;
+use crate::game::launch_options::{self, LaunchOptions};
+use crate::gamepads::Gamepads;
+use crate::renderer::settings::DEFAULT_SETTINGS_PATH;
+use crate::renderer::{
+    DEFAULT_DEPTH_FORMATS, DeviceExtensions, DrawError, FrameRenderer, HdrOutput, Renderer,
+    RendererSettings,
+};
 
-const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(15); // about 60 fps
 const DEFAULT_WINDOW_SIZE: (u32, u32) = (800, 600);
 const DEFAULT_WINDOW_TITLE: &str = "Game";
 
@@ -18,19 +34,105 @@ pub enum MaxMSAASamples {
     Max8,
     Max4,
     Max2,
+    /// no multisampling; the main pass renders straight into its resolve image
+    Max1,
+}
+
+/// How many views the main pass renders, and how they reach the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewMode {
+    #[default]
+    Mono,
+    /// Two views rendered in one pass with VK_KHR_multiview, presented
+    /// side by side (left eye on the left half of the window).
+    /// Shaders pick their per-view matrices with `SV_ViewID`;
+    /// see shaders/lib/stereo.slang.
+    StereoSideBySide,
+}
+
+impl ViewMode {
+    pub fn view_count(self) -> u32 {
+        match self {
+            ViewMode::Mono => 1,
+            ViewMode::StereoSideBySide => 2,
+        }
+    }
+
+    /// The multiview mask for the main pass; 0 disables multiview
+    pub(crate) fn view_mask(self) -> u32 {
+        match self {
+            ViewMode::Mono => 0,
+            ViewMode::StereoSideBySide => 0b11,
+        }
+    }
+}
+
+/// When the app draws frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedrawMode {
+    /// every frame, as fast as the frame delay or display allows
+    #[default]
+    Continuous,
+    /// Only after an event (input, a resize), when the editor overlay is
+    /// animating, or when the game asks with [`Renderer::request_redraw`];
+    /// the app sleeps in between, for tools that mostly sit idle.
+    /// [`Game::update`] runs only before redraws.
+    OnDemand,
+}
+
+/// How the main pass's image is fitted to the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Presentation {
+    /// Render at the render scale's fraction of the window, stretched to fill it.
+    #[default]
+    Stretch,
+    /// Render at a fixed virtual resolution, ignoring the render scale, and
+    /// fit it to the window at its own aspect ratio, centered between black
+    /// borders. Mono view mode only.
+    Virtual {
+        width: u32,
+        height: u32,
+        /// Present at the largest whole-number scale that fits the window,
+        /// with nearest filtering, so every virtual pixel is the same size;
+        /// a window smaller than the virtual resolution still scales down.
+        integer_scale: bool,
+    },
+}
+
+impl Presentation {
+    /// A virtual resolution at whole-number scales, for pixel art
+    pub fn pixel_perfect(width: u32, height: u32) -> Self {
+        Self::Virtual {
+            width,
+            height,
+            integer_scale: true,
+        }
+    }
 }
 
 /// This is the only trait from this module to implement directly.
 pub trait Game {
     /// The debug state type that will be reflected in egui.
     /// Use `()` if no debug UI is needed.
-    type EditState: for<'a> Facet<'a> + 'static;
+    type EditState: Editable;
 
     fn setup(renderer: &mut Renderer) -> anyhow::Result<Self>
     where
         Self: Sized;
 
-    fn update(&mut self) {}
+    /// Called once per frame before drawing, with the keys currently held
+    /// and the game time since the last update.
+    /// Prefer polling `keyboard` for continuous actions like movement;
+    /// edge-triggered actions are simpler with [`Game::input`].
+    ///
+    /// `dt` follows the app's debug time controls (see [`crate::time_control`]):
+    /// it's scaled in slow motion and fast forward, and updates are skipped
+    /// while paused. Timing game logic from `dt` instead of a clock keeps it in step.
+    fn update(&mut self, _keyboard: &KeyboardState, _dt: Duration) {}
+
+    /// Called after each [`Game::update`], for rumble in response to what
+    /// happened in it; see [`Gamepads::rumble`]
+    fn gamepad_feedback(&mut self, _gamepads: &mut Gamepads) {}
 
     fn draw(&mut self, renderer: FrameRenderer) -> Result<(), DrawError>;
 
@@ -53,23 +155,99 @@ pub trait Game {
         }
     }
 
-    fn frame_delay(&self) -> Duration {
-        DEFAULT_FRAME_DELAY
+    /// Override to run at a fixed frame rate, instead of the refresh rate of
+    /// the window's display. Default is None.
+    fn target_fps(&self) -> Option<f32> {
+        None
+    }
+
+    /// The minimum time between frames. Where VK_KHR_present_wait is
+    /// supported, frames also wait for the display, and then sleep out
+    /// whatever is left of this delay.
+    /// Default is a frame at [`Game::target_fps`], or None for a refresh of
+    /// the window's display, which follows the window between displays.
+    fn frame_delay(&self) -> Option<Duration> {
+        self.target_fps()
+            .filter(|&fps| fps > 0.0)
+            .map(|fps| Duration::from_secs_f32(1.0 / fps))
     }
 
     /// Override to set the render scale.
-    /// The default is based on the user's display, with larger displays getting a smaller scale.
+    /// The default comes from the device profile (see [`crate::renderer::DeviceProfile`]):
+    /// on desktops it's based on the user's display, with larger displays getting a smaller scale.
     /// Valid range: 0.25 to 1.0. Lower values improve performance at cost of image quality.
     fn render_scale() -> Option<f32> {
         None
     }
 
+    /// Override to read the player's [`RendererSettings`] from somewhere else,
+    /// or None to ignore them. Relative paths are from the working directory.
+    /// Default is `renderer.toml`.
+    fn settings_path() -> Option<PathBuf> {
+        Some(PathBuf::from(DEFAULT_SETTINGS_PATH))
+    }
+
     /// Override to limit the maximum MSAA sample count.
-    /// Default is Max8 (use best available up to 8x).
+    /// Default is Max8 (use best available up to 8x); the device profile may lower it.
     fn max_msaa_samples() -> MaxMSAASamples {
         MaxMSAASamples::default()
     }
 
+    /// Override to render the main pass once per eye.
+    /// Default is Mono.
+    fn view_mode() -> ViewMode {
+        ViewMode::default()
+    }
+
+    /// Override to render at a fixed virtual resolution, like
+    /// [`Presentation::pixel_perfect`] for pixel art.
+    /// Default is Stretch.
+    fn presentation() -> Presentation {
+        Presentation::default()
+    }
+
+    /// Override to enable extra Vulkan device extensions and features,
+    /// beyond what the renderer needs itself.
+    fn device_extensions() -> DeviceExtensions {
+        DeviceExtensions::default()
+    }
+
+    /// Override to enable `printf` in shaders, through the validation layer.
+    /// Output goes to the log at info level, and to the editor's printf console.
+    /// Debug builds only; see shaders/source/cursor_debug.slang for printing
+    /// just the pixel under the cursor. Default is false.
+    fn shader_printf() -> bool {
+        false
+    }
+
+    /// Override to change the main pass's depth formats, in order of
+    /// preference; the first the device supports is used (see
+    /// [`Renderer::depth_format`]). Default is [`DEFAULT_DEPTH_FORMATS`]:
+    /// D32, D32S8, D24S8, then D16, which every device supports.
+    fn depth_formats() -> &'static [vk::Format] {
+        &DEFAULT_DEPTH_FORMATS
+    }
+
+    /// Override to give the main pass a velocity target, for motion blur and
+    /// TAA with motion vectors; see `renderer::velocity`. Mono view mode only.
+    /// Default is false.
+    fn velocity_buffer() -> bool {
+        false
+    }
+
+    /// Override to ask for an HDR swapchain, on displays and drivers that
+    /// support one. The last pass of each frame then has to encode for it;
+    /// see `renderer::hdr`. Default is Off.
+    fn hdr_output() -> HdrOutput {
+        HdrOutput::Off
+    }
+
+    /// Override to draw only when something changes, instead of continuously.
+    /// Default is Continuous.
+    fn redraw_mode() -> RedrawMode {
+        RedrawMode::default()
+    }
+
     /// Returns the debug window name and a mutable reference to the debug state for egui rendering.
     /// Return None to disable debug UI for this frame.
     /// Default implementation returns None.
@@ -77,17 +255,62 @@ pub trait Game {
         None
     }
 
+    /// Returns a gizmo and the transforms it edits, to draw its handles for
+    /// the selected transform in the scene.
+    /// Default implementation returns None.
+    #[cfg(feature = "editor")]
+    fn editor_gizmo(&mut self) -> Option<(&mut Gizmo, &mut [Transform])> {
+        None
+    }
+
+    /// Returns a ground grid to draw in the scene, with a window to toggle it.
+    /// Default implementation returns None.
+    #[cfg(feature = "editor")]
+    fn editor_grid(&mut self) -> Option<&mut Grid> {
+        None
+    }
+
+    /// Draws egui windows directly, for debug UI whose shape is only known at
+    /// runtime and so can't be a reflected [`Game::EditState`].
+    /// Default implementation draws nothing.
+    #[cfg(feature = "editor")]
+    fn editor_windows(&mut self, _ctx: &egui::Context) {}
+
+    /// Parses the standard [`LaunchOptions`] from the command line, then
+    /// opens the window and runs the game until it quits
     fn run() -> anyhow::Result<()>
     where
         Self: Sized + 'static,
     {
         pretty_env_logger::init();
 
+        let options = LaunchOptions::from_env()?;
+        if options.help {
+            println!("{}\n\n{}", Self::window_title(), launch_options::USAGE);
+            return Ok(());
+        }
+
+        let settings_path = options.settings.clone().or_else(Self::settings_path);
+        let settings = match &settings_path {
+            Some(path) => RendererSettings::load(path).unwrap_or_else(|error| {
+                log::warn!("{error:#}; using the default settings");
+                RendererSettings::default()
+            }),
+            None => RendererSettings::default(),
+        };
+        let mut options = options;
+        options.gpu = options.gpu.or_else(|| settings.gpu.clone());
+        options.present_mode = options.present_mode.or(settings.present_mode());
+
         let sdl = sdl3::init()?;
         let video_subsystem = sdl.video()?;
         let window_desc = Self::window_description();
         let window = video_subsystem
-            .window(window_desc.title, window_desc.width, window_desc.height)
+            .window(
+                window_desc.title,
+                options.width.unwrap_or(window_desc.width),
+                options.height.unwrap_or(window_desc.height),
+            )
             .position_centered()
             .resizable()
             .hidden()
@@ -95,15 +318,32 @@ pub trait Game {
             .build()?;
         let mut startup_window = window.clone();
 
-        let enable_egui = cfg!(debug_assertions);
-        let render_scale = match Self::render_scale() {
-            Some(scale_override) => scale_override,
-            None => compute_render_scale_for_display(&window),
-        };
-        let max_msaa_samples = Self::max_msaa_samples();
-        let mut renderer = Renderer::init(window, enable_egui, render_scale, max_msaa_samples)?;
+        // a player's settings can hide the editor, but never ship it in a release build
+        let enable_egui =
+            cfg!(debug_assertions) && !options.no_egui && settings.editor.unwrap_or(true);
+        let render_scale = options
+            .render_scale
+            .or(settings.render_scale)
+            .or(Self::render_scale());
+        let mut renderer = Renderer::init(
+            window,
+            enable_egui,
+            render_scale,
+            settings.max_msaa_samples(Self::max_msaa_samples()),
+            Self::view_mode(),
+            Self::presentation(),
+            Self::device_extensions(),
+            Self::shader_printf(),
+            Self::velocity_buffer(),
+            Self::hdr_output(),
+            Self::depth_formats(),
+            &options,
+        )?;
         let game = Self::setup(&mut renderer)?;
-        let app = App::init(renderer, game)?;
+        let gamepads = Gamepads::new(sdl.gamepad()?);
+        let app = App::init(renderer, game, gamepads)?;
+        #[cfg(feature = "editor")]
+        let app = app.with_settings(settings, settings_path);
 
         if !startup_window.show() {
             log::warn!("failed to show window: {}", sdl3::get_error());
@@ -114,33 +354,33 @@ pub trait Game {
     }
 
     fn input(&mut self, _input: Input) {}
-}
-
-/// Compute render scale based on display resolution.
-/// Returns lower scale for high-resolution displays to improve performance.
-fn compute_render_scale_for_display(window: &sdl3::video::Window) -> f32 {
-    let Ok(display) = window.get_display() else {
-        return 1.0;
-    };
-    let Ok(bounds) = display.get_bounds() else {
-        return 1.0;
-    };
 
-    let pixel_count = bounds.w as u64 * bounds.h as u64;
+    /// Decides what the app does when [`Game::draw`] returns an error.
+    /// The default shows the error screen when the editor overlay is
+    /// available, and exits otherwise.
+    fn draw_error_policy(&mut self, _error: &DrawError) -> DrawErrorPolicy {
+        DrawErrorPolicy::default()
+    }
 
-    // Scale based on total pixels:
-    // - 4K+ (3840x2160 = 8.3M pixels): 0.5
-    // - 2K/1440p (2560x1440 = 3.7M pixels): 0.75
-    // - 1080p and below: 1.0
-    if pixel_count >= 8_000_000 {
-        0.5
-    } else if pixel_count >= 3_500_000 {
-        0.75
-    } else {
-        1.0
+    /// Like [`Game::input`], with the time the OS reported the event.
+    /// The default implementation discards the timestamp and calls `input`.
+    fn input_event(&mut self, event: InputEvent) {
+        self.input(event.input);
     }
 }
 
+/// The bound on [`Game::EditState`]. With the `editor` feature, the state is
+/// reflected with facet to draw its egui widgets; without it, any type will do.
+#[cfg(feature = "editor")]
+pub trait Editable: for<'a> Facet<'a> + 'static {}
+#[cfg(feature = "editor")]
+impl<T: for<'a> Facet<'a> + 'static> Editable for T {}
+
+#[cfg(not(feature = "editor"))]
+pub trait Editable: 'static {}
+#[cfg(not(feature = "editor"))]
+impl<T: 'static> Editable for T {}
+
 /// parameters passed through to SDL to create a window
 pub struct WindowDescription {
     pub title: &'static str,
@@ -148,28 +388,157 @@ pub struct WindowDescription {
     pub height: u32,
 }
 
+/// What the app does when [`Game::draw`] returns an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawErrorPolicy {
+    /// return the error from [`Game::run`]
+    Exit,
+    /// log the error and carry on with the next frame
+    SkipFrame,
+    /// Pause the game behind an egui error screen showing the error chain,
+    /// with buttons to retry, reload shaders, or quit. Exits instead when the
+    /// editor overlay isn't available, as in release builds.
+    ShowErrorScreen,
+}
+
+impl Default for DrawErrorPolicy {
+    fn default() -> Self {
+        if cfg!(feature = "editor") {
+            Self::ShowErrorScreen
+        } else {
+            Self::Exit
+        }
+    }
+}
+
 /// methods used after initialization
 /// this trait needs to be object-safe
 pub trait RuntimeGame {
-    fn update(&mut self);
+    fn update(&mut self, keyboard: &KeyboardState, dt: Duration);
+
+    fn gamepad_feedback(&mut self, gamepads: &mut Gamepads);
 
     fn draw_frame(&mut self, renderer: FrameRenderer) -> Result<(), DrawError>;
 
-    fn frame_delay(&self) -> Duration;
+    fn frame_delay(&self) -> Option<Duration>;
+
+    fn redraw_mode(&self) -> RedrawMode;
+
+    fn input(&mut self, event: InputEvent);
 
-    fn input(&mut self, input: Input);
+    fn draw_error_policy(&mut self, error: &DrawError) -> DrawErrorPolicy;
 
-    /// Draw debug UI using egui. Called by the renderer during egui pass.
-    fn draw_edit_ui(&mut self, ctx: &egui::Context);
+    /// Draw debug UI using egui. Called by the renderer during egui pass;
+    /// the grid and gizmo go into `overlay`, drawn in the frame after.
+    #[cfg(feature = "editor")]
+    fn draw_edit_ui(&mut self, ctx: &egui::Context, overlay: &mut SceneOverlay);
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+/// An [`Input`] along with when it happened
+#[derive(Debug, PartialEq, Clone)]
+pub struct InputEvent {
+    pub input: Input,
+    /// time since SDL initialization, from the same clock as `SDL_GetTicksNS`
+    pub timestamp: Duration,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Input {
     KeyUp(Key),
     KeyDown(Key),
-    MouseMotion { x: f32, y: f32 },
-    MouseDown { button: MouseButton, x: f32, y: f32 },
-    MouseUp { button: MouseButton, x: f32, y: f32 },
+    MouseMotion {
+        x: f32,
+        y: f32,
+    },
+    /// Mouse motion in pixels since the last event, with +y down; only sent in
+    /// relative mouse mode (see [`FrameRenderer::set_relative_mouse_mode`]),
+    /// after the matching `MouseMotion`
+    RelativeMouseMotion {
+        dx: f32,
+        dy: f32,
+    },
+    MouseDown {
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    },
+    MouseUp {
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    },
+    /// Touch positions are in window coordinates, like the mouse;
+    /// pressure is normalized to 0..=1
+    TouchDown {
+        finger_id: u64,
+        x: f32,
+        y: f32,
+        pressure: f32,
+    },
+    TouchUp {
+        finger_id: u64,
+        x: f32,
+        y: f32,
+        pressure: f32,
+    },
+    TouchMotion {
+        finger_id: u64,
+        x: f32,
+        y: f32,
+        pressure: f32,
+    },
+    /// Pen pressure is normalized to 0..=1, and is the most recent value
+    /// the pen reported; `eraser` is true when using the eraser end
+    PenDown {
+        pen_id: u32,
+        x: f32,
+        y: f32,
+        pressure: f32,
+        eraser: bool,
+    },
+    PenUp {
+        pen_id: u32,
+        x: f32,
+        y: f32,
+        eraser: bool,
+    },
+    PenMotion {
+        pen_id: u32,
+        x: f32,
+        y: f32,
+        pressure: f32,
+    },
+    /// A file dragged from the OS file manager was dropped on the window.
+    /// Dropping several files sends one event per file.
+    FileDropped(PathBuf),
+    /// Gamepad ids are SDL joystick instance ids, unique to each connection;
+    /// gamepads already plugged in at startup connect before the first frame
+    GamepadConnected {
+        gamepad_id: u32,
+    },
+    GamepadDisconnected {
+        gamepad_id: u32,
+    },
+    GamepadButtonDown {
+        gamepad_id: u32,
+        button: GamepadButton,
+    },
+    GamepadButtonUp {
+        gamepad_id: u32,
+        button: GamepadButton,
+    },
+    /// Sticks are normalized to -1..=1, with +y down; triggers to 0..=1
+    GamepadAxisMotion {
+        gamepad_id: u32,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+/// Whether a mouse event's `which` is SDL's stand-in for a touch or a pen,
+/// whose own events already cover it
+pub(crate) fn is_emulated_mouse(which: u32) -> bool {
+    which == sdl3::sys::touch::SDL_TOUCH_MOUSEID || which == sdl3::sys::pen::SDL_PEN_MOUSEID
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -180,71 +549,349 @@ pub enum MouseButton {
     Right,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// Gamepad buttons by position, following SDL: South is A on an Xbox or
+/// Steam Deck layout, and Cross on a PlayStation one
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    /// View on Xbox and Steam Deck, Share on PlayStation
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+impl GamepadButton {
+    pub fn from_sdl_button(button: SDLButton) -> Option<Self> {
+        match button {
+            SDLButton::South => Some(GamepadButton::South),
+            SDLButton::East => Some(GamepadButton::East),
+            SDLButton::West => Some(GamepadButton::West),
+            SDLButton::North => Some(GamepadButton::North),
+            SDLButton::Back => Some(GamepadButton::Back),
+            SDLButton::Guide => Some(GamepadButton::Guide),
+            SDLButton::Start => Some(GamepadButton::Start),
+            SDLButton::LeftStick => Some(GamepadButton::LeftStick),
+            SDLButton::RightStick => Some(GamepadButton::RightStick),
+            SDLButton::LeftShoulder => Some(GamepadButton::LeftShoulder),
+            SDLButton::RightShoulder => Some(GamepadButton::RightShoulder),
+            SDLButton::DPadUp => Some(GamepadButton::DPadUp),
+            SDLButton::DPadDown => Some(GamepadButton::DPadDown),
+            SDLButton::DPadLeft => Some(GamepadButton::DPadLeft),
+            SDLButton::DPadRight => Some(GamepadButton::DPadRight),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    pub fn from_sdl_axis(axis: SDLAxis) -> Self {
+        match axis {
+            SDLAxis::LeftX => GamepadAxis::LeftX,
+            SDLAxis::LeftY => GamepadAxis::LeftY,
+            SDLAxis::RightX => GamepadAxis::RightX,
+            SDLAxis::RightY => GamepadAxis::RightY,
+            SDLAxis::TriggerLeft => GamepadAxis::LeftTrigger,
+            SDLAxis::TriggerRight => GamepadAxis::RightTrigger,
+        }
+    }
+
+    /// SDL reports sticks in `i16::MIN..=i16::MAX` and triggers in `0..=i16::MAX`
+    pub fn normalize_sdl_value(value: i16) -> f32 {
+        (value as f32 / i16::MAX as f32).max(-1.0)
+    }
+}
+
+/// A physical key, named for where it sits on a US layout: `Key::W` is the
+/// key left of E on any layout, so WASD-style bindings stay put on AZERTY.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Key {
-    W,
+    // letters
     A,
-    S,
+    B,
+    C,
     D,
-    Q,
     E,
-    R,
     F,
-    Space,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+
+    // the number row
+    Num0,
     Num1,
     Num2,
     Num3,
     Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+
+    // function keys
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+
+    // arrows
+    Up,
+    Down,
+    Left,
+    Right,
+
+    // modifiers
+    LeftShift,
+    RightShift,
+    LeftCtrl,
+    RightCtrl,
+    LeftAlt,
+    RightAlt,
+    LeftSuper,
+    RightSuper,
+
+    // editing and navigation
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    CapsLock,
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+
+    // punctuation, by US layout position
+    Minus,
+    Equals,
+    LeftBracket,
+    RightBracket,
+    Backslash,
+    Semicolon,
+    Apostrophe,
+    Grave,
+    Comma,
+    Period,
+    Slash,
 }
 
 impl Key {
     pub fn from_sdl_scancode(scancode: SDLScancode) -> Option<Self> {
         match scancode {
-            SDLScancode::W => Some(Key::W),
             SDLScancode::A => Some(Key::A),
-            SDLScancode::S => Some(Key::S),
+            SDLScancode::B => Some(Key::B),
+            SDLScancode::C => Some(Key::C),
             SDLScancode::D => Some(Key::D),
-            SDLScancode::Q => Some(Key::Q),
             SDLScancode::E => Some(Key::E),
-            SDLScancode::R => Some(Key::R),
             SDLScancode::F => Some(Key::F),
-            SDLScancode::Space => Some(Key::Space),
+            SDLScancode::G => Some(Key::G),
+            SDLScancode::H => Some(Key::H),
+            SDLScancode::I => Some(Key::I),
+            SDLScancode::J => Some(Key::J),
+            SDLScancode::K => Some(Key::K),
+            SDLScancode::L => Some(Key::L),
+            SDLScancode::M => Some(Key::M),
+            SDLScancode::N => Some(Key::N),
+            SDLScancode::O => Some(Key::O),
+            SDLScancode::P => Some(Key::P),
+            SDLScancode::Q => Some(Key::Q),
+            SDLScancode::R => Some(Key::R),
+            SDLScancode::S => Some(Key::S),
+            SDLScancode::T => Some(Key::T),
+            SDLScancode::U => Some(Key::U),
+            SDLScancode::V => Some(Key::V),
+            SDLScancode::W => Some(Key::W),
+            SDLScancode::X => Some(Key::X),
+            SDLScancode::Y => Some(Key::Y),
+            SDLScancode::Z => Some(Key::Z),
+            SDLScancode::_0 => Some(Key::Num0),
             SDLScancode::_1 => Some(Key::Num1),
             SDLScancode::_2 => Some(Key::Num2),
             SDLScancode::_3 => Some(Key::Num3),
             SDLScancode::_4 => Some(Key::Num4),
+            SDLScancode::_5 => Some(Key::Num5),
+            SDLScancode::_6 => Some(Key::Num6),
+            SDLScancode::_7 => Some(Key::Num7),
+            SDLScancode::_8 => Some(Key::Num8),
+            SDLScancode::_9 => Some(Key::Num9),
+            SDLScancode::F1 => Some(Key::F1),
+            SDLScancode::F2 => Some(Key::F2),
+            SDLScancode::F3 => Some(Key::F3),
+            SDLScancode::F4 => Some(Key::F4),
+            SDLScancode::F5 => Some(Key::F5),
+            SDLScancode::F6 => Some(Key::F6),
+            SDLScancode::F7 => Some(Key::F7),
+            SDLScancode::F8 => Some(Key::F8),
+            SDLScancode::F9 => Some(Key::F9),
+            SDLScancode::F10 => Some(Key::F10),
+            SDLScancode::F11 => Some(Key::F11),
+            SDLScancode::F12 => Some(Key::F12),
+            SDLScancode::Up => Some(Key::Up),
+            SDLScancode::Down => Some(Key::Down),
+            SDLScancode::Left => Some(Key::Left),
+            SDLScancode::Right => Some(Key::Right),
+            SDLScancode::LShift => Some(Key::LeftShift),
+            SDLScancode::RShift => Some(Key::RightShift),
+            SDLScancode::LCtrl => Some(Key::LeftCtrl),
+            SDLScancode::RCtrl => Some(Key::RightCtrl),
+            SDLScancode::LAlt => Some(Key::LeftAlt),
+            SDLScancode::RAlt => Some(Key::RightAlt),
+            SDLScancode::LGui => Some(Key::LeftSuper),
+            SDLScancode::RGui => Some(Key::RightSuper),
+            SDLScancode::Space => Some(Key::Space),
+            SDLScancode::Return => Some(Key::Enter),
+            SDLScancode::Escape => Some(Key::Escape),
+            SDLScancode::Tab => Some(Key::Tab),
+            SDLScancode::Backspace => Some(Key::Backspace),
+            SDLScancode::CapsLock => Some(Key::CapsLock),
+            SDLScancode::Insert => Some(Key::Insert),
+            SDLScancode::Delete => Some(Key::Delete),
+            SDLScancode::Home => Some(Key::Home),
+            SDLScancode::End => Some(Key::End),
+            SDLScancode::PageUp => Some(Key::PageUp),
+            SDLScancode::PageDown => Some(Key::PageDown),
+            SDLScancode::Minus => Some(Key::Minus),
+            SDLScancode::Equals => Some(Key::Equals),
+            SDLScancode::LeftBracket => Some(Key::LeftBracket),
+            SDLScancode::RightBracket => Some(Key::RightBracket),
+            SDLScancode::Backslash => Some(Key::Backslash),
+            SDLScancode::Semicolon => Some(Key::Semicolon),
+            SDLScancode::Apostrophe => Some(Key::Apostrophe),
+            SDLScancode::Grave => Some(Key::Grave),
+            SDLScancode::Comma => Some(Key::Comma),
+            SDLScancode::Period => Some(Key::Period),
+            SDLScancode::Slash => Some(Key::Slash),
             _ => None,
         }
     }
 }
 
+/// The set of keys currently held down, as of the last processed event
+#[derive(Debug, Default, Clone)]
+pub struct KeyboardState {
+    held: HashSet<Key>,
+}
+
+impl KeyboardState {
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.held.contains(&key)
+    }
+
+    pub(crate) fn press(&mut self, key: Key) {
+        self.held.insert(key);
+    }
+
+    pub(crate) fn release(&mut self, key: Key) {
+        self.held.remove(&key);
+    }
+
+    /// forget all held keys, eg when the window loses focus and
+    /// key up events may never arrive
+    pub(crate) fn clear(&mut self) {
+        self.held.clear();
+    }
+}
+
 impl<G> RuntimeGame for G
 where
     G: Game,
 {
-    fn update(&mut self) {
-        self.update()
+    fn update(&mut self, keyboard: &KeyboardState, dt: Duration) {
+        self.update(keyboard, dt)
+    }
+
+    fn gamepad_feedback(&mut self, gamepads: &mut Gamepads) {
+        self.gamepad_feedback(gamepads)
     }
 
     fn draw_frame(&mut self, renderer: FrameRenderer) -> Result<(), DrawError> {
         self.draw(renderer)
     }
 
-    fn frame_delay(&self) -> Duration {
+    fn frame_delay(&self) -> Option<Duration> {
         self.frame_delay()
     }
 
-    fn input(&mut self, input: Input) {
-        self.input(input);
+    fn redraw_mode(&self) -> RedrawMode {
+        G::redraw_mode()
     }
 
-    fn draw_edit_ui(&mut self, ctx: &egui::Context) {
-        let Some((window_name, debug_state)) = Game::editor_ui(self) else {
-            return;
-        };
+    fn input(&mut self, event: InputEvent) {
+        self.input_event(event);
+    }
+
+    fn draw_error_policy(&mut self, error: &DrawError) -> DrawErrorPolicy {
+        self.draw_error_policy(error)
+    }
+
+    #[cfg(feature = "editor")]
+    fn draw_edit_ui(&mut self, ctx: &egui::Context, overlay: &mut SceneOverlay) {
+        if let Some((window_name, debug_state)) = Game::editor_ui(self) {
+            egui::Window::new(window_name).show(ctx, |ui| {
+                crate::renderer::facet_egui::render_facet_ui(ui, debug_state);
+            });
+        }
+
+        if let Some(grid) = Game::editor_grid(self) {
+            egui::Window::new("Overlays")
+                .default_open(false)
+                .show(ctx, |ui| grid.ui(ui));
+            grid.show(overlay);
+        }
+
+        if let Some((gizmo, transforms)) = Game::editor_gizmo(self) {
+            gizmo.show(ctx, transforms, &mut overlay.lines);
+        }
 
-        egui::Window::new(window_name).show(ctx, |ui| {
-            crate::renderer::facet_egui::render_facet_ui(ui, debug_state);
-        });
+        Game::editor_windows(self, ctx);
     }
 }
@@ -0,0 +1,93 @@
+//! The gamepads SDL has connected.
+//!
+//! SDL only sends a gamepad's button and axis events while it's open, so the
+//! app opens each one as it connects (including those plugged in at startup)
+//! and closes it on disconnect.
+//!
+//! Games rumble them from [`Game::gamepad_feedback`](crate::game::Game::gamepad_feedback),
+//! which runs after each update.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use sdl3::GamepadSubsystem;
+use sdl3::gamepad::Gamepad;
+
+pub struct Gamepads {
+    subsystem: GamepadSubsystem,
+    /// by SDL joystick instance id, the `gamepad_id` of gamepad inputs
+    open: HashMap<u32, Gamepad>,
+}
+
+impl Gamepads {
+    pub fn new(subsystem: GamepadSubsystem) -> Self {
+        Self {
+            subsystem,
+            open: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn connect(&mut self, gamepad_id: u32) {
+        match self.subsystem.open(gamepad_id) {
+            Ok(gamepad) => {
+                self.open.insert(gamepad_id, gamepad);
+            }
+            Err(error) => log::warn!("failed to open gamepad {gamepad_id}: {error}"),
+        }
+    }
+
+    pub(crate) fn disconnect(&mut self, gamepad_id: u32) {
+        self.open.remove(&gamepad_id);
+    }
+
+    /// the ids of the connected gamepads, in no particular order
+    pub fn connected(&self) -> impl Iterator<Item = u32> + '_ {
+        self.open.keys().copied()
+    }
+
+    /// Rumble a gamepad's low and high frequency motors at intensities from
+    /// 0 to 1, replacing any rumble in progress; zeros stop it. Returns false
+    /// if the gamepad isn't connected or can't rumble.
+    pub fn rumble(&mut self, gamepad_id: u32, low: f32, high: f32, duration: Duration) -> bool {
+        let Some(gamepad) = self.open.get_mut(&gamepad_id) else {
+            return false;
+        };
+
+        let duration_ms = duration.as_millis().min(u32::MAX as u128) as u32;
+        match gamepad.set_rumble(rumble_intensity(low), rumble_intensity(high), duration_ms) {
+            Ok(()) => true,
+            Err(error) => {
+                log::debug!("gamepad {gamepad_id} can't rumble: {error}");
+                false
+            }
+        }
+    }
+
+    /// Stop every gamepad's rumble, eg when the game pauses
+    pub fn stop_rumble(&mut self) {
+        let gamepad_ids: Vec<u32> = self.connected().collect();
+        for gamepad_id in gamepad_ids {
+            self.rumble(gamepad_id, 0.0, 0.0, Duration::ZERO);
+        }
+    }
+}
+
+/// SDL's motor intensity for one from 0 to 1
+fn rumble_intensity(intensity: f32) -> u16 {
+    (intensity.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rumble_intensity_is_clamped_to_the_motor_range() {
+        assert_eq!(rumble_intensity(0.0), 0);
+        assert_eq!(rumble_intensity(0.5), 32768);
+        assert_eq!(rumble_intensity(1.0), u16::MAX);
+        assert_eq!(rumble_intensity(-1.0), 0);
+        assert_eq!(rumble_intensity(2.0), u16::MAX);
+        assert_eq!(rumble_intensity(f32::NAN), 0);
+    }
+}
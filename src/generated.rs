@@ -1 +1,2 @@
 pub mod shader_atlas;
+pub mod sprite_atlas;
@@ -1,9 +1,13 @@
 pub mod basic_triangle;
+pub mod color_grade;
+pub mod debug_lines;
 pub mod depth_texture;
 pub mod dragon;
+pub mod fullscreen_blit;
 pub mod gpu_picking;
 pub mod gpu_picking_common;
 pub mod gpu_picking_id;
+pub mod grid;
 pub mod koch_curve;
 pub mod multi_mesh;
 pub mod mvp;
@@ -15,10 +19,13 @@ pub mod particles_compute;
 pub mod projection;
 pub mod ray_march_camera;
 pub mod ray_marching;
+pub mod rcas_upscale;
 pub mod sdf_2d;
+pub mod sdf_sprite_batch;
 pub mod serenity_crt;
 pub mod space_invaders;
 pub mod sprite_batch;
+pub mod sprite_batch_common;
 pub mod suzanne;
 pub mod wc_advect_and_transfer_pigment_compute;
 pub mod wc_capillary_flow_compute;
@@ -43,6 +50,12 @@ pub struct ShaderAtlas {
     pub ray_marching: ray_marching::Shader,
     pub depth_texture: depth_texture::Shader,
     pub sprite_batch: sprite_batch::Shader,
+    pub sdf_sprite_batch: sdf_sprite_batch::Shader,
+    pub debug_lines: debug_lines::Shader,
+    pub grid: grid::Shader,
+    pub fullscreen_blit: fullscreen_blit::Shader,
+    pub color_grade: color_grade::Shader,
+    pub rcas_upscale: rcas_upscale::Shader,
     pub paint_display: paint_display::Shader,
     pub gpu_picking_id: gpu_picking_id::Shader,
     pub wc_update_velocity_compute: wc_update_velocity_compute::Shader,
@@ -73,6 +86,12 @@ impl ShaderAtlas {
             ray_marching: ray_marching::Shader::init(),
             depth_texture: depth_texture::Shader::init(),
             sprite_batch: sprite_batch::Shader::init(),
+            sdf_sprite_batch: sdf_sprite_batch::Shader::init(),
+            debug_lines: debug_lines::Shader::init(),
+            grid: grid::Shader::init(),
+            fullscreen_blit: fullscreen_blit::Shader::init(),
+            color_grade: color_grade::Shader::init(),
+            rcas_upscale: rcas_upscale::Shader::init(),
             paint_display: paint_display::Shader::init(),
             gpu_picking_id: gpu_picking_id::Shader::init(),
             wc_update_velocity_compute: wc_update_velocity_compute::Shader::init(),
@@ -12,6 +12,8 @@ use serde::Serialize;
 pub use super::mvp::MVPMatrices;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -20,10 +22,11 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Vertex {
     pub position: glam::Vec3,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
     pub color: glam::Vec3,
 }
 
@@ -32,6 +35,7 @@ impl GPUWrite for Vertex {}
 pub struct Resources<'a> {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// slang parameter block `matrices`: set 0, binding 0
     pub matrices_buffer: &'a UniformBufferHandle<MVPMatrices>,
 }
 
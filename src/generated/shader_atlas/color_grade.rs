@@ -0,0 +1,215 @@
+// GENERATED FILE (do not edit directly)
+
+//! generated from slang shader: color_grade.shader.slang
+
+use std::ffi::CString;
+use std::io::Cursor;
+
+use ash::util::read_spv;
+use ash::vk;
+use serde::Serialize;
+
+use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
+use crate::renderer::vertex_description::{NoVertex, VertexDescription};
+use crate::renderer::*;
+use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
+use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
+
+// glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
+const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
+
+/// slang `ColorGradeParams`: size 48 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
+#[repr(C, align(16))]
+pub struct ColorGradeParams {
+    /// slang `domainMin`: offset 0, size 12
+    pub domain_min: glam::Vec3,
+    /// slang `size`: offset 12, size 4
+    pub size: u32,
+    /// slang `domainMax`: offset 16, size 12
+    pub domain_max: glam::Vec3,
+    /// slang `threeD`: offset 28, size 4
+    pub three_d: u32,
+    /// slang `strength`: offset 32, size 4
+    pub strength: f32,
+    /// slang `srgbEncoded`: offset 36, size 4
+    pub srgb_encoded: u32,
+    pub _padding_0: [u8; 8],
+}
+
+impl GPUWrite for ColorGradeParams {}
+const _: () = assert!(std::mem::size_of::<ColorGradeParams>() == 48);
+const _: () = assert!(std::mem::offset_of!(ColorGradeParams, domain_min) == 0);
+const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
+const _: () = assert!(std::mem::offset_of!(ColorGradeParams, size) == 12);
+const _: () = assert!(std::mem::size_of::<u32>() == 4);
+const _: () = assert!(std::mem::offset_of!(ColorGradeParams, domain_max) == 16);
+const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
+const _: () = assert!(std::mem::offset_of!(ColorGradeParams, three_d) == 28);
+const _: () = assert!(std::mem::size_of::<u32>() == 4);
+const _: () = assert!(std::mem::offset_of!(ColorGradeParams, strength) == 32);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(ColorGradeParams, srgb_encoded) == 36);
+const _: () = assert!(std::mem::size_of::<u32>() == 4);
+
+impl ColorGradeParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for ColorGradeParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            domain_min: SampleValue::sample_at(word),
+            size: SampleValue::sample_at(word + 3),
+            domain_max: SampleValue::sample_at(word + 4),
+            three_d: SampleValue::sample_at(word + 7),
+            strength: SampleValue::sample_at(word + 8),
+            srgb_encoded: SampleValue::sample_at(word + 9),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
+pub struct Resources<'a> {
+    /// slang `scene`: set 0, binding 1
+    pub scene: &'a TextureHandle,
+    /// slang `lut`: set 0, binding 2
+    pub lut: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
+    pub params_buffer: &'a UniformBufferHandle<ColorGradeParams>,
+}
+
+pub struct Shader {
+    pub reflection_json: ReflectionJson,
+}
+
+impl Shader {
+    pub fn init() -> Self {
+        let json_str = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/color_grade.json"
+        ));
+
+        let reflection_json: ReflectionJson = serde_json::from_str(json_str).unwrap();
+
+        Self { reflection_json }
+    }
+
+    pub fn pipeline_config(
+        self,
+        resources: Resources<'_>,
+    ) -> PipelineConfig<'_, NoVertex, DrawVertexCount> {
+        // NOTE each of these must be in descriptor set layout order in the reflection json
+
+        #[rustfmt::skip]
+        let texture_handles = vec![
+            resources.scene,
+            resources.lut,
+        ];
+
+        #[rustfmt::skip]
+        let uniform_buffer_handles = vec![
+            RawUniformBufferHandle::from_typed(resources.params_buffer),
+        ];
+
+        #[rustfmt::skip]
+        let storage_texture_handles = vec![
+        ];
+
+        let vertex_config = VertexConfig::VertexCount;
+
+        PipelineConfigBuilder {
+            shader: Box::new(self),
+            vertex_config,
+            texture_handles,
+            uniform_buffer_handles,
+            storage_texture_handles,
+            disable_depth_test: false,
+        }
+        .build()
+    }
+
+    fn vert_entry_point_name(&self) -> CString {
+        let entry_point = self
+            .reflection_json
+            .vertex_entry_point
+            .entry_point_name
+            .clone();
+
+        CString::new(entry_point).unwrap()
+    }
+
+    fn frag_entry_point_name(&self) -> CString {
+        let entry_point = self
+            .reflection_json
+            .fragment_entry_point
+            .entry_point_name
+            .clone();
+
+        CString::new(entry_point).unwrap()
+    }
+
+    fn vert_spv(&self) -> Vec<u32> {
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/color_grade.vert.spv"
+        ));
+        let byte_reader = &mut Cursor::new(bytes);
+        read_spv(byte_reader).expect("failed to convert spv byte layout")
+    }
+
+    fn frag_spv(&self) -> Vec<u32> {
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/color_grade.frag.spv"
+        ));
+        let byte_reader = &mut Cursor::new(bytes);
+        read_spv(byte_reader).expect("failed to convert spv byte layout")
+    }
+}
+
+impl ShaderAtlasEntry for Shader {
+    fn source_file_name(&self) -> &str {
+        &self.reflection_json.source_file_name
+    }
+
+    fn vertex_binding_descriptions(&self) -> Vec<vk::VertexInputBindingDescription> {
+        vec![]
+    }
+
+    fn vertex_attribute_descriptions(&self) -> Vec<vk::VertexInputAttributeDescription> {
+        vec![]
+    }
+
+    fn layout_bindings(&self) -> Vec<Vec<LayoutDescription>> {
+        self.reflection_json.layout_bindings()
+    }
+
+    fn precompiled_shaders(&self) -> PrecompiledShaders {
+        let vert = PrecompiledShader {
+            entry_point_name: self.vert_entry_point_name(),
+            spv_bytes: self.vert_spv(),
+        };
+
+        let frag = PrecompiledShader {
+            entry_point_name: self.frag_entry_point_name(),
+            spv_bytes: self.frag_spv(),
+        };
+
+        PrecompiledShaders { vert, frag }
+    }
+
+    fn pipeline_layout(&self) -> &ReflectedPipelineLayout {
+        &self.reflection_json.pipeline_layout
+    }
+
+    fn reflection_json(&self) -> &ReflectionJson {
+        &self.reflection_json
+    }
+}
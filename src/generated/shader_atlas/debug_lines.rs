@@ -0,0 +1,242 @@
+// GENERATED FILE (do not edit directly)
+
+//! generated from slang shader: debug_lines.shader.slang
+
+use std::ffi::CString;
+use std::io::Cursor;
+
+use ash::util::read_spv;
+use ash::vk;
+use serde::Serialize;
+
+pub use super::projection::Projection;
+use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
+use crate::renderer::vertex_description::{NoVertex, VertexDescription};
+use crate::renderer::*;
+use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
+use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
+
+// glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
+const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
+
+/// slang `DebugLinesParams`: size 80 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
+#[repr(C, align(16))]
+pub struct DebugLinesParams {
+    /// slang `lines`: offset 0, size 8
+    pub lines: ImmutableAddr<DebugLine>,
+    /// slang `pixelSize`: offset 8, size 8
+    pub pixel_size: glam::Vec2,
+    /// slang `projection`: offset 16, size 64
+    pub projection: Projection,
+}
+
+impl GPUWrite for DebugLinesParams {}
+const _: () = assert!(std::mem::size_of::<DebugLinesParams>() == 80);
+const _: () = assert!(std::mem::offset_of!(DebugLinesParams, lines) == 0);
+const _: () = assert!(std::mem::size_of::<ImmutableAddr<DebugLine>>() == 8);
+const _: () = assert!(std::mem::offset_of!(DebugLinesParams, pixel_size) == 8);
+const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
+const _: () = assert!(std::mem::offset_of!(DebugLinesParams, projection) == 16);
+const _: () = assert!(std::mem::size_of::<Projection>() == 64);
+
+impl DebugLinesParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for DebugLinesParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            lines: SampleValue::sample_at(word),
+            pixel_size: SampleValue::sample_at(word + 2),
+            projection: SampleValue::sample_at(word + 4),
+        }
+    }
+}
+
+/// slang `DebugLine`: size 48 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
+#[repr(C, align(16))]
+pub struct DebugLine {
+    /// slang `start`: offset 0, size 12
+    pub start: glam::Vec3,
+    /// slang `width`: offset 12, size 4
+    pub width: f32,
+    /// slang `end`: offset 16, size 12
+    pub end: glam::Vec3,
+    /// slang `padding`: offset 28, size 4
+    pub padding: f32,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `color`: offset 32, size 16
+    pub color: glam::Vec4,
+}
+
+impl GPUWrite for DebugLine {}
+const _: () = assert!(std::mem::size_of::<DebugLine>() == 48);
+const _: () = assert!(std::mem::offset_of!(DebugLine, start) == 0);
+const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
+const _: () = assert!(std::mem::offset_of!(DebugLine, width) == 12);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(DebugLine, end) == 16);
+const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
+const _: () = assert!(std::mem::offset_of!(DebugLine, padding) == 28);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(DebugLine, color) == 32);
+const _: () = assert!(std::mem::size_of::<glam::Vec4>() == 16);
+
+impl DebugLine {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for DebugLine {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            start: SampleValue::sample_at(word),
+            width: SampleValue::sample_at(word + 3),
+            end: SampleValue::sample_at(word + 4),
+            padding: SampleValue::sample_at(word + 7),
+            color: SampleValue::sample_at(word + 8),
+        }
+    }
+}
+
+pub struct Resources<'a> {
+    /// slang parameter block `params`: set 0, binding 0
+    pub params_buffer: &'a UniformBufferHandle<DebugLinesParams>,
+}
+
+pub struct Shader {
+    pub reflection_json: ReflectionJson,
+}
+
+impl Shader {
+    pub fn init() -> Self {
+        let json_str = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/debug_lines.json"
+        ));
+
+        let reflection_json: ReflectionJson = serde_json::from_str(json_str).unwrap();
+
+        Self { reflection_json }
+    }
+
+    pub fn pipeline_config(
+        self,
+        resources: Resources<'_>,
+    ) -> PipelineConfig<'_, NoVertex, DrawVertexCount> {
+        // NOTE each of these must be in descriptor set layout order in the reflection json
+
+        #[rustfmt::skip]
+        let texture_handles = vec![
+        ];
+
+        #[rustfmt::skip]
+        let uniform_buffer_handles = vec![
+            RawUniformBufferHandle::from_typed(resources.params_buffer),
+        ];
+
+        #[rustfmt::skip]
+        let storage_texture_handles = vec![
+        ];
+
+        let vertex_config = VertexConfig::VertexCount;
+
+        PipelineConfigBuilder {
+            shader: Box::new(self),
+            vertex_config,
+            texture_handles,
+            uniform_buffer_handles,
+            storage_texture_handles,
+            disable_depth_test: false,
+        }
+        .build()
+    }
+
+    fn vert_entry_point_name(&self) -> CString {
+        let entry_point = self
+            .reflection_json
+            .vertex_entry_point
+            .entry_point_name
+            .clone();
+
+        CString::new(entry_point).unwrap()
+    }
+
+    fn frag_entry_point_name(&self) -> CString {
+        let entry_point = self
+            .reflection_json
+            .fragment_entry_point
+            .entry_point_name
+            .clone();
+
+        CString::new(entry_point).unwrap()
+    }
+
+    fn vert_spv(&self) -> Vec<u32> {
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/debug_lines.vert.spv"
+        ));
+        let byte_reader = &mut Cursor::new(bytes);
+        read_spv(byte_reader).expect("failed to convert spv byte layout")
+    }
+
+    fn frag_spv(&self) -> Vec<u32> {
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/debug_lines.frag.spv"
+        ));
+        let byte_reader = &mut Cursor::new(bytes);
+        read_spv(byte_reader).expect("failed to convert spv byte layout")
+    }
+}
+
+impl ShaderAtlasEntry for Shader {
+    fn source_file_name(&self) -> &str {
+        &self.reflection_json.source_file_name
+    }
+
+    fn vertex_binding_descriptions(&self) -> Vec<vk::VertexInputBindingDescription> {
+        vec![]
+    }
+
+    fn vertex_attribute_descriptions(&self) -> Vec<vk::VertexInputAttributeDescription> {
+        vec![]
+    }
+
+    fn layout_bindings(&self) -> Vec<Vec<LayoutDescription>> {
+        self.reflection_json.layout_bindings()
+    }
+
+    fn precompiled_shaders(&self) -> PrecompiledShaders {
+        let vert = PrecompiledShader {
+            entry_point_name: self.vert_entry_point_name(),
+            spv_bytes: self.vert_spv(),
+        };
+
+        let frag = PrecompiledShader {
+            entry_point_name: self.frag_entry_point_name(),
+            spv_bytes: self.frag_spv(),
+        };
+
+        PrecompiledShaders { vert, frag }
+    }
+
+    fn pipeline_layout(&self) -> &ReflectedPipelineLayout {
+        &self.reflection_json.pipeline_layout
+    }
+
+    fn reflection_json(&self) -> &ReflectionJson {
+        &self.reflection_json
+    }
+}
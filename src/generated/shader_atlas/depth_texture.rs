@@ -12,6 +12,8 @@ use serde::Serialize;
 pub use super::mvp::MVPMatrices;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -20,9 +22,11 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `DepthTextureParams`: size 192 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct DepthTextureParams {
+    /// slang `mvp`: offset 0, size 192
     pub mvp: MVPMatrices,
 }
 
@@ -31,10 +35,26 @@ const _: () = assert!(std::mem::size_of::<DepthTextureParams>() == 192);
 const _: () = assert!(std::mem::offset_of!(DepthTextureParams, mvp) == 0);
 const _: () = assert!(std::mem::size_of::<MVPMatrices>() == 192);
 
-#[derive(Debug, Clone, Serialize)]
+impl DepthTextureParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for DepthTextureParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            mvp: SampleValue::sample_at(word),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Vertex {
     pub position: glam::Vec3,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
     pub color: glam::Vec3,
     pub tex_coord: glam::Vec2,
 }
@@ -44,7 +64,9 @@ impl GPUWrite for Vertex {}
 pub struct Resources<'a> {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// slang `texture`: set 0, binding 1
     pub texture: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<DepthTextureParams>,
 }
 
@@ -0,0 +1,187 @@
+// GENERATED FILE (do not edit directly)
+
+//! generated from slang shader: fullscreen_blit.shader.slang
+
+use std::ffi::CString;
+use std::io::Cursor;
+
+use ash::util::read_spv;
+use ash::vk;
+use serde::Serialize;
+
+use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
+use crate::renderer::vertex_description::{NoVertex, VertexDescription};
+use crate::renderer::*;
+use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
+use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
+
+// glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
+const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
+
+/// slang `FullscreenBlitParams`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
+#[repr(C, align(16))]
+pub struct FullscreenBlitParams {
+    /// slang `flags`: offset 0, size 4
+    pub flags: u32,
+    pub _padding_0: [u8; 12],
+}
+
+impl GPUWrite for FullscreenBlitParams {}
+const _: () = assert!(std::mem::size_of::<FullscreenBlitParams>() == 16);
+const _: () = assert!(std::mem::offset_of!(FullscreenBlitParams, flags) == 0);
+const _: () = assert!(std::mem::size_of::<u32>() == 4);
+
+impl FullscreenBlitParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for FullscreenBlitParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            flags: SampleValue::sample_at(word),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
+pub struct Resources<'a> {
+    /// slang `source`: set 0, binding 1
+    pub source: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
+    pub params_buffer: &'a UniformBufferHandle<FullscreenBlitParams>,
+}
+
+pub struct Shader {
+    pub reflection_json: ReflectionJson,
+}
+
+impl Shader {
+    pub fn init() -> Self {
+        let json_str = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/fullscreen_blit.json"
+        ));
+
+        let reflection_json: ReflectionJson = serde_json::from_str(json_str).unwrap();
+
+        Self { reflection_json }
+    }
+
+    pub fn pipeline_config(
+        self,
+        resources: Resources<'_>,
+    ) -> PipelineConfig<'_, NoVertex, DrawVertexCount> {
+        // NOTE each of these must be in descriptor set layout order in the reflection json
+
+        #[rustfmt::skip]
+        let texture_handles = vec![
+            resources.source,
+        ];
+
+        #[rustfmt::skip]
+        let uniform_buffer_handles = vec![
+            RawUniformBufferHandle::from_typed(resources.params_buffer),
+        ];
+
+        #[rustfmt::skip]
+        let storage_texture_handles = vec![
+        ];
+
+        let vertex_config = VertexConfig::VertexCount;
+
+        PipelineConfigBuilder {
+            shader: Box::new(self),
+            vertex_config,
+            texture_handles,
+            uniform_buffer_handles,
+            storage_texture_handles,
+            disable_depth_test: false,
+        }
+        .build()
+    }
+
+    fn vert_entry_point_name(&self) -> CString {
+        let entry_point = self
+            .reflection_json
+            .vertex_entry_point
+            .entry_point_name
+            .clone();
+
+        CString::new(entry_point).unwrap()
+    }
+
+    fn frag_entry_point_name(&self) -> CString {
+        let entry_point = self
+            .reflection_json
+            .fragment_entry_point
+            .entry_point_name
+            .clone();
+
+        CString::new(entry_point).unwrap()
+    }
+
+    fn vert_spv(&self) -> Vec<u32> {
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/fullscreen_blit.vert.spv"
+        ));
+        let byte_reader = &mut Cursor::new(bytes);
+        read_spv(byte_reader).expect("failed to convert spv byte layout")
+    }
+
+    fn frag_spv(&self) -> Vec<u32> {
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/fullscreen_blit.frag.spv"
+        ));
+        let byte_reader = &mut Cursor::new(bytes);
+        read_spv(byte_reader).expect("failed to convert spv byte layout")
+    }
+}
+
+impl ShaderAtlasEntry for Shader {
+    fn source_file_name(&self) -> &str {
+        &self.reflection_json.source_file_name
+    }
+
+    fn vertex_binding_descriptions(&self) -> Vec<vk::VertexInputBindingDescription> {
+        vec![]
+    }
+
+    fn vertex_attribute_descriptions(&self) -> Vec<vk::VertexInputAttributeDescription> {
+        vec![]
+    }
+
+    fn layout_bindings(&self) -> Vec<Vec<LayoutDescription>> {
+        self.reflection_json.layout_bindings()
+    }
+
+    fn precompiled_shaders(&self) -> PrecompiledShaders {
+        let vert = PrecompiledShader {
+            entry_point_name: self.vert_entry_point_name(),
+            spv_bytes: self.vert_spv(),
+        };
+
+        let frag = PrecompiledShader {
+            entry_point_name: self.frag_entry_point_name(),
+            spv_bytes: self.frag_spv(),
+        };
+
+        PrecompiledShaders { vert, frag }
+    }
+
+    fn pipeline_layout(&self) -> &ReflectedPipelineLayout {
+        &self.reflection_json.pipeline_layout
+    }
+
+    fn reflection_json(&self) -> &ReflectionJson {
+        &self.reflection_json
+    }
+}
@@ -5,15 +5,20 @@
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Cube`: size 32 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Cube {
+    /// slang `position`: offset 0, size 12
     pub position: glam::Vec3,
     pub _padding_0: [u8; 4],
+    /// slang `radii`: offset 16, size 12
     pub radii: glam::Vec3,
     pub _padding_1: [u8; 4],
 }
@@ -24,3 +29,21 @@ const _: () = assert!(std::mem::offset_of!(Cube, position) == 0);
 const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
 const _: () = assert!(std::mem::offset_of!(Cube, radii) == 16);
 const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
+
+impl Cube {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Cube {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            position: SampleValue::sample_at(word),
+            _padding_0: Default::default(),
+            radii: SampleValue::sample_at(word + 4),
+            _padding_1: Default::default(),
+        }
+    }
+}
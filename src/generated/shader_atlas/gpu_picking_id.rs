@@ -13,6 +13,8 @@ pub use super::gpu_picking_common::Cube;
 pub use super::ray_march_camera::RayMarchCamera;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -21,12 +23,16 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `GpuPickingIdParams`: size 96 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct GpuPickingIdParams {
+    /// slang `camera`: offset 0, size 80
     pub camera: RayMarchCamera,
+    /// slang `cubeCount`: offset 80, size 4
     pub cube_count: u32,
     pub _padding_0: [u8; 4],
+    /// slang `cubes`: offset 88, size 8
     pub cubes: ReadAddr<Cube>,
 }
 
@@ -39,7 +45,26 @@ const _: () = assert!(std::mem::size_of::<u32>() == 4);
 const _: () = assert!(std::mem::offset_of!(GpuPickingIdParams, cubes) == 88);
 const _: () = assert!(std::mem::size_of::<ReadAddr<Cube>>() == 8);
 
+impl GpuPickingIdParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for GpuPickingIdParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            camera: SampleValue::sample_at(word),
+            cube_count: SampleValue::sample_at(word + 20),
+            _padding_0: Default::default(),
+            cubes: SampleValue::sample_at(word + 22),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<GpuPickingIdParams>,
 }
 
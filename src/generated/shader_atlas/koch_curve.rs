@@ -11,6 +11,8 @@ use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -19,16 +21,25 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `KochCurveParams`: size 48 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct KochCurveParams {
+    /// slang `resolution`: offset 0, size 8
     pub resolution: glam::Vec2,
+    /// slang `mouse`: offset 8, size 8
     pub mouse: glam::Vec2,
+    /// slang `time`: offset 16, size 4
     pub time: f32,
+    /// slang `koch_iterations`: offset 20, size 4
     pub koch_iterations: f32,
+    /// slang `scale_factor`: offset 24, size 4
     pub scale_factor: f32,
+    /// slang `sphere_radius`: offset 28, size 4
     pub sphere_radius: f32,
+    /// slang `sphere_blend`: offset 32, size 4
     pub sphere_blend: f32,
+    /// slang `rotation_speed`: offset 36, size 4
     pub rotation_speed: f32,
     pub _padding_0: [u8; 8],
 }
@@ -52,8 +63,33 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(KochCurveParams, rotation_speed) == 36);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
+impl KochCurveParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for KochCurveParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            resolution: SampleValue::sample_at(word),
+            mouse: SampleValue::sample_at(word + 2),
+            time: SampleValue::sample_at(word + 4),
+            koch_iterations: SampleValue::sample_at(word + 5),
+            scale_factor: SampleValue::sample_at(word + 6),
+            sphere_radius: SampleValue::sample_at(word + 7),
+            sphere_blend: SampleValue::sample_at(word + 8),
+            rotation_speed: SampleValue::sample_at(word + 9),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `cubeMap`: set 0, binding 1
     pub cube_map: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<KochCurveParams>,
 }
 
@@ -12,6 +12,8 @@ use serde::Serialize;
 pub use super::mvp::MVPMatrices;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -20,10 +22,14 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `MultiMeshParams`: size 208 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct MultiMeshParams {
+    /// slang `mvp`: offset 0, size 192
     pub mvp: MVPMatrices,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `tint`: offset 192, size 16
     pub tint: glam::Vec4,
 }
 
@@ -34,7 +40,23 @@ const _: () = assert!(std::mem::size_of::<MVPMatrices>() == 192);
 const _: () = assert!(std::mem::offset_of!(MultiMeshParams, tint) == 192);
 const _: () = assert!(std::mem::size_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+impl MultiMeshParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for MultiMeshParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            mvp: SampleValue::sample_at(word),
+            tint: SampleValue::sample_at(word + 48),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Vertex {
     pub position: glam::Vec3,
@@ -47,7 +69,9 @@ impl GPUWrite for Vertex {}
 pub struct Resources<'a> {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// slang `texture`: set 0, binding 1
     pub texture: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<MultiMeshParams>,
 }
 
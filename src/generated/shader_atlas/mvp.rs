@@ -5,15 +5,21 @@
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `MVPMatrices`: size 192 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct MVPMatrices {
+    /// slang `model`: offset 0, size 64
     pub model: glam::Mat4,
+    /// slang `view`: offset 64, size 64
     pub view: glam::Mat4,
+    /// slang `proj`: offset 128, size 64
     pub proj: glam::Mat4,
 }
 
@@ -25,3 +31,20 @@ const _: () = assert!(std::mem::offset_of!(MVPMatrices, view) == 64);
 const _: () = assert!(std::mem::size_of::<glam::Mat4>() == 64);
 const _: () = assert!(std::mem::offset_of!(MVPMatrices, proj) == 128);
 const _: () = assert!(std::mem::size_of::<glam::Mat4>() == 64);
+
+impl MVPMatrices {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for MVPMatrices {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            model: SampleValue::sample_at(word),
+            view: SampleValue::sample_at(word + 16),
+            proj: SampleValue::sample_at(word + 32),
+        }
+    }
+}
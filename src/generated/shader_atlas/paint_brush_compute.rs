@@ -9,6 +9,8 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
@@ -16,17 +18,30 @@ use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `BrushParams`: size 80 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct BrushParams {
+    /// slang `pointCount`: offset 0, size 4
     pub point_count: u32,
+    /// slang `brushRadius`: offset 4, size 4
     pub brush_radius: f32,
+    /// slang `brushOpacity`: offset 8, size 4
     pub brush_opacity: f32,
+    /// slang `brushPressure`: offset 12, size 4
     pub brush_pressure: f32,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `pigmentColor_0_3`: offset 16, size 16
     pub pigment_color_0_3: glam::Vec4,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `pigmentColor_4_7`: offset 32, size 16
     pub pigment_color_4_7: glam::Vec4,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `pigmentColor_8_11`: offset 48, size 16
     pub pigment_color_8_11: glam::Vec4,
+    /// slang `canvasSize`: offset 64, size 8
     pub canvas_size: glam::Vec2,
+    /// slang `strokePoints`: offset 72, size 8
     pub stroke_points: ReadAddr<StrokePoint>,
 }
 
@@ -51,9 +66,34 @@ const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 const _: () = assert!(std::mem::offset_of!(BrushParams, stroke_points) == 72);
 const _: () = assert!(std::mem::size_of::<ReadAddr<StrokePoint>>() == 8);
 
-#[derive(Debug, Clone, Serialize)]
+impl BrushParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for BrushParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            point_count: SampleValue::sample_at(word),
+            brush_radius: SampleValue::sample_at(word + 1),
+            brush_opacity: SampleValue::sample_at(word + 2),
+            brush_pressure: SampleValue::sample_at(word + 3),
+            pigment_color_0_3: SampleValue::sample_at(word + 4),
+            pigment_color_4_7: SampleValue::sample_at(word + 8),
+            pigment_color_8_11: SampleValue::sample_at(word + 12),
+            canvas_size: SampleValue::sample_at(word + 16),
+            stroke_points: SampleValue::sample_at(word + 18),
+        }
+    }
+}
+
+/// slang `StrokePoint`: size 8 (std430, align 8)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(8))]
 pub struct StrokePoint {
+    /// slang `position`: offset 0, size 8
     pub position: glam::Vec2,
 }
 
@@ -62,13 +102,35 @@ const _: () = assert!(std::mem::size_of::<StrokePoint>() == 8);
 const _: () = assert!(std::mem::offset_of!(StrokePoint, position) == 0);
 const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 
+impl StrokePoint {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for StrokePoint {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            position: SampleValue::sample_at(word),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `wetMask`: set 0, binding 1
     pub wet_mask: &'a StorageTextureHandle,
+    /// slang `pressure`: set 0, binding 2
     pub pressure: &'a StorageTextureHandle,
+    /// slang `pigment_0_3`: set 0, binding 3
     pub pigment_0_3: &'a StorageTextureHandle,
+    /// slang `pigment_4_7`: set 0, binding 4
     pub pigment_4_7: &'a StorageTextureHandle,
+    /// slang `pigment_8_11`: set 0, binding 5
     pub pigment_8_11: &'a StorageTextureHandle,
+    /// slang `saturation`: set 0, binding 6
     pub saturation: &'a StorageTextureHandle,
+    /// slang parameter block `brushParams`: set 0, binding 0
     pub brush_params_buffer: &'a UniformBufferHandle<BrushParams>,
 }
 
@@ -112,11 +174,16 @@ impl Shader {
             resources.saturation,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
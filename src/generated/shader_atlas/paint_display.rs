@@ -11,6 +11,8 @@ use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -19,25 +21,42 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `DisplayParams`: size 416 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct DisplayParams {
+    /// slang `texelSize`: offset 0, size 8
     pub texel_size: glam::Vec2,
+    /// slang `debugView`: offset 8, size 4
     pub debug_view: u32,
+    /// slang `canvasAspect`: offset 12, size 4
     pub canvas_aspect: f32,
+    /// slang `windowAspect`: offset 16, size 4
     pub window_aspect: f32,
     pub _padding_0: [u8; 12],
+    /// slang `pigment0`: offset 32, size 32
     pub pigment0: PigmentKM,
+    /// slang `pigment1`: offset 64, size 32
     pub pigment1: PigmentKM,
+    /// slang `pigment2`: offset 96, size 32
     pub pigment2: PigmentKM,
+    /// slang `pigment3`: offset 128, size 32
     pub pigment3: PigmentKM,
+    /// slang `pigment4`: offset 160, size 32
     pub pigment4: PigmentKM,
+    /// slang `pigment5`: offset 192, size 32
     pub pigment5: PigmentKM,
+    /// slang `pigment6`: offset 224, size 32
     pub pigment6: PigmentKM,
+    /// slang `pigment7`: offset 256, size 32
     pub pigment7: PigmentKM,
+    /// slang `pigment8`: offset 288, size 32
     pub pigment8: PigmentKM,
+    /// slang `pigment9`: offset 320, size 32
     pub pigment9: PigmentKM,
+    /// slang `pigment10`: offset 352, size 32
     pub pigment10: PigmentKM,
+    /// slang `pigment11`: offset 384, size 32
     pub pigment11: PigmentKM,
 }
 
@@ -76,11 +95,45 @@ const _: () = assert!(std::mem::size_of::<PigmentKM>() == 32);
 const _: () = assert!(std::mem::offset_of!(DisplayParams, pigment11) == 384);
 const _: () = assert!(std::mem::size_of::<PigmentKM>() == 32);
 
-#[derive(Debug, Clone, Serialize)]
+impl DisplayParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for DisplayParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            texel_size: SampleValue::sample_at(word),
+            debug_view: SampleValue::sample_at(word + 2),
+            canvas_aspect: SampleValue::sample_at(word + 3),
+            window_aspect: SampleValue::sample_at(word + 4),
+            _padding_0: Default::default(),
+            pigment0: SampleValue::sample_at(word + 8),
+            pigment1: SampleValue::sample_at(word + 16),
+            pigment2: SampleValue::sample_at(word + 24),
+            pigment3: SampleValue::sample_at(word + 32),
+            pigment4: SampleValue::sample_at(word + 40),
+            pigment5: SampleValue::sample_at(word + 48),
+            pigment6: SampleValue::sample_at(word + 56),
+            pigment7: SampleValue::sample_at(word + 64),
+            pigment8: SampleValue::sample_at(word + 72),
+            pigment9: SampleValue::sample_at(word + 80),
+            pigment10: SampleValue::sample_at(word + 88),
+            pigment11: SampleValue::sample_at(word + 96),
+        }
+    }
+}
+
+/// slang `PigmentKM`: size 32 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct PigmentKM {
+    /// slang `absorption`: offset 0, size 12
     pub absorption: glam::Vec3,
     pub _padding_0: [u8; 4],
+    /// slang `scattering`: offset 16, size 12
     pub scattering: glam::Vec3,
     pub _padding_1: [u8; 4],
 }
@@ -92,12 +145,36 @@ const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
 const _: () = assert!(std::mem::offset_of!(PigmentKM, scattering) == 16);
 const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
 
+impl PigmentKM {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for PigmentKM {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            absorption: SampleValue::sample_at(word),
+            _padding_0: Default::default(),
+            scattering: SampleValue::sample_at(word + 4),
+            _padding_1: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `deposit_0_3`: set 0, binding 1
     pub deposit_0_3: &'a TextureHandle,
+    /// slang `deposit_4_7`: set 0, binding 2
     pub deposit_4_7: &'a TextureHandle,
+    /// slang `deposit_8_11`: set 0, binding 3
     pub deposit_8_11: &'a TextureHandle,
+    /// slang `paperHeight`: set 0, binding 4
     pub paper_height: &'a TextureHandle,
+    /// slang `wetMask`: set 0, binding 5
     pub wet_mask: &'a TextureHandle,
+    /// slang parameter block `displayParams`: set 0, binding 0
     pub display_params_buffer: &'a UniformBufferHandle<DisplayParams>,
 }
 
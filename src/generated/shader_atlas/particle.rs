@@ -5,15 +5,22 @@
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Particle`: size 32 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Particle {
+    /// slang `position`: offset 0, size 8
     pub position: glam::Vec2,
+    /// slang `velocity`: offset 8, size 8
     pub velocity: glam::Vec2,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `color`: offset 16, size 16
     pub color: glam::Vec4,
 }
 
@@ -25,3 +32,20 @@ const _: () = assert!(std::mem::offset_of!(Particle, velocity) == 8);
 const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 const _: () = assert!(std::mem::offset_of!(Particle, color) == 16);
 const _: () = assert!(std::mem::size_of::<glam::Vec4>() == 16);
+
+impl Particle {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Particle {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            position: SampleValue::sample_at(word),
+            velocity: SampleValue::sample_at(word + 2),
+            color: SampleValue::sample_at(word + 4),
+        }
+    }
+}
@@ -12,6 +12,8 @@ use serde::Serialize;
 pub use super::particle::Particle;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -20,11 +22,14 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `RenderParams`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct RenderParams {
+    /// slang `particleCount`: offset 0, size 4
     pub particle_count: u32,
     pub _padding_0: [u8; 4],
+    /// slang `particles`: offset 8, size 8
     pub particles: ReadAddr<Particle>,
 }
 
@@ -35,7 +40,25 @@ const _: () = assert!(std::mem::size_of::<u32>() == 4);
 const _: () = assert!(std::mem::offset_of!(RenderParams, particles) == 8);
 const _: () = assert!(std::mem::size_of::<ReadAddr<Particle>>() == 8);
 
+impl RenderParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for RenderParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            particle_count: SampleValue::sample_at(word),
+            _padding_0: Default::default(),
+            particles: SampleValue::sample_at(word + 2),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang parameter block `renderParams`: set 0, binding 0
     pub render_params_buffer: &'a UniformBufferHandle<RenderParams>,
 }
 
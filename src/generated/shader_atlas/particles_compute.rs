@@ -10,6 +10,8 @@ use serde::Serialize;
 
 pub use super::particle::Particle;
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
@@ -17,11 +19,15 @@ use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `SimParams`: size 32 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct SimParams {
+    /// slang `particlesIn`: offset 0, size 8
     pub particles_in: ReadAddr<Particle>,
+    /// slang `particlesOut`: offset 8, size 8
     pub particles_out: Addr<Particle>,
+    /// slang `deltaTime`: offset 16, size 4
     pub delta_time: f32,
     pub _padding_0: [u8; 12],
 }
@@ -35,7 +41,26 @@ const _: () = assert!(std::mem::size_of::<Addr<Particle>>() == 8);
 const _: () = assert!(std::mem::offset_of!(SimParams, delta_time) == 16);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
+impl SimParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for SimParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            particles_in: SampleValue::sample_at(word),
+            particles_out: SampleValue::sample_at(word + 2),
+            delta_time: SampleValue::sample_at(word + 4),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang parameter block `simParams`: set 0, binding 0
     pub sim_params_buffer: &'a UniformBufferHandle<SimParams>,
 }
 
@@ -73,11 +98,16 @@ impl Shader {
         let storage_texture_handles = vec![
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
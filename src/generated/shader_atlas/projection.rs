@@ -5,13 +5,17 @@
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Projection`: size 64 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Projection {
+    /// slang `matrix`: offset 0, size 64
     pub matrix: glam::Mat4,
 }
 
@@ -19,3 +23,18 @@ impl GPUWrite for Projection {}
 const _: () = assert!(std::mem::size_of::<Projection>() == 64);
 const _: () = assert!(std::mem::offset_of!(Projection, matrix) == 0);
 const _: () = assert!(std::mem::size_of::<glam::Mat4>() == 64);
+
+impl Projection {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Projection {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            matrix: SampleValue::sample_at(word),
+        }
+    }
+}
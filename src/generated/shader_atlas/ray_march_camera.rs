@@ -6,14 +6,19 @@ use serde::Serialize;
 
 use super::projection::Projection;
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `RayMarchCamera`: size 80 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct RayMarchCamera {
+    /// slang `inverseViewProj`: offset 0, size 64
     pub inverse_view_proj: Projection,
+    /// slang `position`: offset 64, size 12
     pub position: glam::Vec3,
     pub _padding_0: [u8; 4],
 }
@@ -24,3 +29,20 @@ const _: () = assert!(std::mem::offset_of!(RayMarchCamera, inverse_view_proj) ==
 const _: () = assert!(std::mem::size_of::<Projection>() == 64);
 const _: () = assert!(std::mem::offset_of!(RayMarchCamera, position) == 64);
 const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
+
+impl RayMarchCamera {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for RayMarchCamera {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            inverse_view_proj: SampleValue::sample_at(word),
+            position: SampleValue::sample_at(word + 16),
+            _padding_0: Default::default(),
+        }
+    }
+}
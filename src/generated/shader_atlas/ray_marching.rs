@@ -13,6 +13,8 @@ pub use super::projection::Projection;
 pub use super::ray_march_camera::RayMarchCamera;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -21,16 +23,24 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `RayMarchingParams`: size 128 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct RayMarchingParams {
+    /// slang `camera`: offset 0, size 80
     pub camera: RayMarchCamera,
+    /// slang `lightPosition`: offset 80, size 12
     pub light_position: glam::Vec3,
+    /// slang `sphereCount`: offset 92, size 4
     pub sphere_count: u32,
+    /// slang `boxCount`: offset 96, size 4
     pub box_count: u32,
     pub _padding_0: [u8; 4],
+    /// slang `resolution`: offset 104, size 8
     pub resolution: glam::Vec2,
+    /// slang `spheres`: offset 112, size 8
     pub spheres: ReadAddr<Sphere>,
+    /// slang `boxes`: offset 120, size 8
     pub boxes: ReadAddr<BoxRect>,
 }
 
@@ -51,12 +61,39 @@ const _: () = assert!(std::mem::size_of::<ReadAddr<Sphere>>() == 8);
 const _: () = assert!(std::mem::offset_of!(RayMarchingParams, boxes) == 120);
 const _: () = assert!(std::mem::size_of::<ReadAddr<BoxRect>>() == 8);
 
-#[derive(Debug, Clone, Serialize)]
+impl RayMarchingParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for RayMarchingParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            camera: SampleValue::sample_at(word),
+            light_position: SampleValue::sample_at(word + 20),
+            sphere_count: SampleValue::sample_at(word + 23),
+            box_count: SampleValue::sample_at(word + 24),
+            _padding_0: Default::default(),
+            resolution: SampleValue::sample_at(word + 26),
+            spheres: SampleValue::sample_at(word + 28),
+            boxes: SampleValue::sample_at(word + 30),
+        }
+    }
+}
+
+/// slang `BoxRect`: size 96 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct BoxRect {
+    /// slang `transform`: offset 0, size 64
     pub transform: Projection,
+    /// slang `radii`: offset 64, size 12
     pub radii: glam::Vec3,
     pub _padding_0: [u8; 4],
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `color`: offset 80, size 12
     pub color: glam::Vec3,
     pub _padding_1: [u8; 4],
 }
@@ -70,11 +107,35 @@ const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
 const _: () = assert!(std::mem::offset_of!(BoxRect, color) == 80);
 const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
 
-#[derive(Debug, Clone, Serialize)]
+impl BoxRect {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for BoxRect {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            transform: SampleValue::sample_at(word),
+            radii: SampleValue::sample_at(word + 16),
+            _padding_0: Default::default(),
+            color: SampleValue::sample_at(word + 20),
+            _padding_1: Default::default(),
+        }
+    }
+}
+
+/// slang `Sphere`: size 32 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Sphere {
+    /// slang `center`: offset 0, size 12
     pub center: glam::Vec3,
+    /// slang `radius`: offset 12, size 4
     pub radius: f32,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `color`: offset 16, size 12
     pub color: glam::Vec3,
     pub _padding_0: [u8; 4],
 }
@@ -88,7 +149,26 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(Sphere, color) == 16);
 const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
 
+impl Sphere {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Sphere {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            center: SampleValue::sample_at(word),
+            radius: SampleValue::sample_at(word + 3),
+            color: SampleValue::sample_at(word + 4),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<RayMarchingParams>,
 }
 
@@ -11,6 +11,8 @@ use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -19,11 +21,15 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `SDF2DParams`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct SDF2DParams {
+    /// slang `resolution`: offset 0, size 8
     pub resolution: glam::Vec2,
+    /// slang `time`: offset 8, size 4
     pub time: f32,
+    /// slang `beatProximity`: offset 12, size 4
     pub beat_proximity: f32,
 }
 
@@ -36,7 +42,25 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(SDF2DParams, beat_proximity) == 12);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
+impl SDF2DParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for SDF2DParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            resolution: SampleValue::sample_at(word),
+            time: SampleValue::sample_at(word + 2),
+            beat_proximity: SampleValue::sample_at(word + 3),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<SDF2DParams>,
 }
 
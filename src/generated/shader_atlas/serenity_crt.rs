@@ -11,6 +11,8 @@ use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -19,23 +21,39 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `SerenityCRTParams`: size 64 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct SerenityCRTParams {
+    /// slang `resolution`: offset 0, size 8
     pub resolution: glam::Vec2,
+    /// slang `scanlineIntensity`: offset 8, size 4
     pub scanline_intensity: f32,
+    /// slang `scanlineCount`: offset 12, size 4
     pub scanline_count: f32,
+    /// slang `time`: offset 16, size 4
     pub time: f32,
+    /// slang `yOffset`: offset 20, size 4
     pub y_offset: f32,
+    /// slang `brightness`: offset 24, size 4
     pub brightness: f32,
+    /// slang `contrast`: offset 28, size 4
     pub contrast: f32,
+    /// slang `saturation`: offset 32, size 4
     pub saturation: f32,
+    /// slang `bloomIntensity`: offset 36, size 4
     pub bloom_intensity: f32,
+    /// slang `bloomThreshold`: offset 40, size 4
     pub bloom_threshold: f32,
+    /// slang `rgbShift`: offset 44, size 4
     pub rgb_shift: f32,
+    /// slang `adaptiveIntensity`: offset 48, size 4
     pub adaptive_intensity: f32,
+    /// slang `vignetteStrength`: offset 52, size 4
     pub vignette_strength: f32,
+    /// slang `curvature`: offset 56, size 4
     pub curvature: f32,
+    /// slang `flickerStrength`: offset 60, size 4
     pub flicker_strength: f32,
 }
 
@@ -72,8 +90,39 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(SerenityCRTParams, flicker_strength) == 60);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
+impl SerenityCRTParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for SerenityCRTParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            resolution: SampleValue::sample_at(word),
+            scanline_intensity: SampleValue::sample_at(word + 2),
+            scanline_count: SampleValue::sample_at(word + 3),
+            time: SampleValue::sample_at(word + 4),
+            y_offset: SampleValue::sample_at(word + 5),
+            brightness: SampleValue::sample_at(word + 6),
+            contrast: SampleValue::sample_at(word + 7),
+            saturation: SampleValue::sample_at(word + 8),
+            bloom_intensity: SampleValue::sample_at(word + 9),
+            bloom_threshold: SampleValue::sample_at(word + 10),
+            rgb_shift: SampleValue::sample_at(word + 11),
+            adaptive_intensity: SampleValue::sample_at(word + 12),
+            vignette_strength: SampleValue::sample_at(word + 13),
+            curvature: SampleValue::sample_at(word + 14),
+            flicker_strength: SampleValue::sample_at(word + 15),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `tex`: set 0, binding 1
     pub tex: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<SerenityCRTParams>,
 }
 
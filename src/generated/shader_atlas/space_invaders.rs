@@ -12,6 +12,8 @@ use serde::Serialize;
 pub use super::projection::Projection;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -20,11 +22,15 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `SpaceInvadersParams`: size 80 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct SpaceInvadersParams {
+    /// slang `projection`: offset 0, size 64
     pub projection: Projection,
+    /// slang `sprites`: offset 64, size 8
     pub sprites: ReadAddr<Sprite>,
+    /// slang `debugBoxes`: offset 72, size 8
     pub debug_boxes: ReadAddr<DebugBox>,
 }
 
@@ -37,11 +43,33 @@ const _: () = assert!(std::mem::size_of::<ReadAddr<Sprite>>() == 8);
 const _: () = assert!(std::mem::offset_of!(SpaceInvadersParams, debug_boxes) == 72);
 const _: () = assert!(std::mem::size_of::<ReadAddr<DebugBox>>() == 8);
 
-#[derive(Debug, Clone, Serialize)]
+impl SpaceInvadersParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for SpaceInvadersParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            projection: SampleValue::sample_at(word),
+            sprites: SampleValue::sample_at(word + 16),
+            debug_boxes: SampleValue::sample_at(word + 18),
+        }
+    }
+}
+
+/// slang `DebugBox`: size 32 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct DebugBox {
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `color`: offset 0, size 16
     pub color: glam::Vec4,
+    /// slang `position`: offset 16, size 8
     pub position: glam::Vec2,
+    /// slang `size`: offset 24, size 8
     pub size: glam::Vec2,
 }
 
@@ -54,18 +82,47 @@ const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 const _: () = assert!(std::mem::offset_of!(DebugBox, size) == 24);
 const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 
-#[derive(Debug, Clone, Serialize)]
+impl DebugBox {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for DebugBox {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            color: SampleValue::sample_at(word),
+            position: SampleValue::sample_at(word + 4),
+            size: SampleValue::sample_at(word + 6),
+        }
+    }
+}
+
+/// slang `Sprite`: size 64 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Sprite {
+    /// slang `scale`: offset 0, size 8
     pub scale: glam::Vec2,
+    /// slang `flags`: offset 8, size 4
     pub flags: u32,
+    /// slang `debugBoxId`: offset 12, size 4
     pub debug_box_id: u32,
+    /// slang `position`: offset 16, size 12
     pub position: glam::Vec3,
+    /// slang `rotation`: offset 28, size 4
     pub rotation: f32,
+    /// slang `texU`: offset 32, size 4
     pub tex_u: f32,
+    /// slang `texV`: offset 36, size 4
     pub tex_v: f32,
+    /// slang `texW`: offset 40, size 4
     pub tex_w: f32,
+    /// slang `texH`: offset 44, size 4
     pub tex_h: f32,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `color`: offset 48, size 16
     pub color: glam::Vec4,
 }
 
@@ -92,8 +149,34 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(Sprite, color) == 48);
 const _: () = assert!(std::mem::size_of::<glam::Vec4>() == 16);
 
+impl Sprite {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Sprite {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            scale: SampleValue::sample_at(word),
+            flags: SampleValue::sample_at(word + 2),
+            debug_box_id: SampleValue::sample_at(word + 3),
+            position: SampleValue::sample_at(word + 4),
+            rotation: SampleValue::sample_at(word + 7),
+            tex_u: SampleValue::sample_at(word + 8),
+            tex_v: SampleValue::sample_at(word + 9),
+            tex_w: SampleValue::sample_at(word + 10),
+            tex_h: SampleValue::sample_at(word + 11),
+            color: SampleValue::sample_at(word + 12),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `spriteSheet`: set 0, binding 1
     pub sprite_sheet: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<SpaceInvadersParams>,
 }
 
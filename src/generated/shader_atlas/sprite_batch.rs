@@ -10,8 +10,11 @@ use ash::vk;
 use serde::Serialize;
 
 pub use super::projection::Projection;
+pub use super::sprite_batch_common::Sprite;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -20,11 +23,14 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `SpriteBatchParams`: size 80 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct SpriteBatchParams {
+    /// slang `sprites`: offset 0, size 8
     pub sprites: ImmutableAddr<Sprite>,
     pub _padding_0: [u8; 8],
+    /// slang `projection`: offset 16, size 64
     pub projection: Projection,
 }
 
@@ -35,43 +41,27 @@ const _: () = assert!(std::mem::size_of::<ImmutableAddr<Sprite>>() == 8);
 const _: () = assert!(std::mem::offset_of!(SpriteBatchParams, projection) == 16);
 const _: () = assert!(std::mem::size_of::<Projection>() == 64);
 
-#[derive(Debug, Clone, Serialize)]
-#[repr(C, align(16))]
-pub struct Sprite {
-    pub position: glam::Vec3,
-    pub rotation: f32,
-    pub scale: glam::Vec2,
-    pub padding: glam::Vec2,
-    pub tex_u: f32,
-    pub tex_v: f32,
-    pub tex_w: f32,
-    pub tex_h: f32,
-    pub color: glam::Vec4,
+impl SpriteBatchParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
 }
 
-impl GPUWrite for Sprite {}
-const _: () = assert!(std::mem::size_of::<Sprite>() == 64);
-const _: () = assert!(std::mem::offset_of!(Sprite, position) == 0);
-const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
-const _: () = assert!(std::mem::offset_of!(Sprite, rotation) == 12);
-const _: () = assert!(std::mem::size_of::<f32>() == 4);
-const _: () = assert!(std::mem::offset_of!(Sprite, scale) == 16);
-const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
-const _: () = assert!(std::mem::offset_of!(Sprite, padding) == 24);
-const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
-const _: () = assert!(std::mem::offset_of!(Sprite, tex_u) == 32);
-const _: () = assert!(std::mem::size_of::<f32>() == 4);
-const _: () = assert!(std::mem::offset_of!(Sprite, tex_v) == 36);
-const _: () = assert!(std::mem::size_of::<f32>() == 4);
-const _: () = assert!(std::mem::offset_of!(Sprite, tex_w) == 40);
-const _: () = assert!(std::mem::size_of::<f32>() == 4);
-const _: () = assert!(std::mem::offset_of!(Sprite, tex_h) == 44);
-const _: () = assert!(std::mem::size_of::<f32>() == 4);
-const _: () = assert!(std::mem::offset_of!(Sprite, color) == 48);
-const _: () = assert!(std::mem::size_of::<glam::Vec4>() == 16);
+impl SampleValue for SpriteBatchParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            sprites: SampleValue::sample_at(word),
+            _padding_0: Default::default(),
+            projection: SampleValue::sample_at(word + 4),
+        }
+    }
+}
 
 pub struct Resources<'a> {
+    /// slang `texture`: set 0, binding 1
     pub texture: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<SpriteBatchParams>,
 }
 
@@ -0,0 +1,81 @@
+// GENERATED FILE (do not edit directly)
+
+//! shared types from slang module: sprite_batch_common.slang
+
+use serde::Serialize;
+
+use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+
+// glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
+const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
+
+/// slang `Sprite`: size 64 (std430, align 16)
+#[derive(Debug, Clone, Serialize, Default)]
+#[repr(C, align(16))]
+pub struct Sprite {
+    /// slang `position`: offset 0, size 12
+    pub position: glam::Vec3,
+    /// slang `rotation`: offset 12, size 4
+    pub rotation: f32,
+    /// slang `scale`: offset 16, size 8
+    pub scale: glam::Vec2,
+    /// slang `padding`: offset 24, size 8
+    pub padding: glam::Vec2,
+    /// slang `texU`: offset 32, size 4
+    pub tex_u: f32,
+    /// slang `texV`: offset 36, size 4
+    pub tex_v: f32,
+    /// slang `texW`: offset 40, size 4
+    pub tex_w: f32,
+    /// slang `texH`: offset 44, size 4
+    pub tex_h: f32,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
+    /// slang `color`: offset 48, size 16
+    pub color: glam::Vec4,
+}
+
+impl GPUWrite for Sprite {}
+const _: () = assert!(std::mem::size_of::<Sprite>() == 64);
+const _: () = assert!(std::mem::offset_of!(Sprite, position) == 0);
+const _: () = assert!(std::mem::size_of::<glam::Vec3>() == 12);
+const _: () = assert!(std::mem::offset_of!(Sprite, rotation) == 12);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(Sprite, scale) == 16);
+const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
+const _: () = assert!(std::mem::offset_of!(Sprite, padding) == 24);
+const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
+const _: () = assert!(std::mem::offset_of!(Sprite, tex_u) == 32);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(Sprite, tex_v) == 36);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(Sprite, tex_w) == 40);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(Sprite, tex_h) == 44);
+const _: () = assert!(std::mem::size_of::<f32>() == 4);
+const _: () = assert!(std::mem::offset_of!(Sprite, color) == 48);
+const _: () = assert!(std::mem::size_of::<glam::Vec4>() == 16);
+
+impl Sprite {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Sprite {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            position: SampleValue::sample_at(word),
+            rotation: SampleValue::sample_at(word + 3),
+            scale: SampleValue::sample_at(word + 4),
+            padding: SampleValue::sample_at(word + 6),
+            tex_u: SampleValue::sample_at(word + 8),
+            tex_v: SampleValue::sample_at(word + 9),
+            tex_w: SampleValue::sample_at(word + 10),
+            tex_h: SampleValue::sample_at(word + 11),
+            color: SampleValue::sample_at(word + 12),
+        }
+    }
+}
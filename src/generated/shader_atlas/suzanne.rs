@@ -12,6 +12,8 @@ use serde::Serialize;
 pub use super::mvp::MVPMatrices;
 use crate::renderer::gpu_write::GPUWrite;
 #[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
+#[allow(unused)]
 use crate::renderer::vertex_description::{NoVertex, VertexDescription};
 use crate::renderer::*;
 use crate::shaders::atlas::{PrecompiledShader, PrecompiledShaders, ShaderAtlasEntry};
@@ -20,10 +22,13 @@ use crate::shaders::json::{ReflectedPipelineLayout, ReflectionJson};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `SuzanneParams`: size 208 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct SuzanneParams {
+    /// slang `mvp`: offset 0, size 192
     pub mvp: MVPMatrices,
+    /// slang `time`: offset 192, size 4
     pub time: f32,
     pub _padding_0: [u8; 12],
 }
@@ -35,10 +40,28 @@ const _: () = assert!(std::mem::size_of::<MVPMatrices>() == 192);
 const _: () = assert!(std::mem::offset_of!(SuzanneParams, time) == 192);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
-#[derive(Debug, Clone, Serialize)]
+impl SuzanneParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for SuzanneParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            mvp: SampleValue::sample_at(word),
+            time: SampleValue::sample_at(word + 48),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Vertex {
     pub position: glam::Vec3,
+    /// linear color, straight alpha; convert sRGB with `crate::color::srgb8`
     pub color: glam::Vec3,
     pub tex_coord: glam::Vec2,
 }
@@ -48,9 +71,13 @@ impl GPUWrite for Vertex {}
 pub struct Resources<'a> {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// slang `texture0`: set 0, binding 1
     pub texture0: &'a TextureHandle,
+    /// slang `texture1`: set 0, binding 2
     pub texture1: &'a TextureHandle,
+    /// slang `texture2`: set 0, binding 3
     pub texture2: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<SuzanneParams>,
 }
 
@@ -5,8 +5,11 @@
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `SimParams`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct SimParams {
     pub delta_time: f32,
@@ -15,3 +18,19 @@ pub struct SimParams {
 
 impl GPUWrite for SimParams {}
 const _: () = assert!(std::mem::size_of::<SimParams>() == 16);
+
+impl SimParams {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for SimParams {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            delta_time: Default::default(),
+            _padding_0: Default::default(),
+        }
+    }
+}
@@ -9,6 +9,8 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
@@ -16,23 +18,39 @@ use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Params`: size 208 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Params {
+    /// slang `gridSize`: offset 0, size 8
     pub grid_size: glam::Vec2,
+    /// slang `dt`: offset 8, size 4
     pub dt: f32,
+    /// slang `transferRate`: offset 12, size 4
     pub transfer_rate: f32,
+    /// slang `pigment0`: offset 16, size 16
     pub pigment0: PigmentProperties,
+    /// slang `pigment1`: offset 32, size 16
     pub pigment1: PigmentProperties,
+    /// slang `pigment2`: offset 48, size 16
     pub pigment2: PigmentProperties,
+    /// slang `pigment3`: offset 64, size 16
     pub pigment3: PigmentProperties,
+    /// slang `pigment4`: offset 80, size 16
     pub pigment4: PigmentProperties,
+    /// slang `pigment5`: offset 96, size 16
     pub pigment5: PigmentProperties,
+    /// slang `pigment6`: offset 112, size 16
     pub pigment6: PigmentProperties,
+    /// slang `pigment7`: offset 128, size 16
     pub pigment7: PigmentProperties,
+    /// slang `pigment8`: offset 144, size 16
     pub pigment8: PigmentProperties,
+    /// slang `pigment9`: offset 160, size 16
     pub pigment9: PigmentProperties,
+    /// slang `pigment10`: offset 176, size 16
     pub pigment10: PigmentProperties,
+    /// slang `pigment11`: offset 192, size 16
     pub pigment11: PigmentProperties,
 }
 
@@ -69,11 +87,44 @@ const _: () = assert!(std::mem::size_of::<PigmentProperties>() == 16);
 const _: () = assert!(std::mem::offset_of!(Params, pigment11) == 192);
 const _: () = assert!(std::mem::size_of::<PigmentProperties>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+impl Params {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Params {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            grid_size: SampleValue::sample_at(word),
+            dt: SampleValue::sample_at(word + 2),
+            transfer_rate: SampleValue::sample_at(word + 3),
+            pigment0: SampleValue::sample_at(word + 4),
+            pigment1: SampleValue::sample_at(word + 8),
+            pigment2: SampleValue::sample_at(word + 12),
+            pigment3: SampleValue::sample_at(word + 16),
+            pigment4: SampleValue::sample_at(word + 20),
+            pigment5: SampleValue::sample_at(word + 24),
+            pigment6: SampleValue::sample_at(word + 28),
+            pigment7: SampleValue::sample_at(word + 32),
+            pigment8: SampleValue::sample_at(word + 36),
+            pigment9: SampleValue::sample_at(word + 40),
+            pigment10: SampleValue::sample_at(word + 44),
+            pigment11: SampleValue::sample_at(word + 48),
+        }
+    }
+}
+
+/// slang `PigmentProperties`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct PigmentProperties {
+    /// slang `density`: offset 0, size 4
     pub density: f32,
+    /// slang `stainingPower`: offset 4, size 4
     pub staining_power: f32,
+    /// slang `granulation`: offset 8, size 4
     pub granulation: f32,
     pub _padding_0: [u8; 4],
 }
@@ -87,23 +138,58 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(PigmentProperties, granulation) == 8);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
+impl PigmentProperties {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for PigmentProperties {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            density: SampleValue::sample_at(word),
+            staining_power: SampleValue::sample_at(word + 1),
+            granulation: SampleValue::sample_at(word + 2),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `pigmentIn_0_3`: set 0, binding 1
     pub pigment_in_0_3: &'a TextureHandle,
+    /// slang `pigmentIn_4_7`: set 0, binding 2
     pub pigment_in_4_7: &'a TextureHandle,
+    /// slang `pigmentIn_8_11`: set 0, binding 3
     pub pigment_in_8_11: &'a TextureHandle,
+    /// slang `uIn`: set 0, binding 4
     pub u_in: &'a TextureHandle,
+    /// slang `vIn`: set 0, binding 5
     pub v_in: &'a TextureHandle,
+    /// slang `wetMask`: set 0, binding 6
     pub wet_mask: &'a TextureHandle,
+    /// slang `paperHeight`: set 0, binding 7
     pub paper_height: &'a TextureHandle,
+    /// slang `pigmentOut_0_3`: set 0, binding 8
     pub pigment_out_0_3: &'a StorageTextureHandle,
+    /// slang `pigmentOut_4_7`: set 0, binding 9
     pub pigment_out_4_7: &'a StorageTextureHandle,
+    /// slang `pigmentOut_8_11`: set 0, binding 10
     pub pigment_out_8_11: &'a StorageTextureHandle,
+    /// slang `depositIn_0_3`: set 0, binding 11
     pub deposit_in_0_3: &'a TextureHandle,
+    /// slang `depositIn_4_7`: set 0, binding 12
     pub deposit_in_4_7: &'a TextureHandle,
+    /// slang `depositIn_8_11`: set 0, binding 13
     pub deposit_in_8_11: &'a TextureHandle,
+    /// slang `depositOut_0_3`: set 0, binding 14
     pub deposit_out_0_3: &'a StorageTextureHandle,
+    /// slang `depositOut_4_7`: set 0, binding 15
     pub deposit_out_4_7: &'a StorageTextureHandle,
+    /// slang `depositOut_8_11`: set 0, binding 16
     pub deposit_out_8_11: &'a StorageTextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<Params>,
 }
 
@@ -157,11 +243,16 @@ impl Shader {
             resources.deposit_out_8_11,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
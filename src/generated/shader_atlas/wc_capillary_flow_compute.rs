@@ -9,6 +9,8 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
@@ -16,13 +18,19 @@ use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Params`: size 32 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Params {
+    /// slang `gridSize`: offset 0, size 8
     pub grid_size: glam::Vec2,
+    /// slang `diffuseRate`: offset 8, size 4
     pub diffuse_rate: f32,
+    /// slang `capacity`: offset 12, size 4
     pub capacity: f32,
+    /// slang `sigma`: offset 16, size 4
     pub sigma: f32,
+    /// slang `dryThreshold`: offset 20, size 4
     pub dry_threshold: f32,
     pub _padding_0: [u8; 8],
 }
@@ -40,12 +48,38 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(Params, dry_threshold) == 20);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
+impl Params {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Params {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            grid_size: SampleValue::sample_at(word),
+            diffuse_rate: SampleValue::sample_at(word + 2),
+            capacity: SampleValue::sample_at(word + 3),
+            sigma: SampleValue::sample_at(word + 4),
+            dry_threshold: SampleValue::sample_at(word + 5),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `saturationIn`: set 0, binding 1
     pub saturation_in: &'a TextureHandle,
+    /// slang `wetMaskIn`: set 0, binding 2
     pub wet_mask_in: &'a TextureHandle,
+    /// slang `paperHeight`: set 0, binding 3
     pub paper_height: &'a TextureHandle,
+    /// slang `saturationOut`: set 0, binding 4
     pub saturation_out: &'a StorageTextureHandle,
+    /// slang `wetMaskOut`: set 0, binding 5
     pub wet_mask_out: &'a StorageTextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<Params>,
 }
 
@@ -88,11 +122,16 @@ impl Shader {
             resources.wet_mask_out,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
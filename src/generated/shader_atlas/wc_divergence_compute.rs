@@ -9,6 +9,8 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
@@ -16,9 +18,11 @@ use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Params`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Params {
+    /// slang `gridSize`: offset 0, size 8
     pub grid_size: glam::Vec2,
     pub _padding_0: [u8; 8],
 }
@@ -28,10 +32,30 @@ const _: () = assert!(std::mem::size_of::<Params>() == 16);
 const _: () = assert!(std::mem::offset_of!(Params, grid_size) == 0);
 const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 
+impl Params {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Params {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            grid_size: SampleValue::sample_at(word),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `uIn`: set 0, binding 1
     pub u_in: &'a TextureHandle,
+    /// slang `vIn`: set 0, binding 2
     pub v_in: &'a TextureHandle,
+    /// slang `divergence`: set 0, binding 3
     pub divergence: &'a StorageTextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<Params>,
 }
 
@@ -72,11 +96,16 @@ impl Shader {
             resources.divergence,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
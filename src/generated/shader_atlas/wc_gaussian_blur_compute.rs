@@ -9,6 +9,8 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
@@ -16,10 +18,13 @@ use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Params`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Params {
+    /// slang `gridSize`: offset 0, size 8
     pub grid_size: glam::Vec2,
+    /// slang `direction`: offset 8, size 8
     pub direction: glam::Vec2,
 }
 
@@ -30,9 +35,28 @@ const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 const _: () = assert!(std::mem::offset_of!(Params, direction) == 8);
 const _: () = assert!(std::mem::size_of::<glam::Vec2>() == 8);
 
+impl Params {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Params {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            grid_size: SampleValue::sample_at(word),
+            direction: SampleValue::sample_at(word + 2),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `inputTex`: set 0, binding 1
     pub input_tex: &'a TextureHandle,
+    /// slang `outputTex`: set 0, binding 2
     pub output_tex: &'a StorageTextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<Params>,
 }
 
@@ -72,11 +96,16 @@ impl Shader {
             resources.output_tex,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
@@ -9,11 +9,14 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Params`: size 16 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Params {
     pub grid_size: glam::Vec2,
@@ -24,12 +27,35 @@ pub struct Params {
 impl GPUWrite for Params {}
 const _: () = assert!(std::mem::size_of::<Params>() == 16);
 
+impl Params {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Params {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            grid_size: Default::default(),
+            dt: Default::default(),
+            _padding_0: Default::default(),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `pigmentIn`: set 0, binding 1
     pub pigment_in: &'a TextureHandle,
+    /// slang `uIn`: set 0, binding 2
     pub u_in: &'a TextureHandle,
+    /// slang `vIn`: set 0, binding 3
     pub v_in: &'a TextureHandle,
+    /// slang `wetMask`: set 0, binding 4
     pub wet_mask: &'a TextureHandle,
+    /// slang `pigmentOut`: set 0, binding 5
     pub pigment_out: &'a StorageTextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<Params>,
 }
 
@@ -72,11 +98,16 @@ impl Shader {
             resources.pigment_out,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
@@ -9,11 +9,14 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Params`: size 80 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Params {
     pub grid_size: glam::Vec2,
@@ -28,7 +31,28 @@ pub struct Params {
 impl GPUWrite for Params {}
 const _: () = assert!(std::mem::size_of::<Params>() == 80);
 
-#[derive(Debug, Clone, Serialize)]
+impl Params {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Params {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            grid_size: Default::default(),
+            transfer_rate: Default::default(),
+            pad: Default::default(),
+            pigment0: Default::default(),
+            pigment1: Default::default(),
+            pigment2: Default::default(),
+            pigment3: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct PigmentProperties {
     pub density: f32,
@@ -40,10 +64,15 @@ pub struct PigmentProperties {
 impl GPUWrite for PigmentProperties {}
 
 pub struct Resources<'a> {
+    /// slang `pigment`: set 0, binding 1
     pub pigment: &'a StorageTextureHandle,
+    /// slang `deposit`: set 0, binding 2
     pub deposit: &'a StorageTextureHandle,
+    /// slang `paperHeight`: set 0, binding 3
     pub paper_height: &'a TextureHandle,
+    /// slang `wetMask`: set 0, binding 4
     pub wet_mask: &'a TextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<Params>,
 }
 
@@ -85,11 +114,16 @@ impl Shader {
             resources.deposit,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
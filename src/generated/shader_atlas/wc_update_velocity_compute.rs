@@ -9,6 +9,8 @@ use ash::util::read_spv;
 use serde::Serialize;
 
 use crate::renderer::gpu_write::GPUWrite;
+#[allow(unused)]
+use crate::renderer::gpu_write::SampleValue;
 use crate::renderer::*;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader};
 use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
@@ -16,14 +18,21 @@ use crate::shaders::json::{ComputeReflectionJson, ReflectedPipelineLayout};
 // glam must be built without its scalar-math feature (GPU layouts need align-16 Vec4)
 const _: () = assert!(std::mem::align_of::<glam::Vec4>() == 16);
 
-#[derive(Debug, Clone, Serialize)]
+/// slang `Params`: size 32 (std140)
+#[derive(Debug, Clone, Serialize, Default)]
 #[repr(C, align(16))]
 pub struct Params {
+    /// slang `gridSize`: offset 0, size 8
     pub grid_size: glam::Vec2,
+    /// slang `texelSize`: offset 8, size 8
     pub texel_size: glam::Vec2,
+    /// slang `dt`: offset 16, size 4
     pub dt: f32,
+    /// slang `mu`: offset 20, size 4
     pub mu: f32,
+    /// slang `kappa`: offset 24, size 4
     pub kappa: f32,
+    /// slang `slopeStrength`: offset 28, size 4
     pub slope_strength: f32,
 }
 
@@ -42,14 +51,42 @@ const _: () = assert!(std::mem::size_of::<f32>() == 4);
 const _: () = assert!(std::mem::offset_of!(Params, slope_strength) == 28);
 const _: () = assert!(std::mem::size_of::<f32>() == 4);
 
+impl Params {
+    /// each float and uint holds its 4-byte word index; see [`SampleValue`]
+    pub fn sample() -> Self {
+        Self::sample_at(0)
+    }
+}
+
+impl SampleValue for Params {
+    fn sample_at(word: u32) -> Self {
+        Self {
+            grid_size: SampleValue::sample_at(word),
+            texel_size: SampleValue::sample_at(word + 2),
+            dt: SampleValue::sample_at(word + 4),
+            mu: SampleValue::sample_at(word + 5),
+            kappa: SampleValue::sample_at(word + 6),
+            slope_strength: SampleValue::sample_at(word + 7),
+        }
+    }
+}
+
 pub struct Resources<'a> {
+    /// slang `uIn`: set 0, binding 1
     pub u_in: &'a TextureHandle,
+    /// slang `vIn`: set 0, binding 2
     pub v_in: &'a TextureHandle,
+    /// slang `pressure`: set 0, binding 3
     pub pressure: &'a TextureHandle,
+    /// slang `paperHeight`: set 0, binding 4
     pub paper_height: &'a TextureHandle,
+    /// slang `wetMask`: set 0, binding 5
     pub wet_mask: &'a TextureHandle,
+    /// slang `uOut`: set 0, binding 6
     pub u_out: &'a StorageTextureHandle,
+    /// slang `vOut`: set 0, binding 7
     pub v_out: &'a StorageTextureHandle,
+    /// slang parameter block `params`: set 0, binding 0
     pub params_buffer: &'a UniformBufferHandle<Params>,
 }
 
@@ -94,11 +131,16 @@ impl Shader {
             resources.v_out,
         ];
 
+        #[rustfmt::skip]
+        let raw_buffer_handles = vec![
+        ];
+
         ComputePipelineConfig {
             shader: Box::new(self),
             texture_handles,
             uniform_buffer_handles,
             storage_texture_handles,
+            raw_buffer_handles,
         }
     }
 
@@ -0,0 +1 @@
+pub mod space_invaders;
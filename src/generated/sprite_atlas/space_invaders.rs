@@ -0,0 +1,107 @@
+// GENERATED FILE (do not edit directly)
+
+//! generated from sprite sheet: textures/space_invaders/sprite_sheet.json
+
+use crate::sprites::AtlasFrame;
+
+/// the sheet's size in pixels
+pub const SHEET_SIZE: [u32; 2] = [480, 288];
+
+/// The sprites in the sheet; see [`AtlasSprite::frames`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AtlasSprite {
+    Bomb,
+    BombExplosion,
+    Bug,
+    Bullet,
+    GameOver,
+    Ship,
+    YouWin,
+}
+
+impl AtlasSprite {
+    pub const ALL: [AtlasSprite; 7] = [
+        AtlasSprite::Bomb,
+        AtlasSprite::BombExplosion,
+        AtlasSprite::Bug,
+        AtlasSprite::Bullet,
+        AtlasSprite::GameOver,
+        AtlasSprite::Ship,
+        AtlasSprite::YouWin,
+    ];
+
+    /// the frames' name in the sheet, less their frame numbers
+    pub fn name(self) -> &'static str {
+        match self {
+            AtlasSprite::Bomb => "bomb",
+            AtlasSprite::BombExplosion => "bomb_explosion",
+            AtlasSprite::Bug => "bug",
+            AtlasSprite::Bullet => "bullet",
+            AtlasSprite::GameOver => "game_over",
+            AtlasSprite::Ship => "ship",
+            AtlasSprite::YouWin => "you_win",
+        }
+    }
+
+    /// the sprite's frames, in frame number order
+    pub fn frames(self) -> &'static [AtlasFrame] {
+        match self {
+            AtlasSprite::Bomb => BOMB,
+            AtlasSprite::BombExplosion => BOMB_EXPLOSION,
+            AtlasSprite::Bug => BUG,
+            AtlasSprite::Bullet => BULLET,
+            AtlasSprite::GameOver => GAME_OVER,
+            AtlasSprite::Ship => SHIP,
+            AtlasSprite::YouWin => YOU_WIN,
+        }
+    }
+}
+
+pub const BOMB: &[AtlasFrame] = &[
+    AtlasFrame::new([0, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([32, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([64, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([96, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([128, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([160, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([192, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([224, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([256, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([288, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([320, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([352, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([384, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([416, 0, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([448, 0, 32, 32], 100, SHEET_SIZE),
+];
+
+pub const BOMB_EXPLOSION: &[AtlasFrame] = &[
+    AtlasFrame::new([0, 32, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([32, 32, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([64, 32, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([96, 32, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([128, 32, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([160, 32, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([192, 32, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([224, 32, 32, 32], 100, SHEET_SIZE),
+];
+
+pub const BUG: &[AtlasFrame] = &[
+    AtlasFrame::new([0, 64, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([32, 64, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([64, 64, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([96, 64, 32, 32], 100, SHEET_SIZE),
+];
+
+pub const BULLET: &[AtlasFrame] = &[AtlasFrame::new([0, 96, 32, 32], 100, SHEET_SIZE)];
+
+pub const GAME_OVER: &[AtlasFrame] = &[AtlasFrame::new([0, 128, 400, 64], 100, SHEET_SIZE)];
+
+pub const SHIP: &[AtlasFrame] = &[
+    AtlasFrame::new([0, 192, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([32, 192, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([64, 192, 32, 32], 100, SHEET_SIZE),
+    AtlasFrame::new([96, 192, 32, 32], 100, SHEET_SIZE),
+];
+
+pub const YOU_WIN: &[AtlasFrame] = &[AtlasFrame::new([0, 224, 400, 64], 100, SHEET_SIZE)];
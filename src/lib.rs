@@ -1,15 +1,34 @@
 pub mod app;
+pub mod camera;
+pub mod color;
+pub mod debug_lines;
+#[cfg(feature = "editor")]
 pub mod editor;
 pub mod game;
+pub mod gamepads;
 pub mod generated;
 pub mod ktx;
+pub mod lut;
 pub mod model_manifest;
+pub mod prelude;
+pub mod quad;
+pub mod random;
 pub mod renderer;
+pub mod sequences;
 pub mod shaders;
+pub mod spatial_index;
+pub mod sprite_batcher;
+pub mod sprite_pages;
+pub mod sprites;
+pub mod text;
+pub mod time_control;
+pub mod timeline;
+pub mod update_thread;
 pub mod util;
 
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, feature = "shader-compiler"))]
 mod shader_watcher;
 
 pub use game::*;
+#[cfg(feature = "shader-compiler")]
 pub use shaders::build_tasks;
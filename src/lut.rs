@@ -0,0 +1,370 @@
+//! Loading of `.cube` color lookup tables (<https://kono.phpage.fr/images/a/a1/Adobe-cube-lut-specification-1.0.pdf>)
+//!
+//! Both 1D and 3D tables are supported, but not files combining a 1D shaper
+//! with a 3D table. Tables are uploaded as RGBA16F strips; sample them with the
+//! helpers in `shaders/lib/color_grading.slang`, or grade the whole scene with
+//! [`ColorGrading`](crate::renderer::ColorGrading).
+
+use std::path::Path;
+
+use anyhow::Context;
+use ash::vk;
+
+/// A parsed `.cube` file, ready for upload via
+/// [`Renderer::create_lut_texture`](crate::renderer::Renderer::create_lut_texture).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeLut {
+    pub title: Option<String>,
+    pub dimension: LutDimension,
+    /// the number of entries along each axis
+    pub size: u32,
+    /// the input values mapped to the first entry of each axis
+    pub domain_min: [f32; 3],
+    /// the input values mapped to the last entry of each axis
+    pub domain_max: [f32; 3],
+    /// output colors, red varying fastest, then green, then blue
+    pub table: Vec<[f32; 3]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutDimension {
+    OneD,
+    ThreeD,
+}
+
+impl CubeLut {
+    /// The extent of the texture strip the table is uploaded as.
+    /// A 3D table lays its blue slices side by side: `size * size` by `size`.
+    pub fn strip_extent(&self) -> vk::Extent2D {
+        match self.dimension {
+            LutDimension::OneD => vk::Extent2D {
+                width: self.size,
+                height: 1,
+            },
+            LutDimension::ThreeD => vk::Extent2D {
+                width: self.size * self.size,
+                height: self.size,
+            },
+        }
+    }
+
+    /// Errors when the strip is wider than the device's largest 2D image,
+    /// which a 3D table reaches long before its size limit
+    pub fn check_strip_fits(&self, max_image_dimension_2d: u32) -> anyhow::Result<()> {
+        let extent = self.strip_extent();
+        anyhow::ensure!(
+            extent.width <= max_image_dimension_2d && extent.height <= max_image_dimension_2d,
+            "a {}-entry table is a {}x{} strip, past the device's largest 2D image ({})",
+            self.size,
+            extent.width,
+            extent.height,
+            max_image_dimension_2d
+        );
+        Ok(())
+    }
+
+    /// The table as tightly packed RGBA16F texels, in strip order
+    pub fn strip_texels(&self) -> Vec<u8> {
+        let size = self.size as usize;
+        let mut bytes = Vec::with_capacity(self.table.len() * 8);
+        let mut push_texel = |rgb: [f32; 3]| {
+            for channel in [rgb[0], rgb[1], rgb[2], 1.0] {
+                bytes.extend_from_slice(&f32_to_f16_bits(channel).to_le_bytes());
+            }
+        };
+
+        match self.dimension {
+            LutDimension::OneD => self.table.iter().copied().for_each(push_texel),
+            LutDimension::ThreeD => {
+                // strip rows are green; within a row, blue slices of red
+                for g in 0..size {
+                    for b in 0..size {
+                        for r in 0..size {
+                            push_texel(self.table[r + g * size + b * size * size]);
+                        }
+                    }
+                }
+            }
+        }
+
+        bytes
+    }
+}
+
+pub fn load_cube(file_path: &Path) -> anyhow::Result<CubeLut> {
+    let text = std::fs::read_to_string(file_path)
+        .with_context(|| format!("failed to read cube file: {file_path:?}"))?;
+
+    parse_cube(&text).with_context(|| format!("failed to parse cube file: {file_path:?}"))
+}
+
+pub fn parse_cube(text: &str) -> anyhow::Result<CubeLut> {
+    let mut title = None;
+    let mut size_1d = None;
+    let mut size_3d = None;
+    let mut domain_min = [0.0; 3];
+    let mut domain_max = [1.0; 3];
+    let mut table = vec![];
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let is_data =
+            keyword.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'));
+        if !is_data {
+            anyhow::ensure!(
+                table.is_empty(),
+                "line {line_number}: keyword {keyword} after table data"
+            );
+        }
+
+        match keyword {
+            "TITLE" => title = Some(rest.trim_matches('"').to_string()),
+            "LUT_1D_SIZE" => size_1d = Some(parse_size(rest, 2..=65536, line_number)?),
+            "LUT_3D_SIZE" => size_3d = Some(parse_size(rest, 2..=256, line_number)?),
+            "DOMAIN_MIN" => domain_min = parse_triple(rest, line_number)?,
+            "DOMAIN_MAX" => domain_max = parse_triple(rest, line_number)?,
+            // Resolve's single-value form of the domain
+            "LUT_1D_INPUT_RANGE" | "LUT_3D_INPUT_RANGE" => {
+                let [min, max] = parse_floats::<2>(rest, line_number)?;
+                domain_min = [min; 3];
+                domain_max = [max; 3];
+            }
+            _ if is_data => table.push(parse_triple(line, line_number)?),
+            _ => log::warn!("line {line_number}: skipping unknown cube keyword {keyword}"),
+        }
+    }
+
+    let (dimension, size, expected_len) = match (size_1d, size_3d) {
+        (Some(size), None) => (LutDimension::OneD, size, size as usize),
+        (None, Some(size)) => (LutDimension::ThreeD, size, (size as usize).pow(3)),
+        (Some(_), Some(_)) => anyhow::bail!("1D shaper tables with a 3D table are unsupported"),
+        (None, None) => anyhow::bail!("missing LUT_1D_SIZE or LUT_3D_SIZE"),
+    };
+
+    anyhow::ensure!(
+        table.len() == expected_len,
+        "expected {expected_len} table entries for size {size}, found {}",
+        table.len()
+    );
+    anyhow::ensure!(
+        (0..3).all(|i| domain_min[i] < domain_max[i]),
+        "domain min {domain_min:?} must be less than domain max {domain_max:?}"
+    );
+
+    Ok(CubeLut {
+        title,
+        dimension,
+        size,
+        domain_min,
+        domain_max,
+        table,
+    })
+}
+
+fn parse_size(
+    rest: &str,
+    range: std::ops::RangeInclusive<u32>,
+    line_number: usize,
+) -> anyhow::Result<u32> {
+    let size: u32 = rest
+        .parse()
+        .with_context(|| format!("line {line_number}: invalid size {rest:?}"))?;
+    anyhow::ensure!(
+        range.contains(&size),
+        "line {line_number}: size {size} outside {range:?}"
+    );
+
+    Ok(size)
+}
+
+fn parse_triple(rest: &str, line_number: usize) -> anyhow::Result<[f32; 3]> {
+    parse_floats::<3>(rest, line_number)
+}
+
+fn parse_floats<const N: usize>(rest: &str, line_number: usize) -> anyhow::Result<[f32; N]> {
+    let values = rest
+        .split_whitespace()
+        .map(|value| {
+            value
+                .parse::<f32>()
+                .with_context(|| format!("line {line_number}: invalid number {value:?}"))
+        })
+        .collect::<anyhow::Result<Vec<f32>>>()?;
+
+    values.try_into().map_err(|values: Vec<f32>| {
+        anyhow::anyhow!(
+            "line {line_number}: expected {N} numbers, found {}",
+            values.len()
+        )
+    })
+}
+
+/// Converts to IEEE half precision, rounding to nearest
+pub(crate) fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    // infinity or nan
+    if exponent == 0xff {
+        let nan_bit = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan_bit;
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    if half_exponent <= 0 {
+        // too small even for a subnormal
+        if half_exponent < -10 {
+            return sign;
+        }
+        let full_mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let round = (full_mantissa >> (shift - 1)) & 1;
+        return sign | ((full_mantissa >> shift) + round) as u16;
+    }
+
+    // a rounding carry out of the mantissa correctly bumps the exponent
+    let half = sign | ((half_exponent as u16) << 10) | (mantissa >> 13) as u16;
+    let round = ((mantissa >> 12) & 1) as u16;
+    half + round
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTITY_3D: &str = "\
+# identity
+TITLE \"Identity\"
+LUT_3D_SIZE 2
+DOMAIN_MIN 0 0 0
+DOMAIN_MAX 1 1 1
+
+0 0 0
+1 0 0
+0 1 0
+1 1 0
+0 0 1
+1 0 1
+0 1 1
+1 1 1
+";
+
+    #[test]
+    fn parses_3d_table() {
+        let lut = parse_cube(IDENTITY_3D).unwrap();
+
+        assert_eq!(lut.title.as_deref(), Some("Identity"));
+        assert_eq!(lut.dimension, LutDimension::ThreeD);
+        assert_eq!(lut.size, 2);
+        assert_eq!(lut.table.len(), 8);
+        assert_eq!(lut.table[1], [1.0, 0.0, 0.0]);
+        assert_eq!(
+            lut.strip_extent(),
+            vk::Extent2D {
+                width: 4,
+                height: 2
+            }
+        );
+    }
+
+    #[test]
+    fn strip_lays_out_blue_slices_side_by_side() {
+        let lut = parse_cube(IDENTITY_3D).unwrap();
+        let texels = lut.strip_texels();
+        assert_eq!(texels.len(), 4 * 2 * 8);
+
+        let red_at = |x: usize, y: usize| {
+            let offset = (y * 4 + x) * 8;
+            u16::from_le_bytes([texels[offset], texels[offset + 1]])
+        };
+        let blue_at = |x: usize, y: usize| {
+            let offset = (y * 4 + x) * 8 + 4;
+            u16::from_le_bytes([texels[offset], texels[offset + 1]])
+        };
+
+        // (r=1, g=0, b=0)
+        assert_eq!((red_at(1, 0), blue_at(1, 0)), (0x3c00, 0));
+        // (r=0, g=0, b=1) starts the second slice
+        assert_eq!((red_at(2, 0), blue_at(2, 0)), (0, 0x3c00));
+    }
+
+    #[test]
+    fn parses_1d_table_with_input_range() {
+        let lut = parse_cube("LUT_1D_SIZE 3\nLUT_1D_INPUT_RANGE 0 2\n0 0 0\n0.5 0.5 0.5\n1 1 1\n")
+            .unwrap();
+
+        assert_eq!(lut.dimension, LutDimension::OneD);
+        assert_eq!(lut.domain_max, [2.0; 3]);
+        assert_eq!(
+            lut.strip_extent(),
+            vk::Extent2D {
+                width: 3,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn oversized_strips_are_errors() {
+        let lut = CubeLut {
+            title: None,
+            dimension: LutDimension::ThreeD,
+            size: 128,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            table: vec![],
+        };
+        // 16384 wide
+        assert!(lut.check_strip_fits(16384).is_ok());
+        assert!(lut.check_strip_fits(4096).is_err());
+
+        let lut = CubeLut {
+            dimension: LutDimension::OneD,
+            size: 65536,
+            ..lut
+        };
+        assert!(lut.check_strip_fits(16384).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_tables() {
+        // missing an entry
+        assert!(parse_cube("LUT_1D_SIZE 2\n0 0 0\n").is_err());
+        // missing a size
+        assert!(parse_cube("0 0 0\n1 1 1\n").is_err());
+        // too few channels
+        assert!(parse_cube("LUT_1D_SIZE 2\n0 0\n1 1 1\n").is_err());
+        // keyword after data
+        assert!(parse_cube("LUT_1D_SIZE 2\n0 0 0\nTITLE \"late\"\n1 1 1\n").is_err());
+        // empty domain
+        assert!(parse_cube("LUT_1D_SIZE 2\nDOMAIN_MIN 1 1 1\n0 0 0\n1 1 1\n").is_err());
+    }
+
+    #[test]
+    fn converts_to_half_precision() {
+        assert_eq!(f32_to_f16_bits(0.0), 0x0000);
+        assert_eq!(f32_to_f16_bits(-0.0), 0x8000);
+        assert_eq!(f32_to_f16_bits(1.0), 0x3c00);
+        assert_eq!(f32_to_f16_bits(0.5), 0x3800);
+        assert_eq!(f32_to_f16_bits(-2.0), 0xc000);
+        assert_eq!(f32_to_f16_bits(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16_bits(1.0e6), 0x7c00);
+        // smallest subnormal
+        assert_eq!(f32_to_f16_bits(2.0f32.powi(-24)), 0x0001);
+        assert_eq!(f32_to_f16_bits(1.0e-10), 0x0000);
+        assert!(f32_to_f16_bits(f32::NAN) & 0x7fff > 0x7c00);
+    }
+}
@@ -0,0 +1,20 @@
+//! The types most games need, for a single glob import:
+//!
+//! ```ignore
+//! use vulkan_slang_renderer::prelude::*;
+//! ```
+//!
+//! Generated shader modules are imported separately,
+//! since each game picks its own.
+
+pub use crate::debug_lines::DebugLines;
+pub use crate::game::{Game, Input, InputMap, Key, KeyboardState, MouseButton};
+pub use crate::generated::shader_atlas::ShaderAtlas;
+pub use crate::renderer::{
+    DrawError, DrawIndexed, DrawVertexCount, FrameRenderer, Gpu, MeshHandle, PipelineHandle,
+    Renderer, StorageBufferHandle, StorageTextureHandle, TextureFilter, TextureHandle,
+    TextureOptions, TextureWrap, UniformBufferHandle,
+};
+pub use crate::sprite_batcher::{SpriteBatcher, SpriteTexture};
+pub use crate::text::{GlyphAtlasMode, TextBrush};
+pub use crate::util::{load_image, manifest_path};
@@ -0,0 +1,37 @@
+//! Quads drawn without a vertex or index buffer, the cpu side of `shaders/lib/quad.slang`.
+//!
+//! A quad shader's vertex entry point takes only `SV_VertexID`, and
+//! `quadVertex(id)` finds the vertex's quad and corner, so drawing `n` quads
+//! takes [`quad_vertex_count`]`(n)` vertices.
+
+/// the corners of the 2 triangles making up a quad;
+/// `VERTICES_PER_QUAD` in `quad.slang`
+pub const VERTICES_PER_QUAD: u32 = 6;
+
+/// The vertex count that draws `quad_count` quads
+pub const fn quad_vertex_count(quad_count: u32) -> u32 {
+    quad_count * VERTICES_PER_QUAD
+}
+
+/// The first vertex and vertex count that draw `quad_count` quads
+/// starting at `first_quad`, for a vertex range draw
+pub const fn quad_vertex_range(first_quad: u32, quad_count: u32) -> (u32, u32) {
+    (quad_vertex_count(first_quad), quad_vertex_count(quad_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_counts_match_the_slang_module() {
+        let source = include_str!("../shaders/lib/quad.slang");
+        assert!(source.contains(&format!(
+            "public static const uint VERTICES_PER_QUAD = {VERTICES_PER_QUAD};"
+        )));
+
+        assert_eq!(quad_vertex_count(0), 0);
+        assert_eq!(quad_vertex_count(3), 18);
+        assert_eq!(quad_vertex_range(2, 5), (12, 30));
+    }
+}
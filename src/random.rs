@@ -0,0 +1,206 @@
+//! A small seeded random number generator, for gameplay and effects that
+//! should replay the same way from the same seed.
+//!
+//! [`Rng`] is a SplitMix64 generator: fast, tiny to copy into a save or
+//! replay, and not suitable for anything security-related.
+//!
+//! ```ignore
+//! let mut rng = Rng::new(0);
+//! sprite.position = rng.vec2_range(Vec2::ZERO, window_size);
+//! sprite.rotation = rng.f32() * TAU;
+//! sprite.color = rng.color();
+//! ```
+
+use std::f32::consts::TAU;
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use glam::{Vec2, Vec3, Vec4};
+
+use crate::color::srgb_to_linear;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seeded from the system clock, for runs that don't need to replay
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    /// The generator's whole state; `Rng::new(rng.state())` continues the same stream
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        (self.u64() >> 32) as u32
+    }
+
+    /// Uniform in [0, n)
+    pub fn below(&mut self, n: u32) -> u32 {
+        assert!(n > 0, "empty random range");
+
+        // Lemire's multiply-and-shift, rejecting the few values that would bias it
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let product = self.u32() as u64 * n as u64;
+            if product as u32 >= threshold {
+                return (product >> 32) as u32;
+            }
+        }
+    }
+
+    /// Uniform in the range; panics if it's empty
+    pub fn u32_range(&mut self, range: Range<u32>) -> u32 {
+        assert!(range.start < range.end, "empty random range");
+        range.start + self.below(range.end - range.start)
+    }
+
+    /// Uniform in the range; panics if it's empty
+    pub fn i32_range(&mut self, range: Range<i32>) -> i32 {
+        assert!(range.start < range.end, "empty random range");
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add_unsigned(self.below(span))
+    }
+
+    /// An index into a collection of `len` items
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u32) as usize
+    }
+
+    /// Uniform in [0, 1)
+    pub fn f32(&mut self) -> f32 {
+        (self.u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Uniform in [start, end)
+    pub fn f32_range(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.f32()
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.u64() >> 63 == 1
+    }
+
+    /// True with probability `p`, from 0 to 1
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.f32() < p
+    }
+
+    /// Uniform in the box between the corners
+    pub fn vec2_range(&mut self, min: Vec2, max: Vec2) -> Vec2 {
+        Vec2::new(self.f32_range(min.x..max.x), self.f32_range(min.y..max.y))
+    }
+
+    /// A direction, uniform around the unit circle
+    pub fn unit_vec2(&mut self) -> Vec2 {
+        Vec2::from_angle(self.f32() * TAU)
+    }
+
+    /// A direction, uniform over the unit sphere
+    pub fn unit_vec3(&mut self) -> Vec3 {
+        let z = self.f32_range(-1.0..1.0);
+        let radius = (1.0 - z * z).sqrt();
+        let xy = self.unit_vec2() * radius;
+        Vec3::new(xy.x, xy.y, z)
+    }
+
+    /// An opaque linear color, with channels uniform in sRGB so darks and
+    /// lights come up about as often as they look like they should
+    pub fn color(&mut self) -> Vec4 {
+        let mut channel = || srgb_to_linear(self.f32());
+        Vec4::new(channel(), channel(), channel(), 1.0)
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        Some(&items[self.index(items.len())])
+    }
+
+    /// Fisher-Yates, in place
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_seed_replays_the_same_values() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let a_values: Vec<_> = (0..8).map(|_| a.u64()).collect();
+        let b_values: Vec<_> = (0..8).map(|_| b.u64()).collect();
+        assert_eq!(a_values, b_values);
+
+        let mut resumed = Rng::new(a.state());
+        assert_eq!(resumed.u64(), a.u64());
+        assert_ne!(Rng::new(8).u64(), Rng::new(7).u64());
+    }
+
+    #[test]
+    fn ranges_stay_in_bounds() {
+        let mut rng = Rng::new(0);
+        let mut seen = [false; 5];
+        for _ in 0..1000 {
+            let value = rng.u32_range(10..15);
+            assert!((10..15).contains(&value));
+            seen[(value - 10) as usize] = true;
+
+            assert!((-3..2).contains(&rng.i32_range(-3..2)));
+            assert!((0.0..1.0).contains(&rng.f32()));
+            assert!((2.0..4.0).contains(&rng.f32_range(2.0..4.0)));
+        }
+        assert_eq!(seen, [true; 5]);
+        assert_eq!(rng.i32_range(i32::MIN..i32::MIN + 1), i32::MIN);
+    }
+
+    #[test]
+    fn unit_vectors_are_normalized() {
+        let mut rng = Rng::new(1);
+        for _ in 0..100 {
+            assert!(rng.unit_vec2().is_normalized());
+            assert!(rng.unit_vec3().is_normalized());
+            let color = rng.color();
+            assert_eq!(color.w, 1.0);
+            assert!(color.truncate().cmple(Vec3::ONE).all());
+        }
+    }
+
+    #[test]
+    fn shuffles_keep_every_item() {
+        let mut rng = Rng::new(2);
+        let mut items: Vec<_> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+
+        assert_eq!(rng.choose::<u8>(&[]), None);
+        assert_eq!(rng.choose(&[3]), Some(&3));
+    }
+}
@@ -1,25 +1,28 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::ffi::{CStr, CString, c_char};
 use std::fs::File;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use ash::vk;
-use glam::Vec2;
+use glam::{Vec2, Vec3};
 use sdl3::sys::vulkan::SDL_Vulkan_DestroySurface;
 use sdl3::video::Window;
+use serde::Serialize;
 use vk_mem::Alloc as _;
 
-use crate::game::MaxMSAASamples;
+#[cfg(feature = "editor")]
+use crate::editor::scene_overlay::SceneOverlay;
+use crate::game::{LaunchOptions, MaxMSAASamples, Presentation, ViewMode};
 use crate::shaders;
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, PrecompiledShader, ShaderAtlasEntry};
 
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, feature = "shader-compiler"))]
 use crate::shader_watcher;
-use crate::shaders::json::ReflectedDescriptorSetLayout;
-#[cfg(debug_assertions)]
+use crate::shaders::json::{GlobalParameter, ReflectedDescriptorSetLayout, global_descriptor_sets};
+#[cfg(all(debug_assertions, feature = "shader-compiler"))]
 use log::*;
 
 pub mod debug;
@@ -37,25 +40,159 @@ pub use texture::*;
 pub mod uniform_buffer;
 pub use uniform_buffer::*;
 
+pub mod frame_globals;
+pub use frame_globals::FrameGlobals;
+use frame_globals::FrameGlobalsState;
+
+pub mod hdr;
+pub use hdr::{DisplayLuminance, HdrOutput};
+
 pub mod storage_buffer;
 pub use storage_buffer::*;
 
+pub mod counter_buffer;
+pub use counter_buffer::CounterBufferHandle;
+use counter_buffer::CounterBufferStorage;
+
 pub mod addr;
 pub use addr::*;
 
 pub mod storage_texture;
 pub use storage_texture::*;
 
+pub mod feedback_texture;
+pub use feedback_texture::*;
+
+pub mod render_target;
+pub use render_target::{RenderTarget, RenderTargetHandle};
+
+pub mod external_memory;
+pub use external_memory::{
+    ExternalImage, ExternalMemoryHandle, ExternalSemaphore, ExternalSemaphoreHandle,
+};
+
+pub mod device_extensions;
+pub use device_extensions::DeviceExtensions;
+
+pub mod device_profile;
+pub use device_profile::DeviceProfile;
+
+pub mod settings;
+pub use settings::RendererSettings;
+
+pub mod acceleration_structure;
+pub use acceleration_structure::{AccelerationStructureHandle, BlasHandle, TlasInstance};
+use acceleration_structure::{AccelerationStructureStorage, RayQuery};
+
 pub mod pipeline;
 pub use pipeline::*;
 
+pub mod clip;
+pub use clip::ClipRect;
+use clip::ClipStack;
+
+pub mod render_pass;
+pub use render_pass::RenderPass;
+
+pub mod shading_rate;
+pub use shading_rate::{FragmentSize, ShadingRate};
+
+#[cfg(feature = "editor")]
 pub mod egui;
+#[cfg(feature = "editor")]
 pub use egui::EguiIntegration;
 
+#[cfg(feature = "editor")]
 pub mod facet_egui;
 
+pub mod frame_stats;
+pub use frame_stats::{FrameStats, PipelineDrawStats};
+
+pub mod leak_report;
+pub use leak_report::LeakReport;
+
+pub mod determinism;
+pub use determinism::{AuditedBuffer, Divergence};
+use determinism::{DeterminismAudit, WrittenRange};
+
+pub mod command_log;
+pub use command_log::FrameLog;
+
+pub mod null_renderer;
+pub use null_renderer::{
+    CreateResources, NullFrameRenderer, NullGpu, NullRenderer, QueueDraws, WriteBuffers,
+};
+
+#[cfg(debug_assertions)]
+mod layout_tracker;
+#[cfg(debug_assertions)]
+use layout_tracker::{BoundImage, COPY_READ_STAGES, DRAW_READ_STAGES, LayoutTracker};
+
+mod gpu_context;
+use gpu_context::GpuContext;
+
+mod window_presenter;
+use window_presenter::WindowPresenter;
+
+mod swapchain;
+use swapchain::{Acquire, FrameEnd, FrameSlots, Swapchain};
+
+mod present_wait;
+use present_wait::PresentWait;
+
+pub mod blit;
+pub use blit::{BlitOptions, BlitPipeline, SrgbConversion, blit_flags};
+
+pub mod color_grading;
+pub use color_grading::ColorGrading;
+
+pub mod buffer_inspector;
+pub use buffer_inspector::{BufferLayout, FieldValue, InspectableBuffer, InspectedField};
+
+mod breadcrumbs;
+use breadcrumbs::{Breadcrumb, BreadcrumbPass, BreadcrumbQueue, Breadcrumbs};
+
 mod picking;
-use picking::PickingResources;
+pub use picking::{MAX_PICKING_RADIUS, PickingPolicy};
+use picking::{PickingResources, PickingWindow};
+
+mod post_process;
+use post_process::{PostProcessTargets, SceneColorWrite};
+
+mod rcas_upscale;
+use rcas_upscale::RcasUpscale;
+
+mod frame_capture;
+use frame_capture::FrameCapture;
+
+mod frame_dump;
+use frame_dump::DumpFormat;
+
+pub mod pixel_debug;
+use pixel_debug::PixelDebugger;
+
+mod readback;
+pub use pixel_debug::{PixelDebugSample, PixelDebugTarget};
+use readback::{Delivery, ReadbackRequest, ReadbackSource, Readbacks};
+pub use readback::{Readback, ReadbackCallback, ReadbackId, ReadbackImage};
+
+mod split_screen;
+pub(crate) use split_screen::SPLIT_SCREEN_VIEWS_TYPE_NAME;
+use split_screen::SplitScreenState;
+pub use split_screen::{MAX_SPLIT_VIEWS, SplitScreenViews, SplitView, ViewportRect};
+
+pub mod taa;
+use taa::TaaHistory;
+pub use taa::{Taa, TaaFrame};
+
+pub mod texture_inspector;
+use texture_inspector::TextureInspector;
+pub use texture_inspector::{
+    InspectableImage, InspectedImage, InspectorSnapshot, InspectorTarget, InspectorView,
+};
+
+pub mod velocity;
+use velocity::VelocityTargets;
 
 /// enables both the validation layer and debug utils logging
 const ENABLE_VALIDATION: bool = cfg!(debug_assertions);
@@ -79,7 +216,8 @@ const COLOR_SUBRESOURCE_RANGE: vk::ImageSubresourceRange = vk::ImageSubresourceR
     base_mip_level: 0,
     level_count: 1,
     base_array_layer: 0,
-    layer_count: 1,
+    // the main pass targets have a layer per view in stereo
+    layer_count: vk::REMAINING_ARRAY_LAYERS,
 };
 
 pub struct Renderer {
@@ -88,42 +226,34 @@ pub struct Renderer {
     width: f32,
     height: f32,
     total_frames: usize,
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
     shader_changes: shader_watcher::ShaderChanges,
+    /// recompile the next frame's shaders, edited or not; see request_shader_reload
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
+    shader_reload_requested: bool,
+    /// the layout each image was last transitioned to, and the storage writes
+    /// not yet behind a barrier, in recording order;
+    /// a RefCell because commands are recorded through &self
     #[cfg(debug_assertions)]
+    layout_tracker: std::cell::RefCell<LayoutTracker>,
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
     old_pipelines: Vec<(
         usize,
         vk::Pipeline,
         ash::vk::PipelineLayout,
         Vec<ash::vk::DescriptorSetLayout>,
     )>,
-    #[expect(unused)]
-    entry: ash::Entry,
-    window: Window,
-    instance: ash::Instance,
-    debug_ext: vk::DebugUtilsMessengerEXT,
-    surface_ext: ash::khr::surface::Instance,
-    debug_loader: ash::ext::debug_utils::Instance,
-    surface: vk::SurfaceKHR,
-    physical_device: vk::PhysicalDevice,
-    physical_device_properties: vk::PhysicalDeviceProperties,
-    queue_family_indices: QueueFamilyIndices,
-    device: ash::Device,
-    /// ManuallyDrop because Renderer has a manual Drop impl: the allocator must be
-    /// destroyed after all buffers/images are freed but before destroy_device.
-    allocator: std::mem::ManuallyDrop<vk_mem::Allocator>,
-    debug_utils_device: ash::ext::debug_utils::Device,
-    graphics_queue: vk::Queue,
-    presentation_queue: vk::Queue,
-    swapchain_device_ext: ash::khr::swapchain::Device,
+    /// the instance, device, queues, and GPU resources; nothing window-specific
+    gpu: GpuContext,
+    /// the window's surface and swapchain
+    presenter: WindowPresenter,
     msaa_samples: vk::SampleCountFlags,
+    /// the main pass's color, depth and resolve images have one layer per view
+    view_mode: ViewMode,
+    presentation: Presentation,
+    device_profile: DeviceProfile,
 
     // fields that change, at least in theory
-    image_format: vk::Format,
-    image_extent: vk::Extent2D,
-    swapchain: vk::SwapchainKHR,
-    swapchain_images: Vec<vk::Image>,
-    swapchain_image_views: Vec<vk::ImageView>,
     depth_format: vk::Format,
     color_image: vk::Image,
     color_image_memory: vk_mem::Allocation,
@@ -132,32 +262,27 @@ pub struct Renderer {
     depth_image_memory: vk_mem::Allocation,
     depth_image_view: vk::ImageView,
 
-    /// resolve images for upscaling, indexed by flight_slot
     render_scale: f32,
     render_extent: vk::Extent2D,
-    resolve_images: [vk::Image; MAX_FRAMES_IN_FLIGHT],
-    resolve_image_memories: [vk_mem::Allocation; MAX_FRAMES_IN_FLIGHT],
-    resolve_image_views: [vk::ImageView; MAX_FRAMES_IN_FLIGHT],
+    /// where the render extent lands in the swapchain image; all of it, unless
+    /// a virtual resolution leaves borders
+    present_rect: vk::Rect2D,
+    upscale_filter: UpscaleFilter,
+    /// created the first time UpscaleFilter::Sharpened is set
+    rcas_upscale: Option<RcasUpscale>,
+    /// the main pass's single-sample output, upscaled to the swapchain or
+    /// sampled as scene color by the post-process pass; indexed by ring_slot,
+    /// like the descriptor sets that sample it
+    resolve_images: [vk::Image; PRE_WAIT_RING_LEN],
+    resolve_image_memories: [vk_mem::Allocation; PRE_WAIT_RING_LEN],
+    resolve_image_views: [vk::ImageView; PRE_WAIT_RING_LEN],
 
     command_pool: vk::CommandPool,
     command_buffers: [vk::CommandBuffer; MAX_FRAMES_IN_FLIGHT],
-    /// image semaphores indexed by ring_slot
-    image_available: [vk::Semaphore; PRE_WAIT_RING_LEN],
-    /// render finished semaphores indexed by image_index
-    /// ie, one per swapchain image, not per frame-in-flight
-    render_finished: Vec<vk::Semaphore>,
     /// timeline semaphore: the graphics submit for frame N signals value N (= total_frames)
     frame_timeline: vk::Semaphore,
-    /// looping index for wait-guarded per-flight resources:
-    ///   command buffers (graphics + compute), resolve images,
-    ///   picking readback, egui texture frees
-    /// (0..MAX_FRAMES_IN_FLIGHT)
-    flight_slot: usize,
-    /// looping index for the pre-wait ring:
-    ///   per-frame buffers, acquire semaphores, and the descriptor
-    ///   sets that reference each slot's buffers
-    /// (0..PRE_WAIT_RING_LEN)
-    ring_slot: usize,
+    /// the looping flight and ring indices of the frame being drawn
+    slots: FrameSlots,
 
     /// timeline semaphore: the k-th compute-signaling submit signals value k
     compute_timeline: vk::Semaphore,
@@ -165,47 +290,174 @@ pub struct Renderer {
     compute_frames: u64,
     has_compute_pipelines: bool,
 
-    /// Dedicated compute queue for async compute (None = single-queue fallback)
-    compute_queue: Option<vk::Queue>,
     /// Command buffers for pipelined compute dispatches
     compute_command_buffers: [vk::CommandBuffer; MAX_FRAMES_IN_FLIGHT],
     /// Use pipelined compute submission; set once during Game::setup via
     /// Renderer::enable_pipelined_compute
     pipelined_compute: bool,
 
-    pipelines: PipelineStorage,
-    compute_pipelines: ComputePipelineStorage,
-    /// meshes shared between pipelines; freed only at renderer teardown
-    meshes: Vec<VertexAndIndexBuffers>,
-    textures: TextureStorage,
-    storage_textures: StorageTextureStorage,
-    uniform_buffers: UniformBufferStorage,
-    storage_buffers: StorageBufferStorage,
-
+    feedback_textures: FeedbackTextureStorage,
+    /// texture updates to record at the start of the next frame's commands
+    pending_texture_updates: Vec<PendingTextureUpdate>,
+    /// imported textures to re-acquire from their producers in the next frame
+    pending_external_acquires: Vec<PendingExternalAcquire>,
+    /// uniform buffer descriptors to rewrite as each ring slot comes around
+    pending_uniform_rebinds: Vec<PendingUniformRebind>,
+    /// staging buffers of recorded texture updates, indexed by flight_slot
+    texture_update_staging: [Vec<(vk::Buffer, vk_mem::Allocation)>; MAX_FRAMES_IN_FLIGHT],
+    /// hashes the buffers written each frame; see record_determinism_hashes
+    determinism_audit: Option<DeterminismAudit>,
+    /// the directory to dump the next frame to; see dump_next_frame
+    pending_frame_dump: Option<PathBuf>,
+    /// the buffers dumped through their types' Serialize impls
+    frame_dump_formats: HashMap<AuditedBuffer, DumpFormat>,
+    /// every frame's submitted commands since record_commands
+    recorded_commands: Option<Vec<FrameLog>>,
+
+    #[cfg(feature = "editor")]
     egui: Option<EguiIntegration>,
+    #[cfg(feature = "editor")]
     text_input_active: bool,
+    /// the editor's grid and gizmo lines, created with the first edit ui;
+    /// drawn after the game's draws each frame
+    #[cfg(feature = "editor")]
+    scene_overlay: Option<SceneOverlay>,
 
     picking: Option<PickingResources>,
+    picking_policy: PickingPolicy,
+    /// the cameras split-screen pipelines draw with, and the buffer shaders read
+    /// them from; no cameras draws those pipelines once, over everything
+    split_screen: SplitScreenState,
+    readbacks: Readbacks,
+    frame_stats: FrameStats,
+    /// created with the first post-process pipeline
+    post_process: Option<PostProcessTargets>,
+    /// TextureStorage indices of the scene color aliases
+    scene_color_texture_indices: Vec<usize>,
+    /// created with the first TAA history texture
+    taa_history: Option<TaaHistory>,
+    /// TextureStorage indices of the TAA history aliases
+    taa_history_texture_indices: Vec<usize>,
+    /// the main pass's second color target, when the game asked for one
+    velocity: Option<VelocityTargets>,
+    /// TextureStorage indices of the velocity aliases
+    velocity_texture_indices: Vec<usize>,
+    /// the buffer shaders declaring FrameGlobals read; see frame_globals
+    frame_globals: FrameGlobalsState,
+    /// debug: tonemap HDR output as if the display had no headroom
+    hdr_sdr_preview: bool,
+    /// set by request_redraw, for RedrawMode::OnDemand
+    redraw_requested: bool,
     last_picked_object_id: u32,
+    frame_capture: FrameCapture,
+    /// whether the validation layer's debug printf is enabled
+    shader_printf: bool,
+    pixel_debug: PixelDebugger,
+    texture_inspector: TextureInspector,
 }
 
-fn calculate_render_extent(display_extent: vk::Extent2D, render_scale: f32) -> vk::Extent2D {
+/// The extent of a single view: a virtual resolution's own, or the render
+/// scale's fraction of the display; side-by-side stereo splits the display's width
+fn calculate_render_extent(
+    display_extent: vk::Extent2D,
+    render_scale: f32,
+    view_mode: ViewMode,
+    presentation: Presentation,
+) -> vk::Extent2D {
+    if let Presentation::Virtual { width, height, .. } = presentation {
+        return vk::Extent2D {
+            width: width.max(1),
+            height: height.max(1),
+        };
+    }
+
+    let view_width = display_extent.width / view_mode.view_count();
     vk::Extent2D {
-        width: ((display_extent.width as f32 * render_scale) as u32).max(1),
+        width: ((view_width as f32 * render_scale) as u32).max(1),
         height: ((display_extent.height as f32 * render_scale) as u32).max(1),
     }
 }
 
+/// Where the views land in the display: all of it when stretched, or a
+/// virtual resolution scaled as large as fits (by whole numbers when it can),
+/// centered between borders
+fn calculate_present_rect(
+    display_extent: vk::Extent2D,
+    render_extent: vk::Extent2D,
+    presentation: Presentation,
+) -> vk::Rect2D {
+    let Presentation::Virtual { integer_scale, .. } = presentation else {
+        return vk::Rect2D {
+            offset: vk::Offset2D::default(),
+            extent: display_extent,
+        };
+    };
+
+    let fit = f32::min(
+        display_extent.width as f32 / render_extent.width as f32,
+        display_extent.height as f32 / render_extent.height as f32,
+    );
+    let scale = if integer_scale && fit >= 1.0 {
+        fit.floor()
+    } else {
+        fit
+    };
+
+    let extent = vk::Extent2D {
+        width: ((render_extent.width as f32 * scale) as u32).clamp(1, display_extent.width),
+        height: ((render_extent.height as f32 * scale) as u32).clamp(1, display_extent.height),
+    };
+    let offset = vk::Offset2D {
+        x: ((display_extent.width - extent.width) / 2) as i32,
+        y: ((display_extent.height - extent.height) / 2) as i32,
+    };
+
+    vk::Rect2D { offset, extent }
+}
+
+/// The aspect ratio of a single view: a virtual resolution's own, or the window's
+/// (split between the views)
+fn view_aspect_ratio(
+    window_width: u32,
+    window_height: u32,
+    view_mode: ViewMode,
+    presentation: Presentation,
+) -> f32 {
+    match presentation {
+        Presentation::Virtual { width, height, .. } => width as f32 / height as f32,
+        Presentation::Stretch => {
+            window_width as f32 / view_mode.view_count() as f32 / window_height as f32
+        }
+    }
+}
+
+/// The scale from the present rect's pixels to render pixels: the configured
+/// render scale when stretched, or whatever fits a virtual resolution to the window
+fn presented_render_scale(
+    render_scale: f32,
+    render_extent: vk::Extent2D,
+    present_rect: vk::Rect2D,
+    presentation: Presentation,
+) -> f32 {
+    match presentation {
+        Presentation::Stretch => render_scale,
+        Presentation::Virtual { .. } => {
+            render_extent.width as f32 / present_rect.extent.width as f32
+        }
+    }
+}
+
 fn create_resolve_images(
     allocator: &vk_mem::Allocator,
     device: &ash::Device,
     render_extent: vk::Extent2D,
     color_format: vk::Format,
+    view_mode: ViewMode,
 ) -> Result<
     (
-        [vk::Image; MAX_FRAMES_IN_FLIGHT],
-        [vk_mem::Allocation; MAX_FRAMES_IN_FLIGHT],
-        [vk::ImageView; MAX_FRAMES_IN_FLIGHT],
+        [vk::Image; PRE_WAIT_RING_LEN],
+        [vk_mem::Allocation; PRE_WAIT_RING_LEN],
+        [vk::ImageView; PRE_WAIT_RING_LEN],
     ),
     anyhow::Error,
 > {
@@ -213,16 +465,24 @@ fn create_resolve_images(
         extent: render_extent,
         format: color_format,
         tiling: vk::ImageTiling::OPTIMAL,
-        usage: vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::COLOR_ATTACHMENT,
+        usage: vk::ImageUsageFlags::TRANSFER_SRC
+            | vk::ImageUsageFlags::COLOR_ATTACHMENT
+            | vk::ImageUsageFlags::SAMPLED,
         mip_levels: 1,
+        array_layers: view_mode.view_count(),
         msaa_samples: vk::SampleCountFlags::TYPE_1,
     };
 
-    let results: [_; MAX_FRAMES_IN_FLIGHT] = (0..MAX_FRAMES_IN_FLIGHT)
+    let results: [_; PRE_WAIT_RING_LEN] = (0..PRE_WAIT_RING_LEN)
         .map(|_| -> anyhow::Result<_> {
             let (image, memory) = create_vk_image(allocator, image_options)?;
-            let view =
-                create_image_view(device, image, color_format, vk::ImageAspectFlags::COLOR, 1)?;
+            let view = create_attachment_view(
+                device,
+                image,
+                color_format,
+                vk::ImageAspectFlags::COLOR,
+                view_mode,
+            )?;
             Ok((image, memory, view))
         })
         .collect::<Result<Vec<_>, _>>()?
@@ -237,18 +497,32 @@ fn create_resolve_images(
 }
 
 impl Renderer {
+    /// `render_scale` overrides the device profile's when set;
+    /// `max_msaa_samples` caps it (see [`DeviceProfile`]), and the launch
+    /// options' GPU, fullscreen, and present mode override the rest
     pub fn init(
-        window: Window,
+        mut window: Window,
         enable_egui: bool,
-        render_scale: f32,
+        render_scale: Option<f32>,
         max_msaa_samples: MaxMSAASamples,
+        view_mode: ViewMode,
+        presentation: Presentation,
+        mut device_extensions: DeviceExtensions,
+        shader_printf: bool,
+        velocity_buffer: bool,
+        mut hdr_output: HdrOutput,
+        depth_formats: &[vk::Format],
+        launch_options: &LaunchOptions,
     ) -> Result<Self, anyhow::Error> {
-        let render_scale = render_scale.clamp(0.25, 1.0);
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, feature = "shader-compiler"))]
         let shader_changes = shader_watcher::watch()?;
 
+        if presentation != Presentation::Stretch && view_mode != ViewMode::Mono {
+            anyhow::bail!("a virtual resolution presentation requires the Mono view mode");
+        }
+
         let (window_width, window_height) = window.size();
-        let aspect_ratio = window_width as f32 / window_height as f32;
+        let aspect_ratio = view_aspect_ratio(window_width, window_height, view_mode, presentation);
 
         let entry = ash::Entry::linked();
 
@@ -273,6 +547,23 @@ impl Renderer {
         }
         enabled_extension_names.push(ash::ext::debug_utils::NAME.as_ptr());
 
+        // debug printf is a validation layer feature, so it needs the layer;
+        // VK_KHR_shader_non_semantic_info, for the printf instructions, is core in 1.3
+        let shader_printf = shader_printf && ENABLE_VALIDATION;
+        if shader_printf {
+            enabled_extension_names.push(ash::ext::validation_features::NAME.as_ptr());
+        }
+
+        // HDR color spaces for the swapchain
+        if hdr_output.is_hdr() {
+            if instance_extension_available(&entry, ash::ext::swapchain_colorspace::NAME)? {
+                enabled_extension_names.push(ash::ext::swapchain_colorspace::NAME.as_ptr());
+            } else {
+                log::warn!("VK_EXT_swapchain_colorspace is unavailable; falling back to SDR");
+                hdr_output = HdrOutput::Off;
+            }
+        }
+
         for platform_instance_ext in platform::ADDITIONAL_INSTANCE_EXTENSIONS {
             enabled_extension_names.push(platform_instance_ext.as_ptr());
         }
@@ -293,6 +584,12 @@ impl Renderer {
         if ENABLE_VALIDATION {
             create_info = create_info.push_next(&mut debug_create_info);
         }
+        let printf_features = [vk::ValidationFeatureEnableEXT::DEBUG_PRINTF];
+        let mut validation_features =
+            vk::ValidationFeaturesEXT::default().enabled_validation_features(&printf_features);
+        if shader_printf {
+            create_info = create_info.push_next(&mut validation_features);
+        }
 
         let instance = unsafe { entry.create_instance(&create_info, None)? };
         let (debug_loader, debug_ext) =
@@ -303,9 +600,112 @@ impl Renderer {
         let surface = window.vulkan_create_surface(instance.handle())?;
 
         let (physical_device, queue_family_indices, physical_device_properties) =
-            choose_physical_device(&instance, &surface_ext, surface)?;
-        let device = create_logical_device(&instance, physical_device, &queue_family_indices)?;
+            choose_physical_device(
+                &instance,
+                &surface_ext,
+                surface,
+                launch_options.gpu.as_deref(),
+            )?;
+        let adapter_name = device_name_as_string(physical_device_properties);
+        let mut device_profile = DeviceProfile::detect(device_profile::DeviceInfo {
+            adapter_name: &adapter_name,
+            display_size: display_size(&window),
+            gamescope: device_profile::running_under_gamescope(),
+        });
+        log::info!("device profile: {} ({adapter_name})", device_profile.name);
+        if let Some(fullscreen) = launch_options.fullscreen {
+            device_profile.fullscreen = fullscreen;
+        }
+        if let Some(present_mode) = launch_options.present_mode {
+            device_profile.present_mode = present_mode;
+        }
+        // the window is still hidden; showing it sends the resize
+        if device_profile.fullscreen
+            && let Err(error) = window.set_fullscreen(true)
+        {
+            log::warn!("failed to go fullscreen: {error}");
+        }
+        let render_scale = render_scale
+            .unwrap_or(device_profile.render_scale)
+            .clamp(0.25, 1.0);
+        let max_msaa_samples =
+            device_profile::min_msaa_samples(max_msaa_samples, device_profile.max_msaa_samples);
+        let external_memory_supported = !external_memory::EXTERNAL_MEMORY_DEVICE_EXTENSIONS
+            .is_empty()
+            && check_device_extension_support(
+                &instance,
+                physical_device,
+                external_memory::EXTERNAL_MEMORY_DEVICE_EXTENSIONS,
+            )?;
+        let mut optional_extensions: Vec<&CStr> = if external_memory_supported {
+            external_memory::EXTERNAL_MEMORY_DEVICE_EXTENSIONS.to_vec()
+        } else {
+            vec![]
+        };
+        let mut breadcrumbs_extension = None;
+        if cfg!(debug_assertions) {
+            for name in Breadcrumbs::EXTENSIONS {
+                if check_device_extension_support(&instance, physical_device, &[name])? {
+                    breadcrumbs_extension = Some(name);
+                    optional_extensions.push(name);
+                    break;
+                }
+            }
+        }
+        if !check_device_extension_support(&instance, physical_device, &device_extensions.names)? {
+            anyhow::bail!(
+                "the device doesn't support the requested extensions: {:?}",
+                device_extensions.names
+            );
+        }
+        // pace frames off presentation where the driver can report it
+        let present_wait_supported =
+            check_device_extension_support(
+                &instance,
+                physical_device,
+                &present_wait::PRESENT_WAIT_DEVICE_EXTENSIONS,
+            )? && present_wait::features_supported(&instance, physical_device);
+        if present_wait_supported {
+            optional_extensions.extend(present_wait::PRESENT_WAIT_DEVICE_EXTENSIONS);
+            device_extensions.features.push(Box::new(
+                vk::PhysicalDevicePresentIdFeaturesKHR::default().present_id(true),
+            ));
+            device_extensions.features.push(Box::new(
+                vk::PhysicalDevicePresentWaitFeaturesKHR::default().present_wait(true),
+            ));
+        }
+        // describe the mastering display to compositors that tone map HDR
+        let hdr_metadata_supported = hdr_output.is_hdr()
+            && check_device_extension_support(
+                &instance,
+                physical_device,
+                &[ash::ext::hdr_metadata::NAME],
+            )?;
+        if hdr_metadata_supported {
+            optional_extensions.push(ash::ext::hdr_metadata::NAME);
+        }
+        let device = create_logical_device(
+            &instance,
+            physical_device,
+            &queue_family_indices,
+            &optional_extensions,
+            view_mode,
+            &mut device_extensions,
+        )?;
+        if let Some(post_init) = device_extensions.post_init.take() {
+            post_init(&instance, physical_device, &device)?;
+        }
+        let ray_query = device_extensions
+            .ray_query
+            .then(|| RayQuery::init(&instance, physical_device, &device));
+        let mesh_shader = device_extensions
+            .mesh_shaders
+            .then(|| ash::ext::mesh_shader::Device::new(&instance, &device));
+        let fragment_shading_rate = device_extensions
+            .fragment_shading_rate
+            .then(|| ash::khr::fragment_shading_rate::Device::new(&instance, &device));
         let debug_utils_device = ash::ext::debug_utils::Device::new(&instance, &device);
+        let present_wait = present_wait_supported.then(|| PresentWait::new(&instance, &device));
 
         let allocator = {
             let mut allocator_create_info =
@@ -315,6 +715,9 @@ impl Renderer {
             allocator_create_info.flags |= vk_mem::AllocatorCreateFlags::BUFFER_DEVICE_ADDRESS;
             std::mem::ManuallyDrop::new(unsafe { vk_mem::Allocator::new(allocator_create_info)? })
         };
+        let breadcrumbs = breadcrumbs_extension
+            .map(|extension| Breadcrumbs::init(&instance, &device, &allocator, extension))
+            .transpose()?;
 
         let msaa_samples =
             get_max_usable_sample_count(physical_device_properties, max_msaa_samples);
@@ -331,8 +734,9 @@ impl Renderer {
         let swapchain_device_ext = ash::khr::swapchain::Device::new(&instance, &device);
         let CreatedSwapchain {
             swapchain,
-            image_format,
+            surface_format,
             image_extent,
+            hdr_output: active_hdr_output,
         } = create_swapchain(
             &window,
             &swapchain_device_ext,
@@ -340,7 +744,10 @@ impl Renderer {
             surface,
             physical_device,
             &queue_family_indices,
+            device_profile.present_mode,
+            SwapchainFormat::Choose(hdr_output),
         )?;
+        let image_format = surface_format.format;
 
         let swapchain_images = unsafe { swapchain_device_ext.get_swapchain_images(swapchain)? };
         let swapchain_image_views =
@@ -348,8 +755,11 @@ impl Renderer {
         let (image_available, render_finished, frame_timeline, compute_timeline) =
             create_sync_objects(&device, &swapchain_images)?;
 
-        let depth_format = find_depth_format(&instance, physical_device);
+        let depth_format = find_depth_format(&instance, physical_device, depth_formats)?;
 
+        #[cfg(not(feature = "editor"))]
+        let _ = enable_egui;
+        #[cfg(feature = "editor")]
         let egui = if enable_egui {
             Some(EguiIntegration::new(
                 &instance,
@@ -366,11 +776,15 @@ impl Renderer {
         let compute_command_buffers = create_command_buffers(&device, command_pool)?;
 
         // Calculate scaled render extent
-        let render_extent = calculate_render_extent(image_extent, render_scale);
+        let render_extent =
+            calculate_render_extent(image_extent, render_scale, view_mode, presentation);
+        let present_rect = calculate_present_rect(image_extent, render_extent, presentation);
+        let render_scale =
+            presented_render_scale(render_scale, render_extent, present_rect, presentation);
 
         // Create resolve images at render_extent
         let (resolve_images, resolve_image_memories, resolve_image_views) =
-            create_resolve_images(&allocator, &device, render_extent, image_format)?;
+            create_resolve_images(&allocator, &device, render_extent, image_format, view_mode)?;
 
         // Color and depth buffers at render_extent (scaled resolution)
         let (color_image, color_image_memory, color_image_view) = create_color_image(
@@ -379,19 +793,34 @@ impl Renderer {
             render_extent,
             image_format,
             msaa_samples,
+            view_mode,
         )?;
 
         let (depth_image, depth_image_memory, depth_image_view) = create_depth_buffer_image(
             &allocator,
-            &instance,
             &device,
-            physical_device,
             command_pool,
             graphics_queue,
+            depth_format,
             render_extent,
             msaa_samples,
+            view_mode,
         )?;
 
+        let velocity = if velocity_buffer {
+            if view_mode != ViewMode::Mono {
+                anyhow::bail!("the velocity buffer doesn't support stereo views yet");
+            }
+            Some(VelocityTargets::init(
+                &allocator,
+                &device,
+                render_extent,
+                msaa_samples,
+            )?)
+        } else {
+            None
+        };
+
         let pipelines = PipelineStorage::new();
         let compute_pipelines = ComputePipelineStorage::new();
         let meshes = vec![];
@@ -404,32 +833,68 @@ impl Renderer {
             width: window_width as f32,
             height: window_height as f32,
             total_frames: 0,
-            #[cfg(debug_assertions)]
+            #[cfg(all(debug_assertions, feature = "shader-compiler"))]
             shader_changes,
+            #[cfg(all(debug_assertions, feature = "shader-compiler"))]
+            shader_reload_requested: false,
             #[cfg(debug_assertions)]
+            layout_tracker: Default::default(),
+            #[cfg(all(debug_assertions, feature = "shader-compiler"))]
             old_pipelines: vec![],
-            window: window.clone(),
-            entry,
-            instance,
-            debug_ext,
-            surface_ext,
-            debug_loader,
-            surface,
-            physical_device,
-            physical_device_properties,
-            queue_family_indices,
-            device,
-            allocator,
-            debug_utils_device,
-            graphics_queue,
-            presentation_queue,
-            swapchain_device_ext,
+            gpu: GpuContext {
+                entry,
+                instance,
+                debug_ext,
+                debug_loader,
+                physical_device,
+                physical_device_properties,
+                queue_family_indices,
+                external_memory_supported,
+                external_semaphores: vec![],
+                device,
+                allocator,
+                debug_utils_device,
+                graphics_queue,
+                compute_queue,
+                ray_query,
+                mesh_shader,
+                fragment_shading_rate,
+                breadcrumbs,
+                pipelines,
+                compute_pipelines,
+                meshes,
+                textures,
+                storage_textures: StorageTextureStorage::new(),
+                uniform_buffers,
+                storage_buffers,
+                counter_buffers: CounterBufferStorage::new(),
+                acceleration_structures: AccelerationStructureStorage::new(),
+            },
+            presenter: WindowPresenter {
+                window: window.clone(),
+                surface_ext,
+                surface,
+                presentation_queue,
+                swapchain_device_ext,
+                preferred_present_mode: device_profile.present_mode,
+                present_wait,
+                hdr_output: active_hdr_output,
+                hdr_metadata_ext: (hdr_metadata_supported && active_hdr_output.is_hdr())
+                    .then(|| ash::ext::hdr_metadata::Device::new(&instance, &device)),
+                hdr_metadata_luminance: None,
+                surface_format,
+                image_format,
+                image_extent,
+                swapchain,
+                swapchain_images,
+                swapchain_image_views,
+                image_available,
+                render_finished,
+            },
             msaa_samples,
-            image_format,
-            image_extent,
-            swapchain,
-            swapchain_images,
-            swapchain_image_views,
+            view_mode,
+            presentation,
+            device_profile,
             depth_format,
             color_image,
             color_image_memory,
@@ -439,40 +904,62 @@ impl Renderer {
             depth_image_view,
             render_scale,
             render_extent,
+            present_rect,
+            upscale_filter: UpscaleFilter::default(),
+            rcas_upscale: None,
             resolve_images,
             resolve_image_memories,
             resolve_image_views,
             command_pool,
             command_buffers,
-            image_available,
-            render_finished,
             frame_timeline,
-            flight_slot: 0,
-            ring_slot: 0,
+            slots: FrameSlots::default(),
 
             compute_timeline,
             compute_frames: 0,
             has_compute_pipelines: false,
-            compute_queue,
             compute_command_buffers,
             pipelined_compute: false,
 
-            pipelines,
-            compute_pipelines,
-            meshes,
-            textures,
-            storage_textures: StorageTextureStorage::new(),
-            uniform_buffers,
-            storage_buffers,
+            feedback_textures: FeedbackTextureStorage::new(),
+            pending_texture_updates: vec![],
+            pending_external_acquires: vec![],
+            determinism_audit: None,
+            pending_frame_dump: None,
+            frame_dump_formats: Default::default(),
+            recorded_commands: None,
+            pending_uniform_rebinds: vec![],
+            texture_update_staging: Default::default(),
+            #[cfg(feature = "editor")]
             egui,
             picking: None,
+            picking_policy: PickingPolicy::default(),
+            split_screen: SplitScreenState::default(),
+            readbacks: Readbacks::new(),
+            frame_stats: FrameStats::default(),
+            post_process: None,
+            scene_color_texture_indices: vec![],
+            taa_history: None,
+            taa_history_texture_indices: vec![],
+            velocity,
+            velocity_texture_indices: vec![],
+            frame_globals: FrameGlobalsState::new(),
+            hdr_sdr_preview: false,
+            redraw_requested: false,
             last_picked_object_id: 0,
+            frame_capture: FrameCapture::connect(),
+            shader_printf,
+            pixel_debug: PixelDebugger::new(depth_format),
+            texture_inspector: TextureInspector::new(),
+            #[cfg(feature = "editor")]
             text_input_active: false,
+            #[cfg(feature = "editor")]
+            scene_overlay: None,
         })
     }
 
     fn renderer_pipeline<D>(&self, handle: &PipelineHandle<D>) -> &RendererPipeline {
-        self.pipelines.get(handle)
+        self.gpu.pipelines.get(handle)
     }
 
     fn set_debug_name<T: vk::Handle>(&self, object: T, name: &str) {
@@ -481,12 +968,47 @@ impl Renderer {
             .object_handle(object)
             .object_name(&c_name);
         unsafe {
-            self.debug_utils_device
+            self.gpu
+                .debug_utils_device
                 .set_debug_utils_object_name(&name_info)
                 .ok();
         }
     }
 
+    /// The descriptor info for a sampled texture, per ring slot. Only the
+    /// aliases of a feedback texture differ between slots.
+    fn texture_image_infos(
+        &self,
+        texture_handle: &TextureHandle,
+    ) -> [vk::DescriptorImageInfo; PRE_WAIT_RING_LEN] {
+        let texture = self.gpu.textures.get(texture_handle);
+
+        let feedback = self.feedback_textures.iter().find_map(|feedback| {
+            feedback
+                .ring_offset_for(texture_handle.index())
+                .map(|offset| (feedback, offset))
+        });
+
+        let scene_color = self
+            .scene_color_texture_indices
+            .contains(&texture_handle.index());
+
+        std::array::from_fn(|frame| {
+            let image_view = match feedback {
+                Some((feedback, offset)) => {
+                    feedback.image_views[(frame + offset) % PRE_WAIT_RING_LEN]
+                }
+                None if scene_color => self.resolve_image_views[frame],
+                None => texture.image_view,
+            };
+
+            vk::DescriptorImageInfo::default()
+                .image_layout(texture.image_layout)
+                .image_view(image_view)
+                .sampler(texture.sampler)
+        })
+    }
+
     /// Create an sRGB, mipmapped, REPEAT-wrapped texture — the long-standing
     /// default. Use [`Self::create_texture_with_options`] to vary any of that.
     pub fn create_texture(
@@ -517,21 +1039,95 @@ impl Renderer {
         let texture = create_texture(
             source_file_name.into(),
             image,
-            &self.allocator,
-            &self.instance,
-            &self.device,
-            self.physical_device,
-            self.physical_device_properties,
+            &self.gpu.allocator,
+            &self.gpu.instance,
+            &self.gpu.device,
+            self.gpu.physical_device,
+            self.gpu.physical_device_properties,
+            self.command_pool,
+            self.gpu.graphics_queue,
+            options,
+        )?;
+
+        let handle = self.gpu.textures.add(texture);
+
+        Ok(handle)
+    }
+
+    /// Create a texture from raw texel bytes in an explicit format, such as a
+    /// heightmap, lookup table, or single-channel mask.
+    ///
+    /// `row_stride` is the distance in bytes between the starts of consecutive
+    /// rows; pass `width * format.bytes_per_texel()` for tightly packed data.
+    /// `options.color_space` is ignored, since the format decides it.
+    pub fn create_texture_from_bytes(
+        &mut self,
+        source_file_name: impl Into<String>,
+        format: TextureFormat,
+        width: u32,
+        height: u32,
+        bytes: &[u8],
+        row_stride: usize,
+        options: TextureOptions,
+    ) -> anyhow::Result<TextureHandle> {
+        let texture = create_texture_from_bytes(
+            source_file_name.into(),
+            format,
+            vk::Extent2D { width, height },
+            bytes,
+            row_stride,
+            &self.gpu.allocator,
+            &self.gpu.instance,
+            &self.gpu.device,
+            self.gpu.physical_device,
+            self.gpu.physical_device_properties,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
             options,
         )?;
 
-        let handle = self.textures.add(texture);
+        let handle = self.gpu.textures.add(texture);
 
         Ok(handle)
     }
 
+    /// Create a linearly filtered, clamped texture strip from a color lookup
+    /// table, for sampling with the helpers in `color_grading.slang`;
+    /// [`ColorGrading`] makes one to grade the scene with.
+    /// A 1D table becomes a `size` by 1 texture.
+    pub fn create_lut_texture(
+        &mut self,
+        source_file_name: impl Into<String>,
+        lut: &crate::lut::CubeLut,
+    ) -> anyhow::Result<TextureHandle> {
+        let source_file_name = source_file_name.into();
+        lut.check_strip_fits(
+            self.gpu
+                .physical_device_properties
+                .limits
+                .max_image_dimension2_d,
+        )
+        .map_err(|error| error.context(format!("can't create lut texture {source_file_name}")))?;
+        let extent = lut.strip_extent();
+        let format = TextureFormat::Rgba16F;
+
+        self.create_texture_from_bytes(
+            source_file_name,
+            format,
+            extent.width,
+            extent.height,
+            &lut.strip_texels(),
+            extent.width as usize * format.bytes_per_texel() as usize,
+            TextureOptions {
+                filter: TextureFilter::Linear,
+                wrap_u: TextureWrap::ClampToEdge,
+                wrap_v: TextureWrap::ClampToEdge,
+                mipmaps: false,
+                ..Default::default()
+            },
+        )
+    }
+
     /// Create a texture from pre-baked mip level data, such as from a KTX2 file.
     /// Unlike [`Self::create_texture`], this uploads all provided mip levels
     /// directly instead of generating them at runtime.
@@ -551,103 +1147,693 @@ impl Renderer {
             format,
             extent,
             mip_data,
-            &self.allocator,
-            &self.instance,
-            &self.device,
-            self.physical_device,
-            self.physical_device_properties,
+            &self.gpu.allocator,
+            &self.gpu.instance,
+            &self.gpu.device,
+            self.gpu.physical_device,
+            self.gpu.physical_device_properties,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
             texture_filter,
         )?;
 
-        let handle = self.textures.add(texture);
+        let handle = self.gpu.textures.add(texture);
 
         Ok(handle)
     }
 
-    pub fn drop_texture(&mut self, texture_handle: TextureHandle) {
-        let texture = self.textures.take(texture_handle);
-        self.destroy_texture(texture);
-    }
-
-    fn destroy_texture(&mut self, texture: Texture) {
-        unsafe {
-            self.device.destroy_sampler(texture.sampler, None);
-            self.device.destroy_image_view(texture.image_view, None);
-            if let texture::ImageOwnership::Owned(mut allocation) = texture.image_ownership {
-                self.allocator.destroy_image(texture.image, &mut allocation);
-            }
+    /// Import a semaphore exported by another process or API, for ordering
+    /// its writes to imported textures before the renderer's reads.
+    /// Fails if the platform's external semaphore extension is unavailable.
+    pub fn import_external_semaphore(
+        &mut self,
+        handle: ExternalSemaphoreHandle,
+    ) -> anyhow::Result<ExternalSemaphore> {
+        if !self.gpu.external_memory_supported {
+            anyhow::bail!("external semaphore import is not supported on this device");
         }
+
+        let semaphore =
+            external_memory::import_semaphore(&self.gpu.instance, &self.gpu.device, handle)?;
+        self.gpu.external_semaphores.push(semaphore);
+
+        Ok(ExternalSemaphore { semaphore })
     }
 
-    pub fn create_storage_texture(
+    /// Import an image allocated outside the renderer (eg, by a video decoder
+    /// or another process) as a sampled texture.
+    ///
+    /// The exporter must release the image to the external queue family in
+    /// GENERAL layout. If `ready` is given, the import waits for the exporter
+    /// to signal it; otherwise the exporter must have finished writing.
+    /// Fails if the platform's external memory extension is unavailable.
+    pub fn import_external_texture(
         &mut self,
-        width: u32,
-        height: u32,
-        format: vk::Format,
-    ) -> anyhow::Result<StorageTextureHandle> {
-        let (image, image_memory) = create_vk_image(
-            &self.allocator,
-            ImageOptions {
-                extent: vk::Extent2D { width, height },
-                format,
-                tiling: vk::ImageTiling::OPTIMAL,
-                usage: vk::ImageUsageFlags::STORAGE
-                    | vk::ImageUsageFlags::SAMPLED
-                    | vk::ImageUsageFlags::TRANSFER_DST,
-                mip_levels: 1,
-                msaa_samples: vk::SampleCountFlags::TYPE_1,
-            },
-        )?;
+        source_file_name: impl Into<String>,
+        external_image: ExternalImage,
+        ready: Option<&ExternalSemaphore>,
+        texture_filter: TextureFilter,
+    ) -> anyhow::Result<TextureHandle> {
+        if !self.gpu.external_memory_supported {
+            anyhow::bail!("external memory import is not supported on this device");
+        }
 
-        transition_image_layout(
-            &self.device,
+        let format = external_image.format;
+        let extent = external_image.extent;
+        let (image, memory) = external_memory::import_image(
+            &self.gpu.instance,
+            &self.gpu.device,
+            self.gpu.physical_device,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
+            self.gpu.queue_family_indices.graphics,
+            external_image,
+            ready.map(|semaphore| semaphore.semaphore),
+        )?;
+
+        let image_view = create_image_view(
+            &self.gpu.device,
             image,
             format,
-            vk::ImageLayout::UNDEFINED,
-            vk::ImageLayout::GENERAL,
+            vk::ImageAspectFlags::COLOR,
             1,
         )?;
 
-        let image_view =
-            create_image_view(&self.device, image, format, vk::ImageAspectFlags::COLOR, 1)?;
+        let sampler = create_texture_sampler(
+            &self.gpu.device,
+            self.gpu.physical_device_properties,
+            TextureOptions {
+                filter: texture_filter,
+                mipmaps: false,
+                ..Default::default()
+            },
+        )?;
 
-        let storage_texture = storage_texture::StorageTexture {
+        let texture = texture::Texture {
+            source_file_name: source_file_name.into(),
             image,
-            image_memory,
+            image_ownership: texture::ImageOwnership::Imported(memory),
             image_view,
+            sampler,
             format,
-            width,
-            height,
+            extent,
+            mip_levels: 1,
+            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
         };
 
-        let handle = self.storage_textures.add(storage_texture);
+        let handle = self.gpu.textures.add(texture);
 
         Ok(handle)
     }
 
-    /// Create a sampled TextureHandle that aliases the same image as the given storage texture.
-    /// The image stays in GENERAL layout (valid for both storage and sampled access).
-    pub fn storage_texture_as_sampled(
+    /// Take back an imported texture after its producer wrote new contents,
+    /// such as the next decoded video frame. The producer releases the image
+    /// to the external queue family in GENERAL layout, then signals `ready`;
+    /// the next frame waits for the signal before reading the texture.
+    ///
+    /// The renderer doesn't release the texture back, so the producer must
+    /// not write it while frames that sample it are in flight.
+    pub fn acquire_external_texture(
         &mut self,
-        storage_texture_handle: &StorageTextureHandle,
-    ) -> anyhow::Result<TextureHandle> {
-        let st = self.storage_textures.get(storage_texture_handle);
+        texture_handle: &TextureHandle,
+        ready: &ExternalSemaphore,
+    ) -> anyhow::Result<()> {
+        let texture = self.gpu.textures.get(texture_handle);
+        anyhow::ensure!(
+            matches!(
+                texture.image_ownership,
+                texture::ImageOwnership::Imported(_)
+            ),
+            "texture {} wasn't imported, so it has no producer to acquire it from",
+            texture.source_file_name
+        );
 
-        let image_view = create_image_view(
-            &self.device,
-            st.image,
+        self.pending_external_acquires.push(PendingExternalAcquire {
+            texture_index: texture_handle.index(),
+            ready: ready.semaphore,
+        });
+
+        Ok(())
+    }
+
+    /// Queue new contents for a region of a texture, such as a procedurally
+    /// generated or decoded video frame. The copy is recorded at the start of
+    /// the next frame's graphics commands, after frames still in flight finish
+    /// sampling the old contents.
+    ///
+    /// `bytes` holds the region's rows tightly packed, in the texture's format.
+    /// Only textures that own their image and have a single mip level (ie,
+    /// created with `mipmaps: false`) can be updated. Pipelined compute on a
+    /// separate queue (see [`Self::enable_pipelined_compute`]) is not
+    /// synchronized with the copy.
+    pub fn update_texture(
+        &mut self,
+        texture_handle: &TextureHandle,
+        region: TextureRegion,
+        bytes: &[u8],
+    ) -> anyhow::Result<()> {
+        let texture = self.gpu.textures.get(texture_handle);
+        anyhow::ensure!(
+            matches!(texture.image_ownership, texture::ImageOwnership::Owned(_)),
+            "texture {} doesn't own its image, so it can't be updated",
+            texture.source_file_name
+        );
+        anyhow::ensure!(
+            texture.mip_levels == 1,
+            "texture {} has mipmaps; create it with `mipmaps: false` to update it",
+            texture.source_file_name
+        );
+        anyhow::ensure!(
+            region.fits_within(texture.extent),
+            "region {region:?} is outside texture {} ({}x{})",
+            texture.source_file_name,
+            texture.extent.width,
+            texture.extent.height
+        );
+        let block = format_block_info(texture.format)
+            .filter(|block| block.block_width == 1 && block.block_height == 1)
+            .ok_or_else(|| anyhow::anyhow!("can't update texture format {:?}", texture.format))?;
+        let row_stride = region.width as usize * block.block_bytes as usize;
+        texel_row_length(
+            region.width,
+            region.height,
+            block.block_bytes,
+            row_stride,
+            bytes.len(),
+        )?;
+
+        let (staging_buffer, mut staging_memory) = create_memory_buffer(
+            &self.gpu.allocator,
+            bytes.len() as u64,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            BufferMemory::Staging,
+        )?;
+        if let Err(e) =
+            unsafe { write_to_gpu_buffer(&self.gpu.allocator, &mut staging_memory, bytes) }
+        {
+            unsafe {
+                self.gpu
+                    .allocator
+                    .destroy_buffer(staging_buffer, &mut staging_memory)
+            };
+            return Err(e);
+        }
+
+        self.pending_texture_updates.push(PendingTextureUpdate {
+            texture_index: texture_handle.index(),
+            region,
+            staging_buffer,
+            staging_memory,
+        });
+
+        Ok(())
+    }
+
+    /// Pipelines that bind the texture can no longer be drawn;
+    /// their draws return [`DrawError::OrphanedPipeline`]
+    pub fn drop_texture(&mut self, texture_handle: TextureHandle) {
+        self.orphan_dependent_pipelines(ResourceId::Texture(texture_handle.index()));
+        self.scene_color_texture_indices
+            .retain(|&index| index != texture_handle.index());
+        self.taa_history_texture_indices
+            .retain(|&index| index != texture_handle.index());
+        self.velocity_texture_indices
+            .retain(|&index| index != texture_handle.index());
+        let texture = self.gpu.textures.take(texture_handle);
+        #[cfg(debug_assertions)]
+        self.layout_tracker.borrow_mut().forget(texture.image);
+        self.destroy_texture(texture);
+    }
+
+    fn destroy_texture(&mut self, texture: Texture) {
+        unsafe {
+            self.gpu.device.destroy_sampler(texture.sampler, None);
+            self.gpu.device.destroy_image_view(texture.image_view, None);
+            match texture.image_ownership {
+                texture::ImageOwnership::Owned(mut allocation) => {
+                    self.gpu
+                        .allocator
+                        .destroy_image(texture.image, &mut allocation);
+                }
+                texture::ImageOwnership::Imported(memory) => {
+                    self.gpu.device.destroy_image(texture.image, None);
+                    self.gpu.device.free_memory(memory, None);
+                }
+                texture::ImageOwnership::Aliased => {}
+            }
+        }
+    }
+
+    /// Create an offscreen color target with one image per frame, for effects
+    /// that read their own previous output. See [`FeedbackTextureHandle`].
+    ///
+    /// The images start cleared to zero. Feedback textures are only
+    /// synchronized for fragment shader reads on the graphics queue.
+    pub fn create_feedback_texture(
+        &mut self,
+        source_file_name: impl Into<String>,
+        width: u32,
+        height: u32,
+        format: vk::Format,
+        texture_filter: TextureFilter,
+    ) -> anyhow::Result<FeedbackTextureHandle> {
+        let source_file_name = source_file_name.into();
+        let extent = vk::Extent2D { width, height };
+        let image_options = ImageOptions {
+            extent,
+            format,
+            tiling: vk::ImageTiling::OPTIMAL,
+            usage: vk::ImageUsageFlags::COLOR_ATTACHMENT
+                | vk::ImageUsageFlags::SAMPLED
+                | vk::ImageUsageFlags::TRANSFER_DST
+                // for frame dumps
+                | vk::ImageUsageFlags::TRANSFER_SRC,
+            mip_levels: 1,
+            array_layers: 1,
+            msaa_samples: vk::SampleCountFlags::TYPE_1,
+        };
+
+        let results: [_; PRE_WAIT_RING_LEN] = (0..PRE_WAIT_RING_LEN)
+            .map(|_| -> anyhow::Result<_> {
+                let (image, memory) = create_vk_image(&self.gpu.allocator, image_options)?;
+                let view = create_image_view(
+                    &self.gpu.device,
+                    image,
+                    format,
+                    vk::ImageAspectFlags::COLOR,
+                    1,
+                )?;
+                Ok((image, memory, view))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .unwrap();
+
+        let images = results.map(|(image, _, _)| image);
+        let image_memories = results.map(|(_, memory, _)| memory);
+        let image_views = results.map(|(_, _, view)| view);
+
+        // clear every image once, so that the first frame's history is defined
+        let command_buffer = begin_single_time_commands(&self.gpu.device, self.command_pool)?;
+        let to_transfer_dst = images.map(|image| {
+            vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::UNDEFINED)
+                .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::NONE)
+                .src_access_mask(vk::AccessFlags2::NONE)
+                .dst_stage_mask(vk::PipelineStageFlags2::CLEAR)
+                .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+        });
+        self.record_barriers(command_buffer, &to_transfer_dst);
+        for image in images {
+            unsafe {
+                self.gpu.device.cmd_clear_color_image(
+                    command_buffer,
+                    image,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &vk::ClearColorValue {
+                        float32: Default::default(),
+                    },
+                    &[COLOR_SUBRESOURCE_RANGE],
+                );
+            }
+        }
+        let to_shader_read = images.map(|image| {
+            vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::CLEAR)
+                .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+                .dst_access_mask(vk::AccessFlags2::SHADER_READ)
+        });
+        self.record_barriers(command_buffer, &to_shader_read);
+        end_single_time_commands(
+            &self.gpu.device,
+            self.command_pool,
+            self.gpu.graphics_queue,
+            command_buffer,
+        )?;
+
+        // the two aliases get their own view and sampler, which destroy_texture
+        // frees; descriptor writes substitute the view for the right ring slot
+        let mut add_alias = |suffix: &str| -> anyhow::Result<TextureHandle> {
+            let image_view = create_image_view(
+                &self.gpu.device,
+                images[0],
+                format,
+                vk::ImageAspectFlags::COLOR,
+                1,
+            )?;
+            let sampler = create_texture_sampler(
+                &self.gpu.device,
+                self.gpu.physical_device_properties,
+                TextureOptions {
+                    filter: texture_filter,
+                    wrap_u: TextureWrap::ClampToEdge,
+                    wrap_v: TextureWrap::ClampToEdge,
+                    mipmaps: false,
+                    ..Default::default()
+                },
+            )?;
+            let texture = texture::Texture {
+                source_file_name: format!("{source_file_name}_{suffix}"),
+                image: images[0],
+                image_ownership: texture::ImageOwnership::Aliased,
+                image_view,
+                sampler,
+                format,
+                extent,
+                mip_levels: 1,
+                image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            };
+            Ok(self.gpu.textures.add(texture))
+        };
+        let history = add_alias("history")?;
+        let current = add_alias("current")?;
+
+        for (i, image) in images.iter().enumerate() {
+            self.set_debug_name(*image, &format!("{source_file_name}_{i}"));
+        }
+
+        let feedback_texture = feedback_texture::FeedbackTexture {
+            images,
+            image_memories,
+            image_views,
+            format,
+            extent,
+            history_texture_index: history.index(),
+            current_texture_index: current.index(),
+        };
+
+        let handle = self
+            .feedback_textures
+            .add(feedback_texture, history, current);
+
+        Ok(handle)
+    }
+
+    /// Create an offscreen color target to draw into, then sample from a later
+    /// pipeline; see [`RenderTargetHandle`]. It starts cleared to zero, and is
+    /// sampled with linear filtering.
+    pub fn create_render_target(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: vk::Format,
+    ) -> anyhow::Result<RenderTargetHandle> {
+        let name = format!("render_target_{}", self.feedback_textures.iter().count());
+        let target =
+            self.create_feedback_texture(name, width, height, format, TextureFilter::Linear)?;
+
+        Ok(RenderTargetHandle::new(target))
+    }
+
+    /// A texture that samples this frame's main pass output (resolved, when
+    /// MSAA is on), for pipelines created with
+    /// [`PipelineConfig::with_post_process`]. It is render-scaled, and the
+    /// renderer keeps it pointed at the current images across resizes.
+    pub fn create_scene_color_texture(
+        &mut self,
+        texture_filter: TextureFilter,
+    ) -> anyhow::Result<TextureHandle> {
+        if self.view_mode != ViewMode::Mono {
+            anyhow::bail!("scene color textures don't support stereo views yet");
+        }
+
+        // like the feedback aliases, this entry's own view is never bound;
+        // descriptor writes substitute the right ring slot's resolve view
+        let image_view = create_image_view(
+            &self.gpu.device,
+            self.resolve_images[0],
+            self.presenter.image_format,
+            vk::ImageAspectFlags::COLOR,
+            1,
+        )?;
+        let sampler = create_texture_sampler(
+            &self.gpu.device,
+            self.gpu.physical_device_properties,
+            TextureOptions {
+                filter: texture_filter,
+                wrap_u: TextureWrap::ClampToEdge,
+                wrap_v: TextureWrap::ClampToEdge,
+                mipmaps: false,
+                ..Default::default()
+            },
+        )?;
+        let texture = texture::Texture {
+            source_file_name: "scene_color".to_string(),
+            image: self.resolve_images[0],
+            image_ownership: texture::ImageOwnership::Aliased,
+            image_view,
+            sampler,
+            format: self.presenter.image_format,
+            extent: self.render_extent,
+            mip_levels: 1,
+            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        };
+
+        let handle = self.gpu.textures.add(texture);
+        self.scene_color_texture_indices.push(handle.index());
+
+        Ok(handle)
+    }
+
+    /// A texture that samples the previous frame's post-process output, for a
+    /// post-process pipeline resolving temporal anti-aliasing; see [`taa`].
+    /// After each frame's post-process pass the renderer copies its output here.
+    pub fn create_taa_history_texture(
+        &mut self,
+        texture_filter: TextureFilter,
+    ) -> anyhow::Result<TextureHandle> {
+        if self.view_mode != ViewMode::Mono {
+            anyhow::bail!("TAA history textures don't support stereo views yet");
+        }
+
+        let format = self.presenter.image_format;
+        if self.taa_history.is_none() {
+            self.taa_history = Some(TaaHistory::init(
+                &self.gpu.allocator,
+                self.render_extent,
+                format,
+            )?);
+        }
+        let history = self.taa_history.as_ref().unwrap();
+
+        let image_view = history.create_view(&self.gpu.device, format)?;
+        let sampler = create_texture_sampler(
+            &self.gpu.device,
+            self.gpu.physical_device_properties,
+            TextureOptions {
+                filter: texture_filter,
+                wrap_u: TextureWrap::ClampToEdge,
+                wrap_v: TextureWrap::ClampToEdge,
+                mipmaps: false,
+                ..Default::default()
+            },
+        )?;
+        let texture = texture::Texture {
+            source_file_name: "taa_history".to_string(),
+            image: history.image,
+            image_ownership: texture::ImageOwnership::Aliased,
+            image_view,
+            sampler,
+            format,
+            extent: self.render_extent,
+            mip_levels: 1,
+            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        };
+
+        let handle = self.gpu.textures.add(texture);
+        self.taa_history_texture_indices.push(handle.index());
+
+        Ok(handle)
+    }
+
+    /// A texture that samples the main pass's velocity buffer, for a
+    /// post-process pipeline (motion blur, or TAA with motion vectors); see
+    /// [`velocity`]. Requires `Game::velocity_buffer`.
+    pub fn create_velocity_texture(
+        &mut self,
+        texture_filter: TextureFilter,
+    ) -> anyhow::Result<TextureHandle> {
+        let Some(velocity) = &self.velocity else {
+            anyhow::bail!("velocity textures need Game::velocity_buffer to return true");
+        };
+
+        let image_view = velocity.create_view(&self.gpu.device)?;
+        let sampler = create_texture_sampler(
+            &self.gpu.device,
+            self.gpu.physical_device_properties,
+            TextureOptions {
+                filter: texture_filter,
+                wrap_u: TextureWrap::ClampToEdge,
+                wrap_v: TextureWrap::ClampToEdge,
+                mipmaps: false,
+                ..Default::default()
+            },
+        )?;
+        let texture = texture::Texture {
+            source_file_name: "velocity".to_string(),
+            image: velocity.image,
+            image_ownership: texture::ImageOwnership::Aliased,
+            image_view,
+            sampler,
+            format: velocity::VELOCITY_FORMAT,
+            extent: self.render_extent,
+            mip_levels: 1,
+            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        };
+
+        let handle = self.gpu.textures.add(texture);
+        self.velocity_texture_indices.push(handle.index());
+
+        Ok(handle)
+    }
+
+    /// Whether the main pass has a velocity target; see [`velocity`]
+    pub fn has_velocity_buffer(&self) -> bool {
+        self.velocity.is_some()
+    }
+
+    pub fn create_storage_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: vk::Format,
+    ) -> anyhow::Result<StorageTextureHandle> {
+        let (image, image_memory) = create_vk_image(
+            &self.gpu.allocator,
+            ImageOptions {
+                extent: vk::Extent2D { width, height },
+                format,
+                tiling: vk::ImageTiling::OPTIMAL,
+                usage: vk::ImageUsageFlags::STORAGE
+                    | vk::ImageUsageFlags::SAMPLED
+                    | vk::ImageUsageFlags::TRANSFER_DST
+                    // for the texture inspector
+                    | vk::ImageUsageFlags::TRANSFER_SRC,
+                mip_levels: 1,
+                array_layers: 1,
+                msaa_samples: vk::SampleCountFlags::TYPE_1,
+            },
+        )?;
+
+        transition_image_layout(
+            &self.gpu.device,
+            self.command_pool,
+            self.gpu.graphics_queue,
+            image,
+            format,
+            vk::ImageLayout::UNDEFINED,
+            vk::ImageLayout::GENERAL,
+            1,
+        )?;
+
+        let image_view = create_image_view(
+            &self.gpu.device,
+            image,
+            format,
+            vk::ImageAspectFlags::COLOR,
+            1,
+        )?;
+
+        let storage_texture = storage_texture::StorageTexture {
+            image,
+            image_memory,
+            image_view,
+            format,
+            width,
+            height,
+        };
+
+        let handle = self.gpu.storage_textures.add(storage_texture);
+
+        Ok(handle)
+    }
+
+    /// Build a bottom-level acceleration structure from a triangle list,
+    /// to place in a tlas with [`Self::build_tlas`].
+    /// Requires [`DeviceExtensions::with_ray_query`].
+    pub fn build_blas(
+        &mut self,
+        positions: &[Vec3],
+        indices: &[u32],
+    ) -> anyhow::Result<BlasHandle> {
+        let blas = self
+            .acceleration_structure_build_context()?
+            .build_blas(positions, indices)?;
+
+        Ok(self.gpu.acceleration_structures.add_blas(blas))
+    }
+
+    /// Build a static top-level acceleration structure, for a pipeline's
+    /// `RaytracingAccelerationStructure` parameter
+    /// (see [`PipelineConfig::with_acceleration_structures`]).
+    /// Requires [`DeviceExtensions::with_ray_query`].
+    pub fn build_tlas(
+        &mut self,
+        instances: &[TlasInstance],
+    ) -> anyhow::Result<AccelerationStructureHandle> {
+        let instances: Vec<_> = instances
+            .iter()
+            .map(|instance| {
+                let blas = self.gpu.acceleration_structures.get_blas(instance.blas);
+                (blas.device_address(), instance.transform)
+            })
+            .collect();
+        let tlas = self
+            .acceleration_structure_build_context()?
+            .build_tlas(&instances)?;
+
+        Ok(self.gpu.acceleration_structures.add_tlas(tlas))
+    }
+
+    fn acceleration_structure_build_context(
+        &self,
+    ) -> anyhow::Result<acceleration_structure::BuildContext<'_>> {
+        let Some(ray_query) = &self.gpu.ray_query else {
+            anyhow::bail!(
+                "acceleration structures require ray query; enable it with DeviceExtensions::with_ray_query"
+            );
+        };
+
+        Ok(acceleration_structure::BuildContext {
+            device: &self.gpu.device,
+            allocator: &self.gpu.allocator,
+            command_pool: self.command_pool,
+            graphics_queue: self.gpu.graphics_queue,
+            ray_query,
+        })
+    }
+
+    /// Create a sampled TextureHandle that aliases the same image as the given storage texture.
+    /// The image stays in GENERAL layout (valid for both storage and sampled access).
+    pub fn storage_texture_as_sampled(
+        &mut self,
+        storage_texture_handle: &StorageTextureHandle,
+    ) -> anyhow::Result<TextureHandle> {
+        let st = self.gpu.storage_textures.get(storage_texture_handle);
+
+        let image_view = create_image_view(
+            &self.gpu.device,
+            st.image,
             st.format,
             vk::ImageAspectFlags::COLOR,
             1,
         )?;
 
         let sampler = create_texture_sampler(
-            &self.device,
-            self.physical_device_properties,
+            &self.gpu.device,
+            self.gpu.physical_device_properties,
             TextureOptions::default(),
         )?;
 
@@ -657,19 +1843,24 @@ impl Renderer {
             image_ownership: texture::ImageOwnership::Aliased,
             image_view,
             sampler,
+            format: st.format,
+            extent: vk::Extent2D {
+                width: st.width,
+                height: st.height,
+            },
             mip_levels: 1,
             image_layout: vk::ImageLayout::GENERAL,
         };
 
-        let handle = self.textures.add(texture);
+        let handle = self.gpu.textures.add(texture);
 
         Ok(handle)
     }
 
     pub fn clear_storage_texture(&self, handle: &StorageTextureHandle) -> anyhow::Result<()> {
-        let st = self.storage_textures.get(handle);
+        let st = self.gpu.storage_textures.get(handle);
 
-        let command_buffer = begin_single_time_commands(&self.device, self.command_pool)?;
+        let command_buffer = begin_single_time_commands(&self.gpu.device, self.command_pool)?;
 
         let subresource_range = vk::ImageSubresourceRange::default()
             .aspect_mask(vk::ImageAspectFlags::COLOR)
@@ -677,7 +1868,7 @@ impl Renderer {
             .layer_count(1);
 
         unsafe {
-            self.device.cmd_clear_color_image(
+            self.gpu.device.cmd_clear_color_image(
                 command_buffer,
                 st.image,
                 vk::ImageLayout::GENERAL,
@@ -689,9 +1880,9 @@ impl Renderer {
         }
 
         end_single_time_commands(
-            &self.device,
+            &self.gpu.device,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
             command_buffer,
         )?;
 
@@ -705,19 +1896,19 @@ impl Renderer {
         handle: &StorageTextureHandle,
         data: &[T],
     ) -> anyhow::Result<()> {
-        let st = self.storage_textures.get(handle);
+        let st = self.gpu.storage_textures.get(handle);
         let buffer_size = std::mem::size_of_val(data) as u64;
 
         let (staging_buffer, mut staging_buffer_memory) = create_memory_buffer(
-            &self.allocator,
+            &self.gpu.allocator,
             buffer_size,
             vk::BufferUsageFlags::TRANSFER_SRC,
             BufferMemory::Staging,
         )?;
 
-        unsafe { write_to_gpu_buffer(&self.allocator, &mut staging_buffer_memory, data)? };
+        unsafe { write_to_gpu_buffer(&self.gpu.allocator, &mut staging_buffer_memory, data)? };
 
-        let command_buffer = begin_single_time_commands(&self.device, self.command_pool)?;
+        let command_buffer = begin_single_time_commands(&self.gpu.device, self.command_pool)?;
 
         // Transition GENERAL -> TRANSFER_DST_OPTIMAL
         let subresource_range = vk::ImageSubresourceRange::default()
@@ -737,7 +1928,7 @@ impl Renderer {
             .dst_stage_mask(vk::PipelineStageFlags2::COPY)
             .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE);
 
-        cmd_barrier2(&self.device, command_buffer, &[barrier_to_transfer]);
+        self.record_barriers(command_buffer, &[barrier_to_transfer]);
 
         // Copy buffer to image
         let image_subresource = vk::ImageSubresourceLayers::default()
@@ -760,7 +1951,7 @@ impl Renderer {
             .image_extent(extent.into());
 
         unsafe {
-            self.device.cmd_copy_buffer_to_image(
+            self.gpu.device.cmd_copy_buffer_to_image(
                 command_buffer,
                 staging_buffer,
                 st.image,
@@ -782,17 +1973,18 @@ impl Renderer {
             .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
             .dst_access_mask(vk::AccessFlags2::SHADER_READ);
 
-        cmd_barrier2(&self.device, command_buffer, &[barrier_to_general]);
+        self.record_barriers(command_buffer, &[barrier_to_general]);
 
         end_single_time_commands(
-            &self.device,
+            &self.gpu.device,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
             command_buffer,
         )?;
 
         unsafe {
-            self.allocator
+            self.gpu
+                .allocator
                 .destroy_buffer(staging_buffer, &mut staging_buffer_memory);
         }
 
@@ -800,44 +1992,751 @@ impl Renderer {
     }
 
     pub fn create_uniform_buffer<T: GPUWrite>(&mut self) -> anyhow::Result<UniformBufferHandle<T>> {
-        let buffer_size = std::mem::size_of::<T>() as u64;
+        let buffers_per_frame = self.create_uniform_ring(std::mem::size_of::<T>() as u64)?;
+        let handle = self.gpu.uniform_buffers.add(buffers_per_frame);
 
+        Ok(handle)
+    }
+
+    /// A uniform buffer of `size` bytes with no Rust type, for tools that lay
+    /// out uniforms from reflection at runtime (see the shader_viewer binary).
+    /// Generated code and games should use [`Renderer::create_uniform_buffer`].
+    pub fn create_raw_uniform_buffer(
+        &mut self,
+        size: u64,
+    ) -> anyhow::Result<RawUniformBufferHandle> {
+        let buffers_per_frame = self.create_uniform_ring(size)?;
+        let handle = self.gpu.uniform_buffers.add_raw(buffers_per_frame, size);
+
+        Ok(handle)
+    }
+
+    /// One persistently mapped, zeroed buffer per ring slot
+    fn create_uniform_ring(
+        &self,
+        buffer_size: u64,
+    ) -> anyhow::Result<[RawUniformBuffer; PRE_WAIT_RING_LEN]> {
         let mut buffers_per_frame: [Option<RawUniformBuffer>; PRE_WAIT_RING_LEN] =
             [const { None }; PRE_WAIT_RING_LEN];
         #[expect(clippy::needless_range_loop)]
         for i in 0..PRE_WAIT_RING_LEN {
             let (buffer, allocation) = create_memory_buffer(
-                &self.allocator,
+                &self.gpu.allocator,
                 buffer_size,
                 vk::BufferUsageFlags::UNIFORM_BUFFER,
                 BufferMemory::PersistentlyMapped,
             )?;
 
-            let mapped_mem = self.allocator.get_allocation_info(&allocation).mapped_data;
+            let mapped_mem = self
+                .gpu
+                .allocator
+                .get_allocation_info(&allocation)
+                .mapped_data;
+            // zeroed so debug reads of a not-yet-written slot see defined memory
+            unsafe { std::ptr::write_bytes(mapped_mem as *mut u8, 0, buffer_size as usize) };
 
             buffers_per_frame[i] = Some(RawUniformBuffer {
                 buffer,
                 allocation,
                 mapped_mem,
+                #[cfg(debug_assertions)]
+                writes: SlotWrites::Unwritten,
             });
         }
-        let buffers_per_frame = buffers_per_frame.map(Option::unwrap);
 
-        let handle = self.uniform_buffers.add(buffers_per_frame);
+        Ok(buffers_per_frame.map(Option::unwrap))
+    }
+
+    /// A uniform buffer with every frame's copy initialized to `initial`,
+    /// for params that only change occasionally (or never)
+    pub fn create_uniform_buffer_with<T: GPUWrite + Clone>(
+        &mut self,
+        initial: T,
+    ) -> anyhow::Result<UniformBufferHandle<T>> {
+        let mut handle = self.create_uniform_buffer::<T>()?;
+        for frame in 0..PRE_WAIT_RING_LEN {
+            let mapped_mem = self
+                .gpu
+                .uniform_buffers
+                .get_mapped_mem_for_frame(&mut handle, frame);
+            // the zeroed memory isn't a T to drop
+            unsafe { std::ptr::write(mapped_mem, initial.clone()) };
+        }
 
         Ok(handle)
     }
 
+    /// Draw counts for the last submitted frame
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.frame_stats
+    }
+
+    /// How picks resolve the pixel under the mouse; the default is
+    /// [`PickingPolicy::Exact`]. Takes effect from the next picking draw.
+    pub fn set_picking_policy(&mut self, policy: PickingPolicy) {
+        self.picking_policy = policy;
+    }
+
+    /// The cameras pipelines created with [`PipelineConfig::with_split_screen`]
+    /// are drawn with, in view index order, each into its rect; see
+    /// [`ViewportRect::split`] for the usual layouts. Shaders declaring a
+    /// [`SplitScreenViews`] block read each camera's matrices from it.
+    /// Views past [`MAX_SPLIT_VIEWS`] are ignored, and an empty slice goes back
+    /// to drawing them once over the whole target.
+    pub fn set_split_screen(&mut self, views: &[SplitView]) {
+        if views.len() > MAX_SPLIT_VIEWS {
+            log::warn!(
+                "{} split-screen views requested; drawing the first {MAX_SPLIT_VIEWS}",
+                views.len()
+            );
+        }
+        self.split_screen.views = views.iter().take(MAX_SPLIT_VIEWS).copied().collect();
+    }
+
+    pub fn split_screen_views(&self) -> &[SplitView] {
+        &self.split_screen.views
+    }
+
+    /// Read back the next frame's final image, after post-processing and
+    /// before the ui layer and editor UI, at render resolution. It arrives a couple of frames
+    /// later from [`Renderer::poll_readbacks`], or the callback set with
+    /// [`Renderer::set_readback_callback`]; see [`Readback::to_rgba_image`].
+    /// Under [`HdrOutput`] it holds the swapchain's HDR format, which isn't
+    /// converted to 8-bit RGBA.
+    pub fn request_screenshot(&mut self) -> ReadbackId {
+        self.readbacks
+            .request(ReadbackRequest::Screenshot, Delivery::Poll)
+    }
+
+    /// Read back a GPU-only buffer's contents at the end of the next frame;
+    /// see [`Renderer::request_screenshot`] for delivery, and [`Readback::to_vec`].
+    pub fn read_gpu_only_buffer<T>(&mut self, handle: &GpuOnlyBufferHandle<T>) -> ReadbackId {
+        let request = ReadbackRequest::GpuOnlyBuffer {
+            index: handle.index(),
+            size: handle.len() as u64 * std::mem::size_of::<T>() as u64,
+        };
+        self.readbacks.request(request, Delivery::Poll)
+    }
+
+    /// Deliver a pending readback to `callback` instead of
+    /// [`Renderer::poll_readbacks`]; returns false if it was already delivered
+    pub fn set_readback_callback(
+        &mut self,
+        id: ReadbackId,
+        callback: impl FnOnce(Readback) + 'static,
+    ) -> bool {
+        self.readbacks.set_callback(id, Box::new(callback))
+    }
+
+    /// The readbacks that have arrived since the last poll, oldest first
+    pub fn poll_readbacks(&mut self) -> Vec<Readback> {
+        self.readbacks.poll()
+    }
+
+    /// Whether shader printf output is being collected;
+    /// see [`Game::shader_printf`](crate::game::Game::shader_printf)
+    pub fn shader_printf_enabled(&self) -> bool {
+        self.shader_printf
+    }
+
+    /// Whether the process is running under RenderDoc,
+    /// and was built with the `renderdoc` feature
+    pub fn can_capture_frames(&self) -> bool {
+        self.frame_capture.is_available()
+    }
+
+    /// Capture the next frame with RenderDoc, from the next present to the one after.
+    /// Returns false (and logs a warning) if RenderDoc isn't available.
+    pub fn trigger_capture(&mut self) -> bool {
+        self.frame_capture.trigger()
+    }
+
+    /// Replay a pipeline's draws for one pixel each frame, after the main pass;
+    /// None stops. Only main pass pipelines are replayed.
+    /// See [`pixel_debug`] for the outputs the replay reads.
+    pub fn set_pixel_debug_target(&mut self, target: Option<PixelDebugTarget>) {
+        self.pixel_debug.target = target;
+        if target.is_none() {
+            self.pixel_debug.last_sample = None;
+        }
+    }
+
+    pub fn pixel_debug_target(&self) -> Option<PixelDebugTarget> {
+        self.pixel_debug.target
+    }
+
+    /// The most recent completed replay, which lags the target by a couple of frames.
+    /// None until one completes, or if the target's pipeline hasn't drawn.
+    pub fn pixel_debug_sample(&self) -> Option<&PixelDebugSample> {
+        self.pixel_debug.last_sample.as_ref()
+    }
+
+    /// The uniform and storage buffers the buffer inspector can read, each
+    /// with the layout a shader reads it through; see [`buffer_inspector`]
+    pub fn inspectable_buffers(&self) -> Vec<InspectableBuffer> {
+        let graphics = self.gpu.pipelines.iter().map(|pipeline| {
+            buffer_inspector::bound_uniforms(
+                pipeline.shader.source_file_name(),
+                &pipeline.shader.reflection_json().global_parameters,
+                &pipeline.shader.layout_bindings(),
+                &pipeline.resources,
+            )
+        });
+        let compute = self.gpu.compute_pipelines.iter().map(|pipeline| {
+            buffer_inspector::bound_uniforms(
+                pipeline.shader.source_file_name(),
+                &pipeline.shader.reflection_json().global_parameters,
+                &pipeline.shader.layout_bindings(),
+                &pipeline.resources,
+            )
+        });
+
+        // a buffer bound by several pipelines is shown with the first one's layout
+        let mut bound_uniforms: Vec<_> = graphics.chain(compute).flatten().collect();
+        let mut seen = BTreeSet::new();
+        bound_uniforms.retain(|bound| seen.insert(bound.index));
+
+        let storage_ranges: Vec<_> = self
+            .gpu
+            .storage_buffers
+            .iter_for_frame(self.slots.ring)
+            .map(|(index, buffer)| {
+                let size = self
+                    .gpu
+                    .allocator
+                    .get_allocation_info(&buffer.allocation)
+                    .size;
+                (index, buffer.device_address..buffer.device_address + size)
+            })
+            .collect();
+        let mut addressed = vec![];
+        let mut addressed_indices = BTreeSet::new();
+        for bound in &bound_uniforms {
+            let bytes = self
+                .inspected_buffer_bytes(AuditedBuffer::Uniform(bound.index))
+                .unwrap_or_default();
+            for pointer in &bound.pointer_fields {
+                let Some(address) = bytes
+                    .get(pointer.offset..pointer.offset + 8)
+                    .map(|address| u64::from_le_bytes(address.try_into().unwrap()))
+                else {
+                    continue;
+                };
+                let Some((index, range)) = storage_ranges
+                    .iter()
+                    .find(|(_, range)| range.contains(&address))
+                else {
+                    continue;
+                };
+                if !addressed_indices.insert(*index) {
+                    continue;
+                }
+
+                addressed.push(InspectableBuffer {
+                    buffer: AuditedBuffer::Storage(*index),
+                    source: format!("{}.{}", bound.source, pointer.path),
+                    layout: buffer_inspector::pointee_layout(pointer),
+                    start: (address - range.start) as usize,
+                });
+            }
+        }
+
+        let uniforms = bound_uniforms.into_iter().map(|bound| InspectableBuffer {
+            buffer: AuditedBuffer::Uniform(bound.index),
+            source: bound.source,
+            layout: bound.layout,
+            start: 0,
+        });
+        uniforms.chain(addressed).collect()
+    }
+
+    /// A uniform or storage buffer's bytes in the ring slot the buffer
+    /// inspector reads; None for push constants or a dropped buffer
+    pub fn inspected_buffer_bytes(&self, buffer: AuditedBuffer) -> Option<&[u8]> {
+        let ring = self.slots.ring;
+        let (allocation, mapped_mem) = match buffer {
+            AuditedBuffer::Uniform(index) => self
+                .gpu
+                .uniform_buffers
+                .iter_for_frame(ring)
+                .find(|(live_index, _)| *live_index == index)
+                .map(|(_, buffer)| (&buffer.allocation, buffer.mapped_mem))?,
+            AuditedBuffer::Storage(index) => self
+                .gpu
+                .storage_buffers
+                .iter_for_frame(ring)
+                .find(|(live_index, _)| *live_index == index)
+                .map(|(_, buffer)| (&buffer.allocation, buffer.mapped_mem))?,
+            AuditedBuffer::PushConstants(_) => return None,
+        };
+
+        let size = self.gpu.allocator.get_allocation_info(allocation).size as usize;
+        // the slot's last frame has retired, and nothing writes it until
+        // the game's next gpu_update
+        Some(unsafe { std::slice::from_raw_parts(mapped_mem as *const u8, size) })
+    }
+
+    /// The textures and render targets the texture inspector can read back:
+    /// those in formats it decodes, and the depth buffer unless it's multisampled.
+    /// The aliases of the renderer's own targets (scene color, TAA history,
+    /// velocity) aren't listed.
+    pub fn inspectable_images(&self) -> Vec<InspectableImage> {
+        let internal_aliases: Vec<usize> = self
+            .scene_color_texture_indices
+            .iter()
+            .chain(&self.taa_history_texture_indices)
+            .chain(&self.velocity_texture_indices)
+            .copied()
+            .collect();
+        let textures = self
+            .gpu
+            .textures
+            .iter_indexed()
+            .filter(|(index, _)| !internal_aliases.contains(index))
+            // imported images are created without TRANSFER_SRC
+            .filter(|(_, texture)| {
+                !matches!(
+                    texture.image_ownership,
+                    texture::ImageOwnership::Imported(_)
+                )
+            })
+            .filter(|(_, texture)| texture_inspector::texel_size(texture.format).is_some())
+            .map(|(index, texture)| InspectableImage {
+                image: InspectedImage::Texture(index),
+                name: texture.source_file_name.clone(),
+                format: texture.format,
+                extent: [texture.extent.width, texture.extent.height],
+                mip_levels: texture.mip_levels,
+                layers: 1,
+            });
+
+        let depth = (self.msaa_samples == vk::SampleCountFlags::TYPE_1
+            && texture_inspector::texel_size(self.depth_format).is_some())
+        .then(|| InspectableImage {
+            image: InspectedImage::Depth,
+            name: "depth".to_string(),
+            format: self.depth_format,
+            extent: [self.render_extent.width, self.render_extent.height],
+            mip_levels: 1,
+            layers: self.view_mode.view_count(),
+        });
+
+        textures.chain(depth).collect()
+    }
+
+    /// Read back a texture's mip level and layer at the end of every frame
+    /// for the texture inspector; None stops. See [`texture_inspector`].
+    pub fn set_texture_inspector_target(&mut self, target: Option<InspectorTarget>) {
+        self.texture_inspector.set_target(target);
+    }
+
+    pub fn texture_inspector_target(&self) -> Option<InspectorTarget> {
+        self.texture_inspector.target()
+    }
+
+    /// The most recent decoded readback, which lags the target by a couple of frames
+    pub fn texture_inspector_snapshot(&self) -> Option<&InspectorSnapshot> {
+        self.texture_inspector.snapshot()
+    }
+
+    /// The scale from window coordinates to render pixels (0.25 to 1.0)
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
+    /// the defaults detected for this adapter and display at startup
+    pub fn device_profile(&self) -> &DeviceProfile {
+        &self.device_profile
+    }
+
+    /// the main pass's depth format: the first of
+    /// [`Game::depth_formats`](crate::game::Game::depth_formats) the device supports
+    pub fn depth_format(&self) -> vk::Format {
+        self.depth_format
+    }
+
+    /// How the frame is scaled up to the window when the render scale is
+    /// below 1.0; takes effect from the next frame.
+    /// Integer-scaled presentation is always Nearest.
+    pub fn set_upscale_filter(&mut self, filter: UpscaleFilter) -> anyhow::Result<()> {
+        if filter == UpscaleFilter::Sharpened && self.rcas_upscale.is_none() {
+            self.rcas_upscale = Some(RcasUpscale::init(
+                &self.gpu.device,
+                self.gpu.physical_device_properties,
+                self.presenter.image_format,
+            )?);
+        }
+        self.upscale_filter = filter;
+
+        Ok(())
+    }
+
+    /// The filter this frame's composite uses; Sharpened falls back to
+    /// Bilinear in stereo, and on an HDR swapchain (RCAS clamps to [0, 1])
+    pub fn upscale_filter(&self) -> UpscaleFilter {
+        match (self.presentation, self.upscale_filter) {
+            (
+                Presentation::Virtual {
+                    integer_scale: true,
+                    ..
+                },
+                _,
+            ) => UpscaleFilter::Nearest,
+            (_, UpscaleFilter::Sharpened)
+                if self.view_mode != ViewMode::Mono || self.hdr_output().is_hdr() =>
+            {
+                UpscaleFilter::Bilinear
+            }
+            (_, filter) => filter,
+        }
+    }
+
+    /// How the main pass's image is fitted to the window; see
+    /// [`Game::presentation`](crate::game::Game::presentation)
+    pub fn presentation(&self) -> Presentation {
+        self.presentation
+    }
+
+    /// Wait for the previous frame to reach the display, where the device
+    /// supports present wait; returns false when the app should pace frames
+    /// with its own timer instead. See [`present_wait`].
+    pub(crate) fn wait_for_display(&mut self) -> anyhow::Result<bool> {
+        let Some(present_wait) = &mut self.presenter.present_wait else {
+            return Ok(false);
+        };
+        present_wait.wait(self.presenter.swapchain)?;
+
+        Ok(true)
+    }
+
+    /// The render pixel under a window position, past the render scale and
+    /// any borders around a virtual resolution
+    pub fn window_to_render_pixel(&self, position: Vec2) -> [u32; 2] {
+        let offset = Vec2::new(
+            self.present_rect.offset.x as f32,
+            self.present_rect.offset.y as f32,
+        );
+        let pixel = (position - offset).max(Vec2::ZERO) * self.render_scale;
+        [pixel.x as u32, pixel.y as u32]
+    }
+
+    /// The refresh rate of the display the window is on, in Hz;
+    /// None when SDL can't tell
+    pub fn display_refresh_rate(&self) -> Option<f32> {
+        let mode = self.presenter.window.get_display().ok()?.get_mode().ok()?;
+        (mode.refresh_rate > 0.0).then_some(mode.refresh_rate)
+    }
+
+    /// Hide the cursor and keep it in the window, for mouse-look; the game
+    /// then receives [`Input::RelativeMouseMotion`](crate::game::Input::RelativeMouseMotion)
+    pub fn set_relative_mouse_mode(&mut self, enabled: bool) {
+        let window = self.presenter.window.raw();
+        if !unsafe { sdl3::sys::mouse::SDL_SetWindowRelativeMouseMode(window, enabled) } {
+            log::warn!("failed to set relative mouse mode: {}", sdl3::get_error());
+        }
+    }
+
+    pub fn relative_mouse_mode(&self) -> bool {
+        unsafe { sdl3::sys::mouse::SDL_GetWindowRelativeMouseMode(self.presenter.window.raw()) }
+    }
+
+    /// The swapchain's output; Off if HDR wasn't asked for, or the
+    /// display or driver can't do it. See [`hdr`].
+    pub fn hdr_output(&self) -> HdrOutput {
+        self.presenter.hdr_output
+    }
+
+    /// The window's display, as the frame globals report it to shaders;
+    /// always SDR on an SDR swapchain
+    pub fn display_luminance(&self) -> DisplayLuminance {
+        if !self.presenter.hdr_output.is_hdr() {
+            return DisplayLuminance::SDR;
+        }
+
+        let luminance = DisplayLuminance::of_window(&self.presenter.window);
+        if self.hdr_sdr_preview {
+            luminance.without_headroom()
+        } else {
+            luminance
+        }
+    }
+
+    /// Debug: report no headroom past paper white to shaders, to see how HDR
+    /// output tonemaps on an SDR display without leaving HDR mode
+    pub fn set_hdr_sdr_preview(&mut self, enabled: bool) {
+        self.hdr_sdr_preview = enabled;
+    }
+
+    pub fn hdr_sdr_preview(&self) -> bool {
+        self.hdr_sdr_preview
+    }
+
+    /// Draw another frame after this one, under
+    /// [`RedrawMode::OnDemand`](crate::game::RedrawMode::OnDemand);
+    /// eg while an animation plays. Continuous apps draw every frame anyway.
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    /// Whether a redraw was requested since the last call
+    pub(crate) fn take_redraw_request(&mut self) -> bool {
+        std::mem::take(&mut self.redraw_requested)
+    }
+
+    /// When the editor overlay next needs a frame, for its animations and
+    /// tooltips; None when it's idle or disabled
+    #[cfg(feature = "editor")]
+    pub(crate) fn egui_repaint_deadline(&self) -> Option<std::time::Instant> {
+        self.egui.as_ref().and_then(|egui| egui.repaint_deadline())
+    }
+
+    /// Whether the device was created with VK_KHR_fragment_shading_rate,
+    /// so pipelines' [`ShadingRate`]s apply
+    pub fn variable_rate_shading(&self) -> bool {
+        self.gpu.fragment_shading_rate.is_some()
+    }
+
+    /// Each pipeline with a variable [`ShadingRate`], by shader name,
+    /// for debug display
+    pub fn shading_rates(&self) -> Vec<(String, ShadingRate)> {
+        self.gpu
+            .pipelines
+            .iter()
+            .filter(|pipeline| pipeline.shading_rate.is_variable())
+            .map(|pipeline| {
+                let name = debug::clean_shader_name(pipeline.shader.source_file_name());
+                (name.to_string(), pipeline.shading_rate)
+            })
+            .collect()
+    }
+
+    /// The full render area, in render pixels
+    pub fn render_area(&self) -> ClipRect {
+        ClipRect::new(0, 0, self.render_extent.width, self.render_extent.height)
+    }
+
+    /// The window position of a render pixel's corner; the inverse of
+    /// [`Self::window_to_render_pixel`]
+    pub fn render_to_window_position(&self, pixel: Vec2) -> Vec2 {
+        let offset = Vec2::new(
+            self.present_rect.offset.x as f32,
+            self.present_rect.offset.y as f32,
+        );
+        offset + pixel / self.render_scale
+    }
+
+    /// The uniform values the most recently completed frame was drawn with,
+    /// for debug display. None until a frame has completed.
+    pub fn debug_read_uniform<T: Clone>(
+        &self,
+        uniform_buffer: &UniformBufferHandle<T>,
+    ) -> Option<T> {
+        let ring_slot = self.last_completed_ring_slot()?;
+        Some(
+            self.gpu
+                .uniform_buffers
+                .read_for_frame(uniform_buffer, ring_slot),
+        )
+    }
+
+    /// The storage buffer contents as of the most recently completed frame,
+    /// including any GPU writes, for debug display. None until a frame has completed.
+    pub fn debug_read_storage<T: Clone>(
+        &self,
+        storage_buffer: &StorageBufferHandle<T>,
+    ) -> Option<Vec<T>> {
+        let ring_slot = self.last_completed_ring_slot()?;
+        Some(
+            self.gpu
+                .storage_buffers
+                .read_for_frame(storage_buffer, ring_slot),
+        )
+    }
+
+    /// The ring slot of the newest frame whose timeline value has signaled.
+    /// Call between frames; the CPU won't rewrite that slot until a later frame.
+    fn last_completed_ring_slot(&self) -> Option<usize> {
+        // draw_frame already waited on this one
+        let known_complete = self.total_frames.saturating_sub(MAX_FRAMES_IN_FLIGHT);
+        let signaled = unsafe {
+            self.gpu
+                .device
+                .get_semaphore_counter_value(self.frame_timeline)
+        }
+        .map(|value| value as usize)
+        .unwrap_or(known_complete);
+        let frame = signaled.clamp(known_complete, self.total_frames);
+        if frame == 0 {
+            return None;
+        }
+
+        // the next frame uses ring_slot; earlier frames count back from it
+        let frames_ago = self.total_frames + 1 - frame;
+        Some((self.slots.ring + PRE_WAIT_RING_LEN - frames_ago) % PRE_WAIT_RING_LEN)
+    }
+
+    /// Pipelines that bind the buffer can no longer be drawn;
+    /// their draws return [`DrawError::OrphanedPipeline`]
     pub fn drop_uniform_buffer<T>(&mut self, uniform_buffer: UniformBufferHandle<T>) {
-        let buffers_per_frame = self.uniform_buffers.take(uniform_buffer);
+        let index = RawUniformBufferHandle::from_typed(&uniform_buffer).index();
+        self.orphan_dependent_pipelines(ResourceId::UniformBuffer(index));
+        self.pending_uniform_rebinds
+            .retain(|rebind| rebind.uniform_buffer.index() != index);
+        let buffers_per_frame = self.gpu.uniform_buffers.take(uniform_buffer);
+        for raw_uniform_buffer in buffers_per_frame {
+            self.destroy_uniform_buffer(raw_uniform_buffer);
+        }
+    }
+
+    /// [`Renderer::drop_uniform_buffer`] for buffers from
+    /// [`Renderer::create_raw_uniform_buffer`]
+    pub fn drop_raw_uniform_buffer(&mut self, uniform_buffer: RawUniformBufferHandle) {
+        let index = uniform_buffer.index();
+        self.orphan_dependent_pipelines(ResourceId::UniformBuffer(index));
+        self.pending_uniform_rebinds
+            .retain(|rebind| rebind.uniform_buffer.index() != index);
+        let buffers_per_frame = self.gpu.uniform_buffers.take_raw(uniform_buffer);
         for raw_uniform_buffer in buffers_per_frame {
             self.destroy_uniform_buffer(raw_uniform_buffer);
         }
     }
 
+    /// Point a pipeline's `uniform_index`th uniform buffer (in the order given
+    /// to its config) at a different buffer, e.g. to switch between two cameras.
+    ///
+    /// Descriptor sets are per ring slot, so each slot's set is rewritten at
+    /// the start of the next frame that uses it, once its previous frame has
+    /// retired. Draws from the next frame on read the new buffer.
+    pub fn rebind_uniform_buffer<D: DrawCall<Index = GraphicsPipelineIndex>, T>(
+        &mut self,
+        pipeline: &PipelineHandle<D>,
+        uniform_index: usize,
+        uniform_buffer: &UniformBufferHandle<T>,
+    ) -> anyhow::Result<()> {
+        let raw_handle = RawUniformBufferHandle::from_typed(uniform_buffer);
+        let renderer_pipeline = self.gpu.pipelines.get(pipeline);
+        let sets_per_frame = renderer_pipeline.layout.descriptor_set_layouts.len();
+        let rebind = uniform_rebind(
+            renderer_pipeline.shader.source_file_name(),
+            &renderer_pipeline.shader.layout_bindings(),
+            &renderer_pipeline.descriptor_sets,
+            sets_per_frame,
+            uniform_index,
+            &raw_handle,
+        )?;
+
+        let renderer_pipeline = self.gpu.pipelines.get_mut(pipeline);
+        rebind_resource(
+            &mut renderer_pipeline.resources,
+            uniform_index,
+            raw_handle.index(),
+        );
+        #[cfg(debug_assertions)]
+        {
+            renderer_pipeline.bound_uniform_buffers[uniform_index] = raw_handle.index();
+        }
+
+        self.queue_uniform_rebind(rebind);
+        Ok(())
+    }
+
+    /// [`Renderer::rebind_uniform_buffer`] for compute pipelines
+    pub fn rebind_compute_uniform_buffer<T>(
+        &mut self,
+        pipeline: &PipelineHandle<Compute>,
+        uniform_index: usize,
+        uniform_buffer: &UniformBufferHandle<T>,
+    ) -> anyhow::Result<()> {
+        let raw_handle = RawUniformBufferHandle::from_typed(uniform_buffer);
+        let compute_pipeline = self.gpu.compute_pipelines.get(pipeline);
+        let sets_per_frame = compute_pipeline.layout.descriptor_set_layouts.len();
+        let rebind = uniform_rebind(
+            compute_pipeline.shader.source_file_name(),
+            &compute_pipeline.shader.layout_bindings(),
+            &compute_pipeline.descriptor_sets,
+            sets_per_frame,
+            uniform_index,
+            &raw_handle,
+        )?;
+
+        let compute_pipeline = self.gpu.compute_pipelines.get_mut(pipeline);
+        rebind_resource(
+            &mut compute_pipeline.resources,
+            uniform_index,
+            raw_handle.index(),
+        );
+        #[cfg(debug_assertions)]
+        {
+            compute_pipeline.bound_uniform_buffers[uniform_index] = raw_handle.index();
+        }
+
+        self.queue_uniform_rebind(rebind);
+        Ok(())
+    }
+
+    fn queue_uniform_rebind(&mut self, rebind: PendingUniformRebind) {
+        // a newer rebind of the same descriptor supersedes any still in progress
+        self.pending_uniform_rebinds.retain(|pending| {
+            pending.dst_sets != rebind.dst_sets || pending.binding != rebind.binding
+        });
+        self.pending_uniform_rebinds.push(rebind);
+    }
+
+    /// Rewrite the current ring slot's descriptors for any queued rebinds.
+    /// Safe before the timeline wait for the same reason the CPU buffer writes are:
+    /// the slot's sets were last used by frame (total - PRE_WAIT_RING_LEN).
+    fn write_uniform_rebinds(&mut self) {
+        let ring_slot = self.slots.ring;
+        for rebind in &mut self.pending_uniform_rebinds {
+            if !rebind.stale_slots[ring_slot] {
+                continue;
+            }
+
+            let raw_buffers = self.gpu.uniform_buffers.get_raw(&rebind.uniform_buffer);
+            let buffer_info = [vk::DescriptorBufferInfo::default()
+                .offset(0)
+                .buffer(raw_buffers[ring_slot].buffer)
+                .range(rebind.range)];
+            let write = vk::WriteDescriptorSet::default()
+                .dst_set(rebind.dst_sets[ring_slot])
+                .dst_binding(rebind.binding)
+                .dst_array_element(0)
+                .descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+                .descriptor_count(rebind.descriptor_count)
+                .buffer_info(&buffer_info);
+
+            unsafe { self.gpu.device.update_descriptor_sets(&[write], &[]) };
+            rebind.stale_slots[ring_slot] = false;
+        }
+
+        self.pending_uniform_rebinds
+            .retain(|rebind| rebind.stale_slots.contains(&true));
+    }
+
+    /// Marks the pipelines whose descriptor sets bind a resource that's about
+    /// to be destroyed. Their descriptors would dangle, so they're never drawn again.
+    fn orphan_dependent_pipelines(&mut self, resource: ResourceId) {
+        let graphics = self
+            .gpu
+            .pipelines
+            .iter_mut()
+            .map(|pipeline| (&pipeline.resources, &mut pipeline.orphaned_by));
+        let compute = self
+            .gpu
+            .compute_pipelines
+            .iter_mut()
+            .map(|pipeline| (&pipeline.resources, &mut pipeline.orphaned_by));
+        for (resources, orphaned_by) in graphics.chain(compute) {
+            if orphaned_by.is_none() && resources.contains(&resource) {
+                *orphaned_by = Some(resource);
+            }
+        }
+    }
+
     fn destroy_uniform_buffer(&mut self, mut uniform_buffer: RawUniformBuffer) {
         unsafe {
-            self.allocator
+            self.gpu
+                .allocator
                 .destroy_buffer(uniform_buffer.buffer, &mut uniform_buffer.allocation);
         }
     }
@@ -846,24 +2745,56 @@ impl Renderer {
         &mut self,
         len: u32,
     ) -> anyhow::Result<StorageBufferHandle<T>> {
-        let buffers_per_frame = self.create_storage_buffers_per_frame::<T>(len)?;
-        Ok(self.storage_buffers.add(buffers_per_frame, len))
+        let buffers_per_frame =
+            self.create_storage_buffers_per_frame::<T>(len, BufferMemory::PersistentlyMapped)?;
+        Ok(self.gpu.storage_buffers.add(buffers_per_frame, len))
     }
 
     pub fn create_immutable_buffer<T: GPUWrite>(
         &mut self,
         len: u32,
     ) -> anyhow::Result<ImmutableBufferHandle<T>> {
-        let buffers_per_frame = self.create_storage_buffers_per_frame::<T>(len)?;
-        Ok(self.storage_buffers.add_immutable(buffers_per_frame, len))
+        let buffers_per_frame =
+            self.create_storage_buffers_per_frame::<T>(len, BufferMemory::PersistentlyMapped)?;
+        Ok(self
+            .gpu
+            .storage_buffers
+            .add_immutable(buffers_per_frame, len))
     }
 
     pub fn create_gpu_only_buffer<T: GPUWrite>(
         &mut self,
         len: u32,
     ) -> anyhow::Result<GpuOnlyBufferHandle<T>> {
-        let buffers_per_frame = self.create_storage_buffers_per_frame::<T>(len)?;
-        Ok(self.storage_buffers.add_gpu_only(buffers_per_frame, len))
+        let buffers_per_frame =
+            self.create_storage_buffers_per_frame::<T>(len, BufferMemory::PersistentlyMapped)?;
+        Ok(self
+            .gpu
+            .storage_buffers
+            .add_gpu_only(buffers_per_frame, len))
+    }
+
+    /// An untyped buffer of `size` bytes, for a shader's `ByteAddressBuffer`
+    /// or `RWByteAddressBuffer` parameter (see [`PipelineConfig::with_raw_buffers`]).
+    /// Byte address buffers load and store whole words, so `size` must be a
+    /// nonzero multiple of 4.
+    pub fn create_raw_buffer(&mut self, size: u32) -> anyhow::Result<RawBufferHandle> {
+        if size == 0 || size % 4 != 0 {
+            anyhow::bail!("raw buffer size must be a nonzero multiple of 4 bytes, got {size}");
+        }
+
+        let buffers_per_frame =
+            self.create_storage_buffers_per_frame::<u8>(size, BufferMemory::PersistentlyMapped)?;
+        Ok(self.gpu.storage_buffers.add_raw(buffers_per_frame, size))
+    }
+
+    /// `len` atomic counters for GPU-side compaction and emission,
+    /// zeroed at the start of every frame; see [`counter_buffer`]
+    pub fn create_counter_buffer(&mut self, len: u32) -> anyhow::Result<CounterBufferHandle> {
+        // read back every frame, so cached host memory rather than write-combined
+        let buffers_per_frame =
+            self.create_storage_buffers_per_frame::<u32>(len, BufferMemory::Readback)?;
+        Ok(self.gpu.counter_buffers.add(buffers_per_frame, len))
     }
 
     /// Enable pipelined async compute; call during Game::setup.
@@ -882,6 +2813,7 @@ impl Renderer {
     fn create_storage_buffers_per_frame<T: GPUWrite>(
         &mut self,
         len: u32,
+        memory: BufferMemory,
     ) -> anyhow::Result<[RawStorageBuffer; PRE_WAIT_RING_LEN]> {
         let buffer_size = (len as usize * std::mem::size_of::<T>()) as u64;
 
@@ -890,16 +2822,22 @@ impl Renderer {
         #[expect(clippy::needless_range_loop)]
         for i in 0..PRE_WAIT_RING_LEN {
             let (buffer, allocation) = create_memory_buffer(
-                &self.allocator,
+                &self.gpu.allocator,
                 buffer_size,
                 vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
-                BufferMemory::PersistentlyMapped,
+                memory,
             )?;
 
-            let mapped_mem = self.allocator.get_allocation_info(&allocation).mapped_data;
+            let mapped_mem = self
+                .gpu
+                .allocator
+                .get_allocation_info(&allocation)
+                .mapped_data;
+            // zeroed so Gpu::storage_slice_mut never hands out uninitialized memory
+            unsafe { std::ptr::write_bytes(mapped_mem as *mut u8, 0, buffer_size as usize) };
 
             let device_address = unsafe {
-                self.device.get_buffer_device_address(
+                self.gpu.device.get_buffer_device_address(
                     &vk::BufferDeviceAddressInfo::default().buffer(buffer),
                 )
             };
@@ -910,6 +2848,8 @@ impl Renderer {
                 allocation,
                 mapped_mem,
                 device_address,
+                #[cfg(debug_assertions)]
+                writes: SlotWrites::Unwritten,
             });
         }
         Ok(buffers_per_frame.map(Option::unwrap))
@@ -919,7 +2859,10 @@ impl Renderer {
         debug_assert!(data.len() <= buf.len() as usize);
         let len = data.len().min(buf.len() as usize);
         for frame in 0..PRE_WAIT_RING_LEN {
-            let mapped = self.storage_buffers.get_mapped_mem_for_frame(buf, frame);
+            let mapped = self
+                .gpu
+                .storage_buffers
+                .get_mapped_mem_for_frame(buf, frame);
             unsafe {
                 std::ptr::copy_nonoverlapping(data.as_ptr(), mapped, len);
             }
@@ -935,6 +2878,7 @@ impl Renderer {
         let len = data.len().min(buf.len() as usize);
         for frame in 0..PRE_WAIT_RING_LEN {
             let mapped = self
+                .gpu
                 .storage_buffers
                 .get_mapped_mem_for_frame_immutable(buf, frame);
             unsafe {
@@ -950,6 +2894,7 @@ impl Renderer {
         let len = data.len().min(buf.len() as usize);
         for frame in 0..PRE_WAIT_RING_LEN {
             let mapped = self
+                .gpu
                 .storage_buffers
                 .get_mapped_mem_for_frame_gpu_only(buf, frame);
             unsafe {
@@ -958,30 +2903,69 @@ impl Renderer {
         }
     }
 
+    /// Copies `bytes` into every frame's buffer; bytes beyond its size are dropped
+    pub fn write_raw_buffer_all_frames(&mut self, buf: &mut RawBufferHandle, bytes: &[u8]) {
+        debug_assert!(bytes.len() <= buf.size() as usize);
+        let len = bytes.len().min(buf.size() as usize);
+        for frame in 0..PRE_WAIT_RING_LEN {
+            let mapped = self
+                .gpu
+                .storage_buffers
+                .get_mapped_mem_for_frame_raw(buf, frame);
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), mapped, len);
+            }
+        }
+    }
+
     pub fn drop_storage_buffer<T>(&mut self, storage_buffer: StorageBufferHandle<T>) {
-        let buffers_per_frame = self.storage_buffers.take(storage_buffer);
+        let buffers_per_frame = self.gpu.storage_buffers.take(storage_buffer);
         for raw_storage_buffer in buffers_per_frame {
             self.destroy_storage_buffer(raw_storage_buffer);
         }
     }
 
     pub fn drop_immutable_buffer<T>(&mut self, immutable_buffer: ImmutableBufferHandle<T>) {
-        let buffers_per_frame = self.storage_buffers.take_immutable(immutable_buffer);
+        let buffers_per_frame = self.gpu.storage_buffers.take_immutable(immutable_buffer);
         for raw_storage_buffer in buffers_per_frame {
             self.destroy_storage_buffer(raw_storage_buffer);
         }
     }
 
     pub fn drop_gpu_only_buffer<T>(&mut self, gpu_only_buffer: GpuOnlyBufferHandle<T>) {
-        let buffers_per_frame = self.storage_buffers.take_gpu_only(gpu_only_buffer);
+        let buffers_per_frame = self.gpu.storage_buffers.take_gpu_only(gpu_only_buffer);
+        for raw_storage_buffer in buffers_per_frame {
+            self.destroy_storage_buffer(raw_storage_buffer);
+        }
+    }
+
+    /// Pipelines that bind the buffer can no longer be drawn;
+    /// their draws return [`DrawError::OrphanedPipeline`]
+    pub fn drop_raw_buffer(&mut self, raw_buffer: RawBufferHandle) {
+        self.orphan_dependent_pipelines(ResourceId::RawBuffer(raw_buffer.index()));
+        let buffers_per_frame = self.gpu.storage_buffers.take_raw(raw_buffer);
+        for raw_storage_buffer in buffers_per_frame {
+            self.destroy_storage_buffer(raw_storage_buffer);
+        }
+    }
+
+    pub fn drop_counter_buffer(&mut self, counter_buffer: CounterBufferHandle) {
+        let buffers_per_frame = self.gpu.counter_buffers.take(counter_buffer);
         for raw_storage_buffer in buffers_per_frame {
             self.destroy_storage_buffer(raw_storage_buffer);
         }
     }
 
+    /// The counts from the last frame to retire with this buffer's ring slot,
+    /// `PRE_WAIT_RING_LEN` frames behind the one being recorded
+    pub fn counts(&self, counter_buffer: &CounterBufferHandle) -> &[u32] {
+        self.gpu.counter_buffers.counts(counter_buffer)
+    }
+
     fn destroy_storage_buffer(&mut self, mut storage_buffer: RawStorageBuffer) {
         unsafe {
-            self.allocator
+            self.gpu
+                .allocator
                 .destroy_buffer(storage_buffer.buffer, &mut storage_buffer.allocation);
         }
     }
@@ -991,7 +2975,7 @@ impl Renderer {
         config: PipelineConfig<V, D>,
     ) -> anyhow::Result<PipelineHandle<D>> {
         let pipeline = self.init_pipeline(config)?;
-        let handle = self.pipelines.add(pipeline);
+        let handle = self.gpu.pipelines.add(pipeline);
 
         Ok(handle)
     }
@@ -1009,23 +2993,23 @@ impl Renderer {
         }
 
         let (vertex_buffer, vertex_buffer_memory) = create_vertex_buffer(
-            &self.allocator,
-            &self.device,
+            &self.gpu.allocator,
+            &self.gpu.device,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
             vertices,
         )?;
 
         let (index_buffer, index_buffer_memory) = create_index_buffer(
-            &self.allocator,
-            &self.device,
+            &self.gpu.allocator,
+            &self.gpu.device,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
             indices,
         )?;
 
-        let index = MeshIndex::from_raw(self.meshes.len());
-        self.meshes.push(VertexAndIndexBuffers {
+        let index = MeshIndex::from_raw(self.gpu.meshes.len());
+        self.gpu.meshes.push(VertexAndIndexBuffers {
             vertex_buffer,
             vertex_buffer_memory,
             index_buffer,
@@ -1039,21 +3023,62 @@ impl Renderer {
         })
     }
 
-    pub fn create_picking_pipeline<V: VertexDescription>(
+    /// Point a config's frame globals and split-screen views placeholders at
+    /// the renderer's buffers, creating each for the first config that binds it
+    fn resolve_renderer_uniforms(
         &mut self,
-        picking_config: PipelineConfig<V, DrawVertexCount>,
-    ) -> anyhow::Result<PickingPipelineHandle> {
-        // Lazily initialize picking resources on first use
-        if self.picking.is_none() {
-            self.picking = Some(PickingResources::init(
-                &self.allocator,
-                &self.device,
-                self.render_extent,
-            )?);
+        uniform_buffer_handles: &mut [RawUniformBufferHandle],
+    ) -> anyhow::Result<()> {
+        if uniform_buffer_handles
+            .iter()
+            .any(RawUniformBufferHandle::is_frame_globals)
+        {
+            if self.frame_globals.buffer.is_none() {
+                let buffer = self.create_uniform_buffer::<FrameGlobals>()?;
+                self.frame_globals.buffer = Some(buffer);
+            }
+            let buffer = self.frame_globals.buffer.as_ref().unwrap();
+            frame_globals::resolve_placeholders(uniform_buffer_handles, buffer);
+        }
+
+        if uniform_buffer_handles
+            .iter()
+            .any(RawUniformBufferHandle::is_split_screen_views)
+        {
+            if self.split_screen.buffer.is_none() {
+                let buffer = self.create_uniform_buffer::<SplitScreenViews>()?;
+                self.split_screen.buffer = Some(buffer);
+            }
+            let buffer = self.split_screen.buffer.as_ref().unwrap();
+            split_screen::resolve_placeholders(uniform_buffer_handles, buffer);
+        }
+
+        Ok(())
+    }
+
+    pub fn create_picking_pipeline<V: VertexDescription>(
+        &mut self,
+        mut picking_config: PipelineConfig<V, DrawVertexCount>,
+    ) -> anyhow::Result<PickingPipelineHandle> {
+        self.resolve_renderer_uniforms(&mut picking_config.uniform_buffer_handles)?;
+        validate_uniform_buffers(
+            picking_config.shader.source_file_name(),
+            &picking_config.shader.reflection_json().global_parameters,
+            &picking_config.shader.layout_bindings(),
+            &picking_config.uniform_buffer_handles,
+        )?;
+
+        // Lazily initialize picking resources on first use
+        if self.picking.is_none() {
+            self.picking = Some(PickingResources::init(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                self.render_extent,
+            )?);
         }
 
         let picking_pipeline_layout =
-            ShaderPipelineLayout::create_from_atlas(&self.device, &*picking_config.shader)?;
+            ShaderPipelineLayout::create_from_atlas(&self.gpu.device, &*picking_config.shader)?;
         // picking renders ids into a uint target with no depth attachment, so
         // blending is meaningless and the depth-stencil state is ignored
         // entirely (depth_write is set false as the honest value)
@@ -1064,24 +3089,27 @@ impl Renderer {
             ..Default::default()
         };
         let picking_pipeline = create_graphics_pipeline(
-            &self.device,
-            picking::PICKING_FORMAT,
+            &self.gpu.device,
+            &[picking::PICKING_FORMAT],
+            false,
             None, // no depth attachment for picking
             vk::SampleCountFlags::TYPE_1,
+            0,
             &picking_pipeline_layout,
             &picking_config.shader.vertex_binding_descriptions(),
             &picking_config.shader.vertex_attribute_descriptions(),
             &picking_raster_state,
+            false,
         )?;
 
         let layout_bindings = picking_config.shader.layout_bindings();
-        let descriptor_pool = create_descriptor_pool(&self.device, &picking_pipeline_layout)?;
+        let descriptor_pool = create_descriptor_pool(&self.gpu.device, &picking_pipeline_layout)?;
 
         let uniform_buffers_in_layout_frame_order: Vec<&[RawUniformBuffer; PRE_WAIT_RING_LEN]> =
             picking_config
                 .uniform_buffer_handles
                 .iter()
-                .map(|raw_handle| self.uniform_buffers.get_raw(raw_handle))
+                .map(|raw_handle| self.gpu.uniform_buffers.get_raw(raw_handle))
                 .collect();
 
         let set_layouts: Vec<_> = picking_pipeline_layout
@@ -1090,15 +3118,22 @@ impl Renderer {
             .map(|t| t.0)
             .collect();
         let descriptor_sets = create_descriptor_sets(
-            &self.device,
+            &self.gpu.device,
             descriptor_pool,
             &set_layouts,
             &uniform_buffers_in_layout_frame_order,
             &[],
             &[],
+            &[],
+            &[],
             layout_bindings,
         )?;
 
+        let push_constants_size = picking_config
+            .shader
+            .pipeline_layout()
+            .push_constants_size();
+        let push_constants = vec![0; push_constants_size];
         let renderer_pipeline = RendererPipeline {
             layout: picking_pipeline_layout,
             pipeline: picking_pipeline,
@@ -1107,28 +3142,48 @@ impl Renderer {
             descriptor_sets,
             shader: picking_config.shader,
             raster_state: picking_raster_state,
+            shading_rate: ShadingRate::Full,
+            depth_prepass: None,
+            render_target: None,
+            post_process: false,
+            split_screen: false,
+            ui_layer: false,
+            velocity: false,
+            scene_color_writes: vec![],
+            variants: vec![],
+            push_constants,
+            #[cfg(debug_assertions)]
+            bound_images: vec![],
+            #[cfg(debug_assertions)]
+            bound_uniform_buffers: raw_handle_indices(&picking_config.uniform_buffer_handles),
+            resources: bound_resources(&[], &picking_config.uniform_buffer_handles, &[]),
+            orphaned_by: None,
         };
 
-        let handle = self.pipelines.add_picking(renderer_pipeline);
+        let handle = self.gpu.pipelines.add_picking(renderer_pipeline);
         Ok(handle)
     }
 
     fn destroy_pipeline(&mut self, pipeline: RendererPipeline) {
         unsafe {
             // this also destroys the sets from the pool
-            self.device
+            self.gpu
+                .device
                 .destroy_descriptor_pool(pipeline.descriptor_pool, None);
 
             for &(desc_set_layout, _) in &pipeline.layout.descriptor_set_layouts {
-                self.device
+                self.gpu
+                    .device
                     .destroy_descriptor_set_layout(desc_set_layout, None);
             }
 
             match pipeline.vertex_pipeline_config {
                 VertexPipelineConfig::VertexAndIndexBuffers(mut vi_bufs) => {
-                    self.allocator
+                    self.gpu
+                        .allocator
                         .destroy_buffer(vi_bufs.index_buffer, &mut vi_bufs.index_buffer_memory);
-                    self.allocator
+                    self.gpu
+                        .allocator
                         .destroy_buffer(vi_bufs.vertex_buffer, &mut vi_bufs.vertex_buffer_memory);
                 }
 
@@ -1138,24 +3193,43 @@ impl Renderer {
                 VertexPipelineConfig::VertexCount => {}
             }
 
-            self.device.destroy_pipeline(pipeline.pipeline, None);
-            self.device
+            for variant in &pipeline.variants {
+                self.gpu.device.destroy_pipeline(variant.pipeline, None);
+                if let Some(depth_prepass) = variant.depth_prepass {
+                    self.gpu.device.destroy_pipeline(depth_prepass, None);
+                }
+            }
+            self.gpu.device.destroy_pipeline(pipeline.pipeline, None);
+            if let Some(depth_prepass) = pipeline.depth_prepass {
+                self.gpu.device.destroy_pipeline(depth_prepass, None);
+            }
+            self.gpu
+                .device
                 .destroy_pipeline_layout(pipeline.layout.pipeline_layout, None);
         }
     }
 
     pub fn create_compute_pipeline(
         &mut self,
-        config: ComputePipelineConfig,
+        mut config: ComputePipelineConfig,
     ) -> anyhow::Result<PipelineHandle<Compute>> {
+        self.resolve_renderer_uniforms(&mut config.uniform_buffer_handles)?;
+        validate_uniform_buffers(
+            config.shader.source_file_name(),
+            &config.shader.reflection_json().global_parameters,
+            &config.shader.layout_bindings(),
+            &config.uniform_buffer_handles,
+        )?;
+
         let pipeline_layout =
-            ComputeShaderPipelineLayout::create_from_atlas(&self.device, &*config.shader)?;
+            ComputeShaderPipelineLayout::create_from_atlas(&self.gpu.device, &*config.shader)?;
 
         let shader_module = {
             let shader_module_create_info = vk::ShaderModuleCreateInfo::default()
                 .code(&pipeline_layout.compute_shader.spv_bytes);
             unsafe {
-                self.device
+                self.gpu
+                    .device
                     .create_shader_module(&shader_module_create_info, None)?
             }
         };
@@ -1170,7 +3244,7 @@ impl Renderer {
             .layout(pipeline_layout.pipeline_layout);
 
         let pipeline = unsafe {
-            self.device.create_compute_pipelines(
+            self.gpu.device.create_compute_pipelines(
                 vk::PipelineCache::null(),
                 &[compute_pipeline_create_info],
                 None,
@@ -1184,35 +3258,38 @@ impl Renderer {
         );
 
         unsafe {
-            self.device.destroy_shader_module(shader_module, None);
+            self.gpu.device.destroy_shader_module(shader_module, None);
         }
 
         let layout_bindings = config.shader.layout_bindings();
         let descriptor_pool = create_descriptor_pool_from_layouts(
-            &self.device,
+            &self.gpu.device,
             &pipeline_layout.descriptor_set_layouts,
         )?;
 
-        let textures = {
-            let mut textures = vec![];
-            for texture_handle in config.texture_handles {
-                let texture = self.textures.get(texture_handle);
-                textures.push(texture);
-            }
-            textures
-        };
+        let textures: Vec<_> = config
+            .texture_handles
+            .iter()
+            .map(|texture_handle| self.texture_image_infos(texture_handle))
+            .collect();
 
         let uniform_buffers_in_layout_frame_order: Vec<&[RawUniformBuffer; PRE_WAIT_RING_LEN]> =
             config
                 .uniform_buffer_handles
                 .iter()
-                .map(|raw_handle| self.uniform_buffers.get_raw(raw_handle))
+                .map(|raw_handle| self.gpu.uniform_buffers.get_raw(raw_handle))
                 .collect();
 
         let storage_images: Vec<&storage_texture::StorageTexture> = config
             .storage_texture_handles
             .iter()
-            .map(|handle| self.storage_textures.get(handle))
+            .map(|handle| self.gpu.storage_textures.get(handle))
+            .collect();
+
+        let raw_buffers: Vec<_> = config
+            .raw_buffer_handles
+            .iter()
+            .map(|handle| self.gpu.storage_buffers.raw_buffers_per_frame(handle))
             .collect();
 
         let set_layouts: Vec<_> = pipeline_layout
@@ -1221,24 +3298,39 @@ impl Renderer {
             .map(|t| t.0)
             .collect();
         let descriptor_sets = create_descriptor_sets(
-            &self.device,
+            &self.gpu.device,
             descriptor_pool,
             &set_layouts,
             &uniform_buffers_in_layout_frame_order,
             &textures,
             &storage_images,
+            &[],
+            &raw_buffers,
             layout_bindings,
         )?;
 
+        #[cfg(debug_assertions)]
+        let bound_images =
+            self.bound_images(&config.texture_handles, &config.storage_texture_handles);
         let compute_renderer_pipeline = ComputeRendererPipeline {
             layout: pipeline_layout,
             pipeline,
             descriptor_pool,
             descriptor_sets,
             shader: config.shader,
+            #[cfg(debug_assertions)]
+            bound_images,
+            #[cfg(debug_assertions)]
+            bound_uniform_buffers: raw_handle_indices(&config.uniform_buffer_handles),
+            resources: bound_resources(
+                &config.texture_handles,
+                &config.uniform_buffer_handles,
+                &config.raw_buffer_handles,
+            ),
+            orphaned_by: None,
         };
 
-        let handle = self.compute_pipelines.add(compute_renderer_pipeline);
+        let handle = self.gpu.compute_pipelines.add(compute_renderer_pipeline);
         self.has_compute_pipelines = true;
 
         Ok(handle)
@@ -1246,26 +3338,146 @@ impl Renderer {
 
     fn destroy_compute_pipeline(&mut self, pipeline: ComputeRendererPipeline) {
         unsafe {
-            self.device
+            self.gpu
+                .device
                 .destroy_descriptor_pool(pipeline.descriptor_pool, None);
 
             for &(desc_set_layout, _) in &pipeline.layout.descriptor_set_layouts {
-                self.device
+                self.gpu
+                    .device
                     .destroy_descriptor_set_layout(desc_set_layout, None);
             }
 
-            self.device.destroy_pipeline(pipeline.pipeline, None);
-            self.device
+            self.gpu.device.destroy_pipeline(pipeline.pipeline, None);
+            self.gpu
+                .device
                 .destroy_pipeline_layout(pipeline.layout.pipeline_layout, None);
         }
     }
 
     fn init_pipeline<V: VertexDescription, D: DrawCall>(
         &mut self,
-        config: PipelineConfig<V, D>,
+        mut config: PipelineConfig<V, D>,
     ) -> anyhow::Result<RendererPipeline> {
+        self.resolve_renderer_uniforms(&mut config.uniform_buffer_handles)?;
+        validate_uniform_buffers(
+            config.shader.source_file_name(),
+            &config.shader.reflection_json().global_parameters,
+            &config.shader.layout_bindings(),
+            &config.uniform_buffer_handles,
+        )?;
+
+        let binds_scene_color = config
+            .texture_handles
+            .iter()
+            .any(|handle| self.scene_color_texture_indices.contains(&handle.index()));
+        if binds_scene_color && !config.post_process {
+            anyhow::bail!(
+                "{}: only post-process pipelines may sample the scene color",
+                config.shader.source_file_name()
+            );
+        }
+        let binds_taa_history = config
+            .texture_handles
+            .iter()
+            .any(|handle| self.taa_history_texture_indices.contains(&handle.index()));
+        if binds_taa_history && !config.post_process {
+            anyhow::bail!(
+                "{}: only post-process pipelines may sample the TAA history",
+                config.shader.source_file_name()
+            );
+        }
+        let binds_velocity = config
+            .texture_handles
+            .iter()
+            .any(|handle| self.velocity_texture_indices.contains(&handle.index()));
+        if binds_velocity && !config.post_process {
+            anyhow::bail!(
+                "{}: only post-process pipelines may sample the velocity buffer",
+                config.shader.source_file_name()
+            );
+        }
+        if config.post_process && config.render_target.is_some() {
+            anyhow::bail!(
+                "{}: a pipeline can't both post-process and draw into a feedback texture",
+                config.shader.source_file_name()
+            );
+        }
+        if config.depth_prepass && (config.post_process || config.render_target.is_some()) {
+            anyhow::bail!(
+                "{}: only main pass pipelines have a depth attachment for a prepass",
+                config.shader.source_file_name()
+            );
+        }
+        if config.depth_prepass
+            && (config.disable_depth_test
+                || config.raster_state.depth_test == DepthCompare::Disabled)
+        {
+            anyhow::bail!(
+                "{}: a depth prepass needs a depth test",
+                config.shader.source_file_name()
+            );
+        }
+        if config.shader.reflection_json().is_mesh_shader() && self.gpu.mesh_shader.is_none() {
+            anyhow::bail!(
+                "{}: mesh shaders require DeviceExtensions::with_mesh_shaders",
+                config.shader.source_file_name()
+            );
+        }
+        if config.shading_rate.is_variable() && self.gpu.fragment_shading_rate.is_none() {
+            anyhow::bail!(
+                "{}: shading rates require DeviceExtensions::with_fragment_shading_rate",
+                config.shader.source_file_name()
+            );
+        }
+        if config.post_process && self.view_mode != ViewMode::Mono {
+            anyhow::bail!(
+                "{}: post-process passes don't support stereo views yet",
+                config.shader.source_file_name()
+            );
+        }
+        if config.split_screen
+            && (config.post_process || config.render_target.is_some() || config.depth_prepass)
+        {
+            anyhow::bail!(
+                "{}: split-screen views are only for main pass pipelines without a depth prepass",
+                config.shader.source_file_name()
+            );
+        }
+        if config.split_screen
+            && (self.view_mode != ViewMode::Mono
+                || config.shader.reflection_json().is_mesh_shader())
+        {
+            anyhow::bail!(
+                "{}: split-screen views need a mono view mode and instanced draws",
+                config.shader.source_file_name()
+            );
+        }
+        if config.ui_layer
+            && (config.post_process
+                || config.render_target.is_some()
+                || config.depth_prepass
+                || config.split_screen)
+        {
+            anyhow::bail!(
+                "{}: ui layer pipelines draw in their own pass, without a depth attachment",
+                config.shader.source_file_name()
+            );
+        }
+        if config.velocity
+            && (config.post_process || config.render_target.is_some() || config.ui_layer)
+        {
+            anyhow::bail!(
+                "{}: only main pass pipelines write velocity",
+                config.shader.source_file_name()
+            );
+        }
+        // shaders that write velocity get the flag from generated code, so
+        // games without a velocity buffer just drop the output
+        let writes_velocity = config.velocity && self.velocity.is_some();
+
         let pipeline_layout =
-            ShaderPipelineLayout::create_from_atlas(&self.device, &*config.shader)?;
+            ShaderPipelineLayout::create_from_atlas(&self.gpu.device, &*config.shader)?;
 
         // the older, coarser disable_depth_test flag (emitted by generated
         // pipeline_config()) wins over the raster state's depth compare
@@ -1274,21 +3486,44 @@ impl Renderer {
             raster_state.depth_test = DepthCompare::Disabled;
         }
 
-        let pipeline = create_graphics_pipeline(
-            &self.device,
-            self.image_format,
-            Some(self.depth_format),
-            self.msaa_samples,
+        let render_target = config.render_target.map(|handle| handle.index);
+        let post_process = config.post_process;
+        if render_target.is_some() || post_process || config.ui_layer {
+            // feedback, post-process, and ui passes have no depth attachment
+            raster_state.depth_test = DepthCompare::Disabled;
+            raster_state.depth_write = false;
+        }
+
+        if post_process && self.post_process.is_none() {
+            self.post_process = Some(PostProcessTargets::init(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                self.render_extent,
+                self.presenter.image_format,
+            )?);
+        }
+
+        let (color_formats, depth_format, samples, view_mask) =
+            self.pipeline_attachment_formats(render_target, post_process || config.ui_layer);
+        let (pipeline, depth_prepass) = create_graphics_pipelines(
+            &self.gpu.device,
+            &color_formats,
+            writes_velocity,
+            depth_format,
+            samples,
+            view_mask,
             &pipeline_layout,
-            &config.shader.vertex_binding_descriptions(),
-            &config.shader.vertex_attribute_descriptions(),
+            &*config.shader,
             &raster_state,
+            config.depth_prepass,
+            config.shading_rate.is_variable(),
         )?;
 
-        self.set_debug_name(
-            pipeline,
-            debug::clean_shader_name(config.shader.source_file_name()),
-        );
+        let shader_name = debug::clean_shader_name(config.shader.source_file_name());
+        self.set_debug_name(pipeline, shader_name);
+        if let Some(depth_prepass) = depth_prepass {
+            self.set_debug_name(depth_prepass, &format!("{shader_name}_depth_prepass"));
+        }
 
         let vertex_pipeline_config = match &config.vertex_config {
             VertexConfig::VertexAndIndexBuffers(vertices, indices) => {
@@ -1300,18 +3535,18 @@ impl Renderer {
                 }
 
                 let (vertex_buffer, vertex_buffer_memory) = create_vertex_buffer(
-                    &self.allocator,
-                    &self.device,
+                    &self.gpu.allocator,
+                    &self.gpu.device,
                     self.command_pool,
-                    self.graphics_queue,
+                    self.gpu.graphics_queue,
                     vertices,
                 )?;
 
                 let (index_buffer, index_buffer_memory) = create_index_buffer(
-                    &self.allocator,
-                    &self.device,
+                    &self.gpu.allocator,
+                    &self.gpu.device,
                     self.command_pool,
-                    self.graphics_queue,
+                    self.gpu.graphics_queue,
                     indices,
                 )?;
 
@@ -1335,28 +3570,42 @@ impl Renderer {
 
         let layout_bindings = config.shader.layout_bindings();
 
-        let descriptor_pool = create_descriptor_pool(&self.device, &pipeline_layout)?;
+        let descriptor_pool = create_descriptor_pool(&self.gpu.device, &pipeline_layout)?;
 
-        let textures = {
-            let mut textures = vec![];
-            for texture_handle in config.texture_handles {
-                let texture = self.textures.get(texture_handle);
-                textures.push(texture);
-            }
-            textures
-        };
+        let textures: Vec<_> = config
+            .texture_handles
+            .iter()
+            .map(|texture_handle| self.texture_image_infos(texture_handle))
+            .collect();
 
         let uniform_buffers_in_layout_frame_order: Vec<&[RawUniformBuffer; PRE_WAIT_RING_LEN]> =
             config
                 .uniform_buffer_handles
                 .iter()
-                .map(|raw_handle| self.uniform_buffers.get_raw(raw_handle))
+                .map(|raw_handle| self.gpu.uniform_buffers.get_raw(raw_handle))
                 .collect();
 
         let storage_images: Vec<&storage_texture::StorageTexture> = config
             .storage_texture_handles
             .iter()
-            .map(|handle| self.storage_textures.get(handle))
+            .map(|handle| self.gpu.storage_textures.get(handle))
+            .collect();
+
+        let acceleration_structures: Vec<vk::AccelerationStructureKHR> = config
+            .acceleration_structure_handles
+            .iter()
+            .map(|handle| {
+                self.gpu
+                    .acceleration_structures
+                    .get_tlas(handle)
+                    .acceleration_structure
+            })
+            .collect();
+
+        let raw_buffers: Vec<_> = config
+            .raw_buffer_handles
+            .iter()
+            .map(|handle| self.gpu.storage_buffers.raw_buffers_per_frame(handle))
             .collect();
 
         let set_layouts: Vec<_> = pipeline_layout
@@ -1364,16 +3613,25 @@ impl Renderer {
             .iter()
             .map(|t| t.0)
             .collect();
+
+        let scene_color_writes = self.scene_color_writes(&config.texture_handles, &layout_bindings);
+        #[cfg(debug_assertions)]
+        let bound_images =
+            self.bound_images(&config.texture_handles, &config.storage_texture_handles);
+
         let descriptor_sets = create_descriptor_sets(
-            &self.device,
+            &self.gpu.device,
             descriptor_pool,
             &set_layouts,
             &uniform_buffers_in_layout_frame_order,
             &textures,
             &storage_images,
+            &acceleration_structures,
+            &raw_buffers,
             layout_bindings,
         )?;
 
+        let push_constants = vec![0; config.shader.pipeline_layout().push_constants_size()];
         Ok(RendererPipeline {
             layout: pipeline_layout,
             pipeline,
@@ -1382,12 +3640,164 @@ impl Renderer {
             descriptor_sets,
             shader: config.shader,
             raster_state,
+            shading_rate: config.shading_rate,
+            depth_prepass,
+            render_target,
+            post_process,
+            split_screen: config.split_screen,
+            ui_layer: config.ui_layer,
+            velocity: writes_velocity,
+            scene_color_writes,
+            variants: vec![],
+            push_constants,
+            #[cfg(debug_assertions)]
+            bound_images,
+            #[cfg(debug_assertions)]
+            bound_uniform_buffers: raw_handle_indices(&config.uniform_buffer_handles),
+            resources: bound_resources(
+                &config.texture_handles,
+                &config.uniform_buffer_handles,
+                &config.raw_buffer_handles,
+            ),
+            orphaned_by: None,
         })
     }
 
+    /// Where the given textures (in layout order) bind a scene color, TAA history, or velocity alias
+    fn scene_color_writes(
+        &self,
+        texture_handles: &[&TextureHandle],
+        layout_bindings: &[Vec<LayoutDescription>],
+    ) -> Vec<SceneColorWrite> {
+        let texture_descriptions =
+            layout_bindings
+                .iter()
+                .enumerate()
+                .flat_map(|(set, descriptions)| {
+                    descriptions
+                        .iter()
+                        .filter_map(move |description| match description {
+                            LayoutDescription::Texture(texture) => Some((set, texture)),
+                            _ => None,
+                        })
+                });
+
+        texture_descriptions
+            .zip(texture_handles)
+            .filter(|(_, handle)| {
+                self.scene_color_texture_indices.contains(&handle.index())
+                    || self.taa_history_texture_indices.contains(&handle.index())
+                    || self.velocity_texture_indices.contains(&handle.index())
+            })
+            .map(|((set, texture), handle)| SceneColorWrite {
+                set,
+                binding: texture.binding,
+                descriptor_type: if texture.sampled_image_only {
+                    vk::DescriptorType::SAMPLED_IMAGE
+                } else {
+                    vk::DescriptorType::COMBINED_IMAGE_SAMPLER
+                },
+                texture_index: handle.index(),
+            })
+            .collect()
+    }
+
+    /// Bake a variant of an existing pipeline with different raster state,
+    /// e.g. an additive-blend copy of a sprite pipeline. The variant reuses the
+    /// original's shader, layout, descriptor sets, and vertex data, so its
+    /// resources are the original's: later writes through them affect both.
+    pub fn clone_pipeline_with<D: DrawCall<Index = GraphicsPipelineIndex>>(
+        &mut self,
+        handle: &PipelineHandle<D>,
+        configure: impl FnOnce(&mut RasterState),
+    ) -> anyhow::Result<PipelineHandle<D>> {
+        let original = self.gpu.pipelines.get(handle);
+
+        let mut raster_state = original.raster_state;
+        configure(&mut raster_state);
+        if original.render_target.is_some() || original.post_process || original.ui_layer {
+            // feedback, post-process, and ui passes have no depth attachment
+            raster_state.depth_test = DepthCompare::Disabled;
+            raster_state.depth_write = false;
+        }
+
+        let (color_formats, depth_format, samples, view_mask) = self.pipeline_attachment_formats(
+            original.render_target,
+            original.post_process || original.ui_layer,
+        );
+        let (pipeline, depth_prepass) = create_graphics_pipelines(
+            &self.gpu.device,
+            &color_formats,
+            original.velocity,
+            depth_format,
+            samples,
+            view_mask,
+            &original.layout,
+            &*original.shader,
+            &raster_state,
+            original.depth_prepass.is_some(),
+            original.shading_rate.is_variable(),
+        )?;
+
+        let shader_name = debug::clean_shader_name(original.shader.source_file_name());
+        self.set_debug_name(pipeline, shader_name);
+        if let Some(depth_prepass) = depth_prepass {
+            self.set_debug_name(depth_prepass, &format!("{shader_name}_depth_prepass"));
+        }
+
+        let variant = PipelineVariant {
+            pipeline,
+            depth_prepass,
+            raster_state,
+        };
+
+        Ok(self.gpu.pipelines.add_variant(handle, variant))
+    }
+
+    /// The color formats, depth format, sample count, and multiview mask of
+    /// the pass a pipeline draws in: the main pass, a feedback texture's pass,
+    /// or one of the single-sampled passes after it (post-process and the ui layer).
+    /// Only the main pass has more than one color target, for velocity.
+    fn pipeline_attachment_formats(
+        &self,
+        render_target: Option<usize>,
+        after_main_pass: bool,
+    ) -> (
+        Vec<vk::Format>,
+        Option<vk::Format>,
+        vk::SampleCountFlags,
+        u32,
+    ) {
+        match render_target {
+            Some(index) => {
+                let feedback = self.feedback_textures.get_by_index(index);
+                (vec![feedback.format], None, vk::SampleCountFlags::TYPE_1, 0)
+            }
+            None if after_main_pass => (
+                vec![self.presenter.image_format],
+                None,
+                vk::SampleCountFlags::TYPE_1,
+                0,
+            ),
+            None => {
+                let mut color_formats = vec![self.presenter.image_format];
+                if self.velocity.is_some() {
+                    color_formats.push(velocity::VELOCITY_FORMAT);
+                }
+                (
+                    color_formats,
+                    Some(self.depth_format),
+                    self.msaa_samples,
+                    self.view_mode.view_mask(),
+                )
+            }
+        }
+    }
+
     fn record_compute_commands(
         &self,
         command_buffer: vk::CommandBuffer,
+        queue: BreadcrumbQueue,
         pending_compute: &[PendingComputeCommand],
     ) {
         for cmd in pending_compute {
@@ -1396,7 +3806,7 @@ impl Renderer {
                     pipeline_index,
                     group_count,
                 } => {
-                    let compute_pipeline = self.compute_pipelines.get_by_index(*pipeline_index);
+                    let compute_pipeline = self.gpu.compute_pipelines.get_by_index(*pipeline_index);
 
                     let label_name = CString::new(debug::clean_shader_name(
                         compute_pipeline.shader.source_file_name(),
@@ -1406,26 +3816,41 @@ impl Renderer {
                         .label_name(&label_name)
                         .color([0.4, 0.8, 0.4, 1.0]);
                     unsafe {
-                        self.debug_utils_device
+                        self.gpu
+                            .debug_utils_device
                             .cmd_begin_debug_utils_label(command_buffer, &label);
                     }
+                    self.mark_breadcrumb(
+                        command_buffer,
+                        queue,
+                        Breadcrumb::Dispatch(*pipeline_index),
+                    );
+                    #[cfg(debug_assertions)]
+                    {
+                        let shader_name =
+                            debug::clean_shader_name(compute_pipeline.shader.source_file_name());
+                        self.check_bound_layouts(&compute_pipeline.bound_images, shader_name);
+                        self.layout_tracker
+                            .borrow_mut()
+                            .storage_write(shader_name, vk::PipelineStageFlags2::COMPUTE_SHADER);
+                    }
 
                     let descriptor_sets_per_frame =
                         compute_pipeline.layout.descriptor_set_layouts.len();
                     let compute_descriptor_sets = compute_pipeline
                         .descriptor_sets
                         .chunks(descriptor_sets_per_frame)
-                        .nth(self.ring_slot)
+                        .nth(self.slots.ring)
                         .unwrap();
 
                     unsafe {
-                        self.device.cmd_bind_pipeline(
+                        self.gpu.device.cmd_bind_pipeline(
                             command_buffer,
                             vk::PipelineBindPoint::COMPUTE,
                             compute_pipeline.pipeline,
                         );
 
-                        self.device.cmd_bind_descriptor_sets(
+                        self.gpu.device.cmd_bind_descriptor_sets(
                             command_buffer,
                             vk::PipelineBindPoint::COMPUTE,
                             compute_pipeline.layout.pipeline_layout,
@@ -1434,7 +3859,7 @@ impl Renderer {
                             &[],
                         );
 
-                        self.device.cmd_dispatch(
+                        self.gpu.device.cmd_dispatch(
                             command_buffer,
                             group_count[0],
                             group_count[1],
@@ -1443,7 +3868,8 @@ impl Renderer {
                     }
 
                     unsafe {
-                        self.debug_utils_device
+                        self.gpu
+                            .debug_utils_device
                             .cmd_end_debug_utils_label(command_buffer);
                     }
                 }
@@ -1459,14 +3885,7 @@ impl Renderer {
                         .src_access_mask(*src_access)
                         .dst_stage_mask(*dst_stage)
                         .dst_access_mask(*dst_access);
-                    let memory_barriers = [memory_barrier];
-                    let dependency_info =
-                        vk::DependencyInfo::default().memory_barriers(&memory_barriers);
-
-                    unsafe {
-                        self.device
-                            .cmd_pipeline_barrier2(command_buffer, &dependency_info);
-                    }
+                    self.record_memory_barrier(command_buffer, memory_barrier);
                 }
             }
         }
@@ -1477,17 +3896,20 @@ impl Renderer {
         &self,
         pending_compute: &[PendingComputeCommand],
     ) -> Result<(), anyhow::Error> {
-        let command_buffer = self.compute_command_buffers[self.flight_slot];
+        let command_buffer = self.compute_command_buffers[self.slots.flight];
 
         let begin_info = vk::CommandBufferBeginInfo::default();
         unsafe {
-            self.device
+            self.gpu
+                .device
                 .begin_command_buffer(command_buffer, &begin_info)?;
         }
+        #[cfg(debug_assertions)]
+        self.layout_tracker.borrow_mut().begin_command_buffer();
 
-        self.record_compute_commands(command_buffer, pending_compute);
+        self.record_compute_commands(command_buffer, BreadcrumbQueue::Compute, pending_compute);
 
-        unsafe { self.device.end_command_buffer(command_buffer)? };
+        unsafe { self.gpu.device.end_command_buffer(command_buffer)? };
         Ok(())
     }
 
@@ -1499,16 +3921,30 @@ impl Renderer {
         pending_compute: &[PendingComputeCommand],
         compute_placement: ComputePlacement,
     ) -> Result<(), anyhow::Error> {
-        let command_buffer = self.command_buffers[self.flight_slot];
+        let command_buffer = self.command_buffers[self.slots.flight];
 
         let begin_info = vk::CommandBufferBeginInfo::default();
         unsafe {
-            self.device
+            self.gpu
+                .device
                 .begin_command_buffer(command_buffer, &begin_info)?;
         }
+        #[cfg(debug_assertions)]
+        self.layout_tracker.borrow_mut().begin_command_buffer();
+
+        if !self.pending_external_acquires.is_empty() {
+            self.record_external_acquires(command_buffer);
+        }
+        if !self.pending_texture_updates.is_empty() {
+            self.record_texture_updates(command_buffer);
+        }
 
         if compute_placement == ComputePlacement::BeforeGraphics {
-            self.record_compute_commands(command_buffer, pending_compute);
+            self.record_compute_commands(
+                command_buffer,
+                BreadcrumbQueue::Graphics,
+                pending_compute,
+            );
         }
 
         // PICKING RENDER PASS (before main pass)
@@ -1518,11 +3954,20 @@ impl Renderer {
                 .label_name(label_name)
                 .color([0.8, 0.4, 0.4, 1.0]);
             unsafe {
-                self.debug_utils_device
+                self.gpu
+                    .debug_utils_device
                     .cmd_begin_debug_utils_label(command_buffer, &label);
             }
-            let picking_pipeline = self.pipelines.get_picking(&picking_config.picking_handle);
-            let picking_image = picking.images[self.flight_slot];
+            self.mark_breadcrumb(
+                command_buffer,
+                BreadcrumbQueue::Graphics,
+                Breadcrumb::Pass(BreadcrumbPass::Picking),
+            );
+            let picking_pipeline = self
+                .gpu
+                .pipelines
+                .get_picking(&picking_config.picking_handle);
+            let picking_image = picking.images[self.slots.flight];
             let picking_render_area = vk::Rect2D::default()
                 .offset(vk::Offset2D::default())
                 .extent(self.render_extent);
@@ -1540,7 +3985,7 @@ impl Renderer {
                 .src_access_mask(vk::AccessFlags2::NONE)
                 .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
                 .dst_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE);
-            cmd_barrier2(&self.device, command_buffer, &[barrier_to_attachment]);
+            self.record_barriers(command_buffer, &[barrier_to_attachment]);
 
             let picking_clear = vk::ClearValue {
                 color: vk::ClearColorValue {
@@ -1548,7 +3993,7 @@ impl Renderer {
                 },
             };
             let picking_color_attachment = vk::RenderingAttachmentInfo::default()
-                .image_view(picking.image_views[self.flight_slot])
+                .image_view(picking.image_views[self.slots.flight])
                 .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
                 .load_op(vk::AttachmentLoadOp::CLEAR)
                 .store_op(vk::AttachmentStoreOp::STORE)
@@ -1560,10 +4005,11 @@ impl Renderer {
                 .color_attachments(&picking_color_attachments);
 
             unsafe {
-                self.device
+                self.gpu
+                    .device
                     .cmd_begin_rendering(command_buffer, &picking_rendering_info);
 
-                self.device.cmd_bind_pipeline(
+                self.gpu.device.cmd_bind_pipeline(
                     command_buffer,
                     vk::PipelineBindPoint::GRAPHICS,
                     picking_pipeline.pipeline,
@@ -1578,18 +4024,26 @@ impl Renderer {
                 .min_depth(0.0)
                 .max_depth(1.0);
             let viewports = [viewport];
-            unsafe { self.device.cmd_set_viewport(command_buffer, 0, &viewports) };
+            unsafe {
+                self.gpu
+                    .device
+                    .cmd_set_viewport(command_buffer, 0, &viewports)
+            };
 
             let scissor = vk::Rect2D::default()
                 .offset(vk::Offset2D::default())
                 .extent(self.render_extent);
             let scissors = [scissor];
-            unsafe { self.device.cmd_set_scissor(command_buffer, 0, &scissors) };
+            unsafe {
+                self.gpu
+                    .device
+                    .cmd_set_scissor(command_buffer, 0, &scissors)
+            };
 
             let picking_descriptor_sets =
                 self.picking_descriptor_sets_for_frame(&picking_config.picking_handle);
             unsafe {
-                self.device.cmd_bind_descriptor_sets(
+                self.gpu.device.cmd_bind_descriptor_sets(
                     command_buffer,
                     vk::PipelineBindPoint::GRAPHICS,
                     picking_pipeline.layout.pipeline_layout,
@@ -1598,8 +4052,8 @@ impl Renderer {
                     &[],
                 );
 
-                self.device.cmd_draw(command_buffer, 3, 1, 0, 0);
-                self.device.cmd_end_rendering(command_buffer);
+                self.gpu.device.cmd_draw(command_buffer, 3, 1, 0, 0);
+                self.gpu.device.cmd_end_rendering(command_buffer);
             }
 
             // transition the picking image for the readback copy
@@ -1614,62 +4068,60 @@ impl Renderer {
                 .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
                 .dst_stage_mask(vk::PipelineStageFlags2::COPY)
                 .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
-            cmd_barrier2(&self.device, command_buffer, &[barrier_to_copy]);
-
-            // Copy 1 pixel from picking image to readback buffer
-            let mouse_x =
-                picking_config.mouse_pixel[0].min(self.render_extent.width.saturating_sub(1));
-            let mouse_y =
-                picking_config.mouse_pixel[1].min(self.render_extent.height.saturating_sub(1));
-
-            let region = vk::BufferImageCopy::default()
-                .buffer_offset(0)
-                .buffer_row_length(0)
-                .buffer_image_height(0)
-                .image_subresource(
-                    vk::ImageSubresourceLayers::default()
-                        .aspect_mask(vk::ImageAspectFlags::COLOR)
-                        .mip_level(0)
-                        .base_array_layer(0)
-                        .layer_count(1),
-                )
-                .image_offset(vk::Offset3D {
-                    x: mouse_x as i32,
-                    y: mouse_y as i32,
-                    z: 0,
-                })
-                .image_extent(vk::Extent3D {
-                    width: 1,
-                    height: 1,
-                    depth: 1,
-                });
+            self.record_barriers(command_buffer, &[barrier_to_copy]);
 
-            unsafe {
-                self.device.cmd_copy_image_to_buffer(
-                    command_buffer,
-                    picking.images[self.flight_slot],
-                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
-                    picking.readback_buffers[self.flight_slot],
-                    &[region],
-                );
-            }
+            // Read back the pixels around the mouse, resolved to one id once they arrive
+            let window = PickingWindow::around(
+                picking_config.mouse_pixel,
+                self.picking_policy.radius(),
+                self.render_extent,
+            );
+            let readback_id = self.readbacks.next_id();
+            self.readbacks.record(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                command_buffer,
+                self.slots.flight,
+                readback_id,
+                ReadbackSource::Image {
+                    image: picking_image,
+                    layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    subresource: readback::COLOR_LAYER,
+                    offset: window.offset,
+                    extent: window.extent,
+                    format: picking::PICKING_FORMAT,
+                    texel_size: 4,
+                },
+                Delivery::Picking(window),
+            )?;
 
             unsafe {
-                self.debug_utils_device
+                self.gpu
+                    .debug_utils_device
                     .cmd_end_debug_utils_label(command_buffer);
             }
         }
 
+        // FEEDBACK TEXTURE PASSES (before main pass, which may sample them)
+        self.record_feedback_passes(command_buffer, pending_draws);
+        self.record_feedback_readbacks(command_buffer)?;
+
         // MAIN RENDER PASS
         {
             let label = vk::DebugUtilsLabelEXT::default()
                 .label_name(c"Main")
                 .color([1.0, 1.0, 1.0, 1.0]);
             unsafe {
-                self.debug_utils_device
+                self.gpu
+                    .debug_utils_device
                     .cmd_begin_debug_utils_label(command_buffer, &label);
             }
         }
+        self.mark_breadcrumb(
+            command_buffer,
+            BreadcrumbQueue::Graphics,
+            Breadcrumb::Pass(BreadcrumbPass::Main),
+        );
 
         // Main rendering uses render_extent (scaled resolution)
         let render_area = vk::Rect2D::default()
@@ -1716,24 +4168,46 @@ impl Renderer {
                     | vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
             );
 
-        // the previous use of this frame's resolve image was the upscale blit read
+        // the previous use of this slot's resolve image was the upscale blit read
+        // or a post-process pass sampling it
         let resolve_barrier = vk::ImageMemoryBarrier2::default()
             .old_layout(vk::ImageLayout::UNDEFINED)
             .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
             .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
             .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-            .image(self.resolve_images[self.flight_slot])
+            .image(self.resolve_images[self.slots.ring])
             .subresource_range(COLOR_SUBRESOURCE_RANGE)
-            .src_stage_mask(vk::PipelineStageFlags2::BLIT)
+            .src_stage_mask(
+                vk::PipelineStageFlags2::BLIT | vk::PipelineStageFlags2::FRAGMENT_SHADER,
+            )
             .src_access_mask(vk::AccessFlags2::NONE)
             .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
             .dst_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE);
 
-        cmd_barrier2(
-            &self.device,
-            command_buffer,
-            &[color_barrier, depth_barrier, resolve_barrier],
-        );
+        // the velocity buffer was last sampled by the previous frame's post-process pass
+        let velocity_barriers = self.velocity.iter().flat_map(|velocity| {
+            velocity.attachment_images().map(|image| {
+                vk::ImageMemoryBarrier2::default()
+                    .old_layout(vk::ImageLayout::UNDEFINED)
+                    .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .image(image)
+                    .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                    .src_stage_mask(
+                        vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT
+                            | vk::PipelineStageFlags2::FRAGMENT_SHADER,
+                    )
+                    .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                    .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                    .dst_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+            })
+        });
+        let barriers: Vec<_> = [color_barrier, depth_barrier, resolve_barrier]
+            .into_iter()
+            .chain(velocity_barriers)
+            .collect();
+        self.record_barriers(command_buffer, &barriers);
 
         let clear_color = vk::ClearValue {
             color: vk::ClearColorValue {
@@ -1747,33 +4221,47 @@ impl Renderer {
             },
         };
 
-        // MSAA color renders at msaa_samples and resolves into this frame's resolve
-        // image; only the resolved output is consumed (by the upscale blit)
-        let color_attachment = vk::RenderingAttachmentInfo::default()
-            .image_view(self.color_image_view)
-            .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-            .resolve_mode(vk::ResolveModeFlags::AVERAGE)
-            .resolve_image_view(self.resolve_image_views[self.flight_slot])
-            .resolve_image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-            .load_op(vk::AttachmentLoadOp::CLEAR)
-            .store_op(vk::AttachmentStoreOp::DONT_CARE)
-            .clear_value(clear_color);
-        let color_attachments = [color_attachment];
+        // MSAA color renders at msaa_samples and resolves into this slot's resolve
+        // image; only the resolved output is consumed (by the upscale blit, or
+        // by post-processing). Without MSAA there is nothing to resolve, so the
+        // main pass draws into the resolve image directly.
+        let resolve_image_view = self.resolve_image_views[self.slots.ring];
+        let color_attachment = if self.msaa_samples == vk::SampleCountFlags::TYPE_1 {
+            vk::RenderingAttachmentInfo::default()
+                .image_view(resolve_image_view)
+                .store_op(vk::AttachmentStoreOp::STORE)
+        } else {
+            vk::RenderingAttachmentInfo::default()
+                .image_view(self.color_image_view)
+                .resolve_mode(vk::ResolveModeFlags::AVERAGE)
+                .resolve_image_view(resolve_image_view)
+                .resolve_image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .store_op(vk::AttachmentStoreOp::DONT_CARE)
+        }
+        .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+        .load_op(vk::AttachmentLoadOp::CLEAR)
+        .clear_value(clear_color);
+        let color_attachments: Vec<_> = std::iter::once(color_attachment)
+            .chain(self.velocity.as_ref().map(VelocityTargets::attachment))
+            .collect();
         let depth_attachment = vk::RenderingAttachmentInfo::default()
             .image_view(self.depth_image_view)
             .image_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
             .load_op(vk::AttachmentLoadOp::CLEAR)
             .store_op(vk::AttachmentStoreOp::DONT_CARE)
             .clear_value(clear_depth_stencil);
+        // with multiview, layer_count is ignored and each view renders to its own layer
         let rendering_info = vk::RenderingInfo::default()
             .render_area(render_area)
             .layer_count(1)
+            .view_mask(self.view_mode.view_mask())
             .color_attachments(&color_attachments)
             .depth_attachment(&depth_attachment);
 
         // BEGIN RENDERING
         unsafe {
-            self.device
+            self.gpu
+                .device
                 .cmd_begin_rendering(command_buffer, &rendering_info);
         }
 
@@ -1786,324 +4274,2153 @@ impl Renderer {
             .min_depth(0.0)
             .max_depth(1.0);
         let viewports = [viewport];
-        unsafe { self.device.cmd_set_viewport(command_buffer, 0, &viewports) };
+        unsafe {
+            self.gpu
+                .device
+                .cmd_set_viewport(command_buffer, 0, &viewports)
+        };
 
         let scissor = vk::Rect2D::default()
             .offset(vk::Offset2D::default())
             .extent(self.render_extent);
         let scissors = [scissor];
-        unsafe { self.device.cmd_set_scissor(command_buffer, 0, &scissors) };
-
-        // consecutive draws often share vertex/index buffers (shared meshes)
-        let mut last_bound_buffers: Option<(vk::Buffer, vk::Buffer)> = None;
+        unsafe {
+            self.gpu
+                .device
+                .cmd_set_scissor(command_buffer, 0, &scissors)
+        };
 
-        for pending_draw in pending_draws {
-            let PendingDrawCommand::Draw {
-                pipeline_index,
-                draw_call,
-            } = pending_draw;
-            let pipeline = self.pipelines.get_by_index(*pipeline_index);
+        let is_main_pass_draw = |pending_draw: &&PendingDrawCommand| {
+            let PendingDrawCommand::Draw { pipeline_index, .. } = pending_draw;
+            let pipeline = self.gpu.pipelines.get_by_index(*pipeline_index);
+            pipeline.render_target.is_none() && !pipeline.post_process && !pipeline.ui_layer
+        };
 
-            let shader_name = debug::clean_shader_name(pipeline.shader.source_file_name());
-            let label_name = CString::new(shader_name).unwrap();
+        // depth for every pipeline with a prepass, before any color draws
+        let has_depth_prepass = pending_draws.iter().any(|pending_draw| {
+            let PendingDrawCommand::Draw { pipeline_index, .. } = pending_draw;
+            self.gpu
+                .pipelines
+                .get_by_index(*pipeline_index)
+                .depth_prepass
+                .is_some()
+        });
+        if has_depth_prepass {
             let label = vk::DebugUtilsLabelEXT::default()
-                .label_name(&label_name)
-                .color([1.0, 1.0, 1.0, 1.0]);
+                .label_name(c"Depth Prepass")
+                .color([0.6, 0.6, 0.6, 1.0]);
             unsafe {
-                self.debug_utils_device
+                self.gpu
+                    .debug_utils_device
                     .cmd_begin_debug_utils_label(command_buffer, &label);
             }
 
+            self.record_draws(
+                command_buffer,
+                pending_draws.iter().filter(is_main_pass_draw),
+                DrawPass::DepthPrepass,
+                scissor,
+            );
+
             unsafe {
-                self.device.cmd_bind_pipeline(
-                    command_buffer,
-                    vk::PipelineBindPoint::GRAPHICS,
-                    pipeline.pipeline,
-                );
+                self.gpu
+                    .debug_utils_device
+                    .cmd_end_debug_utils_label(command_buffer);
             }
+        }
 
-            let draw_buffers = match &pipeline.vertex_pipeline_config {
-                VertexPipelineConfig::VertexAndIndexBuffers(vi_bufs) => {
-                    Some((vi_bufs.vertex_buffer, vi_bufs.index_buffer))
-                }
-                VertexPipelineConfig::SharedMesh(mesh_index) => {
-                    let mesh = &self.meshes[mesh_index.raw()];
-                    Some((mesh.vertex_buffer, mesh.index_buffer))
-                }
-                VertexPipelineConfig::VertexCount => None,
-            };
-            if let Some((vertex_buffer, index_buffer)) = draw_buffers
-                && last_bound_buffers != Some((vertex_buffer, index_buffer))
-            {
-                let buffers = [vertex_buffer];
-                let offsets = [0];
-                unsafe {
-                    self.device
-                        .cmd_bind_vertex_buffers(command_buffer, 0, &buffers, &offsets);
+        let main_pass_draws: Vec<_> = pending_draws.iter().filter(is_main_pass_draw).collect();
+        self.record_main_pass_color_draws(command_buffer, &main_pass_draws);
 
-                    self.device.cmd_bind_index_buffer(
-                        command_buffer,
-                        index_buffer,
-                        0,
-                        vk::IndexType::UINT32,
-                    );
-                }
-                last_bound_buffers = Some((vertex_buffer, index_buffer));
+        // END MAIN RENDERING
+        unsafe { self.gpu.device.cmd_end_rendering(command_buffer) };
+
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_end_debug_utils_label(command_buffer);
+        }
+
+        // VELOCITY (sampled by the post-process pass)
+        if let Some(velocity) = &self.velocity {
+            let velocity_to_sampled = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(velocity.image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+                .dst_access_mask(vk::AccessFlags2::SHADER_SAMPLED_READ);
+            self.record_barriers(command_buffer, &[velocity_to_sampled]);
+        }
+
+        // PIXEL DEBUGGER REPLAY (after everything the replayed draws could sample)
+        self.record_pixel_debug_pass(command_buffer, pending_draws)?;
+
+        // POST-PROCESS PASS (samples the resolved scene color)
+        self.record_taa_history_clear(command_buffer);
+        let blit_source = match self.record_post_process_pass(command_buffer, pending_draws) {
+            Some(post_process_image) => {
+                // TAA HISTORY (the next frame's resolve samples this one's)
+                self.record_taa_history_copy(command_buffer, post_process_image);
+                post_process_image
             }
+            None => {
+                // transition this slot's resolve image for the upscale blit read
+                // (replaces the old render pass's TRANSFER_SRC final layout and exit dependency)
+                let resolve_to_blit_src = vk::ImageMemoryBarrier2::default()
+                    .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                    .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .image(self.resolve_images[self.slots.ring])
+                    .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                    .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                    .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                    .dst_stage_mask(vk::PipelineStageFlags2::BLIT)
+                    .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
+                self.record_barriers(command_buffer, &[resolve_to_blit_src]);
+
+                self.resolve_images[self.slots.ring]
+            }
+        };
 
-            let descriptor_sets = self.descriptor_sets_for_frame(*pipeline_index);
+        // SCREENSHOTS (the blit source, at render resolution)
+        self.record_screenshot_readbacks(command_buffer, blit_source)?;
+
+        // BLIT FROM RESOLVE IMAGE TO SWAPCHAIN (upscale step)
+        {
+            let label = vk::DebugUtilsLabelEXT::default()
+                .label_name(c"Blit")
+                .color([0.4, 0.4, 0.8, 1.0]);
             unsafe {
-                self.device.cmd_bind_descriptor_sets(
-                    command_buffer,
-                    vk::PipelineBindPoint::GRAPHICS,
-                    self.pipelines
-                        .get_by_index(*pipeline_index)
-                        .layout
-                        .pipeline_layout,
-                    0,
-                    descriptor_sets,
-                    &[],
-                );
+                self.gpu
+                    .debug_utils_device
+                    .cmd_begin_debug_utils_label(command_buffer, &label);
+            }
+        }
+        self.mark_breadcrumb(
+            command_buffer,
+            BreadcrumbQueue::Graphics,
+            Breadcrumb::Pass(BreadcrumbPass::Blit),
+        );
+        let swapchain_image = self.presenter.swapchain_images[image_index as usize];
+        let rcas_upscale = match self.upscale_filter() {
+            UpscaleFilter::Sharpened => self.rcas_upscale.as_ref(),
+            _ => None,
+        };
+        // the swapchain image's layout after the composite, and the stage that wrote it
+        let (composite_layout, composite_stage, composite_access) = match rcas_upscale {
+            Some(rcas_upscale) => {
+                self.record_rcas_upscale(command_buffer, rcas_upscale, blit_source, image_index);
+                (
+                    vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+                    vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
+                )
+            }
+            None => {
+                self.record_upscale_blit(command_buffer, blit_source, swapchain_image);
+                (
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    vk::PipelineStageFlags2::BLIT,
+                    vk::AccessFlags2::TRANSFER_WRITE,
+                )
+            }
+        };
+        {
+            // With ui layer draws or egui active the swapchain image is rendered to
+            // again (each loads and draws on top); otherwise it goes straight to present.
+            let drawn_over = self.egui_enabled() || self.has_ui_layer_draws(pending_draws);
+            let barrier_to_next = if drawn_over {
+                vk::ImageMemoryBarrier2::default()
+                    .old_layout(composite_layout)
+                    .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                    .src_stage_mask(composite_stage)
+                    .src_access_mask(composite_access)
+                    .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                    .dst_access_mask(
+                        vk::AccessFlags2::COLOR_ATTACHMENT_READ
+                            | vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
+                    )
+            } else {
+                vk::ImageMemoryBarrier2::default()
+                    .old_layout(composite_layout)
+                    .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                    .src_stage_mask(composite_stage)
+                    .src_access_mask(composite_access)
+                    // presentation waits on the render_finished semaphore, not this barrier
+                    .dst_stage_mask(vk::PipelineStageFlags2::NONE)
+                    .dst_access_mask(vk::AccessFlags2::NONE)
             }
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(swapchain_image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE);
 
-            match draw_call {
-                DrawCallConfig::VertexCount(vertex_count) => unsafe {
-                    self.device.cmd_draw(command_buffer, *vertex_count, 1, 0, 0);
-                },
+            self.record_barriers(command_buffer, &[barrier_to_next]);
+        }
 
-                DrawCallConfig::IndexCount(index_count) => unsafe {
-                    self.device
-                        .cmd_draw_indexed(command_buffer, *index_count, 1, 0, 0, 0);
-                },
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_end_debug_utils_label(command_buffer);
+        }
 
-                DrawCallConfig::IndexRange {
-                    first_index,
-                    index_count,
-                } => unsafe {
-                    self.device.cmd_draw_indexed(
-                        command_buffer,
-                        *index_count,
-                        1,
-                        *first_index,
-                        0,
-                        0,
-                    );
-                },
+        // UI LAYER (window resolution, over the upscaled frame)
+        self.record_ui_layer_pass(command_buffer, pending_draws, image_index);
+
+        // EGUI RENDERING (separate 1-sample rendering for egui overlay)
+        #[cfg(feature = "editor")]
+        if let Some(egui) = &mut self.egui {
+            let label = vk::DebugUtilsLabelEXT::default()
+                .label_name(c"Egui")
+                .color([0.8, 0.8, 0.4, 1.0]);
+            unsafe {
+                self.gpu
+                    .debug_utils_device
+                    .cmd_begin_debug_utils_label(command_buffer, &label);
+            }
+
+            let render_area = vk::Rect2D::default()
+                .offset(vk::Offset2D::default())
+                .extent(self.presenter.image_extent);
+            // draws over the blitted frame on the swapchain image
+            let egui_color_attachment = vk::RenderingAttachmentInfo::default()
+                .image_view(self.presenter.swapchain_image_views[image_index as usize])
+                .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .load_op(vk::AttachmentLoadOp::LOAD)
+                .store_op(vk::AttachmentStoreOp::STORE);
+            let egui_color_attachments = [egui_color_attachment];
+            let egui_rendering_info = vk::RenderingInfo::default()
+                .render_area(render_area)
+                .layer_count(1)
+                .color_attachments(&egui_color_attachments);
+
+            unsafe {
+                self.gpu
+                    .device
+                    .cmd_begin_rendering(command_buffer, &egui_rendering_info);
+            }
+
+            // Draw egui overlay (begin_frame is idempotent, safe to call if already begun)
+            let screen_size = [self.width, self.height];
+            egui.begin_frame(screen_size);
+            let wants_keyboard_input = egui.end_frame_and_draw(
+                self.gpu.graphics_queue,
+                self.command_pool,
+                command_buffer,
+                self.presenter.image_extent,
+                self.slots.flight,
+            );
+
+            // Toggle SDL text input based on whether egui has a text field focused
+            if wants_keyboard_input && !self.text_input_active {
+                unsafe { sdl3::sys::keyboard::SDL_StartTextInput(self.presenter.window.raw()) };
+                self.text_input_active = true;
+            } else if !wants_keyboard_input && self.text_input_active {
+                unsafe { sdl3::sys::keyboard::SDL_StopTextInput(self.presenter.window.raw()) };
+                self.text_input_active = false;
             }
 
+            unsafe { self.gpu.device.cmd_end_rendering(command_buffer) };
+
+            // transition the swapchain image for presentation
+            let barrier_to_present = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(swapchain_image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                // presentation waits on the render_finished semaphore, not this barrier
+                .dst_stage_mask(vk::PipelineStageFlags2::NONE)
+                .dst_access_mask(vk::AccessFlags2::NONE);
+            self.record_barriers(command_buffer, &[barrier_to_present]);
+
             unsafe {
-                self.debug_utils_device
+                self.gpu
+                    .debug_utils_device
                     .cmd_end_debug_utils_label(command_buffer);
             }
         }
 
-        // END MAIN RENDERING
-        unsafe { self.device.cmd_end_rendering(command_buffer) };
+        // BUFFER READBACKS (after all of the frame's writes)
+        self.record_buffer_readbacks(command_buffer)?;
+        self.record_inspector_readback(command_buffer)?;
 
-        // transition this frame's resolve image for the upscale blit read
-        // (replaces the old render pass's TRANSFER_SRC final layout and exit dependency)
-        let resolve_to_blit_src = vk::ImageMemoryBarrier2::default()
-            .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+        unsafe { self.gpu.device.end_command_buffer(command_buffer)? };
+
+        Ok(())
+    }
+
+    /// Copies the blit source for this frame's screenshot requests.
+    /// In stereo, that's the left eye's layer.
+    fn record_screenshot_readbacks(
+        &mut self,
+        command_buffer: vk::CommandBuffer,
+        blit_source: vk::Image,
+    ) -> anyhow::Result<()> {
+        let requests = self
+            .readbacks
+            .take_requests(|request| matches!(request, ReadbackRequest::Screenshot));
+        if requests.is_empty() {
+            return Ok(());
+        }
+
+        // the blit source is already in TRANSFER_SRC_OPTIMAL,
+        // but its barrier only made its writes visible to the blit
+        let to_copy = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
             .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
             .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
             .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-            .image(self.resolve_images[self.flight_slot])
+            .image(blit_source)
             .subresource_range(COLOR_SUBRESOURCE_RANGE)
             .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
             .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
-            .dst_stage_mask(vk::PipelineStageFlags2::BLIT)
+            .dst_stage_mask(vk::PipelineStageFlags2::COPY)
             .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
-        cmd_barrier2(&self.device, command_buffer, &[resolve_to_blit_src]);
-
-        unsafe {
-            self.debug_utils_device
-                .cmd_end_debug_utils_label(command_buffer);
+        self.record_barriers(command_buffer, &[to_copy]);
+
+        let format = self.presenter.image_format;
+        let texel_size = texture_inspector::texel_size(format)
+            .ok_or_else(|| anyhow::anyhow!("can't read back a {format:?} swapchain image"))?;
+        for (id, _, delivery) in requests {
+            let source = ReadbackSource::Image {
+                image: blit_source,
+                layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                subresource: readback::COLOR_LAYER,
+                offset: [0, 0],
+                extent: [self.render_extent.width, self.render_extent.height],
+                format,
+                // 8-bit RGBA or BGRA, or under HDR output, 10-bit or half float
+                texel_size,
+            };
+            self.readbacks.record(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                command_buffer,
+                self.slots.flight,
+                id,
+                source,
+                delivery,
+            )?;
         }
 
-        // BLIT FROM RESOLVE IMAGE TO SWAPCHAIN (upscale step)
-        {
-            let label = vk::DebugUtilsLabelEXT::default()
-                .label_name(c"Blit")
-                .color([0.4, 0.4, 0.8, 1.0]);
-            unsafe {
-                self.debug_utils_device
-                    .cmd_begin_debug_utils_label(command_buffer, &label);
-            }
-        }
-        let swapchain_image = self.swapchain_images[image_index as usize];
-        {
-            // Transition swapchain image from UNDEFINED to TRANSFER_DST
-            let barrier_to_transfer = vk::ImageMemoryBarrier2::default()
-                .old_layout(vk::ImageLayout::UNDEFINED)
-                .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        Ok(())
+    }
+
+    /// Copies this frame's feedback texture images for readback requests,
+    /// between their passes and the main pass that samples them
+    fn record_feedback_readbacks(
+        &mut self,
+        command_buffer: vk::CommandBuffer,
+    ) -> anyhow::Result<()> {
+        let requests = self
+            .readbacks
+            .take_requests(|request| matches!(request, ReadbackRequest::FeedbackTexture { .. }));
+
+        for (id, request, delivery) in requests {
+            let ReadbackRequest::FeedbackTexture { index } = request else {
+                unreachable!("only feedback texture requests were taken");
+            };
+            let feedback = self.feedback_textures.get_by_index(index);
+            let image = feedback.images[self.slots.ring];
+            let source = ReadbackSource::Image {
+                image,
+                layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                subresource: readback::COLOR_LAYER,
+                offset: [0, 0],
+                extent: [feedback.extent.width, feedback.extent.height],
+                format: feedback.format,
+                // only requested for 8-bit RGBA or BGRA formats
+                texel_size: 4,
+            };
+
+            let to_copy = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
                 .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
                 .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                .image(swapchain_image)
-                .subresource_range(vk::ImageSubresourceRange {
-                    aspect_mask: vk::ImageAspectFlags::COLOR,
-                    base_mip_level: 0,
-                    level_count: 1,
-                    base_array_layer: 0,
-                    layer_count: 1,
-                })
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
                 .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+                .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
+            self.record_barriers(command_buffer, &[to_copy]);
+
+            self.readbacks.record(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                command_buffer,
+                self.slots.flight,
+                id,
+                source,
+                delivery,
+            )?;
+
+            let to_sampled = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::COPY)
                 .src_access_mask(vk::AccessFlags2::NONE)
-                .dst_stage_mask(vk::PipelineStageFlags2::BLIT)
-                .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE);
+                .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+                .dst_access_mask(vk::AccessFlags2::SHADER_READ);
+            self.record_barriers(command_buffer, &[to_sampled]);
+        }
+
+        Ok(())
+    }
+
+    /// Copies the buffers for this frame's buffer readback requests
+    fn record_buffer_readbacks(&mut self, command_buffer: vk::CommandBuffer) -> anyhow::Result<()> {
+        let requests = self
+            .readbacks
+            .take_requests(|request| matches!(request, ReadbackRequest::GpuOnlyBuffer { .. }));
+        if requests.is_empty() {
+            return Ok(());
+        }
+
+        let memory_barrier = vk::MemoryBarrier2::default()
+            .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+            .src_access_mask(vk::AccessFlags2::SHADER_STORAGE_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
+        self.record_memory_barrier(command_buffer, memory_barrier);
+        #[cfg(debug_assertions)]
+        self.layout_tracker
+            .borrow_mut()
+            .storage_read("buffer readback", COPY_READ_STAGES);
+
+        for (id, request, delivery) in requests {
+            let ReadbackRequest::GpuOnlyBuffer { index, size } = request else {
+                unreachable!("only buffer requests were taken");
+            };
+            let source = ReadbackSource::Buffer {
+                buffer: self.gpu.storage_buffers.raw_buffer(index, self.slots.ring),
+                offset: 0,
+                size,
+            };
+            self.readbacks.record(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                command_buffer,
+                self.slots.flight,
+                id,
+                source,
+                delivery,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Copies the texture inspector's mip level and layer, once the last copy
+    /// has arrived; recorded after everything else in the frame
+    fn record_inspector_readback(
+        &mut self,
+        command_buffer: vk::CommandBuffer,
+    ) -> anyhow::Result<()> {
+        let Some(target) = self.texture_inspector.pending_target() else {
+            return Ok(());
+        };
+
+        let (image, layout, format, extent, copy_aspect, barrier_aspect) = match target.image {
+            InspectedImage::Texture(index) => {
+                let Some(texture) = self.gpu.textures.get_by_index(index) else {
+                    // dropped since it was picked
+                    self.texture_inspector.set_target(None);
+                    return Ok(());
+                };
+                let aspect = vk::ImageAspectFlags::COLOR;
+                let image = self.texture_image_for_frame(index, texture);
+                (
+                    image,
+                    texture.image_layout,
+                    texture.format,
+                    texture.extent,
+                    aspect,
+                    aspect,
+                )
+            }
+            InspectedImage::Depth => {
+                let mut barrier_aspect = vk::ImageAspectFlags::DEPTH;
+                if has_stencil_component(self.depth_format) {
+                    barrier_aspect |= vk::ImageAspectFlags::STENCIL;
+                }
+                (
+                    self.depth_image,
+                    vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                    self.depth_format,
+                    self.render_extent,
+                    vk::ImageAspectFlags::DEPTH,
+                    barrier_aspect,
+                )
+            }
+        };
+        let Some(texel_size) = texture_inspector::texel_size(format) else {
+            self.texture_inspector.set_target(None);
+            return Ok(());
+        };
+
+        // storage textures are copied in place
+        let copy_layout = match layout {
+            vk::ImageLayout::GENERAL => vk::ImageLayout::GENERAL,
+            _ => vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        };
+        let range = vk::ImageSubresourceRange {
+            aspect_mask: barrier_aspect,
+            base_mip_level: target.mip_level,
+            level_count: 1,
+            base_array_layer: target.layer,
+            layer_count: 1,
+        };
+        let (used_stages, used_access) = match target.image {
+            InspectedImage::Texture(_) => (
+                vk::PipelineStageFlags2::ALL_COMMANDS,
+                vk::AccessFlags2::SHADER_READ,
+            ),
+            InspectedImage::Depth => (
+                vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+                    | vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+                vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ
+                    | vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            ),
+        };
+
+        let to_copy = vk::ImageMemoryBarrier2::default()
+            .old_layout(layout)
+            .new_layout(copy_layout)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(range)
+            .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+            .src_access_mask(vk::AccessFlags2::MEMORY_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
+        self.record_barriers(command_buffer, &[to_copy]);
+
+        let source = ReadbackSource::Image {
+            image,
+            layout: copy_layout,
+            subresource: vk::ImageSubresourceLayers {
+                aspect_mask: copy_aspect,
+                mip_level: target.mip_level,
+                base_array_layer: target.layer,
+                layer_count: 1,
+            },
+            offset: [0, 0],
+            extent: texture_inspector::mip_extent(extent, target.mip_level),
+            format,
+            texel_size,
+        };
+        let id = self.readbacks.next_id();
+        self.readbacks.record(
+            &self.gpu.allocator,
+            &self.gpu.device,
+            command_buffer,
+            self.slots.flight,
+            id,
+            source,
+            Delivery::Inspector(target),
+        )?;
+        self.texture_inspector.readback_recorded();
+
+        let to_used = vk::ImageMemoryBarrier2::default()
+            .old_layout(copy_layout)
+            .new_layout(layout)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(range)
+            .src_stage_mask(vk::PipelineStageFlags2::COPY)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(used_stages)
+            .dst_access_mask(used_access);
+        self.record_barriers(command_buffer, &[to_used]);
+
+        Ok(())
+    }
+
+    /// Replays the pixel debugger's pipeline's draws this frame, if any,
+    /// for its one pixel, and copies the results to this flight slot's readback
+    fn record_pixel_debug_pass(
+        &mut self,
+        command_buffer: vk::CommandBuffer,
+        pending_draws: &[PendingDrawCommand],
+    ) -> anyhow::Result<()> {
+        let Some(target) = self.pixel_debug.target else {
+            return Ok(());
+        };
+        let is_target_draw = |pending_draw: &&PendingDrawCommand| {
+            let PendingDrawCommand::Draw {
+                pipeline_index,
+                clip,
+                ..
+            } = pending_draw;
+            *pipeline_index == target.pipeline
+                && clip.is_none_or(|clip| clip.contains(target.pixel))
+        };
+        if !pending_draws.iter().any(|draw| is_target_draw(&draw)) {
+            return Ok(());
+        }
+
+        let source = self.gpu.pipelines.get_by_index(target.pipeline);
+        // render targets, post-processing, and the ui layer have their own extents and inputs
+        if source.render_target.is_some() || source.post_process || source.ui_layer {
+            return Ok(());
+        }
+        let replay_pipeline = match self.pixel_debug.replay_pipeline(source.pipeline) {
+            Some(replay_pipeline) => replay_pipeline,
+            None => {
+                let replay_pipeline = create_graphics_pipeline(
+                    &self.gpu.device,
+                    &pixel_debug::TARGET_FORMATS,
+                    true,
+                    Some(self.pixel_debug.depth_format),
+                    vk::SampleCountFlags::TYPE_1,
+                    0,
+                    &source.layout,
+                    &source.shader.vertex_binding_descriptions(),
+                    &source.shader.vertex_attribute_descriptions(),
+                    &source.raster_state.for_pixel_debug(),
+                    false,
+                )?;
+                let shader_name = debug::clean_shader_name(source.shader.source_file_name());
+                self.set_debug_name(replay_pipeline, &format!("{shader_name}_pixel_debug"));
+                let source_pipeline = source.pipeline;
+                self.pixel_debug.set_replay_pipeline(
+                    self.slots.flight,
+                    source_pipeline,
+                    replay_pipeline,
+                );
+                replay_pipeline
+            }
+        };
+
+        let images =
+            self.pixel_debug
+                .images(&self.gpu.allocator, &self.gpu.device, self.slots.flight)?;
+        let [color_images @ .., depth_image] = images.images;
+        let [color_views @ .., depth_view] = images.views;
+        let readback_buffer = images.readback_buffer;
+        let readback_regions = images.readback_regions();
+
+        let label = vk::DebugUtilsLabelEXT::default()
+            .label_name(c"Pixel Debugger")
+            .color([0.9, 0.3, 0.9, 1.0]);
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_begin_debug_utils_label(command_buffer, &label);
+        }
+
+        // the previous readback copies from these images were 2 frames ago
+        let depth_range = vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::DEPTH,
+            ..COLOR_SUBRESOURCE_RANGE
+        };
+        let color_to_attachment = color_images.iter().map(|&image| {
+            vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::UNDEFINED)
+                .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::COPY)
+                .src_access_mask(vk::AccessFlags2::NONE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .dst_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+        });
+        let depth_to_attachment = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::UNDEFINED)
+            .new_layout(vk::ImageLayout::DEPTH_ATTACHMENT_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(depth_image)
+            .subresource_range(depth_range)
+            .src_stage_mask(vk::PipelineStageFlags2::COPY)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(
+                vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+                    | vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+            )
+            .dst_access_mask(
+                vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ
+                    | vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            );
+        let to_attachment: Vec<_> = color_to_attachment
+            .chain(std::iter::once(depth_to_attachment))
+            .collect();
+        self.record_barriers(command_buffer, &to_attachment);
+
+        let color_attachments: Vec<_> = color_views
+            .iter()
+            .map(|&view| {
+                vk::RenderingAttachmentInfo::default()
+                    .image_view(view)
+                    .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                    .load_op(vk::AttachmentLoadOp::CLEAR)
+                    .store_op(vk::AttachmentStoreOp::STORE)
+                    .clear_value(vk::ClearValue::default())
+            })
+            .collect();
+        let depth_attachment = vk::RenderingAttachmentInfo::default()
+            .image_view(depth_view)
+            .image_layout(vk::ImageLayout::DEPTH_ATTACHMENT_OPTIMAL)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .clear_value(vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: 1.0,
+                    stencil: 0,
+                },
+            });
+        let pixel_area = vk::Rect2D::default().extent(vk::Extent2D {
+            width: 1,
+            height: 1,
+        });
+        let rendering_info = vk::RenderingInfo::default()
+            .render_area(pixel_area)
+            .layer_count(1)
+            .color_attachments(&color_attachments)
+            .depth_attachment(&depth_attachment);
+
+        // the main pass's viewport, shifted so the target pixel lands on the 1x1 targets
+        let [x, y] = target.pixel.map(|coord| coord as f32);
+        let viewport = vk::Viewport::default()
+            .x(-x)
+            .y(-y)
+            .width(self.render_extent.width as f32)
+            .height(self.render_extent.height as f32)
+            .min_depth(0.0)
+            .max_depth(1.0);
+        unsafe {
+            self.gpu
+                .device
+                .cmd_begin_rendering(command_buffer, &rendering_info);
+            self.gpu
+                .device
+                .cmd_set_viewport(command_buffer, 0, &[viewport]);
+            self.gpu
+                .device
+                .cmd_set_scissor(command_buffer, 0, &[pixel_area]);
+        }
+
+        self.record_draws(
+            command_buffer,
+            pending_draws.iter().filter(is_target_draw),
+            DrawPass::PixelDebug(replay_pipeline),
+            pixel_area,
+        );
+
+        unsafe { self.gpu.device.cmd_end_rendering(command_buffer) };
+
+        let color_to_copy = color_images.iter().map(|&image| {
+            vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+                .dst_access_mask(vk::AccessFlags2::TRANSFER_READ)
+        });
+        let depth_to_copy = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::DEPTH_ATTACHMENT_OPTIMAL)
+            .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(depth_image)
+            .subresource_range(depth_range)
+            .src_stage_mask(vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS)
+            .src_access_mask(vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
+        let to_copy: Vec<_> = color_to_copy
+            .chain(std::iter::once(depth_to_copy))
+            .collect();
+        self.record_barriers(command_buffer, &to_copy);
+
+        for (image, region) in readback_regions {
+            unsafe {
+                self.gpu.device.cmd_copy_image_to_buffer(
+                    command_buffer,
+                    image,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    readback_buffer,
+                    &[region],
+                );
+            }
+        }
+
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_end_debug_utils_label(command_buffer);
+        }
+
+        self.pixel_debug.record(self.slots.flight, target);
+
+        Ok(())
+    }
+
+    /// cmd_barrier2, checked against the debug layout tracker
+    fn record_barriers(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        image_barriers: &[vk::ImageMemoryBarrier2],
+    ) {
+        #[cfg(debug_assertions)]
+        self.layout_tracker.borrow_mut().transition(image_barriers);
+
+        cmd_barrier2(&self.gpu.device, command_buffer, image_barriers);
+    }
+
+    /// A global memory barrier, for the debug tracker's storage writes
+    fn record_memory_barrier(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        memory_barrier: vk::MemoryBarrier2,
+    ) {
+        #[cfg(debug_assertions)]
+        self.layout_tracker
+            .borrow_mut()
+            .memory_barrier(&memory_barrier);
+
+        let memory_barriers = [memory_barrier];
+        let dependency_info = vk::DependencyInfo::default().memory_barriers(&memory_barriers);
+        unsafe {
+            self.gpu
+                .device
+                .cmd_pipeline_barrier2(command_buffer, &dependency_info);
+        }
+    }
+
+    /// The images a pipeline's descriptor sets bind, for checking their
+    /// layouts each time it's drawn or dispatched
+    #[cfg(debug_assertions)]
+    fn bound_images(
+        &self,
+        texture_handles: &[&TextureHandle],
+        storage_texture_handles: &[&StorageTextureHandle],
+    ) -> Vec<BoundImage> {
+        let textures = texture_handles
+            .iter()
+            .map(|handle| BoundImage::Texture(handle.index()));
+        let storage_textures = storage_texture_handles
+            .iter()
+            .map(|handle| BoundImage::Storage(self.gpu.storage_textures.get(handle).image));
+        textures.chain(storage_textures).collect()
+    }
+
+    /// Warns (once per buffer) when this frame's draws read a uniform buffer,
+    /// or gpu_update addressed a storage buffer, whose current slot was never written
+    #[cfg(debug_assertions)]
+    fn warn_unwritten_buffers(
+        &mut self,
+        pending_draws: &[PendingDrawCommand],
+        pending_compute: &[PendingComputeCommand],
+        addressed_storage: &[usize],
+    ) {
+        let draws = pending_draws.iter().map(|cmd| match cmd {
+            PendingDrawCommand::Draw { pipeline_index, .. } => {
+                let pipeline = self.gpu.pipelines.get_by_index(*pipeline_index);
+                (
+                    pipeline.shader.source_file_name(),
+                    &pipeline.bound_uniform_buffers,
+                )
+            }
+        });
+        let dispatches = pending_compute.iter().filter_map(|cmd| match cmd {
+            PendingComputeCommand::Dispatch { pipeline_index, .. } => {
+                let pipeline = self.gpu.compute_pipelines.get_by_index(*pipeline_index);
+                Some((
+                    pipeline.shader.source_file_name(),
+                    &pipeline.bound_uniform_buffers,
+                ))
+            }
+            _ => None,
+        });
+        let uniform_reads: Vec<(String, usize)> = draws
+            .chain(dispatches)
+            .flat_map(|(shader, indices)| {
+                let shader = debug::clean_shader_name(shader).to_string();
+                indices.iter().map(move |&index| (shader.clone(), index))
+            })
+            .collect();
+
+        for (shader, index) in uniform_reads {
+            if self
+                .gpu
+                .uniform_buffers
+                .take_unwritten_warning(index, self.slots.ring)
+            {
+                log::warn!(
+                    "{shader} reads uniform buffer {index}, which was never written for this frame; \
+                     write it every frame, or create it with create_uniform_buffer_with"
+                );
+            }
+        }
+
+        for &index in addressed_storage {
+            if self
+                .gpu
+                .storage_buffers
+                .take_unwritten_warning(index, self.slots.ring)
+            {
+                log::warn!(
+                    "storage buffer {index} is addressed but was never written for this frame; \
+                     buffers only shaders write should be GpuOnlyBufferHandles"
+                );
+            }
+        }
+    }
+
+    /// The image a texture refers to for this ring slot, the same choice as
+    /// texture_image_infos: feedback and scene color aliases rotate per frame
+    fn texture_image_for_frame(&self, index: usize, texture: &Texture) -> vk::Image {
+        let feedback = self
+            .feedback_textures
+            .iter()
+            .find_map(|feedback| Some((feedback, feedback.ring_offset_for(index)?)));
+        match feedback {
+            Some((feedback, offset)) => {
+                feedback.images[(self.slots.ring + offset) % PRE_WAIT_RING_LEN]
+            }
+            None if self.scene_color_texture_indices.contains(&index) => {
+                self.resolve_images[self.slots.ring]
+            }
+            None => texture.image,
+        }
+    }
+
+    /// Panics if an image bound for this ring slot isn't in the layout its
+    /// descriptor expects
+    #[cfg(debug_assertions)]
+    fn check_bound_layouts(&self, bound_images: &[BoundImage], shader_name: &str) {
+        let layout_tracker = self.layout_tracker.borrow();
+        for bound_image in bound_images {
+            match *bound_image {
+                BoundImage::Texture(index) => {
+                    let Some(texture) = self.gpu.textures.get_by_index(index) else {
+                        continue;
+                    };
+                    let image = self.texture_image_for_frame(index, texture);
+                    layout_tracker.expect(image, texture.image_layout, shader_name);
+                }
+                BoundImage::Storage(image) => {
+                    layout_tracker.expect(image, vk::ImageLayout::GENERAL, shader_name);
+                }
+            }
+        }
+    }
+
+    fn mark_breadcrumb(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        queue: BreadcrumbQueue,
+        breadcrumb: Breadcrumb,
+    ) {
+        if let Some(breadcrumbs) = &self.gpu.breadcrumbs {
+            breadcrumbs.mark(command_buffer, queue, breadcrumb);
+        }
+    }
+
+    /// On a device lost error, log the last breadcrumbs each queue reached
+    /// and write them to device_lost.log
+    fn report_device_lost(&self, error: &anyhow::Error) {
+        if error.downcast_ref::<vk::Result>() != Some(&vk::Result::ERROR_DEVICE_LOST) {
+            return;
+        }
+        let Some(breadcrumbs) = &self.gpu.breadcrumbs else {
+            // debug builds only, on devices with one of Breadcrumbs::EXTENSIONS
+            log::error!("device lost; no breadcrumbs were recorded");
+            return;
+        };
+
+        let mut queues = vec![(BreadcrumbQueue::Graphics, self.gpu.graphics_queue)];
+        if let Some(compute_queue) = self.gpu.compute_queue {
+            queues.push((BreadcrumbQueue::Compute, compute_queue));
+        }
+        let graphics_name = |index| {
+            let shader = &self.gpu.pipelines.get_by_index(index).shader;
+            debug::clean_shader_name(shader.source_file_name()).to_string()
+        };
+        let breadcrumbs_report =
+            breadcrumbs.report(
+                &self.gpu.allocator,
+                &queues,
+                |breadcrumb| match breadcrumb {
+                    Breadcrumb::Pass(pass) => pass.name().to_string(),
+                    Breadcrumb::Draw(index) => format!("draw {}", graphics_name(index)),
+                    Breadcrumb::DepthPrepass(index) => {
+                        format!("depth prepass {}", graphics_name(index))
+                    }
+                    Breadcrumb::Dispatch(index) => {
+                        let shader = &self.gpu.compute_pipelines.get_by_index(index).shader;
+                        format!(
+                            "dispatch {}",
+                            debug::clean_shader_name(shader.source_file_name())
+                        )
+                    }
+                },
+            );
+        let report = format!(
+            "device lost during frame {}\n{breadcrumbs_report}",
+            self.total_frames
+        );
+
+        log::error!("{report}");
+        match std::fs::write("device_lost.log", &report) {
+            Ok(()) => log::error!("wrote device_lost.log"),
+            Err(e) => log::error!("failed to write device_lost.log: {e}"),
+        }
+    }
+
+    /// Records the main pass's color draws in order, drawing each run of
+    /// split-screen pipelines once per view, clipped to that view's rect
+    fn record_main_pass_color_draws(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        main_pass_draws: &[&PendingDrawCommand],
+    ) {
+        let is_split_screen = |pending_draw: &PendingDrawCommand| {
+            let PendingDrawCommand::Draw { pipeline_index, .. } = pending_draw;
+            self.gpu
+                .pipelines
+                .get_by_index(*pipeline_index)
+                .split_screen
+        };
+
+        let full_area = vk::Rect2D::default().extent(self.render_extent);
+        for run in main_pass_draws.chunk_by(|a, b| is_split_screen(a) == is_split_screen(b)) {
+            // without views, split-screen pipelines draw view 0 over the whole target
+            if !is_split_screen(run[0]) || self.split_screen.views.is_empty() {
+                self.record_draws(
+                    command_buffer,
+                    run.iter().copied(),
+                    DrawPass::Color,
+                    full_area,
+                );
+                continue;
+            }
+
+            for (view_index, view) in self.split_screen.views.iter().enumerate() {
+                let view_area = view.rect.to_pixels(self.render_extent);
+                self.set_viewport_and_scissor(command_buffer, view_area);
+                self.record_draws(
+                    command_buffer,
+                    run.iter().copied(),
+                    DrawPass::SplitView(view_index as u32),
+                    view_area,
+                );
+            }
+            self.set_viewport_and_scissor(command_buffer, full_area);
+        }
+    }
+
+    fn set_viewport_and_scissor(&self, command_buffer: vk::CommandBuffer, area: vk::Rect2D) {
+        let viewport = vk::Viewport::default()
+            .x(area.offset.x as f32)
+            .y(area.offset.y as f32)
+            .width(area.extent.width as f32)
+            .height(area.extent.height as f32)
+            .min_depth(0.0)
+            .max_depth(1.0);
+        unsafe {
+            self.gpu
+                .device
+                .cmd_set_viewport(command_buffer, 0, &[viewport]);
+            self.gpu.device.cmd_set_scissor(command_buffer, 0, &[area]);
+        }
+    }
+
+    /// Pushes the pipeline's push constants, one range per stage that declares them
+    fn push_draw_constants(&self, command_buffer: vk::CommandBuffer, pipeline: &RendererPipeline) {
+        if pipeline.push_constants.is_empty() {
+            return;
+        }
+
+        for range in &pipeline.shader.pipeline_layout().push_constant_ranges {
+            let start = range.offset as usize;
+            let end = start + range.size as usize;
+            unsafe {
+                self.gpu.device.cmd_push_constants(
+                    command_buffer,
+                    pipeline.layout.pipeline_layout,
+                    range.stage_flags.to_vk(),
+                    range.offset,
+                    &pipeline.push_constants[start..end],
+                );
+            }
+        }
+    }
+
+    /// Records the draws bound to each pipeline's vk pipeline for `pass`;
+    /// for the depth prepass, only the draws of pipelines that have one.
+    ///
+    /// The pass's viewport is already set, and `area` is its scissor;
+    /// clipped draws narrow the scissor, which is restored after
+    fn record_draws<'d>(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pending_draws: impl Iterator<Item = &'d PendingDrawCommand>,
+        pass: DrawPass,
+        area: vk::Rect2D,
+    ) {
+        // consecutive draws often share vertex/index buffers (shared meshes)
+        let mut last_bound_buffers: Option<(vk::Buffer, vk::Buffer)> = None;
+        let area = ClipRect::from_rect2d(area);
+        let mut scissor = area;
+
+        for pending_draw in pending_draws {
+            let PendingDrawCommand::Draw {
+                pipeline_index,
+                variant,
+                draw_call,
+                clip,
+            } = pending_draw;
+            let pipeline = self.gpu.pipelines.get_by_index(*pipeline_index);
+            let vk_pipeline = match pass {
+                DrawPass::Color | DrawPass::SplitView(_) => pipeline.vk_pipeline(*variant),
+                DrawPass::DepthPrepass => match pipeline.vk_depth_prepass_pipeline(*variant) {
+                    Some(vk_pipeline) => vk_pipeline,
+                    None => continue,
+                },
+                DrawPass::PixelDebug(replay_pipeline) => replay_pipeline,
+            };
+
+            let shader_name = debug::clean_shader_name(pipeline.shader.source_file_name());
+            let label_name = CString::new(shader_name).unwrap();
+            let label = vk::DebugUtilsLabelEXT::default()
+                .label_name(&label_name)
+                .color([1.0, 1.0, 1.0, 1.0]);
+            unsafe {
+                self.gpu
+                    .debug_utils_device
+                    .cmd_begin_debug_utils_label(command_buffer, &label);
+            }
+            let breadcrumb = if pass == DrawPass::DepthPrepass {
+                Breadcrumb::DepthPrepass(*pipeline_index)
+            } else {
+                Breadcrumb::Draw(*pipeline_index)
+            };
+            self.mark_breadcrumb(command_buffer, BreadcrumbQueue::Graphics, breadcrumb);
+            #[cfg(debug_assertions)]
+            {
+                self.check_bound_layouts(&pipeline.bound_images, shader_name);
+                self.layout_tracker
+                    .borrow_mut()
+                    .storage_read(shader_name, DRAW_READ_STAGES);
+            }
+
+            unsafe {
+                self.gpu.device.cmd_bind_pipeline(
+                    command_buffer,
+                    vk::PipelineBindPoint::GRAPHICS,
+                    vk_pipeline,
+                );
+            }
+
+            // the pixel debugger's targets are a single pixel, and it only
+            // replays draws whose clip contains it
+            let draw_scissor = match (pass, clip) {
+                (DrawPass::PixelDebug(_), _) | (_, None) => area,
+                (_, Some(clip)) => clip.intersect(area),
+            };
+            if draw_scissor != scissor {
+                unsafe {
+                    self.gpu
+                        .device
+                        .cmd_set_scissor(command_buffer, 0, &[draw_scissor.to_rect2d()]);
+                }
+                scissor = draw_scissor;
+            }
+
+            let draw_buffers = match &pipeline.vertex_pipeline_config {
+                VertexPipelineConfig::VertexAndIndexBuffers(vi_bufs) => {
+                    Some((vi_bufs.vertex_buffer, vi_bufs.index_buffer))
+                }
+                VertexPipelineConfig::SharedMesh(mesh_index) => {
+                    let mesh = &self.gpu.meshes[mesh_index.raw()];
+                    Some((mesh.vertex_buffer, mesh.index_buffer))
+                }
+                VertexPipelineConfig::VertexCount => None,
+            };
+            if let Some((vertex_buffer, index_buffer)) = draw_buffers
+                && last_bound_buffers != Some((vertex_buffer, index_buffer))
+            {
+                let buffers = [vertex_buffer];
+                let offsets = [0];
+                unsafe {
+                    self.gpu
+                        .device
+                        .cmd_bind_vertex_buffers(command_buffer, 0, &buffers, &offsets);
+
+                    self.gpu.device.cmd_bind_index_buffer(
+                        command_buffer,
+                        index_buffer,
+                        0,
+                        vk::IndexType::UINT32,
+                    );
+                }
+                last_bound_buffers = Some((vertex_buffer, index_buffer));
+            }
+
+            let descriptor_sets = self.descriptor_sets_for_frame(*pipeline_index);
+            unsafe {
+                self.gpu.device.cmd_bind_descriptor_sets(
+                    command_buffer,
+                    vk::PipelineBindPoint::GRAPHICS,
+                    self.gpu
+                        .pipelines
+                        .get_by_index(*pipeline_index)
+                        .layout
+                        .pipeline_layout,
+                    0,
+                    descriptor_sets,
+                    &[],
+                );
+            }
+            self.push_draw_constants(command_buffer, pipeline);
+
+            // shaders read the split-screen view as SV_StartInstanceLocation
+            let first_instance = match pass {
+                DrawPass::SplitView(view_index) => view_index,
+                _ => 0,
+            };
+            // pixel debug replays have no dynamic shading rate
+            let rate = match pass {
+                DrawPass::PixelDebug(_) => ShadingRate::Full,
+                _ => pipeline.shading_rate,
+            };
+            match &self.gpu.fragment_shading_rate {
+                Some(fragment_shading_rate) if rate.is_variable() => {
+                    // one draw per region of the area, scissored to it
+                    for (region, size) in rate.regions(area) {
+                        let region = region.intersect(draw_scissor);
+                        if region.width == 0 || region.height == 0 {
+                            continue;
+                        }
+                        unsafe {
+                            self.gpu.device.cmd_set_scissor(
+                                command_buffer,
+                                0,
+                                &[region.to_rect2d()],
+                            );
+                        }
+                        scissor = region;
+                        shading_rate::cmd_set_fragment_size(
+                            fragment_shading_rate,
+                            command_buffer,
+                            size,
+                        );
+                        self.record_draw_call(command_buffer, draw_call, first_instance);
+                    }
+                }
+                _ => self.record_draw_call(command_buffer, draw_call, first_instance),
+            }
+
+            unsafe {
+                self.gpu
+                    .debug_utils_device
+                    .cmd_end_debug_utils_label(command_buffer);
+            }
+        }
+
+        if scissor != area {
+            unsafe {
+                self.gpu
+                    .device
+                    .cmd_set_scissor(command_buffer, 0, &[area.to_rect2d()]);
+            }
+        }
+    }
+
+    fn record_draw_call(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        draw_call: &DrawCallConfig,
+        first_instance: u32,
+    ) {
+        match draw_call {
+            DrawCallConfig::VertexCount(vertex_count) => unsafe {
+                self.gpu
+                    .device
+                    .cmd_draw(command_buffer, *vertex_count, 1, 0, first_instance);
+            },
+
+            DrawCallConfig::VertexRange {
+                first_vertex,
+                vertex_count,
+            } => unsafe {
+                self.gpu.device.cmd_draw(
+                    command_buffer,
+                    *vertex_count,
+                    1,
+                    *first_vertex,
+                    first_instance,
+                );
+            },
+
+            DrawCallConfig::MeshTasks([x, y, z]) => unsafe {
+                // creating a mesh pipeline requires the extension
+                let mesh_shader = self.gpu.mesh_shader.as_ref().unwrap();
+                mesh_shader.cmd_draw_mesh_tasks(command_buffer, *x, *y, *z);
+            },
+
+            DrawCallConfig::IndexCount(index_count) => unsafe {
+                self.gpu.device.cmd_draw_indexed(
+                    command_buffer,
+                    *index_count,
+                    1,
+                    0,
+                    0,
+                    first_instance,
+                );
+            },
+
+            DrawCallConfig::IndexRange {
+                first_index,
+                index_count,
+            } => unsafe {
+                self.gpu.device.cmd_draw_indexed(
+                    command_buffer,
+                    *index_count,
+                    1,
+                    *first_index,
+                    0,
+                    first_instance,
+                );
+            },
+        }
+    }
+
+    /// Acquires imported textures released again by their producers. The
+    /// semaphore waits are added to the frame's submit, and the queue is kept
+    /// until then in case the frame is abandoned.
+    fn record_external_acquires(&self, command_buffer: vk::CommandBuffer) {
+        let barriers: Vec<_> = self
+            .pending_external_acquires
+            .iter()
+            // the texture was dropped after queueing the acquire
+            .filter_map(|acquire| self.gpu.textures.get_by_index(acquire.texture_index))
+            .map(|texture| {
+                external_memory::acquire_barrier(
+                    texture.image,
+                    self.gpu.queue_family_indices.graphics,
+                )
+            })
+            .collect();
+
+        // the producer's release left the image in its own layout
+        #[cfg(debug_assertions)]
+        for barrier in &barriers {
+            self.layout_tracker.borrow_mut().forget(barrier.image);
+        }
+        self.record_barriers(command_buffer, &barriers);
+    }
+
+    /// Semaphore waits for the acquires recorded into this frame's commands;
+    /// each binary semaphore is waited on once
+    fn take_external_waits(&mut self) -> Vec<vk::SemaphoreSubmitInfo<'static>> {
+        let mut semaphores: Vec<vk::Semaphore> = vec![];
+        for acquire in self.pending_external_acquires.drain(..) {
+            if !semaphores.contains(&acquire.ready) {
+                semaphores.push(acquire.ready);
+            }
+        }
+
+        semaphores
+            .into_iter()
+            .map(|semaphore| {
+                vk::SemaphoreSubmitInfo::default()
+                    .semaphore(semaphore)
+                    .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+            })
+            .collect()
+    }
+
+    /// Copies queued texture updates from their staging buffers. The barriers
+    /// also wait for earlier frames' shader reads of the textures.
+    fn record_texture_updates(&mut self, command_buffer: vk::CommandBuffer) {
+        let label = vk::DebugUtilsLabelEXT::default()
+            .label_name(c"Texture Updates")
+            .color([0.4, 0.6, 0.8, 1.0]);
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_begin_debug_utils_label(command_buffer, &label);
+        }
+
+        let shader_stages = vk::PipelineStageFlags2::VERTEX_SHADER
+            | vk::PipelineStageFlags2::FRAGMENT_SHADER
+            | vk::PipelineStageFlags2::COMPUTE_SHADER;
+
+        for update in std::mem::take(&mut self.pending_texture_updates) {
+            let PendingTextureUpdate {
+                texture_index,
+                region,
+                staging_buffer,
+                staging_memory,
+            } = update;
+            // freed once this flight slot's frame has retired
+            self.texture_update_staging[self.slots.flight].push((staging_buffer, staging_memory));
+
+            // the texture was dropped after queueing the update
+            let Some(texture) = self.gpu.textures.get_by_index(texture_index) else {
+                continue;
+            };
+
+            let to_transfer_dst = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(texture.image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(shader_stages)
+                .src_access_mask(vk::AccessFlags2::NONE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+                .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE);
+            self.record_barriers(command_buffer, &[to_transfer_dst]);
+
+            let image_subresource = vk::ImageSubresourceLayers::default()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .mip_level(0)
+                .base_array_layer(0)
+                .layer_count(1);
+            let copy_region = vk::BufferImageCopy::default()
+                .buffer_offset(0)
+                .buffer_row_length(0)
+                .buffer_image_height(0)
+                .image_subresource(image_subresource)
+                .image_offset(vk::Offset3D {
+                    x: region.x as i32,
+                    y: region.y as i32,
+                    z: 0,
+                })
+                .image_extent(vk::Extent3D {
+                    width: region.width,
+                    height: region.height,
+                    depth: 1,
+                });
+            unsafe {
+                self.gpu.device.cmd_copy_buffer_to_image(
+                    command_buffer,
+                    staging_buffer,
+                    texture.image,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[copy_region],
+                );
+            }
+
+            let to_shader_read = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(texture.image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::COPY)
+                .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+                .dst_stage_mask(shader_stages)
+                .dst_access_mask(vk::AccessFlags2::SHADER_READ);
+            self.record_barriers(command_buffer, &[to_shader_read]);
+        }
+
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_end_debug_utils_label(command_buffer);
+        }
+    }
+
+    /// Renders each feedback texture's pipelines into this frame's image,
+    /// leaving it in SHADER_READ_ONLY_OPTIMAL for the main pass and next frame.
+    fn record_feedback_passes(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pending_draws: &[PendingDrawCommand],
+    ) {
+        for (target_index, feedback) in self.feedback_textures.iter().enumerate() {
+            let label = vk::DebugUtilsLabelEXT::default()
+                .label_name(c"Feedback")
+                .color([0.4, 0.8, 0.4, 1.0]);
+            unsafe {
+                self.gpu
+                    .debug_utils_device
+                    .cmd_begin_debug_utils_label(command_buffer, &label);
+            }
+            self.mark_breadcrumb(
+                command_buffer,
+                BreadcrumbQueue::Graphics,
+                Breadcrumb::Pass(BreadcrumbPass::Feedback),
+            );
+
+            let image = feedback.images[self.slots.ring];
+
+            // this slot's image was last sampled as history, two frames ago
+            let barrier_to_attachment = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::UNDEFINED)
+                .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+                .src_access_mask(vk::AccessFlags2::NONE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .dst_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE);
+            self.record_barriers(command_buffer, &[barrier_to_attachment]);
+
+            let clear_color = vk::ClearValue {
+                color: vk::ClearColorValue {
+                    float32: [0.0, 0.0, 0.0, 0.0],
+                },
+            };
+            let color_attachment = vk::RenderingAttachmentInfo::default()
+                .image_view(feedback.image_views[self.slots.ring])
+                .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .load_op(vk::AttachmentLoadOp::CLEAR)
+                .store_op(vk::AttachmentStoreOp::STORE)
+                .clear_value(clear_color);
+            let color_attachments = [color_attachment];
+            let render_area = vk::Rect2D::default()
+                .offset(vk::Offset2D::default())
+                .extent(feedback.extent);
+            let rendering_info = vk::RenderingInfo::default()
+                .render_area(render_area)
+                .layer_count(1)
+                .color_attachments(&color_attachments);
+
+            unsafe {
+                self.gpu
+                    .device
+                    .cmd_begin_rendering(command_buffer, &rendering_info);
+            }
+
+            let viewport = vk::Viewport::default()
+                .x(0.0)
+                .y(0.0)
+                .width(feedback.extent.width as f32)
+                .height(feedback.extent.height as f32)
+                .min_depth(0.0)
+                .max_depth(1.0);
+            unsafe {
+                self.gpu
+                    .device
+                    .cmd_set_viewport(command_buffer, 0, &[viewport])
+            };
+            unsafe {
+                self.gpu
+                    .device
+                    .cmd_set_scissor(command_buffer, 0, &[render_area])
+            };
+
+            self.record_draws(
+                command_buffer,
+                pending_draws.iter().filter(|pending_draw| {
+                    let PendingDrawCommand::Draw { pipeline_index, .. } = pending_draw;
+                    self.gpu
+                        .pipelines
+                        .get_by_index(*pipeline_index)
+                        .render_target
+                        == Some(target_index)
+                }),
+                DrawPass::Color,
+                render_area,
+            );
+
+            unsafe { self.gpu.device.cmd_end_rendering(command_buffer) };
+
+            let barrier_to_sampled = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+                .dst_access_mask(vk::AccessFlags2::SHADER_READ);
+            self.record_barriers(command_buffer, &[barrier_to_sampled]);
+
+            unsafe {
+                self.gpu
+                    .debug_utils_device
+                    .cmd_end_debug_utils_label(command_buffer);
+            }
+        }
+    }
+
+    /// Clears a new TAA history, before the first post-process pass samples it
+    fn record_taa_history_clear(&mut self, command_buffer: vk::CommandBuffer) {
+        let Some(history) = self.taa_history.as_mut() else {
+            return;
+        };
+        if std::mem::replace(&mut history.initialized, true) {
+            return;
+        }
+        let image = history.image;
+
+        let to_transfer_dst = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::UNDEFINED)
+            .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::NONE)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(vk::PipelineStageFlags2::CLEAR)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE);
+        self.record_barriers(command_buffer, &[to_transfer_dst]);
+
+        unsafe {
+            self.gpu.device.cmd_clear_color_image(
+                command_buffer,
+                image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &vk::ClearColorValue {
+                    float32: [0.0, 0.0, 0.0, 1.0],
+                },
+                &[COLOR_SUBRESOURCE_RANGE],
+            );
+        }
+
+        let to_sampled = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::CLEAR)
+            .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags2::SHADER_READ);
+        self.record_barriers(command_buffer, &[to_sampled]);
+    }
+
+    /// Copies this frame's post-process output into the TAA history,
+    /// after the post-process pass's reads of the old history
+    fn record_taa_history_copy(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        post_process_image: vk::Image,
+    ) {
+        let Some(history) = &self.taa_history else {
+            return;
+        };
+
+        // the post-process pass left its image ready for the blit,
+        // but its barrier only made its writes visible to the blit
+        let source_to_copy = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(post_process_image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
+        let history_to_transfer_dst = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(history.image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE);
+        self.record_barriers(command_buffer, &[source_to_copy, history_to_transfer_dst]);
+
+        let subresource = vk::ImageSubresourceLayers::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .mip_level(0)
+            .base_array_layer(0)
+            .layer_count(1);
+        let region = vk::ImageCopy::default()
+            .src_subresource(subresource)
+            .dst_subresource(subresource)
+            .extent(vk::Extent3D {
+                width: self.render_extent.width,
+                height: self.render_extent.height,
+                depth: 1,
+            });
+        unsafe {
+            self.gpu.device.cmd_copy_image(
+                command_buffer,
+                post_process_image,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                history.image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[region],
+            );
+        }
+
+        let history_to_sampled = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(history.image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::COPY)
+            .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags2::SHADER_READ);
+        self.record_barriers(command_buffer, &[history_to_sampled]);
+    }
+
+    fn has_ui_layer_draws(&self, pending_draws: &[PendingDrawCommand]) -> bool {
+        pending_draws.iter().any(|pending_draw| {
+            let PendingDrawCommand::Draw { pipeline_index, .. } = pending_draw;
+            self.gpu.pipelines.get_by_index(*pipeline_index).ui_layer
+        })
+    }
+
+    /// Draws the ui layer pipelines onto the swapchain image after the upscale
+    /// blit, at window resolution, leaving the image ready for egui or presenting
+    fn record_ui_layer_pass(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pending_draws: &[PendingDrawCommand],
+        image_index: u32,
+    ) {
+        if !self.has_ui_layer_draws(pending_draws) {
+            return;
+        }
+        let is_ui_layer_draw = |pending_draw: &&PendingDrawCommand| {
+            let PendingDrawCommand::Draw { pipeline_index, .. } = pending_draw;
+            self.gpu.pipelines.get_by_index(*pipeline_index).ui_layer
+        };
+
+        let label = vk::DebugUtilsLabelEXT::default()
+            .label_name(c"UI Layer")
+            .color([0.4, 0.8, 0.8, 1.0]);
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_begin_debug_utils_label(command_buffer, &label);
+        }
+        self.mark_breadcrumb(
+            command_buffer,
+            BreadcrumbQueue::Graphics,
+            Breadcrumb::Pass(BreadcrumbPass::Ui),
+        );
+
+        // the blit left the image as a color attachment
+        let render_area = vk::Rect2D::default().extent(self.presenter.image_extent);
+        let color_attachment = vk::RenderingAttachmentInfo::default()
+            .image_view(self.presenter.swapchain_image_views[image_index as usize])
+            .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(vk::AttachmentLoadOp::LOAD)
+            .store_op(vk::AttachmentStoreOp::STORE);
+        let color_attachments = [color_attachment];
+        let rendering_info = vk::RenderingInfo::default()
+            .render_area(render_area)
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+
+        unsafe {
+            self.gpu
+                .device
+                .cmd_begin_rendering(command_buffer, &rendering_info);
+        }
+        self.set_viewport_and_scissor(command_buffer, render_area);
+
+        self.record_draws(
+            command_buffer,
+            pending_draws.iter().filter(is_ui_layer_draw),
+            DrawPass::Color,
+            render_area,
+        );
+
+        unsafe { self.gpu.device.cmd_end_rendering(command_buffer) };
+
+        let barrier_to_next = if self.egui_enabled() {
+            vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .dst_access_mask(
+                    vk::AccessFlags2::COLOR_ATTACHMENT_READ
+                        | vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
+                )
+        } else {
+            vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                // presentation waits on the render_finished semaphore, not this barrier
+                .dst_stage_mask(vk::PipelineStageFlags2::NONE)
+                .dst_access_mask(vk::AccessFlags2::NONE)
+        }
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(self.presenter.swapchain_images[image_index as usize])
+        .subresource_range(COLOR_SUBRESOURCE_RANGE);
+        self.record_barriers(command_buffer, &[barrier_to_next]);
+
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_end_debug_utils_label(command_buffer);
+        }
+    }
+
+    /// Draws the post-process pipelines over the resolved scene color,
+    /// returning the image to upscale in its place, ready for the blit read;
+    /// None if there were no post-process draws this frame
+    fn record_post_process_pass(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pending_draws: &[PendingDrawCommand],
+    ) -> Option<vk::Image> {
+        let post_process = self.post_process.as_ref()?;
+        let is_post_process_draw = |pending_draw: &&PendingDrawCommand| {
+            let PendingDrawCommand::Draw { pipeline_index, .. } = pending_draw;
+            self.gpu
+                .pipelines
+                .get_by_index(*pipeline_index)
+                .post_process
+        };
+        if !pending_draws.iter().any(|draw| is_post_process_draw(&draw)) {
+            return None;
+        }
+
+        let label = vk::DebugUtilsLabelEXT::default()
+            .label_name(c"PostProcess")
+            .color([0.8, 0.4, 0.8, 1.0]);
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_begin_debug_utils_label(command_buffer, &label);
+        }
+        self.mark_breadcrumb(
+            command_buffer,
+            BreadcrumbQueue::Graphics,
+            Breadcrumb::Pass(BreadcrumbPass::PostProcess),
+        );
+
+        let image = post_process.images[self.slots.flight];
+
+        let resolve_to_sampled = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(self.resolve_images[self.slots.ring])
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags2::SHADER_READ);
+        // this frame's image was last read by the upscale blit, two frames ago
+        let barrier_to_attachment = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::UNDEFINED)
+            .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::BLIT)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .dst_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE);
+        self.record_barriers(command_buffer, &[resolve_to_sampled, barrier_to_attachment]);
+
+        let clear_color = vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            },
+        };
+        let color_attachment = vk::RenderingAttachmentInfo::default()
+            .image_view(post_process.image_views[self.slots.flight])
+            .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .clear_value(clear_color);
+        let color_attachments = [color_attachment];
+        let render_area = vk::Rect2D::default()
+            .offset(vk::Offset2D::default())
+            .extent(self.render_extent);
+        let rendering_info = vk::RenderingInfo::default()
+            .render_area(render_area)
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+
+        unsafe {
+            self.gpu
+                .device
+                .cmd_begin_rendering(command_buffer, &rendering_info);
+        }
+
+        let viewport = vk::Viewport::default()
+            .x(0.0)
+            .y(0.0)
+            .width(self.render_extent.width as f32)
+            .height(self.render_extent.height as f32)
+            .min_depth(0.0)
+            .max_depth(1.0);
+        unsafe {
+            self.gpu
+                .device
+                .cmd_set_viewport(command_buffer, 0, &[viewport])
+        };
+        unsafe {
+            self.gpu
+                .device
+                .cmd_set_scissor(command_buffer, 0, &[render_area])
+        };
+
+        self.record_draws(
+            command_buffer,
+            pending_draws.iter().filter(is_post_process_draw),
+            DrawPass::Color,
+            render_area,
+        );
+
+        unsafe { self.gpu.device.cmd_end_rendering(command_buffer) };
+
+        let barrier_to_blit_src = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::BLIT)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_READ);
+        self.record_barriers(command_buffer, &[barrier_to_blit_src]);
 
-            cmd_barrier2(&self.device, command_buffer, &[barrier_to_transfer]);
+        unsafe {
+            self.gpu
+                .debug_utils_device
+                .cmd_end_debug_utils_label(command_buffer);
+        }
 
-            // Blit from resolve_image to swapchain_image
-            let src_subresource = vk::ImageSubresourceLayers::default()
-                .aspect_mask(vk::ImageAspectFlags::COLOR)
-                .mip_level(0)
-                .base_array_layer(0)
-                .layer_count(1);
+        Some(image)
+    }
 
-            let blit = vk::ImageBlit::default()
-                .src_offsets([
-                    vk::Offset3D::default(),
-                    vk::Offset3D {
-                        x: self.render_extent.width as i32,
-                        y: self.render_extent.height as i32,
-                        z: 1,
-                    },
-                ])
-                .src_subresource(src_subresource)
-                .dst_offsets([
-                    vk::Offset3D::default(),
-                    vk::Offset3D {
-                        x: self.image_extent.width as i32,
-                        y: self.image_extent.height as i32,
-                        z: 1,
-                    },
-                ])
-                .dst_subresource(src_subresource);
+    /// The upscale from the blit source to the swapchain image, clearing a
+    /// virtual resolution's borders; leaves the swapchain image for transfer writes
+    fn record_upscale_blit(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        blit_source: vk::Image,
+        swapchain_image: vk::Image,
+    ) {
+        // Transition swapchain image from UNDEFINED to TRANSFER_DST
+        let barrier_to_transfer = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::UNDEFINED)
+            .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(swapchain_image)
+            .subresource_range(vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                layer_count: 1,
+            })
+            .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(vk::PipelineStageFlags2::BLIT | vk::PipelineStageFlags2::CLEAR)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE);
 
+        self.record_barriers(command_buffer, &[barrier_to_transfer]);
+
+        // a virtual resolution's borders
+        if self.present_rect.extent != self.presenter.image_extent {
             unsafe {
-                self.device.cmd_blit_image(
+                self.gpu.device.cmd_clear_color_image(
                     command_buffer,
-                    self.resolve_images[self.flight_slot],
-                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
                     swapchain_image,
                     vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                    &[blit],
-                    vk::Filter::LINEAR,
+                    &vk::ClearColorValue {
+                        float32: [0.0, 0.0, 0.0, 1.0],
+                    },
+                    &[COLOR_SUBRESOURCE_RANGE],
                 );
             }
 
-            // With egui active the swapchain image is rendered to once more (the egui
-            // overlay loads and draws on top); otherwise it goes straight to present.
-            let barrier_to_next = if self.egui.is_some() {
-                vk::ImageMemoryBarrier2::default()
-                    .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-                    .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-                    .src_stage_mask(vk::PipelineStageFlags2::BLIT)
-                    .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
-                    .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
-                    .dst_access_mask(
-                        vk::AccessFlags2::COLOR_ATTACHMENT_READ
-                            | vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
-                    )
-            } else {
-                vk::ImageMemoryBarrier2::default()
-                    .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-                    .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
-                    .src_stage_mask(vk::PipelineStageFlags2::BLIT)
-                    .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
-                    // presentation waits on the render_finished semaphore, not this barrier
-                    .dst_stage_mask(vk::PipelineStageFlags2::NONE)
-                    .dst_access_mask(vk::AccessFlags2::NONE)
-            }
-            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-            .image(swapchain_image)
-            .subresource_range(COLOR_SUBRESOURCE_RANGE);
-
-            cmd_barrier2(&self.device, command_buffer, &[barrier_to_next]);
-        }
-
-        unsafe {
-            self.debug_utils_device
-                .cmd_end_debug_utils_label(command_buffer);
+            let clear_to_blit = vk::ImageMemoryBarrier2::default()
+                .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(swapchain_image)
+                .subresource_range(COLOR_SUBRESOURCE_RANGE)
+                .src_stage_mask(vk::PipelineStageFlags2::CLEAR)
+                .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::BLIT)
+                .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE);
+            self.record_barriers(command_buffer, &[clear_to_blit]);
         }
 
-        // EGUI RENDERING (separate 1-sample rendering for egui overlay)
-        if let Some(egui) = &mut self.egui {
-            let label = vk::DebugUtilsLabelEXT::default()
-                .label_name(c"Egui")
-                .color([0.8, 0.8, 0.4, 1.0]);
-            unsafe {
-                self.debug_utils_device
-                    .cmd_begin_debug_utils_label(command_buffer, &label);
-            }
-
-            let render_area = vk::Rect2D::default()
-                .offset(vk::Offset2D::default())
-                .extent(self.image_extent);
-            // draws over the blitted frame on the swapchain image
-            let egui_color_attachment = vk::RenderingAttachmentInfo::default()
-                .image_view(self.swapchain_image_views[image_index as usize])
-                .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-                .load_op(vk::AttachmentLoadOp::LOAD)
-                .store_op(vk::AttachmentStoreOp::STORE);
-            let egui_color_attachments = [egui_color_attachment];
-            let egui_rendering_info = vk::RenderingInfo::default()
-                .render_area(render_area)
-                .layer_count(1)
-                .color_attachments(&egui_color_attachments);
+        // Blit from the resolve (or post-process) image to swapchain_image;
+        // in stereo, each view's layer fills its own side of the window
+        let dst_subresource = vk::ImageSubresourceLayers::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .mip_level(0)
+            .base_array_layer(0)
+            .layer_count(1);
 
-            unsafe {
-                self.device
-                    .cmd_begin_rendering(command_buffer, &egui_rendering_info);
-            }
+        let view_count = self.view_mode.view_count();
+        let present_rect = self.present_rect;
+        let dst_view_width = present_rect.extent.width / view_count;
+        let blits: Vec<vk::ImageBlit> = (0..view_count)
+            .map(|view| {
+                let dst_x = present_rect.offset.x + (view * dst_view_width) as i32;
+                vk::ImageBlit::default()
+                    .src_offsets([
+                        vk::Offset3D::default(),
+                        vk::Offset3D {
+                            x: self.render_extent.width as i32,
+                            y: self.render_extent.height as i32,
+                            z: 1,
+                        },
+                    ])
+                    .src_subresource(dst_subresource.base_array_layer(view))
+                    .dst_offsets([
+                        vk::Offset3D {
+                            x: dst_x,
+                            y: present_rect.offset.y,
+                            z: 0,
+                        },
+                        vk::Offset3D {
+                            x: dst_x + dst_view_width as i32,
+                            y: present_rect.offset.y + present_rect.extent.height as i32,
+                            z: 1,
+                        },
+                    ])
+                    .dst_subresource(dst_subresource)
+            })
+            .collect();
 
-            // Draw egui overlay (begin_frame is idempotent, safe to call if already begun)
-            let screen_size = [self.width, self.height];
-            egui.begin_frame(screen_size);
-            let wants_keyboard_input = egui.end_frame_and_draw(
-                self.graphics_queue,
-                self.command_pool,
+        unsafe {
+            self.gpu.device.cmd_blit_image(
                 command_buffer,
-                self.image_extent,
-                self.flight_slot,
+                blit_source,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                swapchain_image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &blits,
+                self.upscale_filter().vk_filter(),
             );
+        }
+    }
 
-            // Toggle SDL text input based on whether egui has a text field focused
-            if wants_keyboard_input && !self.text_input_active {
-                unsafe { sdl3::sys::keyboard::SDL_StartTextInput(self.window.raw()) };
-                self.text_input_active = true;
-            } else if !wants_keyboard_input && self.text_input_active {
-                unsafe { sdl3::sys::keyboard::SDL_StopTextInput(self.window.raw()) };
-                self.text_input_active = false;
-            }
+    /// The [`UpscaleFilter::Sharpened`] composite, in place of the blit: draws
+    /// the blit source over the present rect, clearing a virtual resolution's
+    /// borders, and leaves the swapchain image as a color attachment
+    fn record_rcas_upscale(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        rcas_upscale: &RcasUpscale,
+        source: vk::Image,
+        image_index: u32,
+    ) {
+        // the source was left for transfer reads (the blit, and screenshots)
+        let source_to_sampled = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(source)
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::BLIT | vk::PipelineStageFlags2::COPY)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags2::SHADER_SAMPLED_READ);
+        let swapchain_to_attachment = vk::ImageMemoryBarrier2::default()
+            .old_layout(vk::ImageLayout::UNDEFINED)
+            .new_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(self.presenter.swapchain_images[image_index as usize])
+            .subresource_range(COLOR_SUBRESOURCE_RANGE)
+            .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags2::NONE)
+            .dst_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .dst_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE);
+        self.record_barriers(
+            command_buffer,
+            &[source_to_sampled, swapchain_to_attachment],
+        );
 
-            unsafe { self.device.cmd_end_rendering(command_buffer) };
+        let clear_color = vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            },
+        };
+        let color_attachment = vk::RenderingAttachmentInfo::default()
+            .image_view(self.presenter.swapchain_image_views[image_index as usize])
+            .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .clear_value(clear_color);
+        let color_attachments = [color_attachment];
+        let rendering_info = vk::RenderingInfo::default()
+            .render_area(vk::Rect2D::default().extent(self.presenter.image_extent))
+            .layer_count(1)
+            .color_attachments(&color_attachments);
 
-            // transition the swapchain image for presentation
-            let barrier_to_present = vk::ImageMemoryBarrier2::default()
-                .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-                .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
-                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                .image(swapchain_image)
-                .subresource_range(COLOR_SUBRESOURCE_RANGE)
-                .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
-                .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
-                // presentation waits on the render_finished semaphore, not this barrier
-                .dst_stage_mask(vk::PipelineStageFlags2::NONE)
-                .dst_access_mask(vk::AccessFlags2::NONE);
-            cmd_barrier2(&self.device, command_buffer, &[barrier_to_present]);
+        unsafe {
+            self.gpu
+                .device
+                .cmd_begin_rendering(command_buffer, &rendering_info);
+        }
+        rcas_upscale.record_draw(
+            &self.gpu.device,
+            command_buffer,
+            self.slots.ring,
+            self.blit_source_view(source),
+            self.present_rect,
+        );
+        unsafe { self.gpu.device.cmd_end_rendering(command_buffer) };
+    }
 
-            unsafe {
-                self.debug_utils_device
-                    .cmd_end_debug_utils_label(command_buffer);
+    /// The view of this frame's blit source: its post-process image, or
+    /// else its resolve image
+    fn blit_source_view(&self, source: vk::Image) -> vk::ImageView {
+        match &self.post_process {
+            Some(post_process) if post_process.images[self.slots.flight] == source => {
+                post_process.image_views[self.slots.flight]
             }
+            _ => self.resolve_image_views[self.slots.ring],
         }
-
-        unsafe { self.device.end_command_buffer(command_buffer)? };
-
-        Ok(())
     }
 
     fn descriptor_sets_for_frame(
@@ -2111,12 +6428,12 @@ impl Renderer {
         pipeline_index: GraphicsPipelineIndex,
     ) -> &[vk::DescriptorSet] {
         // see create_descriptor_sets
-        let pipeline = self.pipelines.get_by_index(pipeline_index);
+        let pipeline = self.gpu.pipelines.get_by_index(pipeline_index);
         let descriptor_sets_per_frame = pipeline.layout.descriptor_set_layouts.len();
         pipeline
             .descriptor_sets
             .chunks(descriptor_sets_per_frame)
-            .nth(self.ring_slot)
+            .nth(self.slots.ring)
             .unwrap()
     }
 
@@ -2125,12 +6442,12 @@ impl Renderer {
         &self,
         pipeline_handle: &PipelineHandle<Compute>,
     ) -> &[vk::DescriptorSet] {
-        let compute_pipeline = self.compute_pipelines.get(pipeline_handle);
+        let compute_pipeline = self.gpu.compute_pipelines.get(pipeline_handle);
         let descriptor_sets_per_frame = compute_pipeline.layout.descriptor_set_layouts.len();
         compute_pipeline
             .descriptor_sets
             .chunks(descriptor_sets_per_frame)
-            .nth(self.ring_slot)
+            .nth(self.slots.ring)
             .unwrap()
     }
 
@@ -2138,12 +6455,12 @@ impl Renderer {
         &self,
         handle: &PickingPipelineHandle,
     ) -> &[vk::DescriptorSet] {
-        let pipeline = self.pipelines.get_picking(handle);
+        let pipeline = self.gpu.pipelines.get_picking(handle);
         let descriptor_sets_per_frame = pipeline.layout.descriptor_set_layouts.len();
         pipeline
             .descriptor_sets
             .chunks(descriptor_sets_per_frame)
-            .nth(self.ring_slot)
+            .nth(self.slots.ring)
             .unwrap()
     }
 
@@ -2154,7 +6471,7 @@ impl Renderer {
         pending_compute: Vec<PendingComputeCommand>,
         gpu_update: impl FnOnce(&mut Gpu),
     ) -> Result<(), anyhow::Error> {
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, feature = "shader-compiler"))]
         {
             let mut graphics_indices: Vec<GraphicsPipelineIndex> = pending_draws
                 .iter()
@@ -2178,25 +6495,14 @@ impl Renderer {
             self.check_for_shader_recompile(&graphics_indices, &compute_indices)?;
         }
 
-        let command_buffer = self.command_buffers[self.flight_slot];
+        let command_buffer = self.command_buffers[self.slots.flight];
 
         // 1. Acquire swapchain image (can block on vsync)
-        let (image_index, swapchain_was_suboptimal_on_image_acquire) = unsafe {
-            match self.swapchain_device_ext.acquire_next_image(
-                self.swapchain,
-                u64::MAX,
-                self.image_available[self.ring_slot],
-                vk::Fence::null(),
-            ) {
-                Ok(tup) => tup,
-                Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
-                    return self.recreate_swapchain();
-                }
-                Err(other_error) => {
-                    return Err(other_error.into());
-                }
-            }
-        };
+        let (image_index, swapchain_was_suboptimal_on_image_acquire) =
+            match self.presenter.acquire(self.slots.ring)? {
+                Acquire::Image { index, suboptimal } => (index, suboptimal),
+                Acquire::OutOfDate => return self.recreate_swapchain(),
+            };
 
         self.total_frames += 1;
         let frame_value = self.total_frames as u64;
@@ -2204,12 +6510,71 @@ impl Renderer {
         // 2. CPU buffer writes BEFORE the timeline wait
         //    Safe because buffer[ring_slot] was last used by frame (total - PRE_WAIT_RING_LEN)
         //    and that frame's timeline value was waited for during frame (total - 1)
+        self.gpu.counter_buffers.begin_frame(self.slots.ring);
+        // read before gpu borrows the renderer's buffers
+        let display_luminance = self.display_luminance();
+        self.presenter.update_hdr_metadata(display_luminance);
         let mut gpu = Gpu {
-            ring_slot: self.ring_slot,
-            uniform_buffers: &mut self.uniform_buffers,
-            storage_buffers: &mut self.storage_buffers,
+            ring_slot: self.slots.ring,
+            uniform_buffers: &mut self.gpu.uniform_buffers,
+            storage_buffers: &mut self.gpu.storage_buffers,
+            counter_buffers: &self.gpu.counter_buffers,
+            pipelines: &mut self.gpu.pipelines,
+            storage_overflow: None,
+            audited_writes: (self.determinism_audit.is_some() || self.recorded_commands.is_some())
+                .then(Vec::new),
+            #[cfg(debug_assertions)]
+            addressed_storage: Default::default(),
         };
+        // the clock runs from the first frame, bound or not
+        let globals = self.frame_globals.tick(
+            std::time::Instant::now(),
+            (self.total_frames - 1) as u32,
+            Vec2::new(
+                self.render_extent.width as f32,
+                self.render_extent.height as f32,
+            ),
+            self.presenter.hdr_output,
+            display_luminance,
+        );
+        if let Some(frame_globals_buffer) = &mut self.frame_globals.buffer {
+            gpu.write_uniform(frame_globals_buffer, globals);
+        }
+        if let Some(split_screen_buffer) = &mut self.split_screen.buffer {
+            let views = SplitScreenViews::from_views(&self.split_screen.views);
+            gpu.write_uniform(split_screen_buffer, views);
+        }
         gpu_update(&mut gpu);
+        let audited_writes = gpu.audited_writes.take();
+        #[cfg(debug_assertions)]
+        let addressed_storage = gpu.addressed_storage.take();
+
+        if let Some(recorded_commands) = &mut self.recorded_commands {
+            recorded_commands.push(FrameLog::from_pending(
+                &self.gpu,
+                &pending_draws,
+                &pending_compute,
+                audited_writes.as_deref().unwrap_or_default(),
+            ));
+        }
+        if let Some(audited_writes) = audited_writes
+            && let Some(audit) = &mut self.determinism_audit
+        {
+            // the written ranges are in this ring slot's mapped buffers,
+            // which nothing has freed or rewritten since gpu_update
+            if let Err(error) = unsafe { audit.end_frame(audited_writes) } {
+                log::error!("stopping the determinism audit: {error}");
+                self.determinism_audit = None;
+            }
+        }
+        #[cfg(debug_assertions)]
+        self.warn_unwritten_buffers(&pending_draws, &pending_compute, &addressed_storage);
+        if let Some(dir) = self.pending_frame_dump.take()
+            && let Err(error) = self.dump_frame(&dir)
+        {
+            log::error!("frame dump to {} failed: {error}", dir.display());
+        }
+        self.write_uniform_rebinds();
 
         // 3. Wait until frame (N - MAX_FRAMES_IN_FLIGHT)'s graphics submit retires
         //    (command buffer reuse). Frames 1 and 2 wait on value 0, trivially satisfied.
@@ -2218,17 +6583,32 @@ impl Renderer {
         let wait_info = vk::SemaphoreWaitInfo::default()
             .semaphores(&semaphores)
             .values(&values);
-        unsafe { self.device.wait_semaphores(&wait_info, u64::MAX)? };
+        unsafe { self.gpu.device.wait_semaphores(&wait_info, u64::MAX)? };
 
-        // 3a. Read picking result from staging buffer (written 2 frames ago, now safe to read)
-        if let Some(picking) = &self.picking {
-            let id = unsafe { *picking.readback_mapped[self.flight_slot] };
-            self.last_picked_object_id = id;
+        // 3a. Read picking result from staging buffer (written 2 frames ago, now safe to read),
+        //     and deliver the other readbacks from that frame
+        if let Some(picked_object_id) = self.readbacks.after_wait(self.slots.flight) {
+            self.last_picked_object_id = picked_object_id;
+        }
+
+        // 3b. Read the pixel debugger's replay from the same frame
+        self.pixel_debug
+            .after_wait(&self.gpu.device, self.slots.flight);
+        if let Some((target, readback)) = self.readbacks.take_inspected() {
+            self.texture_inspector.deliver(target, readback);
         }
 
         // 4. Free egui textures (must be after the timeline wait)
+        #[cfg(feature = "editor")]
         if let Some(egui) = &mut self.egui {
-            egui.free_pending_textures(self.flight_slot);
+            egui.free_pending_textures(self.slots.flight);
+        }
+
+        // 4a. Free staging buffers from this slot's last texture updates
+        for (buffer, mut memory) in
+            std::mem::take(&mut self.texture_update_staging[self.slots.flight])
+        {
+            unsafe { self.gpu.allocator.destroy_buffer(buffer, &mut memory) };
         }
 
         // Determine if we should use pipelined async compute this frame.
@@ -2240,12 +6620,6 @@ impl Renderer {
         let compute_value = self.compute_frames + 1;
 
         if use_pipelined {
-            // --- PIPELINED: separate compute and graphics submissions ---
-            // Uses dedicated compute queue when available, otherwise same graphics queue.
-            // Two separate vkQueueSubmit calls give the driver freedom to overlap execution.
-            let compute_queue = self.compute_queue.unwrap_or(self.graphics_queue);
-            let compute_cb = self.compute_command_buffers[self.flight_slot];
-
             // Wait until this slot's previous compute submit retires (compute CB reuse).
             // compute_frames advances every frame once compute is active, so the slot's
             // last user signaled compute_value - MAX_FRAMES_IN_FLIGHT.
@@ -2254,27 +6628,28 @@ impl Renderer {
             let wait_info = vk::SemaphoreWaitInfo::default()
                 .semaphores(&semaphores)
                 .values(&values);
-            unsafe { self.device.wait_semaphores(&wait_info, u64::MAX)? };
+            unsafe { self.gpu.device.wait_semaphores(&wait_info, u64::MAX)? };
+        }
 
-            // Record compute command buffer
-            unsafe {
-                self.device
-                    .reset_command_buffer(compute_cb, Default::default())?;
-            }
-            self.record_compute_command_buffer(&pending_compute)?;
+        // 5. Record everything before submitting anything,
+        //    so a failed recording can still retire the frame cleanly
+        if let Err(error) = self.record_frame(
+            &pending_draws,
+            image_index,
+            picking_config.as_ref(),
+            &pending_compute,
+            use_pipelined,
+        ) {
+            self.abandon_frame(frame_value, compute_value)?;
+            return Err(error);
+        }
 
-            // Record graphics command buffer (skip compute section)
-            unsafe {
-                self.device
-                    .reset_command_buffer(command_buffer, Default::default())?;
-            }
-            self.record_command_buffer(
-                &pending_draws,
-                image_index,
-                picking_config.as_ref(),
-                &pending_compute,
-                ComputePlacement::SeparateCommandBuffer,
-            )?;
+        if use_pipelined {
+            // --- PIPELINED: separate compute and graphics submissions ---
+            // Uses dedicated compute queue when available, otherwise same graphics queue.
+            // Two separate vkQueueSubmit calls give the driver freedom to overlap execution.
+            let compute_queue = self.gpu.compute_queue.unwrap_or(self.gpu.graphics_queue);
+            let compute_cb = self.compute_command_buffers[self.slots.flight];
 
             // Submit compute: wait on the previous frame's compute, signal this frame's value
             let compute_waits = [vk::SemaphoreSubmitInfo::default()
@@ -2291,15 +6666,18 @@ impl Renderer {
                 .command_buffer_infos(&compute_cbs)
                 .signal_semaphore_infos(&compute_signals);
             unsafe {
-                self.device
-                    .queue_submit2(compute_queue, &[compute_submit], vk::Fence::null())?;
+                self.gpu.device.queue_submit2(
+                    compute_queue,
+                    &[compute_submit],
+                    vk::Fence::null(),
+                )?;
             }
 
             // Submit graphics: wait on image_available + previous frame's compute,
             // signal render_finished + this frame's timeline value
-            let gfx_waits = [
+            let mut gfx_waits = vec![
                 vk::SemaphoreSubmitInfo::default()
-                    .semaphore(self.image_available[self.ring_slot])
+                    .semaphore(self.presenter.image_available[self.slots.ring])
                     .stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT),
                 vk::SemaphoreSubmitInfo::default()
                     .semaphore(self.compute_timeline)
@@ -2312,9 +6690,10 @@ impl Renderer {
                             | vk::PipelineStageFlags2::COMPUTE_SHADER,
                     ),
             ];
+            gfx_waits.extend(self.take_external_waits());
             let gfx_signals = [
                 vk::SemaphoreSubmitInfo::default()
-                    .semaphore(self.render_finished[image_index as usize])
+                    .semaphore(self.presenter.render_finished[image_index as usize])
                     .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS),
                 vk::SemaphoreSubmitInfo::default()
                     .semaphore(self.frame_timeline)
@@ -2327,34 +6706,25 @@ impl Renderer {
                 .command_buffer_infos(&gfx_cbs)
                 .signal_semaphore_infos(&gfx_signals);
             unsafe {
-                self.device
-                    .queue_submit2(self.graphics_queue, &[gfx_submit], vk::Fence::null())?;
+                self.gpu.device.queue_submit2(
+                    self.gpu.graphics_queue,
+                    &[gfx_submit],
+                    vk::Fence::null(),
+                )?;
             }
 
             self.compute_frames += 1;
         } else {
             // --- NON-PIPELINED: compute + graphics in one command buffer ---
             // Compute runs first with barriers, then graphics reads results in same frame.
-            unsafe {
-                self.device
-                    .reset_command_buffer(command_buffer, Default::default())?;
-            }
-            self.record_command_buffer(
-                &pending_draws,
-                image_index,
-                picking_config.as_ref(),
-                &pending_compute,
-                ComputePlacement::BeforeGraphics,
-            )?;
-
             let submit_command_buffers =
                 [vk::CommandBufferSubmitInfo::default().command_buffer(command_buffer)];
 
             let image_available_wait = vk::SemaphoreSubmitInfo::default()
-                .semaphore(self.image_available[self.ring_slot])
+                .semaphore(self.presenter.image_available[self.slots.ring])
                 .stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT);
             let render_finished_signal = vk::SemaphoreSubmitInfo::default()
-                .semaphore(self.render_finished[image_index as usize])
+                .semaphore(self.presenter.render_finished[image_index as usize])
                 .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS);
             let frame_timeline_signal = vk::SemaphoreSubmitInfo::default()
                 .semaphore(self.frame_timeline)
@@ -2364,7 +6734,7 @@ impl Renderer {
             // When compute pipelines exist, add cross-frame synchronization:
             // - Wait on the previous frame's compute_timeline value (so reads see prior writes)
             // - Signal this frame's value (for the next frame to wait on)
-            let (wait_semaphores, signal_semaphores);
+            let (mut wait_semaphores, signal_semaphores);
             if self.has_compute_pipelines {
                 wait_semaphores = vec![
                     image_available_wait,
@@ -2392,14 +6762,15 @@ impl Renderer {
                 wait_semaphores = vec![image_available_wait];
                 signal_semaphores = vec![render_finished_signal, frame_timeline_signal];
             }
+            wait_semaphores.extend(self.take_external_waits());
 
             let submit_info = vk::SubmitInfo2::default()
                 .wait_semaphore_infos(&wait_semaphores)
                 .command_buffer_infos(&submit_command_buffers)
                 .signal_semaphore_infos(&signal_semaphores);
             unsafe {
-                self.device.queue_submit2(
-                    self.graphics_queue,
+                self.gpu.device.queue_submit2(
+                    self.gpu.graphics_queue,
                     &[submit_info],
                     vk::Fence::null(),
                 )?;
@@ -2410,108 +6781,260 @@ impl Renderer {
             }
         }
 
-        // 6. Advance both frame counters BEFORE present
-        //    This ensures that if present triggers swapchain recreation (early return),
-        //    the next frame won't reuse the same ring slot whose semaphores
-        //    are still signaled from this frame's submit.
-        self.flight_slot = (self.flight_slot + 1) % MAX_FRAMES_IN_FLIGHT;
-        self.ring_slot = (self.ring_slot + 1) % PRE_WAIT_RING_LEN;
+        // 6. Advance both frame counters and present
+        match swapchain::end_frame(
+            &mut self.presenter,
+            &mut self.slots,
+            image_index,
+            swapchain_was_suboptimal_on_image_acquire,
+        )? {
+            FrameEnd::Presented => {}
+            FrameEnd::RecreateSwapchain => return self.recreate_swapchain(),
+        }
+
+        Ok(())
+    }
+
+    /// Resets and records this frame's command buffers: graphics, and compute
+    /// too when it's pipelined into its own submit
+    fn record_frame(
+        &mut self,
+        pending_draws: &[PendingDrawCommand],
+        image_index: u32,
+        picking_config: Option<&PickingDrawConfig>,
+        pending_compute: &[PendingComputeCommand],
+        use_pipelined: bool,
+    ) -> Result<(), anyhow::Error> {
+        let command_buffer = self.command_buffers[self.slots.flight];
+
+        let compute_placement = if use_pipelined {
+            let compute_cb = self.compute_command_buffers[self.slots.flight];
+            unsafe {
+                self.gpu
+                    .device
+                    .reset_command_buffer(compute_cb, Default::default())?;
+            }
+            self.record_compute_command_buffer(pending_compute)?;
+            // the graphics command buffer skips its compute section
+            ComputePlacement::SeparateCommandBuffer
+        } else {
+            ComputePlacement::BeforeGraphics
+        };
+
+        unsafe {
+            self.gpu
+                .device
+                .reset_command_buffer(command_buffer, Default::default())?;
+        }
+        self.record_command_buffer(
+            pending_draws,
+            image_index,
+            picking_config,
+            pending_compute,
+            compute_placement,
+        )
+    }
+
+    /// Retires a frame whose recording failed after its image was acquired.
+    /// An empty submit consumes the acquire semaphore and signals the frame's
+    /// timeline values, so later frames neither wait forever on them nor reuse
+    /// a signaled semaphore; recreating the swapchain releases the image,
+    /// which can't be presented.
+    fn abandon_frame(&mut self, frame_value: u64, compute_value: u64) -> Result<(), anyhow::Error> {
+        let waits = [vk::SemaphoreSubmitInfo::default()
+            .semaphore(self.presenter.image_available[self.slots.ring])
+            .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)];
+        let mut signals = vec![
+            vk::SemaphoreSubmitInfo::default()
+                .semaphore(self.frame_timeline)
+                .value(frame_value)
+                .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS),
+        ];
+        // every frame signals compute once there are compute pipelines
+        if self.has_compute_pipelines {
+            signals.push(
+                vk::SemaphoreSubmitInfo::default()
+                    .semaphore(self.compute_timeline)
+                    .value(compute_value)
+                    .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS),
+            );
+        }
+
+        let submit_info = vk::SubmitInfo2::default()
+            .wait_semaphore_infos(&waits)
+            .signal_semaphore_infos(&signals);
+        unsafe {
+            self.gpu.device.queue_submit2(
+                self.gpu.graphics_queue,
+                &[submit_info],
+                vk::Fence::null(),
+            )?;
+        }
+
+        if self.has_compute_pipelines {
+            self.compute_frames += 1;
+        }
+        self.slots.advance();
+
+        self.recreate_swapchain()
+    }
 
-        let swapchains = [self.swapchain];
-        let image_indices = [image_index];
-        let present_wait = [self.render_finished[image_index as usize]];
-        let present_info = vk::PresentInfoKHR::default()
-            .wait_semaphores(&present_wait)
-            .swapchains(&swapchains)
-            .image_indices(&image_indices);
-        unsafe {
-            match self
-                .swapchain_device_ext
-                .queue_present(self.presentation_queue, &present_info)
-            {
-                Ok(false) => {
-                    // not suboptimal, aka fine, or optimal i guess
-                }
-                Ok(true) | Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
-                    // suboptimal (vk::Result::SUBOPTIMAL_KHR) or out of date
-                    return self.recreate_swapchain();
-                }
-                Err(other_error) => {
-                    return Err(other_error.into());
-                }
-            }
-        }
+    /// The resources the game hasn't dropped; see [`LeakReport`].
+    /// [`Self::drain_gpu`] logs it at shutdown.
+    pub fn leak_report(&self) -> LeakReport {
+        let pipelines = self
+            .gpu
+            .pipelines
+            .iter()
+            .map(|pipeline| {
+                debug::clean_shader_name(pipeline.shader.source_file_name()).to_string()
+            })
+            .collect();
+        let compute_pipelines = self
+            .gpu
+            .compute_pipelines
+            .iter()
+            .map(|pipeline| {
+                debug::clean_shader_name(pipeline.shader.source_file_name()).to_string()
+            })
+            .collect();
+        // aliases belong to the renderer's own targets, or to a storage or feedback texture
+        let textures = self
+            .gpu
+            .textures
+            .iter()
+            .filter(|texture| !matches!(texture.image_ownership, texture::ImageOwnership::Aliased))
+            .map(|texture| texture.source_file_name.clone())
+            .collect();
 
-        if swapchain_was_suboptimal_on_image_acquire {
-            return self.recreate_swapchain();
+        LeakReport {
+            pipelines,
+            compute_pipelines,
+            textures,
+            uniform_buffers: self.gpu.uniform_buffers.live_count(),
+            storage_buffers: self.gpu.storage_buffers.live_count(),
+            counter_buffers: self.gpu.counter_buffers.live_count(),
         }
-
-        Ok(())
     }
 
+    /// Waits for the GPU to finish all submitted work, before shutdown.
+    ///
+    /// Safe to call after a draw error, which retires its frame. A lost device
+    /// is only logged, so the error that lost it is the one that's reported.
     pub fn drain_gpu(&mut self) -> Result<(), anyhow::Error> {
-        unsafe { self.device.device_wait_idle()? };
+        match unsafe { self.gpu.device.device_wait_idle() } {
+            Ok(()) => {}
+            Err(vk::Result::ERROR_DEVICE_LOST) => {
+                log::error!("device lost before shutdown");
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        let leak_report = self.leak_report();
+        if !leak_report.is_empty() {
+            log::info!("still alive at shutdown:\n{leak_report}");
+        }
+
         Ok(())
     }
 
     // to be called on window resize
     pub fn recreate_swapchain(&mut self) -> Result<(), anyhow::Error> {
-        unsafe { self.device.device_wait_idle()? }
+        unsafe { self.gpu.device.device_wait_idle()? }
 
         // NOTE: the timeline semaphores are monotonic and must NOT be recreated here —
         // resetting their values to 0 would deadlock the next frame's waits.
 
+        // the render targets are recreated below, and their old handles may be reused
+        #[cfg(debug_assertions)]
+        self.layout_tracker.borrow_mut().clear();
+
         self.cleanup_swapchain();
+        if let Some(present_wait) = &mut self.presenter.present_wait {
+            present_wait.swapchain_recreated();
+        }
         unsafe {
-            self.device.destroy_image_view(self.depth_image_view, None);
-            self.allocator
+            self.gpu
+                .device
+                .destroy_image_view(self.depth_image_view, None);
+            self.gpu
+                .allocator
                 .destroy_image(self.depth_image, &mut self.depth_image_memory);
         }
         unsafe {
-            self.device.destroy_image_view(self.color_image_view, None);
-            self.allocator
+            self.gpu
+                .device
+                .destroy_image_view(self.color_image_view, None);
+            self.gpu
+                .allocator
                 .destroy_image(self.color_image, &mut self.color_image_memory);
         }
         unsafe {
-            for i in 0..MAX_FRAMES_IN_FLIGHT {
-                self.device
+            for i in 0..PRE_WAIT_RING_LEN {
+                self.gpu
+                    .device
                     .destroy_image_view(self.resolve_image_views[i], None);
-                self.allocator
+                self.gpu
+                    .allocator
                     .destroy_image(self.resolve_images[i], &mut self.resolve_image_memories[i]);
             }
         }
 
         let CreatedSwapchain {
             swapchain,
-            image_format,
             image_extent,
+            ..
         } = create_swapchain(
-            &self.window,
-            &self.swapchain_device_ext,
-            &self.surface_ext,
-            self.surface,
-            self.physical_device,
-            &self.queue_family_indices,
+            &self.presenter.window,
+            &self.presenter.swapchain_device_ext,
+            &self.presenter.surface_ext,
+            self.presenter.surface,
+            self.gpu.physical_device,
+            &self.gpu.queue_family_indices,
+            self.presenter.preferred_present_mode,
+            // keeps the format pipelines were created with
+            SwapchainFormat::Pinned(self.presenter.surface_format, self.presenter.hdr_output),
         )?;
-        self.swapchain = swapchain;
-        self.image_format = image_format;
-        self.image_extent = image_extent;
+        self.presenter.swapchain = swapchain;
+        self.presenter.image_extent = image_extent;
+        // set on the new swapchain with the next frame
+        self.presenter.hdr_metadata_luminance = None;
 
         // Recalculate render extent
-        self.render_extent = calculate_render_extent(image_extent, self.render_scale);
+        self.render_extent = calculate_render_extent(
+            image_extent,
+            self.render_scale,
+            self.view_mode,
+            self.presentation,
+        );
+        self.present_rect =
+            calculate_present_rect(image_extent, self.render_extent, self.presentation);
+        self.render_scale = presented_render_scale(
+            self.render_scale,
+            self.render_extent,
+            self.present_rect,
+            self.presentation,
+        );
 
-        self.swapchain_images =
-            unsafe { self.swapchain_device_ext.get_swapchain_images(swapchain)? };
+        self.presenter.swapchain_images = unsafe {
+            self.presenter
+                .swapchain_device_ext
+                .get_swapchain_images(swapchain)?
+        };
 
-        self.swapchain_image_views =
-            create_swapchain_image_views(&self.device, self.image_format, &self.swapchain_images)?;
+        self.presenter.swapchain_image_views = create_swapchain_image_views(
+            &self.gpu.device,
+            self.presenter.image_format,
+            &self.presenter.swapchain_images,
+        )?;
 
-        // Recreate resolve images at render_extent (one per frame-in-flight)
+        // Recreate resolve images at render_extent (one per ring slot)
         let (resolve_images, resolve_image_memories, resolve_image_views) = create_resolve_images(
-            &self.allocator,
-            &self.device,
+            &self.gpu.allocator,
+            &self.gpu.device,
             self.render_extent,
-            self.image_format,
+            self.presenter.image_format,
+            self.view_mode,
         )?;
         self.resolve_images = resolve_images;
         self.resolve_image_memories = resolve_image_memories;
@@ -2519,50 +7042,333 @@ impl Renderer {
 
         // Depth and color at render_extent
         let (depth_image, depth_image_memory, depth_image_view) = create_depth_buffer_image(
-            &self.allocator,
-            &self.instance,
-            &self.device,
-            self.physical_device,
+            &self.gpu.allocator,
+            &self.gpu.device,
             self.command_pool,
-            self.graphics_queue,
+            self.gpu.graphics_queue,
+            self.depth_format,
             self.render_extent,
             self.msaa_samples,
+            self.view_mode,
         )?;
         self.depth_image = depth_image;
         self.depth_image_memory = depth_image_memory;
         self.depth_image_view = depth_image_view;
 
         let (color_image, color_image_memory, color_image_view) = create_color_image(
-            &self.allocator,
-            &self.device,
+            &self.gpu.allocator,
+            &self.gpu.device,
             self.render_extent,
-            self.image_format,
+            self.presenter.image_format,
             self.msaa_samples,
+            self.view_mode,
         )?;
         self.color_image = color_image;
         self.color_image_memory = color_image_memory;
         self.color_image_view = color_image_view;
 
         if let Some(picking) = &mut self.picking {
-            picking.recreate_images(&self.allocator, &self.device, self.render_extent)?;
+            picking.recreate_images(&self.gpu.allocator, &self.gpu.device, self.render_extent)?;
+        }
+
+        if let Some(post_process) = &mut self.post_process {
+            post_process.recreate_images(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                self.render_extent,
+                self.presenter.image_format,
+            )?;
+        }
+        if let Some(history) = self.taa_history.take() {
+            history.destroy(&self.gpu.allocator);
+            self.taa_history = Some(TaaHistory::init(
+                &self.gpu.allocator,
+                self.render_extent,
+                self.presenter.image_format,
+            )?);
+        }
+        if let Some(velocity) = self.velocity.take() {
+            velocity.destroy(&self.gpu.allocator, &self.gpu.device);
+            self.velocity = Some(VelocityTargets::init(
+                &self.gpu.allocator,
+                &self.gpu.device,
+                self.render_extent,
+                self.msaa_samples,
+            )?);
+        }
+        self.recreate_scene_color_views()?;
+        self.write_scene_color_descriptors();
+
+        Ok(())
+    }
+
+    /// Re-alias the scene color, TAA history, and velocity textures to their recreated images
+    fn recreate_scene_color_views(&mut self) -> anyhow::Result<()> {
+        for &index in &self.scene_color_texture_indices {
+            let Some(texture) = self.gpu.textures.get_mut_by_index(index) else {
+                continue;
+            };
+
+            unsafe { self.gpu.device.destroy_image_view(texture.image_view, None) };
+            texture.image_view = create_image_view(
+                &self.gpu.device,
+                self.resolve_images[0],
+                self.presenter.image_format,
+                vk::ImageAspectFlags::COLOR,
+                1,
+            )?;
+            texture.image = self.resolve_images[0];
+            texture.extent = self.render_extent;
+        }
+
+        if let Some(history) = &self.taa_history {
+            for &index in &self.taa_history_texture_indices {
+                let Some(texture) = self.gpu.textures.get_mut_by_index(index) else {
+                    continue;
+                };
+
+                unsafe { self.gpu.device.destroy_image_view(texture.image_view, None) };
+                texture.image_view = history.create_view(&self.gpu.device, texture.format)?;
+                texture.image = history.image;
+                texture.extent = self.render_extent;
+            }
+        }
+
+        if let Some(velocity) = &self.velocity {
+            for &index in &self.velocity_texture_indices {
+                let Some(texture) = self.gpu.textures.get_mut_by_index(index) else {
+                    continue;
+                };
+
+                unsafe { self.gpu.device.destroy_image_view(texture.image_view, None) };
+                texture.image_view = velocity.create_view(&self.gpu.device)?;
+                texture.image = velocity.image;
+                texture.extent = self.render_extent;
+            }
         }
 
         Ok(())
     }
 
+    /// Point every descriptor sampling the scene color, TAA history, or velocity at their current images
+    fn write_scene_color_descriptors(&self) {
+        for pipeline in self.gpu.pipelines.iter() {
+            let sets_per_frame = pipeline.layout.descriptor_set_layouts.len();
+
+            for scene_color_write in &pipeline.scene_color_writes {
+                let Some(texture) = self
+                    .gpu
+                    .textures
+                    .get_by_index(scene_color_write.texture_index)
+                else {
+                    continue;
+                };
+
+                let scene_color = self
+                    .scene_color_texture_indices
+                    .contains(&scene_color_write.texture_index);
+                for frame in 0..PRE_WAIT_RING_LEN {
+                    // the history and velocity have one image, which their aliases view directly
+                    let image_view = if scene_color {
+                        self.resolve_image_views[frame]
+                    } else {
+                        texture.image_view
+                    };
+                    let image_info = [vk::DescriptorImageInfo::default()
+                        .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                        .image_view(image_view)
+                        .sampler(texture.sampler)];
+                    let write = vk::WriteDescriptorSet::default()
+                        .dst_set(
+                            pipeline.descriptor_sets
+                                [frame * sets_per_frame + scene_color_write.set],
+                        )
+                        .dst_binding(scene_color_write.binding)
+                        .dst_array_element(0)
+                        .descriptor_type(scene_color_write.descriptor_type)
+                        .image_info(&image_info);
+
+                    unsafe { self.gpu.device.update_descriptor_sets(&[write], &[]) };
+                }
+            }
+        }
+    }
+
     fn cleanup_swapchain(&mut self) {
         unsafe {
-            for image_view in &self.swapchain_image_views {
-                self.device.destroy_image_view(*image_view, None);
+            for image_view in &self.presenter.swapchain_image_views {
+                self.gpu.device.destroy_image_view(*image_view, None);
             }
 
             // NOTE this also frees the images
-            self.swapchain_device_ext
-                .destroy_swapchain(self.swapchain, None);
+            self.presenter
+                .swapchain_device_ext
+                .destroy_swapchain(self.presenter.swapchain, None);
         }
     }
 
-    #[cfg(debug_assertions)]
+    /// Start hashing every buffer the game writes through [`Gpu`] each frame,
+    /// saving the hashes to `path` for [`Self::compare_determinism_hashes`].
+    /// See the [`determinism`] module.
+    pub fn record_determinism_hashes(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let file = std::fs::File::create(path)?;
+        self.determinism_audit = Some(DeterminismAudit::record(Box::new(std::io::BufWriter::new(
+            file,
+        ))));
+        Ok(())
+    }
+
+    /// Start checking the buffers the game writes each frame against a
+    /// recording from [`Self::record_determinism_hashes`]; the first frame
+    /// that differs is logged, and every difference is kept for
+    /// [`Self::determinism_divergences`]
+    pub fn compare_determinism_hashes(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let recording = std::fs::read_to_string(path)?;
+        self.determinism_audit = Some(DeterminismAudit::compare(&recording)?);
+        Ok(())
+    }
+
+    /// the differences found so far by a running comparison
+    pub fn determinism_divergences(&self) -> &[Divergence] {
+        self.determinism_audit
+            .as_ref()
+            .map(DeterminismAudit::divergences)
+            .unwrap_or_default()
+    }
+
+    /// End a recording or comparison, returning the comparison's differences
+    pub fn stop_determinism_audit(&mut self) -> anyhow::Result<Vec<Divergence>> {
+        match self.determinism_audit.take() {
+            Some(audit) => audit.finish(),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Start logging every frame's draws, dispatches, and buffer writes as
+    /// they're submitted, for tests to assert on; see [`command_log`]
+    pub fn record_commands(&mut self) {
+        self.recorded_commands.get_or_insert_default();
+    }
+
+    /// The frames logged since recording started or the last take;
+    /// recording continues
+    pub fn take_recorded_commands(&mut self) -> Vec<FrameLog> {
+        self.recorded_commands
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// End recording, returning the frames not yet taken
+    pub fn stop_recording_commands(&mut self) -> Vec<FrameLog> {
+        self.recorded_commands.take().unwrap_or_default()
+    }
+
+    /// Save the next frame's buffers and offscreen targets to `dir`, which is
+    /// created if needed: a JSON file per uniform and storage buffer, as the
+    /// game left it, plus PNGs of the scene and each 8-bit RGBA feedback
+    /// texture, which arrive a couple of frames later
+    pub fn dump_next_frame(&mut self, dir: impl Into<PathBuf>) {
+        self.pending_frame_dump = Some(dir.into());
+    }
+
+    /// Write the uniform buffer's contents in frame dumps through `T`'s
+    /// `Serialize` impl, instead of as hex
+    pub fn serialize_uniform_in_dumps<T: GPUWrite + Serialize>(
+        &mut self,
+        handle: &UniformBufferHandle<T>,
+    ) {
+        self.frame_dump_formats.insert(
+            AuditedBuffer::Uniform(handle.index()),
+            DumpFormat::value::<T>(),
+        );
+    }
+
+    /// Write the storage buffer's elements in frame dumps through `T`'s
+    /// `Serialize` impl, instead of as hex
+    pub fn serialize_storage_in_dumps<T: GPUWrite + Serialize>(
+        &mut self,
+        handle: &StorageBufferHandle<T>,
+    ) {
+        self.frame_dump_formats.insert(
+            AuditedBuffer::Storage(handle.index()),
+            DumpFormat::elements::<T>(handle.len()),
+        );
+    }
+
+    /// Writes this ring slot's buffers, after the game's writes, and requests
+    /// the frame's images
+    fn dump_frame(&mut self, dir: &Path) -> anyhow::Result<()> {
+        std::fs::create_dir_all(dir)?;
+
+        let write_buffer = |buffer: AuditedBuffer,
+                            allocation: &vk_mem::Allocation,
+                            mapped_mem: *mut std::ffi::c_void| {
+            let size = self.gpu.allocator.get_allocation_info(allocation).size as usize;
+            // the slot's last frame has retired, and nothing writes it until
+            // the game's next gpu_update
+            let bytes = unsafe { std::slice::from_raw_parts(mapped_mem as *const u8, size) };
+            let format = self.frame_dump_formats.get(&buffer).copied();
+            frame_dump::write_buffer(dir, buffer, bytes, format)
+        };
+        for (index, uniform_buffer) in self.gpu.uniform_buffers.iter_for_frame(self.slots.ring) {
+            write_buffer(
+                AuditedBuffer::Uniform(index),
+                &uniform_buffer.allocation,
+                uniform_buffer.mapped_mem,
+            )?;
+        }
+        for (index, storage_buffer) in self.gpu.storage_buffers.iter_for_frame(self.slots.ring) {
+            write_buffer(
+                AuditedBuffer::Storage(index),
+                &storage_buffer.allocation,
+                storage_buffer.mapped_mem,
+            )?;
+        }
+
+        let scene_path = dir.join("scene.png");
+        self.readbacks.request(
+            ReadbackRequest::Screenshot,
+            Delivery::Callback(Box::new(move |readback| {
+                frame_dump::save_png(&scene_path, readback)
+            })),
+        );
+        for (index, feedback) in self.feedback_textures.iter().enumerate() {
+            if !matches!(
+                feedback.format,
+                vk::Format::R8G8B8A8_UNORM
+                    | vk::Format::R8G8B8A8_SRGB
+                    | vk::Format::B8G8R8A8_UNORM
+                    | vk::Format::B8G8R8A8_SRGB
+            ) {
+                log::warn!("frame dump: skipping feedback texture {index}, which isn't 8-bit RGBA");
+                continue;
+            }
+            let path = dir.join(format!("feedback_{index}.png"));
+            self.readbacks.request(
+                ReadbackRequest::FeedbackTexture { index },
+                Delivery::Callback(Box::new(move |readback| {
+                    frame_dump::save_png(&path, readback)
+                })),
+            );
+        }
+
+        log::info!(
+            "frame dump: wrote frame {}'s buffers to {}",
+            self.total_frames,
+            dir.display()
+        );
+        Ok(())
+    }
+
+    /// Recompile the shaders of the next frame that draws, whether or not
+    /// their files changed; for retrying after a failed reload
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
+    pub fn request_shader_reload(&mut self) {
+        self.shader_reload_requested = true;
+    }
+
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
     fn check_for_shader_recompile(
         &mut self,
         graphics_pipeline_indices: &[GraphicsPipelineIndex],
@@ -2579,14 +7385,16 @@ impl Renderer {
             }
 
             unsafe {
-                self.device.destroy_pipeline(*old_pipeline, None);
-                self.device
+                self.gpu.device.destroy_pipeline(*old_pipeline, None);
+                self.gpu
+                    .device
                     .destroy_pipeline_layout(*old_pipeline_layout, None);
             }
 
             for &desc_set_layout in old_descriptor_set_layouts {
                 unsafe {
-                    self.device
+                    self.gpu
+                        .device
                         .destroy_descriptor_set_layout(desc_set_layout, None);
                 }
             }
@@ -2600,7 +7408,11 @@ impl Renderer {
 
         // recompile shaders if necessary
         let edit_events = self.shader_changes.events()?;
-        if !edit_events.is_empty() {
+        // a requested reload waits for a frame that draws something to reload
+        let reload_requested = self.shader_reload_requested
+            && !(graphics_pipeline_indices.is_empty() && compute_pipeline_indices.is_empty());
+        if !edit_events.is_empty() || reload_requested {
+            self.shader_reload_requested = false;
             info!("recompiling shaders...");
             for &graphics_index in graphics_pipeline_indices {
                 self.try_shader_recompile(graphics_index)?;
@@ -2614,14 +7426,14 @@ impl Renderer {
     }
 
     // shader hot reload
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
     fn try_shader_recompile(
         &mut self,
         pipeline_index: GraphicsPipelineIndex,
     ) -> Result<(), anyhow::Error> {
         let mut tmp_pipeline_layout = match ShaderPipelineLayout::create_from_atlas(
-            &self.device,
-            &*self.pipelines.get_by_index(pipeline_index).shader,
+            &self.gpu.device,
+            &*self.gpu.pipelines.get_by_index(pipeline_index).shader,
         ) {
             Ok(shaders) => shaders,
             Err(e) => {
@@ -2630,7 +7442,12 @@ impl Renderer {
             }
         };
 
-        let render_pipeline_mut = self.pipelines.get_mut_by_index(pipeline_index);
+        let original = self.gpu.pipelines.get_by_index(pipeline_index);
+        let (color_formats, depth_format, samples, view_mask) = self.pipeline_attachment_formats(
+            original.render_target,
+            original.post_process || original.ui_layer,
+        );
+        let render_pipeline_mut = self.gpu.pipelines.get_mut_by_index(pipeline_index);
 
         std::mem::swap(&mut tmp_pipeline_layout, &mut render_pipeline_mut.layout);
 
@@ -2650,32 +7467,77 @@ impl Renderer {
         // default — otherwise a reloaded shader silently reverts to
         // alpha-blend / back-cull / depth-less
         let raster_state = render_pipeline_mut.raster_state;
+        let has_depth_prepass = render_pipeline_mut.depth_prepass.is_some();
+        let writes_velocity = render_pipeline_mut.velocity;
+        let dynamic_shading_rate = render_pipeline_mut.shading_rate.is_variable();
+
+        // prepasses and variants share the layout swapped out above;
+        // only their pipelines retire
+        let retired_pipelines = render_pipeline_mut.depth_prepass.into_iter().chain(
+            render_pipeline_mut
+                .variants
+                .iter()
+                .flat_map(|variant| std::iter::once(variant.pipeline).chain(variant.depth_prepass)),
+        );
+        for pipeline in retired_pipelines {
+            self.old_pipelines.push((
+                self.total_frames,
+                pipeline,
+                vk::PipelineLayout::null(),
+                vec![],
+            ));
+        }
 
-        render_pipeline_mut.pipeline = create_graphics_pipeline(
-            &self.device,
-            self.image_format,
-            Some(self.depth_format),
-            self.msaa_samples,
+        (
+            render_pipeline_mut.pipeline,
+            render_pipeline_mut.depth_prepass,
+        ) = create_graphics_pipelines(
+            &self.gpu.device,
+            &color_formats,
+            writes_velocity,
+            depth_format,
+            samples,
+            view_mask,
             &render_pipeline_mut.layout,
-            &render_pipeline_mut.shader.vertex_binding_descriptions(),
-            &render_pipeline_mut.shader.vertex_attribute_descriptions(),
+            &*render_pipeline_mut.shader,
             &raster_state,
+            has_depth_prepass,
+            dynamic_shading_rate,
         )?;
 
+        for variant in &mut render_pipeline_mut.variants {
+            (variant.pipeline, variant.depth_prepass) = create_graphics_pipelines(
+                &self.gpu.device,
+                &color_formats,
+                writes_velocity,
+                depth_format,
+                samples,
+                view_mask,
+                &render_pipeline_mut.layout,
+                &*render_pipeline_mut.shader,
+                &variant.raster_state,
+                has_depth_prepass,
+                dynamic_shading_rate,
+            )?;
+        }
+
         info!("finished recompiling shaders");
 
         Ok(())
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
     fn try_compute_shader_recompile(
         &mut self,
         compute_pipeline_index: ComputePipelineIndex,
     ) -> Result<(), anyhow::Error> {
-        let compute_pipeline = self.compute_pipelines.get_by_index(compute_pipeline_index);
+        let compute_pipeline = self
+            .gpu
+            .compute_pipelines
+            .get_by_index(compute_pipeline_index);
 
         let mut tmp_layout = match ComputeShaderPipelineLayout::create_from_atlas(
-            &self.device,
+            &self.gpu.device,
             &*compute_pipeline.shader,
         ) {
             Ok(layout) => layout,
@@ -2686,6 +7548,7 @@ impl Renderer {
         };
 
         let compute_pipeline_mut = self
+            .gpu
             .compute_pipelines
             .get_mut_by_index(compute_pipeline_index);
 
@@ -2708,7 +7571,8 @@ impl Renderer {
             let shader_module_create_info = vk::ShaderModuleCreateInfo::default()
                 .code(&compute_pipeline_mut.layout.compute_shader.spv_bytes);
             unsafe {
-                self.device
+                self.gpu
+                    .device
                     .create_shader_module(&shader_module_create_info, None)?
             }
         };
@@ -2723,7 +7587,7 @@ impl Renderer {
             .layout(compute_pipeline_mut.layout.pipeline_layout);
 
         compute_pipeline_mut.pipeline = unsafe {
-            self.device.create_compute_pipelines(
+            self.gpu.device.create_compute_pipelines(
                 vk::PipelineCache::null(),
                 &[compute_pipeline_create_info],
                 None,
@@ -2732,7 +7596,7 @@ impl Renderer {
         .map_err(|(_pipelines, err)| err)?[0];
 
         unsafe {
-            self.device.destroy_shader_module(shader_module, None);
+            self.gpu.device.destroy_shader_module(shader_module, None);
         }
 
         let name = compute_pipeline_mut.shader.source_file_name();
@@ -2744,15 +7608,35 @@ impl Renderer {
     pub fn on_resize(&mut self) -> anyhow::Result<()> {
         self.recreate_swapchain()?;
 
-        let (width, height) = (self.image_extent.width, self.image_extent.height);
-        self.aspect_ratio = width as f32 / height as f32;
+        let (width, height) = (
+            self.presenter.image_extent.width,
+            self.presenter.image_extent.height,
+        );
+        self.aspect_ratio = view_aspect_ratio(width, height, self.view_mode, self.presentation);
         self.width = width as f32;
         self.height = height as f32;
 
         Ok(())
     }
 
+    /// the window's size in window coordinates (the space of mouse events)
+    pub(crate) fn window_size(&self) -> (u32, u32) {
+        self.presenter.window.size()
+    }
+
+    /// whether the egui overlay is drawn over the swapchain image
+    #[cfg(feature = "editor")]
+    fn egui_enabled(&self) -> bool {
+        self.egui.is_some()
+    }
+
+    #[cfg(not(feature = "editor"))]
+    fn egui_enabled(&self) -> bool {
+        false
+    }
+
     /// Get mutable access to the egui integration for event handling
+    #[cfg(feature = "editor")]
     pub fn egui(&mut self) -> Option<&mut EguiIntegration> {
         self.egui.as_mut()
     }
@@ -2760,6 +7644,7 @@ impl Renderer {
     /// Begin the egui frame early, before command buffer recording.
     /// This allows games to build egui UI before draw_frame is called.
     /// Idempotent: safe to call multiple times per frame.
+    #[cfg(feature = "editor")]
     pub fn begin_egui_frame(&mut self) {
         if let Some(egui) = &mut self.egui {
             let screen_size = [self.width, self.height];
@@ -2769,115 +7654,237 @@ impl Renderer {
 
     /// Get a clone of the egui context for building UI.
     /// Returns None if egui is disabled.
+    #[cfg(feature = "editor")]
     pub fn egui_context(&self) -> Option<::egui::Context> {
         self.egui.as_ref().map(|e| e.ctx.clone())
     }
+
+    /// What the editor's grid and gizmos draw into the next frame's main pass
+    #[cfg(feature = "editor")]
+    pub(crate) fn scene_overlay(&mut self) -> anyhow::Result<&mut SceneOverlay> {
+        if self.scene_overlay.is_none() {
+            self.scene_overlay = Some(SceneOverlay::new(self)?);
+        }
+        Ok(self.scene_overlay.as_mut().unwrap())
+    }
 }
 
 impl Drop for Renderer {
     fn drop(&mut self) {
         // this is necessary to avoid validation errors after a panic
-        let _ = unsafe { self.device.device_wait_idle() };
+        let _ = unsafe { self.gpu.device.device_wait_idle() };
 
         unsafe {
-            for semaphore in &self.render_finished {
-                self.device.destroy_semaphore(*semaphore, None);
+            for semaphore in &self.presenter.render_finished {
+                self.gpu.device.destroy_semaphore(*semaphore, None);
             }
-            for semaphore in &self.image_available {
-                self.device.destroy_semaphore(*semaphore, None);
+            for semaphore in &self.presenter.image_available {
+                self.gpu.device.destroy_semaphore(*semaphore, None);
             }
-            self.device.destroy_semaphore(self.frame_timeline, None);
-            self.device.destroy_semaphore(self.compute_timeline, None);
-
-            self.device.destroy_command_pool(self.command_pool, None);
-
-            self.device.destroy_image_view(self.depth_image_view, None);
-            self.allocator
+            self.gpu.device.destroy_semaphore(self.frame_timeline, None);
+            for semaphore in &self.gpu.external_semaphores {
+                self.gpu.device.destroy_semaphore(*semaphore, None);
+            }
+            self.gpu
+                .device
+                .destroy_semaphore(self.compute_timeline, None);
+
+            self.gpu
+                .device
+                .destroy_command_pool(self.command_pool, None);
+
+            self.gpu
+                .device
+                .destroy_image_view(self.depth_image_view, None);
+            self.gpu
+                .allocator
                 .destroy_image(self.depth_image, &mut self.depth_image_memory);
 
-            self.device.destroy_image_view(self.color_image_view, None);
-            self.allocator
+            self.gpu
+                .device
+                .destroy_image_view(self.color_image_view, None);
+            self.gpu
+                .allocator
                 .destroy_image(self.color_image, &mut self.color_image_memory);
 
-            for i in 0..MAX_FRAMES_IN_FLIGHT {
-                self.device
+            for i in 0..PRE_WAIT_RING_LEN {
+                self.gpu
+                    .device
                     .destroy_image_view(self.resolve_image_views[i], None);
-                self.allocator
+                self.gpu
+                    .allocator
                     .destroy_image(self.resolve_images[i], &mut self.resolve_image_memories[i]);
             }
 
-            #[cfg(debug_assertions)]
+            #[cfg(all(debug_assertions, feature = "shader-compiler"))]
             for (_frame, old_pipeline, old_pipeline_layout, old_descriptor_set_layouts) in
                 &self.old_pipelines
             {
-                self.device.destroy_pipeline(*old_pipeline, None);
-                self.device
+                self.gpu.device.destroy_pipeline(*old_pipeline, None);
+                self.gpu
+                    .device
                     .destroy_pipeline_layout(*old_pipeline_layout, None);
 
                 for &desc_set_layout in old_descriptor_set_layouts {
-                    self.device
+                    self.gpu
+                        .device
                         .destroy_descriptor_set_layout(desc_set_layout, None);
                 }
             }
 
             if let Some(picking) = self.picking.take() {
-                picking.destroy(&self.allocator, &self.device);
+                picking.destroy(&self.gpu.allocator, &self.gpu.device);
+            }
+
+            self.readbacks.destroy(&self.gpu.allocator);
+
+            self.pixel_debug
+                .destroy(&self.gpu.allocator, &self.gpu.device);
+
+            if let Some(post_process) = self.post_process.take() {
+                post_process.destroy(&self.gpu.allocator, &self.gpu.device);
+            }
+            if let Some(rcas_upscale) = self.rcas_upscale.take() {
+                rcas_upscale.destroy(&self.gpu.device);
+            }
+            if let Some(history) = self.taa_history.take() {
+                history.destroy(&self.gpu.allocator);
+            }
+            if let Some(velocity) = self.velocity.take() {
+                velocity.destroy(&self.gpu.allocator, &self.gpu.device);
+            }
+
+            let queued_staging = self
+                .pending_texture_updates
+                .drain(..)
+                .map(|update| (update.staging_buffer, update.staging_memory));
+            let recorded_staging = self
+                .texture_update_staging
+                .iter_mut()
+                .flat_map(std::mem::take);
+            for (buffer, mut memory) in queued_staging.chain(recorded_staging).collect::<Vec<_>>() {
+                self.gpu.allocator.destroy_buffer(buffer, &mut memory);
             }
 
             self.cleanup_swapchain();
 
-            for texture in self.textures.take_all() {
+            for texture in self.gpu.textures.take_all() {
                 self.destroy_texture(texture);
             }
-            for mut storage_texture in self.storage_textures.take_all() {
-                self.device
+            for mut feedback_texture in self.feedback_textures.take_all() {
+                for i in 0..PRE_WAIT_RING_LEN {
+                    self.gpu
+                        .device
+                        .destroy_image_view(feedback_texture.image_views[i], None);
+                    self.gpu.allocator.destroy_image(
+                        feedback_texture.images[i],
+                        &mut feedback_texture.image_memories[i],
+                    );
+                }
+            }
+            for mut storage_texture in self.gpu.storage_textures.take_all() {
+                self.gpu
+                    .device
                     .destroy_image_view(storage_texture.image_view, None);
-                self.allocator
+                self.gpu
+                    .allocator
                     .destroy_image(storage_texture.image, &mut storage_texture.image_memory);
             }
-            for pipeline in self.pipelines.take_all() {
+            if let Some(breadcrumbs) = &mut self.gpu.breadcrumbs {
+                breadcrumbs.destroy(&self.gpu.allocator);
+            }
+            if let Some(ray_query) = &self.gpu.ray_query {
+                for acceleration_structure in self.gpu.acceleration_structures.take_all() {
+                    acceleration_structure.destroy(ray_query, &self.gpu.allocator);
+                }
+            }
+            for pipeline in self.gpu.pipelines.take_all() {
                 self.destroy_pipeline(pipeline);
             }
-            for mut mesh in std::mem::take(&mut self.meshes) {
-                self.allocator
+            for mut mesh in std::mem::take(&mut self.gpu.meshes) {
+                self.gpu
+                    .allocator
                     .destroy_buffer(mesh.index_buffer, &mut mesh.index_buffer_memory);
-                self.allocator
+                self.gpu
+                    .allocator
                     .destroy_buffer(mesh.vertex_buffer, &mut mesh.vertex_buffer_memory);
             }
-            for compute_pipeline in self.compute_pipelines.take_all() {
+            for compute_pipeline in self.gpu.compute_pipelines.take_all() {
                 self.destroy_compute_pipeline(compute_pipeline);
             }
-            for buffers_per_frame in self.uniform_buffers.take_all() {
+            for buffers_per_frame in self.gpu.uniform_buffers.take_all() {
                 for uniform_buffer in buffers_per_frame {
                     self.destroy_uniform_buffer(uniform_buffer);
                 }
             }
-            for buffers_per_frame in self.storage_buffers.take_all() {
+            for buffers_per_frame in self.gpu.storage_buffers.take_all() {
                 for storage_buffer in buffers_per_frame {
                     self.destroy_storage_buffer(storage_buffer);
                 }
             }
+            for buffers_per_frame in self.gpu.counter_buffers.take_all() {
+                for counter_buffer in buffers_per_frame {
+                    self.destroy_storage_buffer(counter_buffer);
+                }
+            }
 
             // Drop egui before device destruction so it can clean up its Vulkan resources
+            #[cfg(feature = "editor")]
             drop(self.egui.take());
 
             // All allocations must be freed by this point; VMA reports leaks here.
-            std::mem::ManuallyDrop::drop(&mut self.allocator);
+            std::mem::ManuallyDrop::drop(&mut self.gpu.allocator);
 
-            self.device.destroy_device(None);
+            self.gpu.device.destroy_device(None);
 
             // NOTE This must be called before dropping the sdl window,
             // which means that the Renderer must be dropped before the window.
             // That should happen by default, since Renderer::init requires a window,
             // and rust drops variables in reverse initialization order.
-            SDL_Vulkan_DestroySurface(self.instance.handle(), self.surface, std::ptr::null());
+            SDL_Vulkan_DestroySurface(
+                self.gpu.instance.handle(),
+                self.presenter.surface,
+                std::ptr::null(),
+            );
 
             if ENABLE_VALIDATION {
-                self.debug_loader
-                    .destroy_debug_utils_messenger(self.debug_ext, None);
+                self.gpu
+                    .debug_loader
+                    .destroy_debug_utils_messenger(self.gpu.debug_ext, None);
             }
 
-            self.instance.destroy_instance(None);
+            self.gpu.instance.destroy_instance(None);
+        }
+    }
+}
+
+/// The filter for the composite from the render resolution up to the window's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpscaleFilter {
+    /// blocky, for pixel art at whole-number scales
+    Nearest,
+    #[default]
+    Bilinear,
+    /// bilinear, then FSR1's RCAS sharpening at window resolution
+    /// (shaders/lib/sharpen.slang); a fullscreen draw instead of a blit
+    Sharpened,
+}
+
+impl UpscaleFilter {
+    pub const ALL: [Self; 3] = [Self::Nearest, Self::Bilinear, Self::Sharpened];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Nearest => "Nearest",
+            Self::Bilinear => "Bilinear",
+            Self::Sharpened => "Sharpened (RCAS)",
+        }
+    }
+
+    fn vk_filter(self) -> vk::Filter {
+        match self {
+            Self::Nearest => vk::Filter::NEAREST,
+            Self::Bilinear | Self::Sharpened => vk::Filter::LINEAR,
         }
     }
 }
@@ -2907,6 +7914,43 @@ pub enum TextureColorSpace {
     Unorm,
 }
 
+/// The texel format of raw bytes uploaded with
+/// [`Renderer::create_texture_from_bytes`]. Channels are unorm unless noted;
+/// `F` formats are little-endian floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFormat {
+    R8,
+    Rg8,
+    Rgba8,
+    Rgba8Srgb,
+    R16F,
+    Rgba16F,
+    R32F,
+}
+
+impl TextureFormat {
+    pub fn vk_format(self) -> vk::Format {
+        match self {
+            TextureFormat::R8 => vk::Format::R8_UNORM,
+            TextureFormat::Rg8 => vk::Format::R8G8_UNORM,
+            TextureFormat::Rgba8 => vk::Format::R8G8B8A8_UNORM,
+            TextureFormat::Rgba8Srgb => vk::Format::R8G8B8A8_SRGB,
+            TextureFormat::R16F => vk::Format::R16_SFLOAT,
+            TextureFormat::Rgba16F => vk::Format::R16G16B16A16_SFLOAT,
+            TextureFormat::R32F => vk::Format::R32_SFLOAT,
+        }
+    }
+
+    pub fn bytes_per_texel(self) -> u32 {
+        match self {
+            TextureFormat::R8 => 1,
+            TextureFormat::Rg8 | TextureFormat::R16F => 2,
+            TextureFormat::Rgba8 | TextureFormat::Rgba8Srgb | TextureFormat::R32F => 4,
+            TextureFormat::Rgba16F => 8,
+        }
+    }
+}
+
 /// Image and sampler options for [`Renderer::create_texture_with_options`].
 /// [`TextureOptions::default()`] reproduces what [`Renderer::create_texture`]
 /// has always done.
@@ -2990,6 +8034,13 @@ fn check_required_extensions(entry: &ash::Entry) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+fn instance_extension_available(entry: &ash::Entry, name: &CStr) -> Result<bool, anyhow::Error> {
+    let available_extensions = unsafe { entry.enumerate_instance_extension_properties(None)? };
+    Ok(available_extensions
+        .iter()
+        .any(|prop| vk_str_bytes(&prop.extension_name) == name.to_bytes()))
+}
+
 /// trims a null-terminated c string from vulkan to only include
 /// non-null bytes for comparison with CStr constants
 fn vk_str_bytes(vk_str: &[c_char]) -> Vec<u8> {
@@ -3064,10 +8115,13 @@ const REQUIRED_DEVICE_EXTENSIONS: [&CStr; 1] = [
     // is core in 1.1; the feature bit is enabled via PhysicalDeviceVulkan11Features
 ];
 
+/// The most capable suitable device; with `name`, the first suitable one
+/// whose name contains it, ignoring case
 fn choose_physical_device(
     instance: &ash::Instance,
     surface_ext: &ash::khr::surface::Instance,
     surface: vk::SurfaceKHR,
+    name: Option<&str>,
 ) -> anyhow::Result<(
     vk::PhysicalDevice,
     QueueFamilyIndices,
@@ -3161,6 +8215,23 @@ fn choose_physical_device(
         }
     });
 
+    if let Some(name) = name {
+        let device_names: Vec<String> = devices_with_indices_and_props
+            .iter()
+            .map(|(_physical_device, _indices, props)| device_name_as_string(*props))
+            .collect();
+        let Some(index) = device_names
+            .iter()
+            .position(|device_name| device_name.to_lowercase().contains(&name.to_lowercase()))
+        else {
+            anyhow::bail!(
+                "no suitable graphics device matches --gpu {name}; found: {}",
+                device_names.join(", ")
+            );
+        };
+        return Ok(devices_with_indices_and_props.swap_remove(index));
+    }
+
     let Some(chosen_device) = devices_with_indices_and_props.into_iter().next() else {
         anyhow::bail!(
             "no suitable graphics device available \
@@ -3189,23 +8260,12 @@ fn device_name_as_string(props: vk::PhysicalDeviceProperties) -> String {
     String::from_utf8_lossy(&device_name_bytes).to_string()
 }
 
-const PREFERRED_SURFACE_FORMAT: vk::SurfaceFormatKHR = vk::SurfaceFormatKHR {
-    format: vk::Format::B8G8R8A8_SRGB,
-    color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
-};
-
-fn choose_swap_surface_format(swapchain: &SwapChainSupportDetails) -> vk::SurfaceFormatKHR {
-    if swapchain.formats.contains(&PREFERRED_SURFACE_FORMAT) {
-        return PREFERRED_SURFACE_FORMAT;
-    }
-
-    swapchain.fallback_format
-}
-
-fn choose_swap_present_mode(available_modes: &[vk::PresentModeKHR]) -> vk::PresentModeKHR {
-    if available_modes.contains(&vk::PresentModeKHR::MAILBOX) {
-        // burns battery on mobile, good otherwise
-        return vk::PresentModeKHR::MAILBOX;
+fn choose_swap_present_mode(
+    available_modes: &[vk::PresentModeKHR],
+    preferred_mode: vk::PresentModeKHR,
+) -> vk::PresentModeKHR {
+    if available_modes.contains(&preferred_mode) {
+        return preferred_mode;
     }
 
     // aka vsync; guaranteed to be supported
@@ -3232,6 +8292,12 @@ fn check_device_extension_support(
     Ok(required_extensions.is_empty())
 }
 
+/// the size of the display the window is on, as SDL reports its bounds
+fn display_size(window: &Window) -> Option<(u32, u32)> {
+    let bounds = window.get_display().ok()?.get_bounds().ok()?;
+    Some((bounds.w as u32, bounds.h as u32))
+}
+
 fn choose_swap_extent(window: &Window, capabilities: &vk::SurfaceCapabilitiesKHR) -> vk::Extent2D {
     // u32::MAX is used as a sentinel value that means 'refer to the bounds'
     if capabilities.current_extent.width != u32::MAX {
@@ -3255,8 +8321,18 @@ fn choose_swap_extent(window: &Window, capabilities: &vk::SurfaceCapabilitiesKHR
 
 struct CreatedSwapchain {
     swapchain: vk::SwapchainKHR,
-    image_format: vk::Format,
+    surface_format: vk::SurfaceFormatKHR,
     image_extent: vk::Extent2D,
+    /// the requested output, or Off where the surface has no format for it
+    hdr_output: HdrOutput,
+}
+
+/// How create_swapchain picks its surface format
+enum SwapchainFormat {
+    /// the best format for an output, at init
+    Choose(HdrOutput),
+    /// the format and output a swapchain was first created with
+    Pinned(vk::SurfaceFormatKHR, HdrOutput),
 }
 
 fn create_swapchain(
@@ -3266,11 +8342,22 @@ fn create_swapchain(
     surface: vk::SurfaceKHR,
     physical_device: vk::PhysicalDevice,
     queue_family_indices: &QueueFamilyIndices,
+    preferred_present_mode: vk::PresentModeKHR,
+    format: SwapchainFormat,
 ) -> Result<CreatedSwapchain, anyhow::Error> {
     let swapchain_support = SwapChainSupportDetails::query(surface_ext, surface, physical_device)?;
 
-    let surface_format = choose_swap_surface_format(&swapchain_support);
-    let present_mode = choose_swap_present_mode(&swapchain_support.present_modes);
+    let (surface_format, hdr_output) = match format {
+        SwapchainFormat::Choose(hdr_output) => {
+            hdr::choose_surface_format(&swapchain_support.formats, hdr_output)
+        }
+        SwapchainFormat::Pinned(pinned, hdr_output) => (
+            hdr::pinned_surface_format(&swapchain_support.formats, pinned)?,
+            hdr_output,
+        ),
+    };
+    let present_mode =
+        choose_swap_present_mode(&swapchain_support.present_modes, preferred_present_mode);
     let image_extent = choose_swap_extent(window, &swapchain_support.capabilities);
 
     // the number of images in the swapchain
@@ -3324,8 +8411,9 @@ fn create_swapchain(
 
     Ok(CreatedSwapchain {
         swapchain,
-        image_format: surface_format.format,
+        surface_format,
         image_extent,
+        hdr_output,
     })
 }
 
@@ -3333,6 +8421,9 @@ fn create_logical_device(
     instance: &ash::Instance,
     physical_device: vk::PhysicalDevice,
     indices: &QueueFamilyIndices,
+    optional_extensions: &[&CStr],
+    view_mode: ViewMode,
+    device_extensions: &mut DeviceExtensions,
 ) -> Result<ash::Device, anyhow::Error> {
     let unique_queue_families = BTreeSet::from([indices.graphics, indices.presentation]);
 
@@ -3367,8 +8458,11 @@ fn create_logical_device(
     // required by slang's generated spirv after 2025.10
     //   the feature is required by the 2024 roadmap
     //   https://registry.khronos.org/vulkan/specs/latest/html/vkspec.html#profile-features-roadmap-2024
-    let mut vulkan_11_features =
-        vk::PhysicalDeviceVulkan11Features::default().shader_draw_parameters(true);
+    //
+    // multiview is core in 1.1, and its feature bit is required there too
+    let mut vulkan_11_features = vk::PhysicalDeviceVulkan11Features::default()
+        .shader_draw_parameters(true)
+        .multiview(view_mode != ViewMode::Mono);
 
     let mut vulkan_12_features = vk::PhysicalDeviceVulkan12Features::default()
         .timeline_semaphore(true)
@@ -3393,14 +8487,24 @@ fn create_logical_device(
 
     let enabled_extension_names: Vec<_> = REQUIRED_DEVICE_EXTENSIONS
         .iter()
+        .chain(optional_extensions)
+        .chain(&device_extensions.names)
         .map(|cstr| cstr.as_ptr())
         .collect();
 
-    let create_info = vk::DeviceCreateInfo::default()
+    let mut create_info = vk::DeviceCreateInfo::default()
         .queue_create_infos(&queue_create_infos)
         .enabled_extension_names(&enabled_extension_names)
         .push_next(&mut features2);
 
+    // the same prepend that push_next does, for structs it can't take as trait objects;
+    // the boxes outlive create_device below
+    for features in &mut device_extensions.features {
+        let base = features.base_out();
+        unsafe { (*base).p_next = create_info.p_next.cast_mut().cast() };
+        create_info.p_next = base.cast_const().cast();
+    }
+
     let device = unsafe { instance.create_device(physical_device, &create_info, None)? };
 
     Ok(device)
@@ -3409,7 +8513,6 @@ fn create_logical_device(
 struct SwapChainSupportDetails {
     capabilities: vk::SurfaceCapabilitiesKHR,
     formats: Vec<vk::SurfaceFormatKHR>,
-    fallback_format: vk::SurfaceFormatKHR,
     present_modes: Vec<vk::PresentModeKHR>,
 }
 
@@ -3425,11 +8528,6 @@ impl SwapChainSupportDetails {
 
         let formats =
             unsafe { surface_ext.get_physical_device_surface_formats(physical_device, surface)? };
-        let fallback_format = formats
-            .first()
-            .copied()
-            .expect("physical device had no surface formats");
-
         let present_modes = unsafe {
             surface_ext.get_physical_device_surface_present_modes(physical_device, surface)?
         };
@@ -3437,7 +8535,6 @@ impl SwapChainSupportDetails {
         Ok(Self {
             capabilities,
             formats,
-            fallback_format,
             present_modes,
         })
     }
@@ -3490,6 +8587,7 @@ fn vk_depth_compare(depth_test: DepthCompare) -> (bool, vk::CompareOp) {
     match depth_test {
         DepthCompare::Less => (true, vk::CompareOp::LESS),
         DepthCompare::LessEqual => (true, vk::CompareOp::LESS_OR_EQUAL),
+        DepthCompare::Equal => (true, vk::CompareOp::EQUAL),
         DepthCompare::Always => (true, vk::CompareOp::ALWAYS),
         DepthCompare::Disabled => (false, vk::CompareOp::LESS),
     }
@@ -3513,15 +8611,80 @@ fn vk_color_write_mask(color_write: [bool; 4]) -> vk::ColorComponentFlags {
     mask
 }
 
+/// A pipeline's color pipeline and, when it has a depth prepass,
+/// the depth-only pipeline drawn before it
+fn create_graphics_pipelines(
+    device: &ash::Device,
+    color_formats: &[vk::Format],
+    writes_velocity: bool,
+    depth_format: Option<vk::Format>,
+    msaa_samples: vk::SampleCountFlags,
+    view_mask: u32,
+    pipeline_layout: &ShaderPipelineLayout,
+    shader: &dyn ShaderAtlasEntry,
+    raster_state: &RasterState,
+    depth_prepass: bool,
+    dynamic_shading_rate: bool,
+) -> Result<(vk::Pipeline, Option<vk::Pipeline>), anyhow::Error> {
+    let vertex_binding_descriptions = shader.vertex_binding_descriptions();
+    let vertex_attribute_descriptions = shader.vertex_attribute_descriptions();
+
+    let color_raster_state = if depth_prepass {
+        raster_state.after_depth_prepass()
+    } else {
+        *raster_state
+    };
+    let pipeline = create_graphics_pipeline(
+        device,
+        color_formats,
+        writes_velocity,
+        depth_format,
+        msaa_samples,
+        view_mask,
+        pipeline_layout,
+        &vertex_binding_descriptions,
+        &vertex_attribute_descriptions,
+        &color_raster_state,
+        dynamic_shading_rate,
+    )?;
+
+    if !depth_prepass {
+        return Ok((pipeline, None));
+    }
+
+    let prepass_pipeline = create_graphics_pipeline(
+        device,
+        color_formats,
+        false,
+        depth_format,
+        msaa_samples,
+        view_mask,
+        pipeline_layout,
+        &vertex_binding_descriptions,
+        &vertex_attribute_descriptions,
+        &raster_state.for_depth_prepass(),
+        dynamic_shading_rate,
+    )
+    .inspect_err(|_| unsafe { device.destroy_pipeline(pipeline, None) })?;
+
+    Ok((pipeline, Some(prepass_pipeline)))
+}
+
+/// The first color target is blended with the raster state; the rest (the
+/// velocity buffer, or the pixel debugger's outputs) are written unblended,
+/// and only when `write_extra_targets` is set
 fn create_graphics_pipeline(
     device: &ash::Device,
-    color_format: vk::Format,
+    color_formats: &[vk::Format],
+    write_extra_targets: bool,
     depth_format: Option<vk::Format>,
     msaa_samples: vk::SampleCountFlags,
+    view_mask: u32,
     pipeline_layout: &ShaderPipelineLayout,
     vertex_binding_descriptions: &[vk::VertexInputBindingDescription],
     vertex_attribute_descriptions: &[vk::VertexInputAttributeDescription],
     raster_state: &RasterState,
+    dynamic_shading_rate: bool,
 ) -> Result<vk::Pipeline, anyhow::Error> {
     let vert_shader_spv = &pipeline_layout.vertex_shader.spv_bytes;
     let frag_shader_spv = &pipeline_layout.fragment_shader.spv_bytes;
@@ -3531,18 +8694,44 @@ fn create_graphics_pipeline(
 
     let vert_shader = unsafe { device.create_shader_module(&vert_create_info, None)? };
     let frag_shader = unsafe { device.create_shader_module(&frag_create_info, None)? };
+    let task_shader = match &pipeline_layout.task_shader {
+        Some(task_shader) => {
+            let task_create_info =
+                vk::ShaderModuleCreateInfo::default().code(&task_shader.spv_bytes);
+            let task_module = unsafe { device.create_shader_module(&task_create_info, None)? };
+            Some((task_module, task_shader))
+        }
+        None => None,
+    };
 
+    let vert_stage = if pipeline_layout.mesh_shader {
+        vk::ShaderStageFlags::MESH_EXT
+    } else {
+        vk::ShaderStageFlags::VERTEX
+    };
     let vert_create_info = vk::PipelineShaderStageCreateInfo::default()
-        .stage(vk::ShaderStageFlags::VERTEX)
+        .stage(vert_stage)
         .module(vert_shader)
         .name(&pipeline_layout.vertex_shader.entry_point_name);
     let frag_create_info = vk::PipelineShaderStageCreateInfo::default()
         .stage(vk::ShaderStageFlags::FRAGMENT)
         .module(frag_shader)
         .name(&pipeline_layout.fragment_shader.entry_point_name);
-    let stages = [vert_create_info, frag_create_info];
+    let mut stages = vec![vert_create_info, frag_create_info];
+    if let Some((task_module, task_shader)) = task_shader {
+        stages.push(
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::TASK_EXT)
+                .module(task_module)
+                .name(&task_shader.entry_point_name),
+        );
+    }
 
-    let dynamic_states = vec![vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+    let mut dynamic_states = vec![vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+    // set per draw; see shading_rate
+    if dynamic_shading_rate {
+        dynamic_states.push(vk::DynamicState::FRAGMENT_SHADING_RATE_KHR);
+    }
     let dynamic_state =
         vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
 
@@ -3574,17 +8763,55 @@ fn create_graphics_pipeline(
         .rasterization_samples(msaa_samples);
 
     // color blend per attached framebuffer
+    let (
+        src_color_blend_factor,
+        dst_color_blend_factor,
+        src_alpha_blend_factor,
+        dst_alpha_blend_factor,
+    ) = match raster_state.blend {
+        BlendMode::Additive => (
+            vk::BlendFactor::SRC_ALPHA,
+            vk::BlendFactor::ONE,
+            vk::BlendFactor::ZERO,
+            vk::BlendFactor::ONE,
+        ),
+        BlendMode::Premultiplied => (
+            vk::BlendFactor::ONE,
+            vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            vk::BlendFactor::ONE,
+            vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+        ),
+        BlendMode::Alpha | BlendMode::Opaque => (
+            vk::BlendFactor::SRC_ALPHA,
+            vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            vk::BlendFactor::SRC_ALPHA,
+            vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+        ),
+    };
     let color_blend_attachment = vk::PipelineColorBlendAttachmentState::default()
-        .blend_enable(raster_state.blend == BlendMode::Alpha)
+        .blend_enable(raster_state.blend != BlendMode::Opaque)
         .color_blend_op(vk::BlendOp::ADD)
         .alpha_blend_op(vk::BlendOp::ADD)
-        .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
-        .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
-        .src_alpha_blend_factor(vk::BlendFactor::SRC_ALPHA)
-        .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+        .src_color_blend_factor(src_color_blend_factor)
+        .dst_color_blend_factor(dst_color_blend_factor)
+        .src_alpha_blend_factor(src_alpha_blend_factor)
+        .dst_alpha_blend_factor(dst_alpha_blend_factor)
         .color_write_mask(vk_color_write_mask(raster_state.color_write));
 
-    let color_attachments = [color_blend_attachment];
+    let extra_target_write_mask = if write_extra_targets {
+        vk::ColorComponentFlags::RGBA
+    } else {
+        vk::ColorComponentFlags::empty()
+    };
+    let extra_blend_attachment = vk::PipelineColorBlendAttachmentState::default()
+        .blend_enable(false)
+        .color_write_mask(extra_target_write_mask);
+    let color_attachments: Vec<_> = std::iter::once(color_blend_attachment)
+        .chain(std::iter::repeat_n(
+            extra_blend_attachment,
+            color_formats.len().saturating_sub(1),
+        ))
+        .collect();
     // global color blending
     let color_blend_state = vk::PipelineColorBlendStateCreateInfo::default()
         .logic_op_enable(false)
@@ -3598,15 +8825,13 @@ fn create_graphics_pipeline(
         .depth_bounds_test_enable(false)
         .stencil_test_enable(false);
 
-    let color_attachment_formats = [color_format];
     let mut rendering_info = vk::PipelineRenderingCreateInfo::default()
-        .color_attachment_formats(&color_attachment_formats)
-        .depth_attachment_format(depth_format.unwrap_or(vk::Format::UNDEFINED));
+        .color_attachment_formats(color_formats)
+        .depth_attachment_format(depth_format.unwrap_or(vk::Format::UNDEFINED))
+        .view_mask(view_mask);
 
-    let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
+    let mut pipeline_info = vk::GraphicsPipelineCreateInfo::default()
         .stages(&stages)
-        .vertex_input_state(&vertex_input_state)
-        .input_assembly_state(&input_assembly_state)
         .viewport_state(&viewport_state)
         .rasterization_state(&rasterization_state)
         .multisample_state(&multisample_state)
@@ -3615,6 +8840,12 @@ fn create_graphics_pipeline(
         .layout(pipeline_layout.pipeline_layout)
         .depth_stencil_state(&depth_stencil_state)
         .push_next(&mut rendering_info);
+    // mesh pipelines generate their own primitives
+    if !pipeline_layout.mesh_shader {
+        pipeline_info = pipeline_info
+            .vertex_input_state(&vertex_input_state)
+            .input_assembly_state(&input_assembly_state);
+    }
 
     let graphics_pipelines = unsafe {
         device
@@ -3625,6 +8856,9 @@ fn create_graphics_pipeline(
 
     unsafe { device.destroy_shader_module(frag_shader, None) };
     unsafe { device.destroy_shader_module(vert_shader, None) };
+    if let Some((task_module, _)) = task_shader {
+        unsafe { device.destroy_shader_module(task_module, None) };
+    }
 
     Ok(graphics_pipeline)
 }
@@ -3884,6 +9118,12 @@ fn descriptor_pool_sizes(
         vk::DescriptorPoolSize::default()
             .ty(vk::DescriptorType::STORAGE_IMAGE)
             .descriptor_count(sets_across_frames * total_counts.storage_images),
+        vk::DescriptorPoolSize::default()
+            .ty(vk::DescriptorType::ACCELERATION_STRUCTURE_KHR)
+            .descriptor_count(sets_across_frames * total_counts.acceleration_structures),
+        vk::DescriptorPoolSize::default()
+            .ty(vk::DescriptorType::STORAGE_BUFFER)
+            .descriptor_count(sets_across_frames * total_counts.storage_buffers),
     ]
     .into_iter()
     .filter(|s| s.descriptor_count != 0)
@@ -3939,6 +9179,8 @@ pub enum LayoutDescription {
     Uniform(UniformBufferDescription),
     Texture(TextureDescription),
     StorageImage(StorageImageDescription),
+    AccelerationStructure(AccelerationStructureDescription),
+    StorageBuffer(StorageBufferDescription),
 }
 
 #[derive(Debug)]
@@ -3965,13 +9207,156 @@ pub struct StorageImageDescription {
     pub descriptor_count: u32,
 }
 
+#[derive(Debug)]
+pub struct AccelerationStructureDescription {
+    pub binding: u32,
+    pub descriptor_count: u32,
+}
+
+/// a `ByteAddressBuffer` or `RWByteAddressBuffer`, bound whole
+#[derive(Debug)]
+pub struct StorageBufferDescription {
+    pub binding: u32,
+    pub descriptor_count: u32,
+}
+
+/// Checks pipeline uniform buffers (in layout order) against the reflected bindings.
+/// Raw handles erase the buffer's type, and descriptor ranges come from reflection,
+/// so a mismatched buffer would otherwise be read out of bounds.
+fn validate_uniform_buffers(
+    source_file_name: &str,
+    global_parameters: &[GlobalParameter],
+    layout_bindings: &[Vec<LayoutDescription>],
+    uniform_buffers: &[RawUniformBufferHandle],
+) -> anyhow::Result<()> {
+    let element_types: Vec<UniformElementType> = uniform_buffers
+        .iter()
+        .map(|handle| handle.element_type())
+        .collect();
+    // named by set, so nested blocks and loose globals line up with their layouts
+    let descriptor_sets = global_descriptor_sets(global_parameters);
+    let parameter_names: Vec<&str> = descriptor_sets
+        .iter()
+        .map(|descriptor_set| descriptor_set.parameter_path.as_str())
+        .collect();
+
+    validate_uniform_element_types(
+        source_file_name,
+        &parameter_names,
+        layout_bindings,
+        &element_types,
+    )
+}
+
+fn validate_uniform_element_types(
+    source_file_name: &str,
+    parameter_names: &[&str],
+    layout_bindings: &[Vec<LayoutDescription>],
+    element_types: &[UniformElementType],
+) -> anyhow::Result<()> {
+    let mut remaining = element_types.iter();
+
+    for (set, layout_descriptions) in layout_bindings.iter().enumerate() {
+        let parameter_name = parameter_names.get(set).copied().unwrap_or("<unknown>");
+
+        for description in layout_descriptions {
+            let LayoutDescription::Uniform(uniform) = description else {
+                continue;
+            };
+
+            let Some(element_type) = remaining.next() else {
+                anyhow::bail!(
+                    "{source_file_name}: missing uniform buffer for parameter `{parameter_name}` (set {set}, binding {})",
+                    uniform.binding
+                );
+            };
+
+            anyhow::ensure!(
+                element_type.size == uniform.size,
+                "{source_file_name}: uniform buffer for parameter `{parameter_name}` (set {set}, binding {}) holds `{}` ({} bytes), but the shader expects {} bytes",
+                uniform.binding,
+                element_type.type_name,
+                element_type.size,
+                uniform.size
+            );
+        }
+    }
+
+    let extra = remaining.count();
+    anyhow::ensure!(
+        extra == 0,
+        "{source_file_name}: {extra} more uniform buffer(s) given than the shader declares"
+    );
+
+    Ok(())
+}
+
+/// Find and check the descriptor a rebind rewrites: the `uniform_index`th
+/// uniform buffer in layout order, in every ring slot's sets
+fn uniform_rebind(
+    source_file_name: &str,
+    layout_bindings: &[Vec<LayoutDescription>],
+    descriptor_sets: &[vk::DescriptorSet],
+    sets_per_frame: usize,
+    uniform_index: usize,
+    uniform_buffer: &RawUniformBufferHandle,
+) -> anyhow::Result<PendingUniformRebind> {
+    let Some((set, description)) = layout_bindings
+        .iter()
+        .enumerate()
+        .flat_map(|(set, descriptions)| {
+            descriptions
+                .iter()
+                .filter_map(move |description| match description {
+                    LayoutDescription::Uniform(uniform) => Some((set, uniform)),
+                    _ => None,
+                })
+        })
+        .nth(uniform_index)
+    else {
+        anyhow::bail!("{source_file_name}: no uniform buffer at index {uniform_index}");
+    };
+
+    let element_type = uniform_buffer.element_type();
+    anyhow::ensure!(
+        element_type.size == description.size,
+        "{source_file_name}: uniform buffer {uniform_index} (set {set}, binding {}) can't be rebound to `{}` ({} bytes); the shader expects {} bytes",
+        description.binding,
+        element_type.type_name,
+        element_type.size,
+        description.size
+    );
+
+    Ok(PendingUniformRebind {
+        dst_sets: std::array::from_fn(|frame| descriptor_sets[frame * sets_per_frame + set]),
+        binding: description.binding,
+        descriptor_count: description.descriptor_count,
+        range: description.size,
+        uniform_buffer: uniform_buffer.clone(),
+        stale_slots: [true; PRE_WAIT_RING_LEN],
+    })
+}
+
+/// Swap the `uniform_index`th uniform buffer in a pipeline's resources
+fn rebind_resource(resources: &mut [ResourceId], uniform_index: usize, buffer_index: usize) {
+    if let Some(resource) = resources
+        .iter_mut()
+        .filter(|resource| matches!(resource, ResourceId::UniformBuffer(_)))
+        .nth(uniform_index)
+    {
+        *resource = ResourceId::UniformBuffer(buffer_index);
+    }
+}
+
 fn create_descriptor_sets(
     device: &ash::Device,
     descriptor_pool: vk::DescriptorPool,
     descriptor_set_layouts: &[vk::DescriptorSetLayout],
     uniform_buffers_in_layout_frame_order: &[&[RawUniformBuffer; PRE_WAIT_RING_LEN]],
-    textures: &[&Texture],
+    textures: &[[vk::DescriptorImageInfo; PRE_WAIT_RING_LEN]],
     storage_images: &[&storage_texture::StorageTexture],
+    acceleration_structures: &[vk::AccelerationStructureKHR],
+    raw_buffers: &[[vk::Buffer; PRE_WAIT_RING_LEN]],
     layout_bindings: Vec<Vec<LayoutDescription>>,
 ) -> Result<Vec<vk::DescriptorSet>, anyhow::Error> {
     // this vec and the resulting vec of descriptor sets are arranged like this:
@@ -4001,6 +9386,8 @@ fn create_descriptor_sets(
         let mut uniform_buffer_index = 0;
         let mut texture_index = 0;
         let mut storage_image_index = 0;
+        let mut acceleration_structure_index = 0;
+        let mut raw_buffer_index = 0;
 
         #[expect(clippy::needless_range_loop)]
         for layout_offset in 0..descriptor_set_layouts.len() {
@@ -4034,7 +9421,7 @@ fn create_descriptor_sets(
                     }
 
                     LayoutDescription::Texture(texture_description) => {
-                        let texture = textures[texture_index];
+                        let image_info = textures[texture_index][frame];
 
                         let descriptor_type = if texture_description.sampled_image_only {
                             vk::DescriptorType::SAMPLED_IMAGE
@@ -4042,10 +9429,6 @@ fn create_descriptor_sets(
                             vk::DescriptorType::COMBINED_IMAGE_SAMPLER
                         };
 
-                        let image_info = vk::DescriptorImageInfo::default()
-                            .image_layout(texture.image_layout)
-                            .image_view(texture.image_view)
-                            .sampler(texture.sampler);
                         let image_info = [image_info];
                         let image_write = vk::WriteDescriptorSet::default()
                             .dst_set(dst_set)
@@ -4079,6 +9462,48 @@ fn create_descriptor_sets(
                         unsafe { device.update_descriptor_sets(&writes, &[]) };
                         storage_image_index += 1;
                     }
+
+                    LayoutDescription::AccelerationStructure(
+                        acceleration_structure_description,
+                    ) => {
+                        let acceleration_structure =
+                            [acceleration_structures[acceleration_structure_index]];
+
+                        let mut acceleration_structure_write =
+                            vk::WriteDescriptorSetAccelerationStructureKHR::default()
+                                .acceleration_structures(&acceleration_structure);
+                        let write = vk::WriteDescriptorSet::default()
+                            .dst_set(dst_set)
+                            .dst_binding(acceleration_structure_description.binding)
+                            .dst_array_element(0)
+                            .descriptor_type(vk::DescriptorType::ACCELERATION_STRUCTURE_KHR)
+                            .descriptor_count(acceleration_structure_description.descriptor_count)
+                            .push_next(&mut acceleration_structure_write);
+
+                        let writes = [write];
+                        unsafe { device.update_descriptor_sets(&writes, &[]) };
+                        acceleration_structure_index += 1;
+                    }
+
+                    LayoutDescription::StorageBuffer(storage_buffer_description) => {
+                        // each ring slot binds its own copy, like uniform buffers
+                        let buffer_info = vk::DescriptorBufferInfo::default()
+                            .offset(0)
+                            .buffer(raw_buffers[raw_buffer_index][frame])
+                            .range(vk::WHOLE_SIZE);
+                        let buffer_info = [buffer_info];
+                        let storage_buffer_write = vk::WriteDescriptorSet::default()
+                            .dst_set(dst_set)
+                            .dst_binding(storage_buffer_description.binding)
+                            .dst_array_element(0)
+                            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+                            .descriptor_count(storage_buffer_description.descriptor_count)
+                            .buffer_info(&buffer_info);
+
+                        let writes = [storage_buffer_write];
+                        unsafe { device.update_descriptor_sets(&writes, &[]) };
+                        raw_buffer_index += 1;
+                    }
                 }
             }
         }
@@ -4112,8 +9537,28 @@ pub struct FormatBlockInfo {
 /// Block-compressed formats (eg. BC7) can be added here as needed.
 pub fn format_block_info(format: vk::Format) -> Option<FormatBlockInfo> {
     match format {
-        vk::Format::R8G8B8A8_SRGB | vk::Format::R8G8B8A8_UNORM => Some(FormatBlockInfo {
-            block_bytes: 4,
+        vk::Format::R8G8B8A8_SRGB | vk::Format::R8G8B8A8_UNORM | vk::Format::R32_SFLOAT => {
+            Some(FormatBlockInfo {
+                block_bytes: 4,
+                block_width: 1,
+                block_height: 1,
+            })
+        }
+
+        vk::Format::R8_UNORM => Some(FormatBlockInfo {
+            block_bytes: 1,
+            block_width: 1,
+            block_height: 1,
+        }),
+
+        vk::Format::R8G8_UNORM | vk::Format::R16_SFLOAT => Some(FormatBlockInfo {
+            block_bytes: 2,
+            block_width: 1,
+            block_height: 1,
+        }),
+
+        vk::Format::R16G16B16A16_SFLOAT => Some(FormatBlockInfo {
+            block_bytes: 8,
             block_width: 1,
             block_height: 1,
         }),
@@ -4145,10 +9590,132 @@ fn create_texture(
         options,
     )?;
 
+    let format = texture_format(options.color_space);
     let texture_image_view = create_image_view(
         device,
         texture_image,
-        texture_format(options.color_space),
+        format,
+        vk::ImageAspectFlags::COLOR,
+        mip_levels,
+    )?;
+
+    let texture_sampler = create_texture_sampler(device, physical_device_properties, options)?;
+
+    Ok(Texture {
+        source_file_name,
+        image: texture_image,
+        image_ownership: texture::ImageOwnership::Owned(texture_image_memory),
+        format,
+        extent: vk::Extent2D {
+            width: input_image.width(),
+            height: input_image.height(),
+        },
+        mip_levels,
+        image_view: texture_image_view,
+        sampler: texture_sampler,
+        image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+    })
+}
+
+/// Validates raw texel rows against the texture size, returning the
+/// `bufferRowLength` (in texels, 0 for tightly packed) to copy them with.
+fn texel_row_length(
+    width: u32,
+    height: u32,
+    bytes_per_texel: u32,
+    row_stride: usize,
+    byte_len: usize,
+) -> anyhow::Result<u32> {
+    anyhow::ensure!(
+        width > 0 && height > 0,
+        "texture is {width}x{height}; it needs at least one texel"
+    );
+    let row_bytes = width as usize * bytes_per_texel as usize;
+    anyhow::ensure!(
+        row_stride >= row_bytes,
+        "row stride {row_stride} is smaller than a row of {width} texels ({row_bytes} bytes)"
+    );
+    anyhow::ensure!(
+        row_stride.is_multiple_of(bytes_per_texel as usize),
+        "row stride {row_stride} is not a multiple of the texel size {bytes_per_texel}"
+    );
+    let expected_len = row_stride * height as usize;
+    anyhow::ensure!(
+        byte_len == expected_len,
+        "expected {expected_len} bytes for {height} rows of stride {row_stride}, got {byte_len}"
+    );
+
+    let row_length = if row_stride == row_bytes {
+        0
+    } else {
+        (row_stride / bytes_per_texel as usize) as u32
+    };
+
+    Ok(row_length)
+}
+
+fn create_texture_from_bytes(
+    source_file_name: String,
+    format: TextureFormat,
+    extent: vk::Extent2D,
+    bytes: &[u8],
+    row_stride: usize,
+    allocator: &vk_mem::Allocator,
+    instance: &ash::Instance,
+    device: &ash::Device,
+    physical_device: vk::PhysicalDevice,
+    physical_device_properties: vk::PhysicalDeviceProperties,
+    command_pool: vk::CommandPool,
+    graphics_queue: vk::Queue,
+    options: TextureOptions,
+) -> anyhow::Result<Texture> {
+    let buffer_row_length = texel_row_length(
+        extent.width,
+        extent.height,
+        format.bytes_per_texel(),
+        row_stride,
+        bytes.len(),
+    )?;
+
+    let vk_format = format.vk_format();
+    let format_properties =
+        unsafe { instance.get_physical_device_format_properties(physical_device, vk_format) };
+    let mut required_features =
+        vk::FormatFeatureFlags::SAMPLED_IMAGE | vk::FormatFeatureFlags::TRANSFER_DST;
+    if options.filter == TextureFilter::Linear || options.mipmaps {
+        required_features |= vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR;
+    }
+    if options.mipmaps {
+        required_features |= vk::FormatFeatureFlags::BLIT_SRC | vk::FormatFeatureFlags::BLIT_DST;
+    }
+    if !format_properties
+        .optimal_tiling_features
+        .contains(required_features)
+    {
+        anyhow::bail!(
+            "format {format:?} does not support {required_features:?} on this device; \
+             try TextureFilter::Nearest without mipmaps"
+        );
+    }
+
+    let (texture_image, texture_image_memory, mip_levels) = upload_texture_image(
+        bytes,
+        extent,
+        buffer_row_length,
+        vk_format,
+        options.mipmaps,
+        allocator,
+        instance,
+        device,
+        physical_device,
+        command_pool,
+        graphics_queue,
+    )?;
+
+    let texture_image_view = create_image_view(
+        device,
+        texture_image,
+        vk_format,
         vk::ImageAspectFlags::COLOR,
         mip_levels,
     )?;
@@ -4159,6 +9726,8 @@ fn create_texture(
         source_file_name,
         image: texture_image,
         image_ownership: texture::ImageOwnership::Owned(texture_image_memory),
+        format: vk_format,
+        extent,
         mip_levels,
         image_view: texture_image_view,
         sampler: texture_sampler,
@@ -4182,10 +9751,42 @@ fn create_texture_image(
         "expected rgba bytes size"
     );
 
-    let format = texture_format(options.color_space);
+    let extent = vk::Extent2D::default()
+        .width(image.width())
+        .height(image.height());
+
+    upload_texture_image(
+        &bytes,
+        extent,
+        0,
+        texture_format(options.color_space),
+        options.mipmaps,
+        allocator,
+        instance,
+        device,
+        physical_device,
+        command_pool,
+        graphics_queue,
+    )
+}
 
-    let mip_levels = if options.mipmaps {
-        image.width().max(image.height()).ilog2() + 1
+/// Uploads texel rows into a new sampled image, optionally generating a full
+/// mip chain. `buffer_row_length` is in texels, with 0 meaning tightly packed.
+fn upload_texture_image(
+    bytes: &[u8],
+    extent: vk::Extent2D,
+    buffer_row_length: u32,
+    format: vk::Format,
+    mipmaps: bool,
+    allocator: &vk_mem::Allocator,
+    instance: &ash::Instance,
+    device: &ash::Device,
+    physical_device: vk::PhysicalDevice,
+    command_pool: vk::CommandPool,
+    graphics_queue: vk::Queue,
+) -> Result<(vk::Image, vk_mem::Allocation, u32), anyhow::Error> {
+    let mip_levels = if mipmaps {
+        extent.width.max(extent.height).ilog2() + 1
     } else {
         1
     };
@@ -4198,11 +9799,8 @@ fn create_texture_image(
         BufferMemory::Staging,
     )?;
 
-    unsafe { write_to_gpu_buffer(allocator, &mut staging_buffer_memory, &bytes)? };
+    unsafe { write_to_gpu_buffer(allocator, &mut staging_buffer_memory, bytes)? };
 
-    let extent = vk::Extent2D::default()
-        .width(image.width())
-        .height(image.height());
     let image_options = ImageOptions {
         extent,
         format,
@@ -4211,6 +9809,7 @@ fn create_texture_image(
             | vk::ImageUsageFlags::SAMPLED
             | vk::ImageUsageFlags::TRANSFER_SRC, // for mipmap
         mip_levels,
+        array_layers: 1,
         msaa_samples: vk::SampleCountFlags::TYPE_1,
     };
     let (vk_image, image_memory) = create_vk_image(allocator, image_options)?;
@@ -4233,7 +9832,7 @@ fn create_texture_image(
         .layer_count(1);
     let region = vk::BufferImageCopy::default()
         .buffer_offset(0)
-        .buffer_row_length(0)
+        .buffer_row_length(buffer_row_length)
         .buffer_image_height(0)
         .image_subresource(image_subresource)
         .image_offset(vk::Offset3D::default())
@@ -4248,7 +9847,7 @@ fn create_texture_image(
         &[region],
     )?;
 
-    if options.mipmaps {
+    if mipmaps {
         // this also leaves every level in SHADER_READ_ONLY_OPTIMAL
         generate_mipmaps(
             device,
@@ -4344,6 +9943,8 @@ fn create_texture_from_mips(
         source_file_name,
         image: texture_image,
         image_ownership: texture::ImageOwnership::Owned(texture_image_memory),
+        format,
+        extent,
         mip_levels,
         image_view: texture_image_view,
         sampler: texture_sampler,
@@ -4404,9 +10005,13 @@ fn create_texture_image_from_mips(
         extent,
         format,
         tiling: vk::ImageTiling::OPTIMAL,
-        // no TRANSFER_SRC: the mip levels are uploaded directly, not blitted
-        usage: vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED,
+        // the mip levels are uploaded directly, not blitted;
+        // TRANSFER_SRC is only for the texture inspector
+        usage: vk::ImageUsageFlags::TRANSFER_DST
+            | vk::ImageUsageFlags::SAMPLED
+            | vk::ImageUsageFlags::TRANSFER_SRC,
         mip_levels,
+        array_layers: 1,
         msaa_samples: vk::SampleCountFlags::TYPE_1,
     };
     let (vk_image, image_memory) = create_vk_image(allocator, image_options)?;
@@ -4482,6 +10087,7 @@ pub(super) struct ImageOptions {
     tiling: vk::ImageTiling,
     usage: vk::ImageUsageFlags,
     mip_levels: u32,
+    array_layers: u32,
     msaa_samples: vk::SampleCountFlags,
 }
 
@@ -4493,7 +10099,7 @@ pub(super) fn create_vk_image(
         .image_type(vk::ImageType::TYPE_2D)
         .extent(options.extent.into())
         .mip_levels(options.mip_levels)
-        .array_layers(1)
+        .array_layers(options.array_layers)
         .format(options.format)
         .tiling(options.tiling)
         .initial_layout(vk::ImageLayout::UNDEFINED)
@@ -4535,6 +10141,17 @@ fn end_single_time_commands(
     command_pool: vk::CommandPool,
     graphics_queue: vk::Queue,
     command_buffer: vk::CommandBuffer,
+) -> Result<(), anyhow::Error> {
+    end_single_time_commands_waiting(device, command_pool, graphics_queue, command_buffer, &[])
+}
+
+/// [`end_single_time_commands`], with the submit waiting on semaphores
+fn end_single_time_commands_waiting(
+    device: &ash::Device,
+    command_pool: vk::CommandPool,
+    graphics_queue: vk::Queue,
+    command_buffer: vk::CommandBuffer,
+    waits: &[vk::SemaphoreSubmitInfo],
 ) -> Result<(), anyhow::Error> {
     let command_buffers = [command_buffer];
 
@@ -4542,7 +10159,9 @@ fn end_single_time_commands(
 
     let command_buffer_infos =
         [vk::CommandBufferSubmitInfo::default().command_buffer(command_buffer)];
-    let submit_info = vk::SubmitInfo2::default().command_buffer_infos(&command_buffer_infos);
+    let submit_info = vk::SubmitInfo2::default()
+        .wait_semaphore_infos(waits)
+        .command_buffer_infos(&command_buffer_infos);
     let submits = [submit_info];
     unsafe {
         device.queue_submit2(graphics_queue, &submits, vk::Fence::null())?;
@@ -4553,6 +10172,7 @@ fn end_single_time_commands(
     Ok(())
 }
 
+/// Prefer [`Renderer::record_barriers`], which keeps the debug layout tracker up to date
 fn cmd_barrier2(
     device: &ash::Device,
     command_buffer: vk::CommandBuffer,
@@ -4699,6 +10319,37 @@ pub(super) fn create_image_view(
     Ok(image_view)
 }
 
+/// A view of a main pass target; in stereo, an array view over one layer per view
+fn create_attachment_view(
+    device: &ash::Device,
+    image: vk::Image,
+    format: vk::Format,
+    aspect_mask: vk::ImageAspectFlags,
+    view_mode: ViewMode,
+) -> Result<vk::ImageView, anyhow::Error> {
+    let view_count = view_mode.view_count();
+    if view_count == 1 {
+        return create_image_view(device, image, format, aspect_mask, 1);
+    }
+
+    let subresource_range = vk::ImageSubresourceRange::default()
+        .aspect_mask(aspect_mask)
+        .base_mip_level(0)
+        .level_count(1)
+        .base_array_layer(0)
+        .layer_count(view_count);
+
+    let create_info = vk::ImageViewCreateInfo::default()
+        .image(image)
+        .view_type(vk::ImageViewType::TYPE_2D_ARRAY)
+        .format(format)
+        .subresource_range(subresource_range);
+
+    let image_view = unsafe { device.create_image_view(&create_info, None)? };
+
+    Ok(image_view)
+}
+
 fn vk_address_mode(wrap: TextureWrap) -> vk::SamplerAddressMode {
     match wrap {
         TextureWrap::Repeat => vk::SamplerAddressMode::REPEAT,
@@ -4753,35 +10404,35 @@ fn create_texture_sampler(
 
 fn create_depth_buffer_image(
     allocator: &vk_mem::Allocator,
-    instance: &ash::Instance,
     device: &ash::Device,
-    physical_device: vk::PhysicalDevice,
     command_pool: vk::CommandPool,
     graphics_queue: vk::Queue,
+    depth_format: vk::Format,
     swapchain_extent: vk::Extent2D,
     msaa_samples: vk::SampleCountFlags,
+    view_mode: ViewMode,
 ) -> Result<(vk::Image, vk_mem::Allocation, vk::ImageView), anyhow::Error> {
-    let depth_format = find_depth_format(instance, physical_device);
-
     let mip_levels = 1;
 
     let image_options = ImageOptions {
         extent: swapchain_extent,
         format: depth_format,
         tiling: vk::ImageTiling::OPTIMAL,
-        usage: vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+        // TRANSFER_SRC for the texture inspector
+        usage: vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
         mip_levels,
+        array_layers: view_mode.view_count(),
         msaa_samples,
     };
 
     let (depth_image, depth_image_memory) = create_vk_image(allocator, image_options)?;
 
-    let depth_image_view = create_image_view(
+    let depth_image_view = create_attachment_view(
         device,
         depth_image,
         depth_format,
         vk::ImageAspectFlags::DEPTH,
-        mip_levels,
+        view_mode,
     )?;
 
     transition_image_layout(
@@ -4825,17 +10476,35 @@ fn find_supported_format(
     None
 }
 
-fn find_depth_format(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> vk::Format {
-    let candidates = [
-        vk::Format::D32_SFLOAT,
-        vk::Format::D32_SFLOAT_S8_UINT,
-        vk::Format::D24_UNORM_S8_UINT,
-    ];
+/// The depth formats tried for the main pass, in order of preference; see
+/// [`Game::depth_formats`](crate::game::Game::depth_formats). Vulkan requires
+/// D16_UNORM, so the list always ends with a format every device has.
+pub const DEFAULT_DEPTH_FORMATS: [vk::Format; 4] = [
+    vk::Format::D32_SFLOAT,
+    vk::Format::D32_SFLOAT_S8_UINT,
+    vk::Format::D24_UNORM_S8_UINT,
+    vk::Format::D16_UNORM,
+];
+
+/// The first of `candidates` the device can use as a depth attachment
+fn find_depth_format(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    candidates: &[vk::Format],
+) -> anyhow::Result<vk::Format> {
     let tiling = vk::ImageTiling::OPTIMAL;
     let features = vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT;
 
-    find_supported_format(instance, physical_device, &candidates, tiling, features)
-        .expect("no supported depth format available")
+    let Some(depth_format) =
+        find_supported_format(instance, physical_device, candidates, tiling, features)
+    else {
+        anyhow::bail!("none of the depth formats {candidates:?} are supported");
+    };
+    if candidates.first() != Some(&depth_format) {
+        log::info!("falling back to depth format {depth_format:?}");
+    }
+
+    Ok(depth_format)
 }
 
 fn has_stencil_component(format: vk::Format) -> bool {
@@ -4985,6 +10654,7 @@ fn get_max_usable_sample_count(
         ],
         MaxMSAASamples::Max4 => &[vk::SampleCountFlags::TYPE_4, vk::SampleCountFlags::TYPE_2],
         MaxMSAASamples::Max2 => &[vk::SampleCountFlags::TYPE_2],
+        MaxMSAASamples::Max1 => &[],
     };
 
     for option in descending_options {
@@ -4993,8 +10663,7 @@ fn get_max_usable_sample_count(
         }
     }
 
-    // NOTE this will trigger a validation error;
-    // supposed to not use resolve attachment setup at all if not using msaa
+    // the main pass skips its resolve when not multisampling
     vk::SampleCountFlags::TYPE_1
 }
 
@@ -5004,6 +10673,7 @@ fn create_color_image(
     swapchain_extent: vk::Extent2D,
     color_format: vk::Format,
     msaa_samples: vk::SampleCountFlags,
+    view_mode: ViewMode,
 ) -> Result<(vk::Image, vk_mem::Allocation, vk::ImageView), anyhow::Error> {
     let mip_levels = 1;
     let image_options = ImageOptions {
@@ -5012,17 +10682,18 @@ fn create_color_image(
         tiling: vk::ImageTiling::OPTIMAL,
         usage: vk::ImageUsageFlags::TRANSIENT_ATTACHMENT | vk::ImageUsageFlags::COLOR_ATTACHMENT,
         mip_levels,
+        array_layers: view_mode.view_count(),
         msaa_samples,
     };
 
     let (color_image, color_image_memory) = create_vk_image(allocator, image_options)?;
 
-    let color_image_view = create_image_view(
+    let color_image_view = create_attachment_view(
         device,
         color_image,
         color_format,
         vk::ImageAspectFlags::COLOR,
-        mip_levels,
+        view_mode,
     )?;
 
     Ok((color_image, color_image_memory, color_image_view))
@@ -5033,7 +10704,7 @@ fn create_color_image(
 /// updated at runtime. If the reloaded shader's reflected interface changed,
 /// writing through the old structs would silently corrupt GPU data — fail
 /// loudly instead.
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, feature = "shader-compiler"))]
 fn assert_shader_interface_unchanged<T: serde::Serialize>(
     embedded: &T,
     fresh: &T,
@@ -5053,8 +10724,11 @@ fn assert_shader_interface_unchanged<T: serde::Serialize>(
 }
 
 struct ShaderPipelineLayout {
+    /// the vertex stage, or the mesh stage of a mesh shader
     vertex_shader: PrecompiledShader,
+    task_shader: Option<PrecompiledShader>,
     fragment_shader: PrecompiledShader,
+    mesh_shader: bool,
 
     // NOTE the renderer is expected to clean up these fields correctly
     // they need special handling during hot reload
@@ -5063,13 +10737,14 @@ struct ShaderPipelineLayout {
 }
 
 impl ShaderPipelineLayout {
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
     fn create_from_atlas(
         device: &ash::Device,
         shader: &dyn ShaderAtlasEntry,
     ) -> Result<Self, anyhow::Error> {
         let shaders::ReflectedShader {
             vertex_shader,
+            task_shader,
             fragment_shader,
             reflection_json,
         } = shaders::dev_compile_slang_shaders(shader.source_file_name())?;
@@ -5085,6 +10760,14 @@ impl ShaderPipelineLayout {
             entry_point_name: vertex_shader.entry_point_name,
         };
 
+        let task_shader = match task_shader {
+            Some(task_shader) => Some(PrecompiledShader {
+                spv_bytes: task_shader.spv_bytes()?,
+                entry_point_name: task_shader.entry_point_name,
+            }),
+            None => None,
+        };
+
         let fragment_shader = PrecompiledShader {
             spv_bytes: fragment_shader.spv_bytes()?,
             entry_point_name: fragment_shader.entry_point_name,
@@ -5095,7 +10778,9 @@ impl ShaderPipelineLayout {
 
         Ok(ShaderPipelineLayout {
             vertex_shader,
+            task_shader,
             fragment_shader,
+            mesh_shader: reflection_json.is_mesh_shader(),
             pipeline_layout,
             descriptor_set_layouts,
         })
@@ -5113,7 +10798,9 @@ impl ShaderPipelineLayout {
 
         Ok(ShaderPipelineLayout {
             vertex_shader: precompiled.vert,
+            task_shader: shader.precompiled_task_shader(),
             fragment_shader: precompiled.frag,
+            mesh_shader: shader.reflection_json().is_mesh_shader(),
             pipeline_layout,
             descriptor_set_layouts,
         })
@@ -5130,7 +10817,7 @@ pub(crate) struct ComputeShaderPipelineLayout {
 }
 
 impl ComputeShaderPipelineLayout {
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))]
     fn create_from_atlas(
         device: &ash::Device,
         shader: &dyn ComputeShaderAtlasEntry,
@@ -5209,6 +10896,8 @@ impl shaders::json::ReflectedBindingType {
             Self::ConstantBuffer => vk::DescriptorType::UNIFORM_BUFFER,
             Self::CombinedTextureSampler => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
             Self::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
+            Self::AccelerationStructure => vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
+            Self::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
         }
     }
 }
@@ -5256,6 +10945,8 @@ struct DescriptorCounts {
     combined_texture_samplers: u32,
     sampled_images: u32,
     storage_images: u32,
+    acceleration_structures: u32,
+    storage_buffers: u32,
 }
 
 impl std::iter::Sum for DescriptorCounts {
@@ -5279,6 +10970,8 @@ impl std::ops::Add for DescriptorCounts {
                 + rhs.combined_texture_samplers,
             sampled_images: self.sampled_images + rhs.sampled_images,
             storage_images: self.storage_images + rhs.storage_images,
+            acceleration_structures: self.acceleration_structures + rhs.acceleration_structures,
+            storage_buffers: self.storage_buffers + rhs.storage_buffers,
         }
     }
 }
@@ -5289,6 +10982,8 @@ impl DescriptorCounts {
         combined_texture_samplers: 0,
         sampled_images: 0,
         storage_images: 0,
+        acceleration_structures: 0,
+        storage_buffers: 0,
     };
 
     fn from_descriptor_set_layout(set_layout: &ReflectedDescriptorSetLayout) -> Self {
@@ -5296,6 +10991,8 @@ impl DescriptorCounts {
         let mut combined_texture_samplers = 0;
         let mut sampled_images = 0;
         let mut storage_images = 0;
+        let mut acceleration_structures = 0;
+        let mut storage_buffers = 0;
         for binding in &set_layout.binding_ranges {
             match binding.descriptor_type {
                 shaders::json::ReflectedBindingType::ConstantBuffer => {
@@ -5313,6 +11010,12 @@ impl DescriptorCounts {
                 shaders::json::ReflectedBindingType::Texture => {
                     sampled_images += 1;
                 }
+                shaders::json::ReflectedBindingType::AccelerationStructure => {
+                    acceleration_structures += 1;
+                }
+                shaders::json::ReflectedBindingType::StorageBuffer => {
+                    storage_buffers += 1;
+                }
             }
         }
 
@@ -5321,6 +11024,8 @@ impl DescriptorCounts {
             combined_texture_samplers,
             sampled_images,
             storage_images,
+            acceleration_structures,
+            storage_buffers,
         }
     }
 }
@@ -5340,6 +11045,8 @@ impl shaders::json::ReflectedStageFlags {
             Self::Vertex => vk::ShaderStageFlags::VERTEX,
             Self::Fragment => vk::ShaderStageFlags::FRAGMENT,
             Self::Compute => vk::ShaderStageFlags::COMPUTE,
+            Self::Task => vk::ShaderStageFlags::TASK_EXT,
+            Self::Mesh => vk::ShaderStageFlags::MESH_EXT,
             Self::All => vk::ShaderStageFlags::ALL,
             Self::Empty => vk::ShaderStageFlags::empty(),
         }
@@ -5351,6 +11058,16 @@ pub struct Gpu<'f> {
     ring_slot: usize,
     uniform_buffers: &'f mut UniformBufferStorage,
     storage_buffers: &'f mut StorageBufferStorage,
+    counter_buffers: &'f CounterBufferStorage,
+    pipelines: &'f mut PipelineStorage,
+    /// the first oversized write this frame, returned once the frame is submitted
+    storage_overflow: Option<DrawError>,
+    /// the buffer ranges written this frame, while a determinism audit runs
+    audited_writes: Option<Vec<WrittenRange>>,
+    /// storage and immutable buffers addressed this frame, by storage index,
+    /// checked for unwritten slots once gpu_update returns
+    #[cfg(debug_assertions)]
+    addressed_storage: std::cell::RefCell<Vec<usize>>,
 }
 
 impl<'f> Gpu<'f> {
@@ -5360,11 +11077,59 @@ impl<'f> Gpu<'f> {
             .get_mapped_mem_for_frame(uniform_buffer, self.ring_slot);
 
         *mapped_mem = data;
+        let mapped_mem = (mapped_mem as *const T).cast();
+        self.audit_write(
+            AuditedBuffer::Uniform(uniform_buffer.index()),
+            mapped_mem,
+            std::mem::size_of::<T>(),
+        );
+    }
+
+    /// Copy `bytes` into the start of a raw uniform buffer's current frame copy;
+    /// bytes past the buffer's size are dropped
+    pub fn write_uniform_bytes(&mut self, uniform_buffer: &RawUniformBufferHandle, bytes: &[u8]) {
+        let mapped_bytes = self
+            .uniform_buffers
+            .get_mapped_bytes_for_frame(uniform_buffer, self.ring_slot);
+        let len = bytes.len().min(mapped_bytes.len());
+        mapped_bytes[..len].copy_from_slice(&bytes[..len]);
+        let mapped_bytes = mapped_bytes.as_ptr();
+        self.audit_write(
+            AuditedBuffer::Uniform(uniform_buffer.index()),
+            mapped_bytes,
+            len,
+        );
+    }
+
+    /// Sets the push constants for the pipeline's draws, from this frame on;
+    /// `T` is the shader's generated `PushConstants`.
+    /// Variants share their pipeline's push constants.
+    pub fn push_constants<T: GPUWrite, D>(&mut self, pipeline: &PipelineHandle<D>, value: &T) {
+        let push_constants = &mut self.pipelines.get_mut(pipeline).push_constants;
+        assert_eq!(
+            std::mem::size_of::<T>(),
+            push_constants.len(),
+            "push constants of the wrong size for this pipeline's layout"
+        );
+
+        let bytes = unsafe {
+            std::slice::from_raw_parts((value as *const T).cast::<u8>(), std::mem::size_of::<T>())
+        };
+        push_constants.copy_from_slice(bytes);
+        let push_constants = push_constants.as_ptr();
+        self.audit_write(
+            AuditedBuffer::PushConstants(pipeline.raw_index()),
+            push_constants,
+            bytes.len(),
+        );
     }
 
+    /// Copies `data` into the current frame's buffer.
+    ///
+    /// Data beyond the buffer's capacity is dropped, and the frame's draw call
+    /// returns `DrawError::StorageOverflow`.
     pub fn write_storage<T>(&mut self, storage_buffer: &mut StorageBufferHandle<T>, data: &[T]) {
-        debug_assert!(data.len() <= storage_buffer.len() as usize);
-        let len_to_copy = data.len().min(storage_buffer.len() as usize);
+        let len_to_copy = self.checked_write_len(data.len(), storage_buffer.capacity());
 
         let mapped_mem = self
             .storage_buffers
@@ -5373,6 +11138,11 @@ impl<'f> Gpu<'f> {
         unsafe {
             std::ptr::copy_nonoverlapping(data.as_ptr(), mapped_mem, len_to_copy);
         }
+        self.audit_write(
+            AuditedBuffer::Storage(storage_buffer.index()),
+            mapped_mem.cast_const().cast(),
+            len_to_copy * std::mem::size_of::<T>(),
+        );
     }
 
     pub fn write_immutable<T>(
@@ -5380,8 +11150,7 @@ impl<'f> Gpu<'f> {
         immutable_buffer: &mut ImmutableBufferHandle<T>,
         data: &[T],
     ) {
-        debug_assert!(data.len() <= immutable_buffer.len() as usize);
-        let len_to_copy = data.len().min(immutable_buffer.len() as usize);
+        let len_to_copy = self.checked_write_len(data.len(), immutable_buffer.capacity());
 
         let mapped_mem = self
             .storage_buffers
@@ -5390,16 +11159,107 @@ impl<'f> Gpu<'f> {
         unsafe {
             std::ptr::copy_nonoverlapping(data.as_ptr(), mapped_mem, len_to_copy);
         }
+        self.audit_write(
+            AuditedBuffer::Storage(immutable_buffer.index()),
+            mapped_mem.cast_const().cast(),
+            len_to_copy * std::mem::size_of::<T>(),
+        );
+    }
+
+    /// Copies `bytes` into the current frame's buffer.
+    ///
+    /// Bytes beyond the buffer's size are dropped, and the frame's draw call
+    /// returns `DrawError::StorageOverflow`.
+    pub fn write_raw_buffer(&mut self, raw_buffer: &mut RawBufferHandle, bytes: &[u8]) {
+        let len_to_copy = self.checked_write_len(bytes.len(), raw_buffer.size());
+
+        let mapped_mem = self
+            .storage_buffers
+            .get_mapped_mem_for_frame_raw(raw_buffer, self.ring_slot);
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), mapped_mem, len_to_copy);
+        }
+        self.audit_write(
+            AuditedBuffer::Storage(raw_buffer.index()),
+            mapped_mem.cast_const(),
+            len_to_copy,
+        );
+    }
+
+    /// The current frame's buffer, for writing elements in place instead of
+    /// building a slice for `write_storage`.
+    ///
+    /// The contents are whatever was written to this ring slot
+    /// `PRE_WAIT_RING_LEN` frames ago (zeroed before the first write), so
+    /// overwrite everything the GPU will read this frame. The memory may be
+    /// write-combined; avoid reading it back.
+    pub fn storage_slice_mut<T: GPUWrite>(
+        &mut self,
+        storage_buffer: &mut StorageBufferHandle<T>,
+    ) -> &mut [T] {
+        let len = storage_buffer.capacity() as usize;
+        let mapped_mem = self
+            .storage_buffers
+            .get_mapped_mem_for_frame(storage_buffer, self.ring_slot);
+        // hashed whole once gpu_update returns, after the caller's writes
+        self.audit_write(
+            AuditedBuffer::Storage(storage_buffer.index()),
+            mapped_mem.cast_const().cast(),
+            len * std::mem::size_of::<T>(),
+        );
+
+        // the ring slot's buffer is not read by any in-flight frame (see
+        // Renderer::draw_frame), it holds `len` initialized elements, and the
+        // slice borrows the storage for as long as it lives
+        unsafe { std::slice::from_raw_parts_mut(mapped_mem, len) }
+    }
+
+    /// notes a write for the determinism audit, if one is running
+    fn audit_write(&mut self, buffer: AuditedBuffer, bytes: *const u8, len: usize) {
+        if let Some(audited_writes) = &mut self.audited_writes {
+            audited_writes.push(WrittenRange { buffer, bytes, len });
+        }
+    }
+
+    /// the number of elements to copy, recording an overflow if they don't all fit
+    fn checked_write_len(&mut self, requested: usize, capacity: u32) -> usize {
+        let capacity = capacity as usize;
+        if requested > capacity && self.storage_overflow.is_none() {
+            self.storage_overflow = Some(DrawError::StorageOverflow {
+                requested,
+                capacity,
+            });
+        }
+
+        requested.min(capacity)
     }
 
     /// A pointer to the current frame's buffer
     pub fn addr<T>(&self, storage_buffer: &StorageBufferHandle<T>) -> Addr<T> {
+        #[cfg(debug_assertions)]
+        self.addressed_storage
+            .borrow_mut()
+            .push(storage_buffer.index());
         Addr::from_raw(
             self.storage_buffers
                 .get_device_address_for_frame(storage_buffer, self.ring_slot),
         )
     }
 
+    /// A pointer to the current frame's counters, zeroed when the frame began
+    pub fn counter_addr(&self, counter_buffer: &CounterBufferHandle) -> Addr<u32> {
+        Addr::from_raw(
+            self.counter_buffers
+                .get_device_address_for_frame(counter_buffer, self.ring_slot),
+        )
+    }
+
+    /// See [`Renderer::counts`]
+    pub fn counts(&self, counter_buffer: &CounterBufferHandle) -> &[u32] {
+        self.counter_buffers.counts(counter_buffer)
+    }
+
     /// A pointer to the current frame's ping-pong buffer
     ///
     /// We distinguish between current and previous only for gpu-only buffers,
@@ -5427,6 +11287,10 @@ impl<'f> Gpu<'f> {
         &self,
         immutable_buffer: &ImmutableBufferHandle<T>,
     ) -> ImmutableAddr<T> {
+        #[cfg(debug_assertions)]
+        self.addressed_storage
+            .borrow_mut()
+            .push(immutable_buffer.index());
         ImmutableAddr::from_raw(
             self.storage_buffers
                 .get_device_address_for_frame_immutable(immutable_buffer, self.ring_slot),
@@ -5434,6 +11298,17 @@ impl<'f> Gpu<'f> {
     }
 }
 
+/// Which of a pipeline's vk pipelines record_draws binds
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DrawPass {
+    Color,
+    DepthPrepass,
+    /// the pixel debugger's replay pipeline, built from the drawn pipeline
+    PixelDebug(vk::Pipeline),
+    /// color draws for one split-screen view, passing its index as the base instance
+    SplitView(u32),
+}
+
 #[derive(PartialEq, Eq)]
 enum ComputePlacement {
     /// Compute before graphics in the same command buffer
@@ -5455,10 +11330,39 @@ enum PendingComputeCommand {
     },
 }
 
+/// a texture update queued by [`Renderer::update_texture`]
+struct PendingTextureUpdate {
+    texture_index: usize,
+    region: TextureRegion,
+    staging_buffer: vk::Buffer,
+    staging_memory: vk_mem::Allocation,
+}
+
+/// an acquire queued by [`Renderer::acquire_external_texture`]
+struct PendingExternalAcquire {
+    texture_index: usize,
+    ready: vk::Semaphore,
+}
+
+/// a uniform buffer descriptor queued by [`Renderer::rebind_uniform_buffer`]
+struct PendingUniformRebind {
+    /// the descriptor set holding the binding, indexed by ring_slot
+    dst_sets: [vk::DescriptorSet; PRE_WAIT_RING_LEN],
+    binding: u32,
+    descriptor_count: u32,
+    range: vk::DeviceSize,
+    uniform_buffer: RawUniformBufferHandle,
+    /// ring slots whose descriptor set still points at the previous buffer
+    stale_slots: [bool; PRE_WAIT_RING_LEN],
+}
+
 enum PendingDrawCommand {
     Draw {
         pipeline_index: GraphicsPipelineIndex,
+        variant: Option<usize>,
         draw_call: DrawCallConfig,
+        /// the clip stack's top when queued; see [`clip`]
+        clip: Option<ClipRect>,
     },
 }
 
@@ -5475,12 +11379,21 @@ pub struct FrameRenderer<'f> {
     renderer: &'f mut Renderer,
     pending_draws: Vec<PendingDrawCommand>,
     pending_compute: Vec<PendingComputeCommand>,
+    culled: Vec<(GraphicsPipelineIndex, u32)>,
+    clip_stack: ClipStack,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum DrawError {
     #[error("error drawing frame: {0}")]
     DrawError(#[from] anyhow::Error),
+    #[error("storage buffer write of {requested} elements exceeds its capacity of {capacity}")]
+    StorageOverflow { requested: usize, capacity: usize },
+    #[error("pipeline for {shader} binds {resource}, which was dropped")]
+    OrphanedPipeline {
+        shader: String,
+        resource: ResourceId,
+    },
 }
 
 impl<'f> FrameRenderer<'f> {
@@ -5489,6 +11402,8 @@ impl<'f> FrameRenderer<'f> {
             renderer,
             pending_draws: vec![],
             pending_compute: vec![],
+            culled: vec![],
+            clip_stack: ClipStack::default(),
         }
     }
 
@@ -5496,6 +11411,12 @@ impl<'f> FrameRenderer<'f> {
         self.renderer.aspect_ratio
     }
 
+    /// Capture the next frame with RenderDoc; see [`Renderer::trigger_capture`].
+    /// RenderDoc starts capturing at this frame's present.
+    pub fn trigger_capture(&mut self) -> bool {
+        self.renderer.trigger_capture()
+    }
+
     pub fn window_resolution(&self) -> Vec2 {
         Vec2::new(self.renderer.width, self.renderer.height)
     }
@@ -5513,6 +11434,32 @@ impl<'f> FrameRenderer<'f> {
         self.renderer.render_scale
     }
 
+    /// see [`Renderer::request_redraw`]
+    pub fn request_redraw(&mut self) {
+        self.renderer.request_redraw();
+    }
+
+    /// see [`Renderer::set_relative_mouse_mode`]
+    pub fn set_relative_mouse_mode(&mut self, enabled: bool) {
+        self.renderer.set_relative_mouse_mode(enabled);
+    }
+
+    /// see [`Renderer::set_split_screen`]; takes effect for this frame's draws
+    pub fn set_split_screen(&mut self, views: &[SplitView]) {
+        self.renderer.set_split_screen(views);
+    }
+
+    /// Each split-screen view's aspect ratio, for its camera's projection
+    pub fn split_screen_aspect_ratios(&self) -> Vec<f32> {
+        let resolution = self.render_resolution();
+        self.renderer
+            .split_screen
+            .views
+            .iter()
+            .map(|view| view.rect.aspect_ratio(resolution))
+            .collect()
+    }
+
     pub fn dispatch(&mut self, pipeline: &PipelineHandle<Compute>, x: u32, y: u32, z: u32) {
         self.pending_compute.push(PendingComputeCommand::Dispatch {
             pipeline_index: pipeline.index(),
@@ -5520,6 +11467,28 @@ impl<'f> FrameRenderer<'f> {
         });
     }
 
+    /// Queue new contents for a region of a texture, copied before this
+    /// frame's draws; see [`Renderer::update_texture`]
+    pub fn update_texture(
+        &mut self,
+        texture: &TextureHandle,
+        region: TextureRegion,
+        bytes: &[u8],
+    ) -> anyhow::Result<()> {
+        self.renderer.update_texture(texture, region, bytes)
+    }
+
+    /// see [`Renderer::rebind_uniform_buffer`]; draws queued this frame read the new buffer
+    pub fn rebind_uniform_buffer<D: DrawCall<Index = GraphicsPipelineIndex>, T>(
+        &mut self,
+        pipeline: &PipelineHandle<D>,
+        uniform_index: usize,
+        uniform_buffer: &UniformBufferHandle<T>,
+    ) -> anyhow::Result<()> {
+        self.renderer
+            .rebind_uniform_buffer(pipeline, uniform_index, uniform_buffer)
+    }
+
     pub fn memory_barrier(
         &mut self,
         src_stage: vk::PipelineStageFlags2,
@@ -5555,7 +11524,7 @@ impl<'f> FrameRenderer<'f> {
         {
             VertexPipelineConfig::VertexAndIndexBuffers(vi_bufs) => vi_bufs.index_count,
             VertexPipelineConfig::SharedMesh(mesh_index) => {
-                self.renderer.meshes[mesh_index.raw()].index_count
+                self.renderer.gpu.meshes[mesh_index.raw()].index_count
             }
             VertexPipelineConfig::VertexCount => {
                 panic!("unexpected indexed draw call for non-index pipeline")
@@ -5568,7 +11537,9 @@ impl<'f> FrameRenderer<'f> {
         let index_count = self.whole_index_count(pipeline);
         self.pending_draws.push(PendingDrawCommand::Draw {
             pipeline_index: pipeline.index(),
+            variant: pipeline.variant(),
             draw_call: DrawCallConfig::IndexCount(index_count),
+            clip: self.clip_stack.current(),
         });
     }
 
@@ -5594,10 +11565,12 @@ impl<'f> FrameRenderer<'f> {
 
         self.pending_draws.push(PendingDrawCommand::Draw {
             pipeline_index: pipeline.index(),
+            variant: pipeline.variant(),
             draw_call: DrawCallConfig::IndexRange {
                 first_index,
                 index_count,
             },
+            clip: self.clip_stack.current(),
         });
     }
 
@@ -5609,10 +11582,84 @@ impl<'f> FrameRenderer<'f> {
     ) {
         self.pending_draws.push(PendingDrawCommand::Draw {
             pipeline_index: pipeline.index(),
+            variant: pipeline.variant(),
             draw_call: DrawCallConfig::VertexCount(vertex_count),
+            clip: self.clip_stack.current(),
+        });
+    }
+
+    /// queue a vertex-count draw of [first_vertex, first_vertex + vertex_count);
+    /// SV_VertexID starts at first_vertex
+    pub fn queue_draw_vertex_range(
+        &mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        first_vertex: u32,
+        vertex_count: u32,
+    ) {
+        self.pending_draws.push(PendingDrawCommand::Draw {
+            pipeline_index: pipeline.index(),
+            variant: pipeline.variant(),
+            draw_call: DrawCallConfig::VertexRange {
+                first_vertex,
+                vertex_count,
+            },
+            clip: self.clip_stack.current(),
+        });
+    }
+
+    /// queue a mesh shader draw of `group_count` task workgroups
+    /// (or mesh workgroups, for shaders without a task stage)
+    pub fn queue_draw_mesh_tasks(
+        &mut self,
+        pipeline: &PipelineHandle<DrawMeshTasks>,
+        group_count: [u32; 3],
+    ) {
+        self.pending_draws.push(PendingDrawCommand::Draw {
+            pipeline_index: pipeline.index(),
+            variant: pipeline.variant(),
+            draw_call: DrawCallConfig::MeshTasks(group_count),
+            clip: self.clip_stack.current(),
         });
     }
 
+    /// Clip the draws queued from now until the matching [`FrameRenderer::pop_clip`]
+    /// to `rect`, intersected with any clips already pushed; see [`clip`].
+    /// Sprites and text are batched until they're queued, so they take
+    /// their clips from
+    /// [`SpriteBatcher::push_clip`](crate::sprite_batcher::SpriteBatcher::push_clip)
+    /// instead.
+    pub fn push_clip(&mut self, rect: ClipRect) {
+        self.clip_stack.push(rect);
+    }
+
+    /// End the most recent [`FrameRenderer::push_clip`]
+    pub fn pop_clip(&mut self) {
+        let popped = self.clip_stack.pop();
+        debug_assert!(popped.is_some(), "pop_clip without a matching push_clip");
+    }
+
+    /// Queue draws clipped to `rect`, popping the clip afterwards
+    pub fn with_clip(&mut self, rect: ClipRect, queue_draws: impl FnOnce(&mut Self)) {
+        self.push_clip(rect);
+        queue_draws(self);
+        self.pop_clip();
+    }
+
+    /// Count draws that culling skipped instead of queueing, so they show up
+    /// as submitted but not drawn in [`Renderer::frame_stats`]
+    pub fn record_culled<D: DrawCall<Index = GraphicsPipelineIndex>>(
+        &mut self,
+        pipeline: &PipelineHandle<D>,
+        count: u32,
+    ) {
+        self.culled.push((pipeline.index(), count));
+    }
+
+    /// See [`Renderer::frame_stats`]
+    pub fn last_frame_stats(&self) -> &FrameStats {
+        &self.renderer.frame_stats
+    }
+
     /// submit all queued draws as this frame's rendering
     pub fn submit_draws(self, gpu_update: impl FnOnce(&mut Gpu)) -> Result<(), DrawError> {
         self.draw_frame(None, gpu_update)
@@ -5637,6 +11684,16 @@ impl<'f> FrameRenderer<'f> {
         self.submit_draws(gpu_update)
     }
 
+    pub fn draw_mesh_tasks(
+        mut self,
+        pipeline_handle: &PipelineHandle<DrawMeshTasks>,
+        group_count: [u32; 3],
+        gpu_update: impl FnOnce(&mut Gpu),
+    ) -> Result<(), DrawError> {
+        self.queue_draw_mesh_tasks(pipeline_handle, group_count);
+        self.submit_draws(gpu_update)
+    }
+
     pub fn draw_vertex_count_with_picking(
         mut self,
         main_pipeline: &PipelineHandle<DrawVertexCount>,
@@ -5652,11 +11709,9 @@ impl<'f> FrameRenderer<'f> {
             "draw_vertex_count_with_picking does not support queued draws"
         );
 
-        let render_scale = self.renderer.render_scale;
-        let mouse_pixel = [
-            (mouse_position[0] * render_scale) as u32,
-            (mouse_position[1] * render_scale) as u32,
-        ];
+        let mouse_pixel = self
+            .renderer
+            .window_to_render_pixel(Vec2::from(mouse_position));
         let picking_config = PickingDrawConfig {
             picking_handle: PickingPipelineHandle {
                 index: picking_pipeline.index,
@@ -5671,19 +11726,146 @@ impl<'f> FrameRenderer<'f> {
         self.renderer.last_picked_object_id
     }
 
+    /// Read back this frame's final image; see [`Renderer::request_screenshot`]
+    pub fn request_screenshot(&mut self) -> ReadbackId {
+        self.renderer.request_screenshot()
+    }
+
+    /// Read back a GPU-only buffer at the end of this frame;
+    /// see [`Renderer::read_gpu_only_buffer`]
+    pub fn read_gpu_only_buffer<T>(&mut self, handle: &GpuOnlyBufferHandle<T>) -> ReadbackId {
+        self.renderer.read_gpu_only_buffer(handle)
+    }
+
+    /// See [`Renderer::poll_readbacks`]
+    pub fn poll_readbacks(&mut self) -> Vec<Readback> {
+        self.renderer.poll_readbacks()
+    }
+
+    /// See [`Renderer::debug_read_uniform`]
+    pub fn debug_read_uniform<T: Clone>(
+        &self,
+        uniform_buffer: &UniformBufferHandle<T>,
+    ) -> Option<T> {
+        self.renderer.debug_read_uniform(uniform_buffer)
+    }
+
+    /// See [`Renderer::debug_read_storage`]
+    pub fn debug_read_storage<T: Clone>(
+        &self,
+        storage_buffer: &StorageBufferHandle<T>,
+    ) -> Option<Vec<T>> {
+        self.renderer.debug_read_storage(storage_buffer)
+    }
+
+    // only the editor's scene overlay queues draws here
+    #[cfg_attr(not(feature = "editor"), allow(unused_mut))]
     fn draw_frame(
-        self,
+        mut self,
         picking_config: Option<PickingDrawConfig>,
         gpu_update: impl FnOnce(&mut Gpu),
     ) -> Result<(), DrawError> {
-        self.renderer
-            .draw_frame(
-                self.pending_draws,
-                picking_config,
-                self.pending_compute,
-                gpu_update,
-            )
-            .map_err(DrawError::DrawError)
+        self.check_orphaned_pipelines(picking_config.as_ref())?;
+
+        // the grid and gizmos go over the game's draws, then back for the next edit ui
+        #[cfg(feature = "editor")]
+        let mut scene_overlay = self.renderer.scene_overlay.take();
+        #[cfg(feature = "editor")]
+        if let Some(overlay) = &mut scene_overlay {
+            overlay.queue_draws(&mut self);
+        }
+
+        let renderer = &*self.renderer;
+        let mut frame_stats = FrameStats::count(
+            self.pending_draws.iter().map(|cmd| match cmd {
+                PendingDrawCommand::Draw { pipeline_index, .. } => *pipeline_index,
+            }),
+            &self.culled,
+            |index| {
+                let shader = &renderer.gpu.pipelines.get_by_index(index).shader;
+                debug::clean_shader_name(shader.source_file_name()).to_string()
+            },
+        );
+
+        // the frame is still submitted with truncated data;
+        // stopping partway through would leave the acquired image unpresented
+        let mut storage_overflow = None;
+        let drawn = self.renderer.draw_frame(
+            self.pending_draws,
+            picking_config,
+            self.pending_compute,
+            |gpu| {
+                gpu_update(gpu);
+                #[cfg(feature = "editor")]
+                if let Some(overlay) = &mut scene_overlay {
+                    overlay.write(gpu);
+                }
+                storage_overflow = gpu.storage_overflow.take();
+            },
+        );
+        #[cfg(feature = "editor")]
+        {
+            self.renderer.scene_overlay = scene_overlay;
+        }
+        drawn.inspect_err(|e| self.renderer.report_device_lost(e))?;
+        frame_stats.display_latency = self
+            .renderer
+            .presenter
+            .present_wait
+            .as_ref()
+            .and_then(PresentWait::display_latency);
+        self.renderer.frame_stats = frame_stats;
+
+        match storage_overflow {
+            Some(overflow) => Err(overflow),
+            None => Ok(()),
+        }
+    }
+}
+
+/// the storage indices of a pipeline's uniform buffers, for debug write checks
+#[cfg(debug_assertions)]
+fn raw_handle_indices(handles: &[RawUniformBufferHandle]) -> Vec<usize> {
+    handles.iter().map(RawUniformBufferHandle::index).collect()
+}
+
+impl FrameRenderer<'_> {
+    /// Fails before anything is recorded if a queued pipeline binds a dropped resource
+    fn check_orphaned_pipelines(
+        &self,
+        picking_config: Option<&PickingDrawConfig>,
+    ) -> Result<(), DrawError> {
+        let pipelines = &self.renderer.gpu.pipelines;
+        let compute_pipelines = &self.renderer.gpu.compute_pipelines;
+
+        let draws = self.pending_draws.iter().map(|cmd| match cmd {
+            PendingDrawCommand::Draw { pipeline_index, .. } => {
+                let pipeline = pipelines.get_by_index(*pipeline_index);
+                (pipeline.orphaned_by, pipeline.shader.source_file_name())
+            }
+        });
+        let picking = picking_config.map(|config| {
+            let pipeline = pipelines.get_picking(&config.picking_handle);
+            (pipeline.orphaned_by, pipeline.shader.source_file_name())
+        });
+        let dispatches = self.pending_compute.iter().filter_map(|cmd| match cmd {
+            PendingComputeCommand::Dispatch { pipeline_index, .. } => {
+                let pipeline = compute_pipelines.get_by_index(*pipeline_index);
+                Some((pipeline.orphaned_by, pipeline.shader.source_file_name()))
+            }
+            _ => None,
+        });
+
+        for (orphaned_by, shader) in draws.chain(picking).chain(dispatches) {
+            if let Some(resource) = orphaned_by {
+                return Err(DrawError::OrphanedPipeline {
+                    shader: debug::clean_shader_name(shader).to_string(),
+                    resource,
+                });
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -5698,8 +11880,17 @@ fn index_range_in_bounds(first_index: u32, index_count: u32, total_index_count:
 #[derive(Debug, Clone, Copy)]
 enum DrawCallConfig {
     VertexCount(u32),
+    VertexRange {
+        first_vertex: u32,
+        vertex_count: u32,
+    },
+    /// task (or mesh) workgroup counts
+    MeshTasks([u32; 3]),
     IndexCount(u32),
-    IndexRange { first_index: u32, index_count: u32 },
+    IndexRange {
+        first_index: u32,
+        index_count: u32,
+    },
 }
 
 struct PickingDrawConfig {
@@ -5712,10 +11903,93 @@ mod tests {
     use ash::vk;
 
     use super::{
-        BlendMode, CullMode, DepthCompare, RasterState, index_range_in_bounds, vk_color_write_mask,
-        vk_cull_mode, vk_depth_compare,
+        BlendMode, CounterBufferStorage, CullMode, DepthCompare, DrawError, FrameStats, Gpu,
+        GraphicsPipelineIndex, LayoutDescription, PipelineIndex, Presentation, RasterState,
+        StorageBufferStorage, TextureDescription, TextureRegion, UniformBufferDescription,
+        UniformBufferStorage, UniformElementType, calculate_present_rect, index_range_in_bounds,
+        texel_row_length, validate_uniform_element_types, vk_color_write_mask, vk_cull_mode,
+        vk_depth_compare,
     };
 
+    #[test]
+    fn frame_stats_count_culled_draws_as_submitted() {
+        let sprites = GraphicsPipelineIndex::from_raw(0);
+        let meshes = GraphicsPipelineIndex::from_raw(1);
+        let shader_name = |index: GraphicsPipelineIndex| format!("pipeline_{}", index.raw());
+
+        let stats = FrameStats::count(
+            [sprites, sprites, meshes, sprites],
+            &[(meshes, 5), (sprites, 2), (meshes, 1)],
+            shader_name,
+        );
+
+        let counts: Vec<_> = stats
+            .pipelines
+            .iter()
+            .map(|pipeline| {
+                (
+                    pipeline.shader_name.as_str(),
+                    pipeline.submitted,
+                    pipeline.drawn,
+                )
+            })
+            .collect();
+        assert_eq!(counts, [("pipeline_0", 5, 3), ("pipeline_1", 7, 1)]);
+        assert_eq!(stats.pipelines[1].culled(), 6);
+        assert_eq!(stats.draw_calls(), 4);
+
+        // a pipeline whose draws were all culled is still listed
+        let stats = FrameStats::count([], &[(meshes, 3)], shader_name);
+        assert_eq!(stats.pipelines[0].submitted, 3);
+        assert_eq!(stats.draw_calls(), 0);
+    }
+
+    #[test]
+    fn integer_scaled_presentation_is_centered_between_borders() {
+        let virtual_resolution = vk::Extent2D {
+            width: 320,
+            height: 180,
+        };
+        let window = vk::Extent2D {
+            width: 1000,
+            height: 700,
+        };
+
+        // 3x fits (960x540), 3.125x would be the fractional fit
+        let rect = calculate_present_rect(
+            window,
+            virtual_resolution,
+            Presentation::pixel_perfect(320, 180),
+        );
+        assert_eq!((rect.offset.x, rect.offset.y), (20, 80));
+        assert_eq!((rect.extent.width, rect.extent.height), (960, 540));
+
+        let fitted = Presentation::Virtual {
+            width: 320,
+            height: 180,
+            integer_scale: false,
+        };
+        let rect = calculate_present_rect(window, virtual_resolution, fitted);
+        assert_eq!((rect.offset.x, rect.offset.y), (0, 69));
+        assert_eq!((rect.extent.width, rect.extent.height), (1000, 562));
+
+        // smaller than the virtual resolution still fits, at a fraction
+        let small_window = vk::Extent2D {
+            width: 160,
+            height: 120,
+        };
+        let rect = calculate_present_rect(
+            small_window,
+            virtual_resolution,
+            Presentation::pixel_perfect(320, 180),
+        );
+        assert_eq!((rect.extent.width, rect.extent.height), (160, 90));
+        assert_eq!((rect.offset.x, rect.offset.y), (0, 15));
+
+        let rect = calculate_present_rect(window, virtual_resolution, Presentation::Stretch);
+        assert_eq!(rect.extent, window);
+    }
+
     #[test]
     fn cull_mode_mapping() {
         assert_eq!(vk_cull_mode(CullMode::Back), vk::CullModeFlags::BACK);
@@ -5733,6 +12007,10 @@ mod tests {
             vk_depth_compare(DepthCompare::LessEqual),
             (true, vk::CompareOp::LESS_OR_EQUAL)
         );
+        assert_eq!(
+            vk_depth_compare(DepthCompare::Equal),
+            (true, vk::CompareOp::EQUAL)
+        );
         assert_eq!(
             vk_depth_compare(DepthCompare::Always),
             (true, vk::CompareOp::ALWAYS)
@@ -5762,6 +12040,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn depth_prepass_splits_raster_state() {
+        let raster_state = RasterState {
+            blend: BlendMode::Alpha,
+            cull: CullMode::None,
+            depth_test: DepthCompare::LessEqual,
+            depth_write: false,
+            color_write: [true; 4],
+        };
+
+        let prepass = raster_state.for_depth_prepass();
+        assert_eq!(prepass.depth_test, DepthCompare::LessEqual);
+        assert!(prepass.depth_write);
+        assert_eq!(prepass.color_write, [false; 4]);
+        assert_eq!(prepass.cull, CullMode::None);
+
+        let color = raster_state.after_depth_prepass();
+        assert_eq!(color.depth_test, DepthCompare::Equal);
+        assert!(!color.depth_write);
+        assert_eq!(color.blend, BlendMode::Alpha);
+        assert_eq!(color.color_write, [true; 4]);
+    }
+
+    #[test]
+    fn pixel_debug_replay_keeps_the_nearest_draw_unblended() {
+        let raster_state = RasterState {
+            blend: BlendMode::Additive,
+            cull: CullMode::Front,
+            depth_test: DepthCompare::Disabled,
+            depth_write: false,
+            color_write: [true, true, true, false],
+        };
+
+        let replay = raster_state.for_pixel_debug();
+        assert_eq!(replay.blend, BlendMode::Opaque);
+        assert_eq!(replay.depth_test, DepthCompare::Always);
+        assert!(replay.depth_write);
+        assert_eq!(replay.color_write, [true; 4]);
+        assert_eq!(replay.cull, CullMode::Front);
+
+        let after_prepass = raster_state.after_depth_prepass().for_pixel_debug();
+        assert_eq!(after_prepass.depth_test, DepthCompare::LessEqual);
+    }
+
     /// The default must reproduce the pipeline state that was hardcoded in
     /// create_graphics_pipeline before raster state became configurable.
     #[test]
@@ -5797,4 +12119,143 @@ mod tests {
         // empty range at the end is in bounds
         assert!(index_range_in_bounds(108, 0, 108));
     }
+
+    #[test]
+    fn texel_row_length_validation() {
+        // tightly packed rows copy with a row length of 0
+        assert_eq!(texel_row_length(4, 2, 2, 8, 16).unwrap(), 0);
+        // padded rows copy with the stride in texels
+        assert_eq!(texel_row_length(3, 2, 4, 16, 32).unwrap(), 4);
+        // stride shorter than a row
+        assert!(texel_row_length(4, 2, 4, 12, 24).is_err());
+        // stride that splits a texel
+        assert!(texel_row_length(3, 2, 4, 14, 28).is_err());
+        // too few or too many bytes
+        assert!(texel_row_length(4, 2, 1, 4, 7).is_err());
+        assert!(texel_row_length(4, 2, 1, 4, 9).is_err());
+        // empty
+        assert!(texel_row_length(0, 0, 4, 0, 0).is_err());
+        assert!(texel_row_length(0, 2, 4, 0, 0).is_err());
+        assert!(texel_row_length(4, 0, 4, 16, 0).is_err());
+    }
+
+    #[test]
+    fn texture_region_bounds() {
+        let extent = vk::Extent2D {
+            width: 64,
+            height: 32,
+        };
+        assert!(TextureRegion::new(0, 0, 64, 32).fits_within(extent));
+        assert!(TextureRegion::new(60, 30, 4, 2).fits_within(extent));
+        // past the right or bottom edge
+        assert!(!TextureRegion::new(61, 0, 4, 1).fits_within(extent));
+        assert!(!TextureRegion::new(0, 31, 1, 2).fits_within(extent));
+        // empty
+        assert!(!TextureRegion::new(0, 0, 0, 1).fits_within(extent));
+        // overflowing offsets
+        assert!(!TextureRegion::new(u32::MAX, 0, 2, 1).fits_within(extent));
+    }
+
+    #[test]
+    fn uniform_buffers_match_reflection() {
+        let uniform = |size| {
+            LayoutDescription::Uniform(UniformBufferDescription {
+                size,
+                binding: 0,
+                descriptor_count: 1,
+            })
+        };
+        let texture = LayoutDescription::Texture(TextureDescription {
+            binding: 1,
+            descriptor_count: 1,
+            sampled_image_only: false,
+        });
+        let layout_bindings = vec![vec![uniform(64), texture], vec![uniform(16)]];
+        let names = ["params", "lights"];
+
+        let mat4 = UniformElementType::of::<[f32; 16]>();
+        let vec4 = UniformElementType::of::<[f32; 4]>();
+
+        assert!(
+            validate_uniform_element_types("test.slang", &names, &layout_bindings, &[mat4, vec4])
+                .is_ok()
+        );
+
+        // swapped buffers name the first mismatched parameter
+        let error =
+            validate_uniform_element_types("test.slang", &names, &layout_bindings, &[vec4, mat4])
+                .unwrap_err();
+        assert!(error.to_string().contains("`params`"), "{error}");
+
+        // too few or too many buffers
+        let error = validate_uniform_element_types("test.slang", &names, &layout_bindings, &[mat4])
+            .unwrap_err();
+        assert!(error.to_string().contains("`lights`"), "{error}");
+        assert!(
+            validate_uniform_element_types(
+                "test.slang",
+                &names,
+                &layout_bindings,
+                &[mat4, vec4, vec4]
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn storage_writes_record_the_first_overflow() {
+        let mut uniform_buffers = UniformBufferStorage::new();
+        let mut storage_buffers = StorageBufferStorage::new();
+        let counter_buffers = CounterBufferStorage::new();
+        let mut pipelines = PipelineStorage::new();
+        let mut gpu = Gpu {
+            ring_slot: 0,
+            uniform_buffers: &mut uniform_buffers,
+            storage_buffers: &mut storage_buffers,
+            counter_buffers: &counter_buffers,
+            pipelines: &mut pipelines,
+            storage_overflow: None,
+            audited_writes: None,
+            #[cfg(debug_assertions)]
+            addressed_storage: Default::default(),
+        };
+
+        assert_eq!(gpu.checked_write_len(4, 4), 4);
+        assert!(gpu.storage_overflow.is_none());
+
+        assert_eq!(gpu.checked_write_len(6, 4), 4);
+        assert_eq!(gpu.checked_write_len(9, 8), 8);
+        assert!(matches!(
+            gpu.storage_overflow,
+            Some(DrawError::StorageOverflow {
+                requested: 6,
+                capacity: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn rebinding_swaps_only_the_nth_uniform_buffer() {
+        let mut resources = vec![
+            ResourceId::Texture(0),
+            ResourceId::UniformBuffer(3),
+            ResourceId::Texture(1),
+            ResourceId::UniformBuffer(4),
+        ];
+
+        rebind_resource(&mut resources, 1, 9);
+        assert_eq!(
+            resources,
+            [
+                ResourceId::Texture(0),
+                ResourceId::UniformBuffer(3),
+                ResourceId::Texture(1),
+                ResourceId::UniformBuffer(9),
+            ]
+        );
+
+        // out of range leaves the resources alone
+        rebind_resource(&mut resources, 2, 10);
+        assert_eq!(resources[3], ResourceId::UniformBuffer(9));
+    }
 }
@@ -0,0 +1,361 @@
+use ash::vk;
+use glam::{Mat4, Vec3};
+
+use super::gpu_write::write_to_gpu_buffer;
+use super::{
+    BufferMemory, begin_single_time_commands, create_memory_buffer, end_single_time_commands,
+};
+
+/// A bottom-level acceleration structure over one mesh's triangles,
+/// built with [`super::Renderer::build_blas`].
+/// It's only used as the geometry of a [`TlasInstance`].
+#[derive(Debug)]
+pub struct BlasHandle {
+    pub(super) index: usize,
+}
+
+/// A top-level acceleration structure, built with [`super::Renderer::build_tlas`].
+/// Shaders see it as a `RaytracingAccelerationStructure` parameter,
+/// for use with slang's `RayQuery`.
+#[derive(Debug)]
+pub struct AccelerationStructureHandle {
+    pub(super) index: usize,
+}
+
+/// One placement of a blas in a tlas
+pub struct TlasInstance<'b> {
+    pub blas: &'b BlasHandle,
+    pub transform: Mat4,
+}
+
+/// The extension loader and limits for building acceleration structures;
+/// only present when the game enabled [`super::DeviceExtensions::with_ray_query`]
+pub(super) struct RayQuery {
+    pub(super) ext: ash::khr::acceleration_structure::Device,
+    scratch_alignment: u64,
+}
+
+impl RayQuery {
+    pub(super) fn init(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+        device: &ash::Device,
+    ) -> Self {
+        let mut acceleration_structure_properties =
+            vk::PhysicalDeviceAccelerationStructurePropertiesKHR::default();
+        let mut properties = vk::PhysicalDeviceProperties2::default()
+            .push_next(&mut acceleration_structure_properties);
+        unsafe { instance.get_physical_device_properties2(physical_device, &mut properties) };
+
+        Self {
+            ext: ash::khr::acceleration_structure::Device::new(instance, device),
+            scratch_alignment: acceleration_structure_properties
+                .min_acceleration_structure_scratch_offset_alignment
+                as u64,
+        }
+    }
+}
+
+pub(super) struct AccelerationStructure {
+    pub(super) acceleration_structure: vk::AccelerationStructureKHR,
+    buffer: vk::Buffer,
+    allocation: vk_mem::Allocation,
+    device_address: vk::DeviceAddress,
+}
+
+pub(super) struct AccelerationStructureStorage {
+    blases: Vec<AccelerationStructure>,
+    tlases: Vec<AccelerationStructure>,
+}
+
+impl AccelerationStructureStorage {
+    pub fn new() -> Self {
+        Self {
+            blases: vec![],
+            tlases: vec![],
+        }
+    }
+
+    pub fn add_blas(&mut self, blas: AccelerationStructure) -> BlasHandle {
+        let handle = BlasHandle {
+            index: self.blases.len(),
+        };
+        self.blases.push(blas);
+
+        handle
+    }
+
+    pub fn add_tlas(&mut self, tlas: AccelerationStructure) -> AccelerationStructureHandle {
+        let handle = AccelerationStructureHandle {
+            index: self.tlases.len(),
+        };
+        self.tlases.push(tlas);
+
+        handle
+    }
+
+    pub fn get_blas(&self, handle: &BlasHandle) -> &AccelerationStructure {
+        &self.blases[handle.index]
+    }
+
+    pub fn get_tlas(&self, handle: &AccelerationStructureHandle) -> &AccelerationStructure {
+        &self.tlases[handle.index]
+    }
+
+    pub fn take_all(&mut self) -> Vec<AccelerationStructure> {
+        let mut all = std::mem::take(&mut self.tlases);
+        all.append(&mut self.blases);
+        all
+    }
+}
+
+/// The renderer state needed for a one-shot build on the graphics queue
+pub(super) struct BuildContext<'r> {
+    pub(super) device: &'r ash::Device,
+    pub(super) allocator: &'r vk_mem::Allocator,
+    pub(super) command_pool: vk::CommandPool,
+    pub(super) graphics_queue: vk::Queue,
+    pub(super) ray_query: &'r RayQuery,
+}
+
+impl BuildContext<'_> {
+    pub(super) fn build_blas(
+        &self,
+        positions: &[Vec3],
+        indices: &[u32],
+    ) -> anyhow::Result<AccelerationStructure> {
+        anyhow::ensure!(
+            !positions.is_empty() && !indices.is_empty() && indices.len() % 3 == 0,
+            "a blas needs at least one triangle, got {} positions and {} indices",
+            positions.len(),
+            indices.len()
+        );
+
+        let input_usage = vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR
+            | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS;
+        let floats: Vec<f32> = positions.iter().flat_map(|p| p.to_array()).collect();
+        let (vertex_buffer, mut vertex_memory, vertex_address) =
+            self.create_input_buffer(&floats, input_usage)?;
+        let (index_buffer, mut index_memory, index_address) =
+            self.create_input_buffer(indices, input_usage)?;
+
+        let triangles = vk::AccelerationStructureGeometryTrianglesDataKHR::default()
+            .vertex_format(vk::Format::R32G32B32_SFLOAT)
+            .vertex_data(vk::DeviceOrHostAddressConstKHR {
+                device_address: vertex_address,
+            })
+            .vertex_stride(std::mem::size_of::<Vec3>() as u64)
+            .max_vertex(positions.len() as u32 - 1)
+            .index_type(vk::IndexType::UINT32)
+            .index_data(vk::DeviceOrHostAddressConstKHR {
+                device_address: index_address,
+            });
+        let geometry = vk::AccelerationStructureGeometryKHR::default()
+            .geometry_type(vk::GeometryTypeKHR::TRIANGLES)
+            .geometry(vk::AccelerationStructureGeometryDataKHR { triangles })
+            .flags(vk::GeometryFlagsKHR::OPAQUE);
+
+        let blas = self.build(
+            vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL,
+            geometry,
+            indices.len() as u32 / 3,
+        );
+
+        unsafe {
+            self.allocator
+                .destroy_buffer(vertex_buffer, &mut vertex_memory);
+            self.allocator
+                .destroy_buffer(index_buffer, &mut index_memory);
+        }
+
+        blas
+    }
+
+    pub(super) fn build_tlas(
+        &self,
+        instances: &[(vk::DeviceAddress, Mat4)],
+    ) -> anyhow::Result<AccelerationStructure> {
+        anyhow::ensure!(!instances.is_empty(), "a tlas needs at least one instance");
+
+        let instances: Vec<vk::AccelerationStructureInstanceKHR> = instances
+            .iter()
+            .enumerate()
+            .map(|(i, &(blas_address, transform))| {
+                // vulkan wants the top 3 rows, row-major
+                let rows = transform.transpose().to_cols_array();
+                let mut matrix = [0.0; 12];
+                matrix.copy_from_slice(&rows[..12]);
+
+                vk::AccelerationStructureInstanceKHR {
+                    transform: vk::TransformMatrixKHR { matrix },
+                    instance_custom_index_and_mask: vk::Packed24_8::new(i as u32, 0xff),
+                    instance_shader_binding_table_record_offset_and_flags: vk::Packed24_8::new(
+                        0,
+                        vk::GeometryInstanceFlagsKHR::TRIANGLE_FACING_CULL_DISABLE.as_raw() as u8,
+                    ),
+                    acceleration_structure_reference: vk::AccelerationStructureReferenceKHR {
+                        device_handle: blas_address,
+                    },
+                }
+            })
+            .collect();
+
+        let (instance_buffer, mut instance_memory, instance_address) = self.create_input_buffer(
+            &instances,
+            vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR
+                | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+        )?;
+
+        let instances_data = vk::AccelerationStructureGeometryInstancesDataKHR::default()
+            .array_of_pointers(false)
+            .data(vk::DeviceOrHostAddressConstKHR {
+                device_address: instance_address,
+            });
+        let geometry = vk::AccelerationStructureGeometryKHR::default()
+            .geometry_type(vk::GeometryTypeKHR::INSTANCES)
+            .geometry(vk::AccelerationStructureGeometryDataKHR {
+                instances: instances_data,
+            });
+
+        let tlas = self.build(
+            vk::AccelerationStructureTypeKHR::TOP_LEVEL,
+            geometry,
+            instances.len() as u32,
+        );
+
+        unsafe {
+            self.allocator
+                .destroy_buffer(instance_buffer, &mut instance_memory);
+        }
+
+        tlas
+    }
+
+    fn build(
+        &self,
+        ty: vk::AccelerationStructureTypeKHR,
+        geometry: vk::AccelerationStructureGeometryKHR,
+        primitive_count: u32,
+    ) -> anyhow::Result<AccelerationStructure> {
+        let ext = &self.ray_query.ext;
+        let geometries = [geometry];
+        let mut build_info = vk::AccelerationStructureBuildGeometryInfoKHR::default()
+            .ty(ty)
+            .flags(vk::BuildAccelerationStructureFlagsKHR::PREFER_FAST_TRACE)
+            .mode(vk::BuildAccelerationStructureModeKHR::BUILD)
+            .geometries(&geometries);
+
+        let mut sizes = vk::AccelerationStructureBuildSizesInfoKHR::default();
+        unsafe {
+            ext.get_acceleration_structure_build_sizes(
+                vk::AccelerationStructureBuildTypeKHR::DEVICE,
+                &build_info,
+                &[primitive_count],
+                &mut sizes,
+            )
+        };
+
+        let (buffer, allocation) = create_memory_buffer(
+            self.allocator,
+            sizes.acceleration_structure_size,
+            vk::BufferUsageFlags::ACCELERATION_STRUCTURE_STORAGE_KHR
+                | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            BufferMemory::DeviceLocal,
+        )?;
+
+        let create_info = vk::AccelerationStructureCreateInfoKHR::default()
+            .buffer(buffer)
+            .size(sizes.acceleration_structure_size)
+            .ty(ty);
+        let acceleration_structure =
+            unsafe { ext.create_acceleration_structure(&create_info, None)? };
+
+        // over-allocated so the build can start at an aligned address
+        let (scratch_buffer, mut scratch_memory) = create_memory_buffer(
+            self.allocator,
+            sizes.build_scratch_size + self.ray_query.scratch_alignment,
+            vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            BufferMemory::DeviceLocal,
+        )?;
+        let scratch_address = self
+            .buffer_device_address(scratch_buffer)
+            .next_multiple_of(self.ray_query.scratch_alignment.max(1));
+
+        build_info = build_info
+            .dst_acceleration_structure(acceleration_structure)
+            .scratch_data(vk::DeviceOrHostAddressKHR {
+                device_address: scratch_address,
+            });
+        let build_range =
+            vk::AccelerationStructureBuildRangeInfoKHR::default().primitive_count(primitive_count);
+
+        let command_buffer = begin_single_time_commands(self.device, self.command_pool)?;
+        unsafe {
+            ext.cmd_build_acceleration_structures(command_buffer, &[build_info], &[&[build_range]])
+        };
+        // waits for the device to go idle, so the scratch buffer is free to destroy
+        end_single_time_commands(
+            self.device,
+            self.command_pool,
+            self.graphics_queue,
+            command_buffer,
+        )?;
+
+        unsafe {
+            self.allocator
+                .destroy_buffer(scratch_buffer, &mut scratch_memory)
+        };
+
+        let device_address = unsafe {
+            ext.get_acceleration_structure_device_address(
+                &vk::AccelerationStructureDeviceAddressInfoKHR::default()
+                    .acceleration_structure(acceleration_structure),
+            )
+        };
+
+        Ok(AccelerationStructure {
+            acceleration_structure,
+            buffer,
+            allocation,
+            device_address,
+        })
+    }
+
+    fn create_input_buffer<T: super::gpu_write::GPUWrite>(
+        &self,
+        data: &[T],
+        usage: vk::BufferUsageFlags,
+    ) -> anyhow::Result<(vk::Buffer, vk_mem::Allocation, vk::DeviceAddress)> {
+        let (buffer, mut allocation) = create_memory_buffer(
+            self.allocator,
+            std::mem::size_of_val(data) as u64,
+            usage,
+            BufferMemory::Staging,
+        )?;
+        unsafe { write_to_gpu_buffer(self.allocator, &mut allocation, data)? };
+
+        Ok((buffer, allocation, self.buffer_device_address(buffer)))
+    }
+
+    fn buffer_device_address(&self, buffer: vk::Buffer) -> vk::DeviceAddress {
+        unsafe {
+            self.device
+                .get_buffer_device_address(&vk::BufferDeviceAddressInfo::default().buffer(buffer))
+        }
+    }
+}
+
+impl AccelerationStructure {
+    pub(super) fn device_address(&self) -> vk::DeviceAddress {
+        self.device_address
+    }
+
+    pub(super) fn destroy(mut self, ray_query: &RayQuery, allocator: &vk_mem::Allocator) {
+        unsafe {
+            ray_query
+                .ext
+                .destroy_acceleration_structure(self.acceleration_structure, None);
+            allocator.destroy_buffer(self.buffer, &mut self.allocation);
+        }
+    }
+}
@@ -35,6 +35,16 @@ impl<T> Clone for Addr<T> {
 
 impl<T> Copy for Addr<T> {}
 
+/// the null address, for generated params' `Default`
+impl<T> Default for Addr<T> {
+    fn default() -> Self {
+        Self {
+            address: 0,
+            _pointee: PhantomData,
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for Addr<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -99,6 +109,16 @@ impl<T> Clone for ReadAddr<T> {
 
 impl<T> Copy for ReadAddr<T> {}
 
+/// the null address, for generated params' `Default`
+impl<T> Default for ReadAddr<T> {
+    fn default() -> Self {
+        Self {
+            address: 0,
+            _pointee: PhantomData,
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for ReadAddr<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -167,6 +187,16 @@ impl<T> Clone for ImmutableAddr<T> {
 
 impl<T> Copy for ImmutableAddr<T> {}
 
+/// the null address, for generated params' `Default`
+impl<T> Default for ImmutableAddr<T> {
+    fn default() -> Self {
+        Self {
+            address: 0,
+            _pointee: PhantomData,
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for ImmutableAddr<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -0,0 +1,177 @@
+//! Fullscreen blits: a textured copy with an optional flip and sRGB
+//! conversion, for post effects, debug views of feedback or picking
+//! textures, and composites.
+//!
+//! [`BlitPipeline`] draws one with the crate's own shader, either as a post
+//! effect or into a render target:
+//!
+//! ```ignore
+//! // in setup
+//! let blit = BlitPipeline::post_process(renderer, &scene_color, BlitOptions::default())?;
+//!
+//! // in draw
+//! blit.queue_draw(&mut renderer);
+//! renderer.submit_draws(|gpu| blit.write(gpu))?;
+//! ```
+//!
+//! A game's own shader can do the same with `blitVertex` and `blitSample`
+//! from shaders/lib/blit.slang, whose `BlitOptions` uniform holds the flags
+//! built by [`blit_flags`].
+
+use crate::generated::shader_atlas::fullscreen_blit::{FullscreenBlitParams, Resources, Shader};
+
+use super::{
+    DrawVertexCount, FrameRenderer, Gpu, PipelineHandle, RenderTarget, Renderer, TextureHandle,
+    UniformBufferHandle,
+};
+
+/// Mirror the image horizontally
+pub const BLIT_FLIP_X: u32 = 1;
+/// Mirror the image vertically, e.g. for images stored bottom row first
+pub const BLIT_FLIP_Y: u32 = 2;
+const BLIT_SRGB_DECODE: u32 = 4;
+const BLIT_SRGB_ENCODE: u32 = 8;
+
+/// How a blit converts between sRGB-encoded and linear color.
+/// Views with an `_SRGB` format convert in hardware, and need neither.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SrgbConversion {
+    #[default]
+    None,
+    /// the source holds sRGB-encoded color behind a UNORM view
+    Decode,
+    /// the target is a UNORM image that will be shown as sRGB
+    Encode,
+}
+
+/// The `flags` of a blit.slang `BlitOptions`
+pub fn blit_flags(flip_x: bool, flip_y: bool, srgb: SrgbConversion) -> u32 {
+    let mut flags = 0;
+    if flip_x {
+        flags |= BLIT_FLIP_X;
+    }
+    if flip_y {
+        flags |= BLIT_FLIP_Y;
+    }
+    flags |= match srgb {
+        SrgbConversion::None => 0,
+        SrgbConversion::Decode => BLIT_SRGB_DECODE,
+        SrgbConversion::Encode => BLIT_SRGB_ENCODE,
+    };
+    flags
+}
+
+/// How a [`BlitPipeline`] copies its source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlitOptions {
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub srgb: SrgbConversion,
+}
+
+impl BlitOptions {
+    fn flags(self) -> u32 {
+        blit_flags(self.flip_x, self.flip_y, self.srgb)
+    }
+}
+
+/// A fullscreen copy of a texture, drawn with the crate's blit shader
+pub struct BlitPipeline {
+    pipeline: PipelineHandle<DrawVertexCount>,
+    params_buffer: UniformBufferHandle<FullscreenBlitParams>,
+    options: BlitOptions,
+}
+
+impl BlitPipeline {
+    /// Blits `source` in the post-process pass, replacing the main pass's
+    /// output on screen; see [`with_post_process`](super::PipelineConfig::with_post_process)
+    pub fn post_process(
+        renderer: &mut Renderer,
+        source: &TextureHandle,
+        options: BlitOptions,
+    ) -> anyhow::Result<Self> {
+        let params_buffer = renderer.create_uniform_buffer::<FullscreenBlitParams>()?;
+        let resources = Resources {
+            source,
+            params_buffer: &params_buffer,
+        };
+        let pipeline_config = Shader::init()
+            .pipeline_config(resources)
+            .with_post_process();
+        let pipeline = renderer.create_pipeline(pipeline_config)?;
+
+        Ok(Self {
+            pipeline,
+            params_buffer,
+            options,
+        })
+    }
+
+    /// Blits `source` into `target`, before the main pass;
+    /// see [`with_render_target`](super::PipelineConfig::with_render_target)
+    pub fn into_target(
+        renderer: &mut Renderer,
+        source: &TextureHandle,
+        target: &impl RenderTarget,
+        options: BlitOptions,
+    ) -> anyhow::Result<Self> {
+        let params_buffer = renderer.create_uniform_buffer::<FullscreenBlitParams>()?;
+        let resources = Resources {
+            source,
+            params_buffer: &params_buffer,
+        };
+        let pipeline_config = Shader::init()
+            .pipeline_config(resources)
+            .with_render_target(target);
+        let pipeline = renderer.create_pipeline(pipeline_config)?;
+
+        Ok(Self {
+            pipeline,
+            params_buffer,
+            options,
+        })
+    }
+
+    /// Changes the flip or sRGB conversion from the next [`Self::write`]
+    pub fn set_options(&mut self, options: BlitOptions) {
+        self.options = options;
+    }
+
+    /// Queues the blit's three vertices
+    pub fn queue_draw(&self, renderer: &mut FrameRenderer) {
+        renderer.queue_draw_vertex_count(&self.pipeline, 3);
+    }
+
+    /// Uploads the options, in the same submit as [`Self::queue_draw`]
+    pub fn write(&mut self, gpu: &mut Gpu) {
+        let params = FullscreenBlitParams {
+            flags: self.options.flags(),
+            ..Default::default()
+        };
+        gpu.write_uniform(&mut self.params_buffer, params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_match_blit_slang() {
+        assert_eq!(blit_flags(false, false, SrgbConversion::None), 0);
+        assert_eq!(blit_flags(true, false, SrgbConversion::None), 1);
+        assert_eq!(blit_flags(false, true, SrgbConversion::Decode), 2 | 4);
+        assert_eq!(blit_flags(true, true, SrgbConversion::Encode), 1 | 2 | 8);
+    }
+
+    #[test]
+    fn blit_options_fill_the_shader_flags() {
+        let options = BlitOptions {
+            flip_y: true,
+            srgb: SrgbConversion::Decode,
+            ..Default::default()
+        };
+        assert_eq!(options.flags(), BLIT_FLIP_Y | BLIT_SRGB_DECODE);
+        assert_eq!(BlitOptions::default().flags(), 0);
+    }
+}
@@ -0,0 +1,267 @@
+//! GPU crash breadcrumbs: markers recorded before each pass, draw, and
+//! dispatch, read back after a device lost error to find what the GPU was
+//! running when it hung (an unbounded raymarch loop, an out of bounds index).
+//!
+//! Uses `VK_AMD_buffer_marker` when the device supports it, otherwise
+//! `VK_NV_device_diagnostic_checkpoints`. Debug builds only.
+
+use std::ffi::{CStr, c_void};
+use std::fmt::Write as _;
+
+use ash::vk;
+
+use super::pipeline::{ComputePipelineIndex, GraphicsPipelineIndex, PipelineIndex};
+use super::{BufferMemory, create_memory_buffer};
+
+/// The passes of a frame, in recording order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BreadcrumbPass {
+    Picking,
+    Feedback,
+    Main,
+    PostProcess,
+    Blit,
+    Ui,
+}
+
+impl BreadcrumbPass {
+    const ALL: [Self; 6] = [
+        Self::Picking,
+        Self::Feedback,
+        Self::Main,
+        Self::PostProcess,
+        Self::Blit,
+        Self::Ui,
+    ];
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Picking => "picking pass",
+            Self::Feedback => "feedback passes",
+            Self::Main => "main pass",
+            Self::PostProcess => "post-process pass",
+            Self::Blit => "upscale blit",
+            Self::Ui => "ui layer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Breadcrumb {
+    Pass(BreadcrumbPass),
+    Draw(GraphicsPipelineIndex),
+    DepthPrepass(GraphicsPipelineIndex),
+    Dispatch(ComputePipelineIndex),
+}
+
+const TAG_SHIFT: u32 = 28;
+const PAYLOAD_MASK: u32 = (1 << TAG_SHIFT) - 1;
+
+impl Breadcrumb {
+    /// A nonzero marker value: a tag in the top 4 bits, and the pass or
+    /// pipeline index below. 0 means nothing was marked.
+    fn encode(self) -> u32 {
+        let (tag, payload) = match self {
+            Self::Pass(pass) => (1, pass as usize),
+            Self::Draw(index) => (2, index.raw()),
+            Self::DepthPrepass(index) => (3, index.raw()),
+            Self::Dispatch(index) => (4, index.raw()),
+        };
+        (tag << TAG_SHIFT) | (payload as u32 & PAYLOAD_MASK)
+    }
+
+    fn decode(marker: u32) -> Option<Self> {
+        let payload = (marker & PAYLOAD_MASK) as usize;
+        match marker >> TAG_SHIFT {
+            1 => BreadcrumbPass::ALL.get(payload).copied().map(Self::Pass),
+            2 => Some(Self::Draw(GraphicsPipelineIndex::from_raw(payload))),
+            3 => Some(Self::DepthPrepass(GraphicsPipelineIndex::from_raw(payload))),
+            4 => Some(Self::Dispatch(ComputePipelineIndex::from_raw(payload))),
+            _ => None,
+        }
+    }
+}
+
+/// Which queue a command buffer is submitted to;
+/// buffer markers keep a separate pair of values for each
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BreadcrumbQueue {
+    Graphics,
+    Compute,
+}
+
+const QUEUE_COUNT: usize = 2;
+
+enum Backend {
+    /// two u32s per queue: the last marker the queue reached (top of pipe),
+    /// and the last marker all of whose preceding work finished (bottom of pipe)
+    BufferMarker {
+        ext: ash::amd::buffer_marker::Device,
+        buffer: vk::Buffer,
+        memory: vk_mem::Allocation,
+        mapped: *const u32,
+    },
+    Checkpoints {
+        ext: ash::nv::device_diagnostic_checkpoints::Device,
+    },
+}
+
+pub(super) struct Breadcrumbs {
+    backend: Backend,
+}
+
+impl Breadcrumbs {
+    /// In order of preference; the first one the device supports is enabled
+    pub(super) const EXTENSIONS: [&'static CStr; 2] = [
+        ash::amd::buffer_marker::NAME,
+        ash::nv::device_diagnostic_checkpoints::NAME,
+    ];
+
+    pub(super) fn init(
+        instance: &ash::Instance,
+        device: &ash::Device,
+        allocator: &vk_mem::Allocator,
+        extension: &CStr,
+    ) -> Result<Self, anyhow::Error> {
+        let backend = if extension == ash::amd::buffer_marker::NAME {
+            let (buffer, memory) = create_memory_buffer(
+                allocator,
+                (QUEUE_COUNT * 2 * size_of::<u32>()) as vk::DeviceSize,
+                vk::BufferUsageFlags::TRANSFER_DST,
+                BufferMemory::Readback,
+            )?;
+            let mapped = allocator.get_allocation_info(&memory).mapped_data as *mut u32;
+            // the allocation isn't zeroed, and stale values would read as markers
+            unsafe { std::ptr::write_bytes(mapped, 0, QUEUE_COUNT * 2) };
+
+            Backend::BufferMarker {
+                ext: ash::amd::buffer_marker::Device::new(instance, device),
+                buffer,
+                memory,
+                mapped,
+            }
+        } else {
+            Backend::Checkpoints {
+                ext: ash::nv::device_diagnostic_checkpoints::Device::new(instance, device),
+            }
+        };
+
+        Ok(Self { backend })
+    }
+
+    pub(super) fn mark(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        queue: BreadcrumbQueue,
+        breadcrumb: Breadcrumb,
+    ) {
+        let marker = breadcrumb.encode();
+        match &self.backend {
+            Backend::BufferMarker { ext, buffer, .. } => {
+                let offset = (queue as usize * 2 * size_of::<u32>()) as vk::DeviceSize;
+                unsafe {
+                    ext.cmd_write_buffer_marker(
+                        command_buffer,
+                        vk::PipelineStageFlags::TOP_OF_PIPE,
+                        *buffer,
+                        offset,
+                        marker,
+                    );
+                    ext.cmd_write_buffer_marker(
+                        command_buffer,
+                        vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                        *buffer,
+                        offset + size_of::<u32>() as vk::DeviceSize,
+                        marker,
+                    );
+                }
+            }
+            Backend::Checkpoints { ext } => unsafe {
+                // the checkpoint "pointer" is only handed back, never dereferenced
+                ext.cmd_set_checkpoint(command_buffer, marker as usize as *const c_void);
+            },
+        }
+    }
+
+    /// A human-readable account of the last markers each queue reached
+    pub(super) fn report(
+        &self,
+        allocator: &vk_mem::Allocator,
+        queues: &[(BreadcrumbQueue, vk::Queue)],
+        name: impl Fn(Breadcrumb) -> String,
+    ) -> String {
+        let describe = |marker: u32| match Breadcrumb::decode(marker) {
+            Some(breadcrumb) => name(breadcrumb),
+            None => "(nothing)".to_string(),
+        };
+
+        let mut report = String::new();
+        match &self.backend {
+            Backend::BufferMarker { memory, mapped, .. } => {
+                if let Err(e) = allocator.invalidate_allocation(memory, 0, vk::WHOLE_SIZE) {
+                    let _ = writeln!(report, "failed to invalidate the marker buffer: {e}");
+                }
+                for (queue, _) in queues {
+                    let (started, finished) = unsafe {
+                        let slot = mapped.add(*queue as usize * 2);
+                        (slot.read_volatile(), slot.add(1).read_volatile())
+                    };
+                    let _ = writeln!(report, "{queue:?} queue:");
+                    let _ = writeln!(report, "  last started: {}", describe(started));
+                    let _ = writeln!(
+                        report,
+                        "  finished everything before: {}",
+                        describe(finished)
+                    );
+                }
+            }
+            Backend::Checkpoints { ext } => {
+                for (queue, vk_queue) in queues {
+                    let len = unsafe { ext.get_queue_checkpoint_data_len(*vk_queue) };
+                    let mut checkpoints = vec![vk::CheckpointDataNV::default(); len];
+                    unsafe { ext.get_queue_checkpoint_data(*vk_queue, &mut checkpoints) };
+
+                    let _ = writeln!(report, "{queue:?} queue:");
+                    for checkpoint in &checkpoints {
+                        let marker = checkpoint.p_checkpoint_marker as usize as u32;
+                        let _ = writeln!(report, "  {:?}: {}", checkpoint.stage, describe(marker));
+                    }
+                }
+            }
+        }
+
+        report
+    }
+
+    pub(super) fn destroy(&mut self, allocator: &vk_mem::Allocator) {
+        if let Backend::BufferMarker { buffer, memory, .. } = &mut self.backend {
+            unsafe { allocator.destroy_buffer(*buffer, memory) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_round_trip() {
+        let breadcrumbs = [
+            Breadcrumb::Pass(BreadcrumbPass::Picking),
+            Breadcrumb::Pass(BreadcrumbPass::Blit),
+            Breadcrumb::Draw(GraphicsPipelineIndex::from_raw(0)),
+            Breadcrumb::DepthPrepass(GraphicsPipelineIndex::from_raw(7)),
+            Breadcrumb::Dispatch(ComputePipelineIndex::from_raw(3)),
+        ];
+        for breadcrumb in breadcrumbs {
+            let marker = breadcrumb.encode();
+            assert_ne!(marker, 0);
+            assert_eq!(Breadcrumb::decode(marker), Some(breadcrumb));
+        }
+    }
+
+    #[test]
+    fn unmarked_decodes_to_none() {
+        assert_eq!(Breadcrumb::decode(0), None);
+    }
+}
@@ -0,0 +1,322 @@
+//! The buffer inspector: uniform and storage buffer contents read through the
+//! layouts their shaders were compiled with, rather than the Rust types that
+//! wrote them, so a std140/std430 mismatch shows up as fields holding their
+//! neighbors' values.
+//!
+//! A uniform buffer's layout comes from the reflection of a pipeline that binds
+//! it. A storage buffer's comes from the pointer field that addresses it: a
+//! bound uniform buffer holding an address within it for the frame. Buffers
+//! no shader reads through either aren't listed.
+//!
+//! Like frame dumps, the inspector reads the ring slot the game is about to
+//! write, whose last frame has retired; its values trail by
+//! `PRE_WAIT_RING_LEN` frames.
+
+use std::fmt;
+
+use crate::shaders::json::{
+    GlobalParameter, LOOSE_GLOBALS_NAME, PointerField, UniformField, UniformFieldKind,
+    global_descriptor_sets,
+};
+
+use super::{AuditedBuffer, LayoutDescription, ResourceId};
+
+/// A buffer and the reflected layout to read it with;
+/// see [`Renderer::inspectable_buffers`](super::Renderer::inspectable_buffers)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectableBuffer {
+    pub buffer: AuditedBuffer,
+    /// where the layout came from: the shader, and the parameter block or pointer
+    pub source: String,
+    pub layout: BufferLayout,
+    /// the byte offset the layout starts at, for a pointer into the middle of a buffer
+    pub start: usize,
+}
+
+/// The shader's view of a buffer: one struct, or an array of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferLayout {
+    pub type_name: String,
+    pub fields: Vec<UniformField>,
+    /// pointer fields, as path and offset, which read as addresses
+    pub pointers: Vec<(String, usize)>,
+    /// the reflected size of one element; for a uniform buffer, of the whole block
+    pub stride: usize,
+}
+
+/// A field of one element, read from a buffer's bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedField {
+    pub path: String,
+    /// from the start of the buffer
+    pub offset: usize,
+    pub value: FieldValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Float(f32),
+    Uint(u32),
+    Uint64(u64),
+    FloatVector(Vec<f32>),
+    /// row-major
+    Float4x4([f32; 16]),
+    Address(u64),
+    /// the field extends past the end of the buffer
+    OutOfBounds,
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let floats = |values: &[f32]| {
+            values
+                .iter()
+                .map(|value| format!("{value:.4}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            FieldValue::Float(value) => write!(f, "{value:.4}"),
+            FieldValue::Uint(value) => write!(f, "{value}"),
+            FieldValue::Uint64(value) => write!(f, "{value}"),
+            FieldValue::FloatVector(values) => write!(f, "[{}]", floats(values)),
+            FieldValue::Float4x4(values) => {
+                let rows: Vec<_> = values.chunks(4).map(floats).collect();
+                write!(f, "[{}]", rows.join("; "))
+            }
+            FieldValue::Address(address) => write!(f, "0x{address:x}"),
+            FieldValue::OutOfBounds => write!(f, "(out of bounds)"),
+        }
+    }
+}
+
+impl BufferLayout {
+    /// Up to `count` elements read from `bytes` (the whole buffer), from `start`
+    pub fn read(&self, bytes: &[u8], start: usize, count: usize) -> Vec<Vec<InspectedField>> {
+        let available = bytes.len().saturating_sub(start) / self.stride.max(1);
+        (0..count.min(available.max(1)))
+            .map(|element| {
+                let base = start + element * self.stride;
+                let fields = self.fields.iter().map(|field| InspectedField {
+                    path: field.path.clone(),
+                    offset: base + field.offset,
+                    value: read_field(bytes, base + field.offset, field.kind),
+                });
+                let pointers = self.pointers.iter().map(|(path, offset)| InspectedField {
+                    path: path.clone(),
+                    offset: base + offset,
+                    value: match read_field(bytes, base + offset, UniformFieldKind::Uint64) {
+                        FieldValue::Uint64(address) => FieldValue::Address(address),
+                        out_of_bounds => out_of_bounds,
+                    },
+                });
+
+                let mut fields: Vec<_> = fields.chain(pointers).collect();
+                fields.sort_by_key(|field| field.offset);
+                fields
+            })
+            .collect()
+    }
+}
+
+fn read_field(bytes: &[u8], offset: usize, kind: UniformFieldKind) -> FieldValue {
+    let Some(field_bytes) = bytes.get(offset..offset + kind.size()) else {
+        return FieldValue::OutOfBounds;
+    };
+    let word = |i: usize| field_bytes[4 * i..4 * i + 4].try_into().unwrap();
+    let float = |i: usize| f32::from_le_bytes(word(i));
+
+    match kind {
+        UniformFieldKind::Float => FieldValue::Float(float(0)),
+        UniformFieldKind::Uint => FieldValue::Uint(u32::from_le_bytes(word(0))),
+        UniformFieldKind::Uint64 => {
+            FieldValue::Uint64(u64::from_le_bytes(field_bytes.try_into().unwrap()))
+        }
+        UniformFieldKind::FloatVector(count) => {
+            FieldValue::FloatVector((0..count).map(float).collect())
+        }
+        UniformFieldKind::Float4x4 => FieldValue::Float4x4(std::array::from_fn(float)),
+    }
+}
+
+/// A uniform buffer bound by a pipeline, with its layout and pointers
+pub(super) struct BoundUniform {
+    /// the UniformBufferStorage index
+    pub index: usize,
+    pub source: String,
+    pub layout: BufferLayout,
+    pub pointer_fields: Vec<PointerField>,
+}
+
+/// The uniform buffers a pipeline binds, with their reflected layouts.
+/// `resources` are the pipeline's, whose uniform buffers are in the same
+/// order as the layout's uniform bindings.
+pub(super) fn bound_uniforms(
+    source_file_name: &str,
+    global_parameters: &[GlobalParameter],
+    layout_bindings: &[Vec<LayoutDescription>],
+    resources: &[ResourceId],
+) -> Vec<BoundUniform> {
+    let descriptor_sets = global_descriptor_sets(global_parameters);
+    let mut uniform_indices = resources.iter().filter_map(|resource| match resource {
+        ResourceId::UniformBuffer(index) => Some(*index),
+        _ => None,
+    });
+
+    let mut bound_uniforms = vec![];
+    for (set, descriptions) in layout_bindings.iter().enumerate() {
+        for description in descriptions {
+            let LayoutDescription::Uniform(uniform) = description else {
+                continue;
+            };
+            let Some(index) = uniform_indices.next() else {
+                return bound_uniforms;
+            };
+            let Some(descriptor_set) = descriptor_sets.get(set) else {
+                continue;
+            };
+
+            let pointer_fields = descriptor_set.pointer_fields();
+            bound_uniforms.push(BoundUniform {
+                index,
+                source: format!("{source_file_name} {}", descriptor_set.parameter_path),
+                layout: BufferLayout {
+                    type_name: descriptor_set
+                        .type_name
+                        .unwrap_or(LOOSE_GLOBALS_NAME)
+                        .to_string(),
+                    fields: descriptor_set.uniform_fields(),
+                    pointers: pointer_fields
+                        .iter()
+                        .map(|pointer| (pointer.path.clone(), pointer.offset))
+                        .collect(),
+                    stride: uniform.size as usize,
+                },
+                pointer_fields,
+            });
+        }
+    }
+
+    bound_uniforms
+}
+
+/// The layout a pointer field reads the buffer it addresses with
+pub(super) fn pointee_layout(pointer: &PointerField) -> BufferLayout {
+    BufferLayout {
+        type_name: pointer.pointee_type_name.clone(),
+        fields: pointer.pointee_fields.clone(),
+        pointers: vec![],
+        stride: pointer.pointee_size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shaders::json::ReflectionJson;
+
+    use super::*;
+
+    fn sprite_batch() -> ReflectionJson {
+        let raw = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/sprite_batch.json"
+        ));
+        serde_json::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn uniform_layouts_follow_the_pipelines_resources() {
+        let reflection_json = sprite_batch();
+        let resources = [ResourceId::Texture(0), ResourceId::UniformBuffer(5)];
+        let bound = bound_uniforms(
+            "sprite_batch.shader.slang",
+            &reflection_json.global_parameters,
+            &reflection_json.layout_bindings(),
+            &resources,
+        );
+
+        assert_eq!(bound.len(), 1);
+        assert_eq!(bound[0].index, 5);
+        assert_eq!(bound[0].source, "sprite_batch.shader.slang params");
+        assert_eq!(bound[0].layout.type_name, "SpriteBatchParams");
+        assert_eq!(bound[0].layout.pointers, [("sprites".to_string(), 0)]);
+        assert_eq!(bound[0].pointer_fields[0].pointee_size, 64);
+    }
+
+    #[test]
+    fn sampled_elements_read_back_their_word_indices() {
+        use crate::generated::shader_atlas::sprite_batch::Sprite;
+
+        let reflection_json = sprite_batch();
+        let resources = [ResourceId::Texture(0), ResourceId::UniformBuffer(0)];
+        let bound = bound_uniforms(
+            "sprite_batch.shader.slang",
+            &reflection_json.global_parameters,
+            &reflection_json.layout_bindings(),
+            &resources,
+        );
+        let layout = pointee_layout(&bound[0].pointer_fields[0]);
+
+        let sprite = Sprite::sample();
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                (&sprite as *const Sprite).cast::<u8>(),
+                std::mem::size_of::<Sprite>(),
+            )
+        };
+
+        let elements = layout.read(bytes, 0, 1);
+        for field in &elements[0] {
+            let first_word = (field.offset / 4) as f32;
+            match &field.value {
+                FieldValue::FloatVector(values) => {
+                    assert_eq!(values[0], first_word, "{}", field.path)
+                }
+                value => assert_eq!(*value, FieldValue::Float(first_word), "{}", field.path),
+            }
+        }
+    }
+
+    #[test]
+    fn elements_are_read_at_the_reflected_stride() {
+        // std430 packs a float after a float3, where std140 rules would not
+        let layout = BufferLayout {
+            type_name: "Particle".to_string(),
+            fields: vec![
+                UniformField {
+                    path: "position".to_string(),
+                    offset: 0,
+                    kind: UniformFieldKind::FloatVector(3),
+                },
+                UniformField {
+                    path: "age".to_string(),
+                    offset: 12,
+                    kind: UniformFieldKind::Float,
+                },
+            ],
+            pointers: vec![],
+            stride: 16,
+        };
+        let floats = [1.0_f32, 2.0, 3.0, 0.5, 4.0, 5.0, 6.0, 0.25];
+        let bytes: Vec<u8> = floats
+            .iter()
+            .flat_map(|float| float.to_le_bytes())
+            .collect();
+
+        let elements = layout.read(&bytes, 0, 8);
+        assert_eq!(elements.len(), 2);
+        assert_eq!(
+            elements[1][0].value,
+            FieldValue::FloatVector(vec![4.0, 5.0, 6.0])
+        );
+        assert_eq!(elements[1][1].offset, 28);
+        assert_eq!(elements[1][1].value, FieldValue::Float(0.25));
+
+        // a uniform block reflected as larger than the buffer the game wrote
+        let short = &bytes[..14];
+        let elements = layout.read(short, 0, 1);
+        assert_eq!(elements[0][1].value, FieldValue::OutOfBounds);
+        assert_eq!(elements[0][1].value.to_string(), "(out of bounds)");
+    }
+}
@@ -0,0 +1,128 @@
+//! Scissor clipping for queued draws.
+//!
+//! [`FrameRenderer::push_clip`](super::FrameRenderer::push_clip) narrows the
+//! clip for every draw queued until the matching
+//! [`pop_clip`](super::FrameRenderer::pop_clip); nested clips intersect, so a
+//! scrollable panel inside a window never draws outside either. Each draw
+//! records its clip when queued, and is drawn with it as its scissor (also
+//! intersected with its pass's area, like a split-screen view).
+
+use ash::vk;
+use serde::Serialize;
+
+/// A rectangle in pixels of the target a draw renders to, with the origin at
+/// the top left: render resolution for the main pass, and window resolution
+/// for the ui layer (see `FrameRenderer::render_resolution` and
+/// `FrameRenderer::window_resolution`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ClipRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ClipRect {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The area inside both rects; empty (with a zero width or height)
+    /// when they don't overlap
+    pub fn intersect(self, other: Self) -> Self {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        Self {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+
+    pub fn contains(self, pixel: [u32; 2]) -> bool {
+        let [x, y] = pixel;
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+
+    pub(super) fn from_rect2d(rect: vk::Rect2D) -> Self {
+        Self {
+            x: rect.offset.x.max(0) as u32,
+            y: rect.offset.y.max(0) as u32,
+            width: rect.extent.width,
+            height: rect.extent.height,
+        }
+    }
+
+    pub(super) fn to_rect2d(self) -> vk::Rect2D {
+        vk::Rect2D {
+            offset: vk::Offset2D {
+                x: self.x as i32,
+                y: self.y as i32,
+            },
+            extent: vk::Extent2D {
+                width: self.width,
+                height: self.height,
+            },
+        }
+    }
+}
+
+/// The clips pushed so far this frame, each already intersected with the ones
+/// before it
+#[derive(Debug, Default)]
+pub(super) struct ClipStack(Vec<ClipRect>);
+
+impl ClipStack {
+    pub fn push(&mut self, rect: ClipRect) {
+        let rect = match self.current() {
+            Some(enclosing) => rect.intersect(enclosing),
+            None => rect,
+        };
+        self.0.push(rect);
+    }
+
+    pub fn pop(&mut self) -> Option<ClipRect> {
+        self.0.pop()
+    }
+
+    /// the clip for draws queued now; None draws over the whole pass
+    pub fn current(&self) -> Option<ClipRect> {
+        self.0.last().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_clips_intersect() {
+        let mut stack = ClipStack::default();
+        assert_eq!(stack.current(), None);
+
+        stack.push(ClipRect::new(10, 10, 100, 50));
+        stack.push(ClipRect::new(80, 0, 100, 100));
+        assert_eq!(stack.current(), Some(ClipRect::new(80, 10, 30, 50)));
+
+        stack.pop();
+        assert_eq!(stack.current(), Some(ClipRect::new(10, 10, 100, 50)));
+        stack.pop();
+        assert_eq!(stack.current(), None);
+    }
+
+    #[test]
+    fn disjoint_clips_are_empty() {
+        let clip = ClipRect::new(0, 0, 10, 10).intersect(ClipRect::new(20, 5, 10, 10));
+        assert_eq!(clip.width, 0);
+        assert!(!clip.contains([20, 5]));
+    }
+}
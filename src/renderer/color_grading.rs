@@ -0,0 +1,126 @@
+//! Color grading as a post effect: the scene color looked up in a `.cube`
+//! table (see [`crate::lut`]), with the crate's own shader
+//! (`shaders/source/color_grade.shader.slang`).
+//!
+//! ```ignore
+//! // in setup
+//! let scene_color = renderer.create_scene_color_texture(TextureFilter::Nearest)?;
+//! let lut = load_cube(&manifest_path(["luts", "warm.cube"]))?;
+//! let mut grading = ColorGrading::new(renderer, &scene_color, &lut)?;
+//!
+//! // in draw
+//! grading.queue_draw(&mut renderer);
+//! renderer.submit_draws(|gpu| grading.write(gpu))?;
+//! ```
+//!
+//! It draws in the post-process pass, replacing the main pass's output on
+//! screen; see [`with_post_process`](super::PipelineConfig::with_post_process).
+//! A game grading in its own post shader can sample a table from
+//! [`Renderer::create_lut_texture`] with `shaders/lib/color_grading.slang`.
+
+use crate::generated::shader_atlas::color_grade::{ColorGradeParams, Resources, Shader};
+use crate::lut::{CubeLut, LutDimension};
+
+use super::{
+    DrawVertexCount, FrameRenderer, Gpu, PipelineHandle, Renderer, TextureHandle,
+    UniformBufferHandle,
+};
+
+/// A fullscreen lookup of the scene color in a table
+pub struct ColorGrading {
+    pipeline: PipelineHandle<DrawVertexCount>,
+    params_buffer: UniformBufferHandle<ColorGradeParams>,
+    /// the table, kept alive for the pipeline
+    _lut: TextureHandle,
+    /// the table's shape; strength and srgb_encoded are filled in by write
+    table_params: ColorGradeParams,
+    /// 0 for the scene as is, 1 (the default) for the fully graded color
+    pub strength: f32,
+    /// whether the table maps sRGB-encoded colors, as most grading luts do
+    /// (the default); false for tables made for linear color
+    pub srgb_encoded: bool,
+}
+
+impl ColorGrading {
+    /// Grades `scene_color`, from
+    /// [`Renderer::create_scene_color_texture`], with `lut`
+    pub fn new(
+        renderer: &mut Renderer,
+        scene_color: &TextureHandle,
+        lut: &CubeLut,
+    ) -> anyhow::Result<Self> {
+        let lut_name = lut.title.as_deref().unwrap_or("color_grading_lut");
+        let lut_texture = renderer.create_lut_texture(lut_name, lut)?;
+        let params_buffer = renderer.create_uniform_buffer::<ColorGradeParams>()?;
+        let resources = Resources {
+            scene: scene_color,
+            lut: &lut_texture,
+            params_buffer: &params_buffer,
+        };
+        let pipeline_config = Shader::init()
+            .pipeline_config(resources)
+            .with_post_process();
+        let pipeline = renderer.create_pipeline(pipeline_config)?;
+
+        Ok(Self {
+            pipeline,
+            params_buffer,
+            _lut: lut_texture,
+            table_params: table_params(lut),
+            strength: 1.0,
+            srgb_encoded: true,
+        })
+    }
+
+    /// Queues the grade's three vertices
+    pub fn queue_draw(&self, renderer: &mut FrameRenderer) {
+        renderer.queue_draw_vertex_count(&self.pipeline, 3);
+    }
+
+    /// Uploads the strength and encoding, in the same submit as
+    /// [`Self::queue_draw`]
+    pub fn write(&mut self, gpu: &mut Gpu) {
+        let params = ColorGradeParams {
+            strength: self.strength.clamp(0.0, 1.0),
+            srgb_encoded: self.srgb_encoded as u32,
+            ..self.table_params.clone()
+        };
+        gpu.write_uniform(&mut self.params_buffer, params);
+    }
+}
+
+fn table_params(lut: &CubeLut) -> ColorGradeParams {
+    ColorGradeParams {
+        domain_min: lut.domain_min.into(),
+        size: lut.size,
+        domain_max: lut.domain_max.into(),
+        three_d: (lut.dimension == LutDimension::ThreeD) as u32,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+    use crate::lut::parse_cube;
+
+    #[test]
+    fn table_params_follow_the_cube_file() {
+        let lut = parse_cube(
+            "LUT_3D_SIZE 2\n\
+             DOMAIN_MIN 0 0 0.1\n\
+             DOMAIN_MAX 1 2 1\n\
+             0 0 0\n1 0 0\n0 1 0\n1 1 0\n\
+             0 0 1\n1 0 1\n0 1 1\n1 1 1\n",
+        )
+        .unwrap();
+        let params = table_params(&lut);
+
+        assert_eq!(params.size, 2);
+        assert_eq!(params.three_d, 1);
+        assert_eq!(params.domain_min, Vec3::new(0.0, 0.0, 0.1));
+        assert_eq!(params.domain_max, Vec3::new(1.0, 2.0, 1.0));
+    }
+}
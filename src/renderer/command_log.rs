@@ -0,0 +1,207 @@
+//! What a frame submitted, as plain data for tests to assert on.
+//!
+//! With [`Renderer::record_commands`](super::Renderer::record_commands), the
+//! renderer logs each frame's queued draws, dispatches, and buffer writes
+//! before recording them into command buffers; the
+//! [`NullRenderer`](super::NullRenderer) logs the same [`FrameLog`] without
+//! a device. Logs serialize to JSON for snapshotting.
+
+use serde::Serialize;
+
+use super::determinism::WrittenRange;
+use super::gpu_context::GpuContext;
+use super::{
+    AuditedBuffer, ClipRect, DrawCall, DrawCallConfig, PendingComputeCommand, PendingDrawCommand,
+    PipelineHandle, PipelineIndex,
+};
+
+/// A draw as it was queued; see [`FrameLog`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoggedDraw {
+    pub pipeline: usize,
+    pub variant: Option<usize>,
+    /// the pipeline's shader source file
+    pub shader: String,
+    pub call: LoggedDrawCall,
+    pub clip: Option<ClipRect>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LoggedDrawCall {
+    IndexCount(u32),
+    IndexRange {
+        first_index: u32,
+        index_count: u32,
+    },
+    VertexCount(u32),
+    VertexRange {
+        first_vertex: u32,
+        vertex_count: u32,
+    },
+    MeshTasks([u32; 3]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoggedDispatch {
+    pub pipeline: usize,
+    pub shader: String,
+    pub group_count: [u32; 3],
+}
+
+/// A buffer write from a frame's gpu update, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LoggedWrite {
+    pub buffer: AuditedBuffer,
+    pub len: usize,
+}
+
+/// Everything one frame submitted, each list in the order it was queued
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct FrameLog {
+    pub draws: Vec<LoggedDraw>,
+    pub dispatches: Vec<LoggedDispatch>,
+    pub writes: Vec<LoggedWrite>,
+}
+
+impl FrameLog {
+    /// the draws of a pipeline (or one of its variants)
+    pub fn draws_of<D: DrawCall>(
+        &self,
+        pipeline: &PipelineHandle<D>,
+    ) -> impl Iterator<Item = &LoggedDraw> {
+        let (index, variant) = (pipeline.raw_index(), pipeline.variant());
+        self.draws
+            .iter()
+            .filter(move |draw| draw.pipeline == index && draw.variant == variant)
+    }
+
+    /// Logs a frame about to be recorded on a device
+    pub(super) fn from_pending(
+        gpu: &GpuContext,
+        pending_draws: &[PendingDrawCommand],
+        pending_compute: &[PendingComputeCommand],
+        writes: &[WrittenRange],
+    ) -> Self {
+        let draws = pending_draws
+            .iter()
+            .map(|command| match command {
+                PendingDrawCommand::Draw {
+                    pipeline_index,
+                    variant,
+                    draw_call,
+                    clip,
+                } => LoggedDraw {
+                    pipeline: pipeline_index.raw(),
+                    variant: *variant,
+                    shader: gpu
+                        .pipelines
+                        .get_by_index(*pipeline_index)
+                        .shader
+                        .source_file_name()
+                        .to_string(),
+                    call: logged_draw_call(*draw_call),
+                    clip: *clip,
+                },
+            })
+            .collect();
+
+        // barriers are ordering, not work; they're left out
+        let dispatches = pending_compute
+            .iter()
+            .filter_map(|command| match command {
+                PendingComputeCommand::Dispatch {
+                    pipeline_index,
+                    group_count,
+                } => Some(LoggedDispatch {
+                    pipeline: pipeline_index.raw(),
+                    shader: gpu
+                        .compute_pipelines
+                        .get_by_index(*pipeline_index)
+                        .shader
+                        .source_file_name()
+                        .to_string(),
+                    group_count: *group_count,
+                }),
+                PendingComputeCommand::Barrier { .. } => None,
+            })
+            .collect();
+
+        let writes = writes
+            .iter()
+            .map(|write| LoggedWrite {
+                buffer: write.buffer,
+                len: write.len,
+            })
+            .collect();
+
+        Self {
+            draws,
+            dispatches,
+            writes,
+        }
+    }
+}
+
+fn logged_draw_call(draw_call: DrawCallConfig) -> LoggedDrawCall {
+    match draw_call {
+        DrawCallConfig::IndexCount(index_count) => LoggedDrawCall::IndexCount(index_count),
+        DrawCallConfig::IndexRange {
+            first_index,
+            index_count,
+        } => LoggedDrawCall::IndexRange {
+            first_index,
+            index_count,
+        },
+        DrawCallConfig::VertexCount(vertex_count) => LoggedDrawCall::VertexCount(vertex_count),
+        DrawCallConfig::VertexRange {
+            first_vertex,
+            vertex_count,
+        } => LoggedDrawCall::VertexRange {
+            first_vertex,
+            vertex_count,
+        },
+        DrawCallConfig::MeshTasks(group_count) => LoggedDrawCall::MeshTasks(group_count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_logs_serialize_for_snapshots() {
+        let log = FrameLog {
+            draws: vec![LoggedDraw {
+                pipeline: 2,
+                variant: None,
+                shader: "sprite_batch.shader.slang".to_string(),
+                call: logged_draw_call(DrawCallConfig::VertexRange {
+                    first_vertex: 6,
+                    vertex_count: 12,
+                }),
+                clip: Some(ClipRect::new(0, 0, 64, 32)),
+            }],
+            dispatches: vec![],
+            writes: vec![LoggedWrite {
+                buffer: AuditedBuffer::Uniform(0),
+                len: 192,
+            }],
+        };
+
+        let json = serde_json::to_value(&log).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "draws": [{
+                    "pipeline": 2,
+                    "variant": null,
+                    "shader": "sprite_batch.shader.slang",
+                    "call": { "VertexRange": { "first_vertex": 6, "vertex_count": 12 } },
+                    "clip": { "x": 0, "y": 0, "width": 64, "height": 32 },
+                }],
+                "dispatches": [],
+                "writes": [{ "buffer": { "Uniform": 0 }, "len": 192 }],
+            })
+        );
+    }
+}
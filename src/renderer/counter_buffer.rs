@@ -0,0 +1,122 @@
+//! GPU atomic counters, for compaction and emission.
+//!
+//! A shader appends to a list by atomically incrementing a counter through its
+//! `Addr<uint>` and writing the element at the returned slot of a GPU-only
+//! buffer: visible-object lists, emitted particles, and so on. Consuming is the
+//! mirror image, decrementing from a count written at setup.
+//!
+//! Like storage buffers, each counter buffer has one copy per ring slot. When a
+//! frame begins, the renderer reads its slot's counts (left by the frame that
+//! last used the slot, which has retired) and zeroes them, so every frame's
+//! counters start at 0 and the counts the CPU sees trail by `PRE_WAIT_RING_LEN`
+//! frames.
+
+use ash::vk;
+
+use super::PRE_WAIT_RING_LEN;
+use super::storage_buffer::RawStorageBuffer;
+
+/// A buffer of `u32` counters that the GPU increments atomically,
+/// zeroed at the start of every frame
+///
+/// Shaders take it as an `Addr<uint>` from `Gpu::counter_addr`; the CPU reads
+/// the completed counts with `Gpu::counts` or `Renderer::counts`.
+#[derive(Debug)]
+pub struct CounterBufferHandle {
+    index: usize,
+    len: u32,
+}
+
+#[expect(clippy::len_without_is_empty)] // vulkan does not allow allocating an empty buffer
+impl CounterBufferHandle {
+    /// a handle to nothing allocated, for the null renderer
+    pub(super) fn from_raw(index: usize, len: u32) -> Self {
+        Self { index, len }
+    }
+
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+
+    /// the number of counters
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+}
+
+pub(super) struct CounterBuffer {
+    buffers_per_frame: [RawStorageBuffer; PRE_WAIT_RING_LEN],
+    /// the counts from the last frame collected by `begin_frame`
+    counts: Vec<u32>,
+}
+
+pub(super) struct CounterBufferStorage(Vec<Option<CounterBuffer>>);
+
+impl CounterBufferStorage {
+    pub fn new() -> Self {
+        Self(Default::default())
+    }
+
+    pub fn add(
+        &mut self,
+        buffers_per_frame: [RawStorageBuffer; PRE_WAIT_RING_LEN],
+        len: u32,
+    ) -> CounterBufferHandle {
+        let handle = CounterBufferHandle {
+            index: self.0.len(),
+            len,
+        };
+
+        self.0.push(Some(CounterBuffer {
+            buffers_per_frame,
+            counts: vec![0; len as usize],
+        }));
+
+        handle
+    }
+
+    pub(super) fn get_device_address_for_frame(
+        &self,
+        handle: &CounterBufferHandle,
+        frame: usize,
+    ) -> vk::DeviceAddress {
+        self.0[handle.index].as_ref().unwrap().buffers_per_frame[frame].device_address
+    }
+
+    pub(super) fn counts(&self, handle: &CounterBufferHandle) -> &[u32] {
+        &self.0[handle.index].as_ref().unwrap().counts
+    }
+
+    /// Collects the counts the ring slot's last frame left, then zeroes the
+    /// slot for this frame. The slot's last frame must have retired.
+    pub(super) fn begin_frame(&mut self, frame: usize) {
+        for counter_buffer in self.0.iter_mut().flatten() {
+            let mapped_mem = counter_buffer.buffers_per_frame[frame].mapped_mem as *mut u32;
+            let len = counter_buffer.counts.len();
+
+            // the memory is host coherent, and no frame in flight uses this slot
+            unsafe {
+                let mapped = std::slice::from_raw_parts_mut(mapped_mem, len);
+                counter_buffer.counts.copy_from_slice(mapped);
+                mapped.fill(0);
+            }
+        }
+    }
+
+    pub fn take(&mut self, handle: CounterBufferHandle) -> [RawStorageBuffer; PRE_WAIT_RING_LEN] {
+        self.0[handle.index].take().unwrap().buffers_per_frame
+    }
+
+    /// the number of buffers not yet dropped
+    pub fn live_count(&self) -> usize {
+        self.0.iter().flatten().count()
+    }
+
+    pub fn take_all(&mut self) -> Vec<[RawStorageBuffer; PRE_WAIT_RING_LEN]> {
+        self.0
+            .iter_mut()
+            .filter_map(|option| option.take())
+            .map(|counter_buffer| counter_buffer.buffers_per_frame)
+            .collect()
+    }
+}
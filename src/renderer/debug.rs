@@ -1,13 +1,45 @@
 use ash::vk;
 
+use std::collections::VecDeque;
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::os::raw::c_void;
 use std::ptr;
+use std::sync::Mutex;
 
 use ash::ext::debug_utils;
 use log::*;
 
+/// how many shader printf lines to keep for the editor's console
+const MAX_PRINTF_LINES: usize = 256;
+
+/// the most recent shader printf lines, oldest first;
+/// the callback can run on any thread that calls into vulkan
+static PRINTF_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// The most recent shader printf output, oldest first
+pub fn printf_lines() -> Vec<String> {
+    PRINTF_LINES.lock().unwrap().iter().cloned().collect()
+}
+
+pub fn clear_printf_lines() {
+    PRINTF_LINES.lock().unwrap().clear();
+}
+
+fn record_printf_line(line: &str) {
+    let mut lines = PRINTF_LINES.lock().unwrap();
+    if lines.len() == MAX_PRINTF_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line.to_string());
+}
+
+/// The shader's output from a validation layer debug printf message
+fn printf_output(message: &str) -> Option<&str> {
+    let (_vulkan_prefix, output) = message.split_once("DebugPrintf:")?;
+    Some(output.trim())
+}
+
 // based on the logging callback here:
 // https://github.com/unknownue/vulkan-tutorial-rust/blob/master/src/utility/debug.rs#L8
 
@@ -30,18 +62,19 @@ unsafe extern "system" fn vulkan_debug_utils_callback(
     let prefixed_message = format!("{message_type} {message}");
 
     use vk::DebugUtilsMessageSeverityFlagsEXT as Severity;
+    if message_severity == Severity::INFO
+        && let Some(printf_message) = printf_output(&prefixed_message)
+    {
+        info!("{printf_message}");
+        record_printf_line(printf_message);
+        return vk::FALSE;
+    }
+
     match message_severity {
         Severity::ERROR => error!("{prefixed_message}"),
         Severity::WARNING => warn!("{prefixed_message}"),
         Severity::VERBOSE => debug!("{prefixed_message}"),
 
-        Severity::INFO if prefixed_message.contains("DebugPrintf:\n") => {
-            let mut split = prefixed_message.split("DebugPrintf:");
-            let _vulkan_prefix = split.next().unwrap();
-            let printf_message = split.next().unwrap();
-            info!("{printf_message}")
-        }
-
         _info_or_other => info!("{prefixed_message}"),
     };
 
@@ -90,3 +123,17 @@ pub fn build_messenger_create_info() -> vk::DebugUtilsMessengerCreateInfoEXT<'st
         _marker: PhantomData,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn printf_output_strips_the_layer_prefix() {
+        let message = "[General] Validation Information: [ WARNING-DEBUG-PRINTF ] \
+            | MessageID = 0x76589099 | vkQueueSubmit(): DebugPrintf:\ndistance: 1.50\n";
+        assert_eq!(printf_output(message), Some("distance: 1.50"));
+
+        assert_eq!(printf_output("[Validation] some other message"), None);
+    }
+}
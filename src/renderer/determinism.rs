@@ -0,0 +1,263 @@
+//! A debug mode for tracking down nondeterminism, like a replay or a
+//! fixed-timestep port drifting from the original run.
+//!
+//! While an audit is running, every buffer the game writes through [`Gpu`]
+//! in a frame is hashed once the frame's writes are done. Recording saves the
+//! hashes to a text file, one line per buffer per frame; comparing checks each
+//! frame against an earlier recording and logs the first frame that differs.
+//! Frames are counted from the start of the audit, so start both runs at the
+//! same point (ie, in `Game::setup`).
+//!
+//! [`Gpu`]: super::Gpu
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+
+use serde::Serialize;
+
+/// A buffer the CPU writes each frame, by its storage index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum AuditedBuffer {
+    Uniform(usize),
+    /// storage, immutable, and raw buffers
+    Storage(usize),
+    /// a graphics pipeline's push constants
+    PushConstants(usize),
+}
+
+impl fmt::Display for AuditedBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditedBuffer::Uniform(index) => write!(f, "uniform {index}"),
+            AuditedBuffer::Storage(index) => write!(f, "storage {index}"),
+            AuditedBuffer::PushConstants(index) => write!(f, "push_constants {index}"),
+        }
+    }
+}
+
+impl AuditedBuffer {
+    fn parse(kind: &str, index: &str) -> Option<Self> {
+        let index = index.parse().ok()?;
+        match kind {
+            "uniform" => Some(AuditedBuffer::Uniform(index)),
+            "storage" => Some(AuditedBuffer::Storage(index)),
+            "push_constants" => Some(AuditedBuffer::PushConstants(index)),
+            _ => None,
+        }
+    }
+}
+
+/// A frame whose buffer contents differ from the recording;
+/// None for a buffer only one of the runs wrote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub frame: u64,
+    pub buffer: AuditedBuffer,
+    pub expected: Option<u64>,
+    pub actual: Option<u64>,
+}
+
+/// A byte range the game wrote this frame, hashed once gpu_update returns
+pub(super) struct WrittenRange {
+    pub buffer: AuditedBuffer,
+    pub bytes: *const u8,
+    pub len: usize,
+}
+
+type FrameHashes = BTreeMap<AuditedBuffer, u64>;
+
+pub(super) enum DeterminismAudit {
+    Record {
+        frame: u64,
+        out: Box<dyn Write + Send>,
+    },
+    Compare {
+        frame: u64,
+        expected: BTreeMap<u64, FrameHashes>,
+        divergences: Vec<Divergence>,
+    },
+}
+
+impl DeterminismAudit {
+    pub fn record(out: Box<dyn Write + Send>) -> Self {
+        Self::Record { frame: 0, out }
+    }
+
+    pub fn compare(recording: &str) -> anyhow::Result<Self> {
+        Ok(Self::Compare {
+            frame: 0,
+            expected: parse_recording(recording)?,
+            divergences: vec![],
+        })
+    }
+
+    /// Hashes a frame's written ranges, then records or compares them.
+    ///
+    /// # Safety
+    /// each range must still point to `len` readable bytes
+    pub unsafe fn end_frame(&mut self, written: Vec<WrittenRange>) -> std::io::Result<()> {
+        let mut hashes = FrameHashes::new();
+        for range in written {
+            let bytes = unsafe { std::slice::from_raw_parts(range.bytes, range.len) };
+            // a buffer written more than once is hashed as it ended up
+            hashes.insert(range.buffer, fnv1a(bytes));
+        }
+
+        match self {
+            DeterminismAudit::Record { frame, out } => {
+                for (buffer, hash) in &hashes {
+                    writeln!(out, "{frame} {buffer} {hash:016x}")?;
+                }
+                *frame += 1;
+            }
+            DeterminismAudit::Compare {
+                frame,
+                expected,
+                divergences,
+            } => {
+                let expected = expected.remove(&*frame).unwrap_or_default();
+                let frame_divergences = diff_frame(*frame, &expected, &hashes);
+                if divergences.is_empty()
+                    && let Some(first) = frame_divergences.first()
+                {
+                    log::warn!(
+                        "frame {} diverged from the recording, first in {}",
+                        first.frame,
+                        first.buffer
+                    );
+                }
+                divergences.extend(frame_divergences);
+                *frame += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// flushes a recording, or returns a comparison's divergences
+    pub fn finish(self) -> anyhow::Result<Vec<Divergence>> {
+        match self {
+            DeterminismAudit::Record { mut out, .. } => {
+                out.flush()?;
+                Ok(vec![])
+            }
+            DeterminismAudit::Compare { divergences, .. } => Ok(divergences),
+        }
+    }
+
+    pub fn divergences(&self) -> &[Divergence] {
+        match self {
+            DeterminismAudit::Record { .. } => &[],
+            DeterminismAudit::Compare { divergences, .. } => divergences,
+        }
+    }
+}
+
+fn diff_frame(frame: u64, expected: &FrameHashes, actual: &FrameHashes) -> Vec<Divergence> {
+    let mut buffers: Vec<_> = expected.keys().chain(actual.keys()).copied().collect();
+    buffers.sort_unstable();
+    buffers.dedup();
+
+    buffers
+        .into_iter()
+        .filter_map(|buffer| {
+            let expected = expected.get(&buffer).copied();
+            let actual = actual.get(&buffer).copied();
+            (expected != actual).then_some(Divergence {
+                frame,
+                buffer,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+fn parse_recording(recording: &str) -> anyhow::Result<BTreeMap<u64, FrameHashes>> {
+    let mut frames: BTreeMap<u64, FrameHashes> = BTreeMap::new();
+    for (line_number, line) in recording.lines().enumerate() {
+        let parsed = match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [frame, kind, index, hash] => frame
+                .parse()
+                .ok()
+                .zip(AuditedBuffer::parse(kind, index).zip(u64::from_str_radix(hash, 16).ok())),
+            _ => None,
+        };
+        let Some((frame, (buffer, hash))) = parsed else {
+            anyhow::bail!("bad determinism recording line {}: {line}", line_number + 1);
+        };
+
+        frames.entry(frame).or_default().insert(buffer, hash);
+    }
+
+    Ok(frames)
+}
+
+/// FNV-1a, which unlike std's hashers is the same on every run and build
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(buffer: AuditedBuffer, bytes: &[u8]) -> WrittenRange {
+        WrittenRange {
+            buffer,
+            bytes: bytes.as_ptr(),
+            len: bytes.len(),
+        }
+    }
+
+    #[test]
+    fn comparing_a_recording_finds_the_changed_buffer() {
+        let recording = format!(
+            "0 uniform 0 {:016x}\n0 storage 2 {:016x}\n",
+            fnv1a(&[1, 2, 3]),
+            fnv1a(&[4, 5])
+        );
+
+        let mut compare = DeterminismAudit::compare(&recording).unwrap();
+        unsafe {
+            compare
+                .end_frame(vec![
+                    written(AuditedBuffer::Uniform(0), &[1, 2, 3]),
+                    written(AuditedBuffer::Storage(2), &[4, 6]),
+                ])
+                .unwrap();
+            compare
+                .end_frame(vec![written(AuditedBuffer::PushConstants(1), &[7])])
+                .unwrap();
+        }
+
+        assert_eq!(
+            compare.divergences(),
+            [
+                Divergence {
+                    frame: 0,
+                    buffer: AuditedBuffer::Storage(2),
+                    expected: Some(fnv1a(&[4, 5])),
+                    actual: Some(fnv1a(&[4, 6])),
+                },
+                Divergence {
+                    frame: 1,
+                    buffer: AuditedBuffer::PushConstants(1),
+                    expected: None,
+                    actual: Some(fnv1a(&[7])),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_recordings_are_rejected() {
+        assert!(DeterminismAudit::compare("0 uniform 1 00ff\n0 texture 2 00ff\n").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::ffi::CStr;
+
+use ash::vk;
+
+/// Extra device extensions and feature structs to enable when the renderer
+/// creates its device, for experimenting with Vulkan features the renderer
+/// doesn't use itself.
+/// See [`crate::game::Game::device_extensions`].
+///
+/// Apart from [`Self::with_ray_query`], [`Self::with_mesh_shaders`], and
+/// [`Self::with_fragment_shading_rate`], the renderer doesn't know what these
+/// enable; anything beyond creating the device (loading extension functions,
+/// recording commands) is up to the post-init callback.
+#[derive(Default)]
+pub struct DeviceExtensions {
+    pub(super) names: Vec<&'static CStr>,
+    pub(super) features: Vec<Box<dyn DeviceFeatures>>,
+    pub(super) post_init: Option<Box<PostInitCallback>>,
+    pub(super) ray_query: bool,
+    pub(super) mesh_shaders: bool,
+    pub(super) fragment_shading_rate: bool,
+}
+
+type PostInitCallback =
+    dyn FnOnce(&ash::Instance, vk::PhysicalDevice, &ash::Device) -> anyhow::Result<()>;
+
+impl DeviceExtensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Device creation fails with an error if the device doesn't support it.
+    pub fn with_extension(mut self, name: &'static CStr) -> Self {
+        self.names.push(name);
+        self
+    }
+
+    /// Add a feature struct to the device create info's pNext chain.
+    ///
+    /// It must not have its own pNext chain, or be one of the core feature
+    /// structs the renderer already enables (PhysicalDeviceVulkan11Features,
+    /// 12, 13, and PhysicalDeviceFeatures2).
+    pub fn with_features<T: vk::ExtendsDeviceCreateInfo + 'static>(mut self, features: T) -> Self {
+        self.features.push(Box::new(features));
+        self
+    }
+
+    /// Enable VK_KHR_ray_query and the acceleration structures it traces
+    /// against, for [`super::Renderer::build_blas`] and
+    /// [`super::Renderer::build_tlas`]
+    pub fn with_ray_query(self) -> Self {
+        let mut device_extensions = self
+            .with_extension(ash::khr::acceleration_structure::NAME)
+            .with_extension(ash::khr::ray_query::NAME)
+            .with_extension(ash::khr::deferred_host_operations::NAME)
+            .with_features(
+                vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default()
+                    .acceleration_structure(true),
+            )
+            .with_features(vk::PhysicalDeviceRayQueryFeaturesKHR::default().ray_query(true));
+        device_extensions.ray_query = true;
+        device_extensions
+    }
+
+    /// Enable VK_EXT_mesh_shader, for pipelines from shaders with mesh
+    /// (and task) entry points, drawn with [`super::DrawMeshTasks`]
+    pub fn with_mesh_shaders(self) -> Self {
+        let mut device_extensions = self
+            .with_extension(ash::ext::mesh_shader::NAME)
+            .with_features(
+                vk::PhysicalDeviceMeshShaderFeaturesEXT::default()
+                    .task_shader(true)
+                    .mesh_shader(true),
+            );
+        device_extensions.mesh_shaders = true;
+        device_extensions
+    }
+
+    /// Enable VK_KHR_fragment_shading_rate's per-pipeline rates, for
+    /// pipelines with a [`super::ShadingRate`]; see [`super::shading_rate`]
+    pub fn with_fragment_shading_rate(self) -> Self {
+        let mut device_extensions = self
+            .with_extension(ash::khr::fragment_shading_rate::NAME)
+            .with_features(
+                vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::default()
+                    .pipeline_fragment_shading_rate(true),
+            );
+        device_extensions.fragment_shading_rate = true;
+        device_extensions
+    }
+
+    /// Called once, right after the device is created
+    pub fn with_post_init(
+        mut self,
+        post_init: impl FnOnce(&ash::Instance, vk::PhysicalDevice, &ash::Device) -> anyhow::Result<()>
+        + 'static,
+    ) -> Self {
+        self.post_init = Some(Box::new(post_init));
+        self
+    }
+}
+
+/// A boxed feature struct that can be linked into a pNext chain
+pub(super) trait DeviceFeatures {
+    fn base_out(&mut self) -> *mut vk::BaseOutStructure<'static>;
+}
+
+impl<T: vk::ExtendsDeviceCreateInfo + 'static> DeviceFeatures for T {
+    fn base_out(&mut self) -> *mut vk::BaseOutStructure<'static> {
+        (self as *mut T).cast()
+    }
+}
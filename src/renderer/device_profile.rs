@@ -0,0 +1,170 @@
+//! Defaults picked from the adapter and display the game starts on.
+//!
+//! [`DeviceProfile::detect`] runs once, after the renderer has chosen a
+//! physical device. It decides the render scale and MSAA cap when the game
+//! doesn't set them (see `Game::render_scale` and `Game::max_msaa_samples`,
+//! which still win, and only cap the profile respectively), along with the
+//! preferred present mode and whether to go fullscreen.
+//!
+//! To support a new device, add a match to `detect` ahead of the generic
+//! display-based profile, keyed on the adapter name as the driver reports it
+//! (logged at startup) and the display resolution.
+
+use ash::vk;
+
+use crate::game::MaxMSAASamples;
+
+/// the APU both Steam Deck models report, as `AMD Custom GPU 0405 (RADV VANGOGH)`
+/// on the LCD model and `AMD Custom GPU 0932 (RADV VANGOGH)` on the OLED one
+const STEAM_DECK_ADAPTER: &str = "VANGOGH";
+
+/// the Steam Deck's fixed panel, on both models
+const STEAM_DECK_DISPLAY: (u32, u32) = (1280, 800);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceProfile {
+    /// for logs and the editor
+    pub name: &'static str,
+    /// used when `Game::render_scale` returns None
+    pub render_scale: f32,
+    /// a cap on top of `Game::max_msaa_samples`
+    pub max_msaa_samples: MaxMSAASamples,
+    /// used when the surface supports it; FIFO otherwise
+    pub present_mode: vk::PresentModeKHR,
+    /// Fill the display instead of opening a window. Gamescope shows every
+    /// window fullscreen anyway, scaling it up, so rendering at the display's
+    /// resolution keeps the image sharp.
+    pub fullscreen: bool,
+}
+
+/// What the profile is chosen from
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceInfo<'a> {
+    pub adapter_name: &'a str,
+    /// in pixels; None when SDL can't tell which display the window is on
+    pub display_size: Option<(u32, u32)>,
+    /// see [`running_under_gamescope`]
+    pub gamescope: bool,
+}
+
+impl DeviceProfile {
+    pub fn detect(device: DeviceInfo) -> Self {
+        let is_steam_deck = device.adapter_name.contains(STEAM_DECK_ADAPTER)
+            && device.display_size == Some(STEAM_DECK_DISPLAY);
+
+        if is_steam_deck {
+            return Self {
+                name: "Steam Deck",
+                // 800p is already cheap; scaling it down is visibly blurry
+                render_scale: 1.0,
+                // the APU is short on memory bandwidth, which MSAA spends
+                max_msaa_samples: MaxMSAASamples::Max2,
+                // gamescope paces frames itself, and its frame limiter
+                // (in the quick access menu) only applies to FIFO
+                present_mode: vk::PresentModeKHR::FIFO,
+                fullscreen: device.gamescope,
+            };
+        }
+
+        if device.gamescope {
+            return Self {
+                name: "gamescope",
+                present_mode: vk::PresentModeKHR::FIFO,
+                fullscreen: true,
+                ..Self::for_display(device.display_size)
+            };
+        }
+
+        Self::for_display(device.display_size)
+    }
+
+    /// the desktop default, with larger displays getting a smaller render scale
+    fn for_display(display_size: Option<(u32, u32)>) -> Self {
+        let pixel_count = display_size.map_or(0, |(width, height)| width as u64 * height as u64);
+
+        // Scale based on total pixels:
+        // - 4K+ (3840x2160 = 8.3M pixels): 0.5
+        // - 2K/1440p (2560x1440 = 3.7M pixels): 0.75
+        // - 1080p and below: 1.0
+        let render_scale = if pixel_count >= 8_000_000 {
+            0.5
+        } else if pixel_count >= 3_500_000 {
+            0.75
+        } else {
+            1.0
+        };
+
+        Self {
+            name: "desktop",
+            render_scale,
+            max_msaa_samples: MaxMSAASamples::Max8,
+            // burns battery on mobile, good otherwise
+            present_mode: vk::PresentModeKHR::MAILBOX,
+            fullscreen: false,
+        }
+    }
+}
+
+/// Gamescope (the Steam Deck's compositor in gaming mode, and Steam's
+/// Big Picture sessions on Linux) sets this for the games it runs
+pub fn running_under_gamescope() -> bool {
+    std::env::var_os("GAMESCOPE_WAYLAND_DISPLAY").is_some()
+}
+
+/// the lower of two caps
+pub(super) fn min_msaa_samples(a: MaxMSAASamples, b: MaxMSAASamples) -> MaxMSAASamples {
+    let count = |samples| match samples {
+        MaxMSAASamples::Max8 => 8,
+        MaxMSAASamples::Max4 => 4,
+        MaxMSAASamples::Max2 => 2,
+        MaxMSAASamples::Max1 => 1,
+    };
+
+    if count(a) <= count(b) { a } else { b }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECK_ADAPTER: &str = "AMD Custom GPU 0405 (RADV VANGOGH)";
+
+    #[test]
+    fn steam_deck_in_gaming_mode_is_fullscreen_fifo() {
+        let profile = DeviceProfile::detect(DeviceInfo {
+            adapter_name: DECK_ADAPTER,
+            display_size: Some((1280, 800)),
+            gamescope: true,
+        });
+
+        assert_eq!(profile.name, "Steam Deck");
+        assert_eq!(profile.present_mode, vk::PresentModeKHR::FIFO);
+        assert_eq!(profile.max_msaa_samples, MaxMSAASamples::Max2);
+        assert!(profile.fullscreen);
+    }
+
+    #[test]
+    fn steam_deck_on_an_external_display_uses_the_display() {
+        let profile = DeviceProfile::detect(DeviceInfo {
+            adapter_name: DECK_ADAPTER,
+            display_size: Some((3840, 2160)),
+            gamescope: false,
+        });
+
+        assert_eq!(profile.name, "desktop");
+        assert_eq!(profile.render_scale, 0.5);
+        assert!(!profile.fullscreen);
+    }
+
+    #[test]
+    fn msaa_caps_take_the_lower() {
+        assert_eq!(
+            min_msaa_samples(MaxMSAASamples::Max8, MaxMSAASamples::Max2),
+            MaxMSAASamples::Max2
+        );
+        assert_eq!(
+            min_msaa_samples(MaxMSAASamples::Max1, MaxMSAASamples::Max4),
+            MaxMSAASamples::Max1
+        );
+    }
+}
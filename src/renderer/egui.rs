@@ -6,6 +6,10 @@ use sdl3::keyboard::Keycode;
 use sdl3::mouse::MouseButton;
 
 use super::MAX_FRAMES_IN_FLIGHT;
+use crate::traits::{GamepadAxis, GamepadButton, is_emulated_mouse};
+
+mod gamepad_navigation;
+use gamepad_navigation::GamepadNavigation;
 
 pub struct EguiIntegration {
     start_time: std::time::Instant,
@@ -14,8 +18,18 @@ pub struct EguiIntegration {
     pub ctx: Context,
     renderer: egui_ash_renderer::Renderer,
     raw_input: RawInput,
+    /// the most recent screen size passed to begin_frame, used to scale
+    /// SDL's normalized touch coordinates
+    screen_size: Vec2,
+    /// the finger that drives the emulated pointer, like egui-winit's
+    pointer_touch_id: Option<u64>,
+    gamepad_navigation: GamepadNavigation,
+    /// raw_input.time of the last frame, to move the gamepad cursor by
+    last_frame_time: f64,
     // Textures to free on the next frame (per frame-in-flight slot)
     pending_free_textures: [Vec<egui::TextureId>; MAX_FRAMES_IN_FLIGHT],
+    /// when the last frame asked to be drawn again, if it did
+    repaint_at: Option<std::time::Instant>,
 }
 
 impl EguiIntegration {
@@ -43,9 +57,14 @@ impl EguiIntegration {
             ctx: Context::default(),
             renderer,
             raw_input: RawInput::default(),
+            screen_size: Vec2::ZERO,
+            pointer_touch_id: None,
+            gamepad_navigation: GamepadNavigation::new(),
+            last_frame_time: 0.0,
             start_time: std::time::Instant::now(),
             frame_begun: false,
             pending_free_textures: [vec![], vec![]],
+            repaint_at: None,
         })
     }
 
@@ -60,9 +79,50 @@ impl EguiIntegration {
 
     /// Translate SDL3 event to egui event and accumulate
     pub fn handle_sdl_event(&mut self, event: &SdlEvent) {
-        if let Some(egui_event) = translate_sdl_event(event) {
-            self.raw_input.events.push(egui_event);
+        match event {
+            // SDL only names the files once they're dropped,
+            // so hovering shows egui an anonymous file
+            SdlEvent::DropBegin { .. } => {
+                self.raw_input.hovered_files = vec![egui::HoveredFile::default()];
+                return;
+            }
+            SdlEvent::DropFile { filename, .. } => {
+                self.raw_input.dropped_files.push(egui::DroppedFile {
+                    path: Some(filename.into()),
+                    name: filename.clone(),
+                    ..Default::default()
+                });
+                return;
+            }
+            SdlEvent::DropComplete { .. } => {
+                self.raw_input.hovered_files.clear();
+                return;
+            }
+            SdlEvent::ControllerButtonDown { button, .. }
+            | SdlEvent::ControllerButtonUp { button, .. } => {
+                let pressed = matches!(event, SdlEvent::ControllerButtonDown { .. });
+                if let Some(button) = GamepadButton::from_sdl_button(*button) {
+                    self.gamepad_navigation
+                        .button(button, pressed, &mut self.raw_input.events);
+                }
+                return;
+            }
+            SdlEvent::ControllerAxisMotion { axis, value, .. } => {
+                self.gamepad_navigation.axis(
+                    GamepadAxis::from_sdl_axis(*axis),
+                    GamepadAxis::normalize_sdl_value(*value),
+                );
+                return;
+            }
+            _ => {}
         }
+
+        push_pointer_and_key_events(
+            event,
+            &mut self.pointer_touch_id,
+            self.screen_size,
+            &mut self.raw_input.events,
+        );
         update_modifiers(&mut self.raw_input.modifiers, event);
     }
 
@@ -74,13 +134,43 @@ impl EguiIntegration {
         }
         self.frame_begun = true;
 
-        self.raw_input.time = Some(self.start_time.elapsed().as_secs_f64());
+        self.screen_size = Vec2::new(screen_size[0], screen_size[1]);
+        let time = self.start_time.elapsed().as_secs_f64();
+        self.raw_input.time = Some(time);
         self.raw_input.screen_rect = Some(egui::Rect::from_min_size(
             Pos2::ZERO,
             Vec2::new(screen_size[0], screen_size[1]),
         ));
 
+        let dt = (time - self.last_frame_time) as f32;
+        self.last_frame_time = time;
+        self.gamepad_navigation
+            .update(dt, self.screen_size, &mut self.raw_input.events);
+
         self.ctx.begin_pass(self.raw_input.take());
+
+        if let Some(cursor) = self.gamepad_navigation.cursor() {
+            let layer = egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("gamepad_cursor"));
+            let painter = self.ctx.layer_painter(layer);
+            painter.circle(
+                cursor,
+                6.0,
+                egui::Color32::WHITE,
+                egui::Stroke::new(2.0, egui::Color32::BLACK),
+            );
+        }
+    }
+
+    /// whether gamepad navigation is on, and gamepad input should be kept from the game;
+    /// see the gamepad_navigation module for the controls
+    pub fn wants_gamepad_input(&self) -> bool {
+        self.gamepad_navigation.wants_input()
+    }
+
+    /// When egui next wants a frame, for an animation or a tooltip delay;
+    /// None when it's idle
+    pub fn repaint_deadline(&self) -> Option<std::time::Instant> {
+        self.repaint_at
     }
 
     /// End egui frame and record draw commands into the command buffer.
@@ -96,6 +186,10 @@ impl EguiIntegration {
     ) -> bool {
         let wants_keyboard_input = self.ctx.wants_keyboard_input();
         let output = self.ctx.end_pass();
+        self.repaint_at = output
+            .viewport_output
+            .get(&egui::ViewportId::ROOT)
+            .and_then(|viewport| std::time::Instant::now().checked_add(viewport.repaint_delay));
 
         // Handle texture updates
         self.renderer
@@ -127,6 +221,87 @@ impl EguiIntegration {
     }
 }
 
+/// Pushes the egui events for an SDL event, other than drops and gamepad navigation
+fn push_pointer_and_key_events(
+    event: &SdlEvent,
+    pointer_touch_id: &mut Option<u64>,
+    screen_size: Vec2,
+    events: &mut Vec<Event>,
+) {
+    // SDL also sends a mouse event for each touch and pen action, by default;
+    // the finger and pen events already drive egui's pointer
+    if is_emulated_mouse_event(event) {
+        return;
+    }
+
+    if let Some(touch) = touch_event_parts(event) {
+        handle_touch(touch, pointer_touch_id, screen_size, events);
+        return;
+    }
+
+    if let Some(egui_event) = translate_sdl_event(event) {
+        events.push(egui_event);
+    }
+}
+
+fn is_emulated_mouse_event(event: &SdlEvent) -> bool {
+    match *event {
+        SdlEvent::MouseMotion { which, .. }
+        | SdlEvent::MouseButtonDown { which, .. }
+        | SdlEvent::MouseButtonUp { which, .. } => is_emulated_mouse(which),
+        _ => false,
+    }
+}
+
+/// Forwards a touch to egui's multi-touch handling, and emulates the
+/// pointer with the first finger down so that widgets respond to taps
+fn handle_touch(
+    touch: TouchParts,
+    pointer_touch_id: &mut Option<u64>,
+    screen_size: Vec2,
+    events: &mut Vec<Event>,
+) {
+    let (touch_id, finger_id, phase, x, y, pressure) = touch;
+    let pos = Pos2::new(x * screen_size.x, y * screen_size.y);
+
+    events.push(Event::Touch {
+        device_id: egui::TouchDeviceId(touch_id),
+        id: egui::TouchId(finger_id),
+        phase,
+        pos,
+        force: Some(pressure),
+    });
+
+    match phase {
+        egui::TouchPhase::Start if pointer_touch_id.is_none() => {
+            *pointer_touch_id = Some(finger_id);
+            events.push(Event::PointerMoved(pos));
+            events.push(Event::PointerButton {
+                pos,
+                button: egui::PointerButton::Primary,
+                pressed: true,
+                modifiers: Modifiers::default(),
+            });
+        }
+        egui::TouchPhase::Move if *pointer_touch_id == Some(finger_id) => {
+            events.push(Event::PointerMoved(pos));
+        }
+        egui::TouchPhase::End | egui::TouchPhase::Cancel
+            if *pointer_touch_id == Some(finger_id) =>
+        {
+            *pointer_touch_id = None;
+            events.push(Event::PointerButton {
+                pos,
+                button: egui::PointerButton::Primary,
+                pressed: false,
+                modifiers: Modifiers::default(),
+            });
+            events.push(Event::PointerGone);
+        }
+        _ => {}
+    }
+}
+
 fn translate_sdl_event(event: &SdlEvent) -> Option<Event> {
     match event {
         SdlEvent::MouseMotion { x, y, .. } => Some(Event::PointerMoved(Pos2::new(*x, *y))),
@@ -181,6 +356,23 @@ fn translate_sdl_event(event: &SdlEvent) -> Option<Event> {
 
         SdlEvent::TextInput { text, .. } => Some(Event::Text(text.clone())),
 
+        // pens act as a plain pointer; pressure only reaches the game
+        SdlEvent::PenMotion { x, y, .. } => Some(Event::PointerMoved(Pos2::new(*x, *y))),
+
+        SdlEvent::PenDown { x, y, .. } => Some(Event::PointerButton {
+            pos: Pos2::new(*x, *y),
+            button: egui::PointerButton::Primary,
+            pressed: true,
+            modifiers: Modifiers::default(),
+        }),
+
+        SdlEvent::PenUp { x, y, .. } => Some(Event::PointerButton {
+            pos: Pos2::new(*x, *y),
+            button: egui::PointerButton::Primary,
+            pressed: false,
+            modifiers: Modifiers::default(),
+        }),
+
         SdlEvent::Window { win_event, .. } => match win_event {
             WindowEvent::FocusGained => Some(Event::WindowFocused(true)),
             WindowEvent::FocusLost => Some(Event::WindowFocused(false)),
@@ -192,6 +384,41 @@ fn translate_sdl_event(event: &SdlEvent) -> Option<Event> {
     }
 }
 
+/// the touch device, finger, phase, normalized position, and pressure of a finger event
+type TouchParts = (u64, u64, egui::TouchPhase, f32, f32, f32);
+
+fn touch_event_parts(event: &SdlEvent) -> Option<TouchParts> {
+    let (phase, touch_id, finger_id, x, y, pressure) = match *event {
+        SdlEvent::FingerDown {
+            touch_id,
+            finger_id,
+            x,
+            y,
+            pressure,
+            ..
+        } => (egui::TouchPhase::Start, touch_id, finger_id, x, y, pressure),
+        SdlEvent::FingerMotion {
+            touch_id,
+            finger_id,
+            x,
+            y,
+            pressure,
+            ..
+        } => (egui::TouchPhase::Move, touch_id, finger_id, x, y, pressure),
+        SdlEvent::FingerUp {
+            touch_id,
+            finger_id,
+            x,
+            y,
+            pressure,
+            ..
+        } => (egui::TouchPhase::End, touch_id, finger_id, x, y, pressure),
+        _ => return None,
+    };
+
+    Some((touch_id, finger_id, phase, x, y, pressure))
+}
+
 fn translate_mouse_button(btn: MouseButton) -> Option<egui::PointerButton> {
     match btn {
         MouseButton::Left => Some(egui::PointerButton::Primary),
@@ -272,3 +499,46 @@ fn update_modifiers(modifiers: &mut Modifiers, event: &SdlEvent) {
         modifiers.command = modifiers.ctrl;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_tap_presses_the_pointer_once() {
+        let finger_down = SdlEvent::FingerDown {
+            timestamp: 0,
+            touch_id: 1,
+            finger_id: 1,
+            x: 0.5,
+            y: 0.5,
+            dx: 0.0,
+            dy: 0.0,
+            pressure: 1.0,
+        };
+        // the mouse press SDL sends along with it
+        let mouse_down = SdlEvent::MouseButtonDown {
+            timestamp: 0,
+            window_id: 1,
+            which: sdl3::sys::touch::SDL_TOUCH_MOUSEID,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x: 400.0,
+            y: 300.0,
+        };
+
+        let mut pointer_touch_id = None;
+        let mut events = vec![];
+        let screen_size = Vec2::new(800.0, 600.0);
+        for event in [finger_down, mouse_down] {
+            push_pointer_and_key_events(&event, &mut pointer_touch_id, screen_size, &mut events);
+        }
+
+        let presses = events
+            .iter()
+            .filter(|event| matches!(event, Event::PointerButton { pressed: true, .. }))
+            .count();
+        assert_eq!(presses, 1);
+        assert_eq!(pointer_touch_id, Some(1));
+    }
+}
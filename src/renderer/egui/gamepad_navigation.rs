@@ -0,0 +1,292 @@
+//! Drives egui from a gamepad, so the editor is usable on controller-only
+//! setups like the Steam Deck.
+//!
+//! The Back button (View on Xbox and Steam Deck) toggles navigation; while it's
+//! on, gamepad input goes to egui instead of the game. The left stick moves a
+//! virtual mouse cursor, locked to the window, and South clicks with it. The
+//! d-pad and shoulders move keyboard focus instead, as the arrow keys and
+//! Tab / Shift+Tab do, and South then presses Enter on the focused widget.
+//! East is Escape, closing popups and dropping focus, and the right stick
+//! scrolls whatever is under the cursor.
+
+use egui::{Event, Key, Modifiers, Pos2, Vec2};
+
+use crate::traits::{GamepadAxis, GamepadButton};
+
+const TOGGLE_BUTTON: GamepadButton = GamepadButton::Back;
+
+/// stick deflection below this is treated as centered
+const STICK_DEADZONE: f32 = 0.2;
+
+/// cursor speed at full deflection, in points per second
+const CURSOR_SPEED: f32 = 800.0;
+
+/// scroll speed at full deflection, in points per second
+const SCROLL_SPEED: f32 = 1200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// the stick moved last; South clicks at the cursor
+    Cursor,
+    /// the d-pad or shoulders moved last; South presses the focused widget
+    Focus,
+}
+
+#[derive(Debug)]
+pub(super) struct GamepadNavigation {
+    active: bool,
+    /// the toggle button is down; its release goes to egui either way
+    toggle_held: bool,
+    mode: Mode,
+    cursor: Pos2,
+    screen_size: Vec2,
+    left_stick: Vec2,
+    right_stick: Vec2,
+    /// South went down in cursor mode, so its release ends a click
+    clicking: bool,
+}
+
+impl GamepadNavigation {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            toggle_held: false,
+            mode: Mode::Cursor,
+            cursor: Pos2::ZERO,
+            screen_size: Vec2::ZERO,
+            left_stick: Vec2::ZERO,
+            right_stick: Vec2::ZERO,
+            clicking: false,
+        }
+    }
+
+    /// whether gamepad input should be kept from the game
+    pub fn wants_input(&self) -> bool {
+        self.active || self.toggle_held
+    }
+
+    /// where to draw the virtual cursor, when it's in use
+    pub fn cursor(&self) -> Option<Pos2> {
+        (self.active && self.mode == Mode::Cursor).then_some(self.cursor)
+    }
+
+    pub fn button(&mut self, button: GamepadButton, pressed: bool, events: &mut Vec<Event>) {
+        if button == TOGGLE_BUTTON {
+            self.toggle_held = pressed;
+            if pressed {
+                self.active = !self.active;
+                if self.active {
+                    self.mode = Mode::Cursor;
+                    self.cursor = (self.screen_size / 2.0).to_pos2();
+                    events.push(Event::PointerMoved(self.cursor));
+                } else {
+                    self.release(events);
+                }
+            }
+            return;
+        }
+
+        if !self.active {
+            return;
+        }
+
+        match button {
+            GamepadButton::South => match self.mode {
+                Mode::Cursor => {
+                    // a press from focus mode ends here, after switching
+                    if pressed || self.clicking {
+                        self.clicking = pressed;
+                        events.push(Event::PointerButton {
+                            pos: self.cursor,
+                            button: egui::PointerButton::Primary,
+                            pressed,
+                            modifiers: Modifiers::NONE,
+                        });
+                    }
+                }
+                Mode::Focus => events.push(key_event(Key::Enter, pressed, Modifiers::NONE)),
+            },
+            GamepadButton::East => events.push(key_event(Key::Escape, pressed, Modifiers::NONE)),
+            GamepadButton::DPadUp => {
+                self.move_focus(Key::ArrowUp, pressed, Modifiers::NONE, events)
+            }
+            GamepadButton::DPadDown => {
+                self.move_focus(Key::ArrowDown, pressed, Modifiers::NONE, events)
+            }
+            GamepadButton::DPadLeft => {
+                self.move_focus(Key::ArrowLeft, pressed, Modifiers::NONE, events)
+            }
+            GamepadButton::DPadRight => {
+                self.move_focus(Key::ArrowRight, pressed, Modifiers::NONE, events)
+            }
+            GamepadButton::LeftShoulder => {
+                self.move_focus(Key::Tab, pressed, Modifiers::SHIFT, events)
+            }
+            GamepadButton::RightShoulder => {
+                self.move_focus(Key::Tab, pressed, Modifiers::NONE, events)
+            }
+            _ => {}
+        }
+    }
+
+    pub fn axis(&mut self, axis: GamepadAxis, value: f32) {
+        match axis {
+            GamepadAxis::LeftX => self.left_stick.x = value,
+            GamepadAxis::LeftY => self.left_stick.y = value,
+            GamepadAxis::RightX => self.right_stick.x = value,
+            GamepadAxis::RightY => self.right_stick.y = value,
+            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => {}
+        }
+    }
+
+    /// Moves the cursor and scrolls by the sticks' deflection over `dt`
+    /// seconds. SDL only reports the sticks when they change, so this runs
+    /// every frame rather than per event.
+    pub fn update(&mut self, dt: f32, screen_size: Vec2, events: &mut Vec<Event>) {
+        self.screen_size = screen_size;
+        if !self.active {
+            return;
+        }
+
+        let left_stick = apply_deadzone(self.left_stick);
+        if left_stick != Vec2::ZERO {
+            self.mode = Mode::Cursor;
+            self.cursor = (self.cursor + left_stick * CURSOR_SPEED * dt)
+                .clamp(Pos2::ZERO, screen_size.to_pos2());
+            events.push(Event::PointerMoved(self.cursor));
+        }
+
+        let right_stick = apply_deadzone(self.right_stick);
+        if right_stick != Vec2::ZERO {
+            // pushing the stick down scrolls down, like dragging a scroll bar
+            events.push(Event::MouseWheel {
+                unit: egui::MouseWheelUnit::Point,
+                delta: -right_stick * SCROLL_SPEED * dt,
+                modifiers: Modifiers::NONE,
+            });
+        }
+    }
+
+    fn move_focus(
+        &mut self,
+        key: Key,
+        pressed: bool,
+        modifiers: Modifiers,
+        events: &mut Vec<Event>,
+    ) {
+        if pressed && self.mode == Mode::Cursor {
+            self.release(events);
+            self.mode = Mode::Focus;
+        }
+        events.push(key_event(key, pressed, modifiers));
+    }
+
+    /// ends a held click, and hides the cursor from egui's hover
+    fn release(&mut self, events: &mut Vec<Event>) {
+        if self.clicking {
+            self.clicking = false;
+            events.push(Event::PointerButton {
+                pos: self.cursor,
+                button: egui::PointerButton::Primary,
+                pressed: false,
+                modifiers: Modifiers::NONE,
+            });
+        }
+        if self.mode == Mode::Cursor {
+            events.push(Event::PointerGone);
+        }
+    }
+}
+
+fn key_event(key: Key, pressed: bool, modifiers: Modifiers) -> Event {
+    Event::Key {
+        key,
+        physical_key: None,
+        pressed,
+        repeat: false,
+        modifiers,
+    }
+}
+
+/// Zeroes small deflections, and rescales the rest to start from 0 with a
+/// quadratic curve, for finer control near the center
+fn apply_deadzone(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length <= STICK_DEADZONE {
+        return Vec2::ZERO;
+    }
+
+    let scaled = ((length - STICK_DEADZONE) / (1.0 - STICK_DEADZONE)).min(1.0);
+    stick * (scaled * scaled / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Vec2 = Vec2::new(800.0, 600.0);
+
+    fn activated() -> (GamepadNavigation, Vec<Event>) {
+        let mut navigation = GamepadNavigation::new();
+        let mut events = vec![];
+        navigation.update(0.0, SCREEN, &mut events);
+        navigation.button(TOGGLE_BUTTON, true, &mut events);
+        navigation.button(TOGGLE_BUTTON, false, &mut events);
+        events.clear();
+        (navigation, events)
+    }
+
+    #[test]
+    fn inactive_navigation_ignores_the_gamepad() {
+        let mut navigation = GamepadNavigation::new();
+        let mut events = vec![];
+
+        navigation.axis(GamepadAxis::LeftX, 1.0);
+        navigation.update(1.0, SCREEN, &mut events);
+        navigation.button(GamepadButton::South, true, &mut events);
+
+        assert!(events.is_empty());
+        assert!(!navigation.wants_input());
+    }
+
+    #[test]
+    fn stick_cursor_is_locked_to_the_screen() {
+        let (mut navigation, mut events) = activated();
+        assert_eq!(navigation.cursor(), Some(Pos2::new(400.0, 300.0)));
+
+        navigation.axis(GamepadAxis::LeftX, 1.0);
+        navigation.update(10.0, SCREEN, &mut events);
+        assert_eq!(navigation.cursor(), Some(Pos2::new(800.0, 300.0)));
+
+        navigation.button(GamepadButton::South, true, &mut events);
+        assert_eq!(
+            events.last(),
+            Some(&Event::PointerButton {
+                pos: Pos2::new(800.0, 300.0),
+                button: egui::PointerButton::Primary,
+                pressed: true,
+                modifiers: Modifiers::NONE,
+            })
+        );
+    }
+
+    #[test]
+    fn dpad_moves_focus_and_south_presses_enter() {
+        let (mut navigation, mut events) = activated();
+
+        navigation.button(GamepadButton::DPadDown, true, &mut events);
+        assert_eq!(navigation.cursor(), None);
+        assert_eq!(events[0], Event::PointerGone);
+        assert_eq!(events[1], key_event(Key::ArrowDown, true, Modifiers::NONE));
+
+        events.clear();
+        navigation.button(GamepadButton::South, true, &mut events);
+        assert_eq!(events, [key_event(Key::Enter, true, Modifiers::NONE)]);
+    }
+
+    #[test]
+    fn small_deflections_are_ignored() {
+        assert_eq!(apply_deadzone(Vec2::new(0.1, -0.1)), Vec2::ZERO);
+        assert_eq!(apply_deadzone(Vec2::new(0.0, 1.0)), Vec2::new(0.0, 1.0));
+    }
+}
@@ -0,0 +1,307 @@
+//! Importing images allocated outside the renderer (video decoders, other
+//! processes, other APIs) as sampled textures.
+//!
+//! Only the platform's opaque handle types are supported
+//! (`VK_KHR_external_memory_fd` on linux, `VK_KHR_external_memory_win32` on windows).
+//!
+//! The producer's writes are ordered before the renderer's reads through an
+//! imported semaphore (`VK_KHR_external_semaphore_fd` / `_win32`), which the
+//! producer signals after releasing the image to the external queue family.
+
+use ash::vk;
+
+use super::{begin_single_time_commands, end_single_time_commands_waiting};
+
+/// An owned OS handle to exported device memory.
+///
+/// On success the renderer takes ownership of the handle (Vulkan closes it
+/// when the imported memory is freed). On failure it is dropped normally.
+#[derive(Debug)]
+pub enum ExternalMemoryHandle {
+    #[cfg(unix)]
+    OpaqueFd(std::os::fd::OwnedFd),
+    #[cfg(windows)]
+    OpaqueWin32(std::os::windows::io::OwnedHandle),
+}
+
+impl ExternalMemoryHandle {
+    fn handle_type(&self) -> vk::ExternalMemoryHandleTypeFlags {
+        match self {
+            #[cfg(unix)]
+            Self::OpaqueFd(_) => vk::ExternalMemoryHandleTypeFlags::OPAQUE_FD,
+            #[cfg(windows)]
+            Self::OpaqueWin32(_) => vk::ExternalMemoryHandleTypeFlags::OPAQUE_WIN32,
+        }
+    }
+}
+
+/// An owned OS handle to an exported binary semaphore.
+/// Ownership follows the same rules as [`ExternalMemoryHandle`].
+#[derive(Debug)]
+pub enum ExternalSemaphoreHandle {
+    #[cfg(unix)]
+    OpaqueFd(std::os::fd::OwnedFd),
+    #[cfg(windows)]
+    OpaqueWin32(std::os::windows::io::OwnedHandle),
+}
+
+/// A semaphore shared with another process or API, imported with
+/// [`super::Renderer::import_external_semaphore`].
+/// It lives until the renderer is dropped.
+#[derive(Debug)]
+pub struct ExternalSemaphore {
+    pub(super) semaphore: vk::Semaphore,
+}
+
+/// Describes an externally allocated 2D image, as created by the exporter.
+/// The image parameters must match the exporter's exactly.
+#[derive(Debug)]
+pub struct ExternalImage {
+    pub handle: ExternalMemoryHandle,
+    pub extent: vk::Extent2D,
+    pub format: vk::Format,
+    /// the size of the exported allocation, as reported by the exporter
+    pub allocation_size: vk::DeviceSize,
+    /// whether the exporter made a dedicated allocation for this image
+    pub dedicated: bool,
+}
+
+#[cfg(target_os = "linux")]
+pub(super) const EXTERNAL_MEMORY_DEVICE_EXTENSIONS: &[&std::ffi::CStr] = &[
+    ash::khr::external_memory_fd::NAME,
+    ash::khr::external_semaphore_fd::NAME,
+];
+#[cfg(target_os = "windows")]
+pub(super) const EXTERNAL_MEMORY_DEVICE_EXTENSIONS: &[&std::ffi::CStr] = &[
+    ash::khr::external_memory_win32::NAME,
+    ash::khr::external_semaphore_win32::NAME,
+];
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub(super) const EXTERNAL_MEMORY_DEVICE_EXTENSIONS: &[&std::ffi::CStr] = &[];
+
+/// Creates a binary semaphore and permanently imports the exported payload into it
+pub(super) fn import_semaphore(
+    instance: &ash::Instance,
+    device: &ash::Device,
+    handle: ExternalSemaphoreHandle,
+) -> anyhow::Result<vk::Semaphore> {
+    let semaphore = unsafe { device.create_semaphore(&vk::SemaphoreCreateInfo::default(), None)? };
+
+    let import_result = match handle {
+        #[cfg(unix)]
+        ExternalSemaphoreHandle::OpaqueFd(fd) => {
+            use std::os::fd::{AsRawFd, IntoRawFd};
+
+            let external_semaphore_fd =
+                ash::khr::external_semaphore_fd::Device::new(instance, device);
+            let import_info = vk::ImportSemaphoreFdInfoKHR::default()
+                .semaphore(semaphore)
+                .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD)
+                .fd(fd.as_raw_fd());
+            let result = unsafe { external_semaphore_fd.import_semaphore_fd(&import_info) };
+            if result.is_ok() {
+                // a successful import transfers fd ownership to the driver
+                let _ = fd.into_raw_fd();
+            }
+            result
+        }
+
+        #[cfg(windows)]
+        ExternalSemaphoreHandle::OpaqueWin32(owned_handle) => {
+            use std::os::windows::io::AsRawHandle;
+
+            let external_semaphore_win32 =
+                ash::khr::external_semaphore_win32::Device::new(instance, device);
+            let import_info = vk::ImportSemaphoreWin32HandleInfoKHR::default()
+                .semaphore(semaphore)
+                .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_WIN32)
+                .handle(owned_handle.as_raw_handle() as vk::HANDLE);
+            unsafe { external_semaphore_win32.import_semaphore_win32_handle(&import_info) }
+        }
+    };
+
+    if let Err(e) = import_result {
+        unsafe { device.destroy_semaphore(semaphore, None) };
+        anyhow::bail!("failed to import external semaphore: {e}");
+    }
+
+    Ok(semaphore)
+}
+
+/// The queue family ownership acquire half of the exporter's release.
+/// old_layout must be the exporter's final layout to keep the contents,
+/// and the source stages chain with a semaphore wait on ALL_COMMANDS.
+pub(super) fn acquire_barrier(
+    image: vk::Image,
+    graphics_queue_family: u32,
+) -> vk::ImageMemoryBarrier2<'static> {
+    vk::ImageMemoryBarrier2::default()
+        .old_layout(vk::ImageLayout::GENERAL)
+        .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+        .src_queue_family_index(vk::QUEUE_FAMILY_EXTERNAL)
+        .dst_queue_family_index(graphics_queue_family)
+        .image(image)
+        .subresource_range(super::COLOR_SUBRESOURCE_RANGE)
+        .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+        .src_access_mask(vk::AccessFlags2::NONE)
+        .dst_stage_mask(
+            vk::PipelineStageFlags2::VERTEX_SHADER
+                | vk::PipelineStageFlags2::FRAGMENT_SHADER
+                | vk::PipelineStageFlags2::COMPUTE_SHADER,
+        )
+        .dst_access_mask(vk::AccessFlags2::SHADER_READ)
+}
+
+/// Creates an image bound to imported memory, and transitions it to
+/// SHADER_READ_ONLY_OPTIMAL while acquiring it from the external queue family.
+/// The contents written by the exporter are preserved.
+/// When `ready` is given, the acquire waits for it to be signaled.
+pub(super) fn import_image(
+    instance: &ash::Instance,
+    device: &ash::Device,
+    physical_device: vk::PhysicalDevice,
+    command_pool: vk::CommandPool,
+    graphics_queue: vk::Queue,
+    graphics_queue_family: u32,
+    external_image: ExternalImage,
+    ready: Option<vk::Semaphore>,
+) -> anyhow::Result<(vk::Image, vk::DeviceMemory)> {
+    let ExternalImage {
+        handle,
+        extent,
+        format,
+        allocation_size,
+        dedicated,
+    } = external_image;
+    let handle_type = handle.handle_type();
+
+    let mut external_info = vk::ExternalMemoryImageCreateInfo::default().handle_types(handle_type);
+    let image_create_info = vk::ImageCreateInfo::default()
+        .image_type(vk::ImageType::TYPE_2D)
+        .extent(extent.into())
+        .mip_levels(1)
+        .array_layers(1)
+        .format(format)
+        .tiling(vk::ImageTiling::OPTIMAL)
+        .initial_layout(vk::ImageLayout::UNDEFINED)
+        .usage(vk::ImageUsageFlags::SAMPLED)
+        .sharing_mode(vk::SharingMode::EXCLUSIVE)
+        .samples(vk::SampleCountFlags::TYPE_1)
+        .push_next(&mut external_info);
+    let image = unsafe { device.create_image(&image_create_info, None)? };
+
+    let requirements = unsafe { device.get_image_memory_requirements(image) };
+    let memory_properties =
+        unsafe { instance.get_physical_device_memory_properties(physical_device) };
+    let Some(memory_type_index) = find_memory_type_index(
+        &memory_properties,
+        requirements.memory_type_bits,
+        vk::MemoryPropertyFlags::DEVICE_LOCAL,
+    ) else {
+        unsafe { device.destroy_image(image, None) };
+        anyhow::bail!("no device-local memory type for imported image");
+    };
+
+    let mut dedicated_info = vk::MemoryDedicatedAllocateInfo::default().image(image);
+    let allocate_result = match handle {
+        #[cfg(unix)]
+        ExternalMemoryHandle::OpaqueFd(fd) => {
+            use std::os::fd::{AsRawFd, IntoRawFd};
+
+            let mut import_info = vk::ImportMemoryFdInfoKHR::default()
+                .handle_type(handle_type)
+                .fd(fd.as_raw_fd());
+            let mut allocate_info = vk::MemoryAllocateInfo::default()
+                .allocation_size(allocation_size)
+                .memory_type_index(memory_type_index)
+                .push_next(&mut import_info);
+            if dedicated {
+                allocate_info = allocate_info.push_next(&mut dedicated_info);
+            }
+
+            let result = unsafe { device.allocate_memory(&allocate_info, None) };
+            if result.is_ok() {
+                // a successful import transfers fd ownership to the driver
+                let _ = fd.into_raw_fd();
+            }
+            result
+        }
+
+        #[cfg(windows)]
+        ExternalMemoryHandle::OpaqueWin32(owned_handle) => {
+            use std::os::windows::io::AsRawHandle;
+
+            // NOTE unlike fds, win32 handle imports don't transfer ownership;
+            // owned_handle is closed when it drops at the end of this arm
+            let mut import_info = vk::ImportMemoryWin32HandleInfoKHR::default()
+                .handle_type(handle_type)
+                .handle(owned_handle.as_raw_handle() as vk::HANDLE);
+            let mut allocate_info = vk::MemoryAllocateInfo::default()
+                .allocation_size(allocation_size)
+                .memory_type_index(memory_type_index)
+                .push_next(&mut import_info);
+            if dedicated {
+                allocate_info = allocate_info.push_next(&mut dedicated_info);
+            }
+
+            unsafe { device.allocate_memory(&allocate_info, None) }
+        }
+    };
+
+    let memory = match allocate_result {
+        Ok(memory) => memory,
+        Err(e) => {
+            unsafe { device.destroy_image(image, None) };
+            anyhow::bail!("failed to import external memory: {e}");
+        }
+    };
+
+    let bind_and_acquire = || -> anyhow::Result<()> {
+        unsafe { device.bind_image_memory(image, memory, 0)? };
+
+        let command_buffer = begin_single_time_commands(device, command_pool)?;
+
+        let barrier = acquire_barrier(image, graphics_queue_family);
+        super::cmd_barrier2(device, command_buffer, &[barrier]);
+
+        let waits: Vec<_> = ready
+            .into_iter()
+            .map(|semaphore| {
+                vk::SemaphoreSubmitInfo::default()
+                    .semaphore(semaphore)
+                    .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+            })
+            .collect();
+        end_single_time_commands_waiting(
+            device,
+            command_pool,
+            graphics_queue,
+            command_buffer,
+            &waits,
+        )
+    };
+
+    if let Err(e) = bind_and_acquire() {
+        unsafe {
+            device.destroy_image(image, None);
+            device.free_memory(memory, None);
+        }
+        return Err(e);
+    }
+
+    Ok((image, memory))
+}
+
+fn find_memory_type_index(
+    memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    memory_type_bits: u32,
+    required_flags: vk::MemoryPropertyFlags,
+) -> Option<u32> {
+    memory_properties.memory_types[..memory_properties.memory_type_count as usize]
+        .iter()
+        .enumerate()
+        .find(|(i, memory_type)| {
+            memory_type_bits & (1 << i) != 0 && memory_type.property_flags.contains(required_flags)
+        })
+        .map(|(i, _)| i as u32)
+}
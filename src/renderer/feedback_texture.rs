@@ -0,0 +1,94 @@
+use ash::vk;
+
+use super::{PRE_WAIT_RING_LEN, TextureHandle};
+
+/// An offscreen color target that keeps one image per ring slot, so a pass can
+/// read what was rendered into it on the previous frame (trails, cellular
+/// automata, feedback effects) without compute.
+///
+/// Pipelines created with [`super::PipelineConfig::with_render_target`] draw
+/// into this frame's image in a pass that runs before the main pass, which
+/// clears it first. The two texture handles can be bound like any other
+/// texture; the renderer rotates which image they refer to every frame.
+#[derive(Debug)]
+pub struct FeedbackTextureHandle {
+    pub(super) index: usize,
+    history: TextureHandle,
+    current: TextureHandle,
+}
+
+impl FeedbackTextureHandle {
+    /// the image rendered into on the previous frame
+    pub fn history(&self) -> &TextureHandle {
+        &self.history
+    }
+
+    /// the image rendered into on this frame; only valid to sample after the
+    /// feedback pass, ie from the main pass
+    pub fn current(&self) -> &TextureHandle {
+        &self.current
+    }
+}
+
+pub(super) struct FeedbackTextureStorage(Vec<FeedbackTexture>);
+
+impl FeedbackTextureStorage {
+    pub fn new() -> Self {
+        Self(Default::default())
+    }
+
+    pub fn add(
+        &mut self,
+        texture: FeedbackTexture,
+        history: TextureHandle,
+        current: TextureHandle,
+    ) -> FeedbackTextureHandle {
+        let handle = FeedbackTextureHandle {
+            index: self.0.len(),
+            history,
+            current,
+        };
+        self.0.push(texture);
+
+        handle
+    }
+
+    pub fn get_by_index(&self, index: usize) -> &FeedbackTexture {
+        &self.0[index]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FeedbackTexture> {
+        self.0.iter()
+    }
+
+    pub fn take_all(&mut self) -> Vec<FeedbackTexture> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+pub(super) struct FeedbackTexture {
+    /// images indexed by ring_slot
+    pub(super) images: [vk::Image; PRE_WAIT_RING_LEN],
+    pub(super) image_memories: [vk_mem::Allocation; PRE_WAIT_RING_LEN],
+    pub(super) image_views: [vk::ImageView; PRE_WAIT_RING_LEN],
+    pub(super) format: vk::Format,
+    pub(super) extent: vk::Extent2D,
+    /// the TextureStorage index of the aliased history texture
+    pub(super) history_texture_index: usize,
+    /// the TextureStorage index of the aliased current texture
+    pub(super) current_texture_index: usize,
+}
+
+impl FeedbackTexture {
+    /// The offset from the current ring slot to the image the given texture
+    /// index should sample, if it's one of this feedback texture's aliases
+    pub(super) fn ring_offset_for(&self, texture_index: usize) -> Option<usize> {
+        if texture_index == self.history_texture_index {
+            Some(PRE_WAIT_RING_LEN - 1)
+        } else if texture_index == self.current_texture_index {
+            Some(0)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Programmatic frame captures with RenderDoc's in-application API.
+//!
+//! With the `renderdoc` feature, the renderer connects to RenderDoc when the
+//! process was launched or injected by it; it never loads RenderDoc itself.
+//! Without the feature, or outside RenderDoc, capture requests are ignored.
+
+use log::*;
+
+pub(super) struct FrameCapture {
+    #[cfg(feature = "renderdoc")]
+    api: Option<renderdoc::RenderDoc<renderdoc::V141>>,
+}
+
+impl FrameCapture {
+    #[cfg(feature = "renderdoc")]
+    pub(super) fn connect() -> Self {
+        let api = match renderdoc::RenderDoc::new() {
+            Ok(api) => {
+                let (major, minor, patch) = api.get_api_version();
+                info!("connected to RenderDoc API {major}.{minor}.{patch}");
+                Some(api)
+            }
+            Err(error) => {
+                debug!("not running under RenderDoc: {error}");
+                None
+            }
+        };
+
+        Self { api }
+    }
+
+    #[cfg(not(feature = "renderdoc"))]
+    pub(super) fn connect() -> Self {
+        Self {}
+    }
+
+    pub(super) fn is_available(&self) -> bool {
+        #[cfg(feature = "renderdoc")]
+        let available = self.api.is_some();
+        #[cfg(not(feature = "renderdoc"))]
+        let available = false;
+
+        available
+    }
+
+    /// Capture the next frame; returns false if RenderDoc isn't available
+    pub(super) fn trigger(&mut self) -> bool {
+        #[cfg(feature = "renderdoc")]
+        if let Some(api) = &mut self.api {
+            api.trigger_capture();
+            info!("capturing the next frame with RenderDoc");
+            return true;
+        }
+
+        #[cfg(feature = "renderdoc")]
+        warn!("frame capture requested, but not running under RenderDoc");
+        #[cfg(not(feature = "renderdoc"))]
+        warn!("frame capture requested, but built without the renderdoc feature");
+
+        false
+    }
+}
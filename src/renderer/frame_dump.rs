@@ -0,0 +1,210 @@
+//! Saving one frame's GPU inputs and offscreen targets to a directory, for
+//! diffing frames offline when chasing layout or upload bugs.
+//!
+//! [`Renderer::dump_next_frame`](super::Renderer::dump_next_frame) writes
+//! every uniform and storage buffer's copy for the frame, as the game left it
+//! once its writes were done, to a JSON file named for the buffer
+//! (`uniform_3.json`). Buffers registered with
+//! [`Renderer::serialize_uniform_in_dumps`](super::Renderer::serialize_uniform_in_dumps)
+//! or [`Renderer::serialize_storage_in_dumps`](super::Renderer::serialize_storage_in_dumps)
+//! are written through their types' `Serialize` derives; the rest as hex.
+//! The scene image and each 8-bit RGBA feedback texture are read back and
+//! saved as PNGs a couple of frames later.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::{AuditedBuffer, Readback};
+
+/// How to write a buffer's contents as JSON, from its type
+#[derive(Clone, Copy)]
+pub(super) struct DumpFormat {
+    type_name: &'static str,
+    /// the bytes the typed contents span
+    size: usize,
+    serialize: fn(&[u8]) -> serde_json::Result<serde_json::Value>,
+}
+
+impl DumpFormat {
+    pub fn value<T: Serialize>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            size: std::mem::size_of::<T>(),
+            serialize: serialize_value::<T>,
+        }
+    }
+
+    pub fn elements<T: Serialize>(len: u32) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            size: len as usize * std::mem::size_of::<T>(),
+            serialize: serialize_elements::<T>,
+        }
+    }
+}
+
+fn serialize_value<T: Serialize>(bytes: &[u8]) -> serde_json::Result<serde_json::Value> {
+    check_layout::<T>(bytes)?;
+    if bytes.len() < std::mem::size_of::<T>() {
+        return Err(serde::ser::Error::custom(format!(
+            "{} bytes can't hold a {} of {} bytes",
+            bytes.len(),
+            std::any::type_name::<T>(),
+            std::mem::size_of::<T>()
+        )));
+    }
+    // the bytes are a mapped copy of a buffer created for T
+    let value = unsafe { &*bytes.as_ptr().cast::<T>() };
+    serde_json::to_value(value)
+}
+
+fn serialize_elements<T: Serialize>(bytes: &[u8]) -> serde_json::Result<serde_json::Value> {
+    check_layout::<T>(bytes)?;
+    let len = bytes.len() / std::mem::size_of::<T>();
+    let elements = unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast::<T>(), len) };
+    serde_json::to_value(elements)
+}
+
+fn check_layout<T>(bytes: &[u8]) -> serde_json::Result<()> {
+    if std::mem::size_of::<T>() == 0 || !bytes.as_ptr().cast::<T>().is_aligned() {
+        return Err(serde::ser::Error::custom(format!(
+            "the bytes can't be read as {}",
+            std::any::type_name::<T>()
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct BufferDump {
+    buffer: String,
+    size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    type_name: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hex: Option<String>,
+}
+
+impl BufferDump {
+    fn new(
+        buffer: AuditedBuffer,
+        bytes: &[u8],
+        format: Option<DumpFormat>,
+    ) -> anyhow::Result<Self> {
+        let dump = match format {
+            Some(format) => {
+                let bytes = &bytes[..format.size.min(bytes.len())];
+                Self {
+                    buffer: buffer.to_string(),
+                    size: bytes.len(),
+                    type_name: Some(format.type_name),
+                    value: Some((format.serialize)(bytes)?),
+                    hex: None,
+                }
+            }
+            None => Self {
+                buffer: buffer.to_string(),
+                size: bytes.len(),
+                type_name: None,
+                value: None,
+                hex: Some(hex(bytes)),
+            },
+        };
+
+        Ok(dump)
+    }
+}
+
+/// Writes one buffer's contents to `dir`, named for the buffer
+pub(super) fn write_buffer(
+    dir: &Path,
+    buffer: AuditedBuffer,
+    bytes: &[u8],
+    format: Option<DumpFormat>,
+) -> anyhow::Result<()> {
+    let dump = BufferDump::new(buffer, bytes, format)?;
+    let file_name = format!("{}.json", buffer.to_string().replace(' ', "_"));
+    let json = serde_json::to_string_pretty(&dump)?;
+    std::fs::write(dir.join(file_name), json)?;
+    Ok(())
+}
+
+/// Saves an image readback as a PNG, logging what went wrong instead,
+/// since it arrives frames after the dump was requested
+pub(super) fn save_png(path: &Path, readback: Readback) {
+    let Some(image) = readback.to_rgba_image() else {
+        log::warn!("frame dump: {} is not 8-bit RGBA", path.display());
+        return;
+    };
+    match image.save(path) {
+        Ok(()) => log::info!("frame dump: saved {}", path.display()),
+        Err(error) => log::error!("frame dump: saving {}: {error}", path.display()),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    #[repr(C, align(16))]
+    struct Params {
+        time: f32,
+        scale: f32,
+    }
+
+    #[test]
+    fn registered_buffers_are_serialized_and_the_rest_are_hex() {
+        let params = Params {
+            time: 1.5,
+            scale: 2.0,
+        };
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                (&params as *const Params).cast::<u8>(),
+                std::mem::size_of::<Params>(),
+            )
+        };
+
+        let typed = BufferDump::new(
+            AuditedBuffer::Uniform(0),
+            bytes,
+            Some(DumpFormat::value::<Params>()),
+        )
+        .unwrap();
+        assert_eq!(
+            typed.value,
+            Some(serde_json::json!({ "time": 1.5, "scale": 2.0 }))
+        );
+        assert_eq!(typed.hex, None);
+
+        let raw = BufferDump::new(AuditedBuffer::Storage(1), &[0, 1, 0xab], None).unwrap();
+        assert_eq!(raw.hex.as_deref(), Some("0001ab"));
+        assert_eq!(raw.value, None);
+    }
+
+    #[test]
+    fn buffers_too_short_for_their_type_are_errors() {
+        let params = Params {
+            time: 1.5,
+            scale: 2.0,
+        };
+        // just the time
+        let bytes =
+            unsafe { std::slice::from_raw_parts((&params as *const Params).cast::<u8>(), 4) };
+
+        let short = BufferDump::new(
+            AuditedBuffer::Uniform(0),
+            bytes,
+            Some(DumpFormat::value::<Params>()),
+        );
+        assert!(short.is_err());
+    }
+}
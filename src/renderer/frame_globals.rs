@@ -0,0 +1,189 @@
+//! Values every shader might want each frame, kept by the renderer in a uniform
+//! buffer of its own.
+//!
+//! A shader opts in by importing `frame_globals` from shaders/lib, and declaring a
+//! `ParameterBlock<FrameGlobals>`. Its generated `pipeline_config` then binds
+//! [`RawUniformBufferHandle::frame_globals`] instead of taking the buffer from
+//! its `Resources`, and the renderer swaps in its buffer at pipeline creation
+//! and writes it before each frame's `gpu_update`.
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+use super::gpu_write::GPUWrite;
+use super::hdr::{DisplayLuminance, HdrOutput};
+use super::{RawUniformBufferHandle, UniformBufferHandle, UniformElementType};
+
+/// `FrameGlobals` in shaders/lib/frame_globals.slang, laid out to match its std140 reflection
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[repr(C, align(16))]
+pub struct FrameGlobals {
+    /// the render extent in pixels
+    pub resolution: glam::Vec2,
+    /// seconds since the renderer's first frame
+    pub time: f32,
+    /// seconds since the previous frame; 0 on the first
+    pub delta_time: f32,
+    /// frames drawn before this one
+    pub frame_index: u32,
+    /// the swapchain's [`HdrOutput`], as its `shader_value`
+    pub output_color_space: u32,
+    /// what 1.0 in linear color shows as
+    pub paper_white_nits: f32,
+    /// the brightest the display shows; paper white on SDR displays
+    pub display_max_nits: f32,
+}
+
+impl GPUWrite for FrameGlobals {}
+const _: () = assert!(std::mem::size_of::<FrameGlobals>() == 32);
+
+/// the slang struct generated code recognizes as the renderer's
+pub const FRAME_GLOBALS_TYPE_NAME: &str = "FrameGlobals";
+
+/// stands in for the renderer's buffer until a pipeline is created
+const PLACEHOLDER_INDEX: usize = usize::MAX;
+
+impl RawUniformBufferHandle {
+    /// The renderer's frame globals buffer, for generated pipeline configs;
+    /// resolved to the real buffer when the pipeline is created
+    pub fn frame_globals() -> Self {
+        Self::from_parts(PLACEHOLDER_INDEX, UniformElementType::of::<FrameGlobals>())
+    }
+
+    pub(super) fn is_frame_globals(&self) -> bool {
+        self.index() == PLACEHOLDER_INDEX
+    }
+}
+
+/// The renderer's frame globals buffer, created by the first pipeline that binds it,
+/// and the clock its times come from
+pub(super) struct FrameGlobalsState {
+    pub(super) buffer: Option<UniformBufferHandle<FrameGlobals>>,
+    first_frame: Option<Instant>,
+    previous_frame: Option<Instant>,
+}
+
+impl FrameGlobalsState {
+    pub(super) fn new() -> Self {
+        Self {
+            buffer: None,
+            first_frame: None,
+            previous_frame: None,
+        }
+    }
+
+    /// This frame's values, advancing the clock to `now`
+    pub(super) fn tick(
+        &mut self,
+        now: Instant,
+        frame_index: u32,
+        resolution: glam::Vec2,
+        output: HdrOutput,
+        luminance: DisplayLuminance,
+    ) -> FrameGlobals {
+        let first_frame = *self.first_frame.get_or_insert(now);
+        let delta_time = self
+            .previous_frame
+            .map_or(0.0, |previous| (now - previous).as_secs_f32());
+        self.previous_frame = Some(now);
+
+        FrameGlobals {
+            resolution,
+            time: (now - first_frame).as_secs_f32(),
+            delta_time,
+            frame_index,
+            output_color_space: output.shader_value(),
+            paper_white_nits: luminance.paper_white_nits,
+            display_max_nits: luminance.max_nits,
+        }
+    }
+}
+
+/// Points any placeholder handles at the renderer's buffer
+pub(super) fn resolve_placeholders(
+    handles: &mut [RawUniformBufferHandle],
+    buffer: &UniformBufferHandle<FrameGlobals>,
+) {
+    for handle in handles {
+        if handle.is_frame_globals() {
+            *handle = RawUniformBufferHandle::from_typed(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn times_count_from_the_first_frame() {
+        let mut state = FrameGlobalsState::new();
+        let start = Instant::now();
+        let resolution = glam::Vec2::new(640.0, 480.0);
+
+        let tick = |state: &mut FrameGlobalsState, elapsed: u64, frame_index| {
+            let now = start + Duration::from_millis(elapsed);
+            state.tick(
+                now,
+                frame_index,
+                resolution,
+                HdrOutput::Off,
+                DisplayLuminance::SDR,
+            )
+        };
+
+        let first = tick(&mut state, 0, 0);
+        assert_eq!(first.time, 0.0);
+        assert_eq!(first.delta_time, 0.0);
+
+        let second = tick(&mut state, 250, 1);
+        let third = tick(&mut state, 750, 2);
+        assert_eq!(second.delta_time, 0.25);
+        assert_eq!(third.delta_time, 0.5);
+        assert_eq!(third.time, 0.75);
+        assert_eq!(third.frame_index, 2);
+        assert_eq!(third.resolution, resolution);
+    }
+
+    #[test]
+    fn display_values_come_from_the_swapchain() {
+        let mut state = FrameGlobalsState::new();
+        let luminance = DisplayLuminance {
+            paper_white_nits: 200.0,
+            max_nits: 1000.0,
+        };
+
+        let globals = state.tick(
+            Instant::now(),
+            0,
+            glam::Vec2::ONE,
+            HdrOutput::Hdr10,
+            luminance,
+        );
+        assert_eq!(globals.output_color_space, 2);
+        assert_eq!(globals.paper_white_nits, 200.0);
+        assert_eq!(globals.display_max_nits, 1000.0);
+    }
+
+    #[test]
+    fn placeholders_resolve_to_the_renderer_buffer() {
+        let buffer = UniformBufferHandle::<FrameGlobals>::from_raw(3);
+        let other = UniformBufferHandle::<FrameGlobals>::from_raw(1);
+        let mut handles = [
+            RawUniformBufferHandle::from_typed(&other),
+            RawUniformBufferHandle::frame_globals(),
+        ];
+        assert!(handles[1].is_frame_globals());
+
+        resolve_placeholders(&mut handles, &buffer);
+        let indices: Vec<_> = handles.iter().map(|handle| handle.index()).collect();
+        assert_eq!(indices, [1, 3]);
+        assert_eq!(
+            handles[1].element_type(),
+            UniformElementType::of::<FrameGlobals>()
+        );
+    }
+}
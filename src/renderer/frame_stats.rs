@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use super::pipeline::{GraphicsPipelineIndex, PipelineIndex};
+
+/// Per-pipeline draw counts for the last submitted frame, for checking that
+/// culling is doing its job. See [`super::Renderer::frame_stats`].
+///
+/// Culling code reports the draws it skipped with
+/// [`super::FrameRenderer::record_culled`]; every queued draw counts as both
+/// submitted and drawn. Variants count toward the pipeline they were cloned from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// in order of each pipeline's first draw this frame,
+    /// then the pipelines whose draws were all culled
+    pub pipelines: Vec<PipelineDrawStats>,
+    /// how long the most recently displayed frame took to reach the display
+    /// after being presented; None without present wait support
+    pub display_latency: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineDrawStats {
+    pub shader_name: String,
+    /// draws queued plus draws culled before queueing
+    pub submitted: u32,
+    /// draws recorded into the frame's command buffer
+    pub drawn: u32,
+    pipeline_index: usize,
+}
+
+impl PipelineDrawStats {
+    pub fn culled(&self) -> u32 {
+        self.submitted - self.drawn
+    }
+
+    pub fn pipeline(&self) -> GraphicsPipelineIndex {
+        GraphicsPipelineIndex::from_raw(self.pipeline_index)
+    }
+}
+
+impl FrameStats {
+    /// The number of draw calls recorded, across all pipelines
+    pub fn draw_calls(&self) -> u32 {
+        self.pipelines.iter().map(|stats| stats.drawn).sum()
+    }
+
+    pub(super) fn count(
+        drawn: impl IntoIterator<Item = GraphicsPipelineIndex>,
+        culled: &[(GraphicsPipelineIndex, u32)],
+        shader_name: impl Fn(GraphicsPipelineIndex) -> String,
+    ) -> Self {
+        let mut stats = Self::default();
+
+        let drawn = drawn.into_iter().map(|index| (index, 1, 1));
+        let culled = culled.iter().map(|&(index, count)| (index, count, 0));
+        for (index, submitted, drawn) in drawn.chain(culled) {
+            let pipeline_stats = match stats
+                .pipelines
+                .iter_mut()
+                .position(|stats| stats.pipeline_index == index.raw())
+            {
+                Some(position) => &mut stats.pipelines[position],
+                None => {
+                    stats.pipelines.push(PipelineDrawStats {
+                        shader_name: shader_name(index),
+                        submitted: 0,
+                        drawn: 0,
+                        pipeline_index: index.raw(),
+                    });
+                    stats.pipelines.last_mut().unwrap()
+                }
+            };
+
+            pipeline_stats.submitted += submitted;
+            pipeline_stats.drawn += drawn;
+        }
+
+        stats
+    }
+}
@@ -0,0 +1,59 @@
+//! The half of the renderer that doesn't depend on a window:
+//! the Vulkan instance and device, their queues, the allocator,
+//! and the pipelines and buffers created through them.
+
+use ash::vk;
+
+use super::QueueFamilyIndices;
+use super::acceleration_structure::{AccelerationStructureStorage, RayQuery};
+use super::breadcrumbs::Breadcrumbs;
+use super::counter_buffer::CounterBufferStorage;
+use super::pipeline::{ComputePipelineStorage, PipelineStorage, VertexAndIndexBuffers};
+use super::storage_buffer::StorageBufferStorage;
+use super::storage_texture::StorageTextureStorage;
+use super::texture::TextureStorage;
+use super::uniform_buffer::UniformBufferStorage;
+
+/// Owned by the Renderer, which destroys everything in it on drop;
+/// the device has to outlive the window's swapchain, and the instance its surface.
+pub struct GpuContext {
+    #[expect(unused)]
+    pub(super) entry: ash::Entry,
+    pub(super) instance: ash::Instance,
+    pub(super) debug_ext: vk::DebugUtilsMessengerEXT,
+    pub(super) debug_loader: ash::ext::debug_utils::Instance,
+    pub(super) physical_device: vk::PhysicalDevice,
+    pub(super) physical_device_properties: vk::PhysicalDeviceProperties,
+    pub(super) queue_family_indices: QueueFamilyIndices,
+    /// whether the platform's external memory and semaphore import extensions were enabled
+    pub(super) external_memory_supported: bool,
+    /// imported with Renderer::import_external_semaphore; destroyed at teardown
+    pub(super) external_semaphores: Vec<vk::Semaphore>,
+    pub(super) device: ash::Device,
+    /// ManuallyDrop because Renderer has a manual Drop impl: the allocator must be
+    /// destroyed after all buffers/images are freed but before destroy_device.
+    pub(super) allocator: std::mem::ManuallyDrop<vk_mem::Allocator>,
+    pub(super) debug_utils_device: ash::ext::debug_utils::Device,
+    pub(super) graphics_queue: vk::Queue,
+    /// Dedicated compute queue for async compute (None = single-queue fallback)
+    pub(super) compute_queue: Option<vk::Queue>,
+    /// present when the game enabled DeviceExtensions::with_ray_query
+    pub(super) ray_query: Option<RayQuery>,
+    /// present when the game enabled DeviceExtensions::with_mesh_shaders
+    pub(super) mesh_shader: Option<ash::ext::mesh_shader::Device>,
+    /// present when the game enabled DeviceExtensions::with_fragment_shading_rate
+    pub(super) fragment_shading_rate: Option<ash::khr::fragment_shading_rate::Device>,
+    /// GPU crash markers; present in debug builds on devices that support them
+    pub(super) breadcrumbs: Option<Breadcrumbs>,
+
+    pub(super) pipelines: PipelineStorage,
+    pub(super) compute_pipelines: ComputePipelineStorage,
+    /// meshes shared between pipelines; freed only at renderer teardown
+    pub(super) meshes: Vec<VertexAndIndexBuffers>,
+    pub(super) textures: TextureStorage,
+    pub(super) storage_textures: StorageTextureStorage,
+    pub(super) uniform_buffers: UniformBufferStorage,
+    pub(super) storage_buffers: StorageBufferStorage,
+    pub(super) counter_buffers: CounterBufferStorage,
+    pub(super) acceleration_structures: AccelerationStructureStorage,
+}
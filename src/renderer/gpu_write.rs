@@ -1,3 +1,5 @@
+use super::{Addr, ImmutableAddr, ReadAddr};
+
 /// Marker type for shaders that don't use vertex input buffers.
 /// Used as the vertex type parameter for fullscreen quads, procedural geometry, etc.
 pub enum NoVertex {}
@@ -11,8 +13,87 @@ pub trait GPUWrite {}
 impl GPUWrite for u8 {} // image bytes
 impl GPUWrite for f32 {} // storage texture data
 impl GPUWrite for u32 {} // index buffer
+impl GPUWrite for u64 {}
+// std430 arrays behind a scalar or vector Addr<T>
+impl GPUWrite for glam::Vec2 {}
+impl GPUWrite for glam::Vec3A {}
+impl GPUWrite for glam::Vec4 {}
+impl GPUWrite for glam::UVec2 {}
+impl GPUWrite for glam::UVec4 {}
+impl GPUWrite for ash::vk::AccelerationStructureInstanceKHR {} // tlas instances
 impl GPUWrite for NoVertex {}
 
+/// Values for the `sample()` constructors of generated GPU structs,
+/// for tools and tests that need some instance of any shader's params
+///
+/// Each float or uint holds the index of the 4-byte word it's written to
+/// (from `word`), so a value read back through the wrong layout names
+/// the offset it came from. Addresses are null.
+pub trait SampleValue {
+    fn sample_at(word: u32) -> Self;
+}
+
+impl SampleValue for f32 {
+    fn sample_at(word: u32) -> Self {
+        word as f32
+    }
+}
+
+impl SampleValue for u32 {
+    fn sample_at(word: u32) -> Self {
+        word
+    }
+}
+
+// one word index for both halves
+impl SampleValue for u64 {
+    fn sample_at(word: u32) -> Self {
+        word as u64
+    }
+}
+
+impl SampleValue for glam::Vec2 {
+    fn sample_at(word: u32) -> Self {
+        glam::Vec2::from_array(std::array::from_fn(|i| (word + i as u32) as f32))
+    }
+}
+
+impl SampleValue for glam::Vec3 {
+    fn sample_at(word: u32) -> Self {
+        glam::Vec3::from_array(std::array::from_fn(|i| (word + i as u32) as f32))
+    }
+}
+
+impl SampleValue for glam::Vec4 {
+    fn sample_at(word: u32) -> Self {
+        glam::Vec4::from_array(std::array::from_fn(|i| (word + i as u32) as f32))
+    }
+}
+
+impl SampleValue for glam::Mat4 {
+    fn sample_at(word: u32) -> Self {
+        glam::Mat4::from_cols_array(&std::array::from_fn(|i| (word + i as u32) as f32))
+    }
+}
+
+impl<T> SampleValue for Addr<T> {
+    fn sample_at(_word: u32) -> Self {
+        Self::default()
+    }
+}
+
+impl<T> SampleValue for ReadAddr<T> {
+    fn sample_at(_word: u32) -> Self {
+        Self::default()
+    }
+}
+
+impl<T> SampleValue for ImmutableAddr<T> {
+    fn sample_at(_word: u32) -> Self {
+        Self::default()
+    }
+}
+
 pub(super) unsafe fn write_to_gpu_buffer<T: GPUWrite>(
     allocator: &vk_mem::Allocator,
     allocation: &mut vk_mem::Allocation,
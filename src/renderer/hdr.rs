@@ -0,0 +1,294 @@
+//! HDR output, on displays and drivers that offer an HDR swapchain through
+//! VK_EXT_swapchain_colorspace.
+//!
+//! A game asks for it with [`Game::hdr_output`](crate::game::Game::hdr_output).
+//! Where the surface has a matching format, the swapchain (and so the resolve,
+//! scene color, and post-process images that share its format) switches to it;
+//! elsewhere the renderer stays SDR, and [`Renderer::hdr_output`](super::Renderer::hdr_output)
+//! says which one is active.
+//!
+//! Unlike the SDR swapchain, neither HDR format encodes in hardware, so the
+//! last shader to draw a frame has to. Shaders importing `hdr` from
+//! shaders/lib do that from the frame globals:
+//!
+//! ```slang
+//! let display = tonemapToDisplay(sceneColor * exposure(ev), frame);
+//! return encodeOutput(display, 1.0, frame);
+//! ```
+//!
+//! The editor's UI is drawn without an encoding, so it looks off under HDR10.
+//!
+//! Where the device has VK_EXT_hdr_metadata, HDR swapchains also describe the
+//! display they're mastered for ([`hdr_metadata`]), so compositors that tone
+//! map, like gamescope, map the game's highlights to the panel's range
+//! instead of guessing.
+
+use ash::vk;
+use sdl3::video::Window;
+
+/// The swapchain color space to ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u32)]
+pub enum HdrOutput {
+    /// 8-bit sRGB, which the swapchain encodes
+    #[default]
+    Off = 0,
+    /// 16-bit float linear Rec. 709, where 1.0 is 80 nits;
+    /// what Windows composes HDR desktops in
+    Scrgb = 1,
+    /// 10-bit Rec. 2020 with the ST 2084 (PQ) curve, the HDR10 signal itself
+    Hdr10 = 2,
+}
+
+impl HdrOutput {
+    /// matches the `OUTPUT_*` constants in shaders/lib/hdr.slang
+    pub fn shader_value(self) -> u32 {
+        self as u32
+    }
+
+    pub fn is_hdr(self) -> bool {
+        self != Self::Off
+    }
+
+    /// The surface formats for this output, in order of preference
+    fn surface_formats(self) -> &'static [vk::SurfaceFormatKHR] {
+        match self {
+            Self::Off => &[SDR_SURFACE_FORMAT],
+            Self::Scrgb => &[vk::SurfaceFormatKHR {
+                format: vk::Format::R16G16B16A16_SFLOAT,
+                color_space: vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT,
+            }],
+            Self::Hdr10 => &[
+                vk::SurfaceFormatKHR {
+                    format: vk::Format::A2B10G10R10_UNORM_PACK32,
+                    color_space: vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+                },
+                vk::SurfaceFormatKHR {
+                    format: vk::Format::A2R10G10B10_UNORM_PACK32,
+                    color_space: vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+                },
+            ],
+        }
+    }
+}
+
+const SDR_SURFACE_FORMAT: vk::SurfaceFormatKHR = vk::SurfaceFormatKHR {
+    format: vk::Format::B8G8R8A8_SRGB,
+    color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+};
+
+/// The surface format for the requested output, and the output it gives;
+/// falls back to SDR, and then to whatever the surface lists first
+pub(super) fn choose_surface_format(
+    available: &[vk::SurfaceFormatKHR],
+    requested: HdrOutput,
+) -> (vk::SurfaceFormatKHR, HdrOutput) {
+    let preferred = requested
+        .surface_formats()
+        .iter()
+        .find(|format| available.contains(format));
+    if let Some(&format) = preferred {
+        return (format, requested);
+    }
+
+    if requested.is_hdr() {
+        log::warn!("no {requested:?} surface format; falling back to SDR");
+    }
+    if available.contains(&SDR_SURFACE_FORMAT) {
+        return (SDR_SURFACE_FORMAT, HdrOutput::Off);
+    }
+
+    let fallback = available
+        .first()
+        .copied()
+        .expect("physical device had no surface formats");
+    (fallback, HdrOutput::Off)
+}
+
+/// The surface format a swapchain was first created with, for recreating it;
+/// pipelines were created for its format, so it can't change under them
+pub(super) fn pinned_surface_format(
+    available: &[vk::SurfaceFormatKHR],
+    pinned: vk::SurfaceFormatKHR,
+) -> anyhow::Result<vk::SurfaceFormatKHR> {
+    if !available.contains(&pinned) {
+        anyhow::bail!(
+            "the surface no longer offers {:?} in {:?}, which the pipelines were created for",
+            pinned.format,
+            pinned.color_space
+        );
+    }
+    Ok(pinned)
+}
+
+/// scRGB's 1.0, and the nits sRGB's white is mastered at
+const SCRGB_WHITE_NITS: f32 = 80.0;
+
+/// How bright the window's display shows SDR white, and how bright it goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayLuminance {
+    /// aka SDR white; what 1.0 in a shader's linear color shows as
+    pub paper_white_nits: f32,
+    pub max_nits: f32,
+}
+
+impl DisplayLuminance {
+    /// A display with no headroom, where white is as bright as it gets
+    pub const SDR: Self = Self {
+        paper_white_nits: SCRGB_WHITE_NITS,
+        max_nits: SCRGB_WHITE_NITS,
+    };
+
+    /// From SDL's window properties: its SDR white level, in scRGB units,
+    /// and its HDR headroom, in multiples of that white
+    fn from_sdl(sdr_white_level: f32, hdr_headroom: f32) -> Self {
+        let paper_white_nits = SCRGB_WHITE_NITS * sdr_white_level.max(1.0);
+        Self {
+            paper_white_nits,
+            max_nits: paper_white_nits * hdr_headroom.max(1.0),
+        }
+    }
+
+    /// What the window's display reports now; SDL updates it when the
+    /// window moves between displays, or the OS's HDR settings change
+    pub(super) fn of_window(window: &Window) -> Self {
+        use sdl3::sys::properties::SDL_GetFloatProperty;
+        use sdl3::sys::video::{
+            SDL_GetWindowProperties, SDL_PROP_WINDOW_HDR_HEADROOM_FLOAT,
+            SDL_PROP_WINDOW_SDR_WHITE_LEVEL_FLOAT,
+        };
+
+        unsafe {
+            let properties = SDL_GetWindowProperties(window.raw());
+            let sdr_white_level =
+                SDL_GetFloatProperty(properties, SDL_PROP_WINDOW_SDR_WHITE_LEVEL_FLOAT, 1.0);
+            let hdr_headroom =
+                SDL_GetFloatProperty(properties, SDL_PROP_WINDOW_HDR_HEADROOM_FLOAT, 1.0);
+            Self::from_sdl(sdr_white_level, hdr_headroom)
+        }
+    }
+
+    /// For a debug view of how HDR content tonemaps without headroom
+    pub fn without_headroom(self) -> Self {
+        Self {
+            max_nits: self.paper_white_nits,
+            ..self
+        }
+    }
+}
+
+/// CIE 1931 xy chromaticities of each output's primaries and white point
+const REC_709_PRIMARIES: [vk::XYColorEXT; 3] = [
+    vk::XYColorEXT { x: 0.640, y: 0.330 },
+    vk::XYColorEXT { x: 0.300, y: 0.600 },
+    vk::XYColorEXT { x: 0.150, y: 0.060 },
+];
+const REC_2020_PRIMARIES: [vk::XYColorEXT; 3] = [
+    vk::XYColorEXT { x: 0.708, y: 0.292 },
+    vk::XYColorEXT { x: 0.170, y: 0.797 },
+    vk::XYColorEXT { x: 0.131, y: 0.046 },
+];
+const D65_WHITE_POINT: vk::XYColorEXT = vk::XYColorEXT {
+    x: 0.3127,
+    y: 0.3290,
+};
+
+/// The mastering display for vkSetHdrMetadataEXT: the output's primaries, and
+/// the window's display luminance, which shaders already tonemap to; None on SDR
+pub(super) fn hdr_metadata(
+    output: HdrOutput,
+    luminance: DisplayLuminance,
+) -> Option<vk::HdrMetadataEXT<'static>> {
+    let [red, green, blue] = match output {
+        HdrOutput::Off => return None,
+        HdrOutput::Scrgb => REC_709_PRIMARIES,
+        HdrOutput::Hdr10 => REC_2020_PRIMARIES,
+    };
+
+    Some(
+        vk::HdrMetadataEXT::default()
+            .display_primary_red(red)
+            .display_primary_green(green)
+            .display_primary_blue(blue)
+            .white_point(D65_WHITE_POINT)
+            .max_luminance(luminance.max_nits)
+            .min_luminance(0.0)
+            // tonemapToDisplay never goes past the display's max, and most of
+            // a frame sits around paper white
+            .max_content_light_level(luminance.max_nits)
+            .max_frame_average_light_level(luminance.paper_white_nits),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hdr_falls_back_to_sdr() {
+        let sdr_only = [
+            vk::SurfaceFormatKHR {
+                format: vk::Format::B8G8R8A8_UNORM,
+                color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+            },
+            SDR_SURFACE_FORMAT,
+        ];
+        assert_eq!(
+            choose_surface_format(&sdr_only, HdrOutput::Hdr10),
+            (SDR_SURFACE_FORMAT, HdrOutput::Off)
+        );
+        assert_eq!(
+            choose_surface_format(&sdr_only[..1], HdrOutput::Off),
+            (sdr_only[0], HdrOutput::Off)
+        );
+
+        let with_hdr10 = [SDR_SURFACE_FORMAT, HdrOutput::Hdr10.surface_formats()[1]];
+        assert_eq!(
+            choose_surface_format(&with_hdr10, HdrOutput::Hdr10),
+            (with_hdr10[1], HdrOutput::Hdr10)
+        );
+        assert_eq!(
+            choose_surface_format(&with_hdr10, HdrOutput::Scrgb).1,
+            HdrOutput::Off
+        );
+    }
+
+    #[test]
+    fn recreated_swapchains_keep_their_format() {
+        let hdr10 = HdrOutput::Hdr10.surface_formats()[0];
+        let with_hdr10 = [SDR_SURFACE_FORMAT, hdr10];
+        assert_eq!(pinned_surface_format(&with_hdr10, hdr10).unwrap(), hdr10);
+
+        // moved to a display without it; falling back to SDR would break pipelines
+        assert!(pinned_surface_format(&[SDR_SURFACE_FORMAT], hdr10).is_err());
+    }
+
+    #[test]
+    fn luminance_scales_from_sdr_white() {
+        assert_eq!(DisplayLuminance::from_sdl(1.0, 1.0), DisplayLuminance::SDR);
+
+        // 200 nit paper white, on a 1000 nit display
+        let hdr = DisplayLuminance::from_sdl(2.5, 5.0);
+        assert_eq!(hdr.paper_white_nits, 200.0);
+        assert_eq!(hdr.max_nits, 1000.0);
+        assert_eq!(hdr.without_headroom().max_nits, 200.0);
+
+        // SDL reports 0 for a window that's not on a display yet
+        assert_eq!(DisplayLuminance::from_sdl(0.0, 0.0), DisplayLuminance::SDR);
+    }
+
+    #[test]
+    fn metadata_describes_the_display() {
+        let luminance = DisplayLuminance::from_sdl(2.5, 5.0);
+        assert!(hdr_metadata(HdrOutput::Off, luminance).is_none());
+
+        let hdr10 = hdr_metadata(HdrOutput::Hdr10, luminance).unwrap();
+        assert_eq!(hdr10.display_primary_red.x, 0.708);
+        assert_eq!(hdr10.max_luminance, 1000.0);
+        assert_eq!(hdr10.max_frame_average_light_level, 200.0);
+
+        let scrgb = hdr_metadata(HdrOutput::Scrgb, luminance).unwrap();
+        assert_eq!(scrgb.display_primary_red.x, 0.640);
+        assert_eq!(scrgb.white_point.x, 0.3127);
+    }
+}
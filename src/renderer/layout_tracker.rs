@@ -0,0 +1,291 @@
+//! Debug builds only: a CPU-side shadow of the layout each image was last
+//! transitioned to, updated as barriers are recorded. Command buffers are
+//! submitted in the order they're recorded, so the shadow matches what the
+//! GPU will see when each command runs.
+//!
+//! It panics when a barrier's old layout or a draw's bound image disagrees
+//! with the shadow, e.g. sampling a render target that was never transitioned
+//! out of COLOR_ATTACHMENT_OPTIMAL after its pass.
+//!
+//! Images only enter the shadow through barriers recorded with
+//! `Renderer::record_barriers`; images it has never seen aren't checked.
+//!
+//! Buffers are tracked too, by their use rather than a layout: each dispatch's
+//! shader writes, and the memory barriers that make them visible to later
+//! stages. Shaders reach storage through device addresses, so which buffer a
+//! dispatch wrote isn't known on the CPU, and all storage is tracked as one;
+//! a draw after a dispatch with no barrier between warns (once per shader)
+//! rather than panicking, since the two may not share a buffer.
+//! Command buffers are submitted with semaphores between them, so the writes
+//! are forgotten as each one begins.
+
+use std::collections::{HashMap, HashSet};
+
+use ash::vk;
+
+/// An image a pipeline's descriptor sets bind, for checking its layout at draw time
+#[derive(Debug, Clone, Copy)]
+pub(super) enum BoundImage {
+    /// a TextureStorage index; feedback and scene color aliases resolve to a
+    /// different image per ring slot
+    Texture(usize),
+    /// storage images are always used in GENERAL
+    Storage(vk::Image),
+}
+
+/// Any stage a draw reads storage in
+pub(super) const DRAW_READ_STAGES: vk::PipelineStageFlags2 = vk::PipelineStageFlags2::from_raw(
+    vk::PipelineStageFlags2::DRAW_INDIRECT.as_raw()
+        | vk::PipelineStageFlags2::VERTEX_INPUT.as_raw()
+        | vk::PipelineStageFlags2::VERTEX_SHADER.as_raw()
+        | vk::PipelineStageFlags2::FRAGMENT_SHADER.as_raw()
+        | vk::PipelineStageFlags2::ALL_GRAPHICS.as_raw(),
+);
+
+/// Any stage a buffer copy reads in
+pub(super) const COPY_READ_STAGES: vk::PipelineStageFlags2 = vk::PipelineStageFlags2::from_raw(
+    vk::PipelineStageFlags2::COPY.as_raw() | vk::PipelineStageFlags2::ALL_TRANSFER.as_raw(),
+);
+
+/// A shader's writes to storage in the command buffer being recorded
+struct StorageWrite {
+    writer: String,
+    stage: vk::PipelineStageFlags2,
+    /// the stages barriers since have made the writes visible to
+    visible_to: vk::PipelineStageFlags2,
+}
+
+#[derive(Default)]
+pub(super) struct LayoutTracker {
+    layouts: HashMap<vk::Image, vk::ImageLayout>,
+    storage_writes: Vec<StorageWrite>,
+    /// (reader, writer) pairs already warned about
+    warned_reads: HashSet<(String, String)>,
+}
+
+impl LayoutTracker {
+    pub fn transition(&mut self, image_barriers: &[vk::ImageMemoryBarrier2]) {
+        for barrier in image_barriers {
+            // per-level transitions (mip chain generation) leave the image in
+            // mixed layouts; only whole-image transitions are tracked
+            let range = barrier.subresource_range;
+            if range.base_mip_level != 0 || range.base_array_layer != 0 {
+                continue;
+            }
+
+            // UNDEFINED discards the contents, which is valid from any layout
+            if barrier.old_layout != vk::ImageLayout::UNDEFINED
+                && let Some(&tracked) = self.layouts.get(&barrier.image)
+            {
+                assert_eq!(
+                    tracked, barrier.old_layout,
+                    "a barrier transitions image {:?} from {:?}, but it was last transitioned to {:?}",
+                    barrier.image, barrier.old_layout, tracked
+                );
+            }
+
+            self.layouts.insert(barrier.image, barrier.new_layout);
+        }
+    }
+
+    /// Panics if the image was last transitioned to some other layout
+    pub fn expect(&self, image: vk::Image, layout: vk::ImageLayout, user: &str) {
+        if let Some(&tracked) = self.layouts.get(&image) {
+            assert_eq!(
+                tracked, layout,
+                "{user} binds image {image:?} as {layout:?}, but it was last transitioned to {tracked:?}"
+            );
+        }
+    }
+
+    /// For images about to be destroyed; their handles may be reused
+    pub fn forget(&mut self, image: vk::Image) {
+        self.layouts.remove(&image);
+    }
+
+    pub fn clear(&mut self) {
+        self.layouts.clear();
+        self.storage_writes.clear();
+    }
+
+    /// A new command buffer; writes from earlier ones are waited on by semaphore
+    pub fn begin_command_buffer(&mut self) {
+        self.storage_writes.clear();
+    }
+
+    /// A dispatch (or any shader) that may write storage in `stage`
+    pub fn storage_write(&mut self, writer: &str, stage: vk::PipelineStageFlags2) {
+        self.storage_writes.push(StorageWrite {
+            writer: writer.to_string(),
+            stage,
+            visible_to: vk::PipelineStageFlags2::NONE,
+        });
+    }
+
+    pub fn memory_barrier(&mut self, barrier: &vk::MemoryBarrier2) {
+        let src_access = vk::AccessFlags2::SHADER_WRITE
+            | vk::AccessFlags2::SHADER_STORAGE_WRITE
+            | vk::AccessFlags2::MEMORY_WRITE;
+        if !barrier.src_access_mask.intersects(src_access) || barrier.dst_access_mask.is_empty() {
+            return;
+        }
+
+        for write in &mut self.storage_writes {
+            if barrier
+                .src_stage_mask
+                .intersects(write.stage | vk::PipelineStageFlags2::ALL_COMMANDS)
+            {
+                write.visible_to |= barrier.dst_stage_mask;
+            }
+        }
+    }
+
+    /// Warns if storage written earlier in the command buffer hasn't been
+    /// made visible to any of `stages`
+    pub fn storage_read(&mut self, reader: &str, stages: vk::PipelineStageFlags2) {
+        if let Some(writer) = self.unsynced_writer(stages).map(str::to_string)
+            && self
+                .warned_reads
+                .insert((reader.to_string(), writer.clone()))
+        {
+            log::warn!(
+                "{reader} may read storage {writer} wrote earlier in the command buffer, \
+                 with no memory barrier between; see FrameRenderer::memory_barrier"
+            );
+        }
+    }
+
+    /// The first write not yet visible to any of `stages`
+    fn unsynced_writer(&self, stages: vk::PipelineStageFlags2) -> Option<&str> {
+        let stages = stages | vk::PipelineStageFlags2::ALL_COMMANDS;
+        self.storage_writes
+            .iter()
+            .find(|write| !write.visible_to.intersects(stages))
+            .map(|write| write.writer.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ash::vk::Handle;
+
+    use super::*;
+
+    fn barrier(
+        image: vk::Image,
+        old_layout: vk::ImageLayout,
+        new_layout: vk::ImageLayout,
+    ) -> vk::ImageMemoryBarrier2<'static> {
+        vk::ImageMemoryBarrier2::default()
+            .image(image)
+            .old_layout(old_layout)
+            .new_layout(new_layout)
+    }
+
+    #[test]
+    fn follows_a_render_target_through_its_pass() {
+        let image = vk::Image::from_raw(1);
+        let mut tracker = LayoutTracker::default();
+
+        tracker.transition(&[barrier(
+            image,
+            vk::ImageLayout::UNDEFINED,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        )]);
+        tracker.transition(&[barrier(
+            image,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        )]);
+
+        tracker.expect(image, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, "test");
+    }
+
+    #[test]
+    #[should_panic(expected = "last transitioned to COLOR_ATTACHMENT_OPTIMAL")]
+    fn sampling_an_untransitioned_render_target_panics() {
+        let image = vk::Image::from_raw(1);
+        let mut tracker = LayoutTracker::default();
+
+        tracker.transition(&[barrier(
+            image,
+            vk::ImageLayout::UNDEFINED,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        )]);
+
+        tracker.expect(image, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, "test");
+    }
+
+    #[test]
+    #[should_panic(expected = "from TRANSFER_SRC_OPTIMAL")]
+    fn mismatched_old_layout_panics() {
+        let image = vk::Image::from_raw(1);
+        let mut tracker = LayoutTracker::default();
+
+        tracker.transition(&[barrier(
+            image,
+            vk::ImageLayout::UNDEFINED,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        )]);
+        tracker.transition(&[barrier(
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            vk::ImageLayout::PRESENT_SRC_KHR,
+        )]);
+    }
+
+    fn compute_to(dst_stage: vk::PipelineStageFlags2) -> vk::MemoryBarrier2<'static> {
+        vk::MemoryBarrier2::default()
+            .src_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
+            .src_access_mask(vk::AccessFlags2::SHADER_WRITE)
+            .dst_stage_mask(dst_stage)
+            .dst_access_mask(vk::AccessFlags2::SHADER_READ)
+    }
+
+    #[test]
+    fn draws_after_a_dispatch_need_a_barrier_to_a_graphics_stage() {
+        let mut tracker = LayoutTracker::default();
+        tracker.storage_write("particles", vk::PipelineStageFlags2::COMPUTE_SHADER);
+        assert_eq!(tracker.unsynced_writer(DRAW_READ_STAGES), Some("particles"));
+
+        // a compute to compute barrier orders the next dispatch, not the draws
+        tracker.memory_barrier(&compute_to(vk::PipelineStageFlags2::COMPUTE_SHADER));
+        assert_eq!(tracker.unsynced_writer(DRAW_READ_STAGES), Some("particles"));
+
+        tracker.memory_barrier(&compute_to(vk::PipelineStageFlags2::VERTEX_SHADER));
+        assert_eq!(tracker.unsynced_writer(DRAW_READ_STAGES), None);
+        assert_eq!(tracker.unsynced_writer(COPY_READ_STAGES), Some("particles"));
+    }
+
+    #[test]
+    fn writes_are_forgotten_between_command_buffers() {
+        let mut tracker = LayoutTracker::default();
+        tracker.storage_write("particles", vk::PipelineStageFlags2::COMPUTE_SHADER);
+        tracker.begin_command_buffer();
+        assert_eq!(tracker.unsynced_writer(DRAW_READ_STAGES), None);
+    }
+
+    #[test]
+    fn all_commands_barriers_cover_every_write() {
+        let mut tracker = LayoutTracker::default();
+        tracker.storage_write("particles", vk::PipelineStageFlags2::COMPUTE_SHADER);
+        tracker.memory_barrier(
+            &vk::MemoryBarrier2::default()
+                .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+                .src_access_mask(vk::AccessFlags2::SHADER_STORAGE_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+                .dst_access_mask(vk::AccessFlags2::TRANSFER_READ),
+        );
+        assert_eq!(tracker.unsynced_writer(COPY_READ_STAGES), None);
+    }
+
+    #[test]
+    fn unknown_images_are_not_checked() {
+        let tracker = LayoutTracker::default();
+        tracker.expect(
+            vk::Image::from_raw(1),
+            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            "test",
+        );
+    }
+}
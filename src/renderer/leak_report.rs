@@ -0,0 +1,76 @@
+//! What a game still holds when the renderer shuts down.
+//!
+//! The renderer frees everything on drop either way, so this is a report
+//! rather than an error: it makes a forgotten `drop_*` call visible, for
+//! games that create resources as they go (per level, per streamed chunk)
+//! and expect to have returned them all.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LeakReport {
+    /// shader names; pipelines live as long as the renderer
+    pub pipelines: Vec<String>,
+    pub compute_pipelines: Vec<String>,
+    /// source file names, of textures the game created or loaded
+    pub textures: Vec<String>,
+    pub uniform_buffers: usize,
+    /// including immutable, GPU-only, and raw buffers
+    pub storage_buffers: usize,
+    pub counter_buffers: usize,
+}
+
+impl LeakReport {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let named = [
+            ("pipelines", &self.pipelines),
+            ("compute pipelines", &self.compute_pipelines),
+            ("textures", &self.textures),
+        ];
+        for (kind, names) in named {
+            if !names.is_empty() {
+                writeln!(f, "  {} {kind}: {}", names.len(), names.join(", "))?;
+            }
+        }
+
+        let counted = [
+            ("uniform buffers", self.uniform_buffers),
+            ("storage buffers", self.storage_buffers),
+            ("counter buffers", self.counter_buffers),
+        ];
+        for (kind, count) in counted {
+            if count > 0 {
+                writeln!(f, "  {count} {kind}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_only_live_kinds() {
+        let report = LeakReport {
+            textures: vec!["sprites.png".to_string(), "font.png".to_string()],
+            storage_buffers: 3,
+            ..Default::default()
+        };
+
+        assert!(!report.is_empty());
+        assert_eq!(
+            report.to_string(),
+            "  2 textures: sprites.png, font.png\n  3 storage buffers\n"
+        );
+        assert!(LeakReport::default().is_empty());
+    }
+}
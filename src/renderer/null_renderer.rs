@@ -0,0 +1,802 @@
+//! A renderer without Vulkan, for testing game logic.
+//!
+//! [`NullRenderer`] hands out the real handle types without allocating
+//! anything, and its [`NullFrameRenderer`] logs each frame's draws,
+//! dispatches, and buffer writes instead of submitting them. Game code
+//! written against [`CreateResources`], [`QueueDraws`], and [`WriteBuffers`]
+//! runs unchanged on both, so its update and draw logic can be unit tested
+//! (and run on CI) without a GPU:
+//!
+//! ```ignore
+//! impl Game for MyGame {
+//!     fn setup(renderer: &mut Renderer) -> anyhow::Result<Self> {
+//!         Self::create(renderer)
+//!     }
+//!
+//!     fn draw(&mut self, renderer: FrameRenderer) -> Result<(), DrawError> {
+//!         self.queue(renderer)
+//!     }
+//! }
+//!
+//! impl MyGame {
+//!     fn create(renderer: &mut impl CreateResources) -> anyhow::Result<Self> { ... }
+//!     fn queue(&mut self, renderer: impl QueueDraws) -> Result<(), DrawError> { ... }
+//! }
+//!
+//! #[test]
+//! fn draws_the_player() {
+//!     let mut renderer = NullRenderer::new(800, 600);
+//!     let mut game = MyGame::create(&mut renderer).unwrap();
+//!     game.queue(renderer.frame()).unwrap();
+//!     assert_eq!(renderer.last_frame().unwrap().draws.len(), 1);
+//! }
+//! ```
+//!
+//! Pipelines are still validated against their shader's reflection, so a
+//! mismatched uniform buffer fails here like it would on a device.
+//! Anything that needs the GPU's results (readbacks, counters, picking)
+//! is out of reach.
+
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+use super::clip::ClipStack;
+use super::command_log::{FrameLog, LoggedDispatch, LoggedDraw, LoggedDrawCall, LoggedWrite};
+use super::gpu_write::GPUWrite;
+use super::render_pass::RenderPass;
+use super::vertex_description::VertexDescription;
+use super::{
+    Addr, AuditedBuffer, ClipRect, Compute, ComputePipelineConfig, CounterBufferHandle, DrawCall,
+    DrawError, DrawIndexed, DrawMeshTasks, DrawVertexCount, FrameRenderer, Gpu,
+    GpuOnlyBufferHandle, PipelineConfig, PipelineHandle, Renderer, StorageBufferHandle,
+    TextureFilter, TextureHandle, UniformBufferHandle, VertexConfig, validate_uniform_buffers,
+};
+
+/// Creating the resources a game sets up with, on [`Renderer`] or [`NullRenderer`]
+pub trait CreateResources {
+    fn create_uniform_buffer<T: GPUWrite>(&mut self) -> anyhow::Result<UniformBufferHandle<T>>;
+
+    fn create_storage_buffer<T: GPUWrite>(
+        &mut self,
+        len: u32,
+    ) -> anyhow::Result<StorageBufferHandle<T>>;
+
+    fn create_gpu_only_buffer<T: GPUWrite>(
+        &mut self,
+        len: u32,
+    ) -> anyhow::Result<GpuOnlyBufferHandle<T>>;
+
+    fn create_counter_buffer(&mut self, len: u32) -> anyhow::Result<CounterBufferHandle>;
+
+    fn create_texture(
+        &mut self,
+        source_file_name: impl Into<String>,
+        image: &image::DynamicImage,
+        texture_filter: TextureFilter,
+    ) -> anyhow::Result<TextureHandle>;
+
+    fn create_pipeline<V: VertexDescription, D: DrawCall>(
+        &mut self,
+        config: PipelineConfig<V, D>,
+    ) -> anyhow::Result<PipelineHandle<D>>;
+
+    fn create_compute_pipeline(
+        &mut self,
+        config: ComputePipelineConfig,
+    ) -> anyhow::Result<PipelineHandle<Compute>>;
+}
+
+/// Queueing a frame's draws, on [`FrameRenderer`] or [`NullFrameRenderer`]
+pub trait QueueDraws: Sized {
+    type Gpu<'g>: WriteBuffers;
+
+    fn aspect_ratio(&self) -> f32;
+    fn window_resolution(&self) -> Vec2;
+    fn render_resolution(&self) -> Vec2;
+
+    fn queue_draw_indexed(&mut self, pipeline: &PipelineHandle<DrawIndexed>);
+    fn queue_draw_index_range(
+        &mut self,
+        pipeline: &PipelineHandle<DrawIndexed>,
+        first_index: u32,
+        index_count: u32,
+    );
+    fn queue_draw_vertex_count(
+        &mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        vertex_count: u32,
+    );
+    fn queue_draw_vertex_range(
+        &mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        first_vertex: u32,
+        vertex_count: u32,
+    );
+    fn queue_draw_mesh_tasks(
+        &mut self,
+        pipeline: &PipelineHandle<DrawMeshTasks>,
+        group_count: [u32; 3],
+    );
+    fn dispatch(&mut self, pipeline: &PipelineHandle<Compute>, x: u32, y: u32, z: u32);
+
+    fn push_clip(&mut self, rect: ClipRect);
+    fn pop_clip(&mut self);
+
+    fn submit_draws(self, gpu_update: impl FnOnce(&mut Self::Gpu<'_>)) -> Result<(), DrawError>;
+
+    fn draw_indexed(
+        mut self,
+        pipeline: &PipelineHandle<DrawIndexed>,
+        gpu_update: impl FnOnce(&mut Self::Gpu<'_>),
+    ) -> Result<(), DrawError> {
+        self.queue_draw_indexed(pipeline);
+        self.submit_draws(gpu_update)
+    }
+
+    fn draw_vertex_count(
+        mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        vertex_count: u32,
+        gpu_update: impl FnOnce(&mut Self::Gpu<'_>),
+    ) -> Result<(), DrawError> {
+        self.queue_draw_vertex_count(pipeline, vertex_count);
+        self.submit_draws(gpu_update)
+    }
+
+    /// Start an ordered list of draws with their own writes; see [`RenderPass`]
+    fn render_pass<'w>(self) -> RenderPass<'w, Self> {
+        RenderPass::new(self)
+    }
+}
+
+/// Writing a frame's buffers, on [`Gpu`] or [`NullGpu`]
+pub trait WriteBuffers {
+    fn write_uniform<T: GPUWrite>(&mut self, uniform_buffer: &mut UniformBufferHandle<T>, data: T);
+    fn write_storage<T: GPUWrite>(
+        &mut self,
+        storage_buffer: &mut StorageBufferHandle<T>,
+        data: &[T],
+    );
+    fn push_constants<T: GPUWrite, D>(&mut self, pipeline: &PipelineHandle<D>, value: &T);
+    fn addr<T>(&self, storage_buffer: &StorageBufferHandle<T>) -> Addr<T>;
+}
+
+/// The number of each resource a [`NullRenderer`] has created
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub uniform_buffers: usize,
+    /// storage and GPU-only buffers
+    pub storage_buffers: usize,
+    /// the elements allocated across every storage buffer
+    pub storage_elements: u64,
+    pub counter_buffers: usize,
+    pub textures: usize,
+    /// graphics and compute pipelines
+    pub pipelines: usize,
+}
+
+/// Stands in for [`Renderer`] in tests; see the [module docs](self)
+pub struct NullRenderer {
+    window_resolution: Vec2,
+    render_scale: f32,
+    /// by raw index
+    pipelines: Vec<NullPipeline>,
+    usage: ResourceUsage,
+    /// the last bytes written to each buffer
+    contents: HashMap<AuditedBuffer, Vec<u8>>,
+    frames: Vec<FrameLog>,
+}
+
+impl NullRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            window_resolution: Vec2::new(width as f32, height as f32),
+            render_scale: 1.0,
+            pipelines: vec![],
+            usage: ResourceUsage::default(),
+            contents: HashMap::new(),
+            frames: vec![],
+        }
+    }
+
+    /// renders at a fraction of the window resolution, like [`crate::game::Game::render_scale`]
+    pub fn with_render_scale(mut self, render_scale: f32) -> Self {
+        self.render_scale = render_scale;
+        self
+    }
+
+    /// Starts a frame; its log is kept once it's submitted
+    pub fn frame(&mut self) -> NullFrameRenderer<'_> {
+        NullFrameRenderer {
+            renderer: self,
+            log: FrameLog::default(),
+            clip_stack: ClipStack::default(),
+        }
+    }
+
+    /// every submitted frame's log, oldest first
+    pub fn frames(&self) -> &[FrameLog] {
+        &self.frames
+    }
+
+    pub fn last_frame(&self) -> Option<&FrameLog> {
+        self.frames.last()
+    }
+
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.usage
+    }
+
+    /// the shader a pipeline was created from
+    pub fn shader<D>(&self, pipeline: &PipelineHandle<D>) -> &str {
+        &self.pipelines[pipeline.raw_index()].shader
+    }
+
+    /// The value last written to a uniform buffer, if any
+    pub fn uniform<T: GPUWrite>(&self, uniform_buffer: &UniformBufferHandle<T>) -> Option<T> {
+        let bytes = self
+            .contents
+            .get(&AuditedBuffer::Uniform(uniform_buffer.index()))?;
+        read_elements(bytes).pop()
+    }
+
+    /// A storage buffer's whole capacity as last written; empty if it never was
+    pub fn storage<T: GPUWrite>(&self, storage_buffer: &StorageBufferHandle<T>) -> Vec<T> {
+        self.contents
+            .get(&AuditedBuffer::Storage(storage_buffer.index()))
+            .map(|bytes| read_elements(bytes))
+            .unwrap_or_default()
+    }
+
+    /// The push constants last set for a pipeline, if any
+    pub fn push_constants<T: GPUWrite, D>(&self, pipeline: &PipelineHandle<D>) -> Option<T> {
+        let bytes = self
+            .contents
+            .get(&AuditedBuffer::PushConstants(pipeline.raw_index()))?;
+        read_elements(bytes).pop()
+    }
+
+    fn add_pipeline<D>(&mut self, shader: &str, index_count: u32) -> PipelineHandle<D> {
+        let handle = PipelineHandle::from_raw(self.pipelines.len());
+        self.pipelines.push(NullPipeline {
+            shader: shader.to_string(),
+            index_count,
+        });
+        self.usage.pipelines += 1;
+        handle
+    }
+
+    fn add_storage(&mut self, len: u32) -> usize {
+        // storage and GPU-only buffers share an index space, like on a device
+        let index = self.usage.storage_buffers;
+        self.usage.storage_buffers += 1;
+        self.usage.storage_elements += len as u64;
+        index
+    }
+}
+
+impl CreateResources for NullRenderer {
+    fn create_uniform_buffer<T: GPUWrite>(&mut self) -> anyhow::Result<UniformBufferHandle<T>> {
+        let handle = UniformBufferHandle::from_raw(self.usage.uniform_buffers);
+        self.usage.uniform_buffers += 1;
+        Ok(handle)
+    }
+
+    fn create_storage_buffer<T: GPUWrite>(
+        &mut self,
+        len: u32,
+    ) -> anyhow::Result<StorageBufferHandle<T>> {
+        let index = self.add_storage(len);
+        Ok(StorageBufferHandle::from_raw(index, len))
+    }
+
+    fn create_gpu_only_buffer<T: GPUWrite>(
+        &mut self,
+        len: u32,
+    ) -> anyhow::Result<GpuOnlyBufferHandle<T>> {
+        let index = self.add_storage(len);
+        Ok(GpuOnlyBufferHandle::from_raw(index, len))
+    }
+
+    fn create_counter_buffer(&mut self, len: u32) -> anyhow::Result<CounterBufferHandle> {
+        let handle = CounterBufferHandle::from_raw(self.usage.counter_buffers, len);
+        self.usage.counter_buffers += 1;
+        Ok(handle)
+    }
+
+    fn create_texture(
+        &mut self,
+        source_file_name: impl Into<String>,
+        _image: &image::DynamicImage,
+        _texture_filter: TextureFilter,
+    ) -> anyhow::Result<TextureHandle> {
+        let handle = TextureHandle::from_raw(self.usage.textures, source_file_name.into());
+        self.usage.textures += 1;
+        Ok(handle)
+    }
+
+    fn create_pipeline<V: VertexDescription, D: DrawCall>(
+        &mut self,
+        config: PipelineConfig<V, D>,
+    ) -> anyhow::Result<PipelineHandle<D>> {
+        validate_uniform_buffers(
+            config.shader.source_file_name(),
+            &config.shader.reflection_json().global_parameters,
+            &config.shader.layout_bindings(),
+            &config.uniform_buffer_handles,
+        )?;
+
+        let index_count = match &config.vertex_config {
+            VertexConfig::VertexAndIndexBuffers(_, indices) => indices.len() as u32,
+            // shared meshes are only created on a device
+            VertexConfig::SharedMesh(_) | VertexConfig::VertexCount => 0,
+        };
+        Ok(self.add_pipeline(config.shader.source_file_name(), index_count))
+    }
+
+    fn create_compute_pipeline(
+        &mut self,
+        config: ComputePipelineConfig,
+    ) -> anyhow::Result<PipelineHandle<Compute>> {
+        validate_uniform_buffers(
+            config.shader.source_file_name(),
+            &config.shader.reflection_json().global_parameters,
+            &config.shader.layout_bindings(),
+            &config.uniform_buffer_handles,
+        )?;
+
+        Ok(self.add_pipeline(config.shader.source_file_name(), 0))
+    }
+}
+
+struct NullPipeline {
+    shader: String,
+    /// for draws of the whole index buffer
+    index_count: u32,
+}
+
+/// Stands in for [`FrameRenderer`] in tests, logging what's submitted
+pub struct NullFrameRenderer<'f> {
+    renderer: &'f mut NullRenderer,
+    log: FrameLog,
+    clip_stack: ClipStack,
+}
+
+impl NullFrameRenderer<'_> {
+    fn queue_draw<D: DrawCall>(&mut self, pipeline: &PipelineHandle<D>, call: LoggedDrawCall) {
+        self.log.draws.push(LoggedDraw {
+            pipeline: pipeline.raw_index(),
+            variant: pipeline.variant(),
+            shader: self.renderer.shader(pipeline).to_string(),
+            call,
+            clip: self.clip_stack.current(),
+        });
+    }
+}
+
+impl QueueDraws for NullFrameRenderer<'_> {
+    type Gpu<'g> = NullGpu<'g>;
+
+    fn aspect_ratio(&self) -> f32 {
+        self.renderer.window_resolution.x / self.renderer.window_resolution.y
+    }
+
+    fn window_resolution(&self) -> Vec2 {
+        self.renderer.window_resolution
+    }
+
+    fn render_resolution(&self) -> Vec2 {
+        (self.renderer.window_resolution * self.renderer.render_scale).floor()
+    }
+
+    fn queue_draw_indexed(&mut self, pipeline: &PipelineHandle<DrawIndexed>) {
+        let index_count = self.renderer.pipelines[pipeline.raw_index()].index_count;
+        self.queue_draw(pipeline, LoggedDrawCall::IndexCount(index_count));
+    }
+
+    fn queue_draw_index_range(
+        &mut self,
+        pipeline: &PipelineHandle<DrawIndexed>,
+        first_index: u32,
+        index_count: u32,
+    ) {
+        let call = LoggedDrawCall::IndexRange {
+            first_index,
+            index_count,
+        };
+        self.queue_draw(pipeline, call);
+    }
+
+    fn queue_draw_vertex_count(
+        &mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        vertex_count: u32,
+    ) {
+        self.queue_draw(pipeline, LoggedDrawCall::VertexCount(vertex_count));
+    }
+
+    fn queue_draw_vertex_range(
+        &mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        first_vertex: u32,
+        vertex_count: u32,
+    ) {
+        let call = LoggedDrawCall::VertexRange {
+            first_vertex,
+            vertex_count,
+        };
+        self.queue_draw(pipeline, call);
+    }
+
+    fn queue_draw_mesh_tasks(
+        &mut self,
+        pipeline: &PipelineHandle<DrawMeshTasks>,
+        group_count: [u32; 3],
+    ) {
+        self.queue_draw(pipeline, LoggedDrawCall::MeshTasks(group_count));
+    }
+
+    fn dispatch(&mut self, pipeline: &PipelineHandle<Compute>, x: u32, y: u32, z: u32) {
+        self.log.dispatches.push(LoggedDispatch {
+            pipeline: pipeline.raw_index(),
+            shader: self.renderer.shader(pipeline).to_string(),
+            group_count: [x, y, z],
+        });
+    }
+
+    fn push_clip(&mut self, rect: ClipRect) {
+        self.clip_stack.push(rect);
+    }
+
+    fn pop_clip(&mut self) {
+        let popped = self.clip_stack.pop();
+        debug_assert!(popped.is_some(), "pop_clip without a matching push_clip");
+    }
+
+    fn submit_draws(self, gpu_update: impl FnOnce(&mut NullGpu<'_>)) -> Result<(), DrawError> {
+        let mut gpu = NullGpu {
+            renderer: self.renderer,
+            writes: vec![],
+            storage_overflow: None,
+        };
+        gpu_update(&mut gpu);
+
+        let NullGpu {
+            renderer,
+            writes,
+            storage_overflow,
+        } = gpu;
+        renderer.frames.push(FrameLog { writes, ..self.log });
+
+        match storage_overflow {
+            Some(overflow) => Err(overflow),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Stands in for [`Gpu`] in tests, keeping what's written
+/// for [`NullRenderer::uniform`] and friends
+pub struct NullGpu<'g> {
+    renderer: &'g mut NullRenderer,
+    writes: Vec<LoggedWrite>,
+    /// the first write past a storage buffer's capacity, returned from the draw
+    storage_overflow: Option<DrawError>,
+}
+
+impl NullGpu<'_> {
+    fn write(&mut self, buffer: AuditedBuffer, offset: usize, bytes: &[u8]) {
+        let contents = self.renderer.contents.entry(buffer).or_default();
+        if contents.len() < offset + bytes.len() {
+            contents.resize(offset + bytes.len(), 0);
+        }
+        contents[offset..offset + bytes.len()].copy_from_slice(bytes);
+
+        self.writes.push(LoggedWrite {
+            buffer,
+            len: bytes.len(),
+        });
+    }
+}
+
+impl WriteBuffers for NullGpu<'_> {
+    fn write_uniform<T: GPUWrite>(&mut self, uniform_buffer: &mut UniformBufferHandle<T>, data: T) {
+        let buffer = AuditedBuffer::Uniform(uniform_buffer.index());
+        self.write(buffer, 0, bytes_of(std::slice::from_ref(&data)));
+    }
+
+    fn write_storage<T: GPUWrite>(
+        &mut self,
+        storage_buffer: &mut StorageBufferHandle<T>,
+        data: &[T],
+    ) {
+        // the same truncation as Gpu::write_storage
+        let capacity = storage_buffer.capacity() as usize;
+        if data.len() > capacity && self.storage_overflow.is_none() {
+            self.storage_overflow = Some(DrawError::StorageOverflow {
+                requested: data.len(),
+                capacity,
+            });
+        }
+
+        let buffer = AuditedBuffer::Storage(storage_buffer.index());
+        let whole_buffer = capacity * std::mem::size_of::<T>();
+        let contents = self.renderer.contents.entry(buffer).or_default();
+        contents.resize(whole_buffer, 0);
+
+        self.write(buffer, 0, bytes_of(&data[..data.len().min(capacity)]));
+    }
+
+    fn push_constants<T: GPUWrite, D>(&mut self, pipeline: &PipelineHandle<D>, value: &T) {
+        let buffer = AuditedBuffer::PushConstants(pipeline.raw_index());
+        self.write(buffer, 0, bytes_of(std::slice::from_ref(value)));
+    }
+
+    /// a fake address, distinct per buffer
+    fn addr<T>(&self, storage_buffer: &StorageBufferHandle<T>) -> Addr<T> {
+        Addr::from_raw((storage_buffer.index() as u64 + 1) << 32)
+    }
+}
+
+impl CreateResources for Renderer {
+    fn create_uniform_buffer<T: GPUWrite>(&mut self) -> anyhow::Result<UniformBufferHandle<T>> {
+        Renderer::create_uniform_buffer(self)
+    }
+
+    fn create_storage_buffer<T: GPUWrite>(
+        &mut self,
+        len: u32,
+    ) -> anyhow::Result<StorageBufferHandle<T>> {
+        Renderer::create_storage_buffer(self, len)
+    }
+
+    fn create_gpu_only_buffer<T: GPUWrite>(
+        &mut self,
+        len: u32,
+    ) -> anyhow::Result<GpuOnlyBufferHandle<T>> {
+        Renderer::create_gpu_only_buffer(self, len)
+    }
+
+    fn create_counter_buffer(&mut self, len: u32) -> anyhow::Result<CounterBufferHandle> {
+        Renderer::create_counter_buffer(self, len)
+    }
+
+    fn create_texture(
+        &mut self,
+        source_file_name: impl Into<String>,
+        image: &image::DynamicImage,
+        texture_filter: TextureFilter,
+    ) -> anyhow::Result<TextureHandle> {
+        Renderer::create_texture(self, source_file_name, image, texture_filter)
+    }
+
+    fn create_pipeline<V: VertexDescription, D: DrawCall>(
+        &mut self,
+        config: PipelineConfig<V, D>,
+    ) -> anyhow::Result<PipelineHandle<D>> {
+        Renderer::create_pipeline(self, config)
+    }
+
+    fn create_compute_pipeline(
+        &mut self,
+        config: ComputePipelineConfig,
+    ) -> anyhow::Result<PipelineHandle<Compute>> {
+        Renderer::create_compute_pipeline(self, config)
+    }
+}
+
+impl QueueDraws for FrameRenderer<'_> {
+    type Gpu<'g> = Gpu<'g>;
+
+    fn aspect_ratio(&self) -> f32 {
+        FrameRenderer::aspect_ratio(self)
+    }
+
+    fn window_resolution(&self) -> Vec2 {
+        FrameRenderer::window_resolution(self)
+    }
+
+    fn render_resolution(&self) -> Vec2 {
+        FrameRenderer::render_resolution(self)
+    }
+
+    fn queue_draw_indexed(&mut self, pipeline: &PipelineHandle<DrawIndexed>) {
+        FrameRenderer::queue_draw_indexed(self, pipeline);
+    }
+
+    fn queue_draw_index_range(
+        &mut self,
+        pipeline: &PipelineHandle<DrawIndexed>,
+        first_index: u32,
+        index_count: u32,
+    ) {
+        FrameRenderer::queue_draw_index_range(self, pipeline, first_index, index_count);
+    }
+
+    fn queue_draw_vertex_count(
+        &mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        vertex_count: u32,
+    ) {
+        FrameRenderer::queue_draw_vertex_count(self, pipeline, vertex_count);
+    }
+
+    fn queue_draw_vertex_range(
+        &mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        first_vertex: u32,
+        vertex_count: u32,
+    ) {
+        FrameRenderer::queue_draw_vertex_range(self, pipeline, first_vertex, vertex_count);
+    }
+
+    fn queue_draw_mesh_tasks(
+        &mut self,
+        pipeline: &PipelineHandle<DrawMeshTasks>,
+        group_count: [u32; 3],
+    ) {
+        FrameRenderer::queue_draw_mesh_tasks(self, pipeline, group_count);
+    }
+
+    fn dispatch(&mut self, pipeline: &PipelineHandle<Compute>, x: u32, y: u32, z: u32) {
+        FrameRenderer::dispatch(self, pipeline, x, y, z);
+    }
+
+    fn push_clip(&mut self, rect: ClipRect) {
+        FrameRenderer::push_clip(self, rect);
+    }
+
+    fn pop_clip(&mut self) {
+        FrameRenderer::pop_clip(self);
+    }
+
+    fn submit_draws(self, gpu_update: impl FnOnce(&mut Gpu<'_>)) -> Result<(), DrawError> {
+        FrameRenderer::submit_draws(self, gpu_update)
+    }
+}
+
+impl WriteBuffers for Gpu<'_> {
+    fn write_uniform<T: GPUWrite>(&mut self, uniform_buffer: &mut UniformBufferHandle<T>, data: T) {
+        Gpu::write_uniform(self, uniform_buffer, data);
+    }
+
+    fn write_storage<T: GPUWrite>(
+        &mut self,
+        storage_buffer: &mut StorageBufferHandle<T>,
+        data: &[T],
+    ) {
+        Gpu::write_storage(self, storage_buffer, data);
+    }
+
+    fn push_constants<T: GPUWrite, D>(&mut self, pipeline: &PipelineHandle<D>, value: &T) {
+        Gpu::push_constants(self, pipeline, value);
+    }
+
+    fn addr<T>(&self, storage_buffer: &StorageBufferHandle<T>) -> Addr<T> {
+        Gpu::addr(self, storage_buffer)
+    }
+}
+
+fn bytes_of<T: GPUWrite>(values: &[T]) -> &[u8] {
+    // GPUWrite types are plain data, the same bytes a device buffer would get
+    unsafe {
+        std::slice::from_raw_parts(values.as_ptr().cast::<u8>(), std::mem::size_of_val(values))
+    }
+}
+
+fn read_elements<T: GPUWrite>(bytes: &[u8]) -> Vec<T> {
+    bytes
+        .chunks_exact(std::mem::size_of::<T>())
+        .map(|element| unsafe { element.as_ptr().cast::<T>().read_unaligned() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+
+    use super::*;
+    use crate::generated::shader_atlas::ShaderAtlas;
+    use crate::generated::shader_atlas::basic_triangle::{MVPMatrices, Resources, Vertex};
+    use crate::renderer::{PipelineConfigBuilder, RawUniformBufferHandle};
+
+    fn triangle_pipeline(
+        renderer: &mut NullRenderer,
+        matrices_buffer: &UniformBufferHandle<MVPMatrices>,
+    ) -> PipelineHandle<DrawIndexed> {
+        let resources = Resources {
+            vertices: vec![],
+            indices: vec![],
+            matrices_buffer,
+        };
+        let config = ShaderAtlas::init()
+            .basic_triangle
+            .pipeline_config(resources);
+        renderer.create_pipeline(config).unwrap()
+    }
+
+    #[test]
+    fn frames_log_draws_and_writes() {
+        let mut renderer = NullRenderer::new(800, 600);
+        let mut matrices_buffer = renderer.create_uniform_buffer::<MVPMatrices>().unwrap();
+        let pipeline = triangle_pipeline(&mut renderer, &matrices_buffer);
+
+        let matrices = MVPMatrices {
+            model: Mat4::from_scale(glam::Vec3::splat(2.0)),
+            view: Mat4::IDENTITY,
+            proj: Mat4::IDENTITY,
+        };
+        let mut frame = renderer.frame();
+        frame.push_clip(ClipRect::new(0, 0, 100, 100));
+        frame.queue_draw_indexed(&pipeline);
+        frame.pop_clip();
+        frame
+            .draw_indexed(&pipeline, |gpu| {
+                gpu.write_uniform(&mut matrices_buffer, matrices.clone());
+            })
+            .unwrap();
+
+        let log = renderer.last_frame().unwrap();
+        assert_eq!(log.draws_of(&pipeline).count(), 2);
+        assert_eq!(log.draws[0].clip, Some(ClipRect::new(0, 0, 100, 100)));
+        assert_eq!(log.draws[1].clip, None);
+        assert_eq!(log.draws[1].shader, "basic_triangle.shader.slang");
+        assert_eq!(
+            log.writes,
+            [LoggedWrite {
+                buffer: AuditedBuffer::Uniform(0),
+                len: std::mem::size_of::<MVPMatrices>(),
+            }]
+        );
+        let written = renderer.uniform(&matrices_buffer).unwrap();
+        assert_eq!(written.model, matrices.model);
+    }
+
+    #[test]
+    fn pipelines_are_validated_against_reflection() {
+        let mut renderer = NullRenderer::new(800, 600);
+        // basic_triangle's matrices are an MVPMatrices, not a u32
+        let wrong_buffer = renderer.create_uniform_buffer::<u32>().unwrap();
+        let config = PipelineConfigBuilder::<Vertex> {
+            shader: Box::new(ShaderAtlas::init().basic_triangle),
+            vertex_config: VertexConfig::VertexAndIndexBuffers(vec![], vec![]),
+            texture_handles: vec![],
+            uniform_buffer_handles: vec![RawUniformBufferHandle::from_typed(&wrong_buffer)],
+            storage_texture_handles: vec![],
+            disable_depth_test: false,
+        }
+        .build::<DrawIndexed>();
+
+        assert!(renderer.create_pipeline(config).is_err());
+        assert_eq!(renderer.resource_usage().pipelines, 0);
+    }
+
+    #[test]
+    fn storage_overflow_fails_the_draw() {
+        let mut renderer = NullRenderer::new(800, 600);
+        let mut storage_buffer = renderer.create_storage_buffer::<u32>(2).unwrap();
+
+        let result = renderer.frame().submit_draws(|gpu| {
+            gpu.write_storage(&mut storage_buffer, &[1, 2, 3]);
+        });
+
+        assert!(matches!(
+            result,
+            Err(DrawError::StorageOverflow {
+                requested: 3,
+                capacity: 2
+            })
+        ));
+        assert_eq!(renderer.storage(&storage_buffer), [1, 2]);
+        assert_eq!(renderer.frames().len(), 1);
+        assert_eq!(
+            renderer.resource_usage(),
+            ResourceUsage {
+                storage_buffers: 1,
+                storage_elements: 2,
+                ..Default::default()
+            }
+        );
+    }
+}
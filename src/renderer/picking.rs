@@ -1,19 +1,106 @@
 use ash::vk;
 
-use super::{
-    BufferMemory, ImageOptions, MAX_FRAMES_IN_FLIGHT, create_image_view, create_memory_buffer,
-    create_vk_image,
-};
+use super::{ImageOptions, MAX_FRAMES_IN_FLIGHT, create_image_view, create_vk_image};
 
 pub(super) const PICKING_FORMAT: vk::Format = vk::Format::R32_UINT;
 
+/// The largest radius for [`PickingPolicy::Neighborhood`]
+pub const MAX_PICKING_RADIUS: u32 = 4;
+
+/// How a pick turns the single-sampled id image into one id.
+///
+/// The id pass has no MSAA, so near a silhouette it can disagree with the
+/// antialiased image by a pixel, and picks there flicker as the cursor moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PickingPolicy {
+    /// the id at the cursor's pixel
+    #[default]
+    Exact,
+    /// the id at the cursor's pixel; if that's empty, the id covering the most
+    /// pixels within `radius` of it, preferring the nearest on ties.
+    /// The radius is capped at [`MAX_PICKING_RADIUS`].
+    Neighborhood { radius: u32 },
+}
+
+impl PickingPolicy {
+    pub(super) fn radius(self) -> u32 {
+        match self {
+            PickingPolicy::Exact => 0,
+            PickingPolicy::Neighborhood { radius } => radius.min(MAX_PICKING_RADIUS),
+        }
+    }
+}
+
+/// The block of the id image copied for a pick, in render pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct PickingWindow {
+    pub offset: [u32; 2],
+    pub extent: [u32; 2],
+    /// the cursor's pixel, relative to `offset`
+    pub cursor: [u32; 2],
+}
+
+impl PickingWindow {
+    /// The pixels within `radius` of the cursor, clamped to the image
+    pub fn around(cursor: [u32; 2], radius: u32, render_extent: vk::Extent2D) -> Self {
+        let max = [
+            render_extent.width.saturating_sub(1),
+            render_extent.height.saturating_sub(1),
+        ];
+        let cursor = [cursor[0].min(max[0]), cursor[1].min(max[1])];
+        let offset = cursor.map(|coord| coord.saturating_sub(radius));
+        let end = [
+            (cursor[0] + radius).min(max[0]),
+            (cursor[1] + radius).min(max[1]),
+        ];
+
+        Self {
+            offset,
+            extent: [end[0] - offset[0] + 1, end[1] - offset[1] + 1],
+            cursor: [cursor[0] - offset[0], cursor[1] - offset[1]],
+        }
+    }
+
+    /// Pick one id from the window's ids, in row order
+    pub fn resolve(&self, ids: &[u32]) -> u32 {
+        let width = self.extent[0] as usize;
+        let cursor_index = self.cursor[1] as usize * width + self.cursor[0] as usize;
+        if ids[cursor_index] != 0 {
+            return ids[cursor_index];
+        }
+
+        let distance = |index: usize| {
+            let dx = (index % width).abs_diff(self.cursor[0] as usize);
+            let dy = (index / width).abs_diff(self.cursor[1] as usize);
+            dx * dx + dy * dy
+        };
+
+        // (id, coverage, nearest distance)
+        let mut candidates: Vec<(u32, usize, usize)> = vec![];
+        for (index, &id) in ids.iter().enumerate() {
+            if id == 0 {
+                continue;
+            }
+            match candidates.iter_mut().find(|candidate| candidate.0 == id) {
+                Some(candidate) => {
+                    candidate.1 += 1;
+                    candidate.2 = candidate.2.min(distance(index));
+                }
+                None => candidates.push((id, 1, distance(index))),
+            }
+        }
+
+        candidates
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)))
+            .map_or(0, |(id, _, _)| id)
+    }
+}
+
 pub(super) struct PickingResources {
     pub images: [vk::Image; MAX_FRAMES_IN_FLIGHT],
     pub image_memories: [vk_mem::Allocation; MAX_FRAMES_IN_FLIGHT],
     pub image_views: [vk::ImageView; MAX_FRAMES_IN_FLIGHT],
-    pub readback_buffers: [vk::Buffer; MAX_FRAMES_IN_FLIGHT],
-    pub readback_memories: [vk_mem::Allocation; MAX_FRAMES_IN_FLIGHT],
-    pub readback_mapped: [*mut u32; MAX_FRAMES_IN_FLIGHT],
 }
 
 impl PickingResources {
@@ -24,16 +111,11 @@ impl PickingResources {
     ) -> Result<Self, anyhow::Error> {
         let (images, image_memories, image_views) =
             create_picking_images(allocator, device, render_extent)?;
-        let (readback_buffers, readback_memories, readback_mapped) =
-            create_picking_readback_buffers(allocator)?;
 
         Ok(Self {
             images,
             image_memories,
             image_views,
-            readback_buffers,
-            readback_memories,
-            readback_mapped,
         })
     }
 
@@ -65,7 +147,6 @@ impl PickingResources {
             for i in 0..MAX_FRAMES_IN_FLIGHT {
                 device.destroy_image_view(self.image_views[i], None);
                 allocator.destroy_image(self.images[i], &mut self.image_memories[i]);
-                allocator.destroy_buffer(self.readback_buffers[i], &mut self.readback_memories[i]);
             }
         }
     }
@@ -89,6 +170,7 @@ fn create_picking_images(
         tiling: vk::ImageTiling::OPTIMAL,
         usage: vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
         mip_levels: 1,
+        array_layers: 1,
         msaa_samples: vk::SampleCountFlags::TYPE_1,
     };
 
@@ -115,36 +197,62 @@ fn create_picking_images(
     Ok((images, memories, views))
 }
 
-fn create_picking_readback_buffers(
-    allocator: &vk_mem::Allocator,
-) -> Result<
-    (
-        [vk::Buffer; MAX_FRAMES_IN_FLIGHT],
-        [vk_mem::Allocation; MAX_FRAMES_IN_FLIGHT],
-        [*mut u32; MAX_FRAMES_IN_FLIGHT],
-    ),
-    anyhow::Error,
-> {
-    let results: [_; MAX_FRAMES_IN_FLIGHT] = (0..MAX_FRAMES_IN_FLIGHT)
-        .map(|_| -> anyhow::Result<_> {
-            let (buffer, memory) = create_memory_buffer(
-                allocator,
-                4, // sizeof(u32)
-                vk::BufferUsageFlags::TRANSFER_DST,
-                BufferMemory::Readback,
-            )?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTENT: vk::Extent2D = vk::Extent2D {
+        width: 100,
+        height: 50,
+    };
 
-            let ptr = allocator.get_allocation_info(&memory).mapped_data;
+    #[test]
+    fn windows_are_clamped_to_the_image() {
+        let window = PickingWindow::around([1, 49], 2, EXTENT);
+        assert_eq!(window.offset, [0, 47]);
+        assert_eq!(window.extent, [4, 3]);
+        assert_eq!(window.cursor, [1, 2]);
 
-            Ok((buffer, memory, ptr as *mut u32))
-        })
-        .collect::<Result<Vec<_>, _>>()?
-        .try_into()
-        .unwrap();
+        let exact = PickingWindow::around([500, 20], 0, EXTENT);
+        assert_eq!(exact.offset, [99, 20]);
+        assert_eq!(exact.extent, [1, 1]);
+    }
 
-    let buffers = results.map(|(buffer, _, _)| buffer);
-    let memories = results.map(|(_, memory, _)| memory);
-    let mapped = results.map(|(_, _, ptr)| ptr);
+    #[test]
+    fn empty_cursor_pixels_pick_the_most_covered_nearby_id() {
+        let window = PickingWindow::around([10, 10], 1, EXTENT);
+        #[rustfmt::skip]
+        let ids = [
+            2, 2, 0,
+            2, 0, 3,
+            0, 0, 3,
+        ];
+        assert_eq!(window.resolve(&ids), 2);
+
+        // a tie goes to the id nearest the cursor
+        #[rustfmt::skip]
+        let ids = [
+            2, 0, 0,
+            0, 0, 3,
+            2, 0, 3,
+        ];
+        assert_eq!(window.resolve(&ids), 3);
 
-    Ok((buffers, memories, mapped))
+        // the cursor's own id always wins
+        #[rustfmt::skip]
+        let ids = [
+            2, 2, 2,
+            2, 5, 2,
+            2, 2, 2,
+        ];
+        assert_eq!(window.resolve(&ids), 5);
+        assert_eq!(window.resolve(&[0; 9]), 0);
+    }
+
+    #[test]
+    fn neighborhood_radius_is_capped() {
+        let policy = PickingPolicy::Neighborhood { radius: 100 };
+        assert_eq!(policy.radius(), MAX_PICKING_RADIUS);
+        assert_eq!(PickingPolicy::Exact.radius(), 0);
+    }
 }
@@ -4,9 +4,13 @@ use ash::vk;
 
 use crate::shaders::atlas::{ComputeShaderAtlasEntry, ShaderAtlasEntry};
 
+#[cfg(debug_assertions)]
+use super::layout_tracker::BoundImage;
+use super::post_process::SceneColorWrite;
 use super::vertex_description::VertexDescription;
 use super::{
-    ComputeShaderPipelineLayout, RawUniformBufferHandle, ShaderPipelineLayout,
+    AccelerationStructureHandle, ComputeShaderPipelineLayout, FeedbackTextureHandle,
+    RawBufferHandle, RawUniformBufferHandle, RenderTarget, ShaderPipelineLayout, ShadingRate,
     StorageTextureHandle, TextureHandle,
 };
 
@@ -82,6 +86,15 @@ impl DrawCall for DrawIndexed {
     type Index = GraphicsPipelineIndex;
 }
 
+/// A marker that the pipeline uses cmd_draw_mesh_tasks draw calls,
+/// with a mesh (and maybe task) shader in place of a vertex shader.
+/// Requires [`super::DeviceExtensions::with_mesh_shaders`].
+#[derive(Debug)]
+pub struct DrawMeshTasks;
+impl DrawCall for DrawMeshTasks {
+    type Index = GraphicsPipelineIndex;
+}
+
 /// A marker for compute pipelines
 #[derive(Debug)]
 pub struct Compute;
@@ -92,6 +105,8 @@ impl DrawCall for Compute {
 #[derive(Debug)]
 pub struct PipelineHandle<T> {
     index: usize,
+    /// an index into the pipeline's variants, or None for the pipeline itself
+    variant: Option<usize>,
     _phantom_data: PhantomData<T>,
 }
 
@@ -99,6 +114,26 @@ impl<T: DrawCall> PipelineHandle<T> {
     pub(crate) fn index(&self) -> T::Index {
         T::Index::from_raw(self.index)
     }
+
+    pub(crate) fn variant(&self) -> Option<usize> {
+        self.variant
+    }
+}
+
+impl<T> PipelineHandle<T> {
+    /// a handle to nothing allocated, for the null renderer
+    pub(super) fn from_raw(index: usize) -> Self {
+        Self {
+            index,
+            variant: None,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// the storage index, for callers that don't know the draw call type
+    pub(super) fn raw_index(&self) -> usize {
+        self.index
+    }
 }
 
 /// Distinct from PipelineHandle<T> — compile-time prevents misuse with main draw calls
@@ -117,6 +152,7 @@ impl PipelineStorage {
     pub fn add<T: DrawCall>(&mut self, pipeline: RendererPipeline) -> PipelineHandle<T> {
         let handle = PipelineHandle {
             index: self.0.len(),
+            variant: None,
             _phantom_data: PhantomData,
         };
 
@@ -139,6 +175,26 @@ impl PipelineStorage {
         self.0[handle.index].as_ref().unwrap()
     }
 
+    pub fn get_mut<T>(&mut self, handle: &PipelineHandle<T>) -> &mut RendererPipeline {
+        self.0[handle.index].as_mut().unwrap()
+    }
+
+    /// add a variant to the pipeline the handle refers to, returning a handle to it
+    pub fn add_variant<T>(
+        &mut self,
+        handle: &PipelineHandle<T>,
+        variant: PipelineVariant,
+    ) -> PipelineHandle<T> {
+        let pipeline = self.0[handle.index].as_mut().unwrap();
+        pipeline.variants.push(variant);
+
+        PipelineHandle {
+            index: handle.index,
+            variant: Some(pipeline.variants.len() - 1),
+            _phantom_data: PhantomData,
+        }
+    }
+
     pub fn get_picking(&self, handle: &PickingPipelineHandle) -> &RendererPipeline {
         self.0[handle.index.raw()].as_ref().unwrap()
     }
@@ -147,11 +203,19 @@ impl PipelineStorage {
         self.0[index.raw()].as_ref().unwrap()
     }
 
-    #[cfg(debug_assertions)] // used only during hot reload
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))] // used only during hot reload
     pub fn get_mut_by_index(&mut self, index: GraphicsPipelineIndex) -> &mut RendererPipeline {
         self.0[index.raw()].as_mut().unwrap()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &RendererPipeline> {
+        self.0.iter().flatten()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut RendererPipeline> {
+        self.0.iter_mut().flatten()
+    }
+
     #[expect(unused)]
     pub fn take<T>(&mut self, handle: PipelineHandle<T>) -> RendererPipeline {
         self.0[handle.index].take().unwrap()
@@ -173,7 +237,112 @@ pub(super) struct RendererPipeline {
 
     pub shader: Box<dyn ShaderAtlasEntry>,
 
-    #[cfg_attr(not(debug_assertions), expect(unused))] // used only during hot reload
+    pub raster_state: RasterState,
+    /// set before each draw, when variable; shared with the variants
+    pub shading_rate: ShadingRate,
+
+    /// the depth-only pipeline drawn before the main pass's color draws,
+    /// for pipelines created with PipelineConfig::with_depth_prepass
+    pub depth_prepass: Option<vk::Pipeline>,
+
+    /// the FeedbackTextureStorage index this pipeline draws into,
+    /// or None for the main pass
+    pub render_target: Option<usize>,
+
+    /// true if this pipeline draws in the post-process pass
+    pub post_process: bool,
+    /// true if the main pass draws this pipeline once per split-screen view
+    pub split_screen: bool,
+    /// true if this pipeline draws in the ui layer, at window resolution
+    pub ui_layer: bool,
+    /// true if this pipeline writes the main pass's velocity target
+    pub velocity: bool,
+    pub scene_color_writes: Vec<SceneColorWrite>,
+
+    /// pipelines baked from the same shader and layout with other raster state;
+    /// they share this pipeline's descriptor sets and vertex data
+    pub variants: Vec<PipelineVariant>,
+
+    /// the values pushed before each of this pipeline's draws, sized to its
+    /// layout's push constant ranges; set with Gpu::push_constants
+    pub push_constants: Vec<u8>,
+
+    #[cfg(debug_assertions)]
+    pub bound_images: Vec<BoundImage>,
+    /// UniformBufferStorage indices, for warning about unwritten buffers
+    #[cfg(debug_assertions)]
+    pub bound_uniform_buffers: Vec<usize>,
+
+    /// the droppable resources this pipeline's descriptor sets bind
+    pub resources: Vec<ResourceId>,
+    /// set when one of `resources` is dropped; drawing it is then an error
+    pub orphaned_by: Option<ResourceId>,
+}
+
+impl RendererPipeline {
+    pub fn vk_pipeline(&self, variant: Option<usize>) -> vk::Pipeline {
+        match variant {
+            Some(variant) => self.variants[variant].pipeline,
+            None => self.pipeline,
+        }
+    }
+
+    pub fn vk_depth_prepass_pipeline(&self, variant: Option<usize>) -> Option<vk::Pipeline> {
+        match variant {
+            Some(variant) => self.variants[variant].depth_prepass,
+            None => self.depth_prepass,
+        }
+    }
+}
+
+/// A resource a pipeline's descriptor sets can bind, and that can be dropped
+/// while the pipeline lives on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceId {
+    /// a TextureHandle's index
+    Texture(usize),
+    /// a UniformBufferHandle's index
+    UniformBuffer(usize),
+    /// a RawBufferHandle's index
+    RawBuffer(usize),
+}
+
+impl std::fmt::Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Texture(index) => write!(f, "texture {index}"),
+            Self::UniformBuffer(index) => write!(f, "uniform buffer {index}"),
+            Self::RawBuffer(index) => write!(f, "raw buffer {index}"),
+        }
+    }
+}
+
+/// The resources a pipeline config binds, for [`RendererPipeline::resources`]
+pub(super) fn bound_resources(
+    texture_handles: &[&TextureHandle],
+    uniform_buffer_handles: &[RawUniformBufferHandle],
+    raw_buffer_handles: &[&RawBufferHandle],
+) -> Vec<ResourceId> {
+    let textures = texture_handles
+        .iter()
+        .map(|handle| ResourceId::Texture(handle.index()));
+    let uniform_buffers = uniform_buffer_handles
+        .iter()
+        .map(|handle| ResourceId::UniformBuffer(handle.index()));
+    let raw_buffers = raw_buffer_handles
+        .iter()
+        .map(|handle| ResourceId::RawBuffer(handle.index()));
+    textures.chain(uniform_buffers).chain(raw_buffers).collect()
+}
+
+/// see [`Renderer::clone_pipeline_with`](super::Renderer::clone_pipeline_with)
+pub(super) struct PipelineVariant {
+    pub pipeline: vk::Pipeline,
+    pub depth_prepass: Option<vk::Pipeline>,
+    #[cfg_attr(
+        not(all(debug_assertions, feature = "shader-compiler")),
+        expect(unused)
+    )] // used only during hot reload
     pub raster_state: RasterState,
 }
 
@@ -184,6 +353,13 @@ pub enum BlendMode {
     Alpha,
     /// blending disabled; the fragment's alpha is ignored
     Opaque,
+    /// SRC_ALPHA / ONE with BlendOp::ADD for color, leaving the target's alpha;
+    /// for glows, particles, and other light-like effects
+    Additive,
+    /// ONE / ONE_MINUS_SRC_ALPHA with BlendOp::ADD, for color and alpha; for
+    /// textures with premultiplied alpha (see `util::image_ops::premultiply_alpha`),
+    /// which filter and mip without dark fringes
+    Premultiplied,
 }
 
 /// Which triangle facing is discarded. The front face is always
@@ -201,6 +377,9 @@ pub enum CullMode {
 pub enum DepthCompare {
     Less,
     LessEqual,
+    /// passes only fragments at exactly the stored depth; what a pipeline's
+    /// color draws use after its depth prepass
+    Equal,
     Always,
     /// No depth test. NOTE that Vulkan still honors depth writes when the test
     /// is disabled, so `Disabled` with `depth_write: true` writes the depth
@@ -222,6 +401,68 @@ pub struct RasterState {
     pub color_write: [bool; 4],
 }
 
+impl RasterState {
+    /// Alpha blended, tested against the scene's depth without writing it:
+    /// for billboards and labels that hide behind geometry but not each other
+    pub fn depth_tested_overlay() -> Self {
+        Self {
+            cull: CullMode::None,
+            depth_write: false,
+            ..Self::default()
+        }
+    }
+
+    /// Alpha blended over everything drawn before it, with no depth test:
+    /// for editor labels that stay visible through geometry
+    pub fn overlay() -> Self {
+        Self {
+            cull: CullMode::None,
+            depth_test: DepthCompare::Disabled,
+            depth_write: false,
+            ..Self::default()
+        }
+    }
+
+    /// The depth-only state of a pipeline's prepass: the same depth test,
+    /// always writing depth, and no color writes.
+    pub(super) fn for_depth_prepass(self) -> Self {
+        Self {
+            blend: BlendMode::Opaque,
+            depth_write: true,
+            color_write: [false; 4],
+            ..self
+        }
+    }
+
+    /// The state of a pipeline's color draws after its depth prepass,
+    /// which shade only the fragments that won the prepass.
+    pub(super) fn after_depth_prepass(self) -> Self {
+        Self {
+            depth_test: DepthCompare::Equal,
+            depth_write: false,
+            ..self
+        }
+    }
+
+    /// The state of a pipeline's replay in the pixel debugger, which
+    /// keeps the nearest of its draws (or the last, without a depth test)
+    /// and every output unblended.
+    pub(super) fn for_pixel_debug(self) -> Self {
+        let depth_test = match self.depth_test {
+            DepthCompare::Equal => DepthCompare::LessEqual,
+            DepthCompare::Disabled => DepthCompare::Always,
+            depth_test => depth_test,
+        };
+        Self {
+            blend: BlendMode::Opaque,
+            depth_test,
+            depth_write: true,
+            color_write: [true; 4],
+            ..self
+        }
+    }
+}
+
 impl Default for RasterState {
     fn default() -> Self {
         Self {
@@ -269,7 +510,16 @@ pub struct PipelineConfig<'t, V: VertexDescription, D: DrawCall> {
     pub(super) texture_handles: Vec<&'t TextureHandle>,
     pub(super) uniform_buffer_handles: Vec<RawUniformBufferHandle>,
     pub(super) storage_texture_handles: Vec<&'t StorageTextureHandle>,
+    pub(super) acceleration_structure_handles: Vec<&'t AccelerationStructureHandle>,
+    pub(super) raw_buffer_handles: Vec<&'t RawBufferHandle>,
     pub(super) raster_state: RasterState,
+    pub(super) render_target: Option<&'t FeedbackTextureHandle>,
+    pub(super) post_process: bool,
+    pub(super) depth_prepass: bool,
+    pub(super) split_screen: bool,
+    pub(super) ui_layer: bool,
+    pub(super) velocity: bool,
+    pub(super) shading_rate: ShadingRate,
 
     pub disable_depth_test: bool,
 }
@@ -310,6 +560,98 @@ impl<'t, V: VertexDescription, D: DrawCall> PipelineConfig<'t, V, D> {
         self.raster_state = raster_state;
         self
     }
+
+    /// Draw into a render target, or a feedback texture's current image, in a
+    /// pass before the main pass, instead of into the main color target. There
+    /// is no depth attachment in that pass, so the depth test is always disabled.
+    pub fn with_render_target(mut self, render_target: &'t impl RenderTarget) -> Self {
+        self.render_target = Some(render_target.feedback_texture());
+        self
+    }
+
+    /// Draw in a pass after the main pass, into a target that replaces the
+    /// main pass's output on screen. Only these pipelines may sample the
+    /// scene color (see `Renderer::create_scene_color_texture`); a fullscreen
+    /// triangle sampling it is the usual post effect. There is no depth
+    /// attachment in that pass, so the depth test is always disabled.
+    pub fn with_post_process(mut self) -> Self {
+        self.post_process = true;
+        self
+    }
+
+    /// Draw this pipeline's depth in a prepass at the start of the main pass,
+    /// then its color with a [`DepthCompare::Equal`] test, so each pixel is
+    /// shaded once no matter how much of this pipeline's geometry overlaps.
+    ///
+    /// The prepass still runs the fragment shader (with color writes off), so
+    /// shaders that discard stay correct, but the cost only pays off when the
+    /// shading dominates. The raster state's depth test applies to the
+    /// prepass, and must not be [`DepthCompare::Disabled`]. Blended geometry
+    /// only shows its front-most layer.
+    pub fn with_depth_prepass(mut self) -> Self {
+        self.depth_prepass = true;
+        self
+    }
+
+    /// Draw this pipeline once per camera set with `Renderer::set_split_screen`,
+    /// with the viewport and scissor narrowed to that camera's rect. The view
+    /// index arrives as the draw's base instance, and the cameras in the
+    /// renderer's `SplitScreenViews` block; see shaders/lib/split_screen.slang.
+    /// Main pass only, and not with a depth prepass or stereo view mode.
+    pub fn with_split_screen(mut self) -> Self {
+        self.split_screen = true;
+        self
+    }
+
+    /// Draw in the ui layer: after the upscale blit, straight onto the
+    /// window's image at its full resolution, so HUD text and sprites stay
+    /// sharp when the world is drawn at a lower render scale. Draws come
+    /// before the editor's egui overlay. There is no depth attachment or MSAA
+    /// in that pass, so the depth test is always disabled; size things from
+    /// `FrameRenderer::window_resolution` rather than the render resolution.
+    pub fn with_ui_layer(mut self) -> Self {
+        self.ui_layer = true;
+        self
+    }
+
+    /// Write the fragment shader's float2 `SV_Target1` into the main pass's
+    /// velocity buffer, when the game has one (see `Game::velocity_buffer`);
+    /// otherwise the output is dropped. Generated `pipeline_config()` calls
+    /// this for shaders with that output, so it's rarely needed by hand.
+    /// Main pass only.
+    pub fn with_velocity(mut self) -> Self {
+        self.velocity = true;
+        self
+    }
+
+    /// Shade this pipeline's draws at a coarser rate, or only their
+    /// [`ShadingRate::Edges`]; needs
+    /// [`DeviceExtensions::with_fragment_shading_rate`](super::DeviceExtensions::with_fragment_shading_rate).
+    /// See [`super::shading_rate`].
+    pub fn with_shading_rate(mut self, shading_rate: ShadingRate) -> Self {
+        self.shading_rate = shading_rate;
+        self
+    }
+
+    /// Bind tlases to the shader's `RaytracingAccelerationStructure`
+    /// parameters, in layout order. Generated `pipeline_config()` calls this
+    /// for shaders that have any, so it's rarely needed by hand.
+    pub fn with_acceleration_structures(
+        mut self,
+        acceleration_structures: Vec<&'t AccelerationStructureHandle>,
+    ) -> Self {
+        self.acceleration_structure_handles = acceleration_structures;
+        self
+    }
+
+    /// Bind raw buffers to the shader's `ByteAddressBuffer` and
+    /// `RWByteAddressBuffer` parameters, in layout order. Generated
+    /// `pipeline_config()` calls this for shaders that have any, so it's
+    /// rarely needed by hand.
+    pub fn with_raw_buffers(mut self, raw_buffers: Vec<&'t RawBufferHandle>) -> Self {
+        self.raw_buffer_handles = raw_buffers;
+        self
+    }
 }
 
 pub struct PipelineConfigBuilder<'t, V: VertexDescription> {
@@ -332,10 +674,19 @@ impl<'t, V: VertexDescription> PipelineConfigBuilder<'t, V> {
             texture_handles: self.texture_handles,
             uniform_buffer_handles: self.uniform_buffer_handles,
             storage_texture_handles: self.storage_texture_handles,
+            acceleration_structure_handles: vec![],
+            raw_buffer_handles: vec![],
             // generated `pipeline_config()` builds this struct as a complete
             // literal, so raster state is defaulted here and overridden with
             // PipelineConfig::with_raster_state rather than being a field
             raster_state: RasterState::default(),
+            render_target: None,
+            post_process: false,
+            depth_prepass: false,
+            split_screen: false,
+            ui_layer: false,
+            velocity: false,
+            shading_rate: ShadingRate::Full,
             disable_depth_test: self.disable_depth_test,
         }
     }
@@ -349,6 +700,16 @@ pub(super) struct ComputeRendererPipeline {
     pub descriptor_pool: vk::DescriptorPool,
     pub descriptor_sets: Vec<vk::DescriptorSet>,
     pub shader: Box<dyn ComputeShaderAtlasEntry>,
+    #[cfg(debug_assertions)]
+    pub bound_images: Vec<BoundImage>,
+    /// UniformBufferStorage indices, for warning about unwritten buffers
+    #[cfg(debug_assertions)]
+    pub bound_uniform_buffers: Vec<usize>,
+
+    /// the droppable resources this pipeline's descriptor sets bind
+    pub resources: Vec<ResourceId>,
+    /// set when one of `resources` is dropped; dispatching it is then an error
+    pub orphaned_by: Option<ResourceId>,
 }
 
 pub(super) struct ComputePipelineStorage(Vec<Option<ComputeRendererPipeline>>);
@@ -361,6 +722,7 @@ impl ComputePipelineStorage {
     pub fn add(&mut self, pipeline: ComputeRendererPipeline) -> PipelineHandle<Compute> {
         let handle = PipelineHandle {
             index: self.0.len(),
+            variant: None,
             _phantom_data: PhantomData,
         };
 
@@ -373,8 +735,6 @@ impl ComputePipelineStorage {
         self.0[handle.index].as_ref().unwrap()
     }
 
-    #[cfg(debug_assertions)]
-    #[expect(unused)]
     pub fn get_mut(&mut self, handle: &PipelineHandle<Compute>) -> &mut ComputeRendererPipeline {
         self.0[handle.index].as_mut().unwrap()
     }
@@ -383,7 +743,7 @@ impl ComputePipelineStorage {
         self.0[index.raw()].as_ref().unwrap()
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "shader-compiler"))] // used only during hot reload
     pub fn get_mut_by_index(
         &mut self,
         index: ComputePipelineIndex,
@@ -391,6 +751,14 @@ impl ComputePipelineStorage {
         self.0[index.raw()].as_mut().unwrap()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &ComputeRendererPipeline> {
+        self.0.iter().flatten()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut ComputeRendererPipeline> {
+        self.0.iter_mut().flatten()
+    }
+
     pub fn take_all(&mut self) -> Vec<ComputeRendererPipeline> {
         self.0.iter_mut().filter_map(|o| o.take()).collect()
     }
@@ -401,4 +769,5 @@ pub struct ComputePipelineConfig<'t> {
     pub(crate) texture_handles: Vec<&'t TextureHandle>,
     pub(crate) uniform_buffer_handles: Vec<RawUniformBufferHandle>,
     pub(crate) storage_texture_handles: Vec<&'t StorageTextureHandle>,
+    pub(crate) raw_buffer_handles: Vec<&'t RawBufferHandle>,
 }
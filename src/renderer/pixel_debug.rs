@@ -0,0 +1,362 @@
+//! The pixel debugger: replays one pipeline's draws for a single pixel into
+//! 1x1 targets after the main pass, and reads back what its shader wrote.
+//!
+//! The replay has a depth target and a color target for each output a
+//! fragment shader can write for debugging, in `SV_Target` order:
+//! 0. the color, before blending and post-processing
+//! 1. any float4 the shader wants to inspect
+//! 2. a uint object id
+//!
+//! Shaders that only write `SV_Target0` leave the others zeroed; see
+//! shaders/source/pixel_debug.slang for an output struct with all three.
+//! Normal passes drop the extra outputs; the main pass's velocity target only
+//! takes `SV_Target1` from pipelines that write velocity (see [`super::velocity`]),
+//! which the replay shows as the debug value.
+
+use ash::vk;
+use glam::Vec4;
+
+use super::pipeline::GraphicsPipelineIndex;
+use super::{
+    BufferMemory, ImageOptions, MAX_FRAMES_IN_FLIGHT, create_image_view, create_memory_buffer,
+    create_vk_image, has_stencil_component,
+};
+
+const COLOR_FORMAT: vk::Format = vk::Format::R32G32B32A32_SFLOAT;
+const OBJECT_ID_FORMAT: vk::Format = vk::Format::R32_UINT;
+/// the replay's color attachments, in SV_Target order
+pub(super) const TARGET_FORMATS: [vk::Format; 3] = [COLOR_FORMAT, COLOR_FORMAT, OBJECT_ID_FORMAT];
+
+/// the readback buffer holds each target's texel in order, then depth
+const READBACK_OFFSETS: [u64; 4] = [0, 16, 32, 36];
+const READBACK_SIZE: usize = 40;
+
+/// What the pixel debugger replays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelDebugTarget {
+    pub pipeline: GraphicsPipelineIndex,
+    /// in render pixels: window coordinates times the render scale
+    pub pixel: [u32; 2],
+}
+
+/// What a pipeline's draws wrote to one pixel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelDebugSample {
+    pub target: PixelDebugTarget,
+    pub color: Vec4,
+    pub debug: Vec4,
+    pub object_id: u32,
+    /// 1.0 (the clear value) where nothing was drawn
+    pub depth: f32,
+}
+
+impl PixelDebugSample {
+    /// Whether any of the pipeline's draws reached the pixel
+    pub fn covered(&self) -> bool {
+        self.depth < 1.0
+    }
+
+    fn read(
+        target: PixelDebugTarget,
+        depth_format: vk::Format,
+        bytes: &[u8; READBACK_SIZE],
+    ) -> Self {
+        let word = |offset: usize| bytes[offset..offset + 4].try_into().unwrap();
+        let float = |offset: usize| f32::from_ne_bytes(word(offset));
+        let vec4 = |offset: usize| Vec4::from_array(std::array::from_fn(|i| float(offset + 4 * i)));
+
+        Self {
+            target,
+            color: vec4(READBACK_OFFSETS[0] as usize),
+            debug: vec4(READBACK_OFFSETS[1] as usize),
+            object_id: u32::from_ne_bytes(word(READBACK_OFFSETS[2] as usize)),
+            depth: decode_depth(depth_format, word(READBACK_OFFSETS[3] as usize)),
+        }
+    }
+}
+
+/// A depth texel copied to a buffer, which packs it by format
+pub(super) fn decode_depth(depth_format: vk::Format, bytes: [u8; 4]) -> f32 {
+    match depth_format {
+        vk::Format::D16_UNORM | vk::Format::D16_UNORM_S8_UINT => {
+            u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32
+        }
+        // the depth aspect is copied as X8_D24, with the top byte undefined
+        vk::Format::D24_UNORM_S8_UINT | vk::Format::X8_D24_UNORM_PACK32 => {
+            (u32::from_ne_bytes(bytes) & 0x00ff_ffff) as f32 / 0x00ff_ffff as f32
+        }
+        _ => f32::from_ne_bytes(bytes),
+    }
+}
+
+/// One flight slot's replay targets
+pub(super) struct PixelDebugImages {
+    /// the color targets in SV_Target order, then depth
+    pub images: [vk::Image; 4],
+    memories: [vk_mem::Allocation; 4],
+    pub views: [vk::ImageView; 4],
+    pub readback_buffer: vk::Buffer,
+    readback_memory: vk_mem::Allocation,
+    readback_mapped: *const u8,
+}
+
+impl PixelDebugImages {
+    fn init(
+        allocator: &vk_mem::Allocator,
+        device: &ash::Device,
+        depth_format: vk::Format,
+    ) -> anyhow::Result<Self> {
+        let formats = [
+            TARGET_FORMATS[0],
+            TARGET_FORMATS[1],
+            TARGET_FORMATS[2],
+            depth_format,
+        ];
+        let targets: [_; 4] = formats
+            .iter()
+            .map(|&format| -> anyhow::Result<_> {
+                let (usage, aspect) = if format == depth_format {
+                    (
+                        vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                        vk::ImageAspectFlags::DEPTH,
+                    )
+                } else {
+                    (
+                        vk::ImageUsageFlags::COLOR_ATTACHMENT,
+                        vk::ImageAspectFlags::COLOR,
+                    )
+                };
+                let image_options = ImageOptions {
+                    extent: vk::Extent2D {
+                        width: 1,
+                        height: 1,
+                    },
+                    format,
+                    tiling: vk::ImageTiling::OPTIMAL,
+                    usage: usage | vk::ImageUsageFlags::TRANSFER_SRC,
+                    mip_levels: 1,
+                    array_layers: 1,
+                    msaa_samples: vk::SampleCountFlags::TYPE_1,
+                };
+                let (image, memory) = create_vk_image(allocator, image_options)?;
+                let view = create_image_view(device, image, format, aspect, 1)?;
+                Ok((image, memory, view))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .unwrap();
+
+        let (readback_buffer, readback_memory) = create_memory_buffer(
+            allocator,
+            READBACK_SIZE as u64,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            BufferMemory::Readback,
+        )?;
+        let readback_mapped =
+            allocator.get_allocation_info(&readback_memory).mapped_data as *const u8;
+
+        Ok(Self {
+            images: targets.each_ref().map(|(image, _, _)| *image),
+            views: targets.each_ref().map(|(_, _, view)| *view),
+            memories: targets.map(|(_, memory, _)| memory),
+            readback_buffer,
+            readback_memory,
+            readback_mapped,
+        })
+    }
+
+    /// The copies of each target's texel into the readback buffer
+    pub fn readback_regions(&self) -> [(vk::Image, vk::BufferImageCopy); 4] {
+        std::array::from_fn(|i| {
+            let aspect_mask = if i == 3 {
+                vk::ImageAspectFlags::DEPTH
+            } else {
+                vk::ImageAspectFlags::COLOR
+            };
+            let region = vk::BufferImageCopy::default()
+                .buffer_offset(READBACK_OFFSETS[i])
+                .image_subresource(
+                    vk::ImageSubresourceLayers::default()
+                        .aspect_mask(aspect_mask)
+                        .mip_level(0)
+                        .base_array_layer(0)
+                        .layer_count(1),
+                )
+                .image_extent(vk::Extent3D {
+                    width: 1,
+                    height: 1,
+                    depth: 1,
+                });
+            (self.images[i], region)
+        })
+    }
+
+    fn destroy(mut self, allocator: &vk_mem::Allocator, device: &ash::Device) {
+        unsafe {
+            for i in 0..4 {
+                device.destroy_image_view(self.views[i], None);
+                allocator.destroy_image(self.images[i], &mut self.memories[i]);
+            }
+            allocator.destroy_buffer(self.readback_buffer, &mut self.readback_memory);
+        }
+    }
+}
+
+/// A pipeline rebuilt for the replay targets
+struct ReplayPipeline {
+    /// the pipeline it was built from, which changes on hot reload
+    source: vk::Pipeline,
+    pipeline: vk::Pipeline,
+}
+
+pub(super) struct PixelDebugger {
+    pub target: Option<PixelDebugTarget>,
+    /// the main pass's, so the replay tests depth at the same precision,
+    /// unless it has stencil; the replay's barriers only cover depth
+    pub depth_format: vk::Format,
+    /// indexed by flight_slot; empty until first use
+    images: Vec<PixelDebugImages>,
+    replay: Option<ReplayPipeline>,
+    /// replaced replay pipelines, destroyed once their flight slot comes around again
+    retired: [Vec<vk::Pipeline>; MAX_FRAMES_IN_FLIGHT],
+    /// what each flight slot's frame replayed, to read back after its wait
+    recorded: [Option<PixelDebugTarget>; MAX_FRAMES_IN_FLIGHT],
+    pub last_sample: Option<PixelDebugSample>,
+}
+
+impl PixelDebugger {
+    pub fn new(main_depth_format: vk::Format) -> Self {
+        let depth_format = if has_stencil_component(main_depth_format) {
+            // every device supports D16 attachments
+            vk::Format::D16_UNORM
+        } else {
+            main_depth_format
+        };
+
+        Self {
+            target: None,
+            depth_format,
+            images: vec![],
+            replay: None,
+            retired: Default::default(),
+            recorded: Default::default(),
+            last_sample: None,
+        }
+    }
+
+    /// The replay pipeline built from `source`, if it's still current
+    pub fn replay_pipeline(&self, source: vk::Pipeline) -> Option<vk::Pipeline> {
+        self.replay
+            .as_ref()
+            .filter(|replay| replay.source == source)
+            .map(|replay| replay.pipeline)
+    }
+
+    pub fn set_replay_pipeline(
+        &mut self,
+        flight_slot: usize,
+        source: vk::Pipeline,
+        pipeline: vk::Pipeline,
+    ) {
+        let replaced = self.replay.replace(ReplayPipeline { source, pipeline });
+        if let Some(replaced) = replaced {
+            self.retired[flight_slot].push(replaced.pipeline);
+        }
+    }
+
+    /// This flight slot's targets, creating every slot's on first use
+    pub fn images(
+        &mut self,
+        allocator: &vk_mem::Allocator,
+        device: &ash::Device,
+        flight_slot: usize,
+    ) -> anyhow::Result<&PixelDebugImages> {
+        while self.images.len() < MAX_FRAMES_IN_FLIGHT {
+            self.images.push(PixelDebugImages::init(
+                allocator,
+                device,
+                self.depth_format,
+            )?);
+        }
+
+        Ok(&self.images[flight_slot])
+    }
+
+    pub fn record(&mut self, flight_slot: usize, target: PixelDebugTarget) {
+        self.recorded[flight_slot] = Some(target);
+    }
+
+    /// Read back this flight slot's replay, and destroy the pipelines it retired;
+    /// call after waiting for the slot's previous frame
+    pub fn after_wait(&mut self, device: &ash::Device, flight_slot: usize) {
+        for pipeline in self.retired[flight_slot].drain(..) {
+            unsafe { device.destroy_pipeline(pipeline, None) };
+        }
+
+        let Some(target) = self.recorded[flight_slot].take() else {
+            return;
+        };
+        let mapped = self.images[flight_slot].readback_mapped;
+        let bytes = unsafe { &*(mapped as *const [u8; READBACK_SIZE]) };
+        self.last_sample = Some(PixelDebugSample::read(target, self.depth_format, bytes));
+    }
+
+    pub fn destroy(&mut self, allocator: &vk_mem::Allocator, device: &ash::Device) {
+        let replay = self.replay.take().map(|replay| replay.pipeline);
+        let retired = self.retired.iter_mut().flat_map(std::mem::take);
+        for pipeline in replay.into_iter().chain(retired) {
+            unsafe { device.destroy_pipeline(pipeline, None) };
+        }
+
+        for images in self.images.drain(..) {
+            images.destroy(allocator, device);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::pipeline::PipelineIndex;
+
+    #[test]
+    fn samples_read_each_target_from_its_offset() {
+        let mut bytes = [0; READBACK_SIZE];
+        let words: [u32; 10] = [
+            1.0_f32.to_bits(),
+            0.5_f32.to_bits(),
+            0.25_f32.to_bits(),
+            1.0_f32.to_bits(),
+            (-2.0_f32).to_bits(),
+            0,
+            0,
+            7.0_f32.to_bits(),
+            42,
+            0.75_f32.to_bits(),
+        ];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_ne_bytes());
+        }
+
+        let target = PixelDebugTarget {
+            pipeline: GraphicsPipelineIndex::from_raw(3),
+            pixel: [10, 20],
+        };
+        let sample = PixelDebugSample::read(target, vk::Format::D32_SFLOAT, &bytes);
+
+        assert_eq!(sample.color, Vec4::new(1.0, 0.5, 0.25, 1.0));
+        assert_eq!(sample.debug, Vec4::new(-2.0, 0.0, 0.0, 7.0));
+        assert_eq!(sample.object_id, 42);
+        assert_eq!(sample.depth, 0.75);
+        assert!(sample.covered());
+    }
+    #[test]
+    fn unorm_depth_is_decoded_by_format() {
+        let d16 = decode_depth(vk::Format::D16_UNORM, [0xff, 0xff, 0, 0]);
+        assert_eq!(d16, 1.0);
+
+        let d24 = 0xff80_0000_u32.to_ne_bytes();
+        let d24 = decode_depth(vk::Format::D24_UNORM_S8_UINT, d24);
+        assert!((d24 - 0.5).abs() < 1e-6);
+    }
+}
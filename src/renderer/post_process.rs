@@ -0,0 +1,117 @@
+use ash::vk;
+
+use super::{ImageOptions, MAX_FRAMES_IN_FLIGHT, create_image_view, create_vk_image};
+
+/// Color targets for the post-process pass, which runs after the main pass.
+///
+/// Pipelines created with [`super::PipelineConfig::with_post_process`] draw
+/// into this frame's image, which then replaces the main pass's resolved
+/// color as the source of the upscale blit. The resolved color itself is
+/// sampled through a texture from `Renderer::create_scene_color_texture`.
+pub(super) struct PostProcessTargets {
+    /// indexed by flight_slot
+    pub images: [vk::Image; MAX_FRAMES_IN_FLIGHT],
+    pub image_memories: [vk_mem::Allocation; MAX_FRAMES_IN_FLIGHT],
+    pub image_views: [vk::ImageView; MAX_FRAMES_IN_FLIGHT],
+}
+
+impl PostProcessTargets {
+    pub fn init(
+        allocator: &vk_mem::Allocator,
+        device: &ash::Device,
+        render_extent: vk::Extent2D,
+        format: vk::Format,
+    ) -> Result<Self, anyhow::Error> {
+        let (images, image_memories, image_views) =
+            create_post_process_images(allocator, device, render_extent, format)?;
+
+        Ok(Self {
+            images,
+            image_memories,
+            image_views,
+        })
+    }
+
+    pub fn recreate_images(
+        &mut self,
+        allocator: &vk_mem::Allocator,
+        device: &ash::Device,
+        render_extent: vk::Extent2D,
+        format: vk::Format,
+    ) -> Result<(), anyhow::Error> {
+        self.destroy_images(allocator, device);
+
+        let (images, image_memories, image_views) =
+            create_post_process_images(allocator, device, render_extent, format)?;
+
+        self.images = images;
+        self.image_memories = image_memories;
+        self.image_views = image_views;
+
+        Ok(())
+    }
+
+    pub fn destroy(mut self, allocator: &vk_mem::Allocator, device: &ash::Device) {
+        self.destroy_images(allocator, device);
+    }
+
+    fn destroy_images(&mut self, allocator: &vk_mem::Allocator, device: &ash::Device) {
+        unsafe {
+            for i in 0..MAX_FRAMES_IN_FLIGHT {
+                device.destroy_image_view(self.image_views[i], None);
+                allocator.destroy_image(self.images[i], &mut self.image_memories[i]);
+            }
+        }
+    }
+}
+
+/// A descriptor that samples the scene color, the TAA history, or the
+/// velocity buffer, which has to be rewritten whenever their images are recreated
+pub(super) struct SceneColorWrite {
+    /// the descriptor set's index within one ring slot's sets
+    pub set: usize,
+    pub binding: u32,
+    pub descriptor_type: vk::DescriptorType,
+    /// the TextureStorage index of the alias bound here
+    pub texture_index: usize,
+}
+
+fn create_post_process_images(
+    allocator: &vk_mem::Allocator,
+    device: &ash::Device,
+    render_extent: vk::Extent2D,
+    format: vk::Format,
+) -> Result<
+    (
+        [vk::Image; MAX_FRAMES_IN_FLIGHT],
+        [vk_mem::Allocation; MAX_FRAMES_IN_FLIGHT],
+        [vk::ImageView; MAX_FRAMES_IN_FLIGHT],
+    ),
+    anyhow::Error,
+> {
+    let image_options = ImageOptions {
+        extent: render_extent,
+        format,
+        tiling: vk::ImageTiling::OPTIMAL,
+        usage: vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
+        mip_levels: 1,
+        array_layers: 1,
+        msaa_samples: vk::SampleCountFlags::TYPE_1,
+    };
+
+    let results: [_; MAX_FRAMES_IN_FLIGHT] = (0..MAX_FRAMES_IN_FLIGHT)
+        .map(|_| -> anyhow::Result<_> {
+            let (image, memory) = create_vk_image(allocator, image_options)?;
+            let view = create_image_view(device, image, format, vk::ImageAspectFlags::COLOR, 1)?;
+            Ok((image, memory, view))
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .unwrap();
+
+    let images = results.map(|(image, _, _)| image);
+    let memories = results.map(|(_, memory, _)| memory);
+    let views = results.map(|(_, _, view)| view);
+
+    Ok((images, memories, views))
+}
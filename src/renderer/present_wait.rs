@@ -0,0 +1,176 @@
+//! Frame pacing from presentation, with VK_KHR_present_id and VK_KHR_present_wait.
+//!
+//! Where the device supports both, every present gets an id, and the app
+//! waits for the previous frame to reach the display before starting the next
+//! one, instead of sleeping for the game's frame delay. That keeps at most one
+//! frame queued for the display, and measures how long presents take to get
+//! there ([`FrameStats::display_latency`](super::FrameStats::display_latency)).
+//! Elsewhere the app falls back to its timer.
+
+use std::collections::VecDeque;
+use std::ffi::CStr;
+use std::time::{Duration, Instant};
+
+use ash::vk;
+
+pub(super) const PRESENT_WAIT_DEVICE_EXTENSIONS: [&CStr; 2] =
+    [ash::khr::present_id::NAME, ash::khr::present_wait::NAME];
+
+/// frames presented but maybe not yet displayed, past which the app waits
+const MAX_QUEUED_PRESENTS: usize = 1;
+
+/// long enough for any display, short enough that a hidden window
+/// doesn't hang the app
+const WAIT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Whether the device has both features, once their extensions are known to be supported
+pub(super) fn features_supported(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+) -> bool {
+    let mut present_id = vk::PhysicalDevicePresentIdFeaturesKHR::default();
+    let mut present_wait = vk::PhysicalDevicePresentWaitFeaturesKHR::default();
+    let mut features = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut present_id)
+        .push_next(&mut present_wait);
+    unsafe { instance.get_physical_device_features2(physical_device, &mut features) };
+
+    present_id.present_id == vk::TRUE && present_wait.present_wait == vk::TRUE
+}
+
+pub(super) struct PresentWait {
+    device_ext: ash::khr::present_wait::Device,
+    queue: PresentQueue,
+}
+
+impl PresentWait {
+    pub fn new(instance: &ash::Instance, device: &ash::Device) -> Self {
+        Self {
+            device_ext: ash::khr::present_wait::Device::new(instance, device),
+            queue: PresentQueue::default(),
+        }
+    }
+
+    /// The id for a present being queued now
+    pub fn next_present_id(&mut self) -> u64 {
+        self.queue.push(Instant::now())
+    }
+
+    /// Forget presents to a swapchain that's been destroyed
+    pub fn swapchain_recreated(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Block until no more than [`MAX_QUEUED_PRESENTS`] presents are waiting
+    /// to be displayed. A timeout (the window is hidden, say) gives up on the
+    /// presents waited for.
+    pub fn wait(&mut self, swapchain: vk::SwapchainKHR) -> Result<(), vk::Result> {
+        while let Some(present_id) = self.queue.oldest_beyond(MAX_QUEUED_PRESENTS) {
+            let waited = unsafe {
+                self.device_ext.wait_for_present(
+                    swapchain,
+                    present_id,
+                    WAIT_TIMEOUT.as_nanos() as u64,
+                )
+            };
+            match waited {
+                Ok(()) => self.queue.displayed(present_id, Instant::now()),
+                Err(vk::Result::TIMEOUT) => {
+                    self.queue.clear();
+                    return Ok(());
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn display_latency(&self) -> Option<Duration> {
+        self.queue.latency
+    }
+}
+
+/// Ids of presents not yet known to be displayed, and when they were queued
+#[derive(Debug, Default)]
+struct PresentQueue {
+    /// oldest first
+    queued: VecDeque<(u64, Instant)>,
+    /// ids increase with every present, across swapchains; 0 means none
+    last_id: u64,
+    /// from queueing the last displayed present to its wait returning
+    latency: Option<Duration>,
+}
+
+impl PresentQueue {
+    fn push(&mut self, now: Instant) -> u64 {
+        self.last_id += 1;
+        self.queued.push_back((self.last_id, now));
+        self.last_id
+    }
+
+    /// The oldest present to wait for, with more than `max_queued` queued
+    fn oldest_beyond(&self, max_queued: usize) -> Option<u64> {
+        if self.queued.len() <= max_queued {
+            return None;
+        }
+        self.queued.front().map(|&(id, _)| id)
+    }
+
+    /// Presents are displayed in order, so this one's predecessors were too
+    fn displayed(&mut self, present_id: u64, now: Instant) {
+        while let Some(&(id, queued_at)) = self.queued.front() {
+            if id > present_id {
+                break;
+            }
+            self.queued.pop_front();
+            if id == present_id {
+                self.latency = Some(now - queued_at);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.queued.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_micros(16_667);
+
+    #[test]
+    fn waits_once_more_than_one_present_is_queued() {
+        let start = Instant::now();
+        let mut queue = PresentQueue::default();
+
+        assert_eq!(queue.push(start), 1);
+        assert_eq!(queue.oldest_beyond(MAX_QUEUED_PRESENTS), None);
+        assert_eq!(queue.push(start + FRAME), 2);
+        assert_eq!(queue.oldest_beyond(MAX_QUEUED_PRESENTS), Some(1));
+
+        queue.displayed(1, start + FRAME * 2);
+        assert_eq!(queue.latency, Some(FRAME * 2));
+        assert_eq!(queue.oldest_beyond(MAX_QUEUED_PRESENTS), None);
+    }
+
+    #[test]
+    fn displaying_a_present_displays_its_predecessors() {
+        let start = Instant::now();
+        let mut queue = PresentQueue::default();
+        for frame in 0..3 {
+            queue.push(start + FRAME * frame);
+        }
+
+        queue.displayed(2, start + FRAME * 3);
+        assert_eq!(queue.latency, Some(FRAME * 2));
+        assert_eq!(queue.oldest_beyond(0), Some(3));
+
+        // ids keep increasing after the swapchain is recreated
+        queue.clear();
+        assert_eq!(queue.oldest_beyond(0), None);
+        assert_eq!(queue.push(start + FRAME * 4), 4);
+    }
+}
@@ -0,0 +1,175 @@
+//! The [`UpscaleFilter::Sharpened`](super::UpscaleFilter::Sharpened)
+//! composite: in place of the upscale blit, a fullscreen draw into the
+//! swapchain image that samples the render-resolution image bilinearly and
+//! sharpens it with FSR1's RCAS at window resolution (rcas_upscale.shader.slang).
+
+use ash::vk;
+use glam::Vec2;
+
+use crate::generated::shader_atlas::rcas_upscale::{PushConstants, Shader};
+
+use super::{
+    BlendMode, CullMode, DepthCompare, PRE_WAIT_RING_LEN, RasterState, ShaderPipelineLayout,
+    TextureFilter, TextureOptions, TextureWrap, create_descriptor_pool, create_graphics_pipeline,
+    create_texture_sampler,
+};
+
+/// RCAS strength, in stops below its sharpest; FSR1's default
+const SHARPNESS_STOPS: f32 = 0.2;
+
+/// the binding of the shader's `source`
+const SOURCE_BINDING: u32 = 0;
+
+pub(super) struct RcasUpscale {
+    layout: ShaderPipelineLayout,
+    pipeline: vk::Pipeline,
+    descriptor_pool: vk::DescriptorPool,
+    /// indexed by ring_slot, and pointed at that frame's source as it's recorded
+    descriptor_sets: Vec<vk::DescriptorSet>,
+    /// linear, so each RCAS tap reads an upscaled texel
+    sampler: vk::Sampler,
+}
+
+impl RcasUpscale {
+    /// `format` is the swapchain's
+    pub fn init(
+        device: &ash::Device,
+        physical_device_properties: vk::PhysicalDeviceProperties,
+        format: vk::Format,
+    ) -> anyhow::Result<Self> {
+        let shader = Shader::init();
+        let layout = ShaderPipelineLayout::create_from_atlas(device, &shader)?;
+        let raster_state = RasterState {
+            blend: BlendMode::Opaque,
+            cull: CullMode::None,
+            depth_test: DepthCompare::Disabled,
+            depth_write: false,
+            ..Default::default()
+        };
+        let pipeline = create_graphics_pipeline(
+            device,
+            &[format],
+            false,
+            None,
+            vk::SampleCountFlags::TYPE_1,
+            0,
+            &layout,
+            &[],
+            &[],
+            &raster_state,
+            false,
+        )?;
+
+        let descriptor_pool = create_descriptor_pool(device, &layout)?;
+        let set_layouts = vec![layout.descriptor_set_layouts[0].0; PRE_WAIT_RING_LEN];
+        let alloc_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&set_layouts);
+        let descriptor_sets = unsafe { device.allocate_descriptor_sets(&alloc_info)? };
+
+        let sampler = create_texture_sampler(
+            device,
+            physical_device_properties,
+            TextureOptions {
+                filter: TextureFilter::Linear,
+                wrap_u: TextureWrap::ClampToEdge,
+                wrap_v: TextureWrap::ClampToEdge,
+                mipmaps: false,
+                ..Default::default()
+            },
+        )?;
+
+        Ok(Self {
+            layout,
+            pipeline,
+            descriptor_pool,
+            descriptor_sets,
+            sampler,
+        })
+    }
+
+    /// Draws `source_view`, in SHADER_READ_ONLY_OPTIMAL, over `present_rect`
+    /// of the swapchain image being rendered to
+    pub fn record_draw(
+        &self,
+        device: &ash::Device,
+        command_buffer: vk::CommandBuffer,
+        ring_slot: usize,
+        source_view: vk::ImageView,
+        present_rect: vk::Rect2D,
+    ) {
+        // this ring slot's set isn't in use by any frame still in flight
+        let descriptor_set = self.descriptor_sets[ring_slot];
+        let image_info = [vk::DescriptorImageInfo::default()
+            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .image_view(source_view)
+            .sampler(self.sampler)];
+        let write = vk::WriteDescriptorSet::default()
+            .dst_set(descriptor_set)
+            .dst_binding(SOURCE_BINDING)
+            .dst_array_element(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(&image_info);
+
+        let extent = present_rect.extent;
+        let push_constants = PushConstants {
+            texel_size: Vec2::ONE / Vec2::new(extent.width as f32, extent.height as f32),
+            stops: SHARPNESS_STOPS,
+            ..Default::default()
+        };
+        let push_constant_bytes = unsafe {
+            std::slice::from_raw_parts(
+                (&push_constants as *const PushConstants).cast::<u8>(),
+                std::mem::size_of::<PushConstants>(),
+            )
+        };
+
+        let viewport = vk::Viewport::default()
+            .x(present_rect.offset.x as f32)
+            .y(present_rect.offset.y as f32)
+            .width(extent.width as f32)
+            .height(extent.height as f32)
+            .min_depth(0.0)
+            .max_depth(1.0);
+
+        unsafe {
+            device.update_descriptor_sets(&[write], &[]);
+            device.cmd_bind_pipeline(
+                command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.pipeline,
+            );
+            device.cmd_set_viewport(command_buffer, 0, &[viewport]);
+            device.cmd_set_scissor(command_buffer, 0, &[present_rect]);
+            device.cmd_bind_descriptor_sets(
+                command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.layout.pipeline_layout,
+                0,
+                &[descriptor_set],
+                &[],
+            );
+            device.cmd_push_constants(
+                command_buffer,
+                self.layout.pipeline_layout,
+                vk::ShaderStageFlags::FRAGMENT,
+                0,
+                push_constant_bytes,
+            );
+            device.cmd_draw(command_buffer, 3, 1, 0, 0);
+        }
+    }
+
+    pub fn destroy(self, device: &ash::Device) {
+        unsafe {
+            device.destroy_sampler(self.sampler, None);
+            device.destroy_pipeline(self.pipeline, None);
+            // this also frees the sets
+            device.destroy_descriptor_pool(self.descriptor_pool, None);
+            device.destroy_pipeline_layout(self.layout.pipeline_layout, None);
+            for &(set_layout, _) in &self.layout.descriptor_set_layouts {
+                device.destroy_descriptor_set_layout(set_layout, None);
+            }
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Asynchronous GPU-to-CPU readback.
+//!
+//! A readback copies a buffer range or an image region into a staging buffer
+//! at a fixed point in a frame's command buffer. Once the renderer has waited
+//! on that frame's flight slot (two frames later), the bytes are delivered:
+//! to the request's callback if it has one, and otherwise to
+//! [`Renderer::poll_readbacks`](super::Renderer::poll_readbacks).
+//! Picking, screenshots, and GPU-only buffer reads all go through here.
+
+use ash::vk;
+
+use super::picking::PickingWindow;
+use super::texture_inspector::InspectorTarget;
+use super::{BufferMemory, MAX_FRAMES_IN_FLIGHT, create_memory_buffer};
+
+/// Identifies a readback request, and the [`Readback`] that fulfills it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadbackId(u64);
+
+/// The bytes copied back for a request
+#[derive(Debug, Clone)]
+pub struct Readback {
+    pub id: ReadbackId,
+    pub bytes: Vec<u8>,
+    /// set for image readbacks, whose rows are tightly packed
+    pub image: Option<ReadbackImage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadbackImage {
+    pub width: u32,
+    pub height: u32,
+    pub format: vk::Format,
+}
+
+impl Readback {
+    /// The bytes as a slice of `T`, for buffer readbacks;
+    /// trailing bytes that don't fill a whole `T` are dropped.
+    ///
+    /// # Safety
+    /// every bit pattern must be a valid `T`, as for the plain floats, uints,
+    /// and generated shader structs that a shader writes; not bools, enums,
+    /// or references. `T` can't be zero-sized.
+    pub unsafe fn to_vec<T: Copy>(&self) -> Vec<T> {
+        let size = std::mem::size_of::<T>();
+        assert!(size > 0, "can't read back zero-sized values");
+        self.bytes
+            .chunks_exact(size)
+            .map(|chunk| unsafe { std::ptr::read_unaligned(chunk.as_ptr() as *const T) })
+            .collect()
+    }
+
+    /// An image readback as 8-bit RGBA; None for other formats, like the
+    /// 10-bit and half float swapchains of HDR output.
+    /// sRGB formats are left encoded, as they would be saved to a file.
+    pub fn to_rgba_image(&self) -> Option<image::RgbaImage> {
+        let image = self.image?;
+        let swizzle_bgra = match image.format {
+            vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB => false,
+            vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB => true,
+            _ => return None,
+        };
+
+        let mut bytes = self.bytes.clone();
+        if swizzle_bgra {
+            for texel in bytes.chunks_exact_mut(4) {
+                texel.swap(0, 2);
+            }
+        }
+        image::RgbaImage::from_raw(image.width, image.height, bytes)
+    }
+}
+
+/// Called with a finished readback, on the thread that draws frames
+pub type ReadbackCallback = Box<dyn FnOnce(Readback)>;
+
+/// What a readback copies from, when it's recorded
+pub(super) enum ReadbackSource {
+    Buffer {
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    },
+    /// an image, in TRANSFER_SRC_OPTIMAL or GENERAL layout at the copy
+    Image {
+        image: vk::Image,
+        layout: vk::ImageLayout,
+        /// one aspect of one layer; usually [`COLOR_LAYER`]
+        subresource: vk::ImageSubresourceLayers,
+        offset: [u32; 2],
+        extent: [u32; 2],
+        format: vk::Format,
+        texel_size: u32,
+    },
+}
+
+/// The top mip level's first layer of a color image
+pub(super) const COLOR_LAYER: vk::ImageSubresourceLayers = vk::ImageSubresourceLayers {
+    aspect_mask: vk::ImageAspectFlags::COLOR,
+    mip_level: 0,
+    base_array_layer: 0,
+    layer_count: 1,
+};
+
+impl ReadbackSource {
+    fn size(&self) -> vk::DeviceSize {
+        match self {
+            ReadbackSource::Buffer { size, .. } => *size,
+            ReadbackSource::Image {
+                extent, texel_size, ..
+            } => extent[0] as u64 * extent[1] as u64 * *texel_size as u64,
+        }
+    }
+}
+
+/// A request whose source is only known while recording its frame
+pub(super) enum ReadbackRequest {
+    /// the final scene image, before the editor UI
+    Screenshot,
+    /// a GPU-only storage buffer's slot for the frame, at the end of the frame
+    GpuOnlyBuffer { index: usize, size: vk::DeviceSize },
+    /// a feedback texture's image for the frame, after its pass
+    FeedbackTexture { index: usize },
+}
+
+/// Who gets a finished readback
+pub(super) enum Delivery {
+    Poll,
+    Callback(ReadbackCallback),
+    /// the renderer's own picking, resolved to an id
+    Picking(PickingWindow),
+    /// the texture inspector's image, kept until it's taken
+    Inspector(InspectorTarget),
+}
+
+struct StagingBuffer {
+    buffer: vk::Buffer,
+    allocation: vk_mem::Allocation,
+    mapped: *const u8,
+    capacity: vk::DeviceSize,
+}
+
+struct InFlightReadback {
+    id: ReadbackId,
+    staging: StagingBuffer,
+    size: vk::DeviceSize,
+    image: Option<ReadbackImage>,
+    delivery: Delivery,
+}
+
+pub(super) struct Readbacks {
+    next_id: u64,
+    requested: Vec<(ReadbackId, ReadbackRequest, Delivery)>,
+    /// indexed by flight_slot
+    in_flight: [Vec<InFlightReadback>; MAX_FRAMES_IN_FLIGHT],
+    /// staging buffers from delivered readbacks, for reuse
+    free: Vec<StagingBuffer>,
+    completed: Vec<Readback>,
+    inspected: Option<(InspectorTarget, Readback)>,
+}
+
+impl Readbacks {
+    pub fn new() -> Self {
+        Self {
+            next_id: 0,
+            requested: vec![],
+            in_flight: Default::default(),
+            free: vec![],
+            completed: vec![],
+            inspected: None,
+        }
+    }
+
+    pub fn next_id(&mut self) -> ReadbackId {
+        self.next_id += 1;
+        ReadbackId(self.next_id)
+    }
+
+    /// Queue a request for the next recorded frame
+    pub fn request(&mut self, request: ReadbackRequest, delivery: Delivery) -> ReadbackId {
+        let id = self.next_id();
+        self.requested.push((id, request, delivery));
+        id
+    }
+
+    /// Deliver a queued or in-flight readback to `callback`;
+    /// false if it's already been delivered
+    pub fn set_callback(&mut self, id: ReadbackId, callback: ReadbackCallback) -> bool {
+        let requested = self
+            .requested
+            .iter_mut()
+            .filter(|(request_id, _, _)| *request_id == id)
+            .map(|(_, _, delivery)| delivery);
+        let in_flight = self
+            .in_flight
+            .iter_mut()
+            .flatten()
+            .filter(|in_flight| in_flight.id == id)
+            .map(|in_flight| &mut in_flight.delivery);
+
+        let Some(delivery) = requested.chain(in_flight).next() else {
+            return false;
+        };
+        if !matches!(delivery, Delivery::Poll) {
+            return false;
+        }
+        *delivery = Delivery::Callback(callback);
+        true
+    }
+
+    /// Remove and return the queued requests that `matches` accepts
+    pub fn take_requests(
+        &mut self,
+        matches: impl Fn(&ReadbackRequest) -> bool,
+    ) -> Vec<(ReadbackId, ReadbackRequest, Delivery)> {
+        let (taken, kept) = std::mem::take(&mut self.requested)
+            .into_iter()
+            .partition(|(_, request, _)| matches(request));
+        self.requested = kept;
+        taken
+    }
+
+    /// Record the copy of `source` into a staging buffer, to be delivered
+    /// after this flight slot's next wait
+    #[expect(clippy::too_many_arguments)]
+    pub fn record(
+        &mut self,
+        allocator: &vk_mem::Allocator,
+        device: &ash::Device,
+        command_buffer: vk::CommandBuffer,
+        flight_slot: usize,
+        id: ReadbackId,
+        source: ReadbackSource,
+        delivery: Delivery,
+    ) -> anyhow::Result<()> {
+        let size = source.size();
+        let staging = self.staging_buffer(allocator, size)?;
+
+        let image = match source {
+            ReadbackSource::Buffer { buffer, offset, .. } => {
+                let region = vk::BufferCopy::default()
+                    .src_offset(offset)
+                    .dst_offset(0)
+                    .size(size);
+                unsafe {
+                    device.cmd_copy_buffer(command_buffer, buffer, staging.buffer, &[region])
+                };
+                None
+            }
+            ReadbackSource::Image {
+                image,
+                layout,
+                subresource,
+                offset,
+                extent,
+                format,
+                ..
+            } => {
+                let region = vk::BufferImageCopy::default()
+                    .buffer_offset(0)
+                    .buffer_row_length(0)
+                    .buffer_image_height(0)
+                    .image_subresource(subresource)
+                    .image_offset(vk::Offset3D {
+                        x: offset[0] as i32,
+                        y: offset[1] as i32,
+                        z: 0,
+                    })
+                    .image_extent(vk::Extent3D {
+                        width: extent[0],
+                        height: extent[1],
+                        depth: 1,
+                    });
+                unsafe {
+                    device.cmd_copy_image_to_buffer(
+                        command_buffer,
+                        image,
+                        layout,
+                        staging.buffer,
+                        &[region],
+                    );
+                }
+                Some(ReadbackImage {
+                    width: extent[0],
+                    height: extent[1],
+                    format,
+                })
+            }
+        };
+
+        self.in_flight[flight_slot].push(InFlightReadback {
+            id,
+            staging,
+            size,
+            image,
+            delivery,
+        });
+
+        Ok(())
+    }
+
+    fn staging_buffer(
+        &mut self,
+        allocator: &vk_mem::Allocator,
+        size: vk::DeviceSize,
+    ) -> anyhow::Result<StagingBuffer> {
+        if let Some(position) = self.free.iter().position(|free| free.capacity >= size) {
+            return Ok(self.free.swap_remove(position));
+        }
+
+        let (buffer, allocation) = create_memory_buffer(
+            allocator,
+            size,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            BufferMemory::Readback,
+        )?;
+        let mapped = allocator.get_allocation_info(&allocation).mapped_data as *const u8;
+
+        Ok(StagingBuffer {
+            buffer,
+            allocation,
+            mapped,
+            capacity: size,
+        })
+    }
+
+    /// Deliver this flight slot's readbacks; call after waiting for the slot's
+    /// previous frame. Returns the picked object id, if a pick finished.
+    pub fn after_wait(&mut self, flight_slot: usize) -> Option<u32> {
+        let mut picked_object_id = None;
+
+        for in_flight in std::mem::take(&mut self.in_flight[flight_slot]) {
+            let bytes = unsafe {
+                std::slice::from_raw_parts(in_flight.staging.mapped, in_flight.size as usize)
+            };
+            match in_flight.delivery {
+                Delivery::Picking(window) => {
+                    let ids: Vec<u32> = bytes
+                        .chunks_exact(4)
+                        .map(|id| u32::from_ne_bytes(id.try_into().unwrap()))
+                        .collect();
+                    picked_object_id = Some(window.resolve(&ids));
+                }
+                Delivery::Poll => self.completed.push(Readback {
+                    id: in_flight.id,
+                    bytes: bytes.to_vec(),
+                    image: in_flight.image,
+                }),
+                Delivery::Callback(callback) => callback(Readback {
+                    id: in_flight.id,
+                    bytes: bytes.to_vec(),
+                    image: in_flight.image,
+                }),
+                Delivery::Inspector(target) => {
+                    let readback = Readback {
+                        id: in_flight.id,
+                        bytes: bytes.to_vec(),
+                        image: in_flight.image,
+                    };
+                    self.inspected = Some((target, readback));
+                }
+            }
+            self.free.push(in_flight.staging);
+        }
+
+        picked_object_id
+    }
+
+    /// The finished readbacks without callbacks, oldest first
+    pub fn poll(&mut self) -> Vec<Readback> {
+        std::mem::take(&mut self.completed)
+    }
+
+    /// The texture inspector's readback, once it's arrived
+    pub fn take_inspected(&mut self) -> Option<(InspectorTarget, Readback)> {
+        self.inspected.take()
+    }
+
+    pub fn destroy(&mut self, allocator: &vk_mem::Allocator) {
+        let in_flight = self
+            .in_flight
+            .iter_mut()
+            .flat_map(std::mem::take)
+            .map(|in_flight| in_flight.staging);
+        let staging_buffers: Vec<_> = in_flight.chain(self.free.drain(..)).collect();
+        for mut staging in staging_buffers {
+            unsafe { allocator.destroy_buffer(staging.buffer, &mut staging.allocation) };
+        }
+        self.requested.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bgra_readbacks_convert_to_rgba_images() {
+        let readback = Readback {
+            id: ReadbackId(1),
+            bytes: vec![1, 2, 3, 255, 4, 5, 6, 128],
+            image: Some(ReadbackImage {
+                width: 2,
+                height: 1,
+                format: vk::Format::B8G8R8A8_SRGB,
+            }),
+        };
+
+        let image = readback.to_rgba_image().unwrap();
+        assert_eq!(image.as_raw(), &[3, 2, 1, 255, 6, 5, 4, 128]);
+
+        let float_readback = Readback {
+            image: Some(ReadbackImage {
+                format: vk::Format::R32G32B32A32_SFLOAT,
+                ..readback.image.unwrap()
+            }),
+            ..readback.clone()
+        };
+        assert!(float_readback.to_rgba_image().is_none());
+    }
+
+    #[test]
+    fn buffer_readbacks_reinterpret_their_bytes() {
+        let values = [7_u32, 0xdead_beef, 42];
+        let readback = Readback {
+            id: ReadbackId(1),
+            bytes: values
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect(),
+            image: None,
+        };
+
+        assert_eq!(unsafe { readback.to_vec::<u32>() }, values);
+    }
+
+    #[test]
+    fn requests_are_taken_by_kind() {
+        let mut readbacks = Readbacks::new();
+        let screenshot = readbacks.request(ReadbackRequest::Screenshot, Delivery::Poll);
+        let buffer = readbacks.request(
+            ReadbackRequest::GpuOnlyBuffer { index: 0, size: 4 },
+            Delivery::Poll,
+        );
+        assert_ne!(screenshot, buffer);
+
+        let taken =
+            readbacks.take_requests(|request| matches!(request, ReadbackRequest::Screenshot));
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].0, screenshot);
+        assert_eq!(readbacks.requested.len(), 1);
+        assert_eq!(readbacks.requested[0].0, buffer);
+    }
+}
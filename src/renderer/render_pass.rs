@@ -0,0 +1,204 @@
+//! An ordered list of draws for one frame, each with its own buffer writes.
+//!
+//! Queueing draws on a [`FrameRenderer`] leaves every buffer write to the one
+//! `gpu_update` closure passed to `submit_draws`. A [`RenderPass`] keeps each
+//! pipeline's writes next to its draw instead, so a 3D scene and a 2D HUD
+//! can be drawn in the same frame without sharing a closure:
+//!
+//! ```ignore
+//! renderer
+//!     .render_pass()
+//!     .draw_indexed(&self.scene, |gpu| {
+//!         gpu.write_uniform(&mut self.camera_buffer, camera);
+//!     })
+//!     .draw_vertex_count(&self.hud, 6, |gpu| {
+//!         gpu.write_uniform(&mut self.hud_buffer, hud);
+//!     })
+//!     .submit()
+//! ```
+//!
+//! Draws are recorded in the order they were added, into the same swapchain
+//! image and depth buffer; writes run in the same order, before any of them.
+
+use super::{
+    DrawError, DrawIndexed, DrawMeshTasks, DrawVertexCount, FrameRenderer, PipelineHandle,
+    QueueDraws,
+};
+
+type WriteFn<'w, R> = Box<dyn FnOnce(&mut <R as QueueDraws>::Gpu<'_>) + 'w>;
+
+/// Draws and their writes, submitted together as one frame;
+/// see the [module docs](self)
+pub struct RenderPass<'w, R: QueueDraws> {
+    frame: R,
+    writes: Vec<WriteFn<'w, R>>,
+}
+
+impl<'w, R: QueueDraws> RenderPass<'w, R> {
+    pub(super) fn new(frame: R) -> Self {
+        Self {
+            frame,
+            writes: vec![],
+        }
+    }
+
+    fn with_write(mut self, write: impl FnOnce(&mut R::Gpu<'_>) + 'w) -> Self {
+        self.writes.push(Box::new(write));
+        self
+    }
+
+    /// draw the pipeline's whole vertex/index source
+    pub fn draw_indexed(
+        mut self,
+        pipeline: &PipelineHandle<DrawIndexed>,
+        write: impl FnOnce(&mut R::Gpu<'_>) + 'w,
+    ) -> Self {
+        self.frame.queue_draw_indexed(pipeline);
+        self.with_write(write)
+    }
+
+    /// draw an index sub-range of the pipeline's vertex/index source
+    pub fn draw_index_range(
+        mut self,
+        pipeline: &PipelineHandle<DrawIndexed>,
+        first_index: u32,
+        index_count: u32,
+        write: impl FnOnce(&mut R::Gpu<'_>) + 'w,
+    ) -> Self {
+        self.frame
+            .queue_draw_index_range(pipeline, first_index, index_count);
+        self.with_write(write)
+    }
+
+    /// a vertex-count draw (no vertex/index buffers)
+    pub fn draw_vertex_count(
+        mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        vertex_count: u32,
+        write: impl FnOnce(&mut R::Gpu<'_>) + 'w,
+    ) -> Self {
+        self.frame.queue_draw_vertex_count(pipeline, vertex_count);
+        self.with_write(write)
+    }
+
+    /// a vertex-count draw of [first_vertex, first_vertex + vertex_count)
+    pub fn draw_vertex_range(
+        mut self,
+        pipeline: &PipelineHandle<DrawVertexCount>,
+        first_vertex: u32,
+        vertex_count: u32,
+        write: impl FnOnce(&mut R::Gpu<'_>) + 'w,
+    ) -> Self {
+        self.frame
+            .queue_draw_vertex_range(pipeline, first_vertex, vertex_count);
+        self.with_write(write)
+    }
+
+    /// a mesh shader draw of `group_count` task (or mesh) workgroups
+    pub fn draw_mesh_tasks(
+        mut self,
+        pipeline: &PipelineHandle<DrawMeshTasks>,
+        group_count: [u32; 3],
+        write: impl FnOnce(&mut R::Gpu<'_>) + 'w,
+    ) -> Self {
+        self.frame.queue_draw_mesh_tasks(pipeline, group_count);
+        self.with_write(write)
+    }
+
+    /// Submit every draw as this frame's rendering, after running their writes in order
+    pub fn submit(self) -> Result<(), DrawError> {
+        let writes = self.writes;
+        self.frame.submit_draws(|gpu| {
+            for write in writes {
+                write(gpu);
+            }
+        })
+    }
+}
+
+impl FrameRenderer<'_> {
+    /// Start an ordered list of draws with their own writes; see [`RenderPass`]
+    pub fn render_pass<'w>(self) -> RenderPass<'w, Self> {
+        RenderPass::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+
+    use crate::generated::shader_atlas::ShaderAtlas;
+    use crate::generated::shader_atlas::basic_triangle::{MVPMatrices, Resources};
+    use crate::renderer::command_log::LoggedWrite;
+    use crate::renderer::{
+        AuditedBuffer, CreateResources, NullRenderer, UniformBufferHandle, WriteBuffers,
+    };
+
+    use super::*;
+
+    fn triangle_pipeline(
+        renderer: &mut NullRenderer,
+        matrices_buffer: &UniformBufferHandle<MVPMatrices>,
+    ) -> PipelineHandle<DrawIndexed> {
+        let resources = Resources {
+            vertices: vec![],
+            indices: vec![],
+            matrices_buffer,
+        };
+        let config = ShaderAtlas::init()
+            .basic_triangle
+            .pipeline_config(resources);
+        renderer.create_pipeline(config).unwrap()
+    }
+
+    fn matrices(scale: f32) -> MVPMatrices {
+        MVPMatrices {
+            model: Mat4::from_scale(glam::Vec3::splat(scale)),
+            view: Mat4::IDENTITY,
+            proj: Mat4::IDENTITY,
+        }
+    }
+
+    #[test]
+    fn draws_and_writes_keep_their_order() {
+        let mut renderer = NullRenderer::new(800, 600);
+        let mut scene_buffer = renderer.create_uniform_buffer::<MVPMatrices>().unwrap();
+        let mut hud_buffer = renderer.create_uniform_buffer::<MVPMatrices>().unwrap();
+        let scene = triangle_pipeline(&mut renderer, &scene_buffer);
+        let hud = triangle_pipeline(&mut renderer, &hud_buffer);
+
+        renderer
+            .frame()
+            .render_pass()
+            .draw_indexed(&scene, |gpu| {
+                gpu.write_uniform(&mut scene_buffer, matrices(2.0));
+            })
+            .draw_index_range(&hud, 0, 3, |gpu| {
+                gpu.write_uniform(&mut hud_buffer, matrices(0.5));
+            })
+            .submit()
+            .unwrap();
+
+        let log = renderer.last_frame().unwrap();
+        let pipelines: Vec<_> = log.draws.iter().map(|draw| draw.pipeline).collect();
+        assert_eq!(pipelines, [scene.raw_index(), hud.raw_index()]);
+        let write_len = std::mem::size_of::<MVPMatrices>();
+        assert_eq!(
+            log.writes,
+            [
+                LoggedWrite {
+                    buffer: AuditedBuffer::Uniform(0),
+                    len: write_len,
+                },
+                LoggedWrite {
+                    buffer: AuditedBuffer::Uniform(1),
+                    len: write_len,
+                },
+            ]
+        );
+        assert_eq!(
+            renderer.uniform(&hud_buffer).unwrap().model,
+            matrices(0.5).model
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use super::{FeedbackTextureHandle, TextureHandle};
+
+/// An offscreen color target to draw a scene into, and then sample in a later
+/// pass, e.g. to post-process it.
+///
+/// Pipelines created with [`super::PipelineConfig::with_render_target`] draw
+/// into it in a pass before the main pass, which clears it first; main pass
+/// pipelines bind [`texture`](Self::texture) like any other texture. It's a
+/// [`FeedbackTextureHandle`] that never samples its previous frame, so it
+/// shares the feedback pass, readbacks, and frame dumps.
+#[derive(Debug)]
+pub struct RenderTargetHandle {
+    target: FeedbackTextureHandle,
+}
+
+impl RenderTargetHandle {
+    pub(super) fn new(target: FeedbackTextureHandle) -> Self {
+        Self { target }
+    }
+
+    /// what was drawn into the target this frame; only valid to sample after
+    /// its pass, ie from the main pass
+    pub fn texture(&self) -> &TextureHandle {
+        self.target.current()
+    }
+}
+
+/// Offscreen targets a pipeline can draw into;
+/// see [`super::PipelineConfig::with_render_target`]
+pub trait RenderTarget {
+    fn feedback_texture(&self) -> &FeedbackTextureHandle;
+}
+
+impl RenderTarget for FeedbackTextureHandle {
+    fn feedback_texture(&self) -> &FeedbackTextureHandle {
+        self
+    }
+}
+
+impl RenderTarget for RenderTargetHandle {
+    fn feedback_texture(&self) -> &FeedbackTextureHandle {
+        &self.target
+    }
+}
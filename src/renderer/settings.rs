@@ -0,0 +1,152 @@
+//! Player graphics settings, persisted in a `renderer.toml` next to the game
+//! (see [`Game::settings_path`](crate::game::Game::settings_path)):
+//!
+//! ```toml
+//! msaa = 4
+//! render_scale = 0.75
+//! vsync = false
+//! gpu = "radeon"
+//! editor = true
+//! ```
+//!
+//! Every setting is optional. [`Game::run`](crate::game::Game::run) reads the
+//! file before creating the renderer; a launch option beats the file, which
+//! beats the game's own overrides, which beat the device profile. The
+//! exceptions are `msaa`, a cap that can only lower the game's own cap, never
+//! raise it, and `editor`, which can't show the editor in a release build.
+//! The editor's Settings window writes the file back, for the next launch.
+
+use std::path::Path;
+
+use anyhow::Context;
+use ash::vk;
+use serde::{Deserialize, Serialize};
+
+use super::device_profile::min_msaa_samples;
+use crate::game::MaxMSAASamples;
+
+pub const DEFAULT_SETTINGS_PATH: &str = "renderer.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RendererSettings {
+    /// the most MSAA samples to use: 1, 2, 4, or 8;
+    /// the game and device profile may use fewer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msaa: Option<u32>,
+    /// 0.25 to 1.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub render_scale: Option<f32>,
+    /// FIFO when on; mailbox, where the surface supports it, when off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vsync: Option<bool>,
+    /// part of the device name, like `--gpu`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu: Option<String>,
+    /// whether to show the editor overlay in debug builds with the `editor`
+    /// feature, where it's shown by default; release builds never show it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<bool>,
+}
+
+impl RendererSettings {
+    /// Reads the settings at `path`; the defaults when there's no file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(error) => {
+                return Err(error).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        Self::parse(&text).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let settings: Self = toml::from_str(text)?;
+        if let Some(msaa) = settings.msaa
+            && !matches!(msaa, 1 | 2 | 4 | 8)
+        {
+            anyhow::bail!("msaa must be 1, 2, 4, or 8, not {msaa}");
+        }
+        if let Some(scale) = settings.render_scale
+            && !(0.25..=1.0).contains(&scale)
+        {
+            anyhow::bail!("render_scale must be between 0.25 and 1.0, not {scale}");
+        }
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let text = toml::to_string(self)?;
+        std::fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// the game's MSAA cap, lowered to the player's
+    pub fn max_msaa_samples(&self, game: MaxMSAASamples) -> MaxMSAASamples {
+        let player = match self.msaa {
+            None => return game,
+            Some(1) => MaxMSAASamples::Max1,
+            Some(2) => MaxMSAASamples::Max2,
+            Some(4) => MaxMSAASamples::Max4,
+            Some(_) => MaxMSAASamples::Max8,
+        };
+        min_msaa_samples(game, player)
+    }
+
+    pub fn present_mode(&self) -> Option<vk::PresentModeKHR> {
+        self.vsync.map(|vsync| {
+            if vsync {
+                vk::PresentModeKHR::FIFO
+            } else {
+                vk::PresentModeKHR::MAILBOX
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_through_toml() {
+        assert_eq!(
+            RendererSettings::parse("").unwrap(),
+            RendererSettings::default()
+        );
+        assert_eq!(
+            RendererSettings::default().max_msaa_samples(MaxMSAASamples::Max4),
+            MaxMSAASamples::Max4
+        );
+
+        let settings = RendererSettings::parse(
+            "msaa = 2\nrender_scale = 0.5\nvsync = false\ngpu = \"radeon\"\n",
+        )
+        .unwrap();
+        assert_eq!(settings.render_scale, Some(0.5));
+        assert_eq!(settings.editor, None);
+        assert_eq!(settings.present_mode(), Some(vk::PresentModeKHR::MAILBOX));
+        assert_eq!(
+            settings.max_msaa_samples(MaxMSAASamples::Max8),
+            MaxMSAASamples::Max2
+        );
+        assert_eq!(
+            settings.max_msaa_samples(MaxMSAASamples::Max1),
+            MaxMSAASamples::Max1
+        );
+
+        let text = toml::to_string(&settings).unwrap();
+        assert!(!text.contains("editor"));
+        assert_eq!(RendererSettings::parse(&text).unwrap(), settings);
+    }
+
+    #[test]
+    fn out_of_range_settings_are_errors() {
+        assert!(RendererSettings::parse("msaa = 3").is_err());
+        assert!(RendererSettings::parse("render_scale = 1.5").is_err());
+        assert!(RendererSettings::parse("vsync = \"yes\"").is_err());
+    }
+}
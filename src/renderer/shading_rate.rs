@@ -0,0 +1,205 @@
+//! Variable rate shading, with VK_KHR_fragment_shading_rate: running a
+//! pipeline's fragment shader once per 2x2 or 4x4 block of pixels instead of
+//! once per pixel, for expensive shaders where the detail won't be missed.
+//!
+//! Enable it with [`DeviceExtensions::with_fragment_shading_rate`](super::DeviceExtensions::with_fragment_shading_rate),
+//! then give a pipeline a [`ShadingRate`] with
+//! [`PipelineConfig::with_shading_rate`](super::PipelineConfig::with_shading_rate).
+//! Rates are dynamic state, set before each of the pipeline's draws; an
+//! [`ShadingRate::Edges`] draw is recorded once per region, scissored to it,
+//! which suits the fullscreen triangles of raymarched scenes.
+//!
+//! Drivers clamp rates they don't support with the pass's sample count down to
+//! ones they do, so a 4x4 rate may shade 2x2 blocks under MSAA.
+
+use ash::vk;
+
+use super::ClipRect;
+
+/// The size of the block of pixels each fragment shader invocation covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FragmentSize {
+    #[default]
+    X1x1,
+    X1x2,
+    X2x1,
+    X2x2,
+    X2x4,
+    X4x2,
+    X4x4,
+}
+
+impl FragmentSize {
+    pub const ALL: [Self; 7] = [
+        Self::X1x1,
+        Self::X1x2,
+        Self::X2x1,
+        Self::X2x2,
+        Self::X2x4,
+        Self::X4x2,
+        Self::X4x4,
+    ];
+
+    /// width by height, in pixels
+    pub fn extent(self) -> vk::Extent2D {
+        let (width, height) = match self {
+            Self::X1x1 => (1, 1),
+            Self::X1x2 => (1, 2),
+            Self::X2x1 => (2, 1),
+            Self::X2x2 => (2, 2),
+            Self::X2x4 => (2, 4),
+            Self::X4x2 => (4, 2),
+            Self::X4x4 => (4, 4),
+        };
+        vk::Extent2D { width, height }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::X1x1 => "1x1",
+            Self::X1x2 => "1x2",
+            Self::X2x1 => "2x1",
+            Self::X2x2 => "2x2",
+            Self::X2x4 => "2x4",
+            Self::X4x2 => "4x2",
+            Self::X4x4 => "4x4",
+        }
+    }
+}
+
+/// How often a pipeline's fragment shader runs, across the area it draws to
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ShadingRate {
+    /// once per pixel, as without variable rate shading
+    #[default]
+    Full,
+    /// the same fragment size everywhere
+    Uniform(FragmentSize),
+    /// full rate in a centered rectangle, `center` (0 to 1) of the area's
+    /// width and height, and `edges` around it
+    Edges { edges: FragmentSize, center: f32 },
+}
+
+impl ShadingRate {
+    pub fn is_variable(self) -> bool {
+        self != Self::Full
+    }
+
+    /// The fragment size for each part of `area`; the parts don't overlap,
+    /// and together cover it
+    pub fn regions(self, area: ClipRect) -> Vec<(ClipRect, FragmentSize)> {
+        let (edges, center) = match self {
+            Self::Full => return vec![(area, FragmentSize::X1x1)],
+            Self::Uniform(size) => return vec![(area, size)],
+            Self::Edges { edges, center } => (edges, center.clamp(0.0, 1.0)),
+        };
+
+        let inner_width = (area.width as f32 * center).round() as u32;
+        let inner_height = (area.height as f32 * center).round() as u32;
+        let inner = ClipRect::new(
+            area.x + (area.width - inner_width) / 2,
+            area.y + (area.height - inner_height) / 2,
+            inner_width,
+            inner_height,
+        );
+        let area_bottom = area.y + area.height;
+        let inner_bottom = inner.y + inner.height;
+        let inner_right = inner.x + inner.width;
+
+        let top = ClipRect::new(area.x, area.y, area.width, inner.y - area.y);
+        let bottom = ClipRect::new(area.x, inner_bottom, area.width, area_bottom - inner_bottom);
+        let left = ClipRect::new(area.x, inner.y, inner.x - area.x, inner.height);
+        let right = ClipRect::new(
+            inner_right,
+            inner.y,
+            area.x + area.width - inner_right,
+            inner.height,
+        );
+
+        [
+            (inner, FragmentSize::X1x1),
+            (top, edges),
+            (bottom, edges),
+            (left, edges),
+            (right, edges),
+        ]
+        .into_iter()
+        .filter(|(region, _)| region.width > 0 && region.height > 0)
+        .collect()
+    }
+}
+
+/// Sets the fragment size for the next draws of a pipeline with dynamic
+/// shading rate; the pipeline's rate is replaced, and primitive and
+/// attachment rates are ignored
+pub(super) fn cmd_set_fragment_size(
+    device_ext: &ash::khr::fragment_shading_rate::Device,
+    command_buffer: vk::CommandBuffer,
+    size: FragmentSize,
+) {
+    let combiner_ops = [vk::FragmentShadingRateCombinerOpKHR::KEEP; 2];
+    unsafe {
+        device_ext.cmd_set_fragment_shading_rate(command_buffer, &size.extent(), &combiner_ops);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area_of(regions: &[(ClipRect, FragmentSize)]) -> u32 {
+        regions
+            .iter()
+            .map(|(region, _)| region.width * region.height)
+            .sum()
+    }
+
+    #[test]
+    fn edges_surround_a_full_rate_center() {
+        let area = ClipRect::new(10, 20, 100, 60);
+        let rate = ShadingRate::Edges {
+            edges: FragmentSize::X4x4,
+            center: 0.5,
+        };
+
+        let regions = rate.regions(area);
+        assert_eq!(regions.len(), 5);
+        assert_eq!(
+            regions[0],
+            (ClipRect::new(35, 35, 50, 30), FragmentSize::X1x1)
+        );
+        assert!(
+            regions[1..]
+                .iter()
+                .all(|&(_, size)| size == FragmentSize::X4x4)
+        );
+        assert_eq!(area_of(&regions), 100 * 60);
+        for (i, (a, _)) in regions.iter().enumerate() {
+            for (b, _) in &regions[i + 1..] {
+                let overlap = a.intersect(*b);
+                assert!(overlap.width == 0 || overlap.height == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_regions_are_dropped() {
+        let area = ClipRect::new(0, 0, 64, 64);
+        let whole = ShadingRate::Edges {
+            edges: FragmentSize::X2x2,
+            center: 1.0,
+        };
+        assert_eq!(whole.regions(area), [(area, FragmentSize::X1x1)]);
+
+        let none = ShadingRate::Edges {
+            edges: FragmentSize::X2x2,
+            center: 0.0,
+        };
+        let regions = none.regions(area);
+        assert_eq!(area_of(&regions), 64 * 64);
+        assert!(regions.iter().all(|&(_, size)| size == FragmentSize::X2x2));
+
+        let uniform = ShadingRate::Uniform(FragmentSize::X2x1);
+        assert_eq!(uniform.regions(area), [(area, FragmentSize::X2x1)]);
+    }
+}
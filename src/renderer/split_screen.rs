@@ -0,0 +1,263 @@
+//! Split-screen views for the main pass.
+//!
+//! Pipelines created with
+//! [`PipelineConfig::with_split_screen`](super::PipelineConfig::with_split_screen)
+//! are drawn once per camera set with
+//! [`Renderer::set_split_screen`](super::Renderer::set_split_screen), each time
+//! into that camera's rect. The view index is passed as the draw's base
+//! instance, so shaders read it as `SV_StartInstanceLocation`.
+//!
+//! The cameras reach shaders the way frame globals do: a shader declaring a
+//! `ParameterBlock<SplitScreenViews>` (from shaders/lib/split_screen.slang)
+//! has its generated `pipeline_config` bind
+//! [`RawUniformBufferHandle::split_screen_views`], and the renderer swaps in
+//! its buffer at pipeline creation and writes it before each frame's `gpu_update`.
+//! Other main pass pipelines (a HUD, say) still draw once, over the whole target.
+
+use ash::vk;
+use glam::{Mat4, Vec2};
+use serde::Serialize;
+
+use super::gpu_write::GPUWrite;
+use super::{RawUniformBufferHandle, UniformBufferHandle, UniformElementType};
+
+/// Enough for four-player couch co-op
+pub const MAX_SPLIT_VIEWS: usize = 4;
+
+/// One player's camera, and where on the render target it draws
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitView {
+    pub rect: ViewportRect,
+    pub view: Mat4,
+    /// before the shader-side Y flip, eg from `Mat4::perspective_rh` with
+    /// [`ViewportRect::aspect_ratio`]
+    pub projection: Mat4,
+}
+
+/// `SplitScreenViews` in shaders/lib/split_screen.slang, laid out to match its std140 reflection
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[repr(C, align(16))]
+pub struct SplitScreenViews {
+    pub views: [Mat4; MAX_SPLIT_VIEWS],
+    pub projections: [Mat4; MAX_SPLIT_VIEWS],
+    /// the views set this frame
+    pub view_count: u32,
+    pub _padding_0: [u8; 12],
+}
+
+impl GPUWrite for SplitScreenViews {}
+const _: () = assert!(std::mem::size_of::<SplitScreenViews>() == 528);
+
+impl SplitScreenViews {
+    /// Unset views are the identity, so view 0 draws untransformed
+    /// positions when no cameras are set
+    pub(super) fn from_views(split_views: &[SplitView]) -> Self {
+        let mut views = [Mat4::IDENTITY; MAX_SPLIT_VIEWS];
+        let mut projections = [Mat4::IDENTITY; MAX_SPLIT_VIEWS];
+        for (index, split_view) in split_views.iter().take(MAX_SPLIT_VIEWS).enumerate() {
+            views[index] = split_view.view;
+            projections[index] = split_view.projection;
+        }
+
+        Self {
+            views,
+            projections,
+            view_count: split_views.len().min(MAX_SPLIT_VIEWS) as u32,
+            _padding_0: [0; 12],
+        }
+    }
+}
+
+/// the slang struct generated code recognizes as the renderer's
+pub const SPLIT_SCREEN_VIEWS_TYPE_NAME: &str = "SplitScreenViews";
+
+/// stands in for the renderer's buffer until a pipeline is created;
+/// one below frame globals' placeholder
+const PLACEHOLDER_INDEX: usize = usize::MAX - 1;
+
+impl RawUniformBufferHandle {
+    /// The renderer's split-screen views buffer, for generated pipeline configs;
+    /// resolved to the real buffer when the pipeline is created
+    pub fn split_screen_views() -> Self {
+        Self::from_parts(
+            PLACEHOLDER_INDEX,
+            UniformElementType::of::<SplitScreenViews>(),
+        )
+    }
+
+    pub(super) fn is_split_screen_views(&self) -> bool {
+        self.index() == PLACEHOLDER_INDEX
+    }
+}
+
+/// The cameras set with `Renderer::set_split_screen`, and the buffer shaders
+/// read them from, created by the first pipeline that binds it
+#[derive(Default)]
+pub(super) struct SplitScreenState {
+    pub(super) views: Vec<SplitView>,
+    pub(super) buffer: Option<UniformBufferHandle<SplitScreenViews>>,
+}
+
+/// Points any placeholder handles at the renderer's buffer
+pub(super) fn resolve_placeholders(
+    handles: &mut [RawUniformBufferHandle],
+    buffer: &UniformBufferHandle<SplitScreenViews>,
+) {
+    for handle in handles {
+        if handle.is_split_screen_views() {
+            *handle = RawUniformBufferHandle::from_typed(buffer);
+        }
+    }
+}
+
+/// A view's area, as fractions of the render target with the origin at the top left
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ViewportRect {
+    pub const FULL: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The usual layouts for `count` players: side by side for two; one on
+    /// top and two below for three; and quarters for four.
+    /// Counts past [`MAX_SPLIT_VIEWS`] are clamped.
+    pub fn split(count: usize) -> Vec<Self> {
+        match count.min(MAX_SPLIT_VIEWS) {
+            0 => vec![],
+            1 => vec![Self::FULL],
+            2 => vec![Self::new(0.0, 0.0, 0.5, 1.0), Self::new(0.5, 0.0, 0.5, 1.0)],
+            3 => vec![
+                Self::new(0.0, 0.0, 1.0, 0.5),
+                Self::new(0.0, 0.5, 0.5, 0.5),
+                Self::new(0.5, 0.5, 0.5, 0.5),
+            ],
+            _ => vec![
+                Self::new(0.0, 0.0, 0.5, 0.5),
+                Self::new(0.5, 0.0, 0.5, 0.5),
+                Self::new(0.0, 0.5, 0.5, 0.5),
+                Self::new(0.5, 0.5, 0.5, 0.5),
+            ],
+        }
+    }
+
+    /// The view's width over its height, for its projection
+    pub fn aspect_ratio(&self, resolution: Vec2) -> f32 {
+        (self.width * resolution.x) / (self.height * resolution.y)
+    }
+
+    /// The rect in pixels; edges are rounded, so adjacent views share them exactly
+    pub(super) fn to_pixels(self, extent: vk::Extent2D) -> vk::Rect2D {
+        let (width, height) = (extent.width as f32, extent.height as f32);
+        let x0 = (self.x * width).round().clamp(0.0, width) as u32;
+        let y0 = (self.y * height).round().clamp(0.0, height) as u32;
+        let x1 = ((self.x + self.width) * width).round().clamp(0.0, width) as u32;
+        let y1 = ((self.y + self.height) * height).round().clamp(0.0, height) as u32;
+
+        vk::Rect2D {
+            offset: vk::Offset2D {
+                x: x0 as i32,
+                y: y0 as i32,
+            },
+            extent: vk::Extent2D {
+                width: x1.saturating_sub(x0),
+                height: y1.saturating_sub(y0),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_layouts_cover_the_target() {
+        for count in 1..=MAX_SPLIT_VIEWS {
+            let rects = ViewportRect::split(count);
+            assert_eq!(rects.len(), count);
+            let area: f32 = rects.iter().map(|rect| rect.width * rect.height).sum();
+            assert_eq!(area, 1.0);
+        }
+        assert_eq!(ViewportRect::split(9).len(), MAX_SPLIT_VIEWS);
+    }
+
+    #[test]
+    fn adjacent_views_share_pixel_edges() {
+        // odd sizes round the middle edge once, for both views
+        let extent = vk::Extent2D {
+            width: 801,
+            height: 601,
+        };
+        let [left, right] = ViewportRect::split(2)
+            .into_iter()
+            .map(|rect| rect.to_pixels(extent))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        assert_eq!(left.offset.x, 0);
+        assert_eq!(left.offset.x + left.extent.width as i32, right.offset.x);
+        assert_eq!(right.offset.x + right.extent.width as i32, 801);
+        assert_eq!(left.extent.height, 601);
+    }
+
+    #[test]
+    fn unset_views_are_the_identity() {
+        let view = Mat4::from_translation(glam::Vec3::new(0.0, 0.0, -5.0));
+        let projection = Mat4::perspective_rh(1.0, 0.5, 0.1, 100.0);
+        let split_views: Vec<_> = ViewportRect::split(2)
+            .into_iter()
+            .map(|rect| SplitView {
+                rect,
+                view,
+                projection,
+            })
+            .collect();
+
+        let shader_views = SplitScreenViews::from_views(&split_views);
+        assert_eq!(shader_views.view_count, 2);
+        assert_eq!(shader_views.views[1], view);
+        assert_eq!(shader_views.projections[1], projection);
+        assert_eq!(shader_views.views[2], Mat4::IDENTITY);
+        assert_eq!(SplitScreenViews::from_views(&[]).view_count, 0);
+    }
+
+    #[test]
+    fn placeholders_resolve_to_the_renderer_buffer() {
+        let buffer = UniformBufferHandle::<SplitScreenViews>::from_raw(3);
+        let mut handles = [
+            RawUniformBufferHandle::frame_globals(),
+            RawUniformBufferHandle::split_screen_views(),
+        ];
+        assert!(!handles[0].is_split_screen_views());
+
+        resolve_placeholders(&mut handles, &buffer);
+        assert!(handles[0].is_frame_globals());
+        assert_eq!(handles[1].index(), 3);
+    }
+
+    #[test]
+    fn aspect_ratios_follow_the_rect() {
+        let resolution = Vec2::new(1600.0, 900.0);
+        let [left, _] = ViewportRect::split(2).try_into().unwrap();
+        assert_eq!(left.aspect_ratio(resolution), 800.0 / 900.0);
+    }
+}
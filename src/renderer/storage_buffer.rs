@@ -4,6 +4,8 @@ use std::marker::PhantomData;
 use ash::vk;
 
 use super::PRE_WAIT_RING_LEN;
+#[cfg(debug_assertions)]
+use super::uniform_buffer::{SlotWrites, take_unwritten_warning};
 
 #[derive(Debug)]
 pub struct StorageBufferHandle<T> {
@@ -14,9 +16,27 @@ pub struct StorageBufferHandle<T> {
 
 #[expect(clippy::len_without_is_empty)] // vulkan does not allow allocating an empty buffer
 impl<T> StorageBufferHandle<T> {
+    /// a handle to nothing allocated, for the null renderer
+    pub(super) fn from_raw(index: usize, len: u32) -> Self {
+        Self {
+            index,
+            len,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+
     pub fn len(&self) -> u32 {
         self.len
     }
+
+    /// the number of elements the buffer was allocated for
+    pub fn capacity(&self) -> u32 {
+        self.len
+    }
 }
 
 /// A storage buffer that nothing on the GPU ever writes
@@ -33,9 +53,18 @@ pub struct ImmutableBufferHandle<T> {
 
 #[expect(clippy::len_without_is_empty)] // vulkan does not allow allocating an empty buffer
 impl<T> ImmutableBufferHandle<T> {
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+
     pub fn len(&self) -> u32 {
         self.len
     }
+
+    /// the number of elements the buffer was allocated for
+    pub fn capacity(&self) -> u32 {
+        self.len
+    }
 }
 
 /// A storage buffer the CPU writes only at setup, never from `gpu_update`
@@ -56,9 +85,51 @@ pub struct GpuOnlyBufferHandle<T> {
 
 #[expect(clippy::len_without_is_empty)] // vulkan does not allow allocating an empty buffer
 impl<T> GpuOnlyBufferHandle<T> {
+    /// a handle to nothing allocated, for the null renderer
+    pub(super) fn from_raw(index: usize, len: u32) -> Self {
+        Self {
+            index,
+            len,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+
     pub fn len(&self) -> u32 {
         self.len
     }
+
+    /// the number of elements the buffer was allocated for
+    pub fn capacity(&self) -> u32 {
+        self.len
+    }
+}
+
+/// An untyped storage buffer, bound by descriptor to a shader's
+/// `ByteAddressBuffer` or `RWByteAddressBuffer` parameter
+///
+/// For variable-layout data with no fixed Rust struct (e.g. packed particle
+/// streams); shaders `Load`/`Store` it by byte offset. Like the typed handles,
+/// there is one buffer per ring slot, so a `RWByteAddressBuffer` write lands in
+/// the current frame's copy only.
+#[derive(Debug)]
+pub struct RawBufferHandle {
+    index: usize,
+    size: u32,
+}
+
+impl RawBufferHandle {
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+
+    /// the buffer's size in bytes
+    pub fn size(&self) -> u32 {
+        self.size
+    }
 }
 
 pub(super) struct RawStorageBuffer {
@@ -68,6 +139,8 @@ pub(super) struct RawStorageBuffer {
     pub(super) mapped_mem: *mut c_void,
     /// cached at creation; stable for the buffer's whole life
     pub(super) device_address: vk::DeviceAddress,
+    #[cfg(debug_assertions)]
+    pub(super) writes: SlotWrites,
 }
 
 // NOTE renderer has to enforce type safety
@@ -109,9 +182,23 @@ impl StorageBufferStorage {
         frame: usize,
     ) -> *mut T {
         let raw_storage_buffer = &mut self.0[handle.index].as_mut().unwrap()[frame];
+        #[cfg(debug_assertions)]
+        {
+            raw_storage_buffer.writes = SlotWrites::Written;
+        }
         raw_storage_buffer.mapped_mem as *mut T
     }
 
+    pub(super) fn read_for_frame<T: Clone>(
+        &self,
+        handle: &StorageBufferHandle<T>,
+        frame: usize,
+    ) -> Vec<T> {
+        let raw_storage_buffer = &self.0[handle.index].as_ref().unwrap()[frame];
+        let ptr = raw_storage_buffer.mapped_mem as *const T;
+        unsafe { std::slice::from_raw_parts(ptr, handle.len as usize) }.to_vec()
+    }
+
     pub fn take<T>(
         &mut self,
         handle: StorageBufferHandle<T>,
@@ -152,6 +239,10 @@ impl StorageBufferStorage {
         frame: usize,
     ) -> *mut T {
         let raw_storage_buffer = &mut self.0[handle.index].as_mut().unwrap()[frame];
+        #[cfg(debug_assertions)]
+        {
+            raw_storage_buffer.writes = SlotWrites::Written;
+        }
         raw_storage_buffer.mapped_mem as *mut T
     }
 
@@ -198,6 +289,11 @@ impl StorageBufferStorage {
         raw_storage_buffer.mapped_mem as *mut T
     }
 
+    /// Any storage buffer's vk buffer for a frame, by handle index
+    pub(super) fn raw_buffer(&self, index: usize, frame: usize) -> vk::Buffer {
+        self.0[index].as_ref().unwrap()[frame].buffer
+    }
+
     pub fn take_gpu_only<T>(
         &mut self,
         handle: GpuOnlyBufferHandle<T>,
@@ -205,6 +301,79 @@ impl StorageBufferStorage {
         self.0[handle.index].take().unwrap()
     }
 
+    // Raw buffers share this storage too; they're bound by descriptor rather
+    // than addressed, and written as bytes.
+
+    pub fn add_raw(
+        &mut self,
+        buffers_per_frame: [RawStorageBuffer; PRE_WAIT_RING_LEN],
+        size: u32,
+    ) -> RawBufferHandle {
+        let handle = RawBufferHandle {
+            index: self.0.len(),
+            size,
+        };
+
+        self.0.push(Some(buffers_per_frame));
+
+        handle
+    }
+
+    pub(super) fn get_mapped_mem_for_frame_raw(
+        &mut self,
+        handle: &mut RawBufferHandle,
+        frame: usize,
+    ) -> *mut u8 {
+        let raw_storage_buffer = &mut self.0[handle.index].as_mut().unwrap()[frame];
+        #[cfg(debug_assertions)]
+        {
+            raw_storage_buffer.writes = SlotWrites::Written;
+        }
+        raw_storage_buffer.mapped_mem as *mut u8
+    }
+
+    /// a raw buffer's vk buffers, in frame order
+    pub(super) fn raw_buffers_per_frame(
+        &self,
+        handle: &RawBufferHandle,
+    ) -> [vk::Buffer; PRE_WAIT_RING_LEN] {
+        let buffers = self.0[handle.index].as_ref().unwrap();
+        std::array::from_fn(|frame| buffers[frame].buffer)
+    }
+
+    pub fn take_raw(&mut self, handle: RawBufferHandle) -> [RawStorageBuffer; PRE_WAIT_RING_LEN] {
+        self.0[handle.index].take().unwrap()
+    }
+
+    /// Debug builds only: true the first time a frame addresses this buffer
+    /// while the given frame's slot has never been written.
+    /// GPU-only buffers are written by shaders, so they aren't checked.
+    #[cfg(debug_assertions)]
+    pub(super) fn take_unwritten_warning(&mut self, index: usize, frame: usize) -> bool {
+        match self.0[index].as_mut() {
+            Some(buffers) => {
+                take_unwritten_warning(buffers.iter_mut().map(|buffer| &mut buffer.writes), frame)
+            }
+            None => false,
+        }
+    }
+
+    /// each live buffer's copy for a frame, with its handle index
+    pub(super) fn iter_for_frame(
+        &self,
+        frame: usize,
+    ) -> impl Iterator<Item = (usize, &RawStorageBuffer)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, buffers)| Some((index, &buffers.as_ref()?[frame])))
+    }
+
+    /// the number of buffers not yet dropped
+    pub fn live_count(&self) -> usize {
+        self.0.iter().flatten().count()
+    }
+
     pub fn take_all(&mut self) -> Vec<[RawStorageBuffer; PRE_WAIT_RING_LEN]> {
         self.0
             .iter_mut()
@@ -0,0 +1,531 @@
+//! Acquiring and presenting swapchain images, behind a trait so the frame
+//! loop's bookkeeping can be tested without a device.
+//!
+//! A frame acquires an image with its ring slot's semaphore, draws, then
+//! [`end_frame`] advances the slots and presents. The device calls themselves
+//! are [`acquire_next_image`] and [`queue_present`], which the tests drive
+//! through a mock device's function pointers. Either end can find the
+//! swapchain out of date (after a resize, usually), which the renderer
+//! handles by recreating it; [`FrameSlots`] has to stay consistent through
+//! every one of those early returns.
+
+use ash::vk;
+
+use super::{MAX_FRAMES_IN_FLIGHT, PRE_WAIT_RING_LEN};
+
+/// The looping indices of the frame being drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FrameSlots {
+    /// index for wait-guarded per-flight resources:
+    ///   command buffers (graphics + compute), post-process targets,
+    ///   picking images, readbacks, egui texture frees, texture update staging
+    /// (0..MAX_FRAMES_IN_FLIGHT)
+    pub flight: usize,
+    /// index for the pre-wait ring:
+    ///   per-frame buffers, acquire semaphores, resolve images, and the
+    ///   descriptor sets that reference each slot's buffers
+    /// (0..PRE_WAIT_RING_LEN)
+    pub ring: usize,
+}
+
+impl FrameSlots {
+    /// moves on to the next frame's slots, once this frame has been submitted
+    pub fn advance(&mut self) {
+        self.flight = (self.flight + 1) % MAX_FRAMES_IN_FLIGHT;
+        self.ring = (self.ring + 1) % PRE_WAIT_RING_LEN;
+    }
+}
+
+/// The result of acquiring an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Acquire {
+    /// An image to draw to; a suboptimal swapchain can still be presented,
+    /// but should be recreated afterwards
+    Image { index: u32, suboptimal: bool },
+    /// Nothing was acquired (and no semaphore signaled);
+    /// the swapchain must be recreated before drawing
+    OutOfDate,
+}
+
+/// The result of presenting an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Present {
+    Optimal,
+    /// suboptimal or out of date; either way, recreate the swapchain
+    Stale,
+}
+
+/// What the renderer does once a frame has been presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FrameEnd {
+    Presented,
+    RecreateSwapchain,
+}
+
+/// The device calls a frame makes on its swapchain,
+/// implemented by [`WindowPresenter`](super::WindowPresenter)
+pub(super) trait Swapchain {
+    /// Acquires the next image, signaling the ring slot's image available
+    /// semaphore once it's ready. May block on vsync.
+    fn acquire(&mut self, ring_slot: usize) -> Result<Acquire, vk::Result>;
+
+    /// Queues the image for presentation once its render finished semaphore is signaled
+    fn present(&mut self, image_index: u32) -> Result<Present, vk::Result>;
+}
+
+/// vkAcquireNextImageKHR, signaling `image_available` once the image is ready
+pub(super) fn acquire_next_image(
+    swapchain_device_ext: &ash::khr::swapchain::Device,
+    swapchain: vk::SwapchainKHR,
+    image_available: vk::Semaphore,
+) -> Result<Acquire, vk::Result> {
+    let acquired = unsafe {
+        swapchain_device_ext.acquire_next_image(
+            swapchain,
+            u64::MAX,
+            image_available,
+            vk::Fence::null(),
+        )
+    };
+
+    match acquired {
+        Ok((index, suboptimal)) => Ok(Acquire::Image { index, suboptimal }),
+        Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(Acquire::OutOfDate),
+        Err(other_error) => Err(other_error),
+    }
+}
+
+/// vkQueuePresentKHR, waiting on the image's `render_finished` semaphore.
+/// `present_id` is passed along when the device supports waiting on presents.
+pub(super) fn queue_present(
+    swapchain_device_ext: &ash::khr::swapchain::Device,
+    presentation_queue: vk::Queue,
+    swapchain: vk::SwapchainKHR,
+    image_index: u32,
+    render_finished: vk::Semaphore,
+    present_id: Option<u64>,
+) -> Result<Present, vk::Result> {
+    let swapchains = [swapchain];
+    let image_indices = [image_index];
+    let render_finished = [render_finished];
+    let mut present_info = vk::PresentInfoKHR::default()
+        .wait_semaphores(&render_finished)
+        .swapchains(&swapchains)
+        .image_indices(&image_indices);
+
+    let present_ids = present_id.map(|id| [id]);
+    let mut present_id_info;
+    if let Some(present_ids) = &present_ids {
+        present_id_info = vk::PresentIdKHR::default().present_ids(present_ids);
+        present_info = present_info.push_next(&mut present_id_info);
+    }
+
+    let presented =
+        unsafe { swapchain_device_ext.queue_present(presentation_queue, &present_info) };
+
+    match presented {
+        Ok(false) => Ok(Present::Optimal),
+        // suboptimal (vk::Result::SUBOPTIMAL_KHR) or out of date
+        Ok(true) | Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(Present::Stale),
+        Err(other_error) => Err(other_error),
+    }
+}
+
+/// Advances the slots, then presents the frame's image.
+///
+/// The slots advance BEFORE present, so that if present asks for a recreate
+/// (an early return from the frame), the next frame won't reuse the ring
+/// slot whose semaphores are still signaled from this frame's submit.
+pub(super) fn end_frame(
+    swapchain: &mut impl Swapchain,
+    slots: &mut FrameSlots,
+    image_index: u32,
+    suboptimal_on_acquire: bool,
+) -> Result<FrameEnd, vk::Result> {
+    slots.advance();
+
+    match swapchain.present(image_index)? {
+        Present::Stale => Ok(FrameEnd::RecreateSwapchain),
+        Present::Optimal if suboptimal_on_acquire => Ok(FrameEnd::RecreateSwapchain),
+        Present::Optimal => Ok(FrameEnd::Presented),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::ffi::{CStr, c_char, c_void};
+
+    use ash::vk::Handle;
+
+    use super::*;
+
+    /// A device whose swapchain images cycle in order, which goes out of date
+    /// when the window is resized and until it's recreated
+    struct MockDevice {
+        image_count: u32,
+        next_image: u32,
+        extent: (u32, u32),
+        window_extent: (u32, u32),
+        /// results returned before falling back to the extent check, in order
+        scripted_acquires: VecDeque<vk::Result>,
+        scripted_presents: VecDeque<vk::Result>,
+        /// the semaphore of every acquire that returned an image
+        acquire_semaphores: Vec<vk::Semaphore>,
+        presented_images: Vec<u32>,
+        /// the semaphores each present waited on
+        present_waits: Vec<Vec<vk::Semaphore>>,
+        recreations: usize,
+    }
+
+    impl MockDevice {
+        fn new(image_count: u32) -> Self {
+            Self {
+                image_count,
+                next_image: 0,
+                extent: (800, 600),
+                window_extent: (800, 600),
+                scripted_acquires: VecDeque::new(),
+                scripted_presents: VecDeque::new(),
+                acquire_semaphores: vec![],
+                presented_images: vec![],
+                present_waits: vec![],
+                recreations: 0,
+            }
+        }
+    }
+
+    // the function pointers can't capture, so the mock lives per test thread
+    thread_local! {
+        static DEVICE: RefCell<MockDevice> = RefCell::new(MockDevice::new(0));
+    }
+
+    fn with_device<T>(f: impl FnOnce(&mut MockDevice) -> T) -> T {
+        DEVICE.with_borrow_mut(f)
+    }
+
+    unsafe extern "system" fn acquire_next_image_khr(
+        _device: vk::Device,
+        _swapchain: vk::SwapchainKHR,
+        _timeout: u64,
+        semaphore: vk::Semaphore,
+        _fence: vk::Fence,
+        p_image_index: *mut u32,
+    ) -> vk::Result {
+        with_device(|device| {
+            let result = match device.scripted_acquires.pop_front() {
+                Some(result) => result,
+                None if device.extent != device.window_extent => vk::Result::ERROR_OUT_OF_DATE_KHR,
+                None => vk::Result::SUCCESS,
+            };
+
+            if matches!(result, vk::Result::SUCCESS | vk::Result::SUBOPTIMAL_KHR) {
+                unsafe { *p_image_index = device.next_image };
+                device.next_image = (device.next_image + 1) % device.image_count;
+                device.acquire_semaphores.push(semaphore);
+            }
+
+            result
+        })
+    }
+
+    unsafe extern "system" fn queue_present_khr(
+        _queue: vk::Queue,
+        p_present_info: *const vk::PresentInfoKHR<'_>,
+    ) -> vk::Result {
+        let present_info = unsafe { &*p_present_info };
+        let (image_indices, wait_semaphores) = unsafe {
+            (
+                std::slice::from_raw_parts(
+                    present_info.p_image_indices,
+                    present_info.swapchain_count as usize,
+                ),
+                std::slice::from_raw_parts(
+                    present_info.p_wait_semaphores,
+                    present_info.wait_semaphore_count as usize,
+                ),
+            )
+        };
+
+        with_device(|device| {
+            device.presented_images.extend_from_slice(image_indices);
+            device.present_waits.push(wait_semaphores.to_vec());
+            match device.scripted_presents.pop_front() {
+                Some(result) => result,
+                None if device.extent != device.window_extent => vk::Result::ERROR_OUT_OF_DATE_KHR,
+                None => vk::Result::SUCCESS,
+            }
+        })
+    }
+
+    unsafe extern "system" fn get_device_proc_addr(
+        _device: vk::Device,
+        p_name: *const c_char,
+    ) -> vk::PFN_vkVoidFunction {
+        let name = unsafe { CStr::from_ptr(p_name) };
+        let function: *const c_void = match name.to_bytes() {
+            b"vkAcquireNextImageKHR" => acquire_next_image_khr as *const c_void,
+            b"vkQueuePresentKHR" => queue_present_khr as *const c_void,
+            _ => return None,
+        };
+
+        Some(unsafe { std::mem::transmute::<*const c_void, unsafe extern "system" fn()>(function) })
+    }
+
+    /// The renderer's side of the mock device: the same semaphores per ring
+    /// slot and per image as a WindowPresenter
+    struct MockSwapchain {
+        swapchain_device_ext: ash::khr::swapchain::Device,
+        image_available: [vk::Semaphore; PRE_WAIT_RING_LEN],
+        render_finished: Vec<vk::Semaphore>,
+    }
+
+    impl MockSwapchain {
+        fn new(image_count: u32) -> Self {
+            DEVICE.set(MockDevice::new(image_count));
+
+            // ash loads every other function as a panicking stub
+            let instance = unsafe {
+                ash::Instance::load_with(
+                    |name| match name.to_bytes() {
+                        b"vkGetDeviceProcAddr" => get_device_proc_addr as *const c_void,
+                        _ => std::ptr::null(),
+                    },
+                    vk::Instance::from_raw(1),
+                )
+            };
+            let device = unsafe {
+                ash::Device::load_with(|_name| std::ptr::null(), vk::Device::from_raw(1))
+            };
+
+            Self {
+                swapchain_device_ext: ash::khr::swapchain::Device::new(&instance, &device),
+                image_available: std::array::from_fn(|slot| {
+                    vk::Semaphore::from_raw(100 + slot as u64)
+                }),
+                render_finished: (0..image_count)
+                    .map(|image| vk::Semaphore::from_raw(200 + image as u64))
+                    .collect(),
+            }
+        }
+
+        fn resize(&mut self, width: u32, height: u32) {
+            with_device(|device| device.window_extent = (width, height));
+        }
+
+        fn recreate(&mut self) {
+            with_device(|device| {
+                device.extent = device.window_extent;
+                device.next_image = 0;
+                device.recreations += 1;
+            });
+        }
+
+        /// the ring slot of every acquire that returned an image
+        fn acquired_slots(&self) -> Vec<usize> {
+            with_device(|device| {
+                device
+                    .acquire_semaphores
+                    .iter()
+                    .map(|semaphore| {
+                        self.image_available
+                            .iter()
+                            .position(|available| available == semaphore)
+                            .unwrap()
+                    })
+                    .collect()
+            })
+        }
+    }
+
+    impl Swapchain for MockSwapchain {
+        fn acquire(&mut self, ring_slot: usize) -> Result<Acquire, vk::Result> {
+            acquire_next_image(
+                &self.swapchain_device_ext,
+                vk::SwapchainKHR::from_raw(1),
+                self.image_available[ring_slot],
+            )
+        }
+
+        fn present(&mut self, image_index: u32) -> Result<Present, vk::Result> {
+            queue_present(
+                &self.swapchain_device_ext,
+                vk::Queue::from_raw(1),
+                vk::SwapchainKHR::from_raw(1),
+                image_index,
+                self.render_finished[image_index as usize],
+                None,
+            )
+        }
+    }
+
+    /// the acquire and present steps of Renderer::draw_frame
+    fn draw_frame(swapchain: &mut MockSwapchain, slots: &mut FrameSlots) {
+        let (image_index, suboptimal) = match swapchain.acquire(slots.ring).unwrap() {
+            Acquire::Image { index, suboptimal } => (index, suboptimal),
+            Acquire::OutOfDate => {
+                swapchain.recreate();
+                return;
+            }
+        };
+
+        if end_frame(swapchain, slots, image_index, suboptimal).unwrap()
+            == FrameEnd::RecreateSwapchain
+        {
+            swapchain.recreate();
+        }
+    }
+
+    fn presented_images() -> Vec<u32> {
+        with_device(|device| device.presented_images.clone())
+    }
+
+    fn recreations() -> usize {
+        with_device(|device| device.recreations)
+    }
+
+    #[test]
+    fn slots_rotate_independently() {
+        let mut swapchain = MockSwapchain::new(3);
+        let mut slots = FrameSlots::default();
+
+        let mut flights = vec![];
+        for _ in 0..6 {
+            flights.push(slots.flight);
+            draw_frame(&mut swapchain, &mut slots);
+        }
+
+        assert_eq!(flights, [0, 1, 0, 1, 0, 1]);
+        assert_eq!(swapchain.acquired_slots(), [0, 1, 2, 0, 1, 2]);
+        assert_eq!(presented_images(), [0, 1, 2, 0, 1, 2]);
+        assert_eq!(recreations(), 0);
+    }
+
+    #[test]
+    fn presents_wait_on_their_image_semaphore() {
+        let mut swapchain = MockSwapchain::new(2);
+        let mut slots = FrameSlots::default();
+
+        for _ in 0..3 {
+            draw_frame(&mut swapchain, &mut slots);
+        }
+
+        let render_finished = &swapchain.render_finished;
+        let present_waits = with_device(|device| device.present_waits.clone());
+        assert_eq!(
+            present_waits,
+            [
+                vec![render_finished[0]],
+                vec![render_finished[1]],
+                vec![render_finished[0]],
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_date_acquire_skips_the_frame() {
+        let mut swapchain = MockSwapchain::new(3);
+        let mut slots = FrameSlots::default();
+        draw_frame(&mut swapchain, &mut slots);
+
+        swapchain.resize(1024, 768);
+        draw_frame(&mut swapchain, &mut slots);
+
+        // nothing was signaled, so the skipped frame's slots are reused
+        assert_eq!(recreations(), 1);
+        assert_eq!(slots, FrameSlots { flight: 1, ring: 1 });
+        assert_eq!(presented_images(), [0]);
+
+        draw_frame(&mut swapchain, &mut slots);
+        assert_eq!(with_device(|device| device.extent), (1024, 768));
+        assert_eq!(swapchain.acquired_slots(), [0, 1]);
+        assert_eq!(presented_images(), [0, 0]);
+    }
+
+    #[test]
+    fn stale_present_still_advances_the_slots() {
+        let mut swapchain = MockSwapchain::new(3);
+        with_device(|device| {
+            device
+                .scripted_presents
+                .push_back(vk::Result::ERROR_OUT_OF_DATE_KHR)
+        });
+        let mut slots = FrameSlots::default();
+
+        draw_frame(&mut swapchain, &mut slots);
+        assert_eq!(recreations(), 1);
+        assert_eq!(slots, FrameSlots { flight: 1, ring: 1 });
+
+        // the submitted frame's acquire semaphore isn't reused right away
+        draw_frame(&mut swapchain, &mut slots);
+        assert_eq!(swapchain.acquired_slots(), [0, 1]);
+    }
+
+    #[test]
+    fn suboptimal_present_recreates() {
+        let mut swapchain = MockSwapchain::new(3);
+        with_device(|device| {
+            device
+                .scripted_presents
+                .push_back(vk::Result::SUBOPTIMAL_KHR)
+        });
+        let mut slots = FrameSlots::default();
+
+        draw_frame(&mut swapchain, &mut slots);
+
+        assert_eq!(presented_images(), [0]);
+        assert_eq!(recreations(), 1);
+    }
+
+    #[test]
+    fn suboptimal_acquire_presents_before_recreating() {
+        let mut swapchain = MockSwapchain::new(3);
+        with_device(|device| {
+            device.next_image = 2;
+            device
+                .scripted_acquires
+                .push_back(vk::Result::SUBOPTIMAL_KHR);
+        });
+        let mut slots = FrameSlots::default();
+
+        draw_frame(&mut swapchain, &mut slots);
+
+        assert_eq!(presented_images(), [2]);
+        assert_eq!(recreations(), 1);
+        assert_eq!(slots, FrameSlots { flight: 1, ring: 1 });
+    }
+
+    #[test]
+    fn device_errors_are_returned() {
+        let mut swapchain = MockSwapchain::new(3);
+        with_device(|device| {
+            device
+                .scripted_acquires
+                .push_back(vk::Result::ERROR_DEVICE_LOST)
+        });
+
+        assert_eq!(swapchain.acquire(0), Err(vk::Result::ERROR_DEVICE_LOST));
+    }
+
+    #[test]
+    fn resize_between_acquire_and_present_recreates_once() {
+        let mut swapchain = MockSwapchain::new(2);
+        let mut slots = FrameSlots::default();
+
+        let Acquire::Image { index, suboptimal } = swapchain.acquire(slots.ring).unwrap() else {
+            panic!("expected an image");
+        };
+        swapchain.resize(640, 480);
+        let end = end_frame(&mut swapchain, &mut slots, index, suboptimal).unwrap();
+        assert_eq!(end, FrameEnd::RecreateSwapchain);
+        swapchain.recreate();
+
+        for _ in 0..4 {
+            draw_frame(&mut swapchain, &mut slots);
+        }
+        assert_eq!(recreations(), 1);
+        assert_eq!(swapchain.acquired_slots(), [0, 1, 2, 0, 1]);
+        assert_eq!(presented_images(), [0, 0, 1, 0, 1]);
+    }
+}
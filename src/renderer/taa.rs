@@ -0,0 +1,218 @@
+//! Temporal anti-aliasing: jitter the camera by a sub-pixel offset each frame,
+//! and blend each frame's image with the accumulated history.
+//!
+//! A game opts in with three pieces:
+//! 1. a [`Taa`] that jitters its projection each frame, whose [`TaaFrame`]
+//!    fills the `TaaMatrices` in its uniforms
+//! 2. a post-process pipeline that resolves the scene color against the
+//!    texture from `Renderer::create_taa_history_texture`, using
+//!    `taaResolve` from shaders/lib/taa.slang
+//! 3. nothing else: after the post-process pass the renderer copies its output
+//!    into the history, for the next frame's resolve
+//!
+//! Motion vectors are in uv space, from the previous frame's position to the
+//! current one (so history is sampled at `uv - motion`); see `taaMotion`.
+//! Games with `Game::velocity_buffer` write them from the main pass (see
+//! [`velocity`](super::velocity)), and resolve with `taaResolveWithVelocity`,
+//! which reprojects the history along the texture from
+//! `Renderer::create_velocity_texture`.
+
+use ash::vk;
+use glam::{Mat4, Vec2, Vec3};
+
+use super::{ImageOptions, create_image_view, create_vk_image};
+
+/// The length of the jitter sequence; enough to cover a pixel evenly
+/// without cycling so slowly that the history smears
+pub const TAA_JITTER_PHASES: u32 = 8;
+
+/// How much of the history each frame keeps, once it has any
+pub const TAA_HISTORY_WEIGHT: f32 = 0.9;
+
+/// The `index`th element of the Halton sequence in `base`, in [0, 1)
+pub fn halton(mut index: u32, base: u32) -> f32 {
+    let mut fraction = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+/// One frame's camera data for the jittered draw and the resolve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaaFrame {
+    /// the projection to draw the scene with
+    pub jittered_projection: Mat4,
+    /// the jitter in render pixels, each axis in [-0.5, 0.5)
+    pub jitter: Vec2,
+    /// the unjittered view-projections of this frame and the last, for motion vectors
+    pub view_projection: Mat4,
+    pub previous_view_projection: Mat4,
+    /// 0.0 on the first frame and after a reset, when the history is stale
+    pub history_weight: f32,
+}
+
+/// The jitter sequence and the previous frame's camera
+#[derive(Debug, Clone, Default)]
+pub struct Taa {
+    frame: u32,
+    previous_view_projection: Option<Mat4>,
+}
+
+impl Taa {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop the history's weight for a frame, after a camera cut or teleport
+    pub fn reset(&mut self) {
+        self.previous_view_projection = None;
+    }
+
+    /// Advance to the next jitter phase, for a camera drawn at `render_resolution`
+    pub fn next_frame(
+        &mut self,
+        view: Mat4,
+        projection: Mat4,
+        render_resolution: Vec2,
+    ) -> TaaFrame {
+        // skip index 0, which is 0.0 in every base
+        let phase = self.frame % TAA_JITTER_PHASES + 1;
+        self.frame = self.frame.wrapping_add(1);
+        let jitter = Vec2::new(halton(phase, 2), halton(phase, 3)) - 0.5;
+
+        // a pixel is 2 / resolution wide in clip space; translating after the
+        // projection scales the offset by w, so it's constant on screen
+        let clip_offset = jitter * 2.0 / render_resolution;
+        let jittered_projection =
+            Mat4::from_translation(Vec3::new(clip_offset.x, clip_offset.y, 0.0)) * projection;
+
+        let view_projection = projection * view;
+        let (previous_view_projection, history_weight) = match self.previous_view_projection {
+            Some(previous) => (previous, TAA_HISTORY_WEIGHT),
+            None => (view_projection, 0.0),
+        };
+        self.previous_view_projection = Some(view_projection);
+
+        TaaFrame {
+            jittered_projection,
+            jitter,
+            view_projection,
+            previous_view_projection,
+            history_weight,
+        }
+    }
+}
+
+/// The resolved color of the previous frame, at render resolution.
+/// Between frames it's left in SHADER_READ_ONLY_OPTIMAL.
+pub(super) struct TaaHistory {
+    pub image: vk::Image,
+    memory: vk_mem::Allocation,
+    /// cleared in the first frame's command buffer, before anything samples it
+    pub initialized: bool,
+}
+
+impl TaaHistory {
+    pub fn init(
+        allocator: &vk_mem::Allocator,
+        render_extent: vk::Extent2D,
+        format: vk::Format,
+    ) -> anyhow::Result<Self> {
+        let image_options = ImageOptions {
+            extent: render_extent,
+            format,
+            tiling: vk::ImageTiling::OPTIMAL,
+            usage: vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST,
+            mip_levels: 1,
+            array_layers: 1,
+            msaa_samples: vk::SampleCountFlags::TYPE_1,
+        };
+        let (image, memory) = create_vk_image(allocator, image_options)?;
+
+        Ok(Self {
+            image,
+            memory,
+            initialized: false,
+        })
+    }
+
+    /// A view for a history texture alias, which owns it
+    pub fn create_view(
+        &self,
+        device: &ash::Device,
+        format: vk::Format,
+    ) -> anyhow::Result<vk::ImageView> {
+        create_image_view(device, self.image, format, vk::ImageAspectFlags::COLOR, 1)
+    }
+
+    pub fn destroy(mut self, allocator: &vk_mem::Allocator) {
+        unsafe { allocator.destroy_image(self.image, &mut self.memory) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halton_fills_the_unit_interval() {
+        assert_eq!(halton(1, 2), 0.5);
+        assert_eq!(halton(2, 2), 0.25);
+        assert_eq!(halton(3, 2), 0.75);
+        assert_eq!(halton(1, 3), 1.0 / 3.0);
+        assert_eq!(halton(0, 3), 0.0);
+    }
+
+    #[test]
+    fn jitter_cycles_within_a_pixel() {
+        let resolution = Vec2::new(800.0, 600.0);
+        let mut taa = Taa::new();
+        let jitters: Vec<Vec2> = (0..TAA_JITTER_PHASES * 2)
+            .map(|_| {
+                taa.next_frame(Mat4::IDENTITY, Mat4::IDENTITY, resolution)
+                    .jitter
+            })
+            .collect();
+
+        for jitter in &jitters {
+            assert!(jitter.abs().max_element() <= 0.5);
+        }
+        let (first, second) = jitters.split_at(TAA_JITTER_PHASES as usize);
+        assert_eq!(first, second);
+        assert_ne!(first[0], first[1]);
+    }
+
+    #[test]
+    fn history_weight_waits_for_a_previous_frame() {
+        let resolution = Vec2::new(800.0, 600.0);
+        let view = Mat4::from_translation(Vec3::new(1.0, 0.0, 0.0));
+        let mut taa = Taa::new();
+
+        let first = taa.next_frame(Mat4::IDENTITY, Mat4::IDENTITY, resolution);
+        assert_eq!(first.history_weight, 0.0);
+        assert_eq!(first.previous_view_projection, first.view_projection);
+
+        let second = taa.next_frame(view, Mat4::IDENTITY, resolution);
+        assert_eq!(second.history_weight, TAA_HISTORY_WEIGHT);
+        assert_eq!(second.previous_view_projection, Mat4::IDENTITY);
+
+        taa.reset();
+        let after_reset = taa.next_frame(view, Mat4::IDENTITY, resolution);
+        assert_eq!(after_reset.history_weight, 0.0);
+    }
+
+    #[test]
+    fn jitter_moves_clip_space_by_the_pixel_offset() {
+        let resolution = Vec2::new(800.0, 600.0);
+        let mut taa = Taa::new();
+        let frame = taa.next_frame(Mat4::IDENTITY, Mat4::IDENTITY, resolution);
+
+        let clip = frame.jittered_projection * glam::Vec4::new(0.0, 0.0, 0.5, 2.0);
+        let expected = frame.jitter * 2.0 / resolution * 2.0;
+        assert!((clip.truncate().truncate() - expected).abs().max_element() < 1e-6);
+    }
+}
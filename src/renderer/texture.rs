@@ -8,6 +8,21 @@ pub struct TextureHandle {
     index: usize,
 }
 
+impl TextureHandle {
+    /// a handle to nothing allocated, for the null renderer
+    pub(super) fn from_raw(index: usize, _source_file_name: String) -> Self {
+        Self {
+            #[cfg(debug_assertions)]
+            source_file_name: _source_file_name,
+            index,
+        }
+    }
+
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+}
+
 pub(super) struct TextureStorage(Vec<Option<Texture>>);
 
 impl TextureStorage {
@@ -30,10 +45,32 @@ impl TextureStorage {
         self.0[handle.index].as_ref().unwrap()
     }
 
+    /// None if the texture has since been dropped
+    pub fn get_by_index(&self, index: usize) -> Option<&Texture> {
+        self.0.get(index).and_then(Option::as_ref)
+    }
+
+    /// None if the texture has since been dropped
+    pub fn get_mut_by_index(&mut self, index: usize) -> Option<&mut Texture> {
+        self.0.get_mut(index).and_then(Option::as_mut)
+    }
+
     pub fn take(&mut self, handle: TextureHandle) -> Texture {
         self.0[handle.index].take().unwrap()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Texture> {
+        self.0.iter().flatten()
+    }
+
+    /// the live textures with their indices
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, &Texture)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(index, texture)| Some((index, texture.as_ref()?)))
+    }
+
     pub fn take_all(&mut self) -> Vec<Texture> {
         self.0
             .iter_mut()
@@ -42,24 +79,62 @@ impl TextureStorage {
     }
 }
 
+/// A rectangle of texels within the top mip level of a texture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextureRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// whether the region is non-empty and fits within the given extent
+    pub(super) fn fits_within(&self, extent: vk::Extent2D) -> bool {
+        self.width > 0
+            && self.height > 0
+            && self
+                .x
+                .checked_add(self.width)
+                .is_some_and(|r| r <= extent.width)
+            && self
+                .y
+                .checked_add(self.height)
+                .is_some_and(|b| b <= extent.height)
+    }
+}
+
 /// Describes whether a texture owns its underlying image and device memory,
 /// or is a non-owning alias of an image owned by another resource (e.g. a StorageTexture).
 pub(super) enum ImageOwnership {
     /// This texture owns the image and memory, and must free them on destroy.
     Owned(vk_mem::Allocation),
+    /// This texture owns an image bound to memory imported from outside the
+    /// renderer (see [`super::external_memory`]). The image and memory must be
+    /// freed directly, since vk_mem doesn't know about them.
+    Imported(vk::DeviceMemory),
     /// This texture aliases an image owned by another resource. Only the view
     /// and sampler should be destroyed.
     Aliased,
 }
 
 pub(super) struct Texture {
-    #[cfg_attr(not(debug_assertions), expect(unused))]
     pub(super) source_file_name: String,
     pub(super) image: vk::Image,
     pub(super) image_ownership: ImageOwnership,
     pub(super) image_view: vk::ImageView,
     pub(super) sampler: vk::Sampler,
-    #[expect(unused)] // currently unused after init
+    pub(super) format: vk::Format,
+    pub(super) extent: vk::Extent2D,
     pub(super) mip_levels: u32,
     pub(super) image_layout: vk::ImageLayout,
 }
@@ -0,0 +1,396 @@
+//! The texture inspector: reads back one mip level and array layer of a
+//! texture or the depth buffer at the end of each frame, and decodes it for
+//! the editor to show with [`InspectorView`]'s channel isolation, depth
+//! linearization, and zoom.
+//!
+//! Only one readback is in flight at a time, so the image the inspector shows
+//! trails the game by a couple of frames. Block-compressed and other formats
+//! without a decoder here aren't listed.
+
+use ash::vk;
+
+use super::pixel_debug::decode_depth;
+use super::readback::Readback;
+
+/// What the inspector can read back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectedImage {
+    /// a texture, by its index in the renderer's texture storage
+    Texture(usize),
+    /// the main pass's depth buffer
+    Depth,
+}
+
+/// An image the inspector can show; see [`Renderer::inspectable_images`](super::Renderer::inspectable_images)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectableImage {
+    pub image: InspectedImage,
+    pub name: String,
+    pub format: vk::Format,
+    /// the top mip level's size
+    pub extent: [u32; 2],
+    pub mip_levels: u32,
+    pub layers: u32,
+}
+
+/// The subresource the inspector reads back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InspectorTarget {
+    pub image: InspectedImage,
+    pub mip_level: u32,
+    pub layer: u32,
+}
+
+/// A decoded readback of an [`InspectorTarget`]
+#[derive(Debug, Clone)]
+pub struct InspectorSnapshot {
+    pub target: InspectorTarget,
+    pub width: u32,
+    pub height: u32,
+    pub format: vk::Format,
+    /// rows top to bottom, as stored: sRGB texels stay encoded,
+    /// and missing channels are 0 (or 1 for alpha)
+    pub texels: Vec<[f32; 4]>,
+    /// counts up with every snapshot, to tell when to upload a new one
+    pub generation: u64,
+}
+
+impl InspectorSnapshot {
+    pub fn is_depth(&self) -> bool {
+        self.target.image == InspectedImage::Depth
+    }
+
+    pub fn texel(&self, x: u32, y: u32) -> Option<[f32; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.texels
+            .get(y as usize * self.width as usize + x as usize)
+            .copied()
+    }
+
+    /// The texels as 8-bit RGBA as the view displays them, for an egui image
+    pub fn to_rgba(&self, view: &InspectorView) -> Vec<u8> {
+        let is_depth = self.is_depth();
+        self.texels
+            .iter()
+            .flat_map(|texel| view.display_color(*texel, is_depth))
+            .collect()
+    }
+}
+
+/// How the editor displays a snapshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InspectorView {
+    /// which of R, G, B, and A to show; one alone is shown as grayscale
+    pub channels: [bool; 4],
+    /// the near and far planes to linearize depth with; None shows it raw
+    pub linearize_depth: Option<[f32; 2]>,
+    /// screen pixels per texel
+    pub zoom: f32,
+}
+
+impl Default for InspectorView {
+    fn default() -> Self {
+        Self {
+            channels: [true; 4],
+            linearize_depth: None,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl InspectorView {
+    pub fn display_color(&self, texel: [f32; 4], is_depth: bool) -> [u8; 4] {
+        let to_byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        if is_depth {
+            let depth = match self.linearize_depth {
+                Some([near, far]) => linearize_depth(texel[0], near, far),
+                None => texel[0],
+            };
+            let gray = to_byte(depth);
+            return [gray, gray, gray, 255];
+        }
+
+        let shown: Vec<usize> = (0..4).filter(|&i| self.channels[i]).collect();
+        if let [only] = shown[..] {
+            let gray = to_byte(texel[only]);
+            return [gray, gray, gray, 255];
+        }
+
+        std::array::from_fn(|i| {
+            if self.channels[i] {
+                to_byte(texel[i])
+            } else if i == 3 {
+                255
+            } else {
+                0
+            }
+        })
+    }
+}
+
+/// A [0, 1] perspective depth as the fraction of the way from near to far
+pub fn linearize_depth(depth: f32, near: f32, far: f32) -> f32 {
+    let view_z = near * far / (far - depth * (far - near));
+    (view_z - near) / (far - near)
+}
+
+/// The bytes per texel of a format the inspector can decode;
+/// for depth formats, of the depth aspect alone
+pub(super) fn texel_size(format: vk::Format) -> Option<u32> {
+    let size = match format {
+        vk::Format::R8_UNORM => 1,
+        vk::Format::R8G8_UNORM | vk::Format::R16_SFLOAT => 2,
+        vk::Format::R8G8B8A8_UNORM
+        | vk::Format::R8G8B8A8_SRGB
+        | vk::Format::B8G8R8A8_UNORM
+        | vk::Format::B8G8R8A8_SRGB
+        | vk::Format::R16G16_SFLOAT
+        | vk::Format::R32_SFLOAT
+        | vk::Format::R32_UINT
+        | vk::Format::A2B10G10R10_UNORM_PACK32
+        | vk::Format::A2R10G10B10_UNORM_PACK32 => 4,
+        vk::Format::R16G16B16A16_SFLOAT | vk::Format::R32G32_SFLOAT => 8,
+        vk::Format::R32G32B32A32_SFLOAT => 16,
+
+        vk::Format::D16_UNORM | vk::Format::D16_UNORM_S8_UINT => 2,
+        vk::Format::D32_SFLOAT
+        | vk::Format::D32_SFLOAT_S8_UINT
+        | vk::Format::D24_UNORM_S8_UINT
+        | vk::Format::X8_D24_UNORM_PACK32 => 4,
+
+        _ => return None,
+    };
+    Some(size)
+}
+
+fn decode_texel(format: vk::Format, bytes: &[u8]) -> [f32; 4] {
+    let unorm = |i: usize| bytes[i] as f32 / 255.0;
+    let half = |i: usize| f16_to_f32(u16::from_ne_bytes([bytes[i], bytes[i + 1]]));
+    let float = |i: usize| f32::from_ne_bytes(bytes[i..i + 4].try_into().unwrap());
+
+    match format {
+        vk::Format::R8_UNORM => [unorm(0), 0.0, 0.0, 1.0],
+        vk::Format::R8G8_UNORM => [unorm(0), unorm(1), 0.0, 1.0],
+        vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB => {
+            [unorm(0), unorm(1), unorm(2), unorm(3)]
+        }
+        vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB => {
+            [unorm(2), unorm(1), unorm(0), unorm(3)]
+        }
+        vk::Format::R16_SFLOAT => [half(0), 0.0, 0.0, 1.0],
+        vk::Format::R16G16_SFLOAT => [half(0), half(2), 0.0, 1.0],
+        vk::Format::R16G16B16A16_SFLOAT => [half(0), half(2), half(4), half(6)],
+        vk::Format::R32_SFLOAT => [float(0), 0.0, 0.0, 1.0],
+        vk::Format::R32G32_SFLOAT => [float(0), float(4), 0.0, 1.0],
+        vk::Format::R32G32B32A32_SFLOAT => [float(0), float(4), float(8), float(12)],
+        vk::Format::R32_UINT => {
+            let value = u32::from_ne_bytes(bytes[0..4].try_into().unwrap());
+            [value as f32, 0.0, 0.0, 1.0]
+        }
+        vk::Format::A2B10G10R10_UNORM_PACK32 | vk::Format::A2R10G10B10_UNORM_PACK32 => {
+            let word = u32::from_ne_bytes(bytes[0..4].try_into().unwrap());
+            let channel = |shift: u32| ((word >> shift) & 0x3ff) as f32 / 1023.0;
+            let alpha = (word >> 30) as f32 / 3.0;
+            if format == vk::Format::A2B10G10R10_UNORM_PACK32 {
+                [channel(0), channel(10), channel(20), alpha]
+            } else {
+                [channel(20), channel(10), channel(0), alpha]
+            }
+        }
+        // a depth format
+        _ => {
+            let mut word = [0; 4];
+            word[..bytes.len()].copy_from_slice(bytes);
+            [decode_depth(format, word), 0.0, 0.0, 1.0]
+        }
+    }
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// The extent of a mip level, given the top level's
+pub(super) fn mip_extent(extent: vk::Extent2D, mip_level: u32) -> [u32; 2] {
+    [
+        (extent.width >> mip_level).max(1),
+        (extent.height >> mip_level).max(1),
+    ]
+}
+
+/// The renderer's side of the inspector: what to read back, and the last result
+pub(super) struct TextureInspector {
+    target: Option<InspectorTarget>,
+    /// whether a readback is in flight
+    pending: bool,
+    snapshot: Option<InspectorSnapshot>,
+    generation: u64,
+}
+
+impl TextureInspector {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            pending: false,
+            snapshot: None,
+            generation: 0,
+        }
+    }
+
+    pub fn target(&self) -> Option<InspectorTarget> {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Option<InspectorTarget>) {
+        if target.is_none() {
+            self.snapshot = None;
+        }
+        self.target = target;
+    }
+
+    pub fn snapshot(&self) -> Option<&InspectorSnapshot> {
+        self.snapshot.as_ref()
+    }
+
+    /// The target to record a readback of this frame, if one isn't in flight
+    pub fn pending_target(&self) -> Option<InspectorTarget> {
+        if self.pending { None } else { self.target }
+    }
+
+    pub fn readback_recorded(&mut self) {
+        self.pending = true;
+    }
+
+    /// Decodes a finished readback; a stale target's is still shown
+    /// until the current target's arrives
+    pub fn deliver(&mut self, target: InspectorTarget, readback: Readback) {
+        self.pending = false;
+        if self.target.is_none() {
+            return;
+        }
+        let Some(image) = readback.image else {
+            return;
+        };
+        let Some(size) = texel_size(image.format) else {
+            return;
+        };
+
+        let texels = readback
+            .bytes
+            .chunks_exact(size as usize)
+            .map(|bytes| decode_texel(image.format, bytes))
+            .collect();
+        self.generation += 1;
+        self.snapshot = Some(InspectorSnapshot {
+            target,
+            width: image.width,
+            height: image.height,
+            format: image.format,
+            texels,
+            generation: self.generation,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::readback::{ReadbackImage, Readbacks};
+    use super::*;
+
+    fn readback(format: vk::Format, width: u32, bytes: Vec<u8>) -> Readback {
+        let height = bytes.len() as u32 / texel_size(format).unwrap() / width;
+        Readback {
+            id: Readbacks::new().next_id(),
+            bytes,
+            image: Some(ReadbackImage {
+                width,
+                height,
+                format,
+            }),
+        }
+    }
+
+    #[test]
+    fn snapshots_decode_their_formats() {
+        let target = InspectorTarget {
+            image: InspectedImage::Texture(0),
+            mip_level: 1,
+            layer: 0,
+        };
+        let mut inspector = TextureInspector::new();
+        inspector.set_target(Some(target));
+        assert_eq!(inspector.pending_target(), Some(target));
+        inspector.readback_recorded();
+        assert_eq!(inspector.pending_target(), None);
+
+        let bytes = vec![0, 51, 255, 255, 255, 0, 0, 0];
+        inspector.deliver(target, readback(vk::Format::B8G8R8A8_UNORM, 2, bytes));
+        let snapshot = inspector.snapshot().unwrap();
+        assert_eq!(snapshot.texel(0, 0), Some([1.0, 0.2, 0.0, 1.0]));
+        assert_eq!(snapshot.texel(1, 0), Some([0.0, 0.0, 1.0, 0.0]));
+        assert_eq!(snapshot.texel(2, 0), None);
+
+        // 1.0, -2.0, 0.5, and 0 as halves
+        let halves: [u16; 4] = [0x3c00, 0xc000, 0x3800, 0x0000];
+        let bytes = halves.iter().flat_map(|half| half.to_ne_bytes()).collect();
+        inspector.deliver(target, readback(vk::Format::R16G16B16A16_SFLOAT, 1, bytes));
+        let snapshot = inspector.snapshot().unwrap();
+        assert_eq!(snapshot.texels, [[1.0, -2.0, 0.5, 0.0]]);
+        assert_eq!(snapshot.generation, 2);
+    }
+
+    #[test]
+    fn a_single_channel_is_shown_as_grayscale() {
+        let texel = [1.0, 0.5, 0.0, 0.25];
+        let mut view = InspectorView::default();
+        assert_eq!(view.display_color(texel, false), [255, 128, 0, 64]);
+
+        view.channels = [false, true, false, false];
+        assert_eq!(view.display_color(texel, false), [128, 128, 128, 255]);
+
+        view.channels = [true, false, true, false];
+        assert_eq!(view.display_color(texel, false), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn depth_linearizes_between_the_planes() {
+        let (near, far) = (0.1, 100.0);
+        assert!(linearize_depth(0.0, near, far).abs() < 1e-6);
+        assert!((linearize_depth(1.0, near, far) - 1.0).abs() < 1e-6);
+        // perspective depth bunches up near the far plane
+        assert!(linearize_depth(0.99, near, far) < 0.1);
+
+        let view = InspectorView {
+            linearize_depth: Some([near, far]),
+            ..Default::default()
+        };
+        assert_eq!(view.display_color([1.0, 0.0, 0.0, 1.0], true), [255; 4]);
+        assert_eq!(
+            view.display_color([0.0, 0.0, 0.0, 1.0], true),
+            [0, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn mips_halve_down_to_one_texel() {
+        let extent = vk::Extent2D {
+            width: 256,
+            height: 64,
+        };
+        assert_eq!(mip_extent(extent, 0), [256, 64]);
+        assert_eq!(mip_extent(extent, 3), [32, 8]);
+        assert_eq!(mip_extent(extent, 7), [2, 1]);
+    }
+}
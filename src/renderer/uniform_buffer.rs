@@ -11,11 +11,59 @@ pub struct UniformBufferHandle<T> {
     _phantom_data: PhantomData<T>,
 }
 
+impl<T> UniformBufferHandle<T> {
+    /// a handle to nothing allocated, for the null renderer
+    pub(super) fn from_raw(index: usize) -> Self {
+        Self {
+            index,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+}
+
 pub(super) struct RawUniformBuffer {
     pub(super) buffer: vk::Buffer,
     pub(super) allocation: vk_mem::Allocation,
     /// cached from the persistently-mapped allocation's info
     pub(super) mapped_mem: *mut c_void,
+    #[cfg(debug_assertions)]
+    pub(super) writes: SlotWrites,
+}
+
+/// Debug builds only: whether a ring slot's buffer was ever written from the CPU,
+/// to warn when a draw reads one that still holds its zeroed initial contents
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SlotWrites {
+    Unwritten,
+    /// unwritten, and already warned about
+    Warned,
+    Written,
+}
+
+/// Debug builds only: true the first time a never-written slot is read;
+/// the buffer's other unwritten slots are marked too, so it's reported once
+#[cfg(debug_assertions)]
+pub(super) fn take_unwritten_warning<'a>(
+    slot_writes: impl IntoIterator<Item = &'a mut SlotWrites>,
+    frame: usize,
+) -> bool {
+    let mut slot_writes: Vec<_> = slot_writes.into_iter().collect();
+    if *slot_writes[frame] != SlotWrites::Unwritten {
+        return false;
+    }
+
+    for writes in slot_writes.iter_mut() {
+        if **writes == SlotWrites::Unwritten {
+            **writes = SlotWrites::Warned;
+        }
+    }
+
+    true
 }
 
 // NOTE renderer has to enforce type safety
@@ -41,6 +89,26 @@ impl UniformBufferStorage {
         handle
     }
 
+    /// Add a buffer known only by its size, for uniforms whose layout comes
+    /// from reflection at runtime rather than a generated type
+    pub fn add_raw(
+        &mut self,
+        buffers_per_frame: [RawUniformBuffer; PRE_WAIT_RING_LEN],
+        size: u64,
+    ) -> RawUniformBufferHandle {
+        let handle = RawUniformBufferHandle {
+            index: self.0.len(),
+            element_type: UniformElementType {
+                type_name: "raw bytes",
+                size,
+            },
+        };
+
+        self.0.push(Some(buffers_per_frame));
+
+        handle
+    }
+
     pub fn get_raw(
         &self,
         handle: &RawUniformBufferHandle,
@@ -54,10 +122,46 @@ impl UniformBufferStorage {
         frame: usize,
     ) -> &mut T {
         let raw_uniform_buffer = &mut self.0[handle.index].as_mut().unwrap()[frame];
+        #[cfg(debug_assertions)]
+        {
+            raw_uniform_buffer.writes = SlotWrites::Written;
+        }
         let mut_ptr = raw_uniform_buffer.mapped_mem as *mut T;
         unsafe { &mut *mut_ptr }
     }
 
+    pub fn get_mapped_bytes_for_frame(
+        &mut self,
+        handle: &RawUniformBufferHandle,
+        frame: usize,
+    ) -> &mut [u8] {
+        let raw_uniform_buffer = &mut self.0[handle.index].as_mut().unwrap()[frame];
+        #[cfg(debug_assertions)]
+        {
+            raw_uniform_buffer.writes = SlotWrites::Written;
+        }
+        let mut_ptr = raw_uniform_buffer.mapped_mem as *mut u8;
+        unsafe { std::slice::from_raw_parts_mut(mut_ptr, handle.element_type.size as usize) }
+    }
+
+    pub fn read_for_frame<T: Clone>(&self, handle: &UniformBufferHandle<T>, frame: usize) -> T {
+        let raw_uniform_buffer = &self.0[handle.index].as_ref().unwrap()[frame];
+        let ptr = raw_uniform_buffer.mapped_mem as *const T;
+        unsafe { (*ptr).clone() }
+    }
+
+    /// Debug builds only: true the first time a draw reads this buffer
+    /// while the given frame's slot has never been written
+    #[cfg(debug_assertions)]
+    pub(super) fn take_unwritten_warning(&mut self, index: usize, frame: usize) -> bool {
+        match self.0[index].as_mut() {
+            Some(buffers) => {
+                take_unwritten_warning(buffers.iter_mut().map(|buffer| &mut buffer.writes), frame)
+            }
+            None => false,
+        }
+    }
+
     pub fn take<T>(
         &mut self,
         handle: UniformBufferHandle<T>,
@@ -65,6 +169,29 @@ impl UniformBufferStorage {
         self.0[handle.index].take().unwrap()
     }
 
+    pub fn take_raw(
+        &mut self,
+        handle: RawUniformBufferHandle,
+    ) -> [RawUniformBuffer; PRE_WAIT_RING_LEN] {
+        self.0[handle.index].take().unwrap()
+    }
+
+    /// each live buffer's copy for a frame, with its handle index
+    pub(super) fn iter_for_frame(
+        &self,
+        frame: usize,
+    ) -> impl Iterator<Item = (usize, &RawUniformBuffer)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, buffers)| Some((index, &buffers.as_ref()?[frame])))
+    }
+
+    /// the number of buffers not yet dropped
+    pub fn live_count(&self) -> usize {
+        self.0.iter().flatten().count()
+    }
+
     pub fn take_all(&mut self) -> Vec<[RawUniformBuffer; PRE_WAIT_RING_LEN]> {
         self.0
             .iter_mut()
@@ -75,13 +202,84 @@ impl UniformBufferStorage {
 
 // NOTE find a way to limit this to generated code
 //   would need to make PipelineConfig fields private
+#[derive(Clone)]
 pub struct RawUniformBufferHandle {
     index: usize,
+    element_type: UniformElementType,
 }
 
 impl RawUniformBufferHandle {
+    pub(super) fn index(&self) -> usize {
+        self.index
+    }
+
+    pub(super) fn from_parts(index: usize, element_type: UniformElementType) -> Self {
+        Self {
+            index,
+            element_type,
+        }
+    }
+
     pub fn from_typed<T>(handle: &UniformBufferHandle<T>) -> Self {
         let index = handle.index;
-        Self { index }
+        let element_type = UniformElementType::of::<T>();
+        Self {
+            index,
+            element_type,
+        }
+    }
+
+    pub fn element_type(&self) -> UniformElementType {
+        self.element_type
+    }
+}
+
+/// The Rust type a uniform buffer was created for,
+/// kept for validation against shader reflection once the type is erased
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UniformElementType {
+    pub type_name: &'static str,
+    pub size: u64,
+}
+
+impl UniformElementType {
+    pub fn of<T>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            size: std::mem::size_of::<T>() as u64,
+        }
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_once_per_buffer() {
+        let mut slots = [
+            SlotWrites::Unwritten,
+            SlotWrites::Written,
+            SlotWrites::Unwritten,
+        ];
+
+        assert!(take_unwritten_warning(&mut slots, 0));
+        assert_eq!(
+            slots,
+            [SlotWrites::Warned, SlotWrites::Written, SlotWrites::Warned]
+        );
+
+        assert!(!take_unwritten_warning(&mut slots, 2));
+    }
+
+    #[test]
+    fn written_slots_do_not_warn() {
+        let mut slots = [
+            SlotWrites::Unwritten,
+            SlotWrites::Written,
+            SlotWrites::Unwritten,
+        ];
+        assert!(!take_unwritten_warning(&mut slots, 1));
+        assert_eq!(slots[0], SlotWrites::Unwritten);
     }
 }
@@ -0,0 +1,159 @@
+//! The velocity buffer: a second main pass color target holding each pixel's
+//! motion since the previous frame, for motion blur and TAA.
+//!
+//! A game opts in with `Game::velocity_buffer`, which gives the main pass a
+//! [`VELOCITY_FORMAT`] attachment at `SV_Target1`, cleared to zero (no motion).
+//! Only pipelines created with
+//! [`PipelineConfig::with_velocity`](super::PipelineConfig::with_velocity) write
+//! to it; generated `pipeline_config()` sets that for shaders whose fragment
+//! output has a float2 `SV_Target1` (see `VelocityOutput` in
+//! shaders/lib/motion_blur.slang). Other pipelines leave it untouched, so
+//! anything they draw over reads as still.
+//!
+//! Velocities use the TAA motion vector convention: a uv offset from the
+//! previous frame's position to the current one. Post-process pipelines sample
+//! them through a texture from `Renderer::create_velocity_texture`.
+
+use ash::vk;
+use glam::Vec2;
+
+use super::{ImageOptions, create_image_view, create_vk_image};
+
+/// Two half floats: enough range and precision for uv offsets
+pub const VELOCITY_FORMAT: vk::Format = vk::Format::R16G16_SFLOAT;
+
+/// The velocity of something drawn at `current` this frame and `previous` the
+/// last, both in render pixels; for sprites that know their own motion
+pub fn uv_velocity(current: Vec2, previous: Vec2, render_resolution: Vec2) -> Vec2 {
+    (current - previous) / render_resolution
+}
+
+/// The main pass's velocity attachment, and its resolve target when the pass
+/// uses MSAA. Between frames the single-sample image is left in
+/// SHADER_READ_ONLY_OPTIMAL.
+pub(super) struct VelocityTargets {
+    /// the multisampled attachment, resolved into `image`
+    msaa: Option<(vk::Image, vk_mem::Allocation, vk::ImageView)>,
+    pub image: vk::Image,
+    memory: vk_mem::Allocation,
+    view: vk::ImageView,
+}
+
+impl VelocityTargets {
+    pub fn init(
+        allocator: &vk_mem::Allocator,
+        device: &ash::Device,
+        render_extent: vk::Extent2D,
+        msaa_samples: vk::SampleCountFlags,
+    ) -> anyhow::Result<Self> {
+        let image_options = ImageOptions {
+            extent: render_extent,
+            format: VELOCITY_FORMAT,
+            tiling: vk::ImageTiling::OPTIMAL,
+            usage: vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED,
+            mip_levels: 1,
+            array_layers: 1,
+            msaa_samples: vk::SampleCountFlags::TYPE_1,
+        };
+        let (image, memory) = create_vk_image(allocator, image_options)?;
+        let view = create_image_view(
+            device,
+            image,
+            VELOCITY_FORMAT,
+            vk::ImageAspectFlags::COLOR,
+            1,
+        )?;
+
+        let msaa = if msaa_samples == vk::SampleCountFlags::TYPE_1 {
+            None
+        } else {
+            let msaa_options = ImageOptions {
+                usage: vk::ImageUsageFlags::TRANSIENT_ATTACHMENT
+                    | vk::ImageUsageFlags::COLOR_ATTACHMENT,
+                msaa_samples,
+                ..image_options
+            };
+            let (msaa_image, msaa_memory) = create_vk_image(allocator, msaa_options)?;
+            let msaa_view = create_image_view(
+                device,
+                msaa_image,
+                VELOCITY_FORMAT,
+                vk::ImageAspectFlags::COLOR,
+                1,
+            )?;
+            Some((msaa_image, msaa_memory, msaa_view))
+        };
+
+        Ok(Self {
+            msaa,
+            image,
+            memory,
+            view,
+        })
+    }
+
+    /// The images the main pass renders into, for its attachment barriers
+    pub fn attachment_images(&self) -> impl Iterator<Item = vk::Image> {
+        std::iter::once(self.image).chain(self.msaa.as_ref().map(|(image, _, _)| *image))
+    }
+
+    /// The main pass attachment, cleared to zero and resolved like the scene color.
+    /// Averaging the samples blurs velocities along edges, as motion blur would anyway.
+    pub fn attachment(&self) -> vk::RenderingAttachmentInfo<'static> {
+        let clear_value = vk::ClearValue {
+            color: vk::ClearColorValue { float32: [0.0; 4] },
+        };
+
+        match &self.msaa {
+            None => vk::RenderingAttachmentInfo::default()
+                .image_view(self.view)
+                .store_op(vk::AttachmentStoreOp::STORE),
+            Some((_, _, msaa_view)) => vk::RenderingAttachmentInfo::default()
+                .image_view(*msaa_view)
+                .resolve_mode(vk::ResolveModeFlags::AVERAGE)
+                .resolve_image_view(self.view)
+                .resolve_image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .store_op(vk::AttachmentStoreOp::DONT_CARE),
+        }
+        .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+        .load_op(vk::AttachmentLoadOp::CLEAR)
+        .clear_value(clear_value)
+    }
+
+    /// A view for a velocity texture alias, which owns it
+    pub fn create_view(&self, device: &ash::Device) -> anyhow::Result<vk::ImageView> {
+        create_image_view(
+            device,
+            self.image,
+            VELOCITY_FORMAT,
+            vk::ImageAspectFlags::COLOR,
+            1,
+        )
+    }
+
+    pub fn destroy(mut self, allocator: &vk_mem::Allocator, device: &ash::Device) {
+        unsafe {
+            if let Some((msaa_image, mut msaa_memory, msaa_view)) = self.msaa.take() {
+                device.destroy_image_view(msaa_view, None);
+                allocator.destroy_image(msaa_image, &mut msaa_memory);
+            }
+            device.destroy_image_view(self.view, None);
+            allocator.destroy_image(self.image, &mut self.memory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uv_velocity_is_a_fraction_of_the_target() {
+        let resolution = Vec2::new(800.0, 600.0);
+        let velocity = uv_velocity(Vec2::new(500.0, 300.0), Vec2::new(100.0, 360.0), resolution);
+        assert_eq!(velocity, Vec2::new(0.5, -0.1));
+
+        let still = uv_velocity(Vec2::splat(42.0), Vec2::splat(42.0), resolution);
+        assert_eq!(still, Vec2::ZERO);
+    }
+}
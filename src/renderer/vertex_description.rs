@@ -16,3 +16,90 @@ impl VertexDescription for NoVertex {
         vec![]
     }
 }
+
+// Packing for vertex fields with a semantic format override
+// (`float4 color : COLOR_UNORM8;`), which generate as arrays of packed components
+
+/// 0.0..=1.0 components for a `_UNORM8` field
+pub fn unorm8<const N: usize>(values: [f32; N]) -> [u8; N] {
+    values.map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+/// -1.0..=1.0 components for a `_SNORM8` field
+pub fn snorm8<const N: usize>(values: [f32; N]) -> [i8; N] {
+    values.map(|value| (value.clamp(-1.0, 1.0) * 127.0).round() as i8)
+}
+
+/// Half float bits for a `_HALF` field, rounded to nearest even
+pub fn half<const N: usize>(values: [f32; N]) -> [u16; N] {
+    values.map(f32_to_f16)
+}
+
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits >> 16) & 0x8000;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x007f_ffff;
+
+    if exponent == 0xff {
+        let nan_bit = if mantissa != 0 { 0x0200 } else { 0 };
+        return (sign | 0x7c00 | nan_bit) as u16;
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return (sign | 0x7c00) as u16;
+    }
+
+    // the bits shifted out round to nearest, ties to even;
+    // a carry rolls over into the exponent, which is still correct
+    let round = |shifted: u32, mantissa: u32, shift: u32| {
+        let round_bit = 1 << (shift - 1);
+        let lower_and_lsb = (round_bit - 1) | (round_bit << 1);
+        if mantissa & round_bit != 0 && mantissa & lower_and_lsb != 0 {
+            shifted + 1
+        } else {
+            shifted
+        }
+    };
+
+    if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign as u16;
+        }
+
+        // subnormal: the implicit bit joins the mantissa
+        let mantissa = mantissa | 0x0080_0000;
+        let shift = (14 - half_exponent) as u32;
+        return (sign | round(mantissa >> shift, mantissa, shift)) as u16;
+    }
+
+    let shifted = ((half_exponent as u32) << 10) | (mantissa >> 13);
+    (sign | round(shifted, mantissa, 13)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_components_round_and_clamp() {
+        assert_eq!(unorm8([0.0, 1.0, 0.5, 2.0]), [0, 255, 128, 255]);
+        assert_eq!(snorm8([-1.0, 1.0, 0.0, 0.5]), [-127, 127, 0, 64]);
+        assert_eq!(snorm8([-3.0, 0.25]), [-127, 32]);
+    }
+
+    #[test]
+    fn half_floats_match_their_bit_patterns() {
+        assert_eq!(
+            half([1.0, -2.0, 0.5, 65504.0]),
+            [0x3c00, 0xc000, 0x3800, 0x7bff]
+        );
+        // smallest subnormal, then overflow and infinity
+        assert_eq!(half([2f32.powi(-24), 1.0e6]), [0x0001, 0x7c00]);
+        assert_eq!(half([f32::NEG_INFINITY, 0.0]), [0xfc00, 0x0000]);
+        // 1 + 2^-11 is halfway between 1.0 and the next half; ties go to even
+        assert_eq!(half([1.0 + 2f32.powi(-11)]), [0x3c00]);
+        assert!(f32_to_f16(f32::NAN) & 0x7fff > 0x7c00);
+    }
+}
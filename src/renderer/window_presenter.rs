@@ -0,0 +1,89 @@
+//! The half of the renderer tied to a window:
+//! its surface, and the swapchain presented to it.
+
+use ash::vk;
+use sdl3::video::Window;
+
+use super::hdr::{self, DisplayLuminance, HdrOutput};
+use super::present_wait::PresentWait;
+use super::swapchain::{self, Acquire, Present, Swapchain};
+
+/// Owned by the Renderer, which destroys the swapchain on resize and drop;
+/// see Renderer::cleanup_swapchain.
+pub struct WindowPresenter {
+    pub(super) window: Window,
+    pub(super) surface_ext: ash::khr::surface::Instance,
+    pub(super) surface: vk::SurfaceKHR,
+    pub(super) presentation_queue: vk::Queue,
+    pub(super) swapchain_device_ext: ash::khr::swapchain::Device,
+    /// from the device profile; swapchains fall back to FIFO without it
+    pub(super) preferred_present_mode: vk::PresentModeKHR,
+    /// when the device supports present ids and waiting on them
+    pub(super) present_wait: Option<PresentWait>,
+    /// the output asked for at init, or SDR where it wasn't available
+    pub(super) hdr_output: HdrOutput,
+    /// when the device supports VK_EXT_hdr_metadata and the output is HDR
+    pub(super) hdr_metadata_ext: Option<ash::ext::hdr_metadata::Device>,
+    /// what the swapchain's metadata was last set from; None for a new swapchain
+    pub(super) hdr_metadata_luminance: Option<DisplayLuminance>,
+    /// the format chosen at init; recreated swapchains keep it,
+    /// since pipelines were created for it
+    pub(super) surface_format: vk::SurfaceFormatKHR,
+
+    // fields that change with the swapchain
+    pub(super) image_format: vk::Format,
+    pub(super) image_extent: vk::Extent2D,
+    pub(super) swapchain: vk::SwapchainKHR,
+    pub(super) swapchain_images: Vec<vk::Image>,
+    pub(super) swapchain_image_views: Vec<vk::ImageView>,
+    /// image semaphores indexed by ring_slot
+    pub(super) image_available: [vk::Semaphore; super::PRE_WAIT_RING_LEN],
+    /// render finished semaphores indexed by image_index
+    /// ie, one per swapchain image, not per frame-in-flight
+    pub(super) render_finished: Vec<vk::Semaphore>,
+}
+
+impl WindowPresenter {
+    /// Describes the display to the compositor, when it's changed since the
+    /// swapchain's metadata was last set
+    pub(super) fn update_hdr_metadata(&mut self, luminance: DisplayLuminance) {
+        let Some(hdr_metadata_ext) = &self.hdr_metadata_ext else {
+            return;
+        };
+        if self.hdr_metadata_luminance == Some(luminance) {
+            return;
+        }
+        let Some(metadata) = hdr::hdr_metadata(self.hdr_output, luminance) else {
+            return;
+        };
+
+        unsafe { hdr_metadata_ext.set_hdr_metadata(&[self.swapchain], &[metadata]) };
+        self.hdr_metadata_luminance = Some(luminance);
+    }
+}
+
+impl Swapchain for WindowPresenter {
+    fn acquire(&mut self, ring_slot: usize) -> Result<Acquire, vk::Result> {
+        swapchain::acquire_next_image(
+            &self.swapchain_device_ext,
+            self.swapchain,
+            self.image_available[ring_slot],
+        )
+    }
+
+    fn present(&mut self, image_index: u32) -> Result<Present, vk::Result> {
+        let present_id = self
+            .present_wait
+            .as_mut()
+            .map(|present_wait| present_wait.next_present_id());
+
+        swapchain::queue_present(
+            &self.swapchain_device_ext,
+            self.presentation_queue,
+            self.swapchain,
+            image_index,
+            self.render_finished[image_index as usize],
+            present_id,
+        )
+    }
+}
@@ -0,0 +1,281 @@
+//! Scripted sequences of timed game events, like enemy waves or cutscene
+//! camera moves.
+//!
+//! A [`Sequence`] is a small state machine built from steps: emit an event,
+//! wait some ticks, or wait for the game to [`signal`](Sequence::signal) it.
+//! It only moves forward in whole ticks, so a sequence plays out the same way
+//! at any frame rate. [`FixedTimestep`] turns [`Game::update`](crate::game::Game::update)'s
+//! variable `dt` into those ticks:
+//!
+//! ```ignore
+//! let mut waves = Sequence::builder()
+//!     .emit(Wave::Scouts)
+//!     .wait_for_signal()
+//!     .wait(120)
+//!     .emit(Wave::Bombers)
+//!     .build();
+//! let mut clock = FixedTimestep::new(Duration::from_micros(16_667));
+//!
+//! // in update
+//! for _ in 0..clock.ticks(dt) {
+//!     for wave in waves.tick() {
+//!         self.spawn(wave);
+//!     }
+//! }
+//! if self.enemies_cleared() {
+//!     waves.signal();
+//! }
+//! ```
+
+use std::time::Duration;
+
+/// Splits variable frame times into whole fixed-length ticks,
+/// carrying the remainder into the next frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedTimestep {
+    pub tick: Duration,
+    accumulator: Duration,
+}
+
+impl FixedTimestep {
+    pub fn new(tick: Duration) -> Self {
+        assert!(!tick.is_zero(), "fixed timestep must be positive");
+        Self {
+            tick,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// The number of whole ticks that fit in the time so far, after adding `dt`
+    pub fn ticks(&mut self, dt: Duration) -> u32 {
+        self.accumulator += dt;
+        let ticks = self.accumulator.as_nanos() / self.tick.as_nanos();
+        self.accumulator -= self.tick * ticks as u32;
+        ticks as u32
+    }
+
+    /// How far into the next tick the clock is, from 0 to 1; for interpolating
+    /// between the previous and current tick's state when drawing
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.tick.as_secs_f32()
+    }
+
+    /// The number of ticks that fit in `duration`, rounded up
+    pub fn ticks_in(&self, duration: Duration) -> u32 {
+        duration.as_nanos().div_ceil(self.tick.as_nanos()) as u32
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step<E> {
+    Emit(E),
+    Wait(u32),
+    WaitForSignal,
+}
+
+/// Steps of a [`Sequence`], in the order they play
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceBuilder<E> {
+    steps: Vec<Step<E>>,
+}
+
+impl<E: Clone> SequenceBuilder<E> {
+    /// Fire `event` on the tick this step is reached
+    pub fn emit(mut self, event: E) -> Self {
+        self.steps.push(Step::Emit(event));
+        self
+    }
+
+    /// Hold for `ticks` ticks before the next step
+    pub fn wait(mut self, ticks: u32) -> Self {
+        self.steps.push(Step::Wait(ticks));
+        self
+    }
+
+    /// Hold until the game calls [`Sequence::signal`] while waiting here
+    pub fn wait_for_signal(mut self) -> Self {
+        self.steps.push(Step::WaitForSignal);
+        self
+    }
+
+    /// A sequence that plays its steps once
+    pub fn build(self) -> Sequence<E> {
+        Sequence::new(self.steps, false)
+    }
+
+    /// A sequence that starts over after its last step
+    pub fn looping(self) -> Sequence<E> {
+        let holds = self.steps.iter().any(|step| match step {
+            Step::Wait(ticks) => *ticks > 0,
+            Step::WaitForSignal => true,
+            Step::Emit(_) => false,
+        });
+        assert!(
+            holds,
+            "a looping sequence needs a wait to hold between loops"
+        );
+
+        Sequence::new(self.steps, true)
+    }
+}
+
+/// Timed events played out one tick at a time; see the [module docs](self)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence<E> {
+    steps: Vec<Step<E>>,
+    looping: bool,
+    /// the step currently playing
+    cursor: usize,
+    /// ticks spent in the current wait
+    waited: u32,
+    signaled: bool,
+}
+
+impl<E: Clone> Sequence<E> {
+    pub fn builder() -> SequenceBuilder<E> {
+        SequenceBuilder { steps: vec![] }
+    }
+
+    fn new(steps: Vec<Step<E>>, looping: bool) -> Self {
+        Self {
+            steps,
+            looping,
+            cursor: 0,
+            waited: 0,
+            signaled: false,
+        }
+    }
+
+    /// Play one tick, returning the events fired during it, in order
+    pub fn tick(&mut self) -> Vec<E> {
+        let mut events = vec![];
+
+        loop {
+            let Some(step) = self.steps.get(self.cursor) else {
+                if self.looping && !self.steps.is_empty() {
+                    self.cursor = 0;
+                    continue;
+                }
+                break;
+            };
+
+            match step {
+                Step::Emit(event) => {
+                    events.push(event.clone());
+                    self.cursor += 1;
+                }
+                Step::Wait(ticks) => {
+                    if self.waited < *ticks {
+                        self.waited += 1;
+                        break;
+                    }
+                    self.waited = 0;
+                    self.cursor += 1;
+                }
+                Step::WaitForSignal => {
+                    if !self.signaled {
+                        break;
+                    }
+                    self.signaled = false;
+                    self.cursor += 1;
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Release a [`wait_for_signal`](SequenceBuilder::wait_for_signal) step on the next tick;
+    /// ignored unless the sequence is waiting at one
+    pub fn signal(&mut self) {
+        if matches!(self.steps.get(self.cursor), Some(Step::WaitForSignal)) {
+            self.signaled = true;
+        }
+    }
+
+    /// Whether a non-looping sequence has played all of its steps
+    pub fn finished(&self) -> bool {
+        !self.looping && self.cursor >= self.steps.len()
+    }
+
+    pub fn restart(&mut self) {
+        self.cursor = 0;
+        self.waited = 0;
+        self.signaled = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_micros(15_625);
+
+    /// the events fired on each tick, for `ticks` ticks
+    fn play<E: Clone>(sequence: &mut Sequence<E>, ticks: usize) -> Vec<Vec<E>> {
+        (0..ticks).map(|_| sequence.tick()).collect()
+    }
+
+    #[test]
+    fn fixed_timesteps_carry_the_remainder() {
+        let mut clock = FixedTimestep::new(FRAME);
+        assert_eq!(clock.ticks(FRAME / 2), 0);
+        assert_eq!(clock.alpha(), 0.5);
+        assert_eq!(clock.ticks(FRAME), 1);
+        assert_eq!(clock.ticks(FRAME * 3 / 2), 2);
+        assert_eq!(clock.alpha(), 0.0);
+        assert_eq!(clock.ticks_in(Duration::from_millis(100)), 7);
+    }
+
+    #[test]
+    fn waits_hold_for_whole_ticks() {
+        let mut sequence = Sequence::builder()
+            .emit('a')
+            .emit('b')
+            .wait(2)
+            .emit('c')
+            .wait(0)
+            .emit('d')
+            .build();
+
+        let ticks = play(&mut sequence, 4);
+        assert_eq!(ticks, [vec!['a', 'b'], vec![], vec!['c', 'd'], vec![]]);
+        assert!(sequence.finished());
+
+        sequence.restart();
+        assert!(!sequence.finished());
+        assert_eq!(sequence.tick(), ['a', 'b']);
+    }
+
+    #[test]
+    fn signals_only_release_a_waiting_gate() {
+        let mut sequence = Sequence::builder()
+            .wait(1)
+            .wait_for_signal()
+            .emit("wave cleared")
+            .build();
+
+        // too early; the sequence is still in its first wait
+        sequence.signal();
+        assert_eq!(play(&mut sequence, 3), [vec![]; 3]);
+
+        sequence.signal();
+        assert_eq!(sequence.tick(), ["wave cleared"]);
+        assert!(sequence.finished());
+    }
+
+    #[test]
+    fn looping_sequences_start_over() {
+        let mut sequence = Sequence::builder().emit(1).wait(2).emit(2).looping();
+
+        let ticks = play(&mut sequence, 5);
+        assert_eq!(ticks, [vec![1], vec![], vec![2, 1], vec![], vec![2, 1]]);
+        assert!(!sequence.finished());
+    }
+
+    #[test]
+    #[should_panic(expected = "needs a wait")]
+    fn looping_sequences_must_hold() {
+        Sequence::builder().emit(1).wait(0).looping();
+    }
+}
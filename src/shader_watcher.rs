@@ -45,6 +45,8 @@ pub fn watch() -> notify::Result<ShaderChanges> {
 
     let shaders_source_path = manifest_path(["shaders", "source"]);
     watcher.watch(&shaders_source_path, RecursiveMode::Recursive)?;
+    let shaders_lib_path = manifest_path(["shaders", "lib"]);
+    watcher.watch(&shaders_lib_path, RecursiveMode::Recursive)?;
 
     Ok(ShaderChanges { watcher, receiver })
 }
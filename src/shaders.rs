@@ -1,19 +1,32 @@
-use std::collections::HashMap;
-use std::ffi::CString;
-
-use shader_slang as slang;
+//! Shader metadata shared with the renderer (atlas, json), and with the
+//! `shader-compiler` feature, the slang compilation behind build_tasks and
+//! debug hot reload. Games can build without it, using shaders compiled ahead of time.
 
 pub mod atlas;
-pub mod build_tasks;
 pub mod json;
+
+#[cfg(feature = "shader-compiler")]
+pub mod build_tasks;
+#[cfg(feature = "shader-compiler")]
 mod reflection;
 
+#[cfg(feature = "shader-compiler")]
+use std::collections::HashMap;
+#[cfg(feature = "shader-compiler")]
+use std::ffi::CString;
+
+#[cfg(feature = "shader-compiler")]
+use shader_slang as slang;
+
+#[cfg(feature = "shader-compiler")]
 use json::*;
 
 /// whether to use column-major or row-major matricies with slang
 /// https://docs.shader-slang.org/en/latest/external/slang/docs/user-guide/a1-01-matrix-layout.html
+#[cfg(feature = "shader-compiler")]
 const MATRIX_LAYOUT: MatrixLayout = MatrixLayout::RowMajor;
 
+#[cfg(feature = "shader-compiler")]
 #[derive(Debug, PartialEq, Eq)]
 enum MatrixLayout {
     ColumnMajor,
@@ -21,6 +34,17 @@ enum MatrixLayout {
     RowMajor,
 }
 
+/// shaders/lib: the slang modules shipped with the crate (noise, sdf, tonemap,
+/// color, msdf, billboard, quad, frame_globals, hdr, and the helpers for the
+/// renderer's passes, like taa and split_screen), searched after a game's own
+/// shader directory so any shader can import them
+#[cfg(feature = "shader-compiler")]
+fn library_search_path() -> CString {
+    let library_path = crate::util::manifest_path(["shaders", "lib"]);
+    CString::new(library_path.to_str().unwrap()).unwrap()
+}
+
+#[cfg(feature = "shader-compiler")]
 fn load_cpu_constants_module(session: &slang::Session) -> anyhow::Result<slang::Module> {
     let column_major = MATRIX_LAYOUT == MatrixLayout::ColumnMajor;
     let src = format!(
@@ -34,23 +58,30 @@ fn load_cpu_constants_module(session: &slang::Session) -> anyhow::Result<slang::
     Ok(session.load_module_from_source_string("cpu_constants", "cpu_constants.slang", &src)?)
 }
 
+#[cfg(feature = "shader-compiler")]
 pub struct ReflectedShader {
+    /// the vertex stage, or the mesh stage of a mesh shader
     pub vertex_shader: CompiledShader,
+    /// a mesh shader's optional task stage
+    pub task_shader: Option<CompiledShader>,
     pub fragment_shader: CompiledShader,
     pub reflection_json: ReflectionJson,
 }
 
+#[cfg(feature = "shader-compiler")]
 pub struct ReflectedComputeShader {
     pub compute_shader: CompiledShader,
     pub reflection_json: ComputeReflectionJson,
 }
 
+#[cfg(feature = "shader-compiler")]
 fn prepare_reflected_shader(
     source_file_name: &str,
     search_path: &str,
 ) -> anyhow::Result<ReflectedShader> {
     let global_session = slang::GlobalSession::new().unwrap();
     let search_path = CString::new(search_path).unwrap();
+    let library_path = library_search_path();
 
     let session_options = slang::CompilerOptions::default()
         .vulkan_use_entry_point_name(true)
@@ -67,7 +98,7 @@ fn prepare_reflected_shader(
         .profile(global_session.find_profile("glsl_450+spirv_1_6"));
 
     let targets = [target_desc];
-    let search_paths = [search_path.as_ptr()];
+    let search_paths = [search_path.as_ptr(), library_path.as_ptr()];
     let session_desc = slang::SessionDesc::default()
         .targets(&targets)
         .search_paths(&search_paths)
@@ -78,11 +109,12 @@ fn prepare_reflected_shader(
     let shader_module = session.load_module(source_file_name)?;
     let cpu_constants_module = load_cpu_constants_module(&session)?;
 
-    // the examples have 1 vert and 1 frag shader
-    debug_assert!(shader_module.entry_points().len() == 2);
+    // 1 vert or mesh shader, 1 frag shader, and for mesh shaders an optional task shader
+    debug_assert!((2..=3).contains(&shader_module.entry_points().len()));
 
     let mut components = vec![shader_module.clone().into()];
     let mut vertex_shader: Option<CompiledShader> = None;
+    let mut task_shader: Option<CompiledShader> = None;
     let mut fragment_shader: Option<CompiledShader> = None;
     for entry_point in shader_module.entry_points() {
         let compiled_shader = compile_shader(
@@ -92,8 +124,16 @@ fn prepare_reflected_shader(
             &cpu_constants_module,
         )?;
 
-        if compiled_shader.stage == slang::Stage::Vertex {
+        if compiled_shader.stage == slang::Stage::Vertex
+            || compiled_shader.stage == slang::Stage::Mesh
+        {
+            anyhow::ensure!(
+                vertex_shader.is_none(),
+                "{source_file_name} has more than one vertex or mesh entry point"
+            );
             vertex_shader = Some(compiled_shader)
+        } else if compiled_shader.stage == slang::Stage::Amplification {
+            task_shader = Some(compiled_shader)
         } else if compiled_shader.stage == slang::Stage::Fragment {
             fragment_shader = Some(compiled_shader)
         }
@@ -101,8 +141,9 @@ fn prepare_reflected_shader(
         components.push(entry_point.clone().into());
     }
 
-    let vertex_shader = vertex_shader
-        .unwrap_or_else(|| panic!("failed to load vertex entry point for: {source_file_name}"));
+    let vertex_shader = vertex_shader.unwrap_or_else(|| {
+        panic!("failed to load vertex or mesh entry point for: {source_file_name}")
+    });
     let fragment_shader = fragment_shader
         .unwrap_or_else(|| panic!("failed to load fragment entry point for: {source_file_name}"));
 
@@ -114,6 +155,7 @@ fn prepare_reflected_shader(
 
     let reflected_shader = ReflectedShader {
         vertex_shader,
+        task_shader,
         fragment_shader,
         reflection_json,
     };
@@ -121,17 +163,19 @@ fn prepare_reflected_shader(
     Ok(reflected_shader)
 }
 
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, feature = "shader-compiler"))]
 pub fn dev_compile_slang_shaders(source_file_name: &str) -> anyhow::Result<ReflectedShader> {
     prepare_reflected_shader(source_file_name, "shaders/source")
 }
 
+#[cfg(feature = "shader-compiler")]
 fn prepare_reflected_compute_shader(
     source_file_name: &str,
     search_path: &str,
 ) -> anyhow::Result<ReflectedComputeShader> {
     let global_session = slang::GlobalSession::new().unwrap();
     let search_path = CString::new(search_path).unwrap();
+    let library_path = library_search_path();
 
     let session_options = slang::CompilerOptions::default()
         .vulkan_use_entry_point_name(true)
@@ -148,7 +192,7 @@ fn prepare_reflected_compute_shader(
         .profile(global_session.find_profile("glsl_450+spirv_1_6"));
 
     let targets = [target_desc];
-    let search_paths = [search_path.as_ptr()];
+    let search_paths = [search_path.as_ptr(), library_path.as_ptr()];
     let session_desc = slang::SessionDesc::default()
         .targets(&targets)
         .search_paths(&search_paths)
@@ -197,19 +241,21 @@ fn prepare_reflected_compute_shader(
     })
 }
 
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, feature = "shader-compiler"))]
 pub fn dev_compile_slang_compute_shaders(
     source_file_name: &str,
 ) -> anyhow::Result<ReflectedComputeShader> {
     prepare_reflected_compute_shader(source_file_name, "shaders/source")
 }
 
+#[cfg(feature = "shader-compiler")]
 pub fn reflect_shared_module_types(
     module_names: &[&str],
     search_path: &str,
 ) -> anyhow::Result<HashMap<String, String>> {
     let global_session = slang::GlobalSession::new().unwrap();
     let search_path = CString::new(search_path).unwrap();
+    let library_path = library_search_path();
 
     let session_options = slang::CompilerOptions::default()
         .language(slang::SourceLanguage::Slang)
@@ -224,7 +270,7 @@ pub fn reflect_shared_module_types(
         .profile(global_session.find_profile("glsl_450+spirv_1_6"));
 
     let targets = [target_desc];
-    let search_paths = [search_path.as_ptr()];
+    let search_paths = [search_path.as_ptr(), library_path.as_ptr()];
     let session_desc = slang::SessionDesc::default()
         .targets(&targets)
         .search_paths(&search_paths)
@@ -252,12 +298,14 @@ pub fn reflect_shared_module_types(
     Ok(type_to_module)
 }
 
+#[cfg(feature = "shader-compiler")]
 pub struct CompiledShader {
     pub entry_point_name: CString,
     pub stage: slang::Stage,
     pub shader_bytecode: Vec<u8>,
 }
 
+#[cfg(feature = "shader-compiler")]
 impl CompiledShader {
     /// converts compiled spv to vulkan-readable u32s
     #[cfg(debug_assertions)]
@@ -267,6 +315,7 @@ impl CompiledShader {
     }
 }
 
+#[cfg(feature = "shader-compiler")]
 impl std::fmt::Debug for CompiledShader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CompiledShader")
@@ -276,6 +325,7 @@ impl std::fmt::Debug for CompiledShader {
     }
 }
 
+#[cfg(feature = "shader-compiler")]
 fn compile_shader(
     entry_point: &slang::EntryPoint,
     session: &slang::Session,
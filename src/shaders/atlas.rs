@@ -27,10 +27,16 @@ pub trait ShaderAtlasEntry {
 
     fn precompiled_shaders(&self) -> PrecompiledShaders;
 
+    /// a mesh shader's optional task stage
+    fn precompiled_task_shader(&self) -> Option<PrecompiledShader> {
+        None
+    }
+
     fn pipeline_layout(&self) -> &ReflectedPipelineLayout;
 }
 
 pub struct PrecompiledShaders {
+    /// the vertex stage, or the mesh stage of a mesh shader
     pub vert: PrecompiledShader,
     pub frag: PrecompiledShader,
 }
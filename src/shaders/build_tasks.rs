@@ -1,15 +1,22 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use askama::Template;
 use heck::ToSnakeCase;
+use rayon::prelude::*;
 
+use crate::renderer::SPLIT_SCREEN_VIEWS_TYPE_NAME;
+use crate::renderer::frame_globals::FRAME_GLOBALS_TYPE_NAME;
 use crate::util::relative_path;
 
 use super::json::*;
 use super::{ReflectedComputeShader, ReflectedShader};
 use super::{prepare_reflected_compute_shader, prepare_reflected_shader};
 
+mod sprite_atlas;
+pub use sprite_atlas::{SpriteAtlasConfig, write_sprite_atlases};
+
 pub struct Config {
     /// whether to write rust code (or only shader spirv & json)
     pub generate_rust_source: bool,
@@ -23,8 +30,21 @@ pub struct Config {
 
 const SHADER_FILE_SUFFIX: &str = ".shader.slang";
 const COMPUTE_SHADER_FILE_SUFFIX: &str = ".compute.slang";
+/// the generated uniform struct for globals declared outside a parameter block
+const LOOSE_GLOBALS_TYPE_NAME: &str = "GlobalUniforms";
+/// the generated struct for a push constant global, or for uniform entry point parameters,
+/// which slang puts in push constants
+const PUSH_CONSTANTS_TYPE_NAME: &str = "PushConstants";
+
+/// How long one shader took to compile, reflect, and write to disk
+#[derive(Debug, Clone)]
+pub struct ShaderTiming {
+    pub source_file_name: String,
+    pub duration: Duration,
+}
 
-pub fn write_precompiled_shaders(config: Config) -> anyhow::Result<()> {
+/// Compiles every shader in the source dir, returning each one's timing in source dir order
+pub fn write_precompiled_shaders(config: Config) -> anyhow::Result<Vec<ShaderTiming>> {
     let slang_file_names: Vec<_> = std::fs::read_dir(&config.shaders_source_dir)?
         .filter_map(|entry_res| entry_res.ok())
         .map(|dir_entry| dir_entry.path())
@@ -57,77 +77,34 @@ pub fn write_precompiled_shaders(config: Config) -> anyhow::Result<()> {
     let type_to_module = reflect_slang_module_types(&config.shaders_source_dir);
 
     let search_path = config.shaders_source_dir.to_str().unwrap();
+    std::fs::create_dir_all(&config.compiled_shaders_dir)?;
+
+    // Pass 1: Compile all shaders in parallel, write SPIR-V/JSON, collect intermediate build data.
+    // Each compile creates its own slang global session, since those can't be shared across threads.
+    let graphics_results: Vec<(Option<GraphicsShaderData>, ShaderTiming)> = slang_file_names
+        .par_iter()
+        .map(|slang_file_name| {
+            compile_graphics_shader(&config, slang_file_name, search_path, &type_to_module)
+        })
+        .collect::<anyhow::Result<_>>()?;
 
-    // Pass 1: Compile all shaders, write SPIR-V/JSON, collect intermediate build data
-    let mut graphics_data: Vec<GraphicsShaderData> = vec![];
-    let mut compute_data: Vec<ComputeShaderData> = vec![];
-
-    for slang_file_name in &slang_file_names {
-        let ReflectedShader {
-            vertex_shader,
-            fragment_shader,
-            reflection_json,
-        } = prepare_reflected_shader(slang_file_name, search_path)?;
-
-        let source_file_name = &reflection_json.source_file_name;
-        std::fs::create_dir_all(&config.compiled_shaders_dir)?;
-
-        let reflection_json_str = serde_json::to_string_pretty(&reflection_json)?;
-        let reflection_json_file_name = source_file_name.replace(SHADER_FILE_SUFFIX, ".json");
-        std::fs::write(
-            config.compiled_shaders_dir.join(&reflection_json_file_name),
-            reflection_json_str,
-        )?;
-
-        let spv_vert_file_name = source_file_name.replace(SHADER_FILE_SUFFIX, ".vert.spv");
-        std::fs::write(
-            config.compiled_shaders_dir.join(&spv_vert_file_name),
-            vertex_shader.shader_bytecode.as_slice(),
-        )?;
-
-        let spv_frag_file_name = source_file_name.replace(SHADER_FILE_SUFFIX, ".frag.spv");
-        std::fs::write(
-            config.compiled_shaders_dir.join(&spv_frag_file_name),
-            fragment_shader.shader_bytecode.as_slice(),
-        )?;
+    let compute_results: Vec<(Option<ComputeShaderData>, ShaderTiming)> = compute_slang_file_names
+        .par_iter()
+        .map(|slang_file_name| {
+            compile_compute_shader(&config, slang_file_name, search_path, &type_to_module)
+        })
+        .collect::<anyhow::Result<_>>()?;
 
-        if config.generate_rust_source {
-            graphics_data.push(collect_graphics_shader_data(
-                &reflection_json,
-                &type_to_module,
-            ));
-        }
+    let mut timings = vec![];
+    let mut graphics_data: Vec<GraphicsShaderData> = vec![];
+    for (data, timing) in graphics_results {
+        graphics_data.extend(data);
+        timings.push(timing);
     }
-
-    for slang_file_name in &compute_slang_file_names {
-        let ReflectedComputeShader {
-            compute_shader,
-            reflection_json,
-        } = prepare_reflected_compute_shader(slang_file_name, search_path)?;
-
-        let source_file_name = &reflection_json.source_file_name;
-        std::fs::create_dir_all(&config.compiled_shaders_dir)?;
-
-        let reflection_json_str = serde_json::to_string_pretty(&reflection_json)?;
-        let reflection_json_file_name =
-            source_file_name.replace(COMPUTE_SHADER_FILE_SUFFIX, ".comp.json");
-        std::fs::write(
-            config.compiled_shaders_dir.join(&reflection_json_file_name),
-            reflection_json_str,
-        )?;
-
-        let spv_comp_file_name = source_file_name.replace(COMPUTE_SHADER_FILE_SUFFIX, ".comp.spv");
-        std::fs::write(
-            config.compiled_shaders_dir.join(&spv_comp_file_name),
-            compute_shader.shader_bytecode.as_slice(),
-        )?;
-
-        if config.generate_rust_source {
-            compute_data.push(collect_compute_shader_data(
-                &reflection_json,
-                &type_to_module,
-            ));
-        }
+    let mut compute_data: Vec<ComputeShaderData> = vec![];
+    for (data, timing) in compute_results {
+        compute_data.extend(data);
+        timings.push(timing);
     }
 
     if config.generate_rust_source {
@@ -186,11 +163,286 @@ pub fn write_precompiled_shaders(config: Config) -> anyhow::Result<()> {
         );
 
         for source_file in &generated_source_files {
-            write_generated_file(&config, source_file)?;
+            write_generated_file(&config.rust_source_dir, source_file)?;
         }
     }
 
-    Ok(())
+    Ok(timings)
+}
+
+/// Compiles one graphics shader and writes its SPIR-V and reflection json,
+/// returning its codegen data when generating rust source
+fn compile_graphics_shader(
+    config: &Config,
+    slang_file_name: &str,
+    search_path: &str,
+    type_to_module: &HashMap<String, String>,
+) -> anyhow::Result<(Option<GraphicsShaderData>, ShaderTiming)> {
+    let start = Instant::now();
+
+    let ReflectedShader {
+        vertex_shader,
+        task_shader,
+        fragment_shader,
+        reflection_json,
+    } = prepare_reflected_shader(slang_file_name, search_path)?;
+
+    let source_file_name = &reflection_json.source_file_name;
+
+    let reflection_json_str = serde_json::to_string_pretty(&reflection_json)?;
+    let reflection_json_file_name = source_file_name.replace(SHADER_FILE_SUFFIX, ".json");
+    std::fs::write(
+        config.compiled_shaders_dir.join(&reflection_json_file_name),
+        reflection_json_str,
+    )?;
+
+    let vert_suffix = if reflection_json.is_mesh_shader() {
+        ".mesh.spv"
+    } else {
+        ".vert.spv"
+    };
+    let spv_vert_file_name = source_file_name.replace(SHADER_FILE_SUFFIX, vert_suffix);
+    std::fs::write(
+        config.compiled_shaders_dir.join(&spv_vert_file_name),
+        vertex_shader.shader_bytecode.as_slice(),
+    )?;
+
+    if let Some(task_shader) = &task_shader {
+        let spv_task_file_name = source_file_name.replace(SHADER_FILE_SUFFIX, ".task.spv");
+        std::fs::write(
+            config.compiled_shaders_dir.join(&spv_task_file_name),
+            task_shader.shader_bytecode.as_slice(),
+        )?;
+    }
+
+    let spv_frag_file_name = source_file_name.replace(SHADER_FILE_SUFFIX, ".frag.spv");
+    std::fs::write(
+        config.compiled_shaders_dir.join(&spv_frag_file_name),
+        fragment_shader.shader_bytecode.as_slice(),
+    )?;
+
+    let data = config
+        .generate_rust_source
+        .then(|| collect_graphics_shader_data(&reflection_json, type_to_module));
+
+    let timing = ShaderTiming {
+        source_file_name: slang_file_name.to_string(),
+        duration: start.elapsed(),
+    };
+
+    Ok((data, timing))
+}
+
+/// Compiles one compute shader and writes its SPIR-V and reflection json,
+/// returning its codegen data when generating rust source
+fn compile_compute_shader(
+    config: &Config,
+    slang_file_name: &str,
+    search_path: &str,
+    type_to_module: &HashMap<String, String>,
+) -> anyhow::Result<(Option<ComputeShaderData>, ShaderTiming)> {
+    let start = Instant::now();
+
+    let ReflectedComputeShader {
+        compute_shader,
+        reflection_json,
+    } = prepare_reflected_compute_shader(slang_file_name, search_path)?;
+
+    let source_file_name = &reflection_json.source_file_name;
+
+    let reflection_json_str = serde_json::to_string_pretty(&reflection_json)?;
+    let reflection_json_file_name =
+        source_file_name.replace(COMPUTE_SHADER_FILE_SUFFIX, ".comp.json");
+    std::fs::write(
+        config.compiled_shaders_dir.join(&reflection_json_file_name),
+        reflection_json_str,
+    )?;
+
+    let spv_comp_file_name = source_file_name.replace(COMPUTE_SHADER_FILE_SUFFIX, ".comp.spv");
+    std::fs::write(
+        config.compiled_shaders_dir.join(&spv_comp_file_name),
+        compute_shader.shader_bytecode.as_slice(),
+    )?;
+
+    let data = config
+        .generate_rust_source
+        .then(|| collect_compute_shader_data(&reflection_json, type_to_module));
+
+    let timing = ShaderTiming {
+        source_file_name: slang_file_name.to_string(),
+        duration: start.elapsed(),
+    };
+
+    Ok((data, timing))
+}
+
+/// Generates the push constants struct from a `[[vk::push_constant]]` global,
+/// or from scalar `uniform` entry point parameters.
+/// Slang gives each entry point its own push constant range at offset 0,
+/// so only one stage may declare them, and not alongside a global.
+fn add_push_constants(
+    reflection_json: &ReflectionJson,
+    struct_defs: &mut Vec<GeneratedStructDefinition>,
+) {
+    let global_push_constants =
+        reflection_json
+            .global_parameters
+            .iter()
+            .find_map(|param| match param {
+                GlobalParameter::PushConstants(push_constants) => Some(push_constants),
+                _ => None,
+            });
+
+    let entry_points = [
+        &reflection_json.vertex_entry_point,
+        &reflection_json.fragment_entry_point,
+    ];
+
+    let mut push_constant_stages = vec![];
+    let mut push_constant_fields = vec![];
+    for entry_point in entry_points {
+        let mut stage_fields = vec![];
+        for param in &entry_point.parameters {
+            let EntryPointParameter::Scalar(ScalarEntryPointParameter::Bound(scalar)) = param
+            else {
+                continue;
+            };
+
+            let Binding::Uniform(uniform) = &scalar.binding else {
+                panic!(
+                    "{}: entry point parameter '{}' has no semantic or uniform binding; \
+                    put vertex inputs in a struct, and mark cpu-provided values `uniform`",
+                    reflection_json.source_file_name, scalar.parameter_name,
+                );
+            };
+
+            stage_fields.push(StructField::Scalar(ScalarStructField {
+                field_name: scalar.parameter_name.clone(),
+                binding: Binding::Uniform(OffsetSizeBinding {
+                    offset: uniform.offset,
+                    size: uniform.size,
+                }),
+                scalar_type: scalar.scalar_type,
+            }));
+        }
+
+        if !stage_fields.is_empty() {
+            push_constant_stages.push(entry_point.entry_point_name.as_str());
+            push_constant_fields = stage_fields;
+        }
+    }
+
+    if push_constant_stages.len() > 1 {
+        panic!(
+            "{}: uniform parameters in both '{}' and '{}' would alias the same push constants; \
+            declare them on one entry point",
+            reflection_json.source_file_name, push_constant_stages[0], push_constant_stages[1],
+        );
+    }
+
+    let push_constant_fields = match (global_push_constants, push_constant_stages.first()) {
+        (Some(global), Some(stage)) => panic!(
+            "{}: uniform parameters in '{}' would alias the push constant global '{}'; \
+            move them into it",
+            reflection_json.source_file_name, stage, global.parameter_name,
+        ),
+        (Some(global), None) => &global.element_type.fields,
+        (None, _) => &push_constant_fields,
+    };
+
+    if push_constant_fields.is_empty() {
+        return;
+    }
+
+    let (fields, struct_alignment, expected_size) =
+        generate_std430_struct_fields(push_constant_fields, struct_defs);
+    struct_defs.push(GeneratedStructDefinition {
+        type_name: PUSH_CONSTANTS_TYPE_NAME.to_string(),
+        source_module: None,
+        fields,
+        trait_derives: vec!["Debug", "Clone", "Serialize"],
+        alignment: Some(Alignment::Std430 { struct_alignment }),
+        expected_size: Some(expected_size),
+    });
+}
+
+/// Generates each descriptor set's uniform struct, and its resources in layout order
+fn add_global_descriptor_sets(
+    global_parameters: &[GlobalParameter],
+    struct_defs: &mut Vec<GeneratedStructDefinition>,
+    required_resources: &mut Vec<RequiredResource>,
+) {
+    for (set, descriptor_set) in global_descriptor_sets(global_parameters).iter().enumerate() {
+        // the renderer binds its own buffer, and FrameGlobals is its own type
+        if descriptor_set.type_name == Some(FRAME_GLOBALS_TYPE_NAME) {
+            required_resources.push(RequiredResource {
+                field_name: descriptor_set.parameter_path.to_snake_case(),
+                resource_type: RequiredResourceType::FrameGlobals,
+                doc_lines: vec![],
+            });
+            continue;
+        }
+        // likewise for the split-screen cameras
+        if descriptor_set.type_name == Some(SPLIT_SCREEN_VIEWS_TYPE_NAME) {
+            required_resources.push(RequiredResource {
+                field_name: descriptor_set.parameter_path.to_snake_case(),
+                resource_type: RequiredResourceType::SplitScreenViews,
+                doc_lines: vec![],
+            });
+            continue;
+        }
+
+        let (uniform_fields, _struct_alignment, expected_size) =
+            generate_std140_struct_fields(descriptor_set.fields, struct_defs);
+
+        for field in descriptor_set.fields {
+            if let Some(req) = required_resource(field, set) {
+                required_resources.push(req);
+            }
+        }
+
+        let has_uniform_fields = !uniform_fields.is_empty();
+
+        // loose globals have no slang struct, so theirs is named here,
+        // and only generated when there's uniform data to hold
+        let (type_name, description) = match descriptor_set.type_name {
+            Some(type_name) => (
+                type_name.to_string(),
+                format!("slang parameter block `{}`", descriptor_set.parameter_path),
+            ),
+            None => (
+                LOOSE_GLOBALS_TYPE_NAME.to_string(),
+                "slang uniforms outside any parameter block".to_string(),
+            ),
+        };
+        if descriptor_set.type_name.is_some() || has_uniform_fields {
+            // two blocks may share an element type
+            try_add_struct_def(
+                struct_defs,
+                GeneratedStructDefinition {
+                    type_name: type_name.clone(),
+                    source_module: None,
+                    fields: uniform_fields,
+                    trait_derives: vec!["Debug", "Clone", "Serialize"],
+                    alignment: Some(Alignment::Std140),
+                    expected_size: Some(expected_size),
+                },
+            );
+        }
+
+        if has_uniform_fields {
+            // slang puts a descriptor set's uniform data at binding 0
+            let param_name = descriptor_set
+                .parameter_path
+                .replace('.', "_")
+                .to_snake_case();
+            required_resources.push(RequiredResource {
+                field_name: format!("{param_name}_buffer"),
+                resource_type: RequiredResourceType::UniformBuffer(type_name),
+                doc_lines: vec![format!("{description}: set {set}, binding 0")],
+            })
+        }
+    }
 }
 
 fn add_top_level_rust_modules(
@@ -241,7 +493,8 @@ fn add_top_level_rust_modules(
 
     let top_generated_module = GeneratedFile {
         relative_path: relative_path(["generated.rs"]),
-        content: "pub mod shader_atlas;".to_string(),
+        // the sprite atlas module is written by write_sprite_atlases
+        content: "pub mod shader_atlas;\npub mod sprite_atlas;".to_string(),
     };
     generated_source_files.push(top_generated_module);
 }
@@ -274,10 +527,12 @@ fn collect_graphics_shader_data(
             RequiredResource {
                 field_name: "vertices".to_string(),
                 resource_type: RequiredResourceType::VertexBuffer,
+                doc_lines: vec![],
             },
             RequiredResource {
                 field_name: "indices".to_string(),
                 resource_type: RequiredResourceType::IndexBuffer,
+                doc_lines: vec![],
             },
         ]
     } else {
@@ -288,17 +543,23 @@ fn collect_graphics_shader_data(
     for vert_param in &reflection_json.vertex_entry_point.parameters {
         match vert_param {
             EntryPointParameter::Scalar(ScalarEntryPointParameter::Semantic(_)) => {}
-            EntryPointParameter::Scalar(ScalarEntryPointParameter::Bound(_)) => todo!(),
+            // uniform scalars are gathered into push constants below
+            EntryPointParameter::Scalar(ScalarEntryPointParameter::Bound(_)) => {}
 
             EntryPointParameter::Struct(struct_param) => {
                 vertex_type_name = Some(struct_param.type_name.to_string());
 
                 let mut generated_fields = vec![];
+                // parallel to generated_fields; Some for a semantic's format override
+                let mut override_formats = vec![];
                 for field in &struct_param.fields {
-                    if let Some(generated_field) =
+                    if let Some(mut generated_field) =
                         gather_struct_defs(field, &mut struct_defs, Some(Alignment::Std140))
                     {
+                        let format = vertex_format_override(field)
+                            .map(|(format, vector)| format.apply(vector, &mut generated_field));
                         generated_fields.push(generated_field);
+                        override_formats.push(format);
                     };
                 }
 
@@ -313,11 +574,15 @@ fn collect_graphics_shader_data(
 
                 let mut attribute_descriptions = vec![];
                 for (location, field) in def.fields.iter().enumerate() {
-                    let format = match field.type_name.as_str() {
-                        "glam::Vec3" => "ash::vk::Format::R32G32B32_SFLOAT",
-                        "glam::Vec2" => "ash::vk::Format::R32G32_SFLOAT",
-                        "u32" => "ash::vk::Format::R32_UINT",
-                        other => todo!("field without vk format in entry point parameter: {other}"),
+                    let format = match (override_formats[location], field.type_name.as_str()) {
+                        (Some(format), _) => format,
+                        (None, "glam::Vec4") => "ash::vk::Format::R32G32B32A32_SFLOAT",
+                        (None, "glam::Vec3") => "ash::vk::Format::R32G32B32_SFLOAT",
+                        (None, "glam::Vec2") => "ash::vk::Format::R32G32_SFLOAT",
+                        (None, "u32") => "ash::vk::Format::R32_UINT",
+                        (None, other) => {
+                            todo!("field without vk format in entry point parameter: {other}")
+                        }
                     };
 
                     let attr = VertexAttributeDescription {
@@ -339,44 +604,23 @@ fn collect_graphics_shader_data(
         }
     }
 
-    for GlobalParameter::ParameterBlock(parameter_block) in &reflection_json.global_parameters {
-        let (param_block_fields, _struct_alignment, expected_size) =
-            generate_std140_struct_fields(&parameter_block.element_type.fields, &mut struct_defs);
-
-        for field in &parameter_block.element_type.fields {
-            if let Some(req) = required_resource(field) {
-                required_resources.push(req);
-            }
-        }
-
-        let has_uniform_fields = !param_block_fields.is_empty();
-
-        let type_name = &parameter_block.element_type.type_name;
-        struct_defs.push(GeneratedStructDefinition {
-            type_name: type_name.to_string(),
-            source_module: None,
-            fields: param_block_fields,
-            trait_derives: vec!["Debug", "Clone", "Serialize"],
-            alignment: Some(Alignment::Std140),
-            expected_size: Some(expected_size),
-        });
+    add_push_constants(reflection_json, &mut struct_defs);
 
-        let param_name = parameter_block.parameter_name.to_snake_case();
-        let element_type_name = parameter_block.element_type.type_name.clone();
-        if has_uniform_fields {
-            required_resources.push(RequiredResource {
-                field_name: format!("{param_name}_buffer"),
-                resource_type: RequiredResourceType::UniformBuffer(element_type_name),
-            })
-        }
-    }
+    add_global_descriptor_sets(
+        &reflection_json.global_parameters,
+        &mut struct_defs,
+        &mut required_resources,
+    );
 
     struct_defs.reverse();
 
     let resources_fields = required_resources
         .iter()
-        .map(|r| {
+        .filter_map(|r| {
             let type_name = match &r.resource_type {
+                RequiredResourceType::FrameGlobals | RequiredResourceType::SplitScreenViews => {
+                    return None;
+                }
                 RequiredResourceType::VertexBuffer => {
                     let vertex_type_name = vertex_type_name
                         .as_ref()
@@ -389,9 +633,16 @@ fn collect_graphics_shader_data(
                     format!("&'a UniformBufferHandle<{element_type_name}>")
                 }
                 RequiredResourceType::StorageTexture2D => "&'a StorageTextureHandle".to_string(),
+                RequiredResourceType::AccelerationStructure => {
+                    "&'a AccelerationStructureHandle".to_string()
+                }
+                RequiredResourceType::RawBuffer => "&'a RawBufferHandle".to_string(),
             };
 
-            GeneratedStructFieldDefinition::new(r.field_name.clone(), type_name)
+            Some(GeneratedStructFieldDefinition {
+                doc_lines: r.doc_lines.clone(),
+                ..GeneratedStructFieldDefinition::new(r.field_name.clone(), type_name)
+            })
         })
         .collect();
 
@@ -411,8 +662,10 @@ fn collect_graphics_shader_data(
 
     // NOTE these must be in descriptor set layout order in the reflection json
     let mut resources_texture_fields: Vec<String> = vec![];
-    let mut resources_uniform_buffer_fields: Vec<String> = vec![];
+    let mut uniform_buffer_handles: Vec<String> = vec![];
     let mut resources_storage_texture_fields: Vec<String> = vec![];
+    let mut resources_acceleration_structure_fields: Vec<String> = vec![];
+    let mut resources_raw_buffer_fields: Vec<String> = vec![];
     for res in &required_resources {
         match res.resource_type {
             RequiredResourceType::VertexBuffer => {}
@@ -421,11 +674,27 @@ fn collect_graphics_shader_data(
                 resources_texture_fields.push(res.field_name.clone());
             }
             RequiredResourceType::UniformBuffer(_) => {
-                resources_uniform_buffer_fields.push(res.field_name.clone());
+                uniform_buffer_handles.push(format!(
+                    "RawUniformBufferHandle::from_typed(resources.{})",
+                    res.field_name
+                ));
+            }
+            RequiredResourceType::FrameGlobals => {
+                uniform_buffer_handles.push("RawUniformBufferHandle::frame_globals()".to_string());
+            }
+            RequiredResourceType::SplitScreenViews => {
+                uniform_buffer_handles
+                    .push("RawUniformBufferHandle::split_screen_views()".to_string());
             }
             RequiredResourceType::StorageTexture2D => {
                 resources_storage_texture_fields.push(res.field_name.clone());
             }
+            RequiredResourceType::AccelerationStructure => {
+                resources_acceleration_structure_fields.push(res.field_name.clone());
+            }
+            RequiredResourceType::RawBuffer => {
+                resources_raw_buffer_fields.push(res.field_name.clone());
+            }
         }
     }
 
@@ -433,9 +702,14 @@ fn collect_graphics_shader_data(
         shader_name: shader_name.clone(),
         shader_type_name: "Shader".to_string(),
         vertex_type_name,
+        mesh_shader: reflection_json.is_mesh_shader(),
+        task_shader: reflection_json.task_entry_point.is_some(),
+        writes_velocity: reflection_json.writes_velocity,
         resources_texture_fields,
-        resources_uniform_buffer_fields,
+        uniform_buffer_handles,
         resources_storage_texture_fields,
+        resources_acceleration_structure_fields,
+        resources_raw_buffer_fields,
     };
 
     // Tag struct defs with source module info
@@ -524,8 +798,10 @@ struct GeneratedComputeShaderImpl {
     shader_type_name: String,
     workgroup_size: [u32; 3],
     resources_texture_fields: Vec<String>,
-    resources_uniform_buffer_fields: Vec<String>,
+    /// the uniform buffer handle expressions for the pipeline config
+    uniform_buffer_handles: Vec<String>,
     resources_storage_texture_fields: Vec<String>,
+    resources_raw_buffer_fields: Vec<String>,
 }
 
 #[derive(Clone)]
@@ -533,20 +809,35 @@ struct GeneratedShaderImpl {
     shader_name: String,
     shader_type_name: String,
     vertex_type_name: Option<String>,
+    /// whether the vertex stage is a mesh stage
+    mesh_shader: bool,
+    task_shader: bool,
+    /// whether the fragment stage writes the velocity target
+    writes_velocity: bool,
     resources_texture_fields: Vec<String>,
-    resources_uniform_buffer_fields: Vec<String>,
+    /// the uniform buffer handle expressions for the pipeline config
+    uniform_buffer_handles: Vec<String>,
     resources_storage_texture_fields: Vec<String>,
+    resources_acceleration_structure_fields: Vec<String>,
+    resources_raw_buffer_fields: Vec<String>,
 }
 
 impl GeneratedShaderImpl {
     fn draw_call(&self) -> &str {
-        if self.vertex_type_name.is_some() {
+        if self.mesh_shader {
+            "DrawMeshTasks"
+        } else if self.vertex_type_name.is_some() {
             "DrawIndexed"
         } else {
             "DrawVertexCount"
         }
     }
 
+    /// the compiled file extension of the stage before the fragment stage
+    fn vert_spv_extension(&self) -> &str {
+        if self.mesh_shader { "mesh" } else { "vert" }
+    }
+
     fn vertex_type_or_never(&self) -> &str {
         self.vertex_type_name.as_deref().unwrap_or("NoVertex")
     }
@@ -568,55 +859,40 @@ fn collect_compute_shader_data(
     let mut struct_defs = vec![];
     let mut required_resources = vec![];
 
-    for GlobalParameter::ParameterBlock(parameter_block) in &reflection_json.global_parameters {
-        let (param_block_fields, _struct_alignment, expected_size) =
-            generate_std140_struct_fields(&parameter_block.element_type.fields, &mut struct_defs);
-
-        for field in &parameter_block.element_type.fields {
-            if let Some(req) = required_resource(field) {
-                required_resources.push(req);
-            }
-        }
-
-        let has_uniform_fields = !param_block_fields.is_empty();
-
-        let type_name = &parameter_block.element_type.type_name;
-        struct_defs.push(GeneratedStructDefinition {
-            type_name: type_name.to_string(),
-            source_module: None,
-            fields: param_block_fields,
-            trait_derives: vec!["Debug", "Clone", "Serialize"],
-            alignment: Some(Alignment::Std140),
-            expected_size: Some(expected_size),
-        });
-
-        let param_name = parameter_block.parameter_name.to_snake_case();
-        let element_type_name = parameter_block.element_type.type_name.clone();
-        if has_uniform_fields {
-            required_resources.push(RequiredResource {
-                field_name: format!("{param_name}_buffer"),
-                resource_type: RequiredResourceType::UniformBuffer(element_type_name),
-            })
-        }
-    }
+    add_global_descriptor_sets(
+        &reflection_json.global_parameters,
+        &mut struct_defs,
+        &mut required_resources,
+    );
 
     struct_defs.reverse();
 
     let resources_fields = required_resources
         .iter()
-        .map(|r| {
+        .filter_map(|r| {
             let type_name = match &r.resource_type {
+                RequiredResourceType::FrameGlobals | RequiredResourceType::SplitScreenViews => {
+                    return None;
+                }
                 RequiredResourceType::VertexBuffer | RequiredResourceType::IndexBuffer => {
                     unreachable!("compute shaders don't have vertex/index buffers")
                 }
+                RequiredResourceType::AccelerationStructure => panic!(
+                    "{}: acceleration structures are only supported in graphics shaders",
+                    reflection_json.source_file_name
+                ),
                 RequiredResourceType::Texture => "&'a TextureHandle".to_string(),
                 RequiredResourceType::UniformBuffer(element_type_name) => {
                     format!("&'a UniformBufferHandle<{element_type_name}>")
                 }
                 RequiredResourceType::StorageTexture2D => "&'a StorageTextureHandle".to_string(),
+                RequiredResourceType::RawBuffer => "&'a RawBufferHandle".to_string(),
             };
 
-            GeneratedStructFieldDefinition::new(r.field_name.clone(), type_name)
+            Some(GeneratedStructFieldDefinition {
+                doc_lines: r.doc_lines.clone(),
+                ..GeneratedStructFieldDefinition::new(r.field_name.clone(), type_name)
+            })
         })
         .collect();
 
@@ -636,16 +912,32 @@ fn collect_compute_shader_data(
 
     // NOTE these must be in descriptor set layout order in the reflection json
     let mut resources_texture_fields: Vec<String> = vec![];
-    let mut resources_uniform_buffer_fields: Vec<String> = vec![];
+    let mut uniform_buffer_handles: Vec<String> = vec![];
     let mut resources_storage_texture_fields: Vec<String> = vec![];
+    let mut resources_raw_buffer_fields: Vec<String> = vec![];
     for res in &required_resources {
         match res.resource_type {
-            RequiredResourceType::VertexBuffer | RequiredResourceType::IndexBuffer => {}
+            RequiredResourceType::VertexBuffer
+            | RequiredResourceType::IndexBuffer
+            | RequiredResourceType::AccelerationStructure => {}
+            RequiredResourceType::RawBuffer => {
+                resources_raw_buffer_fields.push(res.field_name.clone());
+            }
             RequiredResourceType::Texture => {
                 resources_texture_fields.push(res.field_name.clone());
             }
             RequiredResourceType::UniformBuffer(_) => {
-                resources_uniform_buffer_fields.push(res.field_name.clone());
+                uniform_buffer_handles.push(format!(
+                    "RawUniformBufferHandle::from_typed(resources.{})",
+                    res.field_name
+                ));
+            }
+            RequiredResourceType::FrameGlobals => {
+                uniform_buffer_handles.push("RawUniformBufferHandle::frame_globals()".to_string());
+            }
+            RequiredResourceType::SplitScreenViews => {
+                uniform_buffer_handles
+                    .push("RawUniformBufferHandle::split_screen_views()".to_string());
             }
             RequiredResourceType::StorageTexture2D => {
                 resources_storage_texture_fields.push(res.field_name.clone());
@@ -658,8 +950,9 @@ fn collect_compute_shader_data(
         shader_type_name: "Shader".to_string(),
         workgroup_size: reflection_json.workgroup_size,
         resources_texture_fields,
-        resources_uniform_buffer_fields,
+        uniform_buffer_handles,
         resources_storage_texture_fields,
+        resources_raw_buffer_fields,
     };
 
     // Tag struct defs with source module info
@@ -709,6 +1002,96 @@ fn render_compute_shader_file(
     }
 }
 
+/// The convention from the `color` module, repeated on generated color fields
+const COLOR_FIELD_DOC: &str =
+    "linear color, straight alpha; convert sRGB with `crate::color::srgb8`";
+
+/// Whether a vector field's name marks it as a color, for its doc comment
+fn is_color_field(field_name: &str) -> bool {
+    field_name.contains("color") || field_name.contains("colour") || field_name.ends_with("tint")
+}
+
+/// A compact vertex attribute format, chosen by a suffix on a vertex input's
+/// user semantic, e.g. `float4 color : COLOR_UNORM8;`
+///
+/// The shader still reads floats; the input assembler converts from the packed
+/// format, and the generated field holds the packed components
+/// (see the packing functions in `renderer::vertex_description`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VertexFormatOverride {
+    /// `_UNORM8`: a u8 per component, read as 0.0..=1.0
+    Unorm8,
+    /// `_SNORM8`: an i8 per component, read as -1.0..=1.0
+    Snorm8,
+    /// `_HALF`: a half float's bits per component
+    Half,
+}
+
+impl VertexFormatOverride {
+    fn from_semantic(semantic_name: &str) -> Option<Self> {
+        let semantic_name = semantic_name.to_ascii_uppercase();
+        [
+            ("_UNORM8", Self::Unorm8),
+            ("_SNORM8", Self::Snorm8),
+            ("_HALF", Self::Half),
+        ]
+        .into_iter()
+        .find(|(suffix, _)| semantic_name.ends_with(suffix))
+        .map(|(_, format)| format)
+    }
+
+    /// Retypes a generated vertex field to the packed components,
+    /// returning the attribute's vk format
+    fn apply(
+        self,
+        vector: &BoundVectorStructField,
+        field: &mut GeneratedStructFieldDefinition,
+    ) -> &'static str {
+        let VectorElementType::Scalar(element_type) = &vector.element_type;
+        let count = vector.element_count;
+        assert!(
+            matches!(element_type.scalar_type, ScalarType::Float32),
+            "vertex field '{}': {self:?} needs a float vector",
+            vector.field_name,
+        );
+
+        let (element, format, pack_fn) = match (self, count) {
+            (Self::Unorm8, 2) => ("u8", "ash::vk::Format::R8G8_UNORM", "unorm8"),
+            (Self::Unorm8, 4) => ("u8", "ash::vk::Format::R8G8B8A8_UNORM", "unorm8"),
+            (Self::Snorm8, 2) => ("i8", "ash::vk::Format::R8G8_SNORM", "snorm8"),
+            (Self::Snorm8, 4) => ("i8", "ash::vk::Format::R8G8B8A8_SNORM", "snorm8"),
+            (Self::Half, 2) => ("u16", "ash::vk::Format::R16G16_SFLOAT", "half"),
+            (Self::Half, 4) => ("u16", "ash::vk::Format::R16G16B16A16_SFLOAT", "half"),
+            // three-component 8 and 16 bit formats are rarely supported for vertex input
+            (_, count) => panic!(
+                "vertex field '{}': {self:?} needs a float2 or float4, not {count} components",
+                vector.field_name,
+            ),
+        };
+
+        field.type_name = format!("[{element}; {count}]");
+        field.doc_lines = vec![format!(
+            "`{}` from its `{}` semantic; pack with `crate::renderer::vertex_description::{pack_fn}`",
+            format.trim_start_matches("ash::vk::Format::"),
+            vector.semantic_name.as_deref().unwrap_or_default(),
+        )];
+
+        format
+    }
+}
+
+/// A vertex input field's format override, from its semantic
+fn vertex_format_override(
+    field: &StructField,
+) -> Option<(VertexFormatOverride, &BoundVectorStructField)> {
+    let StructField::Vector(VectorStructField::Bound(vector)) = field else {
+        return None;
+    };
+    let format = VertexFormatOverride::from_semantic(vector.semantic_name.as_deref()?)?;
+
+    Some((format, vector))
+}
+
 /// Generates fields for a std430 storage buffer struct, inserting padding as needed.
 /// Returns (fields, struct_alignment, expected_size).
 fn generate_std430_struct_fields(
@@ -741,6 +1124,10 @@ fn generate_std430_struct_fields(
         check_rust_placeable(&gen_field, expected_offset);
         gen_field.offset = Some(expected_offset);
         gen_field.size = Some(field_size);
+        gen_field.doc_lines.push(format!(
+            "slang `{}`: offset {expected_offset}, size {field_size}",
+            struct_field_name(source_field),
+        ));
 
         // Track max alignment for struct alignment calculation
         let field_align = field_alignment(&gen_field.type_name);
@@ -811,6 +1198,10 @@ fn generate_std140_struct_fields(
         check_rust_placeable(&gen_field, expected_offset);
         gen_field.offset = Some(expected_offset);
         gen_field.size = Some(field_size);
+        gen_field.doc_lines.push(format!(
+            "slang `{}`: offset {expected_offset}, size {field_size}",
+            struct_field_name(source_field),
+        ));
 
         // Insert padding if needed
         if expected_offset > current_offset {
@@ -852,19 +1243,13 @@ fn gather_struct_defs(
     match field {
         // textures are handled via resources; not a field of the uniform buffer struct
         StructField::Resource(_) => None,
+        // nested blocks get their own descriptor set and uniform struct
+        StructField::ParameterBlock(_) => None,
 
-        StructField::Scalar(scalar) => {
-            let field_type = match scalar.scalar_type {
-                ScalarType::Float32 => "f32",
-                ScalarType::Uint32 => "u32",
-                ScalarType::Uint64 => "u64",
-            };
-
-            Some(GeneratedStructFieldDefinition::new(
-                scalar.field_name.to_snake_case(),
-                field_type.to_string(),
-            ))
-        }
+        StructField::Scalar(scalar) => Some(GeneratedStructFieldDefinition::new(
+            scalar.field_name.to_snake_case(),
+            scalar_type_name(scalar.scalar_type).to_string(),
+        )),
 
         StructField::Pointer(ptr) => {
             // The pointee struct is emitted like a StructuredBuffer element
@@ -872,26 +1257,44 @@ fn gather_struct_defs(
             // 8 bytes of uniform data holding a buffer device address, written
             // per-frame via Gpu::device_address — no descriptor, no Resources
             // entry.
-            let (fields, struct_alignment, expected_size) =
-                generate_std430_struct_fields(&ptr.pointee_type.fields, struct_defs);
+            let pointee_type_name = match &ptr.pointee_type {
+                PointeeType::Struct(pointee) => {
+                    let (fields, struct_alignment, expected_size) =
+                        generate_std430_struct_fields(&pointee.fields, struct_defs);
+
+                    assert_eq!(
+                        expected_size, ptr.pointee_size,
+                        "computed std430 size of pointee '{}' disagrees with slang reflection",
+                        pointee.type_name,
+                    );
 
-            assert_eq!(
-                expected_size, ptr.pointee_size,
-                "computed std430 size of pointee '{}' disagrees with slang reflection",
-                ptr.pointee_type.type_name,
-            );
+                    try_add_struct_def(
+                        struct_defs,
+                        GeneratedStructDefinition {
+                            type_name: pointee.type_name.clone(),
+                            source_module: None,
+                            fields,
+                            trait_derives: vec!["Debug", "Clone", "Serialize"],
+                            alignment: Some(Alignment::Std430 { struct_alignment }),
+                            expected_size: Some(expected_size),
+                        },
+                    );
 
-            try_add_struct_def(
-                struct_defs,
-                GeneratedStructDefinition {
-                    type_name: ptr.pointee_type.type_name.clone(),
-                    source_module: None,
-                    fields,
-                    trait_derives: vec!["Debug", "Clone", "Serialize"],
-                    alignment: Some(Alignment::Std430 { struct_alignment }),
-                    expected_size: Some(expected_size),
-                },
-            );
+                    pointee.type_name.clone()
+                }
+
+                PointeeType::Vector(_) | PointeeType::Scalar(_) => {
+                    let (type_name, stride) = pointee_element_type(ptr);
+                    assert_eq!(
+                        stride,
+                        align_to(ptr.pointee_size, field_alignment(type_name)),
+                        "{type_name} doesn't match the std430 array stride of pointer '{}'",
+                        ptr.field_name,
+                    );
+
+                    type_name.to_string()
+                }
+            };
 
             let addr_type = match ptr.access {
                 PointerAccess::ReadWrite => "Addr",
@@ -900,7 +1303,7 @@ fn gather_struct_defs(
             };
             Some(GeneratedStructFieldDefinition::new(
                 ptr.field_name.to_snake_case(),
-                format!("{addr_type}<{}>", ptr.pointee_type.type_name),
+                format!("{addr_type}<{pointee_type_name}>"),
             ))
         }
 
@@ -914,10 +1317,15 @@ fn gather_struct_defs(
                 (t, c) => panic!("vector not supported: type: {t:?}, count: {c}"),
             };
 
-            Some(GeneratedStructFieldDefinition::new(
+            let mut field = GeneratedStructFieldDefinition::new(
                 vector.field_name.to_snake_case(),
                 field_type.to_string(),
-            ))
+            );
+            if vector.element_count >= 3 && is_color_field(&field.field_name) {
+                field.doc_lines.push(COLOR_FIELD_DOC.to_string());
+            }
+
+            Some(field)
         }
 
         StructField::Struct(struct_field) => {
@@ -1002,19 +1410,32 @@ fn gather_struct_defs(
     }
 }
 
-fn required_resource(field: &StructField) -> Option<RequiredResource> {
+fn required_resource(field: &StructField, set: usize) -> Option<RequiredResource> {
     match field {
-        StructField::Resource(res) => match &res.resource_shape {
-            ResourceShape::Texture2D => Some(RequiredResource {
-                field_name: res.field_name.to_snake_case(),
-                resource_type: RequiredResourceType::Texture,
-            }),
+        StructField::Resource(res) => {
+            let resource_type = match &res.resource_shape {
+                ResourceShape::Texture2D => RequiredResourceType::Texture,
+                ResourceShape::RWTexture2D => RequiredResourceType::StorageTexture2D,
+                ResourceShape::AccelerationStructure => RequiredResourceType::AccelerationStructure,
+                ResourceShape::ByteAddressBuffer | ResourceShape::RWByteAddressBuffer => {
+                    RequiredResourceType::RawBuffer
+                }
+            };
+
+            let doc_lines = match &res.binding {
+                Binding::DescriptorTableSlot(slot) => vec![format!(
+                    "slang `{}`: set {set}, binding {}",
+                    res.field_name, slot.index
+                )],
+                _ => vec![format!("slang `{}`", res.field_name)],
+            };
 
-            ResourceShape::RWTexture2D => Some(RequiredResource {
+            Some(RequiredResource {
                 field_name: res.field_name.to_snake_case(),
-                resource_type: RequiredResourceType::StorageTexture2D,
-            }),
-        },
+                resource_type,
+                doc_lines,
+            })
+        }
 
         _ => None,
     }
@@ -1037,7 +1458,15 @@ impl GeneratedStructDefinition {
             return None;
         }
 
-        let trait_list = self.trait_derives.join(", ");
+        // zeroed, with identity matrices and null addresses
+        let default = self.gpu_write().then_some("Default");
+        let trait_list = self
+            .trait_derives
+            .iter()
+            .copied()
+            .chain(default)
+            .collect::<Vec<_>>()
+            .join(", ");
 
         Some(format!("#[derive({trait_list})]"))
     }
@@ -1054,6 +1483,23 @@ impl GeneratedStructDefinition {
         self.expected_size
     }
 
+    /// Doc comment lines for the generated struct, describing its GPU layout
+    fn doc_lines(&self) -> Vec<String> {
+        let (Some(alignment), Some(size)) = (&self.alignment, self.expected_size) else {
+            return vec![];
+        };
+
+        let layout = match alignment {
+            Alignment::Std140 => "std140".to_string(),
+            Alignment::Std430 { struct_alignment } => format!("std430, align {struct_alignment}"),
+        };
+
+        vec![format!(
+            "slang `{}`: size {size} ({layout})",
+            self.type_name
+        )]
+    }
+
     /// Per-field layout assertion lines for the generated source.
     /// Offsets check field placement; sizes check field extent (interior
     /// stride padding always changes a type's total size, which offset
@@ -1083,6 +1529,40 @@ impl GeneratedStructDefinition {
 
         lines
     }
+
+    /// `sample()` for GPU structs with a reflected layout to number their words by;
+    /// see `SampleValue`
+    fn sample_impl_lines(&self) -> Vec<String> {
+        if !self.gpu_write() || self.expected_size.is_none() {
+            return vec![];
+        }
+
+        let type_name = &self.type_name;
+        let mut lines = vec![
+            String::new(),
+            format!("impl {type_name} {{"),
+            "    /// each float and uint holds its 4-byte word index; see [`SampleValue`]"
+                .to_string(),
+            "    pub fn sample() -> Self {".to_string(),
+            "        Self::sample_at(0)".to_string(),
+            "    }".to_string(),
+            "}".to_string(),
+            String::new(),
+            format!("impl SampleValue for {type_name} {{"),
+            "    fn sample_at(word: u32) -> Self {".to_string(),
+            "        Self {".to_string(),
+        ];
+        for field in &self.fields {
+            lines.push(format!(
+                "            {}: {},",
+                field.field_name,
+                field.sample_value()
+            ));
+        }
+        lines.extend(["        }", "    }", "}"].map(String::from));
+
+        lines
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1094,6 +1574,8 @@ struct GeneratedStructFieldDefinition {
     offset: Option<usize>,
     /// reflected size within the GPU struct; None when offset is None
     size: Option<usize>,
+    /// doc comment lines for the generated field
+    doc_lines: Vec<String>,
 }
 
 impl GeneratedStructFieldDefinition {
@@ -1103,15 +1585,31 @@ impl GeneratedStructFieldDefinition {
             type_name,
             offset: None,
             size: None,
+            doc_lines: vec![],
         }
     }
 
     fn padding(index: usize, size: usize) -> Self {
+        // GPU structs derive Default, which arrays only implement up to 32 elements;
+        // alignment gaps are never wider than 12
+        assert!(size <= 32, "padding field of {size} bytes");
+
         Self {
             field_name: format!("_padding_{index}"),
             type_name: format!("[u8; {size}]"),
             offset: None,
             size: None,
+            doc_lines: vec![],
+        }
+    }
+
+    /// the field's value in its struct's generated `sample_at`
+    fn sample_value(&self) -> String {
+        match self.offset {
+            // padding
+            None => "Default::default()".to_string(),
+            Some(0) => "SampleValue::sample_at(word)".to_string(),
+            Some(offset) => format!("SampleValue::sample_at(word + {})", offset / 4),
         }
     }
 }
@@ -1122,8 +1620,8 @@ struct GeneratedFile {
     content: String,
 }
 
-fn write_generated_file(config: &Config, source_file: &GeneratedFile) -> anyhow::Result<()> {
-    let absolute_path = config.rust_source_dir.join(&source_file.relative_path);
+fn write_generated_file(rust_source_dir: &Path, source_file: &GeneratedFile) -> anyhow::Result<()> {
+    let absolute_path = rust_source_dir.join(&source_file.relative_path);
 
     std::fs::create_dir_all(absolute_path.parent().unwrap())?;
     std::fs::write(&absolute_path, &source_file.content)?;
@@ -1147,6 +1645,8 @@ struct VertexAttributeDescription {
 struct RequiredResource {
     field_name: String,
     resource_type: RequiredResourceType,
+    /// doc comment lines for the generated Resources field
+    doc_lines: Vec<String>,
 }
 
 enum RequiredResourceType {
@@ -1154,19 +1654,80 @@ enum RequiredResourceType {
     IndexBuffer,
     Texture,
     StorageTexture2D,
+    AccelerationStructure,
+    RawBuffer,
     UniformBuffer(String),
+    /// the renderer's own uniform buffer; not a Resources field
+    FrameGlobals,
+    /// the renderer's split-screen cameras; not a Resources field either
+    SplitScreenViews,
 }
 
-/// Extracts offset and size from a StructField's binding
-fn field_offset_size(field: &StructField) -> Option<(usize, usize)> {
-    let binding = match field {
-        StructField::Scalar(s) => Some(&s.binding),
-        StructField::Vector(VectorStructField::Bound(v)) => Some(&v.binding),
-        StructField::Vector(VectorStructField::Semantic(_)) => None,
+fn scalar_type_name(scalar_type: ScalarType) -> &'static str {
+    match scalar_type {
+        ScalarType::Float32 => "f32",
+        ScalarType::Uint32 => "u32",
+        ScalarType::Uint64 => "u64",
+    }
+}
+
+/// The Rust element type of a pointer to scalars or vectors, and its size.
+/// The size must be the std430 array stride, so indexing an `Addr<T>` on either
+/// side lands on the same element; that's why a float3 pointee is a `glam::Vec3A`.
+fn pointee_element_type(ptr: &PointerStructField) -> (&'static str, usize) {
+    match &ptr.pointee_type {
+        PointeeType::Scalar(scalar) => match scalar.scalar_type {
+            ScalarType::Float32 => ("f32", std::mem::size_of::<f32>()),
+            ScalarType::Uint32 => ("u32", std::mem::size_of::<u32>()),
+            ScalarType::Uint64 => ("u64", std::mem::size_of::<u64>()),
+        },
+
+        PointeeType::Vector(vector) => {
+            let VectorElementType::Scalar(element_type) = &vector.element_type;
+            match (element_type.scalar_type, vector.element_count) {
+                (ScalarType::Float32, 4) => ("glam::Vec4", std::mem::size_of::<glam::Vec4>()),
+                (ScalarType::Float32, 3) => ("glam::Vec3A", std::mem::size_of::<glam::Vec3A>()),
+                (ScalarType::Float32, 2) => ("glam::Vec2", std::mem::size_of::<glam::Vec2>()),
+                (ScalarType::Uint32, 4) => ("glam::UVec4", std::mem::size_of::<glam::UVec4>()),
+                (ScalarType::Uint32, 2) => ("glam::UVec2", std::mem::size_of::<glam::UVec2>()),
+                (t, c) => panic!(
+                    "pointer field '{}': vector pointee not supported: type: {t:?}, count: {c}",
+                    ptr.field_name,
+                ),
+            }
+        }
+
+        PointeeType::Struct(pointee) => panic!(
+            "pointer field '{}': struct pointee '{}' is a generated type",
+            ptr.field_name, pointee.type_name,
+        ),
+    }
+}
+
+/// The slang-side name of a struct field
+fn struct_field_name(field: &StructField) -> &str {
+    match field {
+        StructField::Scalar(s) => &s.field_name,
+        StructField::Vector(VectorStructField::Bound(v)) => &v.field_name,
+        StructField::Vector(VectorStructField::Semantic(v)) => &v.field_name,
+        StructField::Matrix(m) => &m.field_name,
+        StructField::Struct(s) => &s.field_name,
+        StructField::Pointer(p) => &p.field_name,
+        StructField::Resource(r) => &r.field_name,
+        StructField::ParameterBlock(b) => &b.field_name,
+    }
+}
+
+/// Extracts offset and size from a StructField's binding
+fn field_offset_size(field: &StructField) -> Option<(usize, usize)> {
+    let binding = match field {
+        StructField::Scalar(s) => Some(&s.binding),
+        StructField::Vector(VectorStructField::Bound(v)) => Some(&v.binding),
+        StructField::Vector(VectorStructField::Semantic(_)) => None,
         StructField::Matrix(m) => Some(&m.binding),
         StructField::Struct(s) => Some(&s.binding),
         StructField::Pointer(p) => Some(&p.binding),
-        StructField::Resource(_) => None,
+        StructField::Resource(_) | StructField::ParameterBlock(_) => None,
     };
 
     binding.and_then(|b| match b {
@@ -1179,9 +1740,10 @@ fn field_offset_size(field: &StructField) -> Option<(usize, usize)> {
 /// These rules are the same for both std140 and std430 for basic types.
 fn field_alignment(type_name: &str) -> usize {
     match type_name {
-        "glam::Vec4" | "glam::Mat4" => 16,
-        "glam::Vec3" => 16, // vec3 has 16-byte alignment in both std140 and std430
-        "glam::Vec2" | "u64" => 8,
+        "glam::Vec4" | "glam::Mat4" | "glam::UVec4" => 16,
+        // vec3 has 16-byte alignment in both std140 and std430
+        "glam::Vec3" | "glam::Vec3A" => 16,
+        "glam::Vec2" | "glam::UVec2" | "u64" => 8,
         "f32" | "u32" | "i32" => 4,
         // Addr<T> / ReadAddr<T> / ImmutableAddr<T> are repr(transparent) over u64
         s if s.starts_with("Addr<")
@@ -1625,7 +2187,7 @@ mod tests {
                 }
 
                 StructField::Resource(res) => {
-                    if let ResourceResultType::Struct(s) = &res.result_type {
+                    if let Some(ResourceResultType::Struct(s)) = &res.result_type {
                         let context = format!("{context}.{}", res.field_name);
                         check_field_sizes(&s.fields, &context, mismatches);
                     }
@@ -1635,10 +2197,15 @@ mod tests {
                 StructField::Pointer(ptr) => {
                     // check the pointee's fields, then fall through to the
                     // leaf check for the pointer's own u64
-                    let pointee_context = format!("{context}.{}", ptr.field_name);
-                    check_field_sizes(&ptr.pointee_type.fields, &pointee_context, mismatches);
+                    if let PointeeType::Struct(pointee) = &ptr.pointee_type {
+                        let pointee_context = format!("{context}.{}", ptr.field_name);
+                        check_field_sizes(&pointee.fields, &pointee_context, mismatches);
+                    }
                 }
 
+                // checked as its own descriptor set
+                StructField::ParameterBlock(_) => continue,
+
                 StructField::Scalar(_) | StructField::Vector(_) | StructField::Matrix(_) => {}
             }
 
@@ -1700,10 +2267,9 @@ mod tests {
                 continue;
             };
 
-            for global_parameter in &global_parameters {
-                let GlobalParameter::ParameterBlock(block) = global_parameter;
-                let context = format!("{file_name}: {}", block.parameter_name);
-                check_field_sizes(&block.element_type.fields, &context, &mut mismatches);
+            for descriptor_set in global_descriptor_sets(&global_parameters) {
+                let context = format!("{file_name}: {}", descriptor_set.parameter_path);
+                check_field_sizes(descriptor_set.fields, &context, &mut mismatches);
             }
         }
 
@@ -1965,7 +2531,10 @@ float4 fragMain() : SV_Target {
 
         let reflected = result.expect("an Access.Immutable pointer field must be accepted");
         let GlobalParameter::ParameterBlock(block) =
-            &reflected.reflection_json.global_parameters[0];
+            &reflected.reflection_json.global_parameters[0]
+        else {
+            panic!("expected a parameter block");
+        };
         let access = block
             .element_type
             .fields
@@ -1978,6 +2547,722 @@ float4 fragMain() : SV_Target {
         assert_eq!(access, PointerAccess::Immutable);
     }
 
+    // loose globals take the first descriptor set, and a nested block takes
+    // the set after its parent's; codegen must agree with the pipeline layout
+    #[cfg(not(windows))]
+    #[test]
+    fn nested_blocks_and_loose_globals_get_their_own_sets() {
+        use crate::renderer::LayoutDescription;
+
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module nested_blocks;
+
+struct Material {
+    float4 baseColor;
+}
+
+struct Scene {
+    float4x4 viewProjection;
+    ParameterBlock<Material> material;
+}
+
+uniform float time;
+ParameterBlock<Scene> scene;
+
+[shader("vertex")]
+float4 vertMain(uint id: SV_VertexID) : SV_Position {
+    let uv = float2(float(id & 1), float(id >> 1));
+    return mul(scene.viewProjection, float4(uv, sin(time), 1.0));
+}
+
+[shader("fragment")]
+float4 fragMain() : SV_Target {
+    return scene.material.baseColor;
+}
+"#;
+        std::fs::write(tmp_dir.join("nested_blocks.shader.slang"), source).unwrap();
+
+        let result =
+            prepare_reflected_shader("nested_blocks.shader.slang", tmp_dir.to_str().unwrap());
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("nested parameter blocks failed to reflect: {err:#}"),
+        };
+
+        let descriptor_sets = global_descriptor_sets(&reflection_json.global_parameters);
+        let paths: Vec<&str> = descriptor_sets
+            .iter()
+            .map(|descriptor_set| descriptor_set.parameter_path.as_str())
+            .collect();
+        assert_eq!(paths, ["globals", "scene", "scene.material"]);
+
+        let layout_bindings = reflection_json.layout_bindings();
+        assert_eq!(layout_bindings.len(), descriptor_sets.len());
+
+        for (descriptor_set, layout_descriptions) in descriptor_sets.iter().zip(&layout_bindings) {
+            let (_fields, _alignment, expected_size) =
+                generate_std140_struct_fields(descriptor_set.fields, &mut vec![]);
+            let Some(LayoutDescription::Uniform(uniform)) = layout_descriptions.first() else {
+                panic!(
+                    "{}: no uniform buffer at binding 0",
+                    descriptor_set.parameter_path
+                );
+            };
+            assert_eq!(
+                uniform.size as usize, expected_size,
+                "{}: generated struct size disagrees with the layout",
+                descriptor_set.parameter_path
+            );
+        }
+
+        let data = collect_graphics_shader_data(&reflection_json, &HashMap::new());
+        let resources = data
+            .struct_defs
+            .iter()
+            .find(|def| def.type_name == "Resources<'a>")
+            .expect("no Resources struct generated");
+        let resource_fields: Vec<&str> = resources
+            .fields
+            .iter()
+            .map(|field| field.field_name.as_str())
+            .collect();
+        assert_eq!(
+            resource_fields,
+            ["globals_buffer", "scene_buffer", "scene_material_buffer"]
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn uniform_entry_point_scalars_become_push_constants() {
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module push_constants;
+
+[shader("vertex")]
+float4 vertMain(uint id: SV_VertexID, uniform float scale, uniform uint flags) : SV_Position {
+    let uv = float2(float(id & 1), float(id >> 1)) * scale;
+    return float4(uv, float(flags & 1), 1.0);
+}
+
+[shader("fragment")]
+float4 fragMain() : SV_Target {
+    return float4(1.0);
+}
+"#;
+        std::fs::write(tmp_dir.join("push_constants.shader.slang"), source).unwrap();
+
+        let result =
+            prepare_reflected_shader("push_constants.shader.slang", tmp_dir.to_str().unwrap());
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("uniform entry point parameters failed to reflect: {err:#}"),
+        };
+
+        let bound_scalars: Vec<&str> = reflection_json
+            .vertex_entry_point
+            .parameters
+            .iter()
+            .filter_map(|param| match param {
+                EntryPointParameter::Scalar(ScalarEntryPointParameter::Bound(scalar)) => {
+                    assert!(matches!(scalar.binding, Binding::Uniform(_)));
+                    Some(scalar.parameter_name.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(bound_scalars, ["scale", "flags"]);
+
+        let ranges = &reflection_json.pipeline_layout.push_constant_ranges;
+        assert_eq!(ranges.len(), 1);
+        assert!(matches!(ranges[0].stage_flags, ReflectedStageFlags::Vertex));
+
+        let data = collect_graphics_shader_data(&reflection_json, &HashMap::new());
+        let push_constants = data
+            .struct_defs
+            .iter()
+            .find(|def| def.type_name == PUSH_CONSTANTS_TYPE_NAME)
+            .expect("no PushConstants struct generated");
+        assert_eq!(
+            push_constants.expected_size,
+            Some(reflection_json.pipeline_layout.push_constants_size())
+        );
+        let field_offsets: Vec<_> = push_constants
+            .fields
+            .iter()
+            .map(|field| (field.field_name.as_str(), field.offset))
+            .collect();
+        assert_eq!(field_offsets, [("scale", Some(0)), ("flags", Some(4))]);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn push_constant_globals_become_push_constants() {
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module push_constant_global;
+
+struct DrawParams {
+    float4 tint;
+    float2 offset;
+    uint flags;
+};
+
+[[vk::push_constant]]
+ConstantBuffer<DrawParams> draw;
+
+[shader("vertex")]
+float4 vertMain(uint id: SV_VertexID) : SV_Position {
+    let uv = float2(float(id & 1), float(id >> 1)) + draw.offset;
+    return float4(uv, float(draw.flags & 1), 1.0);
+}
+
+[shader("fragment")]
+float4 fragMain() : SV_Target {
+    return draw.tint;
+}
+"#;
+        std::fs::write(tmp_dir.join("push_constant_global.shader.slang"), source).unwrap();
+
+        let result = prepare_reflected_shader(
+            "push_constant_global.shader.slang",
+            tmp_dir.to_str().unwrap(),
+        );
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("push constant global failed to reflect: {err:#}"),
+        };
+
+        let [GlobalParameter::PushConstants(push_constant_global)] =
+            reflection_json.global_parameters.as_slice()
+        else {
+            panic!(
+                "expected one push constant global, got {:?}",
+                reflection_json.global_parameters
+            );
+        };
+        assert_eq!(push_constant_global.parameter_name, "draw");
+        assert!(global_descriptor_sets(&reflection_json.global_parameters).is_empty());
+
+        let layout = &reflection_json.pipeline_layout;
+        assert!(layout.descriptor_set_layouts.is_empty());
+        assert_eq!(layout.push_constant_ranges.len(), 1);
+        assert!(matches!(
+            layout.push_constant_ranges[0].stage_flags,
+            ReflectedStageFlags::All
+        ));
+
+        let data = collect_graphics_shader_data(&reflection_json, &HashMap::new());
+        let push_constants = data
+            .struct_defs
+            .iter()
+            .find(|def| def.type_name == PUSH_CONSTANTS_TYPE_NAME)
+            .expect("no PushConstants struct generated");
+        assert_eq!(
+            push_constants.expected_size,
+            Some(layout.push_constants_size())
+        );
+        let field_offsets: Vec<_> = push_constants
+            .fields
+            .iter()
+            .filter(|field| field.offset.is_some())
+            .map(|field| (field.field_name.as_str(), field.offset))
+            .collect();
+        assert_eq!(
+            field_offsets,
+            [("tint", Some(0)), ("offset", Some(16)), ("flags", Some(24))]
+        );
+    }
+
+    #[test]
+    fn frame_globals_bind_the_renderer_buffer() {
+        use crate::renderer::FrameGlobals;
+
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module frame_globals_user;
+
+import frame_globals;
+
+struct Params {
+    float4 tint;
+}
+
+ParameterBlock<Params> params;
+ParameterBlock<FrameGlobals> frame;
+
+[shader("vertex")]
+float4 vertMain(uint id: SV_VertexID) : SV_Position {
+    let uv = float2(float(id & 1), float(id >> 1)) / frame.resolution;
+    return float4(uv, float(frame.frameIndex & 1), 1.0);
+}
+
+[shader("fragment")]
+float4 fragMain() : SV_Target {
+    return params.tint * sin(frame.time + frame.deltaTime);
+}
+"#;
+        std::fs::write(tmp_dir.join("frame_globals_user.shader.slang"), source).unwrap();
+
+        let result =
+            prepare_reflected_shader("frame_globals_user.shader.slang", tmp_dir.to_str().unwrap());
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("frame globals failed to reflect: {err:#}"),
+        };
+
+        // the hand-written Rust struct must match the slang module's layout
+        let frame_block = global_descriptor_sets(&reflection_json.global_parameters)
+            .into_iter()
+            .find(|set| set.type_name == Some(FRAME_GLOBALS_TYPE_NAME))
+            .expect("no FrameGlobals descriptor set");
+        let (fields, _alignment, size) =
+            generate_std140_struct_fields(frame_block.fields, &mut vec![]);
+        assert_eq!(size, std::mem::size_of::<FrameGlobals>());
+        let field_offsets: Vec<_> = fields
+            .iter()
+            .filter_map(|field| Some((field.field_name.as_str(), field.offset?)))
+            .collect();
+        let rust_offsets = [
+            ("resolution", std::mem::offset_of!(FrameGlobals, resolution)),
+            ("time", std::mem::offset_of!(FrameGlobals, time)),
+            ("delta_time", std::mem::offset_of!(FrameGlobals, delta_time)),
+            (
+                "frame_index",
+                std::mem::offset_of!(FrameGlobals, frame_index),
+            ),
+            (
+                "output_color_space",
+                std::mem::offset_of!(FrameGlobals, output_color_space),
+            ),
+            (
+                "paper_white_nits",
+                std::mem::offset_of!(FrameGlobals, paper_white_nits),
+            ),
+            (
+                "display_max_nits",
+                std::mem::offset_of!(FrameGlobals, display_max_nits),
+            ),
+        ];
+        assert_eq!(field_offsets, rust_offsets);
+
+        let data = collect_graphics_shader_data(&reflection_json, &HashMap::new());
+        assert!(
+            data.struct_defs
+                .iter()
+                .all(|def| def.type_name != FRAME_GLOBALS_TYPE_NAME),
+            "FrameGlobals is the renderer's type, not generated"
+        );
+        let resources = data
+            .struct_defs
+            .iter()
+            .find(|def| def.type_name == "Resources<'a>")
+            .unwrap();
+        let resource_names: Vec<_> = resources
+            .fields
+            .iter()
+            .map(|field| field.field_name.as_str())
+            .collect();
+        assert_eq!(resource_names, ["params_buffer"]);
+        assert_eq!(
+            data.shader_impl.uniform_buffer_handles,
+            [
+                "RawUniformBufferHandle::from_typed(resources.params_buffer)",
+                "RawUniformBufferHandle::frame_globals()",
+            ]
+        );
+    }
+
+    #[test]
+    fn split_screen_views_bind_the_renderer_buffer() {
+        use crate::renderer::SplitScreenViews;
+
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module split_screen_user;
+
+import split_screen;
+
+struct Params {
+    float4x4 model;
+}
+
+ParameterBlock<Params> params;
+ParameterBlock<SplitScreenViews> splitScreen;
+
+[shader("vertex")]
+float4 vertMain(uint id: SV_VertexID, uint viewIndex: SV_StartInstanceLocation) : SV_Position {
+    let position = float3(float(id & 1), float(id >> 1), float(splitScreen.viewCount));
+    return splitScreen.project(params.model, position, viewIndex);
+}
+
+[shader("fragment")]
+float4 fragMain() : SV_Target {
+    return float4(1.0);
+}
+"#;
+        std::fs::write(tmp_dir.join("split_screen_user.shader.slang"), source).unwrap();
+
+        let result =
+            prepare_reflected_shader("split_screen_user.shader.slang", tmp_dir.to_str().unwrap());
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("split-screen views failed to reflect: {err:#}"),
+        };
+
+        // the hand-written Rust struct must match the slang module's layout
+        let views_block = global_descriptor_sets(&reflection_json.global_parameters)
+            .into_iter()
+            .find(|set| set.type_name == Some(SPLIT_SCREEN_VIEWS_TYPE_NAME))
+            .expect("no SplitScreenViews descriptor set");
+        let (fields, _alignment, size) =
+            generate_std140_struct_fields(views_block.fields, &mut vec![]);
+        assert_eq!(size, std::mem::size_of::<SplitScreenViews>());
+        let field_offsets: Vec<_> = fields
+            .iter()
+            .filter_map(|field| Some((field.field_name.as_str(), field.offset?)))
+            .collect();
+        let rust_offsets = [
+            ("views", std::mem::offset_of!(SplitScreenViews, views)),
+            (
+                "projections",
+                std::mem::offset_of!(SplitScreenViews, projections),
+            ),
+            (
+                "view_count",
+                std::mem::offset_of!(SplitScreenViews, view_count),
+            ),
+        ];
+        assert_eq!(field_offsets, rust_offsets);
+
+        let data = collect_graphics_shader_data(&reflection_json, &HashMap::new());
+        assert!(
+            data.struct_defs
+                .iter()
+                .all(|def| def.type_name != SPLIT_SCREEN_VIEWS_TYPE_NAME),
+            "SplitScreenViews is the renderer's type, not generated"
+        );
+        assert_eq!(
+            data.shader_impl.uniform_buffer_handles,
+            [
+                "RawUniformBufferHandle::from_typed(resources.params_buffer)",
+                "RawUniformBufferHandle::split_screen_views()",
+            ]
+        );
+    }
+
+    #[test]
+    fn velocity_outputs_generate_with_velocity() {
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module velocity_output;
+
+import motion_blur;
+
+struct Params {
+    float4 tint;
+}
+
+ParameterBlock<Params> params;
+
+[shader("vertex")]
+float4 vertMain(uint id: SV_VertexID) : SV_Position {
+    return float4(float(id & 1), float(id >> 1), 0.0, 1.0);
+}
+
+[shader("fragment")]
+VelocityOutput fragMain(float4 position: SV_Position) {
+    VelocityOutput output;
+    output.color = params.tint;
+    output.velocity = position.xy * 0.001;
+    return output;
+}
+"#;
+        std::fs::write(tmp_dir.join("velocity_output.shader.slang"), source).unwrap();
+
+        let result =
+            prepare_reflected_shader("velocity_output.shader.slang", tmp_dir.to_str().unwrap());
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("velocity output failed to reflect: {err:#}"),
+        };
+        assert!(reflection_json.writes_velocity);
+
+        let data = collect_graphics_shader_data(&reflection_json, &HashMap::new());
+        let file = render_graphics_shader_file(&data, &BTreeMap::new());
+
+        // the rest of the file is covered by the generated_files snapshots
+        let start = file
+            .content
+            .find("    pub fn pipeline_config(")
+            .expect("no pipeline_config");
+        let end = start + file.content[start..].find("\n    }\n").unwrap() + "\n    }".len();
+        let pipeline_config = &file.content[start..end];
+        insta::assert_snapshot!(pipeline_config);
+    }
+
+    #[test]
+    fn vertex_semantics_override_attribute_formats() {
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module compact_vertex;
+
+struct Vertex {
+    float3 position;
+    float4 color : COLOR_UNORM8;
+    float2 uv : TEXCOORD_HALF;
+    float4 normal : NORMAL_SNORM8;
+};
+
+struct FragInput {
+    float4 position : SV_Position;
+    float4 color;
+};
+
+[shader("vertex")]
+FragInput vertMain(Vertex vertex) {
+    let shaded = vertex.color * float4(vertex.uv, vertex.normal.xy);
+    return FragInput(float4(vertex.position, 1.0), shaded);
+}
+
+[shader("fragment")]
+float4 fragMain(FragInput input) : SV_Target {
+    return input.color;
+}
+"#;
+        std::fs::write(tmp_dir.join("compact_vertex.shader.slang"), source).unwrap();
+
+        let result =
+            prepare_reflected_shader("compact_vertex.shader.slang", tmp_dir.to_str().unwrap());
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("vertex semantics failed to reflect: {err:#}"),
+        };
+
+        let data = collect_graphics_shader_data(&reflection_json, &HashMap::new());
+        let vertex = data
+            .struct_defs
+            .iter()
+            .find(|def| def.type_name == "Vertex")
+            .expect("no Vertex struct generated");
+        let field_types: Vec<_> = vertex
+            .fields
+            .iter()
+            .map(|field| (field.field_name.as_str(), field.type_name.as_str()))
+            .collect();
+        assert_eq!(
+            field_types,
+            [
+                ("position", "glam::Vec3"),
+                ("color", "[u8; 4]"),
+                ("uv", "[u16; 2]"),
+                ("normal", "[i8; 4]"),
+            ]
+        );
+
+        let formats: Vec<_> = data.vertex_impl_blocks[0]
+            .attribute_descriptions
+            .iter()
+            .map(|attr| attr.format.trim_start_matches("ash::vk::Format::"))
+            .collect();
+        assert_eq!(
+            formats,
+            [
+                "R32G32B32_SFLOAT",
+                "R8G8B8A8_UNORM",
+                "R16G16_SFLOAT",
+                "R8G8B8A8_SNORM",
+            ]
+        );
+    }
+
+    #[test]
+    fn byte_address_buffers_bind_as_raw_buffers() {
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module raw_streams;
+
+struct Streams {
+    ByteAddressBuffer particles;
+    RWByteAddressBuffer positions;
+}
+
+ParameterBlock<Streams> streams;
+
+[shader("compute")]
+[numthreads(64, 1, 1)]
+void main(uint3 id: SV_DispatchThreadID) {
+    let stride = streams.particles.Load(0);
+    let position = streams.particles.Load<float2>(4 + id.x * stride);
+    streams.positions.Store<float2>(id.x * 8, position);
+}
+"#;
+        std::fs::write(tmp_dir.join("raw_streams.compute.slang"), source).unwrap();
+
+        let result = prepare_reflected_compute_shader(
+            "raw_streams.compute.slang",
+            tmp_dir.to_str().unwrap(),
+        );
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        let reflection_json = match result {
+            Ok(reflected) => reflected.reflection_json,
+            Err(err) => panic!("byte address buffers failed to reflect: {err:#}"),
+        };
+
+        let GlobalParameter::ParameterBlock(block) = &reflection_json.global_parameters[0] else {
+            panic!("expected a parameter block");
+        };
+        let shapes: Vec<_> = block
+            .element_type
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                StructField::Resource(res) => {
+                    assert!(res.result_type.is_none());
+                    Some(&res.resource_shape)
+                }
+                _ => None,
+            })
+            .collect();
+        assert!(matches!(
+            shapes[..],
+            [
+                ResourceShape::ByteAddressBuffer,
+                ResourceShape::RWByteAddressBuffer
+            ]
+        ));
+
+        let binding_types: Vec<_> = reflection_json.pipeline_layout.descriptor_set_layouts[0]
+            .binding_ranges
+            .iter()
+            .map(|range| range.descriptor_type)
+            .collect();
+        assert_eq!(
+            binding_types,
+            [
+                ReflectedBindingType::StorageBuffer,
+                ReflectedBindingType::StorageBuffer
+            ]
+        );
+
+        let data = collect_compute_shader_data(&reflection_json, &HashMap::new());
+        assert_eq!(
+            data.shader_impl.resources_raw_buffer_fields,
+            ["particles", "positions"]
+        );
+    }
+
+    // shaders/lib is on every session's search path, so a shader outside the
+    // crate's own source directory can import the shipped modules
+    #[cfg(not(windows))]
+    #[test]
+    fn shader_library_modules_import() {
+        let tmp_dir = std::env::temp_dir().join(format!("shader-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&tmp_dir).unwrap();
+
+        let source = r#"#language slang 2026
+
+module library_imports;
+
+import billboard;
+import blit;
+import color;
+import color_grading;
+import fullscreen_triangle;
+import motion_blur;
+import msdf;
+import noise;
+import sdf;
+import sharpen;
+import split_screen;
+import stereo;
+import taa;
+import tonemap;
+
+struct Params {
+    float4 tint;
+}
+
+ParameterBlock<Params> params;
+
+[shader("vertex")]
+float4 vertMain(uint id: SV_VertexID) : SV_Position {
+    let corner = billboardCorner(id);
+    let view = billboardViewPosition(float3(0.0, 0.0, -2.0), corner, float2(0.5), float2(1.0));
+    let clip = float4(view.xy, 0.5, 1.0);
+    return billboardScreenSizedPosition(clip, corner, float2(0.5), float2(16.0), float2(800.0));
+}
+
+[shader("fragment")]
+float4 fragMain(float4 position: SV_Position) : SV_Target {
+    let p = position.xy * 0.01;
+    let d = opSmoothUnion(sdCircle(p, 1.0), sdBox2D(p - 1.0, float2(0.5)), 0.2);
+    let shade = fbm(p, 4, 0.5) + cellularNoise(p) + hash21(p) - d;
+    let rgb = tonemapAces(params.tint.rgb * shade * exposure(1.0));
+    let text = msdfCoverage(float3(shade), 4.0, float2(512.0), p);
+    return float4(oklabLerp(rgb, hsvToRgb(float3(0.5, 1.0, 1.0)), luminance(rgb)) * text, 1.0);
+}
+"#;
+        std::fs::write(tmp_dir.join("library_imports.shader.slang"), source).unwrap();
+
+        let result =
+            prepare_reflected_shader("library_imports.shader.slang", tmp_dir.to_str().unwrap());
+
+        std::fs::remove_dir_all(&tmp_dir).ok();
+
+        if let Err(err) = result {
+            panic!("shaders/lib modules failed to import: {err:#}");
+        }
+    }
+
     fn count_branch_instructions(spv_bytes: &[u8]) -> usize {
         let module = rspirv::dr::load_bytes(spv_bytes).expect("Failed to parse SPIR-V module");
         module
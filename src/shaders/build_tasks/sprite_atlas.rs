@@ -0,0 +1,150 @@
+//! Generates a module per sprite sheet from its Aseprite or TexturePacker json,
+//! with an enum of the sheet's sprites and their frames as constants
+
+use std::path::{Path, PathBuf};
+
+use askama::Template;
+use heck::{ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+
+use crate::sprites::{AtlasFrame, SheetLayout};
+use crate::util::relative_path;
+
+use super::{GeneratedFile, write_generated_file};
+
+/// the json export each sheet's directory holds
+const SPRITE_SHEET_FILE_NAME: &str = "sprite_sheet.json";
+
+pub struct SpriteAtlasConfig {
+    /// the directory to write the 'generated' module into
+    pub rust_source_dir: PathBuf,
+    /// the directory to find sheets in: a directory per sheet,
+    /// holding a `sprite_sheet.json` and named for its generated module
+    pub textures_dir: PathBuf,
+}
+
+/// Writes `generated::sprite_atlas`, with a module for each sheet in the textures dir
+pub fn write_sprite_atlases(config: &SpriteAtlasConfig) -> anyhow::Result<()> {
+    let mut sheet_paths: Vec<PathBuf> = std::fs::read_dir(&config.textures_dir)?
+        .filter_map(|entry_res| entry_res.ok())
+        .map(|dir_entry| dir_entry.path().join(SPRITE_SHEET_FILE_NAME))
+        .filter(|path| path.is_file())
+        .collect();
+    sheet_paths.sort();
+
+    let mut module_names = vec![];
+    let mut generated_source_files = vec![];
+    for sheet_path in &sheet_paths {
+        let json = std::fs::read_to_string(sheet_path)?;
+        let layout = SheetLayout::from_json(&json)
+            .map_err(|error| error.context(format!("{}", sheet_path.display())))?;
+
+        let sheet_dir = sheet_path.parent().unwrap();
+        let module_name = sheet_dir
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_snake_case();
+        let source_path = sheet_path
+            .strip_prefix(config.textures_dir.parent().unwrap_or(Path::new("")))
+            .unwrap_or(sheet_path);
+
+        let module = SpriteSheetModule::new(source_path, &layout);
+        let file_name = format!("{module_name}.rs");
+        generated_source_files.push(GeneratedFile {
+            relative_path: relative_path(["generated", "sprite_atlas", &file_name]),
+            content: module.render()?,
+        });
+        module_names.push(module_name);
+    }
+
+    let mod_lines: Vec<String> = module_names
+        .iter()
+        .map(|module_name| format!("pub mod {module_name};\n"))
+        .collect();
+    generated_source_files.push(GeneratedFile {
+        relative_path: relative_path(["generated", "sprite_atlas.rs"]),
+        content: mod_lines.concat(),
+    });
+
+    for source_file in &generated_source_files {
+        write_generated_file(&config.rust_source_dir, source_file)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Template)]
+#[template(path = "sprite_atlas_sheet.rs.askama", escape = "none")]
+struct SpriteSheetModule {
+    /// the json's path, relative to the textures dir's parent
+    source_path: String,
+    width: u32,
+    height: u32,
+    sprites: Vec<GeneratedSprite>,
+}
+
+struct GeneratedSprite {
+    name: String,
+    variant_name: String,
+    const_name: String,
+    frames: Vec<AtlasFrame>,
+}
+
+impl SpriteSheetModule {
+    fn new(source_path: &Path, layout: &SheetLayout) -> Self {
+        let sprites = layout
+            .sprites
+            .iter()
+            .map(|sprite| GeneratedSprite {
+                name: sprite.name.clone(),
+                variant_name: sprite.name.to_upper_camel_case(),
+                const_name: sprite.name.to_shouty_snake_case(),
+                frames: sprite.frames.clone(),
+            })
+            .collect();
+
+        Self {
+            source_path: source_path
+                .iter()
+                .map(|segment| segment.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            width: layout.size[0],
+            height: layout.size[1],
+            sprites,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprites_become_variants_and_constants() {
+        let json = r#"{
+            "frames": [
+                { "filename": "bomb_explosion 0", "frame": { "x": 0, "y": 0, "w": 32, "h": 32 }, "duration": 80 },
+                { "filename": "you_win 0", "frame": { "x": 0, "y": 32, "w": 80, "h": 16 }, "duration": 100 }
+            ],
+            "meta": { "size": { "w": 128, "h": 64 } }
+        }"#;
+        let layout = SheetLayout::from_json(json).unwrap();
+
+        let module = SpriteSheetModule::new(Path::new("textures/demo/sprite_sheet.json"), &layout);
+        let source = module.render().unwrap();
+
+        assert!(
+            source.contains("//! generated from sprite sheet: textures/demo/sprite_sheet.json")
+        );
+        assert!(source.contains("pub const SHEET_SIZE: [u32; 2] = [128, 64];"));
+        assert!(source.contains("    BombExplosion,\n    YouWin,\n"));
+        assert!(source.contains("AtlasSprite::YouWin => \"you_win\","));
+        assert!(source.contains("AtlasSprite::BombExplosion => BOMB_EXPLOSION,"));
+        assert!(source.contains(
+            "pub const YOU_WIN: &[AtlasFrame] = &[\n    \
+            AtlasFrame::new([0, 32, 80, 16], 100, SHEET_SIZE),\n];"
+        ));
+    }
+}
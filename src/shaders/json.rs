@@ -8,17 +8,31 @@ pub use parameters::*;
 mod pipeline_builders;
 pub use pipeline_builders::*;
 
+mod uniform_fields;
+pub use uniform_fields::*;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReflectionJson {
     pub source_file_name: String,
     pub global_parameters: Vec<GlobalParameter>,
+    /// the vertex stage, or the mesh stage of a mesh shader
     pub vertex_entry_point: EntryPoint,
+    /// a mesh shader's optional task stage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_entry_point: Option<EntryPoint>,
     pub fragment_entry_point: EntryPoint,
+    /// whether the fragment stage writes a velocity as a float2 `SV_Target1`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub writes_velocity: bool,
     pub pipeline_layout: ReflectedPipelineLayout,
 }
 
 impl ReflectionJson {
+    pub fn is_mesh_shader(&self) -> bool {
+        matches!(self.vertex_entry_point.stage, EntryPointStage::Mesh)
+    }
+
     pub fn layout_bindings(&self) -> Vec<Vec<LayoutDescription>> {
         layout_bindings_from_pipeline_layout(&self.pipeline_layout)
     }
@@ -50,6 +64,7 @@ pub fn layout_bindings_from_pipeline_layout(
             use ash::vk;
 
             use crate::renderer::{
+                AccelerationStructureDescription, StorageBufferDescription,
                 StorageImageDescription, TextureDescription, UniformBufferDescription,
             };
             use crate::shaders::json::ReflectedBindingType;
@@ -91,6 +106,20 @@ pub fn layout_bindings_from_pipeline_layout(
                         })
                     }
 
+                    ReflectedBindingType::AccelerationStructure => {
+                        LayoutDescription::AccelerationStructure(AccelerationStructureDescription {
+                            binding: b.binding,
+                            descriptor_count: 1,
+                        })
+                    }
+
+                    ReflectedBindingType::StorageBuffer => {
+                        LayoutDescription::StorageBuffer(StorageBufferDescription {
+                            binding: b.binding,
+                            descriptor_count: 1,
+                        })
+                    }
+
                     b => todo!("unhandled binding type: {b:?}"),
                 })
                 .collect()
@@ -8,6 +8,32 @@ use serde::{Deserialize, Serialize};
 #[serde(tag = "kind", rename_all = "camelCase")]
 pub enum GlobalParameter {
     ParameterBlock(ParameterBlockGlobalParameter),
+    /// every global declared outside a parameter block, gathered into one entry;
+    /// always the first global parameter when present
+    Loose(LooseGlobalParameters),
+    /// a `[[vk::push_constant]] ConstantBuffer<T>`; it takes no descriptor set
+    PushConstants(PushConstantsGlobalParameter),
+}
+
+impl GlobalParameter {
+    pub fn parameter_name(&self) -> &str {
+        match self {
+            GlobalParameter::ParameterBlock(block) => &block.parameter_name,
+            GlobalParameter::Loose(_) => LOOSE_GLOBALS_NAME,
+            GlobalParameter::PushConstants(push_constants) => &push_constants.parameter_name,
+        }
+    }
+}
+
+/// the name used for the loose globals' descriptor set in codegen and errors
+pub const LOOSE_GLOBALS_NAME: &str = "globals";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LooseGlobalParameters {
+    /// `uniform` values (offsets are within slang's automatically introduced
+    /// uniform buffer) and resources, in declaration order
+    pub fields: Vec<StructField>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +43,14 @@ pub struct ParameterBlockGlobalParameter {
     pub element_type: ParameterBlockElementType,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushConstantsGlobalParameter {
+    pub parameter_name: String,
+    /// fields' offsets are within the push constant range
+    pub element_type: ParameterBlockElementType,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ParameterBlockElementType {
@@ -38,6 +72,8 @@ pub enum EntryPointStage {
     Vertex,
     Fragment,
     Compute,
+    Task,
+    Mesh,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -88,6 +124,16 @@ pub enum StructField {
     Matrix(MatrixStructField),
     Resource(ResourceStructField),
     Pointer(PointerStructField),
+    ParameterBlock(ParameterBlockStructField),
+}
+
+/// A parameter block nested in another; it gets its own descriptor set,
+/// and contributes nothing to the outer block's uniform data or bindings
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterBlockStructField {
+    pub field_name: String,
+    pub element_type: ParameterBlockElementType,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -149,6 +195,10 @@ pub struct BoundVectorStructField {
     pub binding: Binding,
     pub element_count: usize,
     pub element_type: VectorElementType,
+    /// a vertex input's user semantic, which can override its attribute format;
+    /// see `build_tasks::VertexFormatOverride`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic_name: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -167,7 +217,9 @@ pub struct ResourceStructField {
     pub field_name: String,
     pub binding: Binding,
     pub resource_shape: ResourceShape,
-    pub result_type: ResourceResultType,
+    /// None for acceleration structures and byte address buffers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_type: Option<ResourceResultType>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -175,6 +227,9 @@ pub struct ResourceStructField {
 pub enum ResourceShape {
     Texture2D,
     RWTexture2D,
+    AccelerationStructure,
+    ByteAddressBuffer,
+    RWByteAddressBuffer,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -222,13 +277,13 @@ pub struct StructFieldType {
 
 /// A physical-storage-buffer pointer field (slang `Ptr<T, ..., Std430DataLayout>`).
 /// 8 bytes of uniform data holding a buffer device address; consumes no
-/// descriptor slot. The pointee fields carry std430 offsets.
+/// descriptor slot. A struct pointee's fields carry std430 offsets.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PointerStructField {
     pub field_name: String,
     pub binding: Binding,
-    pub pointee_type: StructFieldType,
+    pub pointee_type: PointeeType,
     /// reflected std430 size of the pointee — cross-checked against the
     /// codegen's computed struct size
     pub pointee_size: usize,
@@ -236,6 +291,29 @@ pub struct PointerStructField {
     pub access: PointerAccess,
 }
 
+/// What a pointer field points at; a pointer to scalars or vectors
+/// addresses a plain std430 array of them
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged, rename_all = "camelCase")]
+pub enum PointeeType {
+    Struct(StructFieldType),
+    Vector(VectorPointeeType),
+    Scalar(ScalarPointeeType),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorPointeeType {
+    pub element_count: usize,
+    pub element_type: VectorElementType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScalarPointeeType {
+    pub scalar_type: ScalarType,
+}
+
 /// The access mode of a pointer field
 /// (eg. Slang's `Access.ReadWrite` / `Access.Read` / `Access.Immutable`)
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -269,3 +347,146 @@ pub enum ScalarType {
     Uint32,
     Uint64,
 }
+
+/// The global parameters that make up one descriptor set
+#[derive(Debug)]
+pub struct GlobalDescriptorSet<'a> {
+    /// the parameter block's path from the global scope, like `scene.material`,
+    /// or `globals` for the loose globals
+    pub parameter_path: String,
+    /// the parameter block's element type; None for the loose globals
+    pub type_name: Option<&'a str>,
+    /// includes nested parameter blocks, which belong to later sets
+    pub fields: &'a [StructField],
+}
+
+/// Global parameters grouped by descriptor set, in pipeline layout order:
+/// the loose globals' set, then each parameter block before the blocks nested in it.
+/// Blocks holding nothing but other blocks get no set of their own,
+/// and push constants take none.
+pub fn global_descriptor_sets(
+    global_parameters: &[GlobalParameter],
+) -> Vec<GlobalDescriptorSet<'_>> {
+    let mut descriptor_sets = vec![];
+
+    for global_parameter in global_parameters {
+        match global_parameter {
+            GlobalParameter::Loose(loose) => descriptor_sets.push(GlobalDescriptorSet {
+                parameter_path: LOOSE_GLOBALS_NAME.to_string(),
+                type_name: None,
+                fields: &loose.fields,
+            }),
+
+            GlobalParameter::ParameterBlock(block) => add_parameter_block_sets(
+                block.parameter_name.clone(),
+                &block.element_type,
+                &mut descriptor_sets,
+            ),
+
+            GlobalParameter::PushConstants(_) => {}
+        }
+    }
+
+    descriptor_sets
+}
+
+fn add_parameter_block_sets<'a>(
+    parameter_path: String,
+    element_type: &'a ParameterBlockElementType,
+    descriptor_sets: &mut Vec<GlobalDescriptorSet<'a>>,
+) {
+    let has_own_set = element_type
+        .fields
+        .iter()
+        .any(|field| !matches!(field, StructField::ParameterBlock(_)));
+    if has_own_set {
+        descriptor_sets.push(GlobalDescriptorSet {
+            parameter_path: parameter_path.clone(),
+            type_name: Some(&element_type.type_name),
+            fields: &element_type.fields,
+        });
+    }
+
+    for field in &element_type.fields {
+        if let StructField::ParameterBlock(nested) = field {
+            add_parameter_block_sets(
+                format!("{parameter_path}.{}", nested.field_name),
+                &nested.element_type,
+                descriptor_sets,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn float_field(field_name: &str, offset: usize) -> StructField {
+        StructField::Scalar(ScalarStructField {
+            field_name: field_name.to_string(),
+            binding: Binding::Uniform(OffsetSizeBinding { offset, size: 4 }),
+            scalar_type: ScalarType::Float32,
+        })
+    }
+
+    fn block_field(field_name: &str, type_name: &str, fields: Vec<StructField>) -> StructField {
+        StructField::ParameterBlock(ParameterBlockStructField {
+            field_name: field_name.to_string(),
+            element_type: ParameterBlockElementType {
+                type_name: type_name.to_string(),
+                fields,
+            },
+        })
+    }
+
+    #[test]
+    fn descriptor_sets_are_in_pipeline_layout_order() {
+        let global_parameters = vec![
+            GlobalParameter::Loose(LooseGlobalParameters {
+                fields: vec![float_field("time", 0)],
+            }),
+            GlobalParameter::ParameterBlock(ParameterBlockGlobalParameter {
+                parameter_name: "scene".to_string(),
+                element_type: ParameterBlockElementType {
+                    type_name: "Scene".to_string(),
+                    fields: vec![
+                        float_field("exposure", 0),
+                        block_field("material", "Material", vec![float_field("roughness", 0)]),
+                    ],
+                },
+            }),
+            // only other blocks inside, so no set of its own
+            GlobalParameter::ParameterBlock(ParameterBlockGlobalParameter {
+                parameter_name: "passes".to_string(),
+                element_type: ParameterBlockElementType {
+                    type_name: "Passes".to_string(),
+                    fields: vec![block_field("blur", "Blur", vec![float_field("radius", 0)])],
+                },
+            }),
+            // pushed, not bound
+            GlobalParameter::PushConstants(PushConstantsGlobalParameter {
+                parameter_name: "draw".to_string(),
+                element_type: ParameterBlockElementType {
+                    type_name: "DrawParams".to_string(),
+                    fields: vec![float_field("scale", 0)],
+                },
+            }),
+        ];
+
+        let sets: Vec<_> = global_descriptor_sets(&global_parameters)
+            .into_iter()
+            .map(|set| (set.parameter_path, set.type_name))
+            .collect();
+
+        assert_eq!(
+            sets,
+            [
+                ("globals".to_string(), None),
+                ("scene".to_string(), Some("Scene")),
+                ("scene.material".to_string(), Some("Material")),
+                ("passes.blur".to_string(), Some("Blur")),
+            ]
+        );
+    }
+}
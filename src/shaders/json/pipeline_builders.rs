@@ -12,6 +12,17 @@ pub struct ReflectedPipelineLayout {
     pub push_constant_ranges: Vec<ReflectedPushConstantRange>,
 }
 
+impl ReflectedPipelineLayout {
+    /// the bytes of push constant data covered by all stages' ranges
+    pub fn push_constants_size(&self) -> usize {
+        self.push_constant_ranges
+            .iter()
+            .map(|range| (range.offset + range.size) as usize)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
 /// reflected data for creating a vulkan DescriptorSetLayout
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,6 +59,9 @@ pub enum ReflectedBindingType {
     ConstantBuffer,
     CombinedTextureSampler,
     StorageImage,
+    AccelerationStructure,
+    /// a ByteAddressBuffer or RWByteAddressBuffer
+    StorageBuffer,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
@@ -56,6 +70,8 @@ pub enum ReflectedStageFlags {
     Vertex,
     Fragment,
     Compute,
+    Task,
+    Mesh,
     All,
     Empty,
 }
@@ -0,0 +1,267 @@
+//! A parameter block's uniform data as a flat list of fields with absolute
+//! offsets, for tools that read and write uniforms without generated types
+
+use super::{
+    Binding, GlobalDescriptorSet, ParameterBlockElementType, PointeeType, ScalarType, StructField,
+    VectorStructField,
+};
+
+/// One scalar, vector, or matrix within a parameter block's uniform buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniformField {
+    /// dotted path from the block, like `light.color`
+    pub path: String,
+    /// byte offset from the start of the uniform buffer
+    pub offset: usize,
+    pub kind: UniformFieldKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniformFieldKind {
+    Float,
+    Uint,
+    Uint64,
+    /// a float vector with this many components
+    FloatVector(usize),
+    /// a row-major float4x4
+    Float4x4,
+}
+
+impl UniformFieldKind {
+    pub fn size(self) -> usize {
+        match self {
+            UniformFieldKind::Float | UniformFieldKind::Uint => 4,
+            UniformFieldKind::Uint64 => 8,
+            UniformFieldKind::FloatVector(count) => 4 * count,
+            UniformFieldKind::Float4x4 => 64,
+        }
+    }
+}
+
+/// A pointer within a parameter block's uniform buffer, and the std430
+/// layout of the elements it addresses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerField {
+    /// dotted path from the block, like `particles.alive`
+    pub path: String,
+    /// byte offset of the 8-byte address from the start of the uniform buffer
+    pub offset: usize,
+    pub pointee_type_name: String,
+    /// one element's fields, with offsets from the element's start
+    pub pointee_fields: Vec<UniformField>,
+    /// the reflected std430 size of one element
+    pub pointee_size: usize,
+}
+
+impl ParameterBlockElementType {
+    /// Every uniform field, nested structs flattened, in declaration order.
+    /// Resources, pointers, and nested parameter blocks have no uniform value
+    /// here to edit, and are skipped.
+    pub fn uniform_fields(&self) -> Vec<UniformField> {
+        let mut uniform_fields = vec![];
+        gather_uniform_fields(&self.fields, "", 0, &mut uniform_fields);
+        uniform_fields
+    }
+}
+
+impl GlobalDescriptorSet<'_> {
+    /// The uniform fields of the set's binding 0 buffer,
+    /// like [`ParameterBlockElementType::uniform_fields`]
+    pub fn uniform_fields(&self) -> Vec<UniformField> {
+        let mut uniform_fields = vec![];
+        gather_uniform_fields(self.fields, "", 0, &mut uniform_fields);
+        uniform_fields
+    }
+
+    /// The pointer fields of the set's binding 0 buffer, nested structs flattened
+    pub fn pointer_fields(&self) -> Vec<PointerField> {
+        let mut pointer_fields = vec![];
+        gather_pointer_fields(self.fields, "", 0, &mut pointer_fields);
+        pointer_fields
+    }
+}
+
+fn gather_uniform_fields(
+    fields: &[StructField],
+    prefix: &str,
+    base_offset: usize,
+    uniform_fields: &mut Vec<UniformField>,
+) {
+    for field in fields {
+        let (field_name, binding, kind) = match field {
+            StructField::Scalar(scalar) => {
+                let kind = match scalar.scalar_type {
+                    ScalarType::Float32 => UniformFieldKind::Float,
+                    ScalarType::Uint32 => UniformFieldKind::Uint,
+                    ScalarType::Uint64 => UniformFieldKind::Uint64,
+                };
+                (&scalar.field_name, &scalar.binding, kind)
+            }
+            StructField::Vector(VectorStructField::Bound(vector)) => (
+                &vector.field_name,
+                &vector.binding,
+                UniformFieldKind::FloatVector(vector.element_count),
+            ),
+            StructField::Matrix(matrix) if matrix.row_count == 4 && matrix.column_count == 4 => (
+                &matrix.field_name,
+                &matrix.binding,
+                UniformFieldKind::Float4x4,
+            ),
+            StructField::Struct(nested) => {
+                if let Binding::Uniform(uniform) = &nested.binding {
+                    let nested_prefix = format!("{prefix}{}.", nested.field_name);
+                    gather_uniform_fields(
+                        &nested.struct_type.fields,
+                        &nested_prefix,
+                        base_offset + uniform.offset,
+                        uniform_fields,
+                    );
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        let Binding::Uniform(uniform) = binding else {
+            continue;
+        };
+
+        uniform_fields.push(UniformField {
+            path: format!("{prefix}{field_name}"),
+            offset: base_offset + uniform.offset,
+            kind,
+        });
+    }
+}
+
+fn gather_pointer_fields(
+    fields: &[StructField],
+    prefix: &str,
+    base_offset: usize,
+    pointer_fields: &mut Vec<PointerField>,
+) {
+    for field in fields {
+        match field {
+            StructField::Pointer(pointer) => {
+                let Binding::Uniform(uniform) = &pointer.binding else {
+                    continue;
+                };
+
+                let (pointee_type_name, pointee_fields) = match &pointer.pointee_type {
+                    PointeeType::Struct(struct_type) => {
+                        let mut pointee_fields = vec![];
+                        gather_uniform_fields(&struct_type.fields, "", 0, &mut pointee_fields);
+                        (struct_type.type_name.clone(), pointee_fields)
+                    }
+                    PointeeType::Vector(vector) => {
+                        let kind = UniformFieldKind::FloatVector(vector.element_count);
+                        (
+                            format!("float{}", vector.element_count),
+                            vec![element_field(kind)],
+                        )
+                    }
+                    PointeeType::Scalar(scalar) => {
+                        let (name, kind) = match scalar.scalar_type {
+                            ScalarType::Float32 => ("float", UniformFieldKind::Float),
+                            ScalarType::Uint32 => ("uint", UniformFieldKind::Uint),
+                            ScalarType::Uint64 => ("uint64_t", UniformFieldKind::Uint64),
+                        };
+                        (name.to_string(), vec![element_field(kind)])
+                    }
+                };
+
+                pointer_fields.push(PointerField {
+                    path: format!("{prefix}{}", pointer.field_name),
+                    offset: base_offset + uniform.offset,
+                    pointee_type_name,
+                    pointee_fields,
+                    pointee_size: pointer.pointee_size,
+                });
+            }
+            StructField::Struct(nested) => {
+                if let Binding::Uniform(uniform) = &nested.binding {
+                    let nested_prefix = format!("{prefix}{}.", nested.field_name);
+                    gather_pointer_fields(
+                        &nested.struct_type.fields,
+                        &nested_prefix,
+                        base_offset + uniform.offset,
+                        pointer_fields,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The single unnamed field of a scalar or vector pointee
+fn element_field(kind: UniformFieldKind) -> UniformField {
+    UniformField {
+        path: String::new(),
+        offset: 0,
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shaders::json::{GlobalParameter, ReflectionJson, global_descriptor_sets};
+
+    use super::*;
+
+    #[test]
+    fn sdf_2d_params_are_flattened() {
+        let raw = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/sdf_2d.json"
+        ));
+        let reflection_json: ReflectionJson = serde_json::from_str(raw).unwrap();
+        let GlobalParameter::ParameterBlock(block) = &reflection_json.global_parameters[0] else {
+            panic!("expected a parameter block");
+        };
+
+        let fields: Vec<_> = block
+            .element_type
+            .uniform_fields()
+            .into_iter()
+            .map(|field| (field.path, field.offset, field.kind))
+            .collect();
+
+        assert_eq!(
+            fields,
+            [
+                (
+                    "resolution".to_string(),
+                    0,
+                    UniformFieldKind::FloatVector(2)
+                ),
+                ("time".to_string(), 8, UniformFieldKind::Float),
+                ("beatProximity".to_string(), 12, UniformFieldKind::Float),
+            ]
+        );
+    }
+
+    #[test]
+    fn sprite_batch_pointer_has_std430_pointee_fields() {
+        let raw = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/shaders/compiled/sprite_batch.json"
+        ));
+        let reflection_json: ReflectionJson = serde_json::from_str(raw).unwrap();
+        let descriptor_sets = global_descriptor_sets(&reflection_json.global_parameters);
+
+        let pointer_fields = descriptor_sets[0].pointer_fields();
+        assert_eq!(pointer_fields.len(), 1);
+        let sprites = &pointer_fields[0];
+        assert_eq!(sprites.path, "sprites");
+        assert_eq!(sprites.offset, 0);
+        assert_eq!(sprites.pointee_type_name, "Sprite");
+        assert_eq!(sprites.pointee_size, 64);
+
+        let color = sprites.pointee_fields.last().unwrap();
+        assert_eq!(
+            (color.path.as_str(), color.offset, color.kind),
+            ("color", 48, UniformFieldKind::FloatVector(4))
+        );
+    }
+}
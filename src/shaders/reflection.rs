@@ -13,6 +13,7 @@ pub fn reflection_json(
     program_layout: &slang::reflection::Shader,
 ) -> anyhow::Result<ReflectionJson> {
     let parameters = reflect_entry_points(program_layout)?;
+    let writes_velocity = reflect_writes_velocity(program_layout);
 
     let pipeline_layout = reflect_pipeline_layout(program_layout);
 
@@ -20,7 +21,9 @@ pub fn reflection_json(
         source_file_name: source_file_name.to_string(),
         global_parameters: parameters.global_parameters,
         vertex_entry_point: parameters.entry_points.vertex_entry_point,
+        task_entry_point: parameters.entry_points.task_entry_point,
         fragment_entry_point: parameters.entry_points.fragment_entry_point,
+        writes_velocity,
         pipeline_layout,
     };
 
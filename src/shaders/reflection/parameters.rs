@@ -11,7 +11,9 @@ pub struct Parameters {
 }
 
 pub struct VertFragEntryPoints {
+    /// the vertex stage, or the mesh stage of a mesh shader
     pub vertex_entry_point: EntryPoint,
+    pub task_entry_point: Option<EntryPoint>,
     pub fragment_entry_point: EntryPoint,
 }
 
@@ -25,49 +27,38 @@ pub fn reflect_entry_points(
     program_layout: &slang::reflection::Shader,
 ) -> anyhow::Result<Parameters> {
     let mut vertex_entry_point: Option<EntryPoint> = None;
+    let mut mesh_entry_point: Option<EntryPoint> = None;
+    let mut task_entry_point: Option<EntryPoint> = None;
     let mut fragment_entry_point: Option<EntryPoint> = None;
 
-    let mut global_parameters: Vec<GlobalParameter> = vec![];
-    for global_param in program_layout.parameters() {
-        let parameter_name = global_param.name().unwrap().to_string();
-
-        if global_param.type_layout().unwrap().kind() != slang::TypeKind::ParameterBlock {
-            anyhow::bail!(
-                "non-ParameterBlock global: {parameter_name}; only ParameterBlock globals are supported"
-            )
-        }
+    let global_parameters = reflect_global_parameters(program_layout)?;
 
-        let element_type_layout = global_param
-            .type_layout()
-            .unwrap()
-            .element_type_layout()
-            .unwrap();
-
-        let element_type = match element_type_layout.kind() {
-            slang::TypeKind::Struct => {
-                let element_type_name = element_type_layout.name().unwrap().to_string();
-                let fields = reflect_struct_fields(element_type_layout, program_layout, false)?;
+    for entry_point in program_layout.entry_points() {
+        let entry_point_name = entry_point.name().unwrap().to_string();
 
-                ParameterBlockElementType {
-                    type_name: element_type_name,
-                    fields,
-                }
+        // mesh and task parameters are all outputs, payloads, and system values,
+        // with nothing for the cpu to provide
+        match entry_point.stage() {
+            slang::Stage::Mesh => {
+                mesh_entry_point = Some(EntryPoint {
+                    entry_point_name,
+                    stage: EntryPointStage::Mesh,
+                    parameters: vec![],
+                });
+                continue;
             }
 
-            k => unimplemented!("type kind reflection not implemented: {k:?}"),
-        };
-
-        let parameter_block = ParameterBlockGlobalParameter {
-            parameter_name,
-            element_type,
-        };
-        let global_parameter = GlobalParameter::ParameterBlock(parameter_block);
-
-        global_parameters.push(global_parameter);
-    }
+            slang::Stage::Amplification => {
+                task_entry_point = Some(EntryPoint {
+                    entry_point_name,
+                    stage: EntryPointStage::Task,
+                    parameters: vec![],
+                });
+                continue;
+            }
 
-    for entry_point in program_layout.entry_points() {
-        let entry_point_name = entry_point.name().unwrap().to_string();
+            _ => {}
+        }
 
         let mut params = vec![];
         for param in entry_point.parameters() {
@@ -142,13 +133,20 @@ pub fn reflect_entry_points(
     }
 
     let (vertex_entry_point, fragment_entry_point) =
-        match (vertex_entry_point, fragment_entry_point) {
-            (Some(v), Some(f)) => (v, f),
-            _ => anyhow::bail!("failed to load vertex and fragment entry points"),
+        match (vertex_entry_point, mesh_entry_point, fragment_entry_point) {
+            (Some(v), None, Some(f)) | (None, Some(v), Some(f)) => (v, f),
+            (Some(_), Some(_), _) => {
+                anyhow::bail!("a shader can't have both vertex and mesh entry points")
+            }
+            _ => anyhow::bail!("failed to load vertex (or mesh) and fragment entry points"),
         };
+    if task_entry_point.is_some() && !matches!(vertex_entry_point.stage, EntryPointStage::Mesh) {
+        anyhow::bail!("a task entry point requires a mesh entry point");
+    }
 
     let entry_points = VertFragEntryPoints {
         vertex_entry_point,
+        task_entry_point,
         fragment_entry_point,
     };
 
@@ -160,261 +158,405 @@ pub fn reflect_entry_points(
     Ok(parameters)
 }
 
-fn reflect_struct_fields(
-    struct_type_layout: &slang::reflection::TypeLayout,
+/// Whether the fragment entry point returns a struct with a float2
+/// `SV_Target1`: the velocity target's output convention
+pub fn reflect_writes_velocity(program_layout: &slang::reflection::Shader) -> bool {
+    let Some(fragment_entry_point) = program_layout
+        .entry_points()
+        .find(|entry_point| matches!(entry_point.stage(), slang::Stage::Fragment))
+    else {
+        return false;
+    };
+    let Some(result_type_layout) = fragment_entry_point
+        .result_var_layout()
+        .and_then(|result| result.type_layout())
+    else {
+        return false;
+    };
+    if result_type_layout.kind() != slang::TypeKind::Struct {
+        return false;
+    }
+
+    result_type_layout.fields().any(|field| {
+        let is_target_1 = field
+            .semantic_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case("SV_Target"))
+            && field.semantic_index() == 1;
+        let is_float2 = field.type_layout().is_some_and(|type_layout| {
+            type_layout.kind() == slang::TypeKind::Vector && type_layout.element_count() == Some(2)
+        });
+
+        is_target_1 && is_float2
+    })
+}
+
+/// Parameter blocks in declaration order, after any loose globals
+/// (gathered into one entry, as slang gathers them into one descriptor set)
+fn reflect_global_parameters(
     program_layout: &slang::reflection::Shader,
-    in_pointer_pointee: bool,
-) -> anyhow::Result<Vec<StructField>> {
-    let mut fields = vec![];
+) -> anyhow::Result<Vec<GlobalParameter>> {
+    let mut loose_fields: Vec<StructField> = vec![];
+    let mut global_parameters: Vec<GlobalParameter> = vec![];
 
-    for field in struct_type_layout.fields() {
-        let field_name = field.name().unwrap().to_string();
-        let field_semantic_name = field.semantic_name().map(str::to_string);
-        let field_type_layout = field.type_layout().unwrap();
+    for global_param in program_layout.parameters() {
+        let parameter_name = global_param.name().unwrap().to_string();
+        let type_layout = global_param.type_layout().unwrap();
 
-        let binding = param_binding(field);
+        match type_layout.kind() {
+            slang::TypeKind::ParameterBlock => {
+                let element_type = reflect_parameter_block_element(
+                    type_layout.element_type_layout().unwrap(),
+                    program_layout,
+                )?;
 
-        let field_json = match field_type_layout.kind() {
-            slang::TypeKind::Scalar => {
-                let slang_scalar_type = field_type_layout.scalar_type().unwrap();
-                let scalar_type = scalar_from_slang(slang_scalar_type);
+                let parameter_block = ParameterBlockGlobalParameter {
+                    parameter_name,
+                    element_type,
+                };
+                global_parameters.push(GlobalParameter::ParameterBlock(parameter_block));
+            }
 
-                StructField::Scalar(ScalarStructField {
-                    field_name,
-                    binding: binding.unwrap(),
-                    scalar_type,
-                })
+            slang::TypeKind::ConstantBuffer
+                if global_param.category()
+                    == Some(slang::ParameterCategory::PushConstantBuffer) =>
+            {
+                if global_parameters
+                    .iter()
+                    .any(|param| matches!(param, GlobalParameter::PushConstants(_)))
+                {
+                    anyhow::bail!(
+                        "push constant global: {parameter_name}; \
+                        a pipeline has one push constant range, so declare one"
+                    );
+                }
+
+                let element_type = reflect_parameter_block_element(
+                    type_layout.element_type_layout().unwrap(),
+                    program_layout,
+                )?;
+
+                let push_constants = PushConstantsGlobalParameter {
+                    parameter_name,
+                    element_type,
+                };
+                global_parameters.push(GlobalParameter::PushConstants(push_constants));
             }
 
-            slang::TypeKind::Vector => {
-                let vec_elem_count = field_type_layout.element_count().unwrap();
+            slang::TypeKind::ConstantBuffer => anyhow::bail!(
+                "ConstantBuffer global: {parameter_name}; use a ParameterBlock, \
+                declare its fields as loose `uniform` globals, \
+                or mark it [[vk::push_constant]]"
+            ),
 
-                let vec_element_type_layout = field_type_layout.element_type_layout().unwrap();
+            _ => loose_fields.push(reflect_field(global_param, program_layout, false)?),
+        }
+    }
 
-                let slang_scalar_type = vec_element_type_layout.scalar_type().unwrap();
+    if !loose_fields.is_empty() {
+        let loose = LooseGlobalParameters {
+            fields: loose_fields,
+        };
+        global_parameters.insert(0, GlobalParameter::Loose(loose));
+    }
 
-                let scalar_type = scalar_from_slang(slang_scalar_type);
-                let vec_elem_type =
-                    VectorElementType::Scalar(ScalarVectorElementType { scalar_type });
+    Ok(global_parameters)
+}
 
-                let vec_struct_field = match (binding, field_semantic_name) {
-                    (None, Some(field_semantic)) => {
-                        VectorStructField::Semantic(SemanticVectorStructField {
-                            field_name,
-                            semantic_name: field_semantic,
-                            element_count: vec_elem_count,
-                            element_type: vec_elem_type,
-                        })
-                    }
+fn reflect_parameter_block_element(
+    element_type_layout: &slang::reflection::TypeLayout,
+    program_layout: &slang::reflection::Shader,
+) -> anyhow::Result<ParameterBlockElementType> {
+    match element_type_layout.kind() {
+        slang::TypeKind::Struct => {
+            let element_type_name = element_type_layout.name().unwrap().to_string();
+            let fields = reflect_struct_fields(element_type_layout, program_layout, false)?;
+
+            Ok(ParameterBlockElementType {
+                type_name: element_type_name,
+                fields,
+            })
+        }
 
-                    (Some(field_binding), _optional_semantic) => {
-                        VectorStructField::Bound(BoundVectorStructField {
-                            field_name,
-                            binding: field_binding,
-                            element_count: vec_elem_count,
-                            element_type: vec_elem_type,
-                        })
-                    }
+        k => unimplemented!("type kind reflection not implemented: {k:?}"),
+    }
+}
 
-                    (b, s) => {
-                        anyhow::bail!(
-                            "unexpected combination of vector binding and semantic {b:?}, {s:?}"
-                        )
-                    }
-                };
+fn reflect_struct_fields(
+    struct_type_layout: &slang::reflection::TypeLayout,
+    program_layout: &slang::reflection::Shader,
+    in_pointer_pointee: bool,
+) -> anyhow::Result<Vec<StructField>> {
+    struct_type_layout
+        .fields()
+        .map(|field| reflect_field(field, program_layout, in_pointer_pointee))
+        .collect()
+}
 
-                StructField::Vector(vec_struct_field)
-            }
+fn reflect_field(
+    field: &slang::reflection::VariableLayout,
+    program_layout: &slang::reflection::Shader,
+    in_pointer_pointee: bool,
+) -> anyhow::Result<StructField> {
+    let field_name = field.name().unwrap().to_string();
+    let field_semantic_name = field.semantic_name().map(str::to_string);
+    let field_type_layout = field.type_layout().unwrap();
+
+    // a nested block takes a descriptor set rather than a binding, so it's
+    // handled before asking for one
+    if field_type_layout.kind() == slang::TypeKind::ParameterBlock {
+        if in_pointer_pointee {
+            anyhow::bail!(
+                "parameter block field '{field_name}': not allowed in a pointer's pointee"
+            );
+        }
 
-            slang::TypeKind::Matrix => {
-                let row_count = field_type_layout.row_count().unwrap();
-                let column_count = field_type_layout.column_count().unwrap();
+        let element_type = reflect_parameter_block_element(
+            field_type_layout.element_type_layout().unwrap(),
+            program_layout,
+        )?;
 
-                let mat_element_type_layout = field_type_layout.element_type_layout().unwrap();
+        return Ok(StructField::ParameterBlock(ParameterBlockStructField {
+            field_name,
+            element_type,
+        }));
+    }
 
-                let scalar_type = scalar_from_slang(mat_element_type_layout.scalar_type().unwrap());
-                let element_type =
-                    VectorElementType::Scalar(ScalarVectorElementType { scalar_type });
+    let binding = param_binding(field);
 
-                StructField::Matrix(MatrixStructField {
-                    field_name,
-                    binding: binding.expect("matrix field without binding"),
-                    row_count,
-                    column_count,
-                    element_type,
-                })
-            }
+    let field_json = match field_type_layout.kind() {
+        slang::TypeKind::Scalar => {
+            let slang_scalar_type = field_type_layout.scalar_type().unwrap();
+            let scalar_type = scalar_from_slang(slang_scalar_type);
 
-            slang::TypeKind::Struct => {
-                let field_fields =
-                    reflect_struct_fields(field_type_layout, program_layout, in_pointer_pointee)?;
-                let field_type_name = field_type_layout.name().unwrap().to_string();
-
-                StructField::Struct(StructStructField {
-                    field_name,
-                    binding: binding.expect("struct field without binding"),
-                    struct_type: StructFieldType {
-                        type_name: field_type_name,
-                        fields: field_fields,
-                    },
-                })
-            }
+            StructField::Scalar(ScalarStructField {
+                field_name,
+                binding: binding.unwrap(),
+                scalar_type,
+            })
+        }
 
-            slang::TypeKind::Resource => {
-                let shape_with_flags = field_type_layout.resource_shape().unwrap();
-                let slang_base_shape = slang_base_shape(shape_with_flags);
-
-                let resource_shape = match slang_base_shape {
-                    slang::ResourceShape::SlangTexture2d => {
-                        let access = field_type_layout.resource_access();
-                        if access == Some(slang::ResourceAccess::ReadWrite) {
-                            ResourceShape::RWTexture2D
-                        } else {
-                            ResourceShape::Texture2D
-                        }
-                    }
-                    slang::ResourceShape::SlangStructuredBuffer => anyhow::bail!(
-                        "field '{field_name}': StructuredBuffer/RWStructuredBuffer descriptors \
-                        are unsupported; use a BDA pointer instead (e.g. Addr<T> via import addr, \
-                        or LayoutPtr<T, Std430DataLayout>)"
-                    ),
-                    s => todo!("unhandled slang base shape: {s:?}"),
-                };
+        slang::TypeKind::Vector => {
+            let vec_elem_count = field_type_layout.element_count().unwrap();
 
-                let result_type = field_type_layout.resource_result_type().unwrap();
-                let result_type = match result_type.kind() {
-                    slang::TypeKind::Vector => {
-                        let element_count = result_type.element_count();
+            let vec_element_type_layout = field_type_layout.element_type_layout().unwrap();
 
-                        let scalar_type = scalar_from_slang(result_type.scalar_type());
-                        let element_type =
-                            VectorElementType::Scalar(ScalarVectorElementType { scalar_type });
+            let slang_scalar_type = vec_element_type_layout.scalar_type().unwrap();
 
-                        ResourceResultType::Vector(VectorResultType {
-                            element_count,
-                            element_type,
-                        })
-                    }
+            let scalar_type = scalar_from_slang(slang_scalar_type);
+            let vec_elem_type = VectorElementType::Scalar(ScalarVectorElementType { scalar_type });
 
-                    slang::TypeKind::Struct => {
-                        let element_type_layout = field_type_layout.element_type_layout().unwrap();
-                        let element_type_name = element_type_layout.name().unwrap().to_string();
+            let vec_struct_field = match (binding, field_semantic_name) {
+                (None, Some(field_semantic)) => {
+                    VectorStructField::Semantic(SemanticVectorStructField {
+                        field_name,
+                        semantic_name: field_semantic,
+                        element_count: vec_elem_count,
+                        element_type: vec_elem_type,
+                    })
+                }
 
-                        let struct_fields =
-                            reflect_struct_fields(element_type_layout, program_layout, false)?;
+                (Some(field_binding), semantic_name) => {
+                    VectorStructField::Bound(BoundVectorStructField {
+                        field_name,
+                        binding: field_binding,
+                        element_count: vec_elem_count,
+                        element_type: vec_elem_type,
+                        semantic_name,
+                    })
+                }
 
-                        let struct_result_type = StructResultType {
-                            type_name: element_type_name,
-                            fields: struct_fields,
-                        };
+                (b, s) => {
+                    anyhow::bail!(
+                        "unexpected combination of vector binding and semantic {b:?}, {s:?}"
+                    )
+                }
+            };
 
-                        ResourceResultType::Struct(struct_result_type)
-                    }
+            StructField::Vector(vec_struct_field)
+        }
+
+        slang::TypeKind::Matrix => {
+            let row_count = field_type_layout.row_count().unwrap();
+            let column_count = field_type_layout.column_count().unwrap();
+
+            let mat_element_type_layout = field_type_layout.element_type_layout().unwrap();
+
+            let scalar_type = scalar_from_slang(mat_element_type_layout.scalar_type().unwrap());
+            let element_type = VectorElementType::Scalar(ScalarVectorElementType { scalar_type });
+
+            StructField::Matrix(MatrixStructField {
+                field_name,
+                binding: binding.expect("matrix field without binding"),
+                row_count,
+                column_count,
+                element_type,
+            })
+        }
+
+        slang::TypeKind::Struct => {
+            let field_fields =
+                reflect_struct_fields(field_type_layout, program_layout, in_pointer_pointee)?;
+            let field_type_name = field_type_layout.name().unwrap().to_string();
+
+            StructField::Struct(StructStructField {
+                field_name,
+                binding: binding.expect("struct field without binding"),
+                struct_type: StructFieldType {
+                    type_name: field_type_name,
+                    fields: field_fields,
+                },
+            })
+        }
 
-                    slang::TypeKind::Scalar => {
-                        let scalar_type = scalar_from_slang(result_type.scalar_type());
-                        ResourceResultType::Scalar(ScalarResultType { scalar_type })
+        slang::TypeKind::Resource => {
+            let shape_with_flags = field_type_layout.resource_shape().unwrap();
+            let slang_base_shape = slang_base_shape(shape_with_flags);
+
+            let resource_shape = match slang_base_shape {
+                slang::ResourceShape::SlangTexture2d => {
+                    let access = field_type_layout.resource_access();
+                    if access == Some(slang::ResourceAccess::ReadWrite) {
+                        ResourceShape::RWTexture2D
+                    } else {
+                        ResourceShape::Texture2D
                     }
+                }
+                slang::ResourceShape::SlangStructuredBuffer => anyhow::bail!(
+                    "field '{field_name}': StructuredBuffer/RWStructuredBuffer descriptors \
+                        are unsupported; use a BDA pointer instead (e.g. Addr<T> via import addr, \
+                        or LayoutPtr<T, Std430DataLayout>)"
+                ),
+                slang::ResourceShape::SlangAccelerationStructure => {
+                    ResourceShape::AccelerationStructure
+                }
+                slang::ResourceShape::SlangByteaddressBuffer => {
+                    let access = field_type_layout.resource_access();
+                    if access == Some(slang::ResourceAccess::ReadWrite) {
+                        ResourceShape::RWByteAddressBuffer
+                    } else {
+                        ResourceShape::ByteAddressBuffer
+                    }
+                }
+                s => todo!("unhandled slang base shape: {s:?}"),
+            };
 
-                    k => todo!("result type kind not handled: {k:?}"),
-                };
+            let result_type = match resource_shape {
+                ResourceShape::AccelerationStructure
+                | ResourceShape::ByteAddressBuffer
+                | ResourceShape::RWByteAddressBuffer => None,
+                _ => Some(reflect_resource_result_type(
+                    field_type_layout.resource_result_type().unwrap(),
+                    field_type_layout,
+                    program_layout,
+                )?),
+            };
 
-                StructField::Resource(ResourceStructField {
-                    field_name,
-                    binding: binding.expect("resource struct field without binding"),
-                    resource_shape,
-                    result_type,
-                })
-            }
+            StructField::Resource(ResourceStructField {
+                field_name,
+                binding: binding.expect("resource struct field without binding"),
+                resource_shape,
+                result_type,
+            })
+        }
 
-            slang::TypeKind::Pointer => {
-                if in_pointer_pointee {
-                    anyhow::bail!(
-                        "pointer field '{field_name}': nested pointers \
+        slang::TypeKind::Pointer => {
+            if in_pointer_pointee {
+                anyhow::bail!(
+                    "pointer field '{field_name}': nested pointers \
                         (a pointer inside a pointer's pointee) are not supported"
-                    );
-                }
+                );
+            }
 
-                let ptr_type = field_type_layout.ty().unwrap();
-                let ptr_type_name = ptr_type
-                    .full_name()
-                    .map(|blob| String::from_utf8_lossy(blob.as_slice()).to_string())
-                    .unwrap_or_default();
-
-                // A default `T*` pointee uses slang's natural (C-like) layout, which
-                // reflection misreports for layout-annotated pointers and glam types
-                // cannot always express; only std430 pointees are supported.
-                let layout_arg = ptr_type_name
-                    .trim_end_matches('>')
-                    .rsplit(',')
-                    .next()
-                    .map(str::trim)
-                    .unwrap_or_default();
-                if layout_arg != "Std430DataLayout" {
-                    anyhow::bail!(
-                        "pointer field '{field_name}' ({ptr_type_name}): only Std430DataLayout \
+            let ptr_type = field_type_layout.ty().unwrap();
+            let ptr_type_name = ptr_type
+                .full_name()
+                .map(|blob| String::from_utf8_lossy(blob.as_slice()).to_string())
+                .unwrap_or_default();
+
+            // A default `T*` pointee uses slang's natural (C-like) layout, which
+            // reflection misreports for layout-annotated pointers and glam types
+            // cannot always express; only std430 pointees are supported.
+            let layout_arg = ptr_type_name
+                .trim_end_matches('>')
+                .rsplit(',')
+                .next()
+                .map(str::trim)
+                .unwrap_or_default();
+            if layout_arg != "Std430DataLayout" {
+                anyhow::bail!(
+                    "pointer field '{field_name}' ({ptr_type_name}): only Std430DataLayout \
                         pointers are supported; declare it as Addr<T> (import addr;) \
                         or LayoutPtr<T, Std430DataLayout>"
-                    );
-                }
+                );
+            }
 
-                // The Access generic argument prints as its enum case name
-                // (e.g. `Ptr<T, Access.Read, AddressSpace.Device, Std430DataLayout>`).
-                let generic_args: Vec<&str> = ptr_type_name
-                    .trim_end_matches('>')
-                    .split(',')
-                    .map(str::trim)
-                    .collect();
-                let access = if generic_args.contains(&"Access.Read") {
-                    PointerAccess::Read
-                } else if generic_args.contains(&"Access.Immutable") {
-                    PointerAccess::Immutable
-                } else {
-                    PointerAccess::ReadWrite
-                };
+            // The Access generic argument prints as its enum case name
+            // (e.g. `Ptr<T, Access.Read, AddressSpace.Device, Std430DataLayout>`).
+            let generic_args: Vec<&str> = ptr_type_name
+                .trim_end_matches('>')
+                .split(',')
+                .map(str::trim)
+                .collect();
+            let access = if generic_args.contains(&"Access.Read") {
+                PointerAccess::Read
+            } else if generic_args.contains(&"Access.Immutable") {
+                PointerAccess::Immutable
+            } else {
+                PointerAccess::ReadWrite
+            };
 
-                // The pointer's own element_type_layout() reports default-layout
-                // offsets even for Std430DataLayout pointers; query the std430
-                // layout explicitly.
-                let pointee_ty = field_type_layout
-                    .element_type_layout()
-                    .unwrap()
-                    .ty()
-                    .unwrap();
-                let pointee_layout = program_layout
-                    .type_layout(pointee_ty, slang::LayoutRules::DefaultStructuredBuffer)
-                    .expect("failed to lay out pointer pointee type");
-
-                if pointee_layout.kind() != slang::TypeKind::Struct {
-                    anyhow::bail!(
-                        "pointer field '{field_name}': only struct pointees are supported, got {:?}",
-                        pointee_layout.kind()
-                    );
+            // The pointer's own element_type_layout() reports default-layout
+            // offsets even for Std430DataLayout pointers; query the std430
+            // layout explicitly.
+            let pointee_ty = field_type_layout
+                .element_type_layout()
+                .unwrap()
+                .ty()
+                .unwrap();
+            let pointee_layout = program_layout
+                .type_layout(pointee_ty, slang::LayoutRules::DefaultStructuredBuffer)
+                .expect("failed to lay out pointer pointee type");
+
+            let pointee_type = match pointee_layout.kind() {
+                slang::TypeKind::Struct => PointeeType::Struct(StructFieldType {
+                    type_name: pointee_layout.name().unwrap().to_string(),
+                    fields: reflect_struct_fields(pointee_layout, program_layout, true)?,
+                }),
+
+                slang::TypeKind::Vector => {
+                    let scalar_type = scalar_from_slang(pointee_ty.scalar_type());
+                    PointeeType::Vector(VectorPointeeType {
+                        element_count: pointee_ty.element_count(),
+                        element_type: VectorElementType::Scalar(ScalarVectorElementType {
+                            scalar_type,
+                        }),
+                    })
                 }
 
-                let pointee_type_name = pointee_layout.name().unwrap().to_string();
-                let pointee_fields = reflect_struct_fields(pointee_layout, program_layout, true)?;
-                let pointee_size = pointee_layout.size(slang::ParameterCategory::Uniform);
-
-                StructField::Pointer(PointerStructField {
-                    field_name,
-                    binding: binding.expect("pointer field without binding"),
-                    pointee_type: StructFieldType {
-                        type_name: pointee_type_name,
-                        fields: pointee_fields,
-                    },
-                    pointee_size,
-                    access,
-                })
-            }
+                slang::TypeKind::Scalar => PointeeType::Scalar(ScalarPointeeType {
+                    scalar_type: scalar_from_slang(pointee_ty.scalar_type()),
+                }),
 
-            k => todo!("field type layout kind not handled: {k:?}"),
-        };
+                k => anyhow::bail!(
+                    "pointer field '{field_name}': only struct, vector, and scalar pointees \
+                        are supported, got {k:?}"
+                ),
+            };
+            let pointee_size = pointee_layout.size(slang::ParameterCategory::Uniform);
+
+            StructField::Pointer(PointerStructField {
+                field_name,
+                binding: binding.expect("pointer field without binding"),
+                pointee_type,
+                pointee_size,
+                access,
+            })
+        }
 
-        fields.push(field_json);
-    }
+        k => todo!("field type layout kind not handled: {k:?}"),
+    };
 
-    Ok(fields)
+    Ok(field_json)
 }
 
 fn slang_base_shape(shape_with_flags: slang::ResourceShape) -> slang::ResourceShape {
@@ -425,6 +567,49 @@ fn slang_base_shape(shape_with_flags: slang::ResourceShape) -> slang::ResourceSh
     unsafe { std::mem::transmute(base_shape) }
 }
 
+fn reflect_resource_result_type(
+    result_type: &slang::reflection::Type,
+    field_type_layout: &slang::reflection::TypeLayout,
+    program_layout: &slang::reflection::Shader,
+) -> anyhow::Result<ResourceResultType> {
+    let result_type = match result_type.kind() {
+        slang::TypeKind::Vector => {
+            let element_count = result_type.element_count();
+
+            let scalar_type = scalar_from_slang(result_type.scalar_type());
+            let element_type = VectorElementType::Scalar(ScalarVectorElementType { scalar_type });
+
+            ResourceResultType::Vector(VectorResultType {
+                element_count,
+                element_type,
+            })
+        }
+
+        slang::TypeKind::Struct => {
+            let element_type_layout = field_type_layout.element_type_layout().unwrap();
+            let element_type_name = element_type_layout.name().unwrap().to_string();
+
+            let struct_fields = reflect_struct_fields(element_type_layout, program_layout, false)?;
+
+            let struct_result_type = StructResultType {
+                type_name: element_type_name,
+                fields: struct_fields,
+            };
+
+            ResourceResultType::Struct(struct_result_type)
+        }
+
+        slang::TypeKind::Scalar => {
+            let scalar_type = scalar_from_slang(result_type.scalar_type());
+            ResourceResultType::Scalar(ScalarResultType { scalar_type })
+        }
+
+        k => todo!("result type kind not handled: {k:?}"),
+    };
+
+    Ok(result_type)
+}
+
 fn scalar_from_slang(scalar: slang::ScalarType) -> ScalarType {
     match scalar {
         slang::ScalarType::Uint32 => ScalarType::Uint32,
@@ -439,43 +624,15 @@ fn scalar_from_slang(scalar: slang::ScalarType) -> ScalarType {
 pub fn reflect_compute_entry_point(
     program_layout: &slang::reflection::Shader,
 ) -> anyhow::Result<ComputeParameters> {
-    let mut global_parameters: Vec<GlobalParameter> = vec![];
-    for global_param in program_layout.parameters() {
-        let parameter_name = global_param.name().unwrap().to_string();
-
-        if global_param.type_layout().unwrap().kind() != slang::TypeKind::ParameterBlock {
-            anyhow::bail!(
-                "non-ParameterBlock global: {parameter_name}; only ParameterBlock globals are supported"
-            )
-        }
-
-        let element_type_layout = global_param
-            .type_layout()
-            .unwrap()
-            .element_type_layout()
-            .unwrap();
-
-        let element_type = match element_type_layout.kind() {
-            slang::TypeKind::Struct => {
-                let element_type_name = element_type_layout.name().unwrap().to_string();
-                let fields = reflect_struct_fields(element_type_layout, program_layout, false)?;
-
-                ParameterBlockElementType {
-                    type_name: element_type_name,
-                    fields,
-                }
-            }
-
-            k => unimplemented!("type kind reflection not implemented: {k:?}"),
-        };
-
-        let parameter_block = ParameterBlockGlobalParameter {
-            parameter_name,
-            element_type,
-        };
-        let global_parameter = GlobalParameter::ParameterBlock(parameter_block);
-
-        global_parameters.push(global_parameter);
+    let global_parameters = reflect_global_parameters(program_layout)?;
+    if let Some(push_constants) = global_parameters
+        .iter()
+        .find(|param| matches!(param, GlobalParameter::PushConstants(_)))
+    {
+        anyhow::bail!(
+            "push constant global: {}; compute pipelines don't support push constants",
+            push_constants.parameter_name()
+        );
     }
 
     let mut compute_entry_point: Option<EntryPoint> = None;
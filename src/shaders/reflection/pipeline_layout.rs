@@ -233,8 +233,12 @@ impl DescriptorSetLayoutBuilder {
     ) {
         let binding_type =
             type_layout.descriptor_set_descriptor_range_type(relative_set_index, range_index);
-        if binding_type == slang::BindingType::PushConstant {
-            // this is accounted for in add_sub_object_range
+        if matches!(
+            binding_type,
+            slang::BindingType::PushConstant | slang::BindingType::ParameterBlock
+        ) {
+            // these are accounted for in add_sub_object_range;
+            // a nested ParameterBlock gets its own set, not a binding in this one
             return;
         }
 
@@ -308,9 +312,11 @@ impl ReflectedStageFlags {
             slang::Stage::Vertex => Self::Vertex,
             slang::Stage::Fragment => Self::Fragment,
             slang::Stage::Compute => Self::Compute,
+            slang::Stage::Amplification => Self::Task,
+            slang::Stage::Mesh => Self::Mesh,
             slang::Stage::None => Self::Empty,
 
-            // raytracing, mesh, tesselation, dispatch, & count
+            // raytracing, tesselation, dispatch, & count
             _ => unimplemented!(),
         }
     }
@@ -324,12 +330,11 @@ impl ReflectedBindingType {
             slang::BindingType::Texture => Self::Texture,
             slang::BindingType::ConstantBuffer => Self::ConstantBuffer,
             slang::BindingType::CombinedTextureSampler => Self::CombinedTextureSampler,
-            // unreachable in practice: parameters reflection rejects structured
-            // buffers first with a friendlier, field-specific error
-            slang::BindingType::RawBuffer | slang::BindingType::MutableRawBuffer => panic!(
-                "StructuredBuffer descriptors are unsupported; \
-                use a BDA pointer (LayoutPtr<T, Std430DataLayout>) instead"
-            ),
+            // byte address buffers; structured buffers share these binding
+            // types, but parameters reflection rejects them first
+            slang::BindingType::RawBuffer | slang::BindingType::MutableRawBuffer => {
+                Self::StorageBuffer
+            }
 
             slang::BindingType::PushConstant => todo!(),
             slang::BindingType::ParameterBlock => todo!(),
@@ -339,7 +344,7 @@ impl ReflectedBindingType {
             slang::BindingType::TypedBuffer => todo!(),
             slang::BindingType::InputRenderTarget => todo!(),
             slang::BindingType::InlineUniformData => todo!(),
-            slang::BindingType::RayTracingAccelerationStructure => todo!(),
+            slang::BindingType::RayTracingAccelerationStructure => Self::AccelerationStructure,
             slang::BindingType::ExistentialValue => todo!(),
             slang::BindingType::MutableFlag => todo!(),
             slang::BindingType::MutableTeture => Self::StorageImage,
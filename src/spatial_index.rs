@@ -0,0 +1,196 @@
+//! CPU-side hit testing for 2D games: "which sprite is under the cursor"
+//! without a picking pass and its GPU readback.
+//!
+//! [`SpatialIndex2D`] is a uniform grid. Rebuild it each frame (or whenever
+//! things move) with [`SpatialIndex2D::clear`] and [`SpatialIndex2D::insert`],
+//! in draw order, so the last hit of a query is the topmost.
+
+use std::collections::HashMap;
+
+use glam::{IVec2, Mat2, Vec2};
+
+/// An axis-aligned rectangle, in the same space as the index's queries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Rect {
+    pub fn from_min_size(min: Vec2, size: Vec2) -> Self {
+        Self {
+            min,
+            max: min + size,
+        }
+    }
+
+    /// The bounds of a quad scaled and then rotated (in radians) about its
+    /// `origin` corner, the way the sprite batch shader places sprites
+    pub fn rotated_quad(origin: Vec2, size: Vec2, rotation: f32) -> Self {
+        let rotation = Mat2::from_angle(rotation);
+        // the shader's mul(coord, float2x2(c, s, -s, c)) is this rotation
+        let corners = [Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ONE]
+            .map(|corner| origin + rotation * (corner * size));
+
+        let min = corners.iter().copied().reduce(Vec2::min).unwrap();
+        let max = corners.iter().copied().reduce(Vec2::max).unwrap();
+        Self { min, max }
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+}
+
+pub struct SpatialIndex2D<T> {
+    cell_size: f32,
+    /// inserted items, in insertion order
+    entries: Vec<(Rect, T)>,
+    /// indices into entries, ascending, for each cell an entry overlaps
+    cells: HashMap<IVec2, Vec<usize>>,
+}
+
+impl<T> SpatialIndex2D<T> {
+    /// `cell_size` should be around the size of a typical item;
+    /// much smaller and large items fill many cells
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "cell size must be positive");
+        Self {
+            cell_size,
+            entries: vec![],
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Remove every item, keeping the allocations for the next rebuild
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, bounds: Rect, item: T) {
+        let index = self.entries.len();
+        let (min_cell, max_cell) = self.cell_range(&bounds);
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(index);
+            }
+        }
+        self.entries.push((bounds, item));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Items whose bounds contain the point, in insertion order
+    pub fn query_point(&self, point: Vec2) -> impl Iterator<Item = &T> {
+        let indices = self
+            .cells
+            .get(&self.cell_of(point))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        indices.iter().filter_map(move |&index| {
+            let (bounds, item) = &self.entries[index];
+            bounds.contains(point).then_some(item)
+        })
+    }
+
+    /// Items whose bounds overlap the rectangle, in insertion order
+    pub fn query_rect(&self, rect: Rect) -> impl Iterator<Item = &T> {
+        let (min_cell, max_cell) = self.cell_range(&rect);
+        let mut indices: Vec<usize> = vec![];
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                if let Some(cell) = self.cells.get(&IVec2::new(x, y)) {
+                    indices.extend(cell);
+                }
+            }
+        }
+        // an item spanning several cells is listed in each
+        indices.sort_unstable();
+        indices.dedup();
+
+        indices.into_iter().filter_map(move |index| {
+            let (bounds, item) = &self.entries[index];
+            bounds.intersects(&rect).then_some(item)
+        })
+    }
+
+    fn cell_of(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+
+    fn cell_range(&self, rect: &Rect) -> (IVec2, IVec2) {
+        (self.cell_of(rect.min), self.cell_of(rect.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> Rect {
+        Rect::from_min_size(Vec2::new(x, y), Vec2::splat(size))
+    }
+
+    #[test]
+    fn point_queries_return_overlapping_items_in_insertion_order() {
+        let mut index = SpatialIndex2D::new(32.0);
+        index.insert(square(0.0, 0.0, 50.0), "back");
+        index.insert(square(100.0, 100.0, 10.0), "elsewhere");
+        index.insert(square(40.0, 40.0, 50.0), "front");
+
+        let hits: Vec<_> = index.query_point(Vec2::new(45.0, 45.0)).collect();
+        assert_eq!(hits, [&"back", &"front"]);
+
+        assert_eq!(index.query_point(Vec2::new(-5.0, 10.0)).count(), 0);
+    }
+
+    #[test]
+    fn rect_queries_list_items_spanning_cells_once() {
+        let mut index = SpatialIndex2D::new(10.0);
+        index.insert(square(-25.0, -25.0, 50.0), 1);
+        index.insert(square(30.0, 30.0, 5.0), 2);
+
+        let hits: Vec<_> = index.query_rect(square(-5.0, -5.0, 40.0)).collect();
+        assert_eq!(hits, [&1, &2]);
+
+        let hits: Vec<_> = index.query_rect(square(26.0, 0.0, 3.0)).collect();
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn clear_keeps_cells_but_drops_items() {
+        let mut index = SpatialIndex2D::new(16.0);
+        index.insert(square(0.0, 0.0, 8.0), ());
+        index.clear();
+
+        assert!(index.is_empty());
+        assert_eq!(index.query_point(Vec2::new(4.0, 4.0)).count(), 0);
+    }
+
+    #[test]
+    fn rotated_quads_bound_every_corner() {
+        let bounds =
+            Rect::rotated_quad(Vec2::ZERO, Vec2::new(2.0, 1.0), std::f32::consts::FRAC_PI_2);
+        for corner in [Vec2::ZERO, Vec2::new(0.0, 2.0), Vec2::new(-1.0, 0.0)] {
+            let grown = Rect {
+                min: bounds.min - 1e-5,
+                max: bounds.max + 1e-5,
+            };
+            assert!(grown.contains(corner), "{corner} outside {bounds:?}");
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Immediate-mode 2D sprites, drawn with the crate's own sprite shader
+//! (`shaders/source/sprite_batch.shader.slang`), so a 2D game needs no
+//! shader of its own.
+//!
+//! ```ignore
+//! // in setup
+//! let mut batcher = SpriteBatcher::new(renderer, 4096)?;
+//! let image = load_image("sheet.png")?;
+//! let sheet = batcher.add_image(renderer, "sheet.png", &image, TextureFilter::Nearest)?;
+//!
+//! // in draw
+//! batcher.draw(sheet, position, rotation, animation.uv(), Vec4::ONE);
+//! batcher.flush(renderer)?;
+//! ```
+//!
+//! Each texture is a page of [`SpritePages`], so a frame takes one draw per
+//! texture used; within a texture, sprites layer in draw order.
+//! Positions are in window pixels from the top left, unless
+//! [`SpriteBatcher::set_projection`] says otherwise. A HUD's batcher can draw
+//! in the ui layer instead, at window resolution (see
+//! [`SpriteBatcher::with_ui_layer`]). [`SpriteBatcher::push_clip`] clips
+//! the sprites drawn until the matching pop, eg to a scrolling panel.
+//! Textures added with [`SpriteBatcher::add_sdf_texture`] are signed distance
+//! fields instead, drawn with `sdf_sprite_batch.shader.slang`.
+
+use glam::{Mat4, Vec2, Vec4};
+
+use crate::generated::shader_atlas::sdf_sprite_batch::{self, SdfSpriteBatchParams};
+use crate::generated::shader_atlas::sprite_batch::{
+    Projection, Resources, Shader, Sprite, SpriteBatchParams,
+};
+use crate::renderer::vertex_description::NoVertex;
+use crate::renderer::{
+    ClipRect, DrawError, DrawVertexCount, FrameRenderer, Gpu, ImmutableBufferHandle,
+    PipelineConfig, Renderer, TextureFilter, TextureHandle, UniformBufferHandle,
+};
+use crate::sprite_pages::{PageId, SpritePageStats, SpritePages};
+
+/// A texture registered with a [`SpriteBatcher`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteTexture {
+    page: PageId,
+    /// in pixels
+    size: Vec2,
+}
+
+impl SpriteTexture {
+    /// in pixels
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+}
+
+pub struct SpriteBatcher {
+    pages: SpritePages<Sprite>,
+    params_buffer: UniformBufferHandle<SpriteBatchParams>,
+    /// created with the first [`Self::add_sdf_texture`], and its px_range
+    sdf_params: Option<(UniformBufferHandle<SdfSpriteBatchParams>, f32)>,
+    sprites_buffer: ImmutableBufferHandle<Sprite>,
+    capacity: u32,
+    /// textures created by [`Self::add_image`], kept alive for their pipelines
+    textures: Vec<TextureHandle>,
+    /// whether textures added from now on draw in the ui layer
+    ui_layer: bool,
+    /// None for window pixels
+    projection: Option<Mat4>,
+    /// the projection the queued sprites are drawn with
+    frame_projection: Mat4,
+    /// clips pushed so far this frame, in window pixels, each already
+    /// intersected with the ones before it
+    clips: Vec<ClipRect>,
+    /// sprites drawn this frame
+    drawn: u32,
+    /// sprites drawn past capacity this frame, and left out
+    dropped: u32,
+}
+
+impl SpriteBatcher {
+    /// A batcher for up to `capacity` sprites a frame
+    pub fn new(renderer: &mut Renderer, capacity: u32) -> anyhow::Result<Self> {
+        let params_buffer = renderer.create_uniform_buffer::<SpriteBatchParams>()?;
+        let sprites_buffer = renderer.create_immutable_buffer::<Sprite>(capacity)?;
+
+        Ok(Self {
+            pages: SpritePages::new(),
+            params_buffer,
+            sdf_params: None,
+            sprites_buffer,
+            capacity,
+            textures: vec![],
+            ui_layer: false,
+            projection: None,
+            frame_projection: Mat4::IDENTITY,
+            clips: vec![],
+            drawn: 0,
+            dropped: 0,
+        })
+    }
+
+    /// Draws the textures added after this in the ui layer, at window
+    /// resolution however low the render scale, like a pipeline created with
+    /// [`PipelineConfig::with_ui_layer`](crate::renderer::PipelineConfig::with_ui_layer);
+    /// for a HUD over a scaled world
+    pub fn with_ui_layer(mut self) -> Self {
+        self.ui_layer = true;
+        self
+    }
+
+    /// Creates a texture from `image` to draw sprites from
+    pub fn add_image(
+        &mut self,
+        renderer: &mut Renderer,
+        source_file_name: impl Into<String>,
+        image: &image::DynamicImage,
+        filter: TextureFilter,
+    ) -> anyhow::Result<SpriteTexture> {
+        let texture = renderer.create_texture(source_file_name, image, filter)?;
+        let sprite_texture =
+            self.add_texture(renderer, &texture, [image.width(), image.height()])?;
+        self.textures.push(texture);
+        Ok(sprite_texture)
+    }
+
+    /// Draws sprites from an existing texture of `size` pixels, which the
+    /// caller keeps alive for as long as the batcher
+    pub fn add_texture(
+        &mut self,
+        renderer: &mut Renderer,
+        texture: &TextureHandle,
+        size: [u32; 2],
+    ) -> anyhow::Result<SpriteTexture> {
+        let resources = Resources {
+            texture,
+            params_buffer: &self.params_buffer,
+        };
+        let pipeline_config = Shader::init().pipeline_config(resources);
+        Self::add_page(
+            &mut self.pages,
+            self.ui_layer,
+            renderer,
+            pipeline_config,
+            size,
+        )
+    }
+
+    /// Like [`Self::add_texture`], for a texture whose alpha is a signed
+    /// distance field (see `shaders/lib/msdf.slang`) with `px_range` texels
+    /// of distance across its range; its sprites keep sharp edges at any
+    /// scale. A batcher's distance field textures share one px_range.
+    pub fn add_sdf_texture(
+        &mut self,
+        renderer: &mut Renderer,
+        texture: &TextureHandle,
+        size: [u32; 2],
+        px_range: f32,
+    ) -> anyhow::Result<SpriteTexture> {
+        let params_buffer = match self.sdf_params.take() {
+            Some((params_buffer, existing_range)) => {
+                debug_assert_eq!(
+                    existing_range, px_range,
+                    "a batcher's distance field textures share one px_range"
+                );
+                params_buffer
+            }
+            None => renderer.create_uniform_buffer::<SdfSpriteBatchParams>()?,
+        };
+        let params_buffer = &self.sdf_params.insert((params_buffer, px_range)).0;
+
+        let resources = sdf_sprite_batch::Resources {
+            texture,
+            params_buffer,
+        };
+        let pipeline_config = sdf_sprite_batch::Shader::init().pipeline_config(resources);
+        Self::add_page(
+            &mut self.pages,
+            self.ui_layer,
+            renderer,
+            pipeline_config,
+            size,
+        )
+    }
+
+    /// takes the fields it needs, as `pipeline_config` borrows a params buffer
+    fn add_page(
+        pages: &mut SpritePages<Sprite>,
+        ui_layer: bool,
+        renderer: &mut Renderer,
+        mut pipeline_config: PipelineConfig<'_, NoVertex, DrawVertexCount>,
+        size: [u32; 2],
+    ) -> anyhow::Result<SpriteTexture> {
+        pipeline_config.disable_depth_test = true;
+        if ui_layer {
+            pipeline_config = pipeline_config.with_ui_layer();
+        }
+        let pipeline = renderer.create_pipeline(pipeline_config)?;
+
+        Ok(SpriteTexture {
+            page: pages.add_page(pipeline),
+            size: Vec2::new(size[0] as f32, size[1] as f32),
+        })
+    }
+
+    /// Replaces the window pixel projection, eg for a scrolling camera;
+    /// None goes back to it
+    pub fn set_projection(&mut self, projection: Option<Mat4>) {
+        self.projection = projection;
+    }
+
+    /// Clips the sprites drawn until the matching [`Self::pop_clip`] to
+    /// `rect`, in window pixels from the top left; nested clips intersect.
+    /// The batch's draw is split wherever the clip changes.
+    pub fn push_clip(&mut self, rect: ClipRect) {
+        let rect = match self.clips.last() {
+            Some(&enclosing) => rect.intersect(enclosing),
+            None => rect,
+        };
+        self.clips.push(rect);
+        self.pages.set_clip(Some(rect));
+    }
+
+    /// End the most recent [`Self::push_clip`]
+    pub fn pop_clip(&mut self) {
+        let popped = self.clips.pop();
+        debug_assert!(popped.is_some(), "pop_clip without a matching push_clip");
+        self.pages.set_clip(self.clips.last().copied());
+    }
+
+    /// Draws the `uv_rect` (u, v, width, height) of `texture` at its size in
+    /// pixels, with its top left corner at `position`, rotated about it
+    /// by `rotation` radians, and tinted by `color` (linear, straight alpha)
+    pub fn draw(
+        &mut self,
+        texture: SpriteTexture,
+        position: Vec2,
+        rotation: f32,
+        uv_rect: [f32; 4],
+        color: Vec4,
+    ) {
+        let size = texture.size * Vec2::new(uv_rect[2], uv_rect[3]);
+        self.draw_sized(texture, position, size, rotation, uv_rect, color);
+    }
+
+    /// Like [`Self::draw`], scaled to `size`
+    pub fn draw_sized(
+        &mut self,
+        texture: SpriteTexture,
+        position: Vec2,
+        size: Vec2,
+        rotation: f32,
+        uv_rect: [f32; 4],
+        color: Vec4,
+    ) {
+        let [tex_u, tex_v, tex_w, tex_h] = uv_rect;
+        self.push(
+            texture,
+            Sprite {
+                position: position.extend(0.0),
+                rotation,
+                scale: size,
+                padding: Vec2::ZERO,
+                tex_u,
+                tex_v,
+                tex_w,
+                tex_h,
+                color,
+            },
+        );
+    }
+
+    /// Draws a sprite as the shader reads it
+    pub fn push(&mut self, texture: SpriteTexture, sprite: Sprite) {
+        if self.drawn == self.capacity {
+            self.dropped += 1;
+            return;
+        }
+        self.drawn += 1;
+        self.pages.push(texture.page, sprite);
+    }
+
+    /// Queues this frame's sprites, for [`Self::write`] in the same
+    /// [`FrameRenderer::submit_draws`] to upload
+    pub fn queue_draws(&mut self, renderer: &mut FrameRenderer) {
+        if self.dropped > 0 {
+            log::warn!(
+                "drew {} sprites past the batcher's capacity of {}",
+                self.dropped,
+                self.capacity
+            );
+        }
+        self.frame_projection = self
+            .projection
+            .unwrap_or_else(|| window_projection(renderer.window_resolution()));
+        debug_assert!(
+            self.clips.is_empty(),
+            "push_clip without a matching pop_clip"
+        );
+        self.clips.clear();
+
+        // clips are in window pixels, and the main pass is at render resolution
+        let clip_scale = if self.ui_layer {
+            Vec2::ONE
+        } else {
+            renderer.render_resolution() / renderer.window_resolution()
+        };
+        self.pages.finish();
+        self.pages
+            .queue_clipped_draws(renderer, |clip| scale_clip(clip, clip_scale));
+        self.drawn = 0;
+        self.dropped = 0;
+    }
+
+    /// Uploads the sprites from the last [`Self::queue_draws`]
+    pub fn write(&mut self, gpu: &mut Gpu) {
+        let sprites = gpu.current_immutable_addr(&self.sprites_buffer);
+        let projection = Projection {
+            matrix: self.frame_projection,
+        };
+        if let Some((params_buffer, px_range)) = &mut self.sdf_params {
+            let params = SdfSpriteBatchParams {
+                sprites,
+                projection: projection.clone(),
+                px_range: *px_range,
+                ..Default::default()
+            };
+            gpu.write_uniform(params_buffer, params);
+        }
+        let params = SpriteBatchParams {
+            sprites,
+            _padding_0: Default::default(),
+            projection,
+        };
+        gpu.write_uniform(&mut self.params_buffer, params);
+        gpu.write_immutable(&mut self.sprites_buffer, self.pages.sprites());
+    }
+
+    /// Queues and uploads this frame's sprites, and submits the frame;
+    /// for games that draw nothing else
+    pub fn flush(&mut self, mut renderer: FrameRenderer) -> Result<(), DrawError> {
+        self.queue_draws(&mut renderer);
+        renderer.submit_draws(|gpu| self.write(gpu))
+    }
+
+    /// Counts from the last [`Self::queue_draws`]
+    pub fn stats(&self) -> SpritePageStats {
+        self.pages.stats()
+    }
+}
+
+/// A clip in window pixels, scaled to cover the same area at another
+/// resolution; partly covered pixels are kept
+fn scale_clip(clip: ClipRect, scale: Vec2) -> ClipRect {
+    let min = Vec2::new(clip.x as f32, clip.y as f32) * scale;
+    let max = Vec2::new((clip.x + clip.width) as f32, (clip.y + clip.height) as f32) * scale;
+    let (min, max) = (min.floor(), max.ceil());
+    ClipRect::new(
+        min.x as u32,
+        min.y as u32,
+        (max.x - min.x) as u32,
+        (max.y - min.y) as u32,
+    )
+}
+
+/// Window pixels, from the top left, to clip space
+fn window_projection(window: Vec2) -> Mat4 {
+    Mat4::orthographic_lh(0.0, window.x, window.y, 0.0, 0.0, -1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+
+    #[test]
+    fn clips_scale_to_the_render_resolution() {
+        let clip = ClipRect::new(100, 50, 201, 100);
+        assert_eq!(scale_clip(clip, Vec2::ONE), clip);
+        // half resolution keeps the partly covered column on the right
+        assert_eq!(
+            scale_clip(clip, Vec2::splat(0.5)),
+            ClipRect::new(50, 25, 101, 50)
+        );
+    }
+
+    #[test]
+    fn window_corners_map_to_clip_corners() {
+        let projection = window_projection(Vec2::new(800.0, 600.0));
+        let top_left = projection.project_point3(Vec3::ZERO);
+        let bottom_right = projection.project_point3(Vec3::new(800.0, 600.0, 0.0));
+        assert!(top_left.truncate().abs_diff_eq(Vec2::new(-1.0, 1.0), 1e-6));
+        assert!(
+            bottom_right
+                .truncate()
+                .abs_diff_eq(Vec2::new(1.0, -1.0), 1e-6)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Batching sprites that sample different textures (pages).
+//!
+//! Textures are bound when a pipeline is created, so each page is its own
+//! pipeline, created from the same sprite shader and the same sprite buffer
+//! with a different texture. Sprites are pushed with their page in any order;
+//! [`SpritePages::finish`] groups them so each page's sprites are contiguous
+//! in [`SpritePages::sprites`], which is written to the buffer, and
+//! [`SpritePages::queue_draws`] issues one vertex range draw per page.
+//!
+//! Grouping reorders sprites across pages: every sprite on a later page draws
+//! over every sprite on an earlier one. Within a page, push order is kept.
+//! Use depth to layer sprites across pages.
+//!
+//! Sprites pushed after [`SpritePages::set_clip`] keep that clip, and a page's
+//! draw is split wherever the clip changes, so each run is queued inside
+//! [`FrameRenderer::with_clip`].
+
+use crate::quad::quad_vertex_range;
+use crate::renderer::{ClipRect, DrawVertexCount, FrameRenderer, PipelineHandle};
+
+/// A page registered with [`SpritePages::add_page`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageId(usize);
+
+/// Counts from the last [`SpritePages::finish`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpritePageStats {
+    pub sprites: u32,
+    /// one per page with sprites, and one more per clip change within a page
+    pub draws: u32,
+    /// how often consecutive pushes switched pages;
+    /// drawing in push order would have taken `batch_breaks + 1` draws
+    pub batch_breaks: u32,
+}
+
+pub struct SpritePages<S> {
+    pages: Vec<PipelineHandle<DrawVertexCount>>,
+    /// this frame's sprites with their page indices and clips, in push order
+    pushed: Vec<(usize, Option<ClipRect>, S)>,
+    /// the clip for sprites pushed now
+    clip: Option<ClipRect>,
+    last_pushed_page: Option<usize>,
+    batch_breaks: u32,
+    /// the pushed sprites grouped by page, as of the last finish
+    grouped: Vec<S>,
+    ranges: Vec<PageRange>,
+    stats: SpritePageStats,
+}
+
+/// A run of a page's sprites with the same clip, in the grouped sprites
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PageRange {
+    page: usize,
+    clip: Option<ClipRect>,
+    first_sprite: u32,
+    sprite_count: u32,
+}
+
+impl<S> SpritePages<S> {
+    pub fn new() -> Self {
+        Self {
+            pages: vec![],
+            pushed: vec![],
+            clip: None,
+            last_pushed_page: None,
+            batch_breaks: 0,
+            grouped: vec![],
+            ranges: vec![],
+            stats: SpritePageStats::default(),
+        }
+    }
+
+    /// Register a pipeline that binds one page's texture. All pages must read
+    /// their sprites from the same buffer, the one `sprites` is written to.
+    pub fn add_page(&mut self, pipeline: PipelineHandle<DrawVertexCount>) -> PageId {
+        self.pages.push(pipeline);
+        PageId(self.pages.len() - 1)
+    }
+
+    /// Clips the sprites pushed from now on, in the pixels of the pass they
+    /// draw in (see [`ClipRect`]); None stops clipping
+    pub fn set_clip(&mut self, clip: Option<ClipRect>) {
+        self.clip = clip;
+    }
+
+    pub fn push(&mut self, page: PageId, sprite: S) {
+        if self.last_pushed_page.is_some_and(|last| last != page.0) {
+            self.batch_breaks += 1;
+        }
+        self.last_pushed_page = Some(page.0);
+        self.pushed.push((page.0, self.clip, sprite));
+    }
+
+    /// Group this frame's pushed sprites by page, and start the next frame
+    pub fn finish(&mut self) {
+        self.ranges = group_by_page(&mut self.pushed, &mut self.grouped);
+        self.stats = SpritePageStats {
+            sprites: self.grouped.len() as u32,
+            draws: self.ranges.len() as u32,
+            batch_breaks: self.batch_breaks,
+        };
+
+        self.clip = None;
+        self.last_pushed_page = None;
+        self.batch_breaks = 0;
+    }
+
+    /// The sprites grouped by the last `finish`, to write to the shared sprite buffer
+    pub fn sprites(&self) -> &[S] {
+        &self.grouped
+    }
+
+    /// Queue one draw per page that had sprites at the last `finish`,
+    /// and per clip within it
+    pub fn queue_draws(&self, renderer: &mut FrameRenderer) {
+        self.queue_clipped_draws(renderer, |clip| clip);
+    }
+
+    /// Like [`Self::queue_draws`], with each clip mapped to the pixels of the
+    /// pass first, for clips set in other units
+    pub fn queue_clipped_draws(
+        &self,
+        renderer: &mut FrameRenderer,
+        map_clip: impl Fn(ClipRect) -> ClipRect,
+    ) {
+        for range in &self.ranges {
+            let (first_vertex, vertex_count) =
+                quad_vertex_range(range.first_sprite, range.sprite_count);
+            let pipeline = &self.pages[range.page];
+            match range.clip {
+                Some(clip) => renderer.with_clip(map_clip(clip), |renderer| {
+                    renderer.queue_draw_vertex_range(pipeline, first_vertex, vertex_count);
+                }),
+                None => renderer.queue_draw_vertex_range(pipeline, first_vertex, vertex_count),
+            }
+        }
+    }
+
+    pub fn stats(&self) -> SpritePageStats {
+        self.stats
+    }
+}
+
+impl<S> Default for SpritePages<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drains `pushed` into `grouped`, ordered by page and then push order,
+/// with a range for each run of a page's sprites with the same clip
+fn group_by_page<S>(
+    pushed: &mut Vec<(usize, Option<ClipRect>, S)>,
+    grouped: &mut Vec<S>,
+) -> Vec<PageRange> {
+    // stable, so each page keeps its push order
+    pushed.sort_by_key(|(page, _, _)| *page);
+
+    let mut ranges: Vec<PageRange> = vec![];
+    grouped.clear();
+    for (page, clip, sprite) in pushed.drain(..) {
+        match ranges.last_mut() {
+            Some(range) if range.page == page && range.clip == clip => range.sprite_count += 1,
+            _ => ranges.push(PageRange {
+                page,
+                clip,
+                first_sprite: grouped.len() as u32,
+                sprite_count: 1,
+            }),
+        }
+        grouped.push(sprite);
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_by_page_keeping_push_order() {
+        let mut pushed = vec![
+            (1, None, 'a'),
+            (0, None, 'b'),
+            (1, None, 'c'),
+            (2, None, 'd'),
+            (0, None, 'e'),
+        ];
+        let mut grouped = vec![];
+
+        let ranges = group_by_page(&mut pushed, &mut grouped);
+
+        assert!(pushed.is_empty());
+        assert_eq!(grouped, ['b', 'e', 'a', 'c', 'd']);
+        assert_eq!(
+            ranges,
+            [
+                PageRange {
+                    page: 0,
+                    clip: None,
+                    first_sprite: 0,
+                    sprite_count: 2
+                },
+                PageRange {
+                    page: 1,
+                    clip: None,
+                    first_sprite: 2,
+                    sprite_count: 2
+                },
+                PageRange {
+                    page: 2,
+                    clip: None,
+                    first_sprite: 4,
+                    sprite_count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn clip_changes_split_a_page() {
+        let panel = Some(ClipRect::new(0, 0, 100, 100));
+        let mut pushed = vec![
+            (0, None, 'a'),
+            (0, panel, 'b'),
+            (0, panel, 'c'),
+            (0, None, 'd'),
+        ];
+        let mut grouped = vec![];
+
+        let ranges = group_by_page(&mut pushed, &mut grouped);
+
+        assert_eq!(grouped, ['a', 'b', 'c', 'd']);
+        let runs: Vec<_> = ranges
+            .iter()
+            .map(|range| (range.clip, range.sprite_count))
+            .collect();
+        assert_eq!(runs, [(None, 1), (panel, 2), (None, 1)]);
+    }
+
+    #[test]
+    fn stats_count_page_switches_in_push_order() {
+        let mut pages = SpritePages::new();
+        let (a, b) = (PageId(0), PageId(1));
+        for page in [a, a, b, a, b, b] {
+            pages.push(page, ());
+        }
+
+        pages.finish();
+        assert_eq!(
+            pages.stats(),
+            SpritePageStats {
+                sprites: 6,
+                draws: 2,
+                batch_breaks: 3,
+            }
+        );
+
+        pages.finish();
+        assert_eq!(pages.stats(), SpritePageStats::default());
+    }
+}
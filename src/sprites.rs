@@ -0,0 +1,442 @@
+//! Sprite sheet frames, from the json Aseprite and TexturePacker export
+//! alongside a sheet.
+//!
+//! `build_tasks::write_sprite_atlases` generates a module per sheet in
+//! `generated::sprite_atlas`, with an enum of the sheet's sprites and their
+//! frames as constants, so games look frames up by name without parsing json
+//! or matching strings at runtime. For sheets that aren't known at build
+//! time, like mods' or a level editor's, [`SpriteSheet::load_aseprite`] reads
+//! the json when the game runs, and looks sprites up by name.
+//!
+//! Either way, an [`Animation`] plays a sprite's frames with their durations,
+//! giving the uv rect to draw each frame with.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+
+use serde::Deserialize;
+
+/// One frame of a sprite in a sheet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasFrame {
+    /// the frame's rect in the sheet, in pixels
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+    /// the frame's rect normalized to the sheet's size: u, v, width, height
+    pub uv: [f32; 4],
+    /// how long the frame shows in its sprite's animation;
+    /// 0 for TexturePacker sheets, which have no timing
+    pub duration_millis: u32,
+}
+
+impl AtlasFrame {
+    /// a frame from its pixel rect (x, y, width, height) in a sheet of `sheet_size`
+    pub const fn new(rect: [u32; 4], duration_millis: u32, sheet_size: [u32; 2]) -> Self {
+        let [x, y, w, h] = rect;
+        let [sheet_w, sheet_h] = [sheet_size[0] as f32, sheet_size[1] as f32];
+
+        Self {
+            x,
+            y,
+            w,
+            h,
+            uv: [
+                x as f32 / sheet_w,
+                y as f32 / sheet_h,
+                w as f32 / sheet_w,
+                h as f32 / sheet_h,
+            ],
+            duration_millis,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_millis as u64)
+    }
+}
+
+/// A sheet's sprites, each the frames sharing a name
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetLayout {
+    /// in pixels
+    pub size: [u32; 2],
+    /// sorted by name
+    pub sprites: Vec<SheetSprite>,
+}
+
+/// The frames of one sprite in a sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetSprite {
+    pub name: String,
+    /// in frame number order
+    pub frames: Vec<AtlasFrame>,
+}
+
+impl SheetLayout {
+    /// Parses an Aseprite or TexturePacker json export, in either its array or hash form.
+    ///
+    /// Frames are grouped into sprites by their file name, less any extension
+    /// and trailing frame number: `ship 0` and `ship 1` (Aseprite's
+    /// `{title} {frame}` format), or `ship_0.png` and `ship/1.png`, are frames
+    /// of `ship`. A name without a number is a single-frame sprite.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let sheet: SheetJson = serde_json::from_str(json)?;
+        let size = [sheet.meta.size.w, sheet.meta.size.h];
+
+        let frames: Vec<FrameJson> = match sheet.frames {
+            FramesJson::Array(frames) => frames,
+            FramesJson::Hash(frames) => frames
+                .into_iter()
+                .map(|(filename, frame)| FrameJson {
+                    filename: Some(filename),
+                    ..frame
+                })
+                .collect(),
+        };
+
+        let mut sprites: BTreeMap<String, Vec<(u32, AtlasFrame)>> = BTreeMap::new();
+        for frame in frames {
+            let filename = frame.filename.unwrap_or_default();
+            if frame.rotated {
+                anyhow::bail!(
+                    "frame '{filename}' is rotated in the sheet; export without rotation"
+                );
+            }
+
+            let (name, number) = split_frame_name(&filename);
+            let FrameRectJson { x, y, w, h } = frame.frame;
+            let atlas_frame = AtlasFrame::new([x, y, w, h], frame.duration.unwrap_or(0), size);
+
+            let sprite_frames = sprites.entry(name.to_string()).or_default();
+            let number = number.unwrap_or(sprite_frames.len() as u32);
+            if sprite_frames
+                .iter()
+                .any(|(existing, _)| *existing == number)
+            {
+                anyhow::bail!("frame '{filename}' duplicates frame {number} of '{name}'");
+            }
+            sprite_frames.push((number, atlas_frame));
+        }
+
+        let sprites = sprites
+            .into_iter()
+            .map(|(name, mut frames)| {
+                frames.sort_by_key(|(number, _)| *number);
+                let frames = frames.into_iter().map(|(_, frame)| frame).collect();
+                SheetSprite { name, frames }
+            })
+            .collect();
+
+        Ok(Self { size, sprites })
+    }
+}
+
+/// A sheet's layout, loaded when the game runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSheet {
+    layout: SheetLayout,
+}
+
+impl SpriteSheet {
+    /// Reads an Aseprite (or TexturePacker) json export;
+    /// see [`SheetLayout::from_json`] for how frames are named
+    pub fn load_aseprite(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read sprite sheet {}", path.display()))?;
+        Self::from_json(&json).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let layout = SheetLayout::from_json(json)?;
+        Ok(Self { layout })
+    }
+
+    pub fn layout(&self) -> &SheetLayout {
+        &self.layout
+    }
+
+    /// in pixels
+    pub fn size(&self) -> [u32; 2] {
+        self.layout.size
+    }
+
+    pub fn sprite(&self, name: &str) -> Option<&SheetSprite> {
+        let sprites = &self.layout.sprites;
+        let index = sprites
+            .binary_search_by(|sprite| sprite.name.as_str().cmp(name))
+            .ok()?;
+        Some(&sprites[index])
+    }
+
+    /// A sprite's frames, in frame number order
+    pub fn frames(&self, name: &str) -> Option<&[AtlasFrame]> {
+        self.sprite(name).map(|sprite| sprite.frames.as_slice())
+    }
+
+    pub fn frame(&self, name: &str, index: usize) -> Option<&AtlasFrame> {
+        self.frames(name)?.get(index)
+    }
+
+    /// A looping animation of a sprite's frames
+    pub fn animation(&self, name: &str) -> Option<Animation> {
+        self.frames(name).map(Animation::new)
+    }
+}
+
+/// How long a frame without a duration shows, as in a TexturePacker sheet;
+/// Aseprite's default
+const DEFAULT_FRAME_MILLIS: u32 = 100;
+
+/// A sprite's frames, played with their durations
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    frames: Vec<AtlasFrame>,
+    /// the time into the animation; less than its total duration while looping
+    elapsed: Duration,
+    total_duration: Duration,
+    looping: bool,
+}
+
+impl Animation {
+    /// A looping animation; `frames` must not be empty
+    pub fn new(frames: &[AtlasFrame]) -> Self {
+        assert!(!frames.is_empty(), "an animation needs at least one frame");
+        let frames: Vec<AtlasFrame> = frames
+            .iter()
+            .map(|frame| AtlasFrame {
+                duration_millis: match frame.duration_millis {
+                    0 => DEFAULT_FRAME_MILLIS,
+                    millis => millis,
+                },
+                ..*frame
+            })
+            .collect();
+        let total_duration = frames.iter().map(AtlasFrame::duration).sum();
+
+        Self {
+            frames,
+            elapsed: Duration::ZERO,
+            total_duration,
+            looping: true,
+        }
+    }
+
+    /// Stops on the last frame, instead of looping
+    pub fn once(mut self) -> Self {
+        self.looping = false;
+        self
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        if self.looping {
+            let nanos = self.elapsed.as_nanos() % self.total_duration.as_nanos();
+            self.elapsed = Duration::from_nanos(nanos as u64);
+        } else {
+            self.elapsed = self.elapsed.min(self.total_duration);
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Whether a [`Self::once`] animation has shown its last frame for its
+    /// full duration; never for a looping one
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.elapsed >= self.total_duration
+    }
+
+    /// The index of the frame showing now
+    pub fn frame_index(&self) -> usize {
+        let mut frame_end = Duration::ZERO;
+        for (index, frame) in self.frames.iter().enumerate() {
+            frame_end += frame.duration();
+            if self.elapsed < frame_end {
+                return index;
+            }
+        }
+        self.frames.len() - 1
+    }
+
+    pub fn frame(&self) -> &AtlasFrame {
+        &self.frames[self.frame_index()]
+    }
+
+    /// The frame showing now's rect in the sheet: u, v, width, height
+    pub fn uv(&self) -> [f32; 4] {
+        self.frame().uv
+    }
+}
+
+/// A frame's file name as its sprite's name and frame number
+fn split_frame_name(filename: &str) -> (&str, Option<u32>) {
+    let stem = match filename.rsplit_once('.') {
+        Some((stem, extension))
+            if !extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            stem
+        }
+        _ => filename,
+    };
+
+    match stem.rsplit_once([' ', '_', '-', '/']) {
+        Some((name, number)) if !name.is_empty() => match number.parse() {
+            Ok(number) => (name, Some(number)),
+            Err(_) => (stem, None),
+        },
+        _ => (stem, None),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SheetJson {
+    frames: FramesJson,
+    meta: MetaJson,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FramesJson {
+    Array(Vec<FrameJson>),
+    Hash(BTreeMap<String, FrameJson>),
+}
+
+#[derive(Debug, Deserialize)]
+struct FrameJson {
+    /// only in the array form; the hash form keys frames by it
+    filename: Option<String>,
+    frame: FrameRectJson,
+    #[serde(default)]
+    rotated: bool,
+    /// only from Aseprite
+    duration: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FrameRectJson {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetaJson {
+    size: SizeJson,
+}
+
+#[derive(Debug, Deserialize)]
+struct SizeJson {
+    w: u32,
+    h: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_names_split_off_their_numbers() {
+        assert_eq!(split_frame_name("ship 1"), ("ship", Some(1)));
+        assert_eq!(
+            split_frame_name("bomb_explosion 12"),
+            ("bomb_explosion", Some(12))
+        );
+        assert_eq!(split_frame_name("ship_0.png"), ("ship", Some(0)));
+        assert_eq!(split_frame_name("ship/3.png"), ("ship", Some(3)));
+        assert_eq!(split_frame_name("game_over"), ("game_over", None));
+        assert_eq!(split_frame_name("game_over.png"), ("game_over", None));
+        assert_eq!(split_frame_name("42"), ("42", None));
+    }
+
+    #[test]
+    fn aseprite_frames_are_grouped_in_frame_order() {
+        let json = r#"{
+            "frames": [
+                { "filename": "ship 1", "frame": { "x": 32, "y": 0, "w": 32, "h": 32 }, "duration": 50 },
+                { "filename": "ship 0", "frame": { "x": 0, "y": 0, "w": 32, "h": 32 }, "duration": 100 },
+                { "filename": "bug 0", "frame": { "x": 0, "y": 32, "w": 16, "h": 16 }, "duration": 100 }
+            ],
+            "meta": { "size": { "w": 64, "h": 64 } }
+        }"#;
+
+        let layout = SheetLayout::from_json(json).unwrap();
+        assert_eq!(layout.size, [64, 64]);
+
+        let names: Vec<_> = layout.sprites.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["bug", "ship"]);
+
+        let ship = &layout.sprites[1].frames;
+        assert_eq!(ship[0].duration_millis, 100);
+        assert_eq!(ship[1].uv, [0.5, 0.0, 0.5, 0.5]);
+    }
+
+    const SHEET_JSON: &str = r#"{
+        "frames": [
+            { "filename": "ship 0", "frame": { "x": 0, "y": 0, "w": 32, "h": 32 }, "duration": 100 },
+            { "filename": "ship 1", "frame": { "x": 32, "y": 0, "w": 32, "h": 32 }, "duration": 50 },
+            { "filename": "game_over", "frame": { "x": 0, "y": 32, "w": 64, "h": 32 }, "duration": 100 }
+        ],
+        "meta": { "size": { "w": 64, "h": 64 } }
+    }"#;
+
+    #[test]
+    fn sheets_look_sprites_up_by_name() {
+        let sheet = SpriteSheet::from_json(SHEET_JSON).unwrap();
+        assert_eq!(sheet.frames("ship").map(<[_]>::len), Some(2));
+        assert_eq!(sheet.frame("ship", 1).unwrap().x, 32);
+        assert_eq!(
+            sheet.frame("game_over", 0).unwrap().uv,
+            [0.0, 0.5, 1.0, 0.5]
+        );
+        assert!(sheet.frame("ship", 2).is_none());
+        assert!(sheet.sprite("bug").is_none());
+    }
+
+    #[test]
+    fn animations_play_frames_for_their_durations() {
+        let sheet = SpriteSheet::from_json(SHEET_JSON).unwrap();
+        let mut animation = sheet.animation("ship").unwrap();
+        assert_eq!(animation.uv(), [0.0, 0.0, 0.5, 0.5]);
+
+        animation.tick(Duration::from_millis(99));
+        assert_eq!(animation.frame_index(), 0);
+        animation.tick(Duration::from_millis(1));
+        assert_eq!(animation.frame_index(), 1);
+        assert_eq!(animation.uv(), [0.5, 0.0, 0.5, 0.5]);
+
+        // looped back around
+        animation.tick(Duration::from_millis(60));
+        assert_eq!(animation.frame_index(), 0);
+        assert!(!animation.is_finished());
+
+        let mut once = sheet.animation("ship").unwrap().once();
+        once.tick(Duration::from_millis(500));
+        assert_eq!(once.frame_index(), 1);
+        assert!(once.is_finished());
+        once.restart();
+        assert_eq!(once.frame_index(), 0);
+    }
+
+    #[test]
+    fn texture_packer_hashes_have_no_durations() {
+        let json = r#"{
+            "frames": {
+                "bullet.png": { "frame": { "x": 8, "y": 0, "w": 8, "h": 16 }, "rotated": false }
+            },
+            "meta": { "size": { "w": 16, "h": 16 } }
+        }"#;
+
+        let layout = SheetLayout::from_json(json).unwrap();
+        assert_eq!(layout.sprites[0].name, "bullet");
+        assert_eq!(layout.sprites[0].frames[0].duration_millis, 0);
+        assert_eq!(layout.sprites[0].frames[0].uv, [0.5, 0.0, 0.5, 1.0]);
+    }
+}
@@ -0,0 +1,728 @@
+//! Text drawn with a TTF or OTF font, outside the egui overlay.
+//!
+//! ```ignore
+//! // in setup
+//! let mut text = TextBrush::load(renderer, manifest_path(["fonts", "Inter.ttf"]), 4096)?;
+//!
+//! // in draw
+//! text.draw("Score: 100", Vec2::new(16.0, 16.0), 32.0, Vec4::ONE);
+//! text.flush(renderer)?;
+//! ```
+//!
+//! Glyphs are rasterized the first time they're drawn at a size, into an
+//! atlas texture that's updated before the frame's draws, and then drawn as
+//! sprites with the crate's sprite batch shader (see [`SpriteBatcher`]).
+//! Sizes are rounded to whole pixels; each one used takes its own glyphs in
+//! the atlas, so a game with many sizes may want to settle on a few.
+//! Text draws in the ui layer, at window resolution, so it stays sharp when
+//! the world is drawn at a lower render scale.
+//! A brush in [`GlyphAtlasMode::SignedDistance`] instead keeps one distance
+//! field per glyph, drawn at any size, eg for labels zoomed by a projection.
+//!
+//! Each line is split into runs of one direction with unicode-bidi, and
+//! each run is shaped with rustybuzz, so ligatures, kerning, combining marks,
+//! contextual forms, and right-to-left scripts lay out as the font intends.
+//! Characters missing from the font are drawn from the first fallback font
+//! that has them (see [`TextBrush::add_fallback_font`]), eg a CJK or symbol
+//! face after a Latin one; marks stay in their base character's font.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+use ab_glyph::{Font, FontVec, GlyphId, PxScale, ScaleFont, point};
+use anyhow::Context;
+use glam::{Mat4, Vec2, Vec4};
+use rustybuzz::{Direction, UnicodeBuffer};
+use unicode_bidi::{BidiClass, BidiInfo, bidi_class};
+
+use crate::renderer::{
+    ClipRect, DrawError, FrameRenderer, Gpu, Renderer, TextureFilter, TextureFormat, TextureHandle,
+    TextureOptions, TextureRegion, TextureWrap,
+};
+use crate::sprite_batcher::{SpriteBatcher, SpriteTexture};
+use crate::sprite_pages::SpritePageStats;
+
+/// the width and height of the glyph atlas, in pixels
+const ATLAS_SIZE: u32 = 1024;
+
+/// empty pixels around each glyph, so linear filtering doesn't bleed
+/// a neighbor into its edges
+const GLYPH_PADDING: u32 = 1;
+
+/// the size, in pixels, glyphs' distance fields are rasterized at
+const SDF_GLYPH_SIZE: u32 = 48;
+
+/// texels of distance across a distance field's range (see msdf.slang);
+/// the field reaches half of it past the outline
+const SDF_PX_RANGE: u32 = 8;
+
+/// a squared distance farther than any in the atlas
+const FAR: f32 = 1e20;
+
+/// How a [`TextBrush`] rasterizes glyphs into its atlas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlyphAtlasMode {
+    /// each glyph's coverage, once for every size it's drawn at;
+    /// sharpest at those sizes, but blurry when scaled up
+    #[default]
+    Coverage,
+    /// each glyph's signed distance field, once, drawn at any size with
+    /// `sdf_sprite_batch.shader.slang`; edges stay crisp when scaled or zoomed,
+    /// though sharp corners round off at large sizes
+    SignedDistance,
+}
+
+/// A glyph's place in the atlas
+#[derive(Debug, Clone, Copy)]
+struct CachedGlyph {
+    uv_rect: [f32; 4],
+    /// from the caret on the baseline to the glyph's top left, in pixels
+    offset: Vec2,
+    size: Vec2,
+}
+
+/// Draws text from a font and its fallbacks.
+///
+/// Text is shaped each time it's drawn or measured, which parses the fonts'
+/// shaping tables again; only the rasterized glyphs are cached.
+pub struct TextBrush {
+    /// the font, then its fallbacks in the order they're tried
+    fonts: Vec<FontVec>,
+    mode: GlyphAtlasMode,
+    batcher: SpriteBatcher,
+    atlas: TextureHandle,
+    atlas_texture: SpriteTexture,
+    packer: ShelfPacker,
+    /// by font index, glyph, and size in pixels; None for glyphs with no
+    /// outline, like spaces, and glyphs that didn't fit in the atlas
+    glyphs: HashMap<(usize, GlyphId, u32), Option<CachedGlyph>>,
+    /// glyphs rasterized and not yet uploaded, as RGBA texels
+    pending_uploads: Vec<(TextureRegion, Vec<u8>)>,
+    /// whether the full atlas has been warned about
+    warned_full: bool,
+}
+
+impl TextBrush {
+    /// Loads the font at `path`, to draw up to `capacity` glyphs a frame
+    pub fn load(
+        renderer: &mut Renderer,
+        path: impl AsRef<Path>,
+        capacity: u32,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read font {}", path.display()))?;
+        Self::from_bytes(renderer, path.display().to_string(), bytes, capacity)
+    }
+
+    /// Like [`Self::load`], with glyphs rasterized as `mode` says
+    pub fn load_with_mode(
+        renderer: &mut Renderer,
+        path: impl AsRef<Path>,
+        capacity: u32,
+        mode: GlyphAtlasMode,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read font {}", path.display()))?;
+        Self::from_bytes_with_mode(renderer, path.display().to_string(), bytes, capacity, mode)
+    }
+
+    /// Like [`Self::load`], with the font file's contents
+    pub fn from_bytes(
+        renderer: &mut Renderer,
+        source_file_name: impl Into<String>,
+        bytes: Vec<u8>,
+        capacity: u32,
+    ) -> anyhow::Result<Self> {
+        Self::from_bytes_with_mode(
+            renderer,
+            source_file_name,
+            bytes,
+            capacity,
+            GlyphAtlasMode::Coverage,
+        )
+    }
+
+    /// Like [`Self::load_with_mode`], with the font file's contents
+    pub fn from_bytes_with_mode(
+        renderer: &mut Renderer,
+        source_file_name: impl Into<String>,
+        bytes: Vec<u8>,
+        capacity: u32,
+        mode: GlyphAtlasMode,
+    ) -> anyhow::Result<Self> {
+        let source_file_name = source_file_name.into();
+        let font = parse_font(&source_file_name, bytes)?;
+
+        // white, with each glyph's coverage or distance in alpha,
+        // so the sprite color tints it
+        let texels = vec![0; (ATLAS_SIZE * ATLAS_SIZE * 4) as usize];
+        let atlas = renderer.create_texture_from_bytes(
+            format!("{source_file_name} glyphs"),
+            TextureFormat::Rgba8,
+            ATLAS_SIZE,
+            ATLAS_SIZE,
+            &texels,
+            (ATLAS_SIZE * 4) as usize,
+            TextureOptions {
+                filter: TextureFilter::Linear,
+                wrap_u: TextureWrap::ClampToEdge,
+                wrap_v: TextureWrap::ClampToEdge,
+                mipmaps: false,
+                ..Default::default()
+            },
+        )?;
+
+        let mut batcher = SpriteBatcher::new(renderer, capacity)?.with_ui_layer();
+        let atlas_size = [ATLAS_SIZE, ATLAS_SIZE];
+        let atlas_texture = match mode {
+            GlyphAtlasMode::Coverage => batcher.add_texture(renderer, &atlas, atlas_size)?,
+            GlyphAtlasMode::SignedDistance => {
+                batcher.add_sdf_texture(renderer, &atlas, atlas_size, SDF_PX_RANGE as f32)?
+            }
+        };
+
+        Ok(Self {
+            fonts: vec![font],
+            mode,
+            batcher,
+            atlas,
+            atlas_texture,
+            packer: ShelfPacker::new(ATLAS_SIZE),
+            glyphs: HashMap::new(),
+            pending_uploads: vec![],
+            warned_full: false,
+        })
+    }
+
+    /// Loads a font to draw the characters that the fonts before it don't have
+    pub fn add_fallback_font(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read font {}", path.display()))?;
+        self.add_fallback_font_bytes(path.display().to_string(), bytes)
+    }
+
+    /// Like [`Self::add_fallback_font`], with the font file's contents
+    pub fn add_fallback_font_bytes(
+        &mut self,
+        source_file_name: impl Into<String>,
+        bytes: Vec<u8>,
+    ) -> anyhow::Result<()> {
+        let font = parse_font(&source_file_name.into(), bytes)?;
+        self.fonts.push(font);
+        Ok(())
+    }
+
+    /// Replaces the window pixel projection, like [`SpriteBatcher::set_projection`]
+    pub fn set_projection(&mut self, projection: Option<Mat4>) {
+        self.batcher.set_projection(projection);
+    }
+
+    /// Clips the text drawn until the matching [`Self::pop_clip`], like
+    /// [`SpriteBatcher::push_clip`]
+    pub fn push_clip(&mut self, rect: ClipRect) {
+        self.batcher.push_clip(rect);
+    }
+
+    /// End the most recent [`Self::push_clip`]
+    pub fn pop_clip(&mut self) {
+        self.batcher.pop_clip();
+    }
+
+    /// Draws `text` with its top left corner at `position`, `size` pixels
+    /// tall, and tinted by `color` (linear, straight alpha);
+    /// a `\n` starts a new line
+    pub fn draw(&mut self, text: &str, position: Vec2, size: f32, color: Vec4) {
+        let px = pixel_size(size);
+        let mut placed = vec![];
+        layout(&self.fonts, px, text, position, |font, id, caret| {
+            placed.push((font, id, caret))
+        });
+
+        // distance fields are rasterized at one size, and scaled to the rest
+        let (raster_px, scale) = match self.mode {
+            GlyphAtlasMode::Coverage => (px, 1.0),
+            GlyphAtlasMode::SignedDistance => (SDF_GLYPH_SIZE, px as f32 / SDF_GLYPH_SIZE as f32),
+        };
+        for (font, id, caret) in placed {
+            let Some(glyph) = self.cached_glyph(font, id, raster_px) else {
+                continue;
+            };
+            self.batcher.draw_sized(
+                self.atlas_texture,
+                caret + glyph.offset * scale,
+                glyph.size * scale,
+                0.0,
+                glyph.uv_rect,
+                color,
+            );
+        }
+    }
+
+    /// The width and height `text` takes up when drawn at `size`
+    pub fn measure(&self, text: &str, size: f32) -> Vec2 {
+        layout(
+            &self.fonts,
+            pixel_size(size),
+            text,
+            Vec2::ZERO,
+            |_, _, _| {},
+        )
+    }
+
+    /// Uploads glyphs rasterized this frame, and queues the frame's text,
+    /// for [`Self::write`] in the same [`FrameRenderer::submit_draws`] to upload.
+    /// Glyphs that fail to upload are tried again next frame.
+    pub fn queue_draws(&mut self, renderer: &mut FrameRenderer) -> anyhow::Result<()> {
+        let mut uploaded = 0;
+        let result = self
+            .pending_uploads
+            .iter()
+            .try_for_each(|(region, texels)| {
+                renderer.update_texture(&self.atlas, *region, texels)?;
+                uploaded += 1;
+                anyhow::Ok(())
+            });
+        self.pending_uploads.drain(..uploaded);
+        result.context("failed to update the glyph atlas")?;
+
+        self.batcher.queue_draws(renderer);
+        Ok(())
+    }
+
+    /// Uploads the text from the last [`Self::queue_draws`]
+    pub fn write(&mut self, gpu: &mut Gpu) {
+        self.batcher.write(gpu);
+    }
+
+    /// Queues and uploads this frame's text, and submits the frame;
+    /// for games that draw nothing else
+    pub fn flush(&mut self, mut renderer: FrameRenderer) -> Result<(), DrawError> {
+        self.queue_draws(&mut renderer)?;
+        renderer.submit_draws(|gpu| self.write(gpu))
+    }
+
+    /// Counts from the last [`Self::queue_draws`]
+    pub fn stats(&self) -> SpritePageStats {
+        self.batcher.stats()
+    }
+
+    fn cached_glyph(&mut self, font: usize, id: GlyphId, px: u32) -> Option<CachedGlyph> {
+        if let Some(&glyph) = self.glyphs.get(&(font, id, px)) {
+            return glyph;
+        }
+        let glyph = self.rasterize(font, id, px);
+        self.glyphs.insert((font, id, px), glyph);
+        glyph
+    }
+
+    fn rasterize(&mut self, font: usize, id: GlyphId, px: u32) -> Option<CachedGlyph> {
+        let glyph = id.with_scale_and_position(PxScale::from(px as f32), point(0.0, 0.0));
+        let outlined = self.fonts[font].outline_glyph(glyph)?;
+        let bounds = outlined.px_bounds();
+        // room for a distance field to fall off past the outline
+        let margin = match self.mode {
+            GlyphAtlasMode::Coverage => 0,
+            GlyphAtlasMode::SignedDistance => SDF_PX_RANGE / 2,
+        };
+        let width = bounds.width() as u32 + margin * 2;
+        let height = bounds.height() as u32 + margin * 2;
+
+        let Some((x, y)) = self.packer.allocate(width, height) else {
+            if !self.warned_full {
+                log::warn!("the glyph atlas is full; new glyphs won't be drawn");
+                self.warned_full = true;
+            }
+            return None;
+        };
+
+        let mut coverage = vec![0.0; (width * height) as usize];
+        outlined.draw(|glyph_x, glyph_y, glyph_coverage| {
+            let index = (glyph_y + margin) * width + glyph_x + margin;
+            coverage[index as usize] = glyph_coverage.clamp(0.0, 1.0);
+        });
+        let alpha: Vec<u8> = match self.mode {
+            GlyphAtlasMode::Coverage => coverage
+                .iter()
+                .map(|coverage| (coverage * 255.0).round() as u8)
+                .collect(),
+            GlyphAtlasMode::SignedDistance => signed_distance_field(
+                &coverage,
+                width as usize,
+                height as usize,
+                SDF_PX_RANGE as f32,
+            ),
+        };
+        let texels = alpha
+            .into_iter()
+            .flat_map(|alpha| [255, 255, 255, alpha])
+            .collect();
+        self.pending_uploads
+            .push((TextureRegion::new(x, y, width, height), texels));
+
+        let atlas_size = ATLAS_SIZE as f32;
+        Some(CachedGlyph {
+            uv_rect: [
+                x as f32 / atlas_size,
+                y as f32 / atlas_size,
+                width as f32 / atlas_size,
+                height as f32 / atlas_size,
+            ],
+            offset: Vec2::new(bounds.min.x, bounds.min.y) - Vec2::splat(margin as f32),
+            size: Vec2::new(width as f32, height as f32),
+        })
+    }
+}
+
+/// `size` rounded to whole pixels, for the glyph cache
+fn pixel_size(size: f32) -> u32 {
+    (size.round() as u32).max(1)
+}
+
+fn parse_font(source_file_name: &str, bytes: Vec<u8>) -> anyhow::Result<FontVec> {
+    FontVec::try_from_vec(bytes).with_context(|| format!("failed to parse font {source_file_name}"))
+}
+
+/// Calls `place` with each glyph of `text`, the index of its font, and its
+/// caret on the baseline, and returns the size of the text's bounds.
+/// Lines are spaced by the first font's metrics.
+fn layout(
+    fonts: &[FontVec],
+    px: u32,
+    text: &str,
+    position: Vec2,
+    mut place: impl FnMut(usize, GlyphId, Vec2),
+) -> Vec2 {
+    let scale = PxScale::from(px as f32);
+    let primary = fonts[0].as_scaled(scale);
+    let line_height = primary.height() + primary.line_gap();
+    // shaping faces, parsed the first time a line uses their font
+    let mut faces: Vec<Option<rustybuzz::Face>> = fonts.iter().map(|_| None).collect();
+    let mut width: f32 = 0.0;
+    let mut lines = 0;
+
+    for line in text.split('\n') {
+        let baseline = position.y + primary.ascent() + line_height * lines as f32;
+        let mut caret = Vec2::new(position.x, baseline);
+        lines += 1;
+
+        for (run, rtl) in visual_runs(line) {
+            let run_text = &line[run];
+            let mut segments = font_segments(run_text, fonts.len(), |font, c| {
+                fonts[font].glyph_id(c).0 != 0
+            });
+            // a right-to-left run's segments are in reading order, and each
+            // one's shaped glyphs come out in visual order
+            if rtl {
+                segments.reverse();
+            }
+
+            for (segment, font_index) in segments {
+                if faces[font_index].is_none() {
+                    faces[font_index] =
+                        rustybuzz::Face::from_slice(fonts[font_index].as_slice(), 0);
+                }
+                let Some(face) = &faces[font_index] else {
+                    continue;
+                };
+                let font_scale = fonts[font_index].as_scaled(scale).h_scale_factor();
+
+                let mut buffer = UnicodeBuffer::new();
+                buffer.push_str(&run_text[segment]);
+                buffer.set_direction(if rtl {
+                    Direction::RightToLeft
+                } else {
+                    Direction::LeftToRight
+                });
+                buffer.guess_segment_properties();
+                let shaped = rustybuzz::shape(face, &[], buffer);
+
+                for (info, glyph) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+                    // font units are y-up, and the caret's y-down
+                    let offset = Vec2::new(glyph.x_offset as f32, -glyph.y_offset as f32);
+                    place(
+                        font_index,
+                        GlyphId(info.glyph_id as u16),
+                        caret + offset * font_scale,
+                    );
+                    caret +=
+                        Vec2::new(glyph.x_advance as f32, -glyph.y_advance as f32) * font_scale;
+                }
+            }
+        }
+
+        width = width.max(caret.x - position.x);
+    }
+
+    Vec2::new(width, primary.height() + line_height * (lines - 1) as f32)
+}
+
+/// Combining marks, and joiners, which shape with the character before them
+fn continues_cluster(c: char) -> bool {
+    bidi_class(c) == BidiClass::NSM || c == '\u{200c}' || c == '\u{200d}'
+}
+
+/// The byte ranges of `line`'s runs of one direction, in visual order,
+/// and whether each is right-to-left
+fn visual_runs(line: &str) -> Vec<(Range<usize>, bool)> {
+    let bidi = BidiInfo::new(line, None);
+    let mut runs = vec![];
+    for paragraph in &bidi.paragraphs {
+        let (levels, paragraph_runs) = bidi.visual_runs(paragraph, paragraph.range.clone());
+        runs.extend(paragraph_runs.into_iter().map(|run| {
+            let rtl = levels[run.start].is_rtl();
+            (run, rtl)
+        }));
+    }
+    runs
+}
+
+/// Splits `text` into byte ranges drawn with one font: a character stays
+/// in the font before it when that font has it, so marks keep to their base,
+/// and otherwise takes the first font that has it (or the first font's
+/// missing glyph). Control characters are skipped.
+fn font_segments(
+    text: &str,
+    font_count: usize,
+    has_glyph: impl Fn(usize, char) -> bool,
+) -> Vec<(Range<usize>, usize)> {
+    let mut segments: Vec<(Range<usize>, usize)> = vec![];
+    for (start, c) in text.char_indices() {
+        if c.is_control() {
+            continue;
+        }
+        let end = start + c.len_utf8();
+
+        if let Some((range, font)) = segments.last_mut()
+            && range.end == start
+            && continues_cluster(c)
+            && has_glyph(*font, c)
+        {
+            range.end = end;
+            continue;
+        }
+
+        let font = (0..font_count)
+            .find(|&font| has_glyph(font, c))
+            .unwrap_or(0);
+        match segments.last_mut() {
+            Some((range, last)) if range.end == start && *last == font => range.end = end,
+            _ => segments.push((start..end, font)),
+        }
+    }
+    segments
+}
+
+/// A distance field of a glyph's coverage, as msdf.slang reads it: 0.5 on
+/// the outline, rising inside, with `px_range` texels of distance across
+/// 0 to 255. A partly covered texel places the outline within itself,
+/// as in Mapbox's TinySDF.
+fn signed_distance_field(coverage: &[f32], width: usize, height: usize, px_range: f32) -> Vec<u8> {
+    // squared distances to the nearest texel inside the glyph, and outside it
+    let mut to_inside = vec![FAR; coverage.len()];
+    let mut to_outside = vec![0.0; coverage.len()];
+    for (index, &coverage) in coverage.iter().enumerate() {
+        if coverage >= 1.0 {
+            to_inside[index] = 0.0;
+            to_outside[index] = FAR;
+        } else if coverage > 0.0 {
+            let edge = 0.5 - coverage;
+            to_inside[index] = edge.max(0.0).powi(2);
+            to_outside[index] = edge.min(0.0).powi(2);
+        }
+    }
+    distance_transform(&mut to_inside, width, height);
+    distance_transform(&mut to_outside, width, height);
+
+    to_inside
+        .iter()
+        .zip(&to_outside)
+        .map(|(to_inside, to_outside)| {
+            let distance = to_inside.sqrt() - to_outside.sqrt();
+            ((0.5 - distance / px_range).clamp(0.0, 1.0) * 255.0).round() as u8
+        })
+        .collect()
+}
+
+/// Replaces each squared distance in `grid` with the least, over every
+/// texel, of that texel's plus the squared distance to it; Felzenszwalb and
+/// Huttenlocher's transform, down the columns and then along the rows
+fn distance_transform(grid: &mut [f32], width: usize, height: usize) {
+    let len = width.max(height);
+    let mut line = vec![0.0; len];
+    let mut transformed = vec![0.0; len];
+    let mut parabolas = vec![0; len];
+    let mut boundaries = vec![0.0; len + 1];
+
+    for x in 0..width {
+        for y in 0..height {
+            line[y] = grid[y * width + x];
+        }
+        distance_transform_line(
+            &line[..height],
+            &mut transformed[..height],
+            &mut parabolas,
+            &mut boundaries,
+        );
+        for y in 0..height {
+            grid[y * width + x] = transformed[y];
+        }
+    }
+
+    for row in grid.chunks_exact_mut(width) {
+        line[..width].copy_from_slice(row);
+        distance_transform_line(&line[..width], row, &mut parabolas, &mut boundaries);
+    }
+}
+
+/// The transform along one line, as the lower envelope of a parabola
+/// rooted at each texel; `parabolas` and `boundaries` are scratch space
+fn distance_transform_line(
+    line: &[f32],
+    transformed: &mut [f32],
+    parabolas: &mut [usize],
+    boundaries: &mut [f32],
+) {
+    // where the parabolas rooted at q and r cross
+    let crossing = |q: usize, r: usize| {
+        (line[q] - line[r] + (q * q) as f32 - (r * r) as f32) / (2 * (q - r)) as f32
+    };
+
+    let mut k = 0;
+    parabolas[0] = 0;
+    boundaries[0] = f32::NEG_INFINITY;
+    boundaries[1] = f32::INFINITY;
+    for q in 1..line.len() {
+        let mut s = crossing(q, parabolas[k]);
+        while s <= boundaries[k] {
+            k -= 1;
+            s = crossing(q, parabolas[k]);
+        }
+        k += 1;
+        parabolas[k] = q;
+        boundaries[k] = s;
+        boundaries[k + 1] = f32::INFINITY;
+    }
+
+    k = 0;
+    for (q, transformed) in transformed.iter_mut().enumerate() {
+        while boundaries[k + 1] < q as f32 {
+            k += 1;
+        }
+        let r = parabolas[k];
+        *transformed = line[r] + (q as f32 - r as f32).powi(2);
+    }
+}
+
+/// Packs rectangles into rows, left to right and top to bottom;
+/// glyphs at one size are about the same height, so little is wasted
+#[derive(Debug)]
+struct ShelfPacker {
+    size: u32,
+    /// where the next rectangle goes on the current shelf
+    x: u32,
+    y: u32,
+    /// the tallest rectangle on the current shelf
+    shelf_height: u32,
+}
+
+impl ShelfPacker {
+    fn new(size: u32) -> Self {
+        Self {
+            size,
+            x: GLYPH_PADDING,
+            y: GLYPH_PADDING,
+            shelf_height: 0,
+        }
+    }
+
+    /// The top left corner for a `width` by `height` rectangle,
+    /// or None once it no longer fits
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if self.x + width + GLYPH_PADDING > self.size {
+            self.x = GLYPH_PADDING;
+            self.y += self.shelf_height + GLYPH_PADDING;
+            self.shelf_height = 0;
+        }
+        if self.x + width + GLYPH_PADDING > self.size || self.y + height + GLYPH_PADDING > self.size
+        {
+            return None;
+        }
+
+        let corner = (self.x, self.y);
+        self.x += width + GLYPH_PADDING;
+        self.shelf_height = self.shelf_height.max(height);
+        Some(corner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packer_fills_shelves_then_runs_out() {
+        let mut packer = ShelfPacker::new(16);
+        assert_eq!(packer.allocate(6, 4), Some((1, 1)));
+        assert_eq!(packer.allocate(6, 5), Some((8, 1)));
+        // the next one starts a shelf below the tallest so far
+        assert_eq!(packer.allocate(6, 3), Some((1, 7)));
+        assert_eq!(packer.allocate(20, 1), None);
+        assert_eq!(packer.allocate(6, 8), None);
+    }
+
+    #[test]
+    fn sizes_round_to_whole_pixels() {
+        assert_eq!(pixel_size(15.6), 16);
+        assert_eq!(pixel_size(0.2), 1);
+    }
+
+    #[test]
+    fn distance_fields_are_half_on_the_outline() {
+        // a 4x4 square in a 12x12 field, and a half covered column beside it
+        let mut coverage = vec![0.0; 12 * 12];
+        for y in 4..8 {
+            for x in 4..8 {
+                coverage[y * 12 + x] = 1.0;
+            }
+            coverage[y * 12 + 8] = 0.5;
+        }
+        let field = signed_distance_field(&coverage, 12, 12, 8.0);
+        let at = |x: usize, y: usize| field[y * 12 + x];
+
+        // one texel in from the outline, and at the center
+        assert_eq!(at(4, 5), 159);
+        assert_eq!(at(5, 5), 191);
+        // on the outline, then one texel out from it
+        assert_eq!(at(8, 5), 128);
+        assert_eq!(at(3, 5), 96);
+        assert_eq!(at(9, 5), 96);
+        // further than half the range
+        assert_eq!(at(0, 0), 0);
+    }
+
+    #[test]
+    fn runs_are_reordered_for_display() {
+        let line = "ab \u{5d0}\u{5d1} cd";
+        let runs: Vec<(&str, bool)> = visual_runs(line)
+            .into_iter()
+            .map(|(run, rtl)| (&line[run], rtl))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![("ab ", false), ("\u{5d0}\u{5d1}", true), (" cd", false)]
+        );
+        assert!(visual_runs("").is_empty());
+    }
+
+    #[test]
+    fn marks_stay_with_their_base_font() {
+        // the first font has ascii and the combining acute, the second everything
+        let has_glyph = |font: usize, c: char| font == 1 || c.is_ascii() || c == '\u{301}';
+        let segments = font_segments("ab\u{3b1}\u{301}c\td", 2, has_glyph);
+        assert_eq!(segments, vec![(0..2, 0), (2..6, 1), (6..7, 0), (8..9, 0)]);
+    }
+}
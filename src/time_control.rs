@@ -0,0 +1,152 @@
+//! Debug time control for every game: pause, slow motion, fast forward, and
+//! single-frame stepping.
+//!
+//! The [`App`](crate::app::App) owns a [`TimeControl`] and uses it to scale the
+//! `dt` it passes to [`Game::update`](crate::game::Game::update). Rendering
+//! carries on while paused, so the editor and camera stay usable; games that
+//! time everything from `dt` (rather than their own clock) pause with it.
+
+use std::time::Duration;
+
+/// The speeds the fast-forward and slow-motion controls switch to
+pub const SLOW_MOTION_SCALE: f32 = 0.1;
+pub const FAST_FORWARD_SCALE: f32 = 2.0;
+
+/// The longest real frame passed on to an update, so that a breakpoint or a
+/// dragged window doesn't arrive as one huge step
+const MAX_REAL_DT: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeControl {
+    pub paused: bool,
+    /// multiplies each real frame's dt
+    pub scale: f32,
+    step_requested: bool,
+    /// the sum of every dt passed to an update
+    elapsed: Duration,
+}
+
+impl TimeControl {
+    pub fn new() -> Self {
+        Self {
+            paused: false,
+            scale: 1.0,
+            step_requested: false,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Pause, and run one update on the next frame
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.step_requested = true;
+    }
+
+    /// Switch to `scale`, or back to normal speed if already there
+    pub fn toggle_scale(&mut self, scale: f32) {
+        self.scale = if self.scale == scale { 1.0 } else { scale };
+    }
+
+    /// Game time so far, which stops while paused
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The dt for this frame's update, or None to skip it.
+    /// A step is one `step_dt` (usually the game's frame delay) at the current scale.
+    pub fn update_dt(&mut self, real_dt: Duration, step_dt: Duration) -> Option<Duration> {
+        let dt = if !self.paused {
+            real_dt.min(MAX_REAL_DT)
+        } else if std::mem::take(&mut self.step_requested) {
+            step_dt
+        } else {
+            return None;
+        };
+
+        let dt = dt.mul_f32(self.scale);
+        self.elapsed += dt;
+        Some(dt)
+    }
+
+    /// The pause, step, and speed controls, labeled with their hotkeys
+    #[cfg(feature = "editor")]
+    pub fn ui(&mut self, ui: &mut egui::Ui, hotkeys: [&str; 4]) {
+        let [pause_key, step_key, slow_key, fast_key] = hotkeys;
+        ui.horizontal(|ui| {
+            let pause_label = if self.paused { "Resume" } else { "Pause" };
+            if ui.button(format!("{pause_label} ({pause_key})")).clicked() {
+                self.toggle_pause();
+            }
+            if ui.button(format!("Step ({step_key})")).clicked() {
+                self.step();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.selectable_value(
+                &mut self.scale,
+                SLOW_MOTION_SCALE,
+                format!("0.1x ({slow_key})"),
+            );
+            ui.selectable_value(&mut self.scale, 1.0, "1x");
+            ui.selectable_value(
+                &mut self.scale,
+                FAST_FORWARD_SCALE,
+                format!("2x ({fast_key})"),
+            );
+        });
+        ui.label(format!("game time {:.2}s", self.elapsed.as_secs_f64()));
+    }
+}
+
+impl Default for TimeControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // exact in f32, like the scales, so scaled durations compare exactly
+    const FRAME: Duration = Duration::from_micros(15_625);
+
+    #[test]
+    fn pausing_skips_updates_until_a_step() {
+        let mut time = TimeControl::new();
+        assert_eq!(time.update_dt(FRAME, FRAME), Some(FRAME));
+
+        time.toggle_pause();
+        assert_eq!(time.update_dt(FRAME, FRAME), None);
+
+        time.step();
+        assert_eq!(time.update_dt(FRAME * 3, FRAME), Some(FRAME));
+        assert_eq!(time.update_dt(FRAME, FRAME), None);
+        assert_eq!(time.elapsed(), FRAME * 2);
+
+        time.toggle_pause();
+        assert_eq!(time.update_dt(FRAME, FRAME), Some(FRAME));
+    }
+
+    #[test]
+    fn scale_multiplies_dt_and_toggles_back_to_normal() {
+        let mut time = TimeControl::new();
+        time.toggle_scale(FAST_FORWARD_SCALE);
+        assert_eq!(time.update_dt(FRAME, FRAME), Some(FRAME * 2));
+
+        time.toggle_scale(SLOW_MOTION_SCALE);
+        assert_eq!(time.scale, SLOW_MOTION_SCALE);
+        time.toggle_scale(SLOW_MOTION_SCALE);
+        assert_eq!(time.scale, 1.0);
+
+        // a long stall arrives capped
+        assert_eq!(
+            time.update_dt(Duration::from_secs(5), FRAME),
+            Some(MAX_REAL_DT)
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! Keyframed animation of plain values, for post-effect parameters, camera
+//! moves, and sprite properties.
+//!
+//! A [`Track`] holds keyframes of one value (an `f32` or a glam vector) and
+//! samples it at any time, easing between keyframes. A [`Timeline`] is the
+//! playhead: the game advances it from [`Game::update`](crate::game::Game::update)'s
+//! `dt`, and samples its tracks at [`Timeline::time`]. Its
+//! [`ui`](Timeline::ui) scrubs the playhead in the editor, from
+//! [`Game::editor_windows`](crate::game::Game::editor_windows):
+//!
+//! ```ignore
+//! let curvature = Track::new(0.0)
+//!     .key(1.5, 0.3, Easing::EaseInOut)
+//!     .key(4.0, 0.0, Easing::EaseOut);
+//! let mut timeline = Timeline::new(curvature.duration());
+//!
+//! // in update
+//! timeline.advance(dt);
+//! crt_params.curvature = curvature.sample(timeline.time);
+//! ```
+
+use std::time::Duration;
+
+use glam::{Vec2, Vec3, Vec4};
+
+/// How a value moves from one keyframe to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// starts slow
+    EaseIn,
+    /// ends slow
+    EaseOut,
+    /// starts and ends slow
+    EaseInOut,
+    /// holds the value until the next keyframe
+    Step,
+}
+
+impl Easing {
+    pub const ALL: [Self; 5] = [
+        Self::Linear,
+        Self::EaseIn,
+        Self::EaseOut,
+        Self::EaseInOut,
+        Self::Step,
+    ];
+
+    /// Progress through a segment, from 0 to 1, reshaped by this easing
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Self::Step => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+/// A value a [`Track`] can interpolate
+pub trait Tween: Copy {
+    fn tween(self, to: Self, t: f32) -> Self;
+}
+
+impl Tween for f32 {
+    fn tween(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Tween for Vec2 {
+    fn tween(self, to: Self, t: f32) -> Self {
+        self.lerp(to, t)
+    }
+}
+
+impl Tween for Vec3 {
+    fn tween(self, to: Self, t: f32) -> Self {
+        self.lerp(to, t)
+    }
+}
+
+impl Tween for Vec4 {
+    fn tween(self, to: Self, t: f32) -> Self {
+        self.lerp(to, t)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe<T> {
+    /// seconds from the start of the track
+    pub time: f32,
+    pub value: T,
+    /// how the value arrives here from the previous keyframe
+    pub easing: Easing,
+}
+
+/// Keyframes of one value, in time order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track<T> {
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Tween> Track<T> {
+    /// A track starting at `value` at time 0
+    pub fn new(value: T) -> Self {
+        Self {
+            keyframes: vec![Keyframe {
+                time: 0.0,
+                value,
+                easing: Easing::Linear,
+            }],
+        }
+    }
+
+    /// Add a keyframe, easing into `value` at `time` seconds;
+    /// replaces a keyframe already at that time
+    pub fn key(mut self, time: f32, value: T, easing: Easing) -> Self {
+        self.insert(Keyframe {
+            time,
+            value,
+            easing,
+        });
+        self
+    }
+
+    pub fn insert(&mut self, keyframe: Keyframe<T>) {
+        match self
+            .keyframes
+            .binary_search_by(|existing| existing.time.total_cmp(&keyframe.time))
+        {
+            Ok(index) => self.keyframes[index] = keyframe,
+            Err(index) => self.keyframes.insert(index, keyframe),
+        }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    /// The time of the last keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// The value at `time` seconds; before the first keyframe and after the
+    /// last, the value holds
+    pub fn sample(&self, time: f32) -> T {
+        let next_index = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+        if next_index == 0 {
+            return self.keyframes[0].value;
+        }
+        let previous = &self.keyframes[next_index - 1];
+        let Some(next) = self.keyframes.get(next_index) else {
+            return previous.value;
+        };
+
+        let t = (time - previous.time) / (next.time - previous.time);
+        previous.value.tween(next.value, next.easing.apply(t))
+    }
+}
+
+/// A playhead for sampling tracks, which can be scrubbed in the editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    /// seconds from the start
+    pub time: f32,
+    pub duration: f32,
+    pub playing: bool,
+    /// wrap back to the start at the end, instead of stopping there
+    pub looping: bool,
+}
+
+impl Timeline {
+    /// A playing, non-looping timeline of `duration` seconds
+    pub fn new(duration: f32) -> Self {
+        Self {
+            time: 0.0,
+            duration,
+            playing: true,
+            looping: false,
+        }
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// Move the playhead forward while playing
+    pub fn advance(&mut self, dt: Duration) {
+        if !self.playing {
+            return;
+        }
+
+        self.time += dt.as_secs_f32();
+        if self.time < self.duration {
+            return;
+        }
+
+        if self.looping && self.duration > 0.0 {
+            self.time %= self.duration;
+        } else {
+            self.time = self.duration;
+            self.playing = false;
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+        self.playing = true;
+    }
+
+    pub fn finished(&self) -> bool {
+        !self.looping && self.time >= self.duration
+    }
+
+    /// The play and loop toggles, and a scrubber for the playhead
+    #[cfg(feature = "editor")]
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let play_label = if self.playing { "Pause" } else { "Play" };
+            if ui.button(play_label).clicked() {
+                if self.finished() {
+                    self.restart();
+                } else {
+                    self.playing = !self.playing;
+                }
+            }
+            ui.checkbox(&mut self.looping, "Loop");
+        });
+
+        let scrubber = ui.add(
+            egui::Slider::new(&mut self.time, 0.0..=self.duration)
+                .suffix("s")
+                .text("time"),
+        );
+        // scrubbing takes over from playback
+        if scrubber.dragged() {
+            self.playing = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in Easing::ALL {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+        }
+        assert_eq!(Easing::EaseIn.apply(0.5), 0.25);
+        assert_eq!(Easing::EaseOut.apply(0.5), 0.75);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::Step.apply(0.99), 0.0);
+    }
+
+    #[test]
+    fn tracks_ease_between_keyframes_and_hold_outside_them() {
+        let track = Track::new(Vec2::ZERO)
+            .key(2.0, Vec2::new(4.0, 8.0), Easing::EaseIn)
+            .key(1.0, Vec2::new(2.0, 2.0), Easing::Linear);
+
+        let times: Vec<_> = track.keyframes().iter().map(|key| key.time).collect();
+        assert_eq!(times, [0.0, 1.0, 2.0]);
+        assert_eq!(track.duration(), 2.0);
+
+        assert_eq!(track.sample(-1.0), Vec2::ZERO);
+        assert_eq!(track.sample(0.5), Vec2::new(1.0, 1.0));
+        assert_eq!(track.sample(1.0), Vec2::new(2.0, 2.0));
+        // a quarter of the way from (2, 2) to (4, 8), eased in from halfway
+        assert_eq!(track.sample(1.5), Vec2::new(2.5, 3.5));
+        assert_eq!(track.sample(3.0), Vec2::new(4.0, 8.0));
+    }
+
+    #[test]
+    fn keys_at_the_same_time_replace_each_other() {
+        let track = Track::new(0.0)
+            .key(1.0, 1.0, Easing::Linear)
+            .key(1.0, 5.0, Easing::Step);
+
+        assert_eq!(track.keyframes().len(), 2);
+        assert_eq!(track.sample(0.5), 0.0);
+        assert_eq!(track.sample(1.0), 5.0);
+    }
+
+    #[test]
+    fn timelines_stop_or_wrap_at_the_end() {
+        let mut timeline = Timeline::new(1.0);
+        timeline.advance(Duration::from_millis(750));
+        assert_eq!(timeline.time, 0.75);
+        timeline.advance(Duration::from_millis(500));
+        assert_eq!(timeline.time, 1.0);
+        assert!(timeline.finished());
+        assert!(!timeline.playing);
+
+        let mut timeline = Timeline::new(1.0).looping();
+        timeline.advance(Duration::from_millis(1250));
+        assert_eq!(timeline.time, 0.25);
+        assert!(timeline.playing);
+
+        timeline.playing = false;
+        timeline.advance(Duration::from_millis(500));
+        assert_eq!(timeline.time, 0.25);
+    }
+}
@@ -0,0 +1,257 @@
+//! Opt-in pipelining of game logic with rendering: a [`Simulation`] updates
+//! for frame N+1 on a worker thread while the game records and submits
+//! frame N from a snapshot of the update before.
+//!
+//! ```ignore
+//! impl Game for MyGame {
+//!     fn input_event(&mut self, event: InputEvent) {
+//!         self.world.input(event);
+//!     }
+//!
+//!     fn update(&mut self, keyboard: &KeyboardState, dt: Duration) {
+//!         // hands the previous update's snapshot to draw, and starts the next
+//!         self.world.update(keyboard, dt);
+//!     }
+//!
+//!     fn draw(&mut self, renderer: FrameRenderer) -> Result<(), DrawError> {
+//!         let snapshot = self.world.snapshot();
+//!         // ... write snapshot.instances into a storage buffer, and draw
+//!     }
+//! }
+//! ```
+//!
+//! Snapshots are double buffered: draw reads one while the worker writes the
+//! other, and they swap at the next update, so neither side locks. That costs
+//! a frame of latency between input and what's on screen.
+//! The simulation itself moves to the worker and back, so between updates
+//! the game can still reach it with [`UpdateThread::state`], eg for the editor.
+
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::traits::{InputEvent, KeyboardState};
+
+/// The part of a game that doesn't touch the renderer, updated on a worker thread
+pub trait Simulation: Send + 'static {
+    /// What drawing a frame needs from an update, eg instance data for a
+    /// storage buffer; filled in place, so its allocations are reused
+    type Snapshot: Default + Send + 'static;
+
+    /// Called on the worker with the input since the last update, in order,
+    /// before [`Simulation::update`]
+    fn input(&mut self, _event: InputEvent) {}
+
+    fn update(&mut self, keyboard: &KeyboardState, dt: Duration);
+
+    /// Writes what the next frame draws, after each update
+    fn snapshot(&self, snapshot: &mut Self::Snapshot);
+}
+
+struct Job<S: Simulation> {
+    simulation: S,
+    snapshot: S::Snapshot,
+    inputs: Vec<InputEvent>,
+    keyboard: KeyboardState,
+    dt: Duration,
+}
+
+struct Done<S: Simulation> {
+    simulation: S,
+    snapshot: S::Snapshot,
+}
+
+/// A [`Simulation`], and the worker thread that updates it
+pub struct UpdateThread<S: Simulation> {
+    /// None while an update is running
+    simulation: Option<S>,
+    /// what draw reads
+    front: S::Snapshot,
+    /// the next snapshot for the worker to write; None while it's writing it
+    back: Option<S::Snapshot>,
+    /// input since the last update
+    inputs: Vec<InputEvent>,
+    jobs: Option<Sender<Job<S>>>,
+    done: Receiver<Done<S>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<S: Simulation> UpdateThread<S> {
+    /// Starts the worker; draw reads a snapshot of `simulation` as it is
+    /// until the first update finishes
+    pub fn new(simulation: S) -> anyhow::Result<Self> {
+        let (jobs, job_receiver) = channel::<Job<S>>();
+        let (done_sender, done) = channel();
+        let worker = std::thread::Builder::new()
+            .name("update".to_string())
+            .spawn(move || {
+                for mut job in job_receiver {
+                    for event in job.inputs.drain(..) {
+                        job.simulation.input(event);
+                    }
+                    job.simulation.update(&job.keyboard, job.dt);
+                    job.simulation.snapshot(&mut job.snapshot);
+                    let done = Done {
+                        simulation: job.simulation,
+                        snapshot: job.snapshot,
+                    };
+                    if done_sender.send(done).is_err() {
+                        break;
+                    }
+                }
+            })?;
+
+        let mut front = S::Snapshot::default();
+        simulation.snapshot(&mut front);
+
+        Ok(Self {
+            simulation: Some(simulation),
+            front,
+            back: Some(S::Snapshot::default()),
+            inputs: vec![],
+            jobs: Some(jobs),
+            done,
+            worker: Some(worker),
+        })
+    }
+
+    /// Queues input for the next update
+    pub fn input(&mut self, event: InputEvent) {
+        self.inputs.push(event);
+    }
+
+    /// Waits for the running update, if any, to make its snapshot the one
+    /// draw reads, then starts the next update on the worker
+    pub fn update(&mut self, keyboard: &KeyboardState, dt: Duration) {
+        self.finish_update();
+
+        let job = Job {
+            simulation: self.simulation.take().expect("no update is running"),
+            snapshot: self.back.take().expect("no update is running"),
+            inputs: std::mem::take(&mut self.inputs),
+            keyboard: keyboard.clone(),
+            dt,
+        };
+        let sent = self.jobs.as_ref().map(|jobs| jobs.send(job));
+        if !matches!(sent, Some(Ok(()))) {
+            self.resume_worker_panic();
+        }
+    }
+
+    /// The snapshot from the last finished update
+    pub fn snapshot(&self) -> &S::Snapshot {
+        &self.front
+    }
+
+    /// The simulation, after waiting for the running update;
+    /// its snapshot is taken again at the end of the next update
+    pub fn state(&mut self) -> &mut S {
+        self.finish_update();
+        self.simulation.as_mut().expect("no update is running")
+    }
+
+    fn finish_update(&mut self) {
+        if self.simulation.is_some() {
+            return;
+        }
+        match self.done.recv() {
+            Ok(done) => {
+                self.simulation = Some(done.simulation);
+                self.back = Some(std::mem::replace(&mut self.front, done.snapshot));
+            }
+            Err(_) => self.resume_worker_panic(),
+        }
+    }
+
+    /// The worker only hangs up when a simulation panics; the panic goes on
+    /// on the game's thread
+    fn resume_worker_panic(&mut self) -> ! {
+        self.jobs = None;
+        match self.worker.take().map(JoinHandle::join) {
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            _ => panic!("the update thread stopped"),
+        }
+    }
+}
+
+impl<S: Simulation> Drop for UpdateThread<S> {
+    fn drop(&mut self) {
+        // hanging up ends the worker's loop, after any running update
+        self.jobs = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{Input, Key};
+
+    #[derive(Default)]
+    struct Counter {
+        updates: u32,
+        keys: Vec<Key>,
+    }
+
+    impl Simulation for Counter {
+        type Snapshot = (u32, usize);
+
+        fn input(&mut self, event: InputEvent) {
+            if let Input::KeyDown(key) = event.input {
+                self.keys.push(key);
+            }
+        }
+
+        fn update(&mut self, _keyboard: &KeyboardState, _dt: Duration) {
+            self.updates += 1;
+        }
+
+        fn snapshot(&self, snapshot: &mut Self::Snapshot) {
+            *snapshot = (self.updates, self.keys.len());
+        }
+    }
+
+    #[test]
+    fn draw_sees_the_update_before_the_running_one() {
+        let keyboard = KeyboardState::default();
+        let dt = Duration::from_millis(16);
+        let mut thread = UpdateThread::new(Counter::default()).unwrap();
+        assert_eq!(*thread.snapshot(), (0, 0));
+
+        thread.update(&keyboard, dt);
+        assert_eq!(*thread.snapshot(), (0, 0));
+
+        thread.input(InputEvent {
+            input: Input::KeyDown(Key::Space),
+            timestamp: Duration::ZERO,
+        });
+        thread.update(&keyboard, dt);
+        assert_eq!(*thread.snapshot(), (1, 0));
+
+        thread.update(&keyboard, dt);
+        assert_eq!(*thread.snapshot(), (2, 1));
+
+        let state = thread.state();
+        assert_eq!(state.updates, 3);
+        assert_eq!(state.keys, vec![Key::Space]);
+    }
+
+    #[test]
+    #[should_panic(expected = "simulation failed")]
+    fn worker_panics_reach_the_game_thread() {
+        struct Failing;
+        impl Simulation for Failing {
+            type Snapshot = ();
+            fn update(&mut self, _keyboard: &KeyboardState, _dt: Duration) {
+                panic!("simulation failed");
+            }
+            fn snapshot(&self, _snapshot: &mut ()) {}
+        }
+
+        let mut thread = UpdateThread::new(Failing).unwrap();
+        thread.update(&KeyboardState::default(), Duration::ZERO);
+        thread.state();
+    }
+}
@@ -3,6 +3,8 @@ use std::path::PathBuf;
 use anyhow::Context;
 use image::{DynamicImage, ImageReader};
 
+pub mod image_ops;
+
 pub fn manifest_path<'a>(segments: impl IntoIterator<Item = &'a str>) -> PathBuf {
     let segments = segments.into_iter();
     let full_path = [env!("CARGO_MANIFEST_DIR")].into_iter().chain(segments);
@@ -0,0 +1,194 @@
+//! Preparing loaded images on the cpu before upload: downscaling, mip chains,
+//! flipping, premultiplying alpha, and converting to a [`TextureFormat`]'s
+//! texels for `Renderer::create_texture_from_bytes`.
+//!
+//! Filtering here works on the stored values, so sRGB-encoded images are
+//! averaged in sRGB; that's the usual compromise for sprites and UI art.
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::lut::f32_to_f16_bits;
+use crate::renderer::TextureFormat;
+
+/// Scale the image down so neither side exceeds `max_size`, keeping its
+/// aspect ratio; images that already fit are returned unchanged
+pub fn downscale_to_fit(image: &DynamicImage, max_size: u32) -> DynamicImage {
+    if image.width() <= max_size && image.height() <= max_size {
+        return image.clone();
+    }
+
+    // resize fits within the bounds, preserving the aspect ratio
+    image.resize(max_size, max_size, FilterType::Triangle)
+}
+
+/// The next mip level: half the size on each side (at least one texel),
+/// with each texel the average of the 2x2 block it covers
+pub fn half_size(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let half_width = (width / 2).max(1);
+    let half_height = (height / 2).max(1);
+
+    RgbaImage::from_fn(half_width, half_height, |x, y| {
+        // odd edges reuse their last row or column
+        let xs = [(2 * x).min(width - 1), (2 * x + 1).min(width - 1)];
+        let ys = [(2 * y).min(height - 1), (2 * y + 1).min(height - 1)];
+
+        let mut sums = [0u32; 4];
+        for sy in ys {
+            for sx in xs {
+                let texel = image.get_pixel(sx, sy);
+                for (sum, channel) in sums.iter_mut().zip(texel.0) {
+                    *sum += channel as u32;
+                }
+            }
+        }
+
+        Rgba(sums.map(|sum| ((sum + 2) / 4) as u8))
+    })
+}
+
+/// Every mip level of the image, from the image itself down to 1x1
+pub fn mip_chain(image: &RgbaImage) -> Vec<RgbaImage> {
+    let mut levels = vec![image.clone()];
+    while let Some(last) = levels.last()
+        && (last.width() > 1 || last.height() > 1)
+    {
+        let next = half_size(last);
+        levels.push(next);
+    }
+
+    levels
+}
+
+/// Mirror top to bottom, for images stored bottom row first
+pub fn flip_vertical(image: &mut RgbaImage) {
+    imageops::flip_vertical_in_place(image);
+}
+
+/// Mirror left to right
+pub fn flip_horizontal(image: &mut RgbaImage) {
+    imageops::flip_horizontal_in_place(image);
+}
+
+/// Multiply each texel's color by its alpha, for drawing with
+/// [`BlendMode::Premultiplied`](crate::renderer::BlendMode::Premultiplied).
+/// Premultiplied textures filter and mip without dark fringes around their
+/// transparent edges, so do this before [`mip_chain`] or uploading with mipmaps.
+pub fn premultiply_alpha(image: &mut RgbaImage) {
+    for texel in image.pixels_mut() {
+        let alpha = texel.0[3] as u32;
+        for channel in &mut texel.0[..3] {
+            *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+/// The image as tightly packed texels in `format`, for
+/// `Renderer::create_texture_from_bytes` with a row stride of
+/// `width * format.bytes_per_texel()`. Single-channel formats take the
+/// image's luma, and two-channel ones luma and alpha.
+pub fn to_texture_bytes(image: &DynamicImage, format: TextureFormat) -> Vec<u8> {
+    let halves = |values: &[f32]| -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|&value| f32_to_f16_bits(value).to_le_bytes())
+            .collect()
+    };
+
+    match format {
+        TextureFormat::R8 => image.to_luma8().into_raw(),
+        TextureFormat::Rg8 => image.to_luma_alpha8().into_raw(),
+        TextureFormat::Rgba8 | TextureFormat::Rgba8Srgb => image.to_rgba8().into_raw(),
+        TextureFormat::R16F => halves(&image.to_luma32f()),
+        TextureFormat::Rgba16F => halves(&image.to_rgba32f()),
+        TextureFormat::R32F => image
+            .to_luma32f()
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn premultiplying_scales_color_by_alpha() {
+        let mut image = RgbaImage::from_raw(
+            3,
+            1,
+            vec![
+                255, 255, 255, 255, //
+                200, 100, 50, 128, //
+                255, 0, 255, 0,
+            ],
+        )
+        .unwrap();
+        premultiply_alpha(&mut image);
+
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [100, 50, 25, 128]);
+        assert_eq!(image.get_pixel(2, 0).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn mip_chains_halve_down_to_one_texel() {
+        let image = RgbaImage::from_pixel(5, 2, Rgba([10, 20, 30, 40]));
+        let sizes: Vec<_> = mip_chain(&image)
+            .iter()
+            .map(|level| level.dimensions())
+            .collect();
+
+        assert_eq!(sizes, [(5, 2), (2, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn half_size_averages_blocks() {
+        let image = RgbaImage::from_raw(
+            2,
+            2,
+            vec![
+                0, 0, 0, 255, //
+                255, 0, 0, 255, //
+                0, 255, 0, 255, //
+                0, 0, 255, 255,
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(half_size(&image).get_pixel(0, 0).0, [64, 64, 64, 255]);
+    }
+
+    #[test]
+    fn downscaling_keeps_the_aspect_ratio() {
+        let image = DynamicImage::new_rgba8(400, 100);
+        let scaled = downscale_to_fit(&image, 200);
+        assert_eq!((scaled.width(), scaled.height()), (200, 50));
+
+        let small = downscale_to_fit(&image, 1000);
+        assert_eq!((small.width(), small.height()), (400, 100));
+    }
+
+    #[test]
+    fn texture_bytes_match_the_format_size() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 2, Rgba([255; 4])));
+
+        for format in [
+            TextureFormat::R8,
+            TextureFormat::Rg8,
+            TextureFormat::Rgba8,
+            TextureFormat::Rgba8Srgb,
+            TextureFormat::R16F,
+            TextureFormat::Rgba16F,
+            TextureFormat::R32F,
+        ] {
+            let bytes = to_texture_bytes(&image, format);
+            assert_eq!(bytes.len(), 6 * format.bytes_per_texel() as usize);
+        }
+
+        let halves = to_texture_bytes(&image, TextureFormat::Rgba16F);
+        assert_eq!(&halves[..2], &0x3c00u16.to_le_bytes());
+    }
+}